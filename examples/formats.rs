@@ -0,0 +1,21 @@
+use noise_gui::Expr;
+
+fn main() {
+    let expr: Expr = ron::from_str(include_str!("test.ron")).unwrap();
+    let point = [0.5, 0.25, 0.0];
+
+    // JSON may be read by engines and tools without a RON parser
+    let json = expr.to_json().unwrap();
+    let from_json = Expr::from_json(&json).unwrap();
+    println!("JSON: {json}");
+    assert_eq!(expr.sample(point), from_json.sample(point));
+
+    // The postcard binary format is much smaller, but is not self-describing
+    #[cfg(feature = "postcard")]
+    {
+        let bytes = expr.to_bytes().unwrap();
+        let from_bytes = Expr::from_bytes(&bytes).unwrap();
+        println!("Postcard: {} bytes", bytes.len());
+        assert_eq!(expr.sample(point), from_bytes.sample(point));
+    }
+}
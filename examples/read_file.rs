@@ -4,14 +4,18 @@ fn main() {
     let mut test_expr: Expr = ron::from_str(include_str!("test.ron")).unwrap();
 
     // This gets a noise value which is identical to what was designed in the GUI
-    println!("Sampled value: {}", test_expr.noise().get([0.0, 0.0, 0.0]));
+    println!("Sampled value: {}", test_expr.sample([0.0, 0.0, 0.0]));
 
     // This replaces the value of a variable and samples a new noise function:
     println!(
         "Updated value: {}",
-        test_expr
-            .set_f64("my-var", 42.0)
-            .noise()
-            .get([0.0, 0.0, 0.0])
+        test_expr.set_f64("my-var", 42.0).sample([0.0, 0.0, 0.0])
     );
+
+    // Compiling builds the noise function once, which is much faster for sampling many points
+    let noise = test_expr.compile();
+    let row = (0..8)
+        .map(|x| noise.sample_2d([x as f64 / 8.0, 0.0]))
+        .collect::<Vec<_>>();
+    println!("Sampled row: {row:?}");
 }
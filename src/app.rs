@@ -1,56 +1,267 @@
 use {
     super::{
+        clipboard::Clipboard,
+        diagnostics::DiagnosticsWindow,
         expr::Expr,
-        node::{Image, NoiseNode},
+        inspector,
+        keymap::{Action, Keymap, KeymapWindow},
+        minimap,
+        node::{Image, NoiseNode, SubgraphNode},
+        palette::NodePalette,
+        plot::PlotWindow,
+        presets::Presets,
+        preview::{PreviewWindow, RegionDialog},
         rand::shuffled_u8,
-        thread::{ImageInfo, Threads},
-        view::Viewer,
+        search::GraphSearch,
+        terrain::TerrainPreview,
+        thread::{ImageInfo, TerrainInfo, Threads},
+        timeline::Timeline,
+        variables::VariablesWindow,
+        view::{DependencyChain, Viewer},
     },
     eframe::{get_value, set_value, CreationContext, Frame, Storage, APP_KEY},
     egui::{
-        github_link_file, warn_if_debug_build, Align, CentralPanel, Color32, ColorImage, Context,
-        Id, Layout, Vec2,
+        github_link_file, menu, warn_if_debug_build, widgets, Align, CentralPanel, Color32,
+        ColorImage, Context, Id, Layout, Pos2, Rect, SidePanel, TopBottomPanel, Vec2,
     },
     egui_snarl::{
-        ui::{BackgroundPattern, Grid, SnarlStyle},
+        ui::{get_selected_nodes, BackgroundPattern, Grid, SnarlStyle},
         NodeId, OutPinId, Snarl,
     },
     log::debug,
     std::{
         cell::RefCell,
         collections::{HashMap, HashSet},
+        mem::{replace, swap, take},
         sync::{Arc, RwLock},
     },
 };
 
 #[cfg(not(target_arch = "wasm32"))]
 use {
-    egui::{menu, widgets, TopBottomPanel, ViewportCommand},
-    log::warn,
-    rfd::FileDialog,
-    ron::{
-        de::from_reader,
-        ser::{to_writer_pretty, PrettyConfig},
+    super::{
+        examples::Example,
+        export::{
+            png_or_svg_file_dialog, save_png_or_svg, AnimationExport, BatchExport, NormalMapExport,
+            RawExport,
+        },
+        import,
+        project::{self, Format, Project},
+        recent::RecentFiles,
     },
+    egui::{vec2, Button, Event, ViewportCommand},
+    log::warn,
+    rfd::{FileDialog, MessageButtons, MessageDialog, MessageDialogResult, MessageLevel},
+    ron::ser::{to_writer_pretty, PrettyConfig},
     serde::Serialize,
     std::{
-        fs::OpenOptions,
+        fs::{read_to_string, OpenOptions},
+        io::BufWriter,
         path::{Path, PathBuf},
     },
 };
 
 pub type NodeExprs = Arc<RwLock<HashMap<NodeId, (usize, Arc<Expr>)>>>;
 
+/// A project open in a tab, which is swapped with the graph being edited when its tab is selected.
+#[derive(Default)]
+struct GraphTab {
+    #[cfg(not(target_arch = "wasm32"))]
+    path: Option<PathBuf>,
+
+    snarl: Snarl<NoiseNode>,
+    subgraph_stack: Vec<(NodeId, Snarl<NoiseNode>)>,
+    undo_history: Vec<Snarl<NoiseNode>>,
+    undo_pending: bool,
+    undo_snarl: Snarl<NoiseNode>,
+
+    #[cfg(not(target_arch = "wasm32"))]
+    unsaved: bool,
+}
+
+impl GraphTab {
+    #[cfg(not(target_arch = "wasm32"))]
+    fn title(path: Option<&Path>, unsaved: bool) -> String {
+        let name = path
+            .and_then(Path::file_stem)
+            .map_or_else(|| "Untitled".into(), |name| name.to_string_lossy());
+
+        if unsaved {
+            format!("{name} *")
+        } else {
+            name.into_owned()
+        }
+    }
+}
+
+/// A screenshot of the whole graph, which may be larger than the canvas, so it is captured one
+/// canvas-sized tile at a time.
+///
+/// The view cannot be moved from outside of snarl, so every node is moved under the canvas for each
+/// tile instead and put back once the last tile has been captured.
+#[cfg(not(target_arch = "wasm32"))]
+struct GraphScreenshot {
+    /// The whole graph, filled in as each tile arrives.
+    image: ColorImage,
+
+    /// The graph position of the top-left corner of the image.
+    origin: Pos2,
+
+    path: PathBuf,
+
+    /// Set while waiting for the requested screenshot of a tile to arrive.
+    pending: bool,
+
+    /// The graph position of every node before the screenshot was started.
+    positions: Vec<(NodeId, Pos2)>,
+
+    /// The index of the next tile to capture, in rows of `tile_count[0]` tiles.
+    tile: usize,
+
+    tile_count: [usize; 2],
+
+    /// The size of each tile in graph units and in pixels.
+    tile_size: (Vec2, [usize; 2]),
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl GraphScreenshot {
+    /// The space left around the nodes, in graph units.
+    const MARGIN: f32 = 16.0;
+
+    /// Returns the graph position which the next tile shows at the top-left corner of the canvas.
+    fn next_tile_pos(&self) -> Option<Pos2> {
+        let [columns, rows] = self.tile_count;
+
+        (self.tile < columns * rows).then(|| {
+            let (column, row) = (self.tile % columns, self.tile / columns);
+
+            self.origin + self.tile_size.0 * vec2(column as f32, row as f32)
+        })
+    }
+
+    /// Copies a screenshot of the canvas into the image as the next tile.
+    fn add_tile(&mut self, tile: &ColorImage) {
+        let [columns, _] = self.tile_count;
+        let (column, row) = (self.tile % columns, self.tile / columns);
+        let [tile_width, tile_height] = self.tile_size.1;
+        let (x, y) = (column * tile_width, row * tile_height);
+        let width = tile_width.min(tile.width()).min(self.image.width() - x);
+        let height = tile_height.min(tile.height()).min(self.image.height() - y);
+
+        for tile_y in 0..height {
+            let src = tile_y * tile.width();
+            let dst = (y + tile_y) * self.image.width() + x;
+            self.image.pixels[dst..dst + width].copy_from_slice(&tile.pixels[src..src + width]);
+        }
+
+        self.tile += 1;
+        self.pending = false;
+    }
+}
+
 pub struct App {
+    /// The index of the tab being edited; its entry in `tabs` is empty until another is selected.
+    active_tab: usize,
+
+    #[cfg(not(target_arch = "wasm32"))]
+    animation_export: Option<AnimationExport>,
+
+    #[cfg(not(target_arch = "wasm32"))]
+    batch_export: Option<BatchExport>,
+
+    #[cfg(not(target_arch = "wasm32"))]
+    canvas_rect: Rect,
+
+    /// Nodes copied from the graph, which may be pasted any number of times.
+    clipboard: Clipboard,
+
+    diagnostics_window: Option<DiagnosticsWindow>,
+
+    /// Image nodes which were last rendered as drafts and are rendered again once the drag ends.
+    draft_node_ids: HashSet<NodeId>,
+
+    /// Set while anything is being dragged, during which previews are rendered as drafts.
+    dragging: bool,
+
+    graph_search: Option<GraphSearch>,
+
+    /// Set when the parameters of the selected node are shown in a panel beside the graph.
+    inspector: bool,
+
+    /// The screen position of the graph origin and the graph scale (see `Viewer::graph_transform`).
+    graph_transform: Option<(Pos2, f32)>,
+
+    keymap: Keymap,
+    keymap_window: Option<KeymapWindow>,
+
+    /// Set when panning or zooming one preview moves every preview to the same region.
+    linked_previews: bool,
+
+    /// Set when the minimap overview is shown over the graph.
+    minimap: bool,
+
     node_exprs: NodeExprs,
+    node_palette: Option<NodePalette>,
+
+    #[cfg(not(target_arch = "wasm32"))]
+    normal_map_export: Option<NormalMapExport>,
 
     #[cfg(not(target_arch = "wasm32"))]
     path: Option<PathBuf>,
 
+    /// Set while background rendering is paused; updates are collected and requested on resume.
+    paused: bool,
+
+    plot_window: Option<PlotWindow>,
+    presets: Presets,
+    preview_window: Option<PreviewWindow>,
+
+    #[cfg(not(target_arch = "wasm32"))]
+    raw_export: Option<RawExport>,
+
+    #[cfg(not(target_arch = "wasm32"))]
+    recent_files: RecentFiles,
+
+    region_dialog: Option<RegionDialog>,
+
+    /// Set while the tiles of a screenshot of the graph are being captured.
+    #[cfg(not(target_arch = "wasm32"))]
+    screenshot: Option<GraphScreenshot>,
+
     snarl: Snarl<NoiseNode>,
+
+    /// The graphs which contain the subgraph being edited, outermost first, each with the id of the
+    /// subgraph node which was opened.
+    subgraph_stack: Vec<(NodeId, Snarl<NoiseNode>)>,
+
+    /// Every open project, sharing the worker threads and the clipboard.
+    ///
+    /// Only the tab being edited is kept when the app is closed.
+    tabs: Vec<GraphTab>,
+
+    terrain_preview: Option<TerrainPreview>,
     threads: Threads,
+    timeline: Timeline,
     removed_node_ids: HashSet<NodeId>,
     updated_node_ids: HashSet<NodeId>,
+
+    variables_window: Option<VariablesWindow>,
+
+    /// The graph as it was before each of the most recent edits, oldest first.
+    undo_history: Vec<Snarl<NoiseNode>>,
+
+    /// Set when the graph has been edited but the edit is not yet in the undo history, because a
+    /// value is still being dragged.
+    undo_pending: bool,
+
+    /// The graph as of the last edit in the undo history.
+    undo_snarl: Snarl<NoiseNode>,
+
+    /// Set when the graph has been edited since it was last opened or saved.
+    #[cfg(not(target_arch = "wasm32"))]
+    unsaved: bool,
+
     version: usize,
 }
 
@@ -58,13 +269,16 @@ impl App {
     #[cfg(not(target_arch = "wasm32"))]
     pub const EXTENSION: &'static str = "ron";
 
+    #[cfg(not(target_arch = "wasm32"))]
+    pub const JSON_EXTENSION: &'static str = "json";
+
+    /// The graph distance between duplicated nodes and the originals.
+    const DUPLICATE_OFFSET: f32 = 32.0;
+
     const IMAGE_COUNT: usize = Threads::IMAGE_COORDS as usize * Threads::IMAGE_COORDS as usize;
-    const IMAGE_SIZE: [usize; 2] = [
-        Threads::IMAGE_SIZE * Threads::IMAGE_COORDS as usize,
-        Threads::IMAGE_SIZE * Threads::IMAGE_COORDS as usize,
-    ];
+    const MAX_UNDO_COUNT: usize = 32;
 
-    pub fn new(#[allow(unused_variables)] cc: &CreationContext<'_>) -> Self {
+    pub fn new(cc: &CreationContext<'_>) -> Self {
         let snarl: Snarl<NoiseNode> = if let Some(storage) = cc.storage {
             get_value(storage, APP_KEY).unwrap_or_default()
         } else {
@@ -75,17 +289,72 @@ impl App {
         let threads = Threads::new(&node_exprs);
         let removed_node_ids = Default::default();
         let updated_node_ids = Self::all_image_node_ids(&snarl).collect();
+        let undo_snarl = snarl.clone();
 
         Self {
+            active_tab: 0,
+
+            #[cfg(not(target_arch = "wasm32"))]
+            animation_export: None,
+
+            #[cfg(not(target_arch = "wasm32"))]
+            batch_export: None,
+
+            #[cfg(not(target_arch = "wasm32"))]
+            canvas_rect: Rect::NOTHING,
+
+            clipboard: Default::default(),
+            diagnostics_window: None,
+            draft_node_ids: Default::default(),
+            dragging: false,
+            graph_search: None,
+            inspector: false,
+            graph_transform: None,
+            keymap: Keymap::load(cc.storage),
+            keymap_window: None,
+            linked_previews: false,
+            minimap: true,
             node_exprs,
+            node_palette: None,
+
+            #[cfg(not(target_arch = "wasm32"))]
+            normal_map_export: None,
 
             #[cfg(not(target_arch = "wasm32"))]
             path: None,
 
+            paused: false,
+            plot_window: None,
+            presets: Presets::load(cc.storage),
+            preview_window: None,
+
+            #[cfg(not(target_arch = "wasm32"))]
+            raw_export: None,
+
+            #[cfg(not(target_arch = "wasm32"))]
+            recent_files: RecentFiles::load(cc.storage),
+
+            region_dialog: None,
+
+            #[cfg(not(target_arch = "wasm32"))]
+            screenshot: None,
+
             snarl,
+            subgraph_stack: vec![],
+            tabs: vec![Default::default()],
+            terrain_preview: None,
             threads,
+            timeline: Default::default(),
             removed_node_ids,
             updated_node_ids,
+            variables_window: None,
+            undo_history: vec![],
+            undo_pending: false,
+            undo_snarl,
+
+            #[cfg(not(target_arch = "wasm32"))]
+            unsaved: false,
+
             version: 0,
         }
     }
@@ -96,27 +365,409 @@ impl App {
             .filter_map(|(node_id, node)| node.has_image().then_some(node_id))
     }
 
+    /// Opens the inner graph of a subgraph node for editing in place of the current graph.
+    fn edit_subgraph(&mut self, node_id: NodeId) {
+        let Some(NoiseNode::Subgraph(node)) = self.snarl.get_node_mut(node_id) else {
+            return;
+        };
+
+        let mut snarl = take(&mut node.snarl);
+
+        // Previews inside the subgraph use whatever is connected to the subgraph node
+        SubgraphNode::bind_inputs(&mut snarl, node_id, &self.snarl);
+
+        let snarl = replace(&mut self.snarl, snarl);
+        self.subgraph_stack.push((node_id, snarl));
+        self.replaced_graph();
+        self.reset_undo();
+    }
+
+    /// Returns to the graph containing the subgraph being edited.
+    fn exit_subgraph(&mut self) {
+        let Some((node_id, snarl)) = self.subgraph_stack.pop() else {
+            return;
+        };
+
+        let inner_snarl = replace(&mut self.snarl, snarl);
+        if let Some(NoiseNode::Subgraph(node)) = self.snarl.get_node_mut(node_id) {
+            node.snarl = inner_snarl;
+        }
+
+        self.replaced_graph();
+        self.reset_undo();
+    }
+
+    /// Inserts a copy of the selected nodes and the connections between them, slightly offset.
+    fn duplicate_selected_nodes(&mut self, ctx: &Context) {
+        let node_ids = get_selected_nodes(Id::new("snarl"), ctx);
+        let Some(pos) = node_ids
+            .iter()
+            .filter_map(|&node_id| self.snarl.get_node_info(node_id))
+            .map(|node| node.pos)
+            .reduce(Pos2::min)
+        else {
+            return;
+        };
+
+        // Uses a separate clipboard so that duplicating does not replace copied nodes
+        let mut clipboard = Clipboard::default();
+        clipboard.copy(&self.snarl, &node_ids);
+
+        self.updated_node_ids.extend(
+            clipboard
+                .paste(&mut self.snarl, pos + Vec2::splat(Self::DUPLICATE_OFFSET))
+                .into_values(),
+        );
+    }
+
     #[cfg(not(target_arch = "wasm32"))]
     pub fn file_dialog() -> FileDialog {
-        FileDialog::new().add_filter("Noise Project", &[Self::EXTENSION])
+        FileDialog::new()
+            .add_filter("Noise Project", &[Self::EXTENSION])
+            .add_filter("Noise Project (JSON)", &[Self::JSON_EXTENSION])
+    }
+
+    /// Moves every node so that the graph is centered within the given screen rectangle.
+    ///
+    /// The graph view itself cannot be moved from outside of snarl, so the nodes are moved instead;
+    /// the zoom is unchanged.
+    fn frame_all(&mut self, rect: Rect) {
+        let positions = self
+            .snarl
+            .nodes_pos()
+            .map(|(pos, _)| pos)
+            .collect::<Vec<_>>();
+
+        if positions.is_empty() {
+            return;
+        }
+
+        let bounds = Rect::from_points(&positions);
+        self.move_nodes(self.graph_pos(rect.center()) - bounds.center());
+    }
+
+    /// Moves every node by the given graph offset, which looks the same as moving the view the
+    /// opposite way.
+    fn move_nodes(&mut self, offset: Vec2) {
+        let node_ids = self
+            .snarl
+            .node_ids()
+            .map(|(node_id, _)| node_id)
+            .collect::<Vec<_>>();
+
+        for node_id in node_ids {
+            self.snarl.get_node_info_mut(node_id).unwrap().pos += offset;
+        }
+    }
+
+    /// Moves the view so that the given node is shown in the center of `rect`.
+    fn center_node(&mut self, node_id: NodeId, rect: Rect) {
+        if let Some(node_info) = self.snarl.get_node_info(node_id) {
+            let node_center = node_info.pos + minimap::node_size(&node_info.value) / 2.0;
+            self.move_nodes(self.graph_pos(rect.center()) - node_center);
+        }
+    }
+
+    /// Returns the graph position shown at the given screen position.
+    fn graph_pos(&self, screen_pos: Pos2) -> Pos2 {
+        self.graph_transform
+            .map(|(origin, scale)| ((screen_pos - origin) / scale).to_pos2())
+            .unwrap_or_default()
+    }
+
+    /// Moves the members of each group node by however far the group node has been dragged.
+    ///
+    /// Selected members are skipped because snarl already drags them along with the group node.
+    fn move_groups(&mut self, ctx: &Context) {
+        let selected_node_ids = get_selected_nodes(Id::new("snarl"), ctx);
+        let group_node_ids = self
+            .snarl
+            .node_ids()
+            .filter(|(_, node)| matches!(node, NoiseNode::Group(_)))
+            .map(|(node_id, _)| node_id)
+            .collect::<Vec<_>>();
+
+        for group_node_id in group_node_ids {
+            let pos = self.snarl.get_node_info(group_node_id).unwrap().pos;
+            let Some(NoiseNode::Group(group)) = self.snarl.get_node_mut(group_node_id) else {
+                unreachable!();
+            };
+            let offset = group
+                .pos
+                .replace(pos)
+                .map(|last_pos| pos - last_pos)
+                .unwrap_or_default();
+            let mut node_ids = take(&mut group.node_ids);
+
+            // Removed members leave the group
+            node_ids.retain(|&node_id| self.snarl.get_node(node_id).is_some());
+
+            if offset != Vec2::ZERO {
+                for &node_id in &node_ids {
+                    if !selected_node_ids.contains(&node_id) {
+                        self.snarl.get_node_info_mut(node_id).unwrap().pos += offset;
+                    }
+                }
+            }
+
+            if let Some(NoiseNode::Group(group)) = self.snarl.get_node_mut(group_node_id) {
+                group.node_ids = node_ids;
+            }
+        }
     }
 
     fn has_changes(&self) -> bool {
         !self.removed_node_ids.is_empty() || !self.updated_node_ids.is_empty()
     }
 
+    /// Returns `true` if the given path should be read or written as JSON instead of RON.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn is_json(path: &Path) -> bool {
+        path.extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case(Self::JSON_EXTENSION))
+    }
+
     #[cfg(not(target_arch = "wasm32"))]
     fn open(path: impl AsRef<Path>) -> anyhow::Result<Snarl<NoiseNode>> {
-        Ok(
-            from_reader(OpenOptions::new().read(true).open(path).map_err(|err| {
-                warn!("Unable to open file");
-                err
-            })?)
-            .map_err(|err: ron::error::SpannedError| {
-                warn!("Unable to read file");
-                err
-            })?,
-        )
+        let path = path.as_ref();
+        let s = read_to_string(path).inspect_err(|_| warn!("Unable to open file"))?;
+        let format = if Self::is_json(path) {
+            Format::Json
+        } else {
+            Format::Ron
+        };
+
+        project::read(&s, format).inspect_err(|_| warn!("Unable to read file"))
+    }
+
+    /// Opens a project file, keeping the current graph and showing an error if it cannot be read.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn open_path(&mut self, path: PathBuf) {
+        match Self::open(&path) {
+            Ok(snarl) => {
+                self.snarl = snarl;
+                self.subgraph_stack.clear();
+                self.recent_files.insert(&path, &self.snarl);
+                self.path = Some(path);
+                self.unsaved = false;
+                self.updated_node_ids = Self::all_image_node_ids(&self.snarl).collect();
+                self.reset_undo();
+            }
+            Err(err) => {
+                self.recent_files.remove(&path);
+
+                MessageDialog::new()
+                    .set_level(MessageLevel::Error)
+                    .set_title("Unable to open file")
+                    .set_description(format!("{err:#}"))
+                    .show();
+            }
+        }
+    }
+
+    /// Opens a project file dropped onto the window, asking first if there are unsaved changes.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn open_dropped_file(&mut self, ctx: &Context) {
+        let Some(path) = ctx.input(|input| {
+            input
+                .raw
+                .dropped_files
+                .iter()
+                .find_map(|file| file.path.clone())
+        }) else {
+            return;
+        };
+
+        if !Self::is_json(&path)
+            && !path
+                .extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case(Self::EXTENSION))
+        {
+            warn!("Unsupported file type: {}", path.display());
+
+            return;
+        }
+
+        if self.unsaved
+            && MessageDialog::new()
+                .set_level(MessageLevel::Warning)
+                .set_title("Unsaved changes")
+                .set_description("Discard unsaved changes to the current graph?")
+                .set_buttons(MessageButtons::YesNo)
+                .show()
+                != MessageDialogResult::Yes
+        {
+            return;
+        }
+
+        self.open_path(path);
+    }
+
+    /// Pauses or resumes background rendering.
+    ///
+    /// Pausing forgets every expression so that the worker threads skip any queued requests as
+    /// stale; resuming re-renders every image.
+    fn toggle_paused(&mut self) {
+        self.paused = !self.paused;
+
+        if self.paused {
+            self.node_exprs.write().unwrap().clear();
+        } else {
+            self.updated_node_ids
+                .extend(Self::all_image_node_ids(&self.snarl));
+        }
+    }
+
+    /// Re-renders every node after the graph being edited has been replaced by one of its subgraphs,
+    /// by another tab, or the other way around.
+    fn replaced_graph(&mut self) {
+        self.graph_transform = None;
+        self.node_palette = None;
+        self.plot_window = None;
+        self.preview_window = None;
+        self.region_dialog = None;
+        self.terrain_preview = None;
+        self.refresh_nodes();
+    }
+
+    /// Opens an empty project in a new tab.
+    fn new_tab(&mut self) {
+        self.tabs.push(Default::default());
+        self.select_tab(self.tabs.len() - 1);
+    }
+
+    /// Edits the project of the given tab in place of the current one.
+    fn select_tab(&mut self, idx: usize) {
+        if idx == self.active_tab {
+            return;
+        }
+
+        let mut tab = take(&mut self.tabs[idx]);
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            swap(&mut self.path, &mut tab.path);
+            swap(&mut self.unsaved, &mut tab.unsaved);
+        }
+
+        swap(&mut self.snarl, &mut tab.snarl);
+        swap(&mut self.subgraph_stack, &mut tab.subgraph_stack);
+        swap(&mut self.undo_history, &mut tab.undo_history);
+        swap(&mut self.undo_pending, &mut tab.undo_pending);
+        swap(&mut self.undo_snarl, &mut tab.undo_snarl);
+
+        self.tabs[self.active_tab] = tab;
+        self.active_tab = idx;
+        self.replaced_graph();
+    }
+
+    /// Closes the given tab, asking first if it has unsaved changes.
+    fn close_tab(&mut self, idx: usize) {
+        if self.tabs.len() == 1 {
+            return;
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let unsaved = if idx == self.active_tab {
+                self.unsaved
+            } else {
+                self.tabs[idx].unsaved
+            };
+
+            if unsaved
+                && MessageDialog::new()
+                    .set_level(MessageLevel::Warning)
+                    .set_title("Unsaved changes")
+                    .set_description(format!(
+                        "Discard unsaved changes to {}?",
+                        self.tab_title(idx)
+                    ))
+                    .set_buttons(MessageButtons::YesNo)
+                    .show()
+                    != MessageDialogResult::Yes
+            {
+                return;
+            }
+        }
+
+        if idx == self.active_tab {
+            self.select_tab(if idx == 0 { 1 } else { idx - 1 });
+        }
+
+        self.tabs.remove(idx);
+
+        if idx < self.active_tab {
+            self.active_tab -= 1;
+        }
+    }
+
+    fn tab_title(&self, idx: usize) -> String {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            if idx == self.active_tab {
+                GraphTab::title(self.path.as_deref(), self.unsaved)
+            } else {
+                let tab = &self.tabs[idx];
+                GraphTab::title(tab.path.as_deref(), tab.unsaved)
+            }
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        format!("Graph {}", idx + 1)
+    }
+
+    /// Forgets nodes which no longer exist and renders every image node again.
+    fn refresh_nodes(&mut self) {
+        self.removed_node_ids.extend(
+            self.node_exprs
+                .read()
+                .unwrap()
+                .keys()
+                .copied()
+                .filter(|&node_id| self.snarl.get_node(node_id).is_none()),
+        );
+        self.updated_node_ids = Self::all_image_node_ids(&self.snarl).collect();
+    }
+
+    /// Returns the whole project graph, including the subgraph being edited if there is one.
+    fn root_snarl(&self) -> Snarl<NoiseNode> {
+        let mut snarl = self.snarl.clone();
+
+        for (node_id, outer_snarl) in self.subgraph_stack.iter().rev() {
+            let inner_snarl = replace(&mut snarl, outer_snarl.clone());
+            if let Some(NoiseNode::Subgraph(node)) = snarl.get_node_mut(*node_id) {
+                node.snarl = inner_snarl;
+            }
+        }
+
+        snarl
+    }
+
+    /// Forgets the undo history, such as when a different project is opened.
+    fn reset_undo(&mut self) {
+        self.undo_history.clear();
+        self.undo_pending = false;
+        self.undo_snarl = self.snarl.clone();
+    }
+
+    /// Restores the graph as it was before the last edit.
+    fn undo(&mut self) {
+        let Some(snarl) = self.undo_history.pop() else {
+            return;
+        };
+
+        self.undo_pending = false;
+        self.undo_snarl = snarl.clone();
+        self.snarl = snarl;
+
+        // Nodes added by the undone edit no longer exist
+        self.refresh_nodes();
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            self.unsaved = true;
+        }
     }
 
     fn remove_nodes(&mut self) {
@@ -124,6 +775,7 @@ impl App {
 
         for node_id in self.removed_node_ids.drain() {
             node_exprs.remove(&node_id);
+            self.draft_node_ids.remove(&node_id);
 
             // Just in case (never happens!)
             self.updated_node_ids.remove(&node_id);
@@ -141,6 +793,7 @@ impl App {
             path.set_extension(Self::EXTENSION);
         }
 
+        let json = Self::is_json(&path);
         let file = OpenOptions::new()
             .write(true)
             .create(true)
@@ -150,14 +803,114 @@ impl App {
                 warn!("Unable to create file");
                 err
             })?;
-        to_writer_pretty(file, value, PrettyConfig::default()).map_err(|err| {
-            warn!("Unable to write file");
-            err
-        })?;
+
+        if json {
+            serde_json::to_writer_pretty(BufWriter::new(file), value)
+                .inspect_err(|_| warn!("Unable to write file"))?;
+        } else {
+            to_writer_pretty(file, value, PrettyConfig::default()).map_err(|err| {
+                warn!("Unable to write file");
+                err
+            })?;
+        }
 
         Ok(())
     }
 
+    /// Starts a screenshot of every node of the graph, which is saved once each tile of it has
+    /// been captured.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn start_screenshot(&mut self, ctx: &Context, path: PathBuf) {
+        let Some((_, scale)) = self.graph_transform else {
+            return;
+        };
+
+        let positions = self
+            .snarl
+            .nodes_pos_ids()
+            .map(|(node_id, pos, _)| (node_id, pos))
+            .collect::<Vec<_>>();
+        let Some(bounds) = self
+            .snarl
+            .nodes_pos()
+            .map(|(pos, node)| Rect::from_min_size(pos, minimap::node_size(node)))
+            .reduce(Rect::union)
+            .map(|bounds| bounds.expand(GraphScreenshot::MARGIN))
+        else {
+            return;
+        };
+
+        // Tiles are whole pixels so that they line up exactly
+        let pixels_per_unit = scale * ctx.pixels_per_point();
+        let tile_pixels = (self.canvas_rect.size() * ctx.pixels_per_point()).floor();
+        let image_pixels = (bounds.size() * pixels_per_unit).ceil();
+        let [tile_width, tile_height] = [tile_pixels.x as usize, tile_pixels.y as usize];
+        let [width, height] = [image_pixels.x as usize, image_pixels.y as usize];
+
+        if tile_width == 0 || tile_height == 0 {
+            return;
+        }
+
+        self.screenshot = Some(GraphScreenshot {
+            image: ColorImage::new([width, height], Color32::TRANSPARENT),
+            origin: bounds.min,
+            path,
+            pending: false,
+            positions,
+            tile: 0,
+            tile_count: [width.div_ceil(tile_width), height.div_ceil(tile_height)],
+            tile_size: (tile_pixels / pixels_per_unit, [tile_width, tile_height]),
+        });
+    }
+
+    /// Captures the next tile of the screenshot being taken once the previous one has been
+    /// received, or puts the nodes back and saves the screenshot once every tile has been.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn update_screenshot(&mut self, ctx: &Context) {
+        let canvas_pos = self.graph_pos(self.canvas_rect.min);
+        let Some(screenshot) = &mut self.screenshot else {
+            return;
+        };
+
+        if screenshot.pending {
+            let Some(image) = ctx.input(|input| {
+                input.raw.events.iter().find_map(|event| match event {
+                    Event::Screenshot { image, .. } => Some(Arc::clone(image)),
+                    _ => None,
+                })
+            }) else {
+                return;
+            };
+
+            screenshot.add_tile(&image.region(&self.canvas_rect, Some(ctx.pixels_per_point())));
+        }
+
+        // The nodes are moved before the canvas is drawn, so the requested screenshot shows them
+        let offset = screenshot
+            .next_tile_pos()
+            .map_or(Vec2::ZERO, |pos| canvas_pos - pos);
+
+        for &(node_id, pos) in &screenshot.positions {
+            if let Some(node_info) = self.snarl.get_node_info_mut(node_id) {
+                node_info.pos = pos + offset;
+
+                // Group nodes would otherwise drag their members along a second time
+                if let NoiseNode::Group(group) = &mut node_info.value {
+                    group.pos = Some(node_info.pos);
+                }
+            }
+        }
+
+        if screenshot.next_tile_pos().is_some() {
+            screenshot.pending = true;
+            ctx.send_viewport_cmd(ViewportCommand::Screenshot(Default::default()));
+        } else {
+            save_png_or_svg(&screenshot.path, &screenshot.image).unwrap_or_default();
+
+            self.screenshot = None;
+        }
+    }
+
     fn update_images(&mut self) {
         thread_local! {
             static NODE_IDS: RefCell<Option<HashSet<NodeId>>> = RefCell::new(Some(Default::default()));
@@ -169,7 +922,39 @@ impl App {
             node_ids.insert(node_id);
         }
 
-        for (node_id, image_version, coord, image) in self.threads.try_recv_iter() {
+        for (node_id, image_version, image_info, chunk_image, chunk_stats) in
+            self.threads.try_recv_iter()
+        {
+            let chunk_size = Threads::chunk_size(image_info.size);
+
+            if image_info.detached {
+                if let Some(PreviewWindow {
+                    refined,
+                    texture: Some(texture),
+                    version,
+                    ..
+                }) = self
+                    .preview_window
+                    .as_mut()
+                    .filter(|preview_window| preview_window.node_id == node_id)
+                {
+                    // The resolution of the window may have changed without a new image version
+                    if *version == Some(image_version)
+                        && texture.size() == [image_info.size, image_info.size]
+                        && !(image_info.coarse && *refined)
+                    {
+                        *refined |= !image_info.coarse;
+                        texture.set_partial(
+                            Threads::coord_to_row_col(image_info.coord, chunk_size),
+                            chunk_image,
+                            Default::default(),
+                        );
+                    }
+                }
+
+                continue;
+            }
+
             // We have to check to make sure snarl *still* contains this index because it may have
             // been removed by the time the thread has responded to the image request
             if !node_ids.contains(&node_id) {
@@ -177,6 +962,7 @@ impl App {
             }
 
             if let Some(Image {
+                stats,
                 texture: Some(texture),
                 version,
                 ..
@@ -186,14 +972,16 @@ impl App {
                 .and_then(NoiseNode::image_mut)
             {
                 // We have to check to make sure the current image version is the same one the
-                // thread has responded with - if not a new request will be received later
-                if *version != image_version {
+                // thread has responded with - if not a new request will be received later. Coarse
+                // chunks are dropped once fine chunks have arrived so they never overwrite detail
+                if *version != image_version || (image_info.coarse && stats.count > 0) {
                     continue;
                 }
 
+                stats.merge(&chunk_stats);
                 texture.set_partial(
-                    Threads::coord_to_row_col(coord),
-                    ColorImage::from_gray([Threads::IMAGE_SIZE, Threads::IMAGE_SIZE], &image),
+                    Threads::coord_to_row_col(image_info.coord, chunk_size),
+                    chunk_image,
                     Default::default(),
                 );
             }
@@ -203,6 +991,142 @@ impl App {
         NODE_IDS.set(Some(node_ids));
     }
 
+    fn update_plot_window(&mut self) {
+        let Some(plot_window) = &mut self.plot_window else {
+            return;
+        };
+
+        // Sample the line again whenever the preview image of the node has been updated
+        if let Some(image) = self
+            .snarl
+            .get_node(plot_window.node_id)
+            .and_then(NoiseNode::image)
+            .filter(|image| plot_window.version != Some(image.version))
+        {
+            if let Some((_, expr)) = self
+                .node_exprs
+                .read()
+                .unwrap()
+                .get(&plot_window.node_id)
+                .filter(|(version, _)| *version == image.version)
+            {
+                plot_window.version = Some(image.version);
+                plot_window.sample(expr, image, self.timeline.w());
+            }
+        }
+    }
+
+    fn update_preview_window(&mut self, ctx: &Context) {
+        let Some(preview_window) = &mut self.preview_window else {
+            return;
+        };
+
+        // Request a new texture whenever the preview image of the node has been updated
+        if let Some(image) = self
+            .snarl
+            .get_node(preview_window.node_id)
+            .and_then(NoiseNode::image)
+            .filter(|image| preview_window.version != Some(image.version))
+        {
+            let size = preview_window.resolution.size();
+
+            if preview_window
+                .texture
+                .as_ref()
+                .is_none_or(|texture| texture.size() != [size, size])
+            {
+                preview_window.texture = Some(ctx.load_texture(
+                    "preview_window",
+                    ColorImage::new([size, size], Color32::TRANSPARENT),
+                    Default::default(),
+                ));
+            }
+
+            preview_window.refined = false;
+            preview_window.version = Some(image.version);
+
+            let image_info = ImageInfo {
+                coarse: true,
+                colormap: image.colormap,
+                contours: image.contours,
+                coord: 0,
+                detached: true,
+                draft: self.dragging,
+                ramp: NoiseNode::color_ramp(preview_window.node_id, &self.snarl),
+                scale: image.scale,
+                shaded: image.shaded,
+                size,
+                w: self.timeline.w(),
+                x: image.x,
+                y: image.y,
+            };
+
+            for coord in 0..=u8::MAX {
+                self.threads.send(
+                    preview_window.node_id,
+                    image.version,
+                    ImageInfo {
+                        coord,
+                        ..image_info
+                    },
+                );
+            }
+
+            for coord in shuffled_u8(image.version).iter().copied() {
+                self.threads.send(
+                    preview_window.node_id,
+                    image.version,
+                    ImageInfo {
+                        coarse: false,
+                        coord,
+                        ..image_info
+                    },
+                );
+            }
+        }
+    }
+
+    fn update_terrain(&mut self) {
+        for (node_id, version, terrain_info, heights) in self.threads.try_recv_terrain_iter() {
+            if let Some(terrain_preview) = self.terrain_preview.as_mut().filter(|terrain_preview| {
+                terrain_preview.node_id == node_id
+                    && terrain_preview.projection == terrain_info.projection
+                    && terrain_preview.version == Some(version)
+            }) {
+                terrain_preview.set_band(terrain_info.band, &heights);
+            }
+        }
+
+        let Some(terrain_preview) = &mut self.terrain_preview else {
+            return;
+        };
+
+        // Request new heights whenever the preview image of the node has been updated
+        if let Some(image) = self
+            .snarl
+            .get_node(terrain_preview.node_id)
+            .and_then(NoiseNode::image)
+            .filter(|image| terrain_preview.version != Some(image.version))
+        {
+            terrain_preview.version = Some(image.version);
+
+            for band in 0..Threads::IMAGE_COORDS {
+                self.threads.send_terrain(
+                    terrain_preview.node_id,
+                    image.version,
+                    TerrainInfo {
+                        band,
+                        projection: terrain_preview.projection,
+                        scale: image.scale,
+                        w: self.timeline.w(),
+                        x: image.x,
+                        y: image.y,
+                    },
+                );
+            }
+        }
+    }
+
     fn update_nodes(&mut self, ctx: &Context) {
         thread_local! {
             static CHILD_NODE_IDS: RefCell<Option<HashSet<NodeId>>> = RefCell::new(Some(Default::default()));
@@ -212,6 +1136,28 @@ impl App {
         let mut child_node_ids = CHILD_NODE_IDS.take().unwrap();
         let mut temp_node_ids = TEMP_NODE_IDS.take().unwrap();
 
+        // Formulas and scripts refer to decimal constants by name instead of by wire
+        let formula_node_ids = self
+            .snarl
+            .node_ids()
+            .filter(|(_, node)| {
+                let references = |name: &str| match node {
+                    NoiseNode::Formula(node) => node.references(name),
+                    NoiseNode::Script(node) => node.references(name),
+                    _ => false,
+                };
+
+                self.updated_node_ids.iter().any(|&node_id| {
+                    matches!(
+                        self.snarl.get_node(node_id),
+                        Some(NoiseNode::F64(constant)) if references(&constant.name)
+                    )
+                })
+            })
+            .map(|(node_id, _)| node_id)
+            .collect::<Vec<_>>();
+        self.updated_node_ids.extend(formula_node_ids);
+
         // Before we process the user-updated nodes, we must propagate updates to child nodes
         for node_id in self.updated_node_ids.iter().copied() {
             temp_node_ids.push(node_id);
@@ -244,17 +1190,23 @@ impl App {
                 .get_node_mut(node_id)
                 .and_then(NoiseNode::image_mut)
             {
-                // Ensure all image nodes contain a valid texture
-                if image.texture.is_none() {
+                // Ensure all image nodes contain a valid texture of the selected resolution
+                let size = image.resolution.size();
+                if image
+                    .texture
+                    .as_ref()
+                    .is_none_or(|texture| texture.size() != [size, size])
+                {
                     debug!("Creating image for #{node_id:?}");
 
                     image.texture = Some(ctx.load_texture(
                         format!("image{node_id:?}"),
-                        ColorImage::new(Self::IMAGE_SIZE, Color32::TRANSPARENT),
+                        ColorImage::new([size, size], Color32::TRANSPARENT),
                         Default::default(),
                     ));
                 }
 
+                image.stats = Default::default();
                 image.version = self.version;
             }
         }
@@ -275,9 +1227,53 @@ impl App {
 
                 self.node_exprs.write().unwrap().insert(
                     node_id,
-                    (image.version, Arc::new(node.expr(node_id, &self.snarl))),
+                    (
+                        image.version,
+                        Arc::new(self.timeline.apply(node.expr(node_id, &self.snarl))),
+                    ),
                 );
 
+                // The expression is still kept up to date for the detached views of this node
+                if image.disabled {
+                    continue;
+                }
+
+                if self.dragging {
+                    self.draft_node_ids.insert(node_id);
+                }
+
+                let image_info = ImageInfo {
+                    coarse: false,
+                    colormap: image.colormap,
+                    contours: image.contours,
+                    coord: 0,
+                    detached: false,
+                    draft: self.dragging,
+                    ramp: NoiseNode::color_ramp(node_id, &self.snarl),
+                    scale: image.scale,
+                    shaded: image.shaded,
+                    size: image.resolution.size(),
+                    w: self.timeline.w(),
+                    x: image.x,
+                    y: image.y,
+                };
+
+                // The coarse pass is cheap so it is sent right away, ahead of all fine chunks (drafts
+                // are nearly as cheap so they skip it)
+                if !self.dragging {
+                    for coord in 0..=u8::MAX {
+                        self.threads.send(
+                            node_id,
+                            image.version,
+                            ImageInfo {
+                                coarse: true,
+                                coord,
+                                ..image_info
+                            },
+                        );
+                    }
+                }
+
                 // We request coordinate chunks from the threads using pre-shuffled data so that
                 // all the responses come back in a static-like pattern and not row by row
                 for coord in shuffled_u8(image.version).iter().copied() {
@@ -286,9 +1282,7 @@ impl App {
                         image.version,
                         ImageInfo {
                             coord,
-                            scale: image.scale,
-                            x: image.x,
-                            y: image.y,
+                            ..image_info
                         },
                     ));
                 }
@@ -313,22 +1307,56 @@ impl App {
 
 impl eframe::App for App {
     fn save(&mut self, storage: &mut dyn Storage) {
-        set_value(storage, APP_KEY, &self.snarl);
+        set_value(storage, APP_KEY, &self.root_snarl());
+        self.keymap.save(storage);
+        self.presets.save(storage);
+
+        #[cfg(not(target_arch = "wasm32"))]
+        self.recent_files.save(storage);
     }
 
     fn update(&mut self, ctx: &Context, _frame: &mut Frame) {
         #[cfg(target_arch = "wasm32")]
         self.threads.update();
 
+        self.dragging = ctx.dragged_id().is_some();
         self.update_images();
 
-        #[cfg(not(target_arch = "wasm32"))]
+        if !self.paused {
+            self.update_plot_window();
+            self.update_preview_window(ctx);
+            self.update_terrain();
+        }
+
+        let mut timeline_changed = false;
+
         TopBottomPanel::top("top_panel").show(ctx, |ui| {
             menu::bar(ui, |ui| {
+                #[cfg(not(target_arch = "wasm32"))]
                 ui.menu_button("File", |ui| {
+                    if ui.button("New Tab").clicked() {
+                        self.new_tab();
+
+                        ui.close_menu();
+                    }
+
+                    if ui
+                        .add_enabled(self.tabs.len() > 1, Button::new("Close Tab"))
+                        .clicked()
+                    {
+                        self.close_tab(self.active_tab);
+
+                        ui.close_menu();
+                    }
+
+                    ui.separator();
+
                     if ui.button("New").clicked() {
                         self.path = None;
                         self.snarl = Snarl::new();
+                        self.subgraph_stack.clear();
+                        self.unsaved = false;
+                        self.reset_undo();
 
                         ui.close_menu();
                     }
@@ -337,17 +1365,77 @@ impl eframe::App for App {
 
                     if ui.button("Open File...").clicked() {
                         if let Some(path) = Self::file_dialog().pick_file() {
-                            self.snarl = Self::open(&path).unwrap_or_default();
-                            self.path = Some(path);
+                            self.open_path(path);
+                        }
+
+                        ui.close_menu();
+                    }
+
+                    ui.menu_button("Open Recent", |ui| {
+                        if let Some(path) = self.recent_files.show(ui) {
+                            self.open_path(path);
+
+                            ui.close_menu();
+                        }
+                    });
+
+                    ui.menu_button("Examples", |ui| {
+                        for example in &Example::ALL {
+                            if ui.button(example.name).clicked() {
+                                // Examples open in a new tab so that the current graph is kept
+                                self.new_tab();
+                                self.snarl = example.snarl();
+                                self.updated_node_ids =
+                                    Self::all_image_node_ids(&self.snarl).collect();
+                                self.reset_undo();
+
+                                ui.close_menu();
+                            }
+                        }
+                    });
+
+                    if ui.button("Import libnoise XML...").clicked() {
+                        if let Some(snarl) = import::libnoise_file_dialog()
+                            .pick_file()
+                            .and_then(|path| import::open_libnoise(path).ok())
+                        {
+                            self.snarl = snarl;
+                            self.subgraph_stack.clear();
+                            self.path = None;
+                            self.unsaved = true;
                             self.updated_node_ids = Self::all_image_node_ids(&self.snarl).collect();
+                            self.reset_undo();
                         }
 
                         ui.close_menu();
                     }
 
+                    if ui.button("Import FastNoiseLite JSON...").clicked() {
+                        if let Some(snarl) = import::fast_noise_lite_file_dialog()
+                            .pick_file()
+                            .and_then(|path| import::open_fast_noise_lite(path).ok())
+                        {
+                            self.snarl = snarl;
+                            self.subgraph_stack.clear();
+                            self.path = None;
+                            self.unsaved = true;
+                            self.updated_node_ids = Self::all_image_node_ids(&self.snarl).collect();
+                            self.reset_undo();
+                        }
+
+                        ui.close_menu();
+                    }
+
+                    ui.separator();
+
                     if let Some(path) = &self.path {
                         if ui.button("Save").clicked() {
-                            Self::save_as(path, &self.snarl).unwrap_or_default();
+                            let snarl = self.root_snarl();
+
+                            if Self::save_as(path, &Project::new(&snarl)).is_ok() {
+                                self.recent_files.insert(path, &snarl);
+                                self.unsaved = false;
+                            }
 
                             ui.close_menu();
                         }
@@ -360,7 +1448,13 @@ impl eframe::App for App {
 
                     if ui.button("Save As...").clicked() {
                         if let Some(path) = Self::file_dialog().save_file() {
-                            Self::save_as(&path, &self.snarl).unwrap_or_default();
+                            let snarl = self.root_snarl();
+
+                            if Self::save_as(&path, &Project::new(&snarl)).is_ok() {
+                                self.recent_files.insert(&path, &snarl);
+                                self.unsaved = false;
+                            }
+
                             self.path = Some(path);
                         }
 
@@ -369,6 +1463,16 @@ impl eframe::App for App {
 
                     ui.separator();
 
+                    if ui.button("Export Graph Screenshot...").clicked() {
+                        if let Some(path) = png_or_svg_file_dialog().save_file() {
+                            self.start_screenshot(ctx, path);
+                        }
+
+                        ui.close_menu();
+                    }
+
+                    ui.separator();
+
                     if ui.button("Exit").clicked() {
                         ctx.send_viewport_cmd(ViewportCommand::Close);
                     }
@@ -376,15 +1480,271 @@ impl eframe::App for App {
                 ui.add_space(16.0);
 
                 widgets::global_theme_preference_switch(ui);
+                ui.separator();
+
+                timeline_changed = self.timeline.show(ui, &self.snarl);
+                ui.separator();
+
+                ui.checkbox(&mut self.linked_previews, "Link Previews")
+                    .on_hover_text("Panning or zooming any preview moves every preview with it");
+                ui.checkbox(&mut self.minimap, "Minimap")
+                    .on_hover_text("Shows an overview of the whole graph in the corner");
+                ui.checkbox(&mut self.inspector, "Inspector")
+                    .on_hover_text("Shows the parameters of the selected node in a side panel");
+
+                if ui
+                    .button(if self.paused {
+                        "Resume Rendering"
+                    } else {
+                        "Pause Rendering"
+                    })
+                    .on_hover_text("Stops the worker threads from rendering previews")
+                    .clicked()
+                {
+                    self.toggle_paused();
+                }
+
+                ui.separator();
+
+                if ui.button("Find Nodes...").clicked() {
+                    self.graph_search = Some(Default::default());
+                }
+
+                if ui
+                    .button("Variables...")
+                    .on_hover_text("Lists every decimal and integer constant by name")
+                    .clicked()
+                {
+                    self.variables_window = Some(Default::default());
+                }
+
+                if ui
+                    .button("Diagnostics...")
+                    .on_hover_text("Lists nodes which probably do not do what was intended")
+                    .clicked()
+                {
+                    self.diagnostics_window = Some(Default::default());
+                }
+
+                if ui.button("Keyboard Shortcuts...").clicked() {
+                    self.keymap_window = Some(Default::default());
+                }
+
+                if !self.subgraph_stack.is_empty() {
+                    ui.separator();
+
+                    if ui
+                        .button("Exit Subgraph")
+                        .on_hover_text("Returns to the graph containing this subgraph")
+                        .clicked()
+                    {
+                        self.exit_subgraph();
+                    }
+
+                    ui.weak(format!("Subgraph depth {}", self.subgraph_stack.len()));
+                }
+            });
+        });
+
+        TopBottomPanel::top("tab_panel").show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                let mut closed_tab = None;
+                let mut selected_tab = None;
+
+                for idx in 0..self.tabs.len() {
+                    if ui
+                        .selectable_label(idx == self.active_tab, self.tab_title(idx))
+                        .clicked()
+                    {
+                        selected_tab = Some(idx);
+                    }
+
+                    if self.tabs.len() > 1
+                        && ui
+                            .small_button("×")
+                            .on_hover_text("Close this tab")
+                            .clicked()
+                    {
+                        closed_tab = Some(idx);
+                    }
+
+                    ui.separator();
+                }
+
+                if ui
+                    .small_button("+")
+                    .on_hover_text("Opens an empty project in a new tab")
+                    .clicked()
+                {
+                    self.new_tab();
+                }
+
+                if let Some(idx) = selected_tab {
+                    self.select_tab(idx);
+                }
+
+                if let Some(idx) = closed_tab {
+                    self.close_tab(idx);
+                }
             });
         });
 
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(animation_export) = &mut self.animation_export {
+            if !animation_export.show(ctx) {
+                self.animation_export = None;
+            }
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(batch_export) = &mut self.batch_export {
+            if !batch_export.show(ctx, &self.snarl) {
+                self.batch_export = None;
+            }
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(normal_map_export) = &mut self.normal_map_export {
+            if !normal_map_export.show(ctx) {
+                self.normal_map_export = None;
+            }
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(raw_export) = &mut self.raw_export {
+            if !raw_export.show(ctx) {
+                self.raw_export = None;
+            }
+        }
+
+        if let Some(diagnostics_window) = &mut self.diagnostics_window {
+            if !diagnostics_window.show(ctx, &self.snarl) {
+                self.diagnostics_window = None;
+            }
+        }
+
+        if let Some(graph_search) = &mut self.graph_search {
+            if !graph_search.show(ctx, &self.snarl) {
+                self.graph_search = None;
+            }
+        }
+
+        if let Some(keymap_window) = &mut self.keymap_window {
+            if !keymap_window.show(ctx, &mut self.keymap) {
+                self.keymap_window = None;
+            }
+        }
+
+        if let Some(plot_window) = &mut self.plot_window {
+            if !plot_window.show(ctx, &self.snarl) {
+                self.plot_window = None;
+            }
+        }
+
+        if let Some(preview_window) = &mut self.preview_window {
+            if !preview_window.show(ctx, &self.snarl) {
+                self.preview_window = None;
+            }
+        }
+
+        if let Some(terrain_preview) = &mut self.terrain_preview {
+            if !terrain_preview.show(ctx, &self.snarl) {
+                self.terrain_preview = None;
+            }
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        self.open_dropped_file(ctx);
+
+        #[cfg(not(target_arch = "wasm32"))]
+        self.update_screenshot(ctx);
+
+        // Any nodes the viewer updates or removes are user edits
+        let change_count = self.removed_node_ids.len() + self.updated_node_ids.len();
+
+        if let Some(region_dialog) = &mut self.region_dialog {
+            if !region_dialog.show(ctx, &mut self.snarl, &mut self.updated_node_ids) {
+                self.region_dialog = None;
+            }
+        }
+
+        if let Some(node_palette) = &mut self.node_palette {
+            if !node_palette.show(ctx, &mut self.snarl, &mut self.updated_node_ids) {
+                self.node_palette = None;
+            }
+        }
+
+        if let Some(variables_window) = &mut self.variables_window {
+            if !variables_window.show(ctx, &mut self.snarl, &mut self.updated_node_ids) {
+                self.variables_window = None;
+            }
+        }
+
+        if self.inspector {
+            SidePanel::right("inspector_panel")
+                .default_width(256.0)
+                .show(ctx, |ui| {
+                    match *get_selected_nodes(Id::new("snarl"), ctx).as_slice() {
+                        [node_id] => inspector::show(
+                            ui,
+                            node_id,
+                            &mut self.snarl,
+                            &mut self.updated_node_ids,
+                        ),
+                        [] => {
+                            ui.weak("Select a node to show its parameters");
+                        }
+                        _ => {
+                            ui.weak("Select a single node to show its parameters");
+                        }
+                    }
+                });
+        }
+
+        let mut edit_subgraph = None;
+        let mut undo = false;
+
         CentralPanel::default().show(ctx, |ui| {
+            #[cfg(not(target_arch = "wasm32"))]
+            {
+                self.canvas_rect = ui.max_rect();
+            }
+
+            // Nothing is drawn over the graph while a screenshot of it is being taken
+            #[cfg(not(target_arch = "wasm32"))]
+            let overlay = self.screenshot.is_none();
+            #[cfg(target_arch = "wasm32")]
+            let overlay = true;
+
+            let mut viewer = Viewer {
+                #[cfg(not(target_arch = "wasm32"))]
+                animation_export: &mut self.animation_export,
+                #[cfg(not(target_arch = "wasm32"))]
+                batch_export: &mut self.batch_export,
+                #[cfg(not(target_arch = "wasm32"))]
+                normal_map_export: &mut self.normal_map_export,
+                #[cfg(not(target_arch = "wasm32"))]
+                raw_export: &mut self.raw_export,
+                clipboard: &mut self.clipboard,
+                dependency_chain: DependencyChain::new(
+                    &get_selected_nodes(Id::new("snarl"), ui.ctx()),
+                    &self.snarl,
+                ),
+                edit_subgraph: None,
+                graph_transform: &mut self.graph_transform,
+                linked_previews: self.linked_previews,
+                plot_window: &mut self.plot_window,
+                presets: &mut self.presets,
+                preview_window: &mut self.preview_window,
+                region_dialog: &mut self.region_dialog,
+                removed_node_ids: &mut self.removed_node_ids,
+                terrain_preview: &mut self.terrain_preview,
+                updated_node_ids: &mut self.updated_node_ids,
+                w: self.timeline.w(),
+            };
+
             self.snarl.show(
-                &mut Viewer {
-                    removed_node_ids: &mut self.removed_node_ids,
-                    updated_node_ids: &mut self.updated_node_ids,
-                },
+                &mut viewer,
                 &SnarlStyle {
                     bg_pattern: Some(BackgroundPattern::Grid(Grid::new(
                         Vec2::new(20.0, 20.0),
@@ -396,25 +1756,122 @@ impl eframe::App for App {
                 Id::new("snarl"),
                 ui,
             );
-            ui.with_layout(Layout::bottom_up(Align::LEFT), |ui| {
-                ui.add(github_link_file!(
-                    "https://github.com/attackgoat/noise_gui/blob/master/",
-                    "Source code"
-                ));
+            edit_subgraph = viewer.edit_subgraph;
 
-                ui.horizontal(|ui| {
-                    ui.spacing_mut().item_spacing.x = 0.0;
-                    ui.label("Powered by ");
-                    ui.hyperlink_to("egui-snarl", "https://github.com/zakarumych/egui-snarl");
-                });
+            // Text fields keep the keyboard to themselves
+            if self.keymap_window.is_none() && ui.memory(|memory| memory.focused().is_none()) {
+                match self.keymap.pressed(ui.ctx()) {
+                    Some(Action::Delete) => viewer.remove_selected_nodes(ui.ctx(), &mut self.snarl),
+                    Some(Action::Duplicate) => self.duplicate_selected_nodes(ui.ctx()),
+                    Some(Action::FrameAll) => self.frame_all(ui.max_rect()),
+                    Some(Action::OpenPalette) => {
+                        let screen_pos = ui
+                            .ctx()
+                            .pointer_hover_pos()
+                            .filter(|&pos| ui.max_rect().contains(pos))
+                            .unwrap_or_else(|| ui.max_rect().center());
+                        self.node_palette =
+                            Some(NodePalette::new(screen_pos, self.graph_pos(screen_pos)));
+                        ui.ctx().request_repaint();
+                    }
+                    Some(Action::Search) => self.graph_search = Some(Default::default()),
+                    Some(Action::Undo) => undo = true,
+                    None => (),
+                }
+            }
 
-                warn_if_debug_build(ui);
-            });
+            if let Some(node_id) = self
+                .graph_search
+                .as_mut()
+                .and_then(|graph_search| graph_search.found_node_id.take())
+                .or_else(|| {
+                    self.diagnostics_window
+                        .as_mut()
+                        .and_then(|diagnostics_window| diagnostics_window.found_node_id.take())
+                })
+                .or_else(|| {
+                    self.variables_window
+                        .as_mut()
+                        .and_then(|variables_window| variables_window.found_node_id.take())
+                })
+            {
+                self.center_node(node_id, ui.max_rect());
+            }
+
+            if overlay && self.minimap && self.graph_transform.is_some() {
+                let rect = ui.max_rect();
+                let viewport =
+                    Rect::from_min_max(self.graph_pos(rect.min), self.graph_pos(rect.max));
+
+                if let Some(offset) = minimap::show(ui, rect, viewport, &self.snarl) {
+                    self.move_nodes(offset);
+                }
+            }
+
+            if overlay {
+                ui.with_layout(Layout::bottom_up(Align::LEFT), |ui| {
+                    ui.add(github_link_file!(
+                        "https://github.com/attackgoat/noise_gui/blob/master/",
+                        "Source code"
+                    ));
+
+                    ui.horizontal(|ui| {
+                        ui.spacing_mut().item_spacing.x = 0.0;
+                        ui.label("Powered by ");
+                        ui.hyperlink_to("egui-snarl", "https://github.com/zakarumych/egui-snarl");
+                    });
+
+                    warn_if_debug_build(ui);
+                });
+            }
         });
 
+        self.move_groups(ctx);
+
+        if self.removed_node_ids.len() + self.updated_node_ids.len() > change_count {
+            self.undo_pending = true;
+
+            #[cfg(not(target_arch = "wasm32"))]
+            {
+                self.unsaved = true;
+            }
+        }
+
+        // Each drag of a value is undone in one step, so it is only recorded once released
+        if undo {
+            self.undo();
+        } else if self.undo_pending && !self.dragging {
+            self.undo_history
+                .push(replace(&mut self.undo_snarl, self.snarl.clone()));
+
+            if self.undo_history.len() > Self::MAX_UNDO_COUNT {
+                self.undo_history.remove(0);
+            }
+
+            self.undo_pending = false;
+        }
+
+        if let Some(node_id) = edit_subgraph {
+            self.edit_subgraph(node_id);
+        }
+
+        // The timeline changes every preview without editing the graph
+        if self.timeline.advance(ctx) || timeline_changed {
+            self.updated_node_ids
+                .extend(Self::all_image_node_ids(&self.snarl));
+        }
+
+        // Drafts are rendered again at full resolution once the drag has been released
+        if !self.dragging {
+            self.updated_node_ids.extend(self.draft_node_ids.drain());
+        }
+
         if self.has_changes() {
             self.remove_nodes();
-            self.update_nodes(ctx);
+
+            if !self.paused {
+                self.update_nodes(ctx);
+            }
         }
     }
 }
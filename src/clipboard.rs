@@ -0,0 +1,182 @@
+use {
+    super::node::{NodeValue, NoiseNode},
+    egui::Pos2,
+    egui_snarl::{InPinId, NodeId, OutPinId, Snarl},
+    std::collections::{HashMap, HashSet},
+};
+
+/// Returns a copy of a node where connections to nodes other than `node_ids` are replaced by their
+/// current value.
+pub fn detached_node(
+    snarl: &Snarl<NoiseNode>,
+    node_id: NodeId,
+    node_ids: &HashSet<NodeId>,
+) -> NoiseNode {
+    let mut node = snarl.get_node(node_id).unwrap().clone();
+
+    // Clones share the texture handle of the original node
+    if let Some(image) = node.image_mut() {
+        image.texture = None;
+    }
+
+    // Groups only keep the members which were copied along with them
+    if let NoiseNode::Group(group) = &mut node {
+        group.node_ids.retain(|node_id| node_ids.contains(node_id));
+        group.pos = None;
+    }
+
+    let values = node.node_values_mut();
+
+    for node_id in values
+        .control_point_node_ids
+        .into_iter()
+        .chain(values.vector_node_ids)
+    {
+        if node_id.is_some_and(|node_id| !node_ids.contains(&node_id)) {
+            *node_id = None;
+        }
+    }
+
+    for value in values.f64s {
+        if let NodeValue::Node(node_id) = *value {
+            if !node_ids.contains(&node_id) {
+                *value = NodeValue::Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+            }
+        }
+    }
+
+    for value in values.tuples {
+        if value
+            .as_node_id()
+            .is_some_and(|node_id| !node_ids.contains(&node_id))
+        {
+            *value = NodeValue::Value(());
+        }
+    }
+
+    for value in values.u32s {
+        if let NodeValue::Node(node_id) = *value {
+            if !node_ids.contains(&node_id) {
+                *value = NodeValue::Value(snarl.get_node(node_id).unwrap().eval_u32(snarl));
+            }
+        }
+    }
+
+    node
+}
+
+/// Nodes copied from the graph along with the connections between them.
+///
+/// Values connected to nodes which were not copied are replaced by their current value, so a pasted
+/// sub-graph previews exactly as the original did.
+#[derive(Default)]
+pub struct Clipboard {
+    nodes: Vec<(NodeId, Pos2, NoiseNode)>,
+    wires: Vec<(OutPinId, InPinId)>,
+}
+
+impl Clipboard {
+    pub fn copy(&mut self, snarl: &Snarl<NoiseNode>, node_ids: &[NodeId]) {
+        let node_ids = node_ids
+            .iter()
+            .copied()
+            .filter(|&node_id| snarl.get_node(node_id).is_some())
+            .collect::<HashSet<_>>();
+
+        self.nodes.clear();
+        self.wires.clear();
+
+        for &node_id in &node_ids {
+            self.nodes.push((
+                node_id,
+                snarl.get_node_info(node_id).unwrap().pos,
+                detached_node(snarl, node_id, &node_ids),
+            ));
+        }
+
+        self.wires.extend(
+            snarl
+                .wires()
+                .filter(|(from, to)| node_ids.contains(&from.node) && node_ids.contains(&to.node)),
+        );
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Inserts a copy of the nodes with their top-left corner at `pos`, returning the id of each
+    /// pasted node keyed by the id of the node it was copied from.
+    pub fn paste(&self, snarl: &mut Snarl<NoiseNode>, pos: Pos2) -> HashMap<NodeId, NodeId> {
+        let min = self.nodes.iter().fold(
+            Pos2::new(f32::INFINITY, f32::INFINITY),
+            |min, (_, pos, _)| min.min(*pos),
+        );
+
+        let node_ids = self
+            .nodes
+            .iter()
+            .map(|(node_id, node_pos, node)| {
+                (
+                    *node_id,
+                    snarl.insert_node(pos + (*node_pos - min), node.clone()),
+                )
+            })
+            .collect::<HashMap<_, _>>();
+
+        // Values connected to other copied nodes must refer to the pasted nodes instead
+        for &node_id in node_ids.values() {
+            let node = snarl.get_node_mut(node_id).unwrap();
+
+            if let NoiseNode::Group(group) = node {
+                for node_id in &mut group.node_ids {
+                    *node_id = node_ids[&*node_id];
+                }
+            }
+
+            let values = node.node_values_mut();
+
+            for node_id in values
+                .control_point_node_ids
+                .into_iter()
+                .chain(values.vector_node_ids)
+                .flatten()
+            {
+                *node_id = node_ids[&*node_id];
+            }
+
+            for value in values.f64s {
+                if let NodeValue::Node(node_id) = value {
+                    *node_id = node_ids[&*node_id];
+                }
+            }
+
+            for value in values.tuples {
+                if let NodeValue::Node(node_id) = value {
+                    *node_id = node_ids[&*node_id];
+                }
+            }
+
+            for value in values.u32s {
+                if let NodeValue::Node(node_id) = value {
+                    *node_id = node_ids[&*node_id];
+                }
+            }
+        }
+
+        for (from, to) in &self.wires {
+            snarl.connect(
+                OutPinId {
+                    node: node_ids[&from.node],
+                    output: from.output,
+                },
+                InPinId {
+                    node: node_ids[&to.node],
+                    input: to.input,
+                },
+            );
+        }
+
+        node_ids
+    }
+}
@@ -0,0 +1,156 @@
+use {
+    super::{
+        expr::OpType,
+        node::{ConstantOpNode, NoiseNode},
+    },
+    egui::{Context, Id, RichText, ScrollArea, Window},
+    egui_snarl::{InPinId, NodeId, Snarl},
+    std::collections::HashSet,
+};
+
+/// A problem with a node which does not stop the graph from rendering but probably does not do
+/// what the user intended.
+pub struct Diagnostic {
+    pub node_id: NodeId,
+    pub message: &'static str,
+}
+
+impl Diagnostic {
+    /// Returns every problem found in the graph, in node order.
+    pub fn find_all(snarl: &Snarl<NoiseNode>) -> Vec<Self> {
+        let connected_node_ids = snarl
+            .wires()
+            .map(|(out_pin, _)| out_pin.node)
+            .collect::<HashSet<_>>();
+
+        let mut res = vec![];
+
+        for (node_id, node) in snarl.node_ids() {
+            let mut push = |message| res.push(Self { node_id, message });
+
+            match node {
+                NoiseNode::Curve(node) => {
+                    let mut inputs = Self::control_point_inputs(&node.control_point_node_ids)
+                        .filter_map(|node_id| snarl.get_node(node_id)?.as_control_point())
+                        .map(|control_point| control_point.input.eval(snarl))
+                        .chain(node.points.iter().map(|&[input, _]| input))
+                        .collect::<Vec<_>>();
+                    inputs.sort_by(f64::total_cmp);
+                    inputs.dedup();
+
+                    if inputs.len() < 4 {
+                        push("Curve needs at least 4 control points with different inputs; it renders as a constant");
+                    }
+                }
+                NoiseNode::Terrace(node) => {
+                    let mut inputs = Self::control_point_inputs(&node.control_point_node_ids)
+                        .filter_map(|node_id| Some(snarl.get_node(node_id)?.eval_f64(snarl)))
+                        .chain(node.points.iter().copied())
+                        .collect::<Vec<_>>();
+                    inputs.sort_by(f64::total_cmp);
+                    inputs.dedup();
+
+                    if inputs.len() < 2 {
+                        push("Terrace needs at least 2 different control points; it renders as a constant");
+                    }
+                }
+                NoiseNode::Switch(node)
+                    if node.selector.eval(snarl) as usize >= node.source_count =>
+                {
+                    push("Switch selector is past the last source; the last source is passed through");
+                }
+                NoiseNode::F64Operation(ConstantOpNode {
+                    inputs: [_, rhs],
+                    op_ty: OpType::Divide,
+                    ..
+                }) if rhs.eval(snarl) == 0.0 => {
+                    push("Division by zero; the result is 0");
+                }
+                NoiseNode::U32Operation(ConstantOpNode {
+                    inputs: [_, rhs],
+                    op_ty: OpType::Divide,
+                    ..
+                }) if rhs.eval(snarl) == 0 => {
+                    push("Division by zero; the result is 0");
+                }
+                NoiseNode::SubgraphOutput(_) => {
+                    let input = InPinId {
+                        node: node_id,
+                        input: 0,
+                    };
+
+                    if snarl.in_pin(input).remotes.is_empty() {
+                        push(
+                            "Subgraph output is not connected; the subgraph renders as a constant",
+                        );
+                    }
+                }
+                _ => (),
+            }
+
+            // Value nodes have no preview, so an unconnected output has no effect at all
+            let has_output = !matches!(
+                node,
+                NoiseNode::Comment(_) | NoiseNode::Group(_) | NoiseNode::SubgraphOutput(_)
+            );
+
+            if has_output && !node.has_image() && !connected_node_ids.contains(&node_id) {
+                push("Output is not connected to any node");
+            }
+        }
+
+        res
+    }
+
+    fn control_point_inputs(node_ids: &[Option<NodeId>]) -> impl Iterator<Item = NodeId> + '_ {
+        node_ids.iter().copied().flatten()
+    }
+}
+
+/// A window listing the problems found in the graph.
+#[derive(Default)]
+pub struct DiagnosticsWindow {
+    /// Set when a problem has been clicked; the view is centered on its node.
+    pub found_node_id: Option<NodeId>,
+}
+
+impl DiagnosticsWindow {
+    /// Returns `false` once the window has been closed.
+    pub fn show(&mut self, ctx: &Context, snarl: &Snarl<NoiseNode>) -> bool {
+        let mut open = true;
+
+        Window::new("Diagnostics")
+            .id(Id::new("diagnostics"))
+            .open(&mut open)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                let diagnostics = Diagnostic::find_all(snarl);
+
+                if diagnostics.is_empty() {
+                    ui.weak("No problems found");
+
+                    return;
+                }
+
+                ScrollArea::vertical().max_height(320.0).show(ui, |ui| {
+                    for Diagnostic { node_id, message } in diagnostics {
+                        let node = snarl.get_node(node_id).unwrap();
+
+                        ui.horizontal(|ui| {
+                            if ui
+                                .link(node.title().unwrap_or(node.name()))
+                                .on_hover_text("Moves the view to this node")
+                                .clicked()
+                            {
+                                self.found_node_id = Some(node_id);
+                            }
+
+                            ui.label(RichText::new(message).weak());
+                        });
+                    }
+                });
+            });
+
+        open
+    }
+}
@@ -0,0 +1,61 @@
+use {
+    super::{import::parse_libnoise, node::NoiseNode},
+    egui_snarl::Snarl,
+};
+
+/// A demonstration graph compiled into the binary, stored as a libnoise module description.
+///
+/// Examples are read by the libnoise importer, which is only built for native targets, so the web
+/// build has no examples.
+pub struct Example {
+    pub name: &'static str,
+    xml: &'static str,
+}
+
+impl Example {
+    pub const ALL: [Self; 4] = [
+        Self {
+            name: "Terrain",
+            xml: include_str!("../examples/terrain.xml"),
+        },
+        Self {
+            name: "Wood Grain",
+            xml: include_str!("../examples/wood.xml"),
+        },
+        Self {
+            name: "Caves",
+            xml: include_str!("../examples/caves.xml"),
+        },
+        Self {
+            name: "Clouds",
+            xml: include_str!("../examples/clouds.xml"),
+        },
+    ];
+
+    pub fn snarl(&self) -> Snarl<NoiseNode> {
+        parse_libnoise(self.xml).expect("Invalid example graph")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{super::diagnostics::Diagnostic, *};
+
+    #[test]
+    fn examples() {
+        for example in &Example::ALL {
+            let snarl = example.snarl();
+
+            assert!(
+                snarl.node_ids().next().is_some(),
+                "{} is empty",
+                example.name
+            );
+            assert!(
+                Diagnostic::find_all(&snarl).is_empty(),
+                "{} has diagnostics",
+                example.name
+            );
+        }
+    }
+}
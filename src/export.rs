@@ -0,0 +1,916 @@
+use {
+    super::{
+        app::App,
+        expr::{Expr, ExprBundle, NamedVariable, VariableValue},
+        node::{Image, NoiseNode},
+    },
+    base64::{engine::general_purpose::STANDARD, Engine},
+    egui::{ColorImage, ComboBox, Context, DragValue, Grid, ProgressBar, Window},
+    egui_snarl::{NodeId, Snarl},
+    image::{
+        codecs::png::PngEncoder, save_buffer_with_format, ExtendedColorType, ImageEncoder,
+        ImageFormat,
+    },
+    log::warn,
+    rfd::FileDialog,
+    std::{
+        collections::HashSet,
+        fs::{remove_file, OpenOptions},
+        io::{BufWriter, ErrorKind, Write},
+        mem::discriminant,
+        path::{Path, PathBuf},
+        sync::{
+            atomic::{AtomicBool, AtomicU32, Ordering},
+            Arc,
+        },
+        thread::{spawn, JoinHandle},
+    },
+};
+
+pub const PNG_EXTENSION: &str = "png";
+pub const SVG_EXTENSION: &str = "svg";
+
+pub fn png_file_dialog() -> FileDialog {
+    FileDialog::new().add_filter("PNG Image", &[PNG_EXTENSION])
+}
+
+pub fn save_png(path: impl AsRef<Path>, image: &ColorImage) -> anyhow::Result<()> {
+    let mut path = path.as_ref().to_path_buf();
+
+    if path.extension().is_none() {
+        path.set_extension(PNG_EXTENSION);
+    }
+
+    save_buffer_with_format(
+        path,
+        image.as_raw(),
+        image.width() as _,
+        image.height() as _,
+        ExtendedColorType::Rgba8,
+        ImageFormat::Png,
+    )
+    .inspect_err(|_| warn!("Unable to write file"))?;
+
+    Ok(())
+}
+
+pub fn png_or_svg_file_dialog() -> FileDialog {
+    png_file_dialog().add_filter("SVG Image", &[SVG_EXTENSION])
+}
+
+/// Saves an image as an SVG document when the path has the SVG extension, or as a PNG image
+/// otherwise.
+pub fn save_png_or_svg(path: impl AsRef<Path>, image: &ColorImage) -> anyhow::Result<()> {
+    let path = path.as_ref();
+
+    if path
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case(SVG_EXTENSION))
+    {
+        save_svg(path, image)
+    } else {
+        save_png(path, image)
+    }
+}
+
+/// Saves an image as an SVG document which embeds it as a PNG image, so that it may be placed and
+/// scaled like any other vector graphic.
+pub fn save_svg(path: impl AsRef<Path>, image: &ColorImage) -> anyhow::Result<()> {
+    let mut png = vec![];
+    PngEncoder::new(&mut png)
+        .write_image(
+            image.as_raw(),
+            image.width() as _,
+            image.height() as _,
+            ExtendedColorType::Rgba8,
+        )
+        .inspect_err(|_| warn!("Unable to encode image"))?;
+
+    let file = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(path)
+        .inspect_err(|_| warn!("Unable to create file"))?;
+    let [width, height] = image.size;
+
+    write!(
+        BufWriter::new(file),
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}"><image width="{width}" height="{height}" href="data:image/png;base64,{}"/></svg>"#,
+        STANDARD.encode(png),
+    )
+    .inspect_err(|_| warn!("Unable to write file"))?;
+
+    Ok(())
+}
+
+/// Bundles the expression of a node with all named values which are connected to it.
+///
+/// Names are not required to be unique; if several nodes of the same type share a name only the
+/// first value found is listed because `Expr::set_f64` and `Expr::set_u32` update them together.
+pub fn expr_bundle(node_id: NodeId, snarl: &Snarl<NoiseNode>) -> ExprBundle {
+    let mut variables: Vec<NamedVariable> = vec![];
+    let mut visited_node_ids = HashSet::new();
+    let mut node_ids = vec![node_id];
+
+    while let Some(node_id) = node_ids.pop() {
+        if !visited_node_ids.insert(node_id) {
+            continue;
+        }
+
+        let variable = match snarl.get_node(node_id).unwrap() {
+            NoiseNode::F64(node) => Some(NamedVariable {
+                name: node.name.clone(),
+                value: VariableValue::F64(node.value),
+            }),
+            NoiseNode::U32(node) => Some(NamedVariable {
+                name: node.name.clone(),
+                value: VariableValue::U32(node.value),
+            }),
+            _ => None,
+        };
+
+        if let Some(variable) = variable {
+            if !variables.iter().any(|other| {
+                other.name == variable.name
+                    && discriminant(&other.value) == discriminant(&variable.value)
+            }) {
+                variables.push(variable);
+            }
+        }
+
+        node_ids.extend(
+            snarl
+                .wires()
+                .filter(|(_, to)| to.node == node_id)
+                .map(|(from, _)| from.node),
+        );
+    }
+
+    variables.sort_by(|lhs, rhs| lhs.name.cmp(&rhs.name));
+
+    ExprBundle {
+        expr: snarl.get_node(node_id).unwrap().expr(node_id, snarl),
+        variables,
+    }
+}
+
+/// Samples an expression using the same coordinates as node previews, returning row-major heights
+/// remapped from `-1..1` into `0..1`.
+pub fn sample(expr: &Expr, image: &Image, size: usize, z: f64) -> Vec<f64> {
+    let noise = expr.noise();
+    let step = 1.0 / size as f64;
+    let half_step = step / 2.0;
+    let mut samples = Vec::with_capacity(size * size);
+
+    for row in 0..size {
+        let eval_x = (row as f64 * step + half_step + image.y) * image.scale;
+        for col in 0..size {
+            let eval_y = (col as f64 * step + half_step + image.x) * image.scale;
+            samples.push((noise.get([eval_x, eval_y, z]) + 1.0) / 2.0);
+        }
+    }
+
+    samples
+}
+
+/// Renders an expression as grayscale pixels using the same sampling as node previews.
+pub fn sample_gray(expr: &Expr, image: &Image, size: usize, z: f64) -> Vec<u8> {
+    sample(expr, image, size, z)
+        .into_iter()
+        .map(|sample| (sample * 255.0) as u8)
+        .collect()
+}
+
+/// Renders an expression as an image using the same sampling as node previews; expressions which
+/// produce a color keep their channels and any other expression is grayscale.
+pub fn sample_color(expr: &Expr, image: &Image, size: usize, z: f64) -> ColorImage {
+    let Some(channels) = expr.channels() else {
+        return ColorImage::from_gray([size, size], &sample_gray(expr, image, size, z));
+    };
+
+    let channels = channels
+        .each_ref()
+        .map(|channel| sample_gray(channel, image, size, z));
+    let pixels = (0..size * size)
+        .flat_map(|idx| channels.each_ref().map(|channel| channel[idx]))
+        .collect::<Vec<_>>();
+
+    ColorImage::from_rgb([size, size], &pixels)
+}
+
+/// Settings used to export a node as a series of frames while sweeping the Z axis or a variable.
+pub struct AnimationExport {
+    pub bundle: ExprBundle,
+    pub image: Image,
+
+    pub columns: u32,
+    pub end: f64,
+    pub frames: u32,
+    pub size: u32,
+    pub sprite_sheet: bool,
+    pub start: f64,
+
+    /// The name of the swept variable, or `None` to sweep the Z axis.
+    pub variable: Option<String>,
+}
+
+impl AnimationExport {
+    const MAX_FRAMES: u32 = 1_024;
+    const MAX_SIZE: u32 = 4_096;
+
+    pub fn new(bundle: ExprBundle, image: &Image) -> Self {
+        Self {
+            bundle,
+            image: Image {
+                texture: None,
+                ..image.clone()
+            },
+            columns: 4,
+            end: 1.0,
+            frames: 16,
+            size: 128,
+            sprite_sheet: false,
+            start: 0.0,
+            variable: None,
+        }
+    }
+
+    /// Renders the given frame, where the swept value moves from `start` to `end` inclusive.
+    fn frame(&self, frame: u32) -> Vec<u8> {
+        let t = if self.frames > 1 {
+            frame as f64 / (self.frames - 1) as f64
+        } else {
+            0.0
+        };
+        let value = self.start + (self.end - self.start) * t;
+        let size = self.size as usize;
+
+        let Some(name) = &self.variable else {
+            return sample_gray(&self.bundle.expr, &self.image, size, value);
+        };
+
+        let mut expr = self.bundle.expr.clone();
+
+        for variable in &self.bundle.variables {
+            if &variable.name == name {
+                match variable.value {
+                    VariableValue::F64(_) => {
+                        expr.set_f64(name, value);
+                    }
+                    VariableValue::U32(_) => {
+                        expr.set_u32(name, value.round().max(0.0) as u32);
+                    }
+                }
+            }
+        }
+
+        sample_gray(&expr, &self.image, size, 0.0)
+    }
+
+    /// Writes either one sprite sheet or a numbered sequence of files next to the given path.
+    pub fn save_as(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        let path = path.as_ref();
+        let size = self.size as usize;
+
+        if self.sprite_sheet {
+            let columns = self.columns.clamp(1, self.frames) as usize;
+            let rows = (self.frames as usize).div_ceil(columns);
+            let width = columns * size;
+            let mut pixels = vec![0; width * rows * size];
+
+            for frame in 0..self.frames {
+                let (row, col) = (frame as usize / columns, frame as usize % columns);
+                for (y, frame_row) in self.frame(frame).chunks_exact(size).enumerate() {
+                    let offset = (row * size + y) * width + col * size;
+                    pixels[offset..offset + size].copy_from_slice(frame_row);
+                }
+            }
+
+            save_png(path, &ColorImage::from_gray([width, rows * size], &pixels))
+        } else {
+            let stem = path
+                .file_stem()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_string();
+
+            for frame in 0..self.frames {
+                let frame_path = path
+                    .with_file_name(format!("{stem}_{frame:04}"))
+                    .with_extension(PNG_EXTENSION);
+                save_png(
+                    frame_path,
+                    &ColorImage::from_gray([size, size], &self.frame(frame)),
+                )?;
+            }
+
+            Ok(())
+        }
+    }
+
+    /// Returns `false` once the window has been closed.
+    pub fn show(&mut self, ctx: &Context) -> bool {
+        let mut open = true;
+        let mut close = false;
+
+        Window::new("Export Animation")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                Grid::new("animation_export").num_columns(2).show(ui, |ui| {
+                    ui.label("Size");
+                    ui.add(DragValue::new(&mut self.size).range(1..=Self::MAX_SIZE));
+                    ui.end_row();
+
+                    ui.label("Frames");
+                    ui.add(DragValue::new(&mut self.frames).range(1..=Self::MAX_FRAMES));
+                    ui.end_row();
+
+                    ui.label("Sweep");
+                    ComboBox::from_id_salt("animation_export_variable")
+                        .selected_text(self.variable.as_deref().unwrap_or("Z Axis"))
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(&mut self.variable, None, "Z Axis");
+
+                            for variable in &self.bundle.variables {
+                                ui.selectable_value(
+                                    &mut self.variable,
+                                    Some(variable.name.clone()),
+                                    &variable.name,
+                                );
+                            }
+                        });
+                    ui.end_row();
+
+                    ui.label("Start");
+                    ui.add(DragValue::new(&mut self.start).speed(0.01));
+                    ui.end_row();
+
+                    ui.label("End");
+                    ui.add(DragValue::new(&mut self.end).speed(0.01));
+                    ui.end_row();
+
+                    ui.label("Sprite Sheet");
+                    ui.checkbox(&mut self.sprite_sheet, "");
+                    ui.end_row();
+
+                    if self.sprite_sheet {
+                        ui.label("Columns");
+                        ui.add(DragValue::new(&mut self.columns).range(1..=self.frames));
+                        ui.end_row();
+                    }
+                });
+
+                ui.separator();
+
+                ui.horizontal(|ui| {
+                    if ui.button("Export File...").clicked() {
+                        if let Some(path) = png_file_dialog().save_file() {
+                            self.save_as(path).unwrap_or_default();
+                        }
+
+                        close = true;
+                    }
+
+                    if ui.button("Cancel").clicked() {
+                        close = true;
+                    }
+                });
+            });
+
+        open && !close
+    }
+}
+
+/// Settings used to export several nodes at once using a file name pattern.
+pub struct BatchExport {
+    pub node_ids: Vec<NodeId>,
+
+    pub format: BatchFormat,
+    pub pattern: String,
+    pub size: u32,
+}
+
+impl BatchExport {
+    const MAX_SIZE: u32 = 16_384;
+
+    pub fn new(node_ids: Vec<NodeId>) -> Self {
+        Self {
+            node_ids,
+            format: BatchFormat::Ron,
+            pattern: "{node_name}_{node_id}".to_owned(),
+            size: 512,
+        }
+    }
+
+    /// Writes every node which still exists into the given directory.
+    pub fn export(&self, dir: impl AsRef<Path>, snarl: &Snarl<NoiseNode>) -> anyhow::Result<()> {
+        let dir = dir.as_ref();
+
+        for &node_id in &self.node_ids {
+            let Some(node) = snarl.get_node(node_id) else {
+                continue;
+            };
+
+            let mut path = dir.join(self.file_name(node_id, node, snarl));
+            path.set_extension(self.format.extension());
+
+            let expr = node.expr(node_id, snarl);
+
+            match self.format {
+                BatchFormat::Json | BatchFormat::Ron => App::save_as(path, &expr)?,
+                BatchFormat::Png => {
+                    let size = self.size as usize;
+                    save_png(path, &sample_color(&expr, node.image().unwrap(), size, 0.0))?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Replaces `{node_name}`, `{node_id}` and `{seed}` in the pattern with values of the node.
+    fn file_name(&self, node_id: NodeId, node: &NoiseNode, snarl: &Snarl<NoiseNode>) -> String {
+        let seed = node
+            .seed(snarl)
+            .map(|seed| seed.to_string())
+            .unwrap_or_default();
+
+        self.pattern
+            .replace("{node_name}", node.name())
+            .replace("{node_id}", &node_id.0.to_string())
+            .replace("{seed}", &seed)
+            .replace(['/', '\\'], "_")
+    }
+
+    /// Returns `false` once the window has been closed.
+    pub fn show(&mut self, ctx: &Context, snarl: &Snarl<NoiseNode>) -> bool {
+        let mut open = true;
+        let mut close = false;
+
+        Window::new("Export Selected")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label(format!("{} nodes selected", self.node_ids.len()));
+
+                Grid::new("batch_export").num_columns(2).show(ui, |ui| {
+                    ui.label("File Name");
+                    ui.text_edit_singleline(&mut self.pattern)
+                        .on_hover_text("Use {node_name}, {node_id} and {seed}");
+                    ui.end_row();
+
+                    ui.label("Format");
+                    ComboBox::from_id_salt("batch_export_format")
+                        .selected_text(self.format.label())
+                        .show_ui(ui, |ui| {
+                            for format in [BatchFormat::Ron, BatchFormat::Json, BatchFormat::Png] {
+                                ui.selectable_value(&mut self.format, format, format.label());
+                            }
+                        });
+                    ui.end_row();
+
+                    if self.format == BatchFormat::Png {
+                        ui.label("Size");
+                        ui.add(DragValue::new(&mut self.size).range(1..=Self::MAX_SIZE));
+                        ui.end_row();
+                    }
+                });
+
+                ui.separator();
+
+                ui.horizontal(|ui| {
+                    if ui.button("Export To Folder...").clicked() {
+                        if let Some(dir) = FileDialog::new().pick_folder() {
+                            self.export(dir, snarl).unwrap_or_default();
+                        }
+
+                        close = true;
+                    }
+
+                    if ui.button("Cancel").clicked() {
+                        close = true;
+                    }
+                });
+            });
+
+        open && !close
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum BatchFormat {
+    Json,
+    Png,
+    Ron,
+}
+
+impl BatchFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            Self::Json => App::JSON_EXTENSION,
+            Self::Png => PNG_EXTENSION,
+            Self::Ron => App::EXTENSION,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Json => "Expr (JSON)",
+            Self::Png => "Image (PNG)",
+            Self::Ron => "Expr (RON)",
+        }
+    }
+}
+
+/// Settings used to export a node as a tangent-space normal map derived from its heightfield.
+pub struct NormalMapExport {
+    pub expr: Expr,
+    pub image: Image,
+
+    /// Flips the green channel so that +Y points down, as expected by DirectX-style renderers.
+    pub flip_green: bool,
+
+    /// Also writes the grayscale heightfield next to the normal map as `name_height.png`.
+    pub height_map: bool,
+
+    pub size: u32,
+    pub strength: f64,
+}
+
+impl NormalMapExport {
+    const MAX_SIZE: u32 = 4_096;
+
+    pub fn new(expr: Expr, image: &Image) -> Self {
+        Self {
+            expr,
+            image: Image {
+                texture: None,
+                ..image.clone()
+            },
+            flip_green: false,
+            height_map: false,
+            size: 512,
+            strength: 1.0,
+        }
+    }
+
+    /// Computes normals using central differences (one-sided at the edges) of the heightfield.
+    ///
+    /// Slopes are measured in world units so the result does not depend on the output size; the
+    /// strength scales the height of the surface before the normals are found.
+    fn normals(&self, heights: &[f64]) -> Vec<u8> {
+        let size = self.size as usize;
+        let pixel_size = self.image.scale / size as f64;
+        let height = |row: usize, col: usize| heights[row * size + col] * self.strength;
+        let mut pixels = Vec::with_capacity(size * size * 3);
+
+        for row in 0..size {
+            let (up, down) = (row.saturating_sub(1), (row + 1).min(size - 1));
+            for col in 0..size {
+                let (left, right) = (col.saturating_sub(1), (col + 1).min(size - 1));
+                let dx = (height(row, right) - height(row, left))
+                    / ((right - left).max(1) as f64 * pixel_size);
+
+                // Rows run downwards while tangent-space +Y points up the image
+                let dy = (height(up, col) - height(down, col))
+                    / ((down - up).max(1) as f64 * pixel_size);
+                let dy = if self.flip_green { -dy } else { dy };
+
+                let len = (dx * dx + dy * dy + 1.0).sqrt();
+                for component in [-dx / len, -dy / len, 1.0 / len] {
+                    pixels.push(((component * 0.5 + 0.5) * 255.0).round() as u8);
+                }
+            }
+        }
+
+        pixels
+    }
+
+    pub fn save_as(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        let path = path.as_ref();
+        let size = self.size as usize;
+        let heights = sample(&self.expr, &self.image, size, 0.0);
+
+        save_png(
+            path,
+            &ColorImage::from_rgb([size, size], &self.normals(&heights)),
+        )?;
+
+        if self.height_map {
+            let stem = path
+                .file_stem()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_string();
+            let pixels = heights
+                .into_iter()
+                .map(|height| (height * 255.0) as u8)
+                .collect::<Vec<_>>();
+
+            save_png(
+                path.with_file_name(format!("{stem}_height"))
+                    .with_extension(PNG_EXTENSION),
+                &ColorImage::from_gray([size, size], &pixels),
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns `false` once the window has been closed.
+    pub fn show(&mut self, ctx: &Context) -> bool {
+        let mut open = true;
+        let mut close = false;
+
+        Window::new("Export Normal Map")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                Grid::new("normal_map_export")
+                    .num_columns(2)
+                    .show(ui, |ui| {
+                        ui.label("Size");
+                        ui.add(DragValue::new(&mut self.size).range(1..=Self::MAX_SIZE));
+                        ui.end_row();
+
+                        ui.label("Strength");
+                        ui.add(
+                            DragValue::new(&mut self.strength)
+                                .range(0.0..=f64::MAX)
+                                .speed(0.01),
+                        );
+                        ui.end_row();
+
+                        ui.label("Flip Green (DirectX)");
+                        ui.checkbox(&mut self.flip_green, "");
+                        ui.end_row();
+
+                        ui.label("Height Map");
+                        ui.checkbox(&mut self.height_map, "");
+                        ui.end_row();
+                    });
+
+                ui.separator();
+
+                ui.horizontal(|ui| {
+                    if ui.button("Export File...").clicked() {
+                        if let Some(path) = png_file_dialog().save_file() {
+                            self.save_as(path).unwrap_or_default();
+                        }
+
+                        close = true;
+                    }
+
+                    if ui.button("Cancel").clicked() {
+                        close = true;
+                    }
+                });
+            });
+
+        open && !close
+    }
+}
+
+/// Settings used to export a node as a grid of little-endian `f32` samples.
+///
+/// The output file begins with a small header (see `RawExport::MAGIC`) followed by `width * height`
+/// samples in row-major order. Pixels are square: `scale` is the world-space width of the grid.
+pub struct RawExport {
+    pub expr: Expr,
+
+    pub width: u32,
+    pub height: u32,
+    pub x: f64,
+    pub y: f64,
+    pub scale: f64,
+
+    /// The export being written, which may take a while for large grids.
+    job: Option<RawExportJob>,
+}
+
+impl RawExport {
+    pub const EXTENSION: &'static str = "f32";
+
+    /// Header layout: magic, version (`u32`), width (`u32`), height (`u32`), origin x (`f64`),
+    /// origin y (`f64`) and scale (`f64`) - all values are little-endian.
+    pub const MAGIC: [u8; 4] = *b"NF32";
+    pub const VERSION: u32 = 1;
+
+    const MAX_SIZE: u32 = 16_384;
+
+    pub fn new(expr: Expr) -> Self {
+        Self {
+            expr,
+            width: 512,
+            height: 512,
+            x: 0.0,
+            y: 0.0,
+            scale: 4.0,
+            job: None,
+        }
+    }
+
+    pub fn file_dialog() -> FileDialog {
+        FileDialog::new().add_filter("Raw F32", &[Self::EXTENSION])
+    }
+
+    /// Writes the file, storing the number of rows written so far in `rows`; the file is removed
+    /// if `cancel` is set before it has been written.
+    pub fn save_as(
+        &self,
+        path: impl AsRef<Path>,
+        rows: &AtomicU32,
+        cancel: &AtomicBool,
+    ) -> anyhow::Result<()> {
+        let mut path = path.as_ref().to_path_buf();
+
+        if path.extension().is_none() {
+            path.set_extension(Self::EXTENSION);
+        }
+
+        let file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(&path)
+            .inspect_err(|_| warn!("Unable to create file"))?;
+
+        if let Err(err) = self.write(BufWriter::new(file), rows, cancel) {
+            remove_file(path).unwrap_or_default();
+
+            if err.kind() != ErrorKind::Interrupted {
+                warn!("Unable to write file");
+
+                return Err(err.into());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns `false` once the window has been closed.
+    pub fn show(&mut self, ctx: &Context) -> bool {
+        let mut open = true;
+        let mut close = false;
+
+        Window::new("Export Raw")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                if let Some(job) = &self.job {
+                    let rows = job.rows.load(Ordering::Relaxed);
+
+                    ui.add(
+                        ProgressBar::new(rows as f32 / self.height as f32)
+                            .desired_width(256.0)
+                            .show_percentage(),
+                    );
+                    ui.separator();
+
+                    if ui.button("Cancel").clicked() {
+                        job.cancel.store(true, Ordering::Relaxed);
+                    }
+
+                    ctx.request_repaint();
+
+                    return;
+                }
+
+                Grid::new("raw_export").num_columns(2).show(ui, |ui| {
+                    ui.label("Width");
+                    ui.add(DragValue::new(&mut self.width).range(1..=Self::MAX_SIZE));
+                    ui.end_row();
+
+                    ui.label("Height");
+                    ui.add(DragValue::new(&mut self.height).range(1..=Self::MAX_SIZE));
+                    ui.end_row();
+
+                    ui.label("Origin X");
+                    ui.add(DragValue::new(&mut self.x).speed(0.01));
+                    ui.end_row();
+
+                    ui.label("Origin Y");
+                    ui.add(DragValue::new(&mut self.y).speed(0.01));
+                    ui.end_row();
+
+                    ui.label("Scale");
+                    ui.add(DragValue::new(&mut self.scale).speed(0.01));
+                    ui.end_row();
+                });
+
+                ui.separator();
+
+                ui.horizontal(|ui| {
+                    if ui.button("Export File...").clicked() {
+                        match Self::file_dialog().save_file() {
+                            Some(path) => self.job = Some(self.spawn(path)),
+                            None => close = true,
+                        }
+                    }
+
+                    if ui.button("Cancel").clicked() {
+                        close = true;
+                    }
+                });
+            });
+
+        if let Some(job) = &self.job {
+            if job.thread.is_finished() {
+                self.job.take().unwrap().thread.join().unwrap_or_default();
+                close = true;
+            } else if !open {
+                // Closing the window stops the export
+                job.cancel.store(true, Ordering::Relaxed);
+            }
+        }
+
+        open && !close
+    }
+
+    /// Writes a copy of the export to `path` on a new thread.
+    fn spawn(&self, path: PathBuf) -> RawExportJob {
+        let export = Self {
+            expr: self.expr.clone(),
+            width: self.width,
+            height: self.height,
+            x: self.x,
+            y: self.y,
+            scale: self.scale,
+            job: None,
+        };
+        let cancel = Arc::<AtomicBool>::default();
+        let rows = Arc::<AtomicU32>::default();
+        let thread = {
+            let (cancel, rows) = (Arc::clone(&cancel), Arc::clone(&rows));
+
+            spawn(move || export.save_as(path, &rows, &cancel).unwrap_or_default())
+        };
+
+        RawExportJob {
+            cancel,
+            rows,
+            thread,
+        }
+    }
+
+    /// Returns an `Interrupted` error if `cancel` is set before every row has been written.
+    fn write(
+        &self,
+        mut writer: impl Write,
+        rows: &AtomicU32,
+        cancel: &AtomicBool,
+    ) -> std::io::Result<()> {
+        writer.write_all(&Self::MAGIC)?;
+        writer.write_all(&Self::VERSION.to_le_bytes())?;
+        writer.write_all(&self.width.to_le_bytes())?;
+        writer.write_all(&self.height.to_le_bytes())?;
+        writer.write_all(&self.x.to_le_bytes())?;
+        writer.write_all(&self.y.to_le_bytes())?;
+        writer.write_all(&self.scale.to_le_bytes())?;
+
+        let noise = self.expr.compile();
+        let step = self.scale / self.width as f64;
+        let half_step = step / 2.0;
+        let mut points = vec![[0.0; 3]; self.width as usize];
+        let mut samples = vec![0.0; self.width as usize];
+
+        for row in 0..self.height {
+            if cancel.load(Ordering::Relaxed) {
+                return Err(ErrorKind::Interrupted.into());
+            }
+
+            let eval_y = self.y + row as f64 * step + half_step;
+            for (col, point) in points.iter_mut().enumerate() {
+                let eval_x = self.x + col as f64 * step + half_step;
+                *point = [eval_x, eval_y, 0.0];
+            }
+
+            noise.sample_batch(&points, &mut samples);
+
+            for &sample in &samples {
+                writer.write_all(&(sample as f32).to_le_bytes())?;
+            }
+
+            rows.store(row + 1, Ordering::Relaxed);
+        }
+
+        writer.flush()
+    }
+}
+
+/// A raw export being written on its own thread so that the UI stays responsive.
+struct RawExportJob {
+    /// Set to stop writing; the partly written file is removed.
+    cancel: Arc<AtomicBool>,
+
+    /// The number of rows written so far.
+    rows: Arc<AtomicU32>,
+
+    thread: JoinHandle<()>,
+}
@@ -1,23 +1,82 @@
 use {
+    super::simd::{FbmX4, PerlinX4},
     noise::{
         core::worley::{
             self,
             distance_functions::{chebyshev, euclidean, euclidean_squared, manhattan},
         },
-        Abs, Add, BasicMulti, Billow, Blend, Checkerboard, Clamp, Constant, Curve, Cylinders,
-        Displace, Exponent, Fbm, HybridMulti, Max, Min, MultiFractal, Multiply, Negate, NoiseFn,
-        OpenSimplex, Perlin, PerlinSurflet, Power, RidgedMulti, RotatePoint, ScaleBias, ScalePoint,
-        Seedable, Select, Simplex, SuperSimplex, Terrace, TranslatePoint, Turbulence, Value,
+        permutationtable::{NoiseHasher, PermutationTable},
+        Abs, Add, BasicMulti, Billow, Blend, Cache, Checkerboard, Clamp, Constant, Curve,
+        Cylinders, Displace, Exponent, Fbm, HybridMulti, Max, Min, MultiFractal, Multiply, Negate,
+        NoiseFn, OpenSimplex, Perlin, PerlinSurflet, Power, RidgedMulti, RotatePoint, ScaleBias,
+        ScalePoint, Seedable, Select, Simplex, SuperSimplex, TranslatePoint, Turbulence, Value,
         Worley,
     },
     ordered_float::OrderedFloat,
     serde::{Deserialize, Serialize},
-    std::cell::RefCell,
+    std::{
+        any::Any,
+        array,
+        cell::{Cell, RefCell},
+        collections::{HashMap, HashSet},
+        error::Error,
+        f64::consts::TAU,
+        fmt::{Display, Formatter},
+        hash::{Hash, Hasher},
+        iter::once,
+        mem::{discriminant, replace},
+        rc::Rc,
+    },
 };
 
+pub const MAX_FAULT_ITERATIONS: u32 = 1024;
 pub const MAX_FRACTAL_OCTAVES: u32 = BasicMulti::<Perlin>::MAX_OCTAVES as _;
+pub const MAX_WARP_ITERATIONS: u32 = 8;
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct BiasGainExpr {
+    pub source: Box<Expr>,
+
+    pub bias: Variable<f64>,
+    pub gain: Variable<f64>,
+}
+
+impl BiasGainExpr {
+    /// Returns the coefficients of the rational bias curves which make up the bias and gain curves.
+    pub fn coefficients(&self) -> [f64; 2] {
+        // The gain curve is made of two bias curves which push away from one half when the gain
+        // is above one half
+        [self.bias.value(), 1.0 - self.gain.value()]
+            .map(|value| 1.0 / value.clamp(0.001, 0.999) - 2.0)
+    }
+
+    fn noise<const DIM: usize>(
+        &self,
+        noise: fn(&Expr) -> Box<dyn NoiseFn<f64, DIM>>,
+    ) -> BiasGain<DIM> {
+        BiasGain {
+            source: noise(&self.source),
+            coefficients: self.coefficients(),
+        }
+    }
+
+    fn set_f64(&mut self, name: &str, value: f64) {
+        self.source.set_f64(name, value);
+        self.bias.set_if_named(name, value);
+        self.gain.set_if_named(name, value);
+    }
+
+    fn set_u32(&mut self, name: &str, value: u32) {
+        self.source.set_u32(name, value);
+    }
+
+    fn variables(&self, used_by: &'static str, vars: &mut Variables) {
+        vars.f64(used_by, &self.bias);
+        vars.f64(used_by, &self.gain);
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
 pub struct BlendExpr {
     pub sources: [Box<Expr>; 2],
     pub control: Box<Expr>,
@@ -39,7 +98,7 @@ impl BlendExpr {
     }
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
 pub struct ClampExpr {
     pub source: Box<Expr>,
 
@@ -51,15 +110,213 @@ impl ClampExpr {
     fn set_f64(&mut self, name: &str, value: f64) {
         self.source.set_f64(name, value);
         self.lower_bound.set_if_named(name, value);
-        self.lower_bound.set_if_named(name, value);
+        self.upper_bound.set_if_named(name, value);
     }
 
     fn set_u32(&mut self, name: &str, value: u32) {
         self.source.set_u32(name, value);
     }
+
+    fn variables(&self, used_by: &'static str, vars: &mut Variables) {
+        vars.f64(used_by, &self.lower_bound);
+        vars.f64(used_by, &self.upper_bound);
+    }
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+/// Three sources packed into the red, green and blue channels of a color; as a noise function the
+/// color is reduced to its brightness.
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct CombineRgbExpr {
+    pub channels: [Box<Expr>; 3],
+}
+
+impl CombineRgbExpr {
+    /// The weights of the red, green and blue channels in the brightness of a color (Rec. 709).
+    const LUMA: [f64; 3] = [0.2126, 0.7152, 0.0722];
+
+    /// Returns the brightness of a color whose channels are in `-1..=1`, which is also in `-1..=1`.
+    pub fn luma(rgb: [f64; 3]) -> f64 {
+        rgb.into_iter()
+            .zip(Self::LUMA)
+            .map(|(channel, weight)| channel * weight)
+            .sum()
+    }
+
+    fn noise<const DIM: usize>(
+        &self,
+        noise: fn(&Expr) -> Box<dyn NoiseFn<f64, DIM>>,
+    ) -> CombineRgb<DIM> {
+        CombineRgb {
+            channels: self.channels.each_ref().map(|channel| noise(channel)),
+        }
+    }
+
+    fn set_f64(&mut self, name: &str, value: f64) {
+        self.channels.iter_mut().for_each(|expr| {
+            expr.set_f64(name, value);
+        });
+    }
+
+    fn set_u32(&mut self, name: &str, value: u32) {
+        self.channels.iter_mut().for_each(|expr| {
+            expr.set_u32(name, value);
+        });
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct CompareExpr {
+    pub sources: [Box<Expr>; 2],
+
+    pub compare_ty: CompareType,
+
+    /// How far apart the sources may be and still be considered equal.
+    pub tolerance: Variable<f64>,
+}
+
+impl CompareExpr {
+    fn noise<const DIM: usize>(
+        &self,
+        noise: fn(&Expr) -> Box<dyn NoiseFn<f64, DIM>>,
+    ) -> Compare<DIM> {
+        Compare {
+            sources: [noise(&self.sources[0]), noise(&self.sources[1])],
+            compare_ty: self.compare_ty,
+            tolerance: self.tolerance.value().abs(),
+        }
+    }
+
+    fn set_f64(&mut self, name: &str, value: f64) {
+        self.sources.iter_mut().for_each(|expr| {
+            expr.set_f64(name, value);
+        });
+        self.tolerance.set_if_named(name, value);
+    }
+
+    fn set_u32(&mut self, name: &str, value: u32) {
+        self.sources.iter_mut().for_each(|expr| {
+            expr.set_u32(name, value);
+        });
+    }
+
+    fn variables(&self, used_by: &'static str, vars: &mut Variables) {
+        vars.f64(used_by, &self.tolerance);
+    }
+}
+
+/// How the first source of a `Compare` node is compared to the second.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, Serialize, Deserialize)]
+pub enum CompareType {
+    Equal,
+    Greater,
+    Less,
+}
+
+impl CompareType {
+    pub const ALL: [Self; 3] = [Self::Greater, Self::Less, Self::Equal];
+}
+
+/// The noise function of an [`Expr`], built once by [`Expr::compile`] and flattened into a list of
+/// instructions which are run in a loop over the points being sampled.
+///
+/// Variables are baked in when compiled: compile the expression again after changing them.
+#[allow(unused)]
+pub struct CompiledExpr {
+    noise: CompiledNoise,
+}
+
+#[allow(unused)]
+impl CompiledExpr {
+    /// Samples the expression at a point.
+    pub fn sample(&self, point: [f64; 3]) -> f64 {
+        match &self.noise {
+            CompiledNoise::Program(program) => program.get(point),
+            CompiledNoise::SliceW(slice) => slice.get(point),
+        }
+    }
+
+    /// Samples the expression at a point of the XY plane, as previewed in the app.
+    pub fn sample_2d(&self, [x, y]: [f64; 2]) -> f64 {
+        self.sample([x, y, 0.0])
+    }
+
+    /// Samples the expression at each point, which is much faster than sampling the points one at
+    /// a time as each instruction runs over every point in turn.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `samples` does not hold exactly one sample for each point.
+    pub fn sample_batch(&self, points: &[[f64; 3]], samples: &mut [f64]) {
+        match &self.noise {
+            CompiledNoise::Program(program) => program.get_batch(points, samples),
+            CompiledNoise::SliceW(slice) => {
+                assert_eq!(
+                    points.len(),
+                    samples.len(),
+                    "one sample is needed per point"
+                );
+
+                for (sample, &point) in samples.iter_mut().zip(points) {
+                    *sample = slice.get(point);
+                }
+            }
+        }
+    }
+}
+
+impl NoiseFn<f64, 3> for CompiledExpr {
+    fn get(&self, point: [f64; 3]) -> f64 {
+        self.sample(point)
+    }
+}
+
+/// The noise function of a [`CompiledExpr`].
+enum CompiledNoise {
+    Program(Program),
+
+    /// Four dimensional noise functions are not flattened.
+    SliceW(SliceW),
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct ContrastExpr {
+    pub source: Box<Expr>,
+
+    pub contrast: Variable<f64>,
+    pub pivot: Variable<f64>,
+    pub clamped: bool,
+}
+
+impl ContrastExpr {
+    fn noise<const DIM: usize>(
+        &self,
+        noise: fn(&Expr) -> Box<dyn NoiseFn<f64, DIM>>,
+    ) -> Contrast<DIM> {
+        Contrast {
+            source: noise(&self.source),
+            contrast: self.contrast.value(),
+            pivot: self.pivot.value(),
+            clamped: self.clamped,
+        }
+    }
+
+    fn set_f64(&mut self, name: &str, value: f64) {
+        self.source.set_f64(name, value);
+        self.contrast.set_if_named(name, value);
+        self.pivot.set_if_named(name, value);
+    }
+
+    fn set_u32(&mut self, name: &str, value: u32) {
+        self.source.set_u32(name, value);
+    }
+
+    fn variables(&self, used_by: &'static str, vars: &mut Variables) {
+        vars.f64(used_by, &self.contrast);
+        vars.f64(used_by, &self.pivot);
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
 pub struct ControlPointExpr {
     pub input_value: Variable<f64>,
     pub output_value: Variable<f64>,
@@ -70,9 +327,51 @@ impl ControlPointExpr {
         self.input_value.set_if_named(name, value);
         self.output_value.set_if_named(name, value);
     }
+
+    fn variables(&self, used_by: &'static str, vars: &mut Variables) {
+        vars.f64(used_by, &self.input_value);
+        vars.f64(used_by, &self.output_value);
+    }
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct CraterExpr {
+    pub source: Box<Expr>,
+
+    pub seed: Variable<u32>,
+
+    /// The chance of each unit cell of the XY plane holding a crater.
+    pub density: Variable<f64>,
+
+    /// The largest radius of a crater, as a fraction of a cell.
+    pub radius: Variable<f64>,
+
+    /// How far the bottom of a crater is below the source.
+    pub depth: Variable<f64>,
+}
+
+impl CraterExpr {
+    fn set_f64(&mut self, name: &str, value: f64) {
+        self.source.set_f64(name, value);
+        self.density.set_if_named(name, value);
+        self.radius.set_if_named(name, value);
+        self.depth.set_if_named(name, value);
+    }
+
+    fn set_u32(&mut self, name: &str, value: u32) {
+        self.source.set_u32(name, value);
+        self.seed.set_if_named(name, value);
+    }
+
+    fn variables(&self, used_by: &'static str, vars: &mut Variables) {
+        vars.f64(used_by, &self.density);
+        vars.f64(used_by, &self.radius);
+        vars.f64(used_by, &self.depth);
+        vars.u32(used_by, &self.seed);
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
 pub struct CurveExpr {
     pub source: Box<Expr>,
 
@@ -80,6 +379,51 @@ pub struct CurveExpr {
 }
 
 impl CurveExpr {
+    /// Returns `true` if there are at least four control points with different inputs, which
+    /// noise-rs requires of a curve (it panics otherwise).
+    pub fn is_valid(&self) -> bool {
+        let mut inputs = [OrderedFloat(0.0); 3];
+        let mut len = 0;
+
+        for ControlPointExpr { input_value, .. } in &self.control_points {
+            let input_value = OrderedFloat(input_value.value());
+            if !inputs[..len].contains(&input_value) {
+                if len == 3 {
+                    return true;
+                }
+
+                inputs[len] = input_value;
+                len += 1;
+            }
+        }
+
+        false
+    }
+
+    /// Returns the output of the curve for each of the given inputs, ignoring the source, or `None`
+    /// if the curve is not valid.
+    pub fn sample(&self, inputs: impl IntoIterator<Item = f64>) -> Option<Vec<f64>> {
+        self.is_valid().then(|| {
+            let curve = self.curve(InputValue);
+
+            inputs.into_iter().map(|input| curve.get([input])).collect()
+        })
+    }
+
+    fn curve<S, const DIM: usize>(&self, source: S) -> Curve<f64, S, DIM>
+    where
+        S: NoiseFn<f64, DIM>,
+    {
+        self.control_points
+            .iter()
+            .fold(Curve::new(source), |res, control_point| {
+                res.add_control_point(
+                    control_point.input_value.value(),
+                    control_point.output_value.value(),
+                )
+            })
+    }
+
     fn set_f64(&mut self, name: &str, value: f64) {
         self.source.set_f64(name, value);
         self.control_points
@@ -90,9 +434,15 @@ impl CurveExpr {
     fn set_u32(&mut self, name: &str, value: u32) {
         self.source.set_u32(name, value);
     }
+
+    fn variables(&self, used_by: &'static str, vars: &mut Variables) {
+        self.control_points
+            .iter()
+            .for_each(|expr| expr.variables(used_by, vars));
+    }
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
 pub struct DisplaceExpr {
     pub source: Box<Expr>,
 
@@ -115,25 +465,59 @@ impl DisplaceExpr {
     }
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
-pub enum DistanceFunction {
-    Chebyshev,
-    Euclidean,
-    EuclideanSquared,
-    Manhattan,
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct DomainWarpExpr {
+    pub source: Box<Expr>,
+    pub warp: Box<Expr>,
+
+    pub amplitude: Variable<f64>,
+    pub frequency: Variable<f64>,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
-pub struct ExponentExpr {
+impl DomainWarpExpr {
+    /// Added to the point at which the warp is sampled for each axis.
+    pub const AXIS_OFFSETS: [f64; 4] = [0.0, 5.2, 1.3, 8.3];
+
+    fn noise<const DIM: usize>(
+        &self,
+        noise: fn(&Expr) -> Box<dyn NoiseFn<f64, DIM>>,
+    ) -> DomainWarp<DIM> {
+        DomainWarp {
+            source: noise(&self.source),
+            warp: noise(&self.warp),
+            amplitude: self.amplitude.value(),
+            frequency: self.frequency.value(),
+        }
+    }
+
+    fn set_f64(&mut self, name: &str, value: f64) {
+        self.source.set_f64(name, value);
+        self.warp.set_f64(name, value);
+        self.amplitude.set_if_named(name, value);
+        self.frequency.set_if_named(name, value);
+    }
+
+    fn set_u32(&mut self, name: &str, value: u32) {
+        self.source.set_u32(name, value);
+        self.warp.set_u32(name, value);
+    }
+
+    fn variables(&self, used_by: &'static str, vars: &mut Variables) {
+        vars.f64(used_by, &self.amplitude);
+        vars.f64(used_by, &self.frequency);
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct EaseExpr {
     pub source: Box<Expr>,
 
-    pub exponent: Variable<f64>,
+    pub curve: EasingCurve,
 }
 
-impl ExponentExpr {
+impl EaseExpr {
     fn set_f64(&mut self, name: &str, value: f64) {
         self.source.set_f64(name, value);
-        self.exponent.set_if_named(name, value);
     }
 
     fn set_u32(&mut self, name: &str, value: u32) {
@@ -141,171 +525,784 @@ impl ExponentExpr {
     }
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
-pub struct FractalExpr {
+/// A standard easing curve (see easings.net) which shapes a value from zero to one.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq, Serialize, Deserialize)]
+pub enum EasingCurve {
+    #[default]
+    QuadIn,
+    QuadOut,
+    QuadInOut,
+    CubicIn,
+    CubicOut,
+    CubicInOut,
+    ExpoIn,
+    ExpoOut,
+    ExpoInOut,
+}
+
+impl EasingCurve {
+    pub const ALL: [Self; 9] = [
+        Self::QuadIn,
+        Self::QuadOut,
+        Self::QuadInOut,
+        Self::CubicIn,
+        Self::CubicOut,
+        Self::CubicInOut,
+        Self::ExpoIn,
+        Self::ExpoOut,
+        Self::ExpoInOut,
+    ];
+
+    /// Eases `t`, which is clamped to 0..=1 first.
+    pub fn ease(self, t: f64) -> f64 {
+        let t = t.clamp(0.0, 1.0);
+
+        match self {
+            Self::QuadIn => t * t,
+            Self::QuadOut => 1.0 - (1.0 - t) * (1.0 - t),
+            Self::QuadInOut if t < 0.5 => 2.0 * t * t,
+            Self::QuadInOut => 1.0 - (2.0 - 2.0 * t).powi(2) / 2.0,
+            Self::CubicIn => t * t * t,
+            Self::CubicOut => 1.0 - (1.0 - t).powi(3),
+            Self::CubicInOut if t < 0.5 => 4.0 * t * t * t,
+            Self::CubicInOut => 1.0 - (2.0 - 2.0 * t).powi(3) / 2.0,
+            Self::ExpoIn if t == 0.0 => 0.0,
+            Self::ExpoIn => 2f64.powf(10.0 * t - 10.0),
+            Self::ExpoOut if t == 1.0 => 1.0,
+            Self::ExpoOut => 1.0 - 2f64.powf(-10.0 * t),
+            Self::ExpoInOut if t == 0.0 || t == 1.0 => t,
+            Self::ExpoInOut if t < 0.5 => 2f64.powf(20.0 * t - 10.0) / 2.0,
+            Self::ExpoInOut => (2.0 - 2f64.powf(10.0 - 20.0 * t)) / 2.0,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct ErosionExpr {
     pub source_ty: SourceType,
     pub seed: Variable<u32>,
     pub octaves: Variable<u32>,
     pub frequency: Variable<f64>,
     pub lacunarity: Variable<f64>,
     pub persistence: Variable<f64>,
+
+    /// How strongly the slope accumulated from earlier octaves damps later octaves.
+    pub strength: Variable<f64>,
 }
 
-impl FractalExpr {
+impl ErosionExpr {
     fn set_f64(&mut self, name: &str, value: f64) {
         self.frequency.set_if_named(name, value);
         self.lacunarity.set_if_named(name, value);
         self.persistence.set_if_named(name, value);
+        self.strength.set_if_named(name, value);
     }
 
     fn set_u32(&mut self, name: &str, value: u32) {
         self.seed.set_if_named(name, value);
         self.octaves.set_if_named(name, value);
     }
+
+    fn variables(&self, used_by: &'static str, vars: &mut Variables) {
+        vars.f64(used_by, &self.frequency);
+        vars.f64(used_by, &self.lacunarity);
+        vars.f64(used_by, &self.persistence);
+        vars.f64(used_by, &self.strength);
+        vars.u32(used_by, &self.seed);
+        vars.u32(used_by, &self.octaves);
+    }
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
-pub enum Expr {
-    Abs(Box<Expr>),
-    Add([Box<Expr>; 2]),
-    BasicMulti(FractalExpr),
-    Billow(FractalExpr),
-    Blend(BlendExpr),
-    Checkerboard(Variable<u32>),
-    Clamp(ClampExpr),
-    Constant(Variable<f64>),
-    ConstantU32(Variable<u32>),
-    Curve(CurveExpr),
-    Cylinders(Variable<f64>),
-    Displace(DisplaceExpr),
-    Exponent(ExponentExpr),
-    Fbm(FractalExpr),
-    HybridMulti(FractalExpr),
-    Max([Box<Expr>; 2]),
-    Min([Box<Expr>; 2]),
-    Multiply([Box<Expr>; 2]),
-    Negate(Box<Expr>),
-    OpenSimplex(Variable<u32>),
-    Perlin(Variable<u32>),
-    PerlinSurflet(Variable<u32>),
-    Power([Box<Expr>; 2]),
-    RidgedMulti(RigidFractalExpr),
-    RotatePoint(TransformExpr),
-    ScaleBias(ScaleBiasExpr),
-    ScalePoint(TransformExpr),
-    Select(SelectExpr),
-    Simplex(Variable<u32>),
-    SuperSimplex(Variable<u32>),
-    Terrace(TerraceExpr),
-    TranslatePoint(TransformExpr),
-    Turbulence(TurbulenceExpr),
-    Value(Variable<u32>),
-    Worley(WorleyExpr),
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, Serialize, Deserialize)]
+pub enum DistanceFunction {
+    Chebyshev,
+    Euclidean,
+    EuclideanSquared,
+    Manhattan,
 }
 
-impl Expr {
-    fn basic_multi<T>(expr: &FractalExpr) -> Box<BasicMulti<T>>
-    where
-        T: Default + Seedable,
-    {
-        Box::new(
-            BasicMulti::<T>::new(expr.seed.value())
-                .set_octaves(expr.octaves.value().clamp(1, MAX_FRACTAL_OCTAVES) as _)
-                .set_frequency(expr.frequency.value())
-                .set_lacunarity(expr.lacunarity.value())
-                .set_persistence(expr.persistence.value()),
-        )
-    }
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct ExponentExpr {
+    pub source: Box<Expr>,
+
+    pub exponent: Variable<f64>,
+}
+
+impl ExponentExpr {
+    fn set_f64(&mut self, name: &str, value: f64) {
+        self.source.set_f64(name, value);
+        self.exponent.set_if_named(name, value);
+    }
+
+    fn set_u32(&mut self, name: &str, value: u32) {
+        self.source.set_u32(name, value);
+    }
+
+    fn variables(&self, used_by: &'static str, vars: &mut Variables) {
+        vars.f64(used_by, &self.exponent);
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct FaultLinesExpr {
+    pub seed: Variable<u32>,
+    pub iterations: Variable<u32>,
+    pub amplitude: Variable<f64>,
+}
+
+impl FaultLinesExpr {
+    fn set_f64(&mut self, name: &str, value: f64) {
+        self.amplitude.set_if_named(name, value);
+    }
+
+    fn set_u32(&mut self, name: &str, value: u32) {
+        self.seed.set_if_named(name, value);
+        self.iterations.set_if_named(name, value);
+    }
+
+    fn variables(&self, used_by: &'static str, vars: &mut Variables) {
+        vars.f64(used_by, &self.amplitude);
+        vars.u32(used_by, &self.seed);
+        vars.u32(used_by, &self.iterations);
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct FormulaExpr {
+    pub op: FormulaOp,
+
+    /// The sources referred to by `FormulaOp::Source`.
+    pub sources: Vec<Expr>,
+
+    /// The variables referred to by `FormulaOp::Variable`.
+    pub variables: Vec<Variable<f64>>,
+}
+
+impl FormulaExpr {
+    fn noise<const DIM: usize>(
+        &self,
+        noise: fn(&Expr) -> Box<dyn NoiseFn<f64, DIM>>,
+    ) -> Formula<DIM> {
+        Formula {
+            op: self.op.clone(),
+            sources: self.sources.iter().map(noise).collect(),
+            variables: self
+                .variables
+                .iter()
+                .map(|variable| variable.value())
+                .collect(),
+        }
+    }
+
+    fn set_f64(&mut self, name: &str, value: f64) {
+        self.sources.iter_mut().for_each(|expr| {
+            expr.set_f64(name, value);
+        });
+        self.variables
+            .iter_mut()
+            .for_each(|variable| variable.set_if_named(name, value));
+    }
+
+    fn set_u32(&mut self, name: &str, value: u32) {
+        self.sources.iter_mut().for_each(|expr| {
+            expr.set_u32(name, value);
+        });
+    }
+
+    fn variables(&self, used_by: &'static str, vars: &mut Variables) {
+        self.variables
+            .iter()
+            .for_each(|variable| vars.f64(used_by, variable));
+    }
+}
+
+/// A function which may be called by a formula.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, Serialize, Deserialize)]
+pub enum FormulaFn {
+    Abs,
+    Atan2,
+    Ceil,
+    Clamp,
+    Cos,
+    Exp,
+    Floor,
+    Fract,
+    Ln,
+    Max,
+    Min,
+    Mix,
+    Pow,
+    Sign,
+    Sin,
+    Smoothstep,
+    Sqrt,
+    Step,
+    Tan,
+}
+
+impl FormulaFn {
+    pub const ALL: [Self; 19] = [
+        Self::Abs,
+        Self::Atan2,
+        Self::Ceil,
+        Self::Clamp,
+        Self::Cos,
+        Self::Exp,
+        Self::Floor,
+        Self::Fract,
+        Self::Ln,
+        Self::Max,
+        Self::Min,
+        Self::Mix,
+        Self::Pow,
+        Self::Sign,
+        Self::Sin,
+        Self::Smoothstep,
+        Self::Sqrt,
+        Self::Step,
+        Self::Tan,
+    ];
+
+    /// The largest number of arguments taken by any function.
+    pub const MAX_ARITY: usize = 3;
+
+    /// The number of arguments taken by the function.
+    pub fn arity(self) -> usize {
+        match self {
+            Self::Abs
+            | Self::Ceil
+            | Self::Cos
+            | Self::Exp
+            | Self::Floor
+            | Self::Fract
+            | Self::Ln
+            | Self::Sign
+            | Self::Sin
+            | Self::Sqrt
+            | Self::Tan => 1,
+            Self::Atan2 | Self::Max | Self::Min | Self::Pow | Self::Step => 2,
+            Self::Clamp | Self::Mix | Self::Smoothstep => 3,
+        }
+    }
+
+    /// Calls the function; `args` must hold `arity()` values.
+    fn eval(self, args: &[f64]) -> f64 {
+        match self {
+            Self::Abs => args[0].abs(),
+            Self::Atan2 => args[0].atan2(args[1]),
+            Self::Ceil => args[0].ceil(),
+            Self::Clamp => args[0].max(args[1]).min(args[2]),
+            Self::Cos => args[0].cos(),
+            Self::Exp => args[0].exp(),
+            Self::Floor => args[0].floor(),
+            Self::Fract => args[0] - args[0].floor(),
+            Self::Ln => args[0].ln(),
+            Self::Max => args[0].max(args[1]),
+            Self::Min => args[0].min(args[1]),
+            Self::Mix => args[0] + (args[1] - args[0]) * args[2],
+            Self::Pow => args[0].powf(args[1]),
+            Self::Sign => {
+                if args[0] == 0.0 {
+                    0.0
+                } else {
+                    args[0].signum()
+                }
+            }
+            Self::Sin => args[0].sin(),
+            Self::Smoothstep => {
+                let t = if args[1] != args[0] {
+                    ((args[2] - args[0]) / (args[1] - args[0])).clamp(0.0, 1.0)
+                } else {
+                    (args[2] >= args[0]) as u8 as f64
+                };
+
+                t * t * (3.0 - 2.0 * t)
+            }
+            Self::Sqrt => args[0].sqrt(),
+            Self::Step => (args[1] >= args[0]) as u8 as f64,
+            Self::Tan => args[0].tan(),
+        }
+    }
+
+    /// The name used to call the function.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Abs => "abs",
+            Self::Atan2 => "atan2",
+            Self::Ceil => "ceil",
+            Self::Clamp => "clamp",
+            Self::Cos => "cos",
+            Self::Exp => "exp",
+            Self::Floor => "floor",
+            Self::Fract => "fract",
+            Self::Ln => "ln",
+            Self::Max => "max",
+            Self::Min => "min",
+            Self::Mix => "mix",
+            Self::Pow => "pow",
+            Self::Sign => "sign",
+            Self::Sin => "sin",
+            Self::Smoothstep => "smoothstep",
+            Self::Sqrt => "sqrt",
+            Self::Step => "step",
+            Self::Tan => "tan",
+        }
+    }
+}
+
+/// An operation of a parsed formula.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum FormulaOp {
+    Add(Box<[FormulaOp; 2]>),
+    Call(FormulaFn, Vec<FormulaOp>),
+    Constant(f64),
+
+    /// A coordinate of the sampled point; coordinates which are not sampled, such as W of 3D
+    /// noise, are zero.
+    Coord(usize),
+    Divide(Box<[FormulaOp; 2]>),
+    Multiply(Box<[FormulaOp; 2]>),
+    Negate(Box<FormulaOp>),
+    Power(Box<[FormulaOp; 2]>),
+    Remainder(Box<[FormulaOp; 2]>),
+
+    /// The value of a source at the sampled point; missing sources are zero.
+    Source(usize),
+    Subtract(Box<[FormulaOp; 2]>),
+    Variable(usize),
+}
+
+// Constants are compared and hashed by their bits, as variables are
+impl Eq for FormulaOp {}
+
+impl Hash for FormulaOp {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        discriminant(self).hash(state);
+
+        match self {
+            Self::Add(operands)
+            | Self::Divide(operands)
+            | Self::Multiply(operands)
+            | Self::Power(operands)
+            | Self::Remainder(operands)
+            | Self::Subtract(operands) => operands.hash(state),
+            Self::Call(function, args) => {
+                function.hash(state);
+                args.hash(state);
+            }
+            Self::Constant(value) => value.to_bits().hash(state),
+            Self::Coord(idx) | Self::Source(idx) | Self::Variable(idx) => idx.hash(state),
+            Self::Negate(operand) => operand.hash(state),
+        }
+    }
+}
+
+impl PartialEq for FormulaOp {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Add(lhs), Self::Add(rhs))
+            | (Self::Divide(lhs), Self::Divide(rhs))
+            | (Self::Multiply(lhs), Self::Multiply(rhs))
+            | (Self::Power(lhs), Self::Power(rhs))
+            | (Self::Remainder(lhs), Self::Remainder(rhs))
+            | (Self::Subtract(lhs), Self::Subtract(rhs)) => lhs == rhs,
+            (Self::Call(lhs_function, lhs_args), Self::Call(rhs_function, rhs_args)) => {
+                lhs_function == rhs_function && lhs_args == rhs_args
+            }
+            (Self::Constant(lhs), Self::Constant(rhs)) => lhs.to_bits() == rhs.to_bits(),
+            (Self::Coord(lhs), Self::Coord(rhs))
+            | (Self::Source(lhs), Self::Source(rhs))
+            | (Self::Variable(lhs), Self::Variable(rhs)) => lhs == rhs,
+            (Self::Negate(lhs), Self::Negate(rhs)) => lhs == rhs,
+            _ => false,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct FractalExpr {
+    pub source_ty: SourceType,
+    pub seed: Variable<u32>,
+    pub octaves: Variable<u32>,
+    pub frequency: Variable<f64>,
+    pub lacunarity: Variable<f64>,
+    pub persistence: Variable<f64>,
+
+    /// The amplitude of each octave, starting with the first; octaves past the end of the list
+    /// keep the amplitude given by the persistence. When empty every octave does.
+    #[serde(default)]
+    pub weights: Vec<f64>,
+}
 
-    fn billow<T>(expr: &FractalExpr) -> Box<Billow<T>>
+impl FractalExpr {
+    fn damped<T>(&self, damping_ty: DampingType) -> DampedFractal<T>
     where
         T: Default + Seedable,
     {
-        Box::new(
-            Billow::<T>::new(expr.seed.value())
-                .set_octaves(expr.octaves.value().clamp(1, MAX_FRACTAL_OCTAVES) as _)
-                .set_frequency(expr.frequency.value())
-                .set_lacunarity(expr.lacunarity.value())
-                .set_persistence(expr.persistence.value()),
-        )
+        DampedFractal {
+            damping_ty,
+            sources: self.sources(),
+            frequency: self.frequency.value(),
+            lacunarity: self.lacunarity.value(),
+            weights: self.octave_weights(),
+        }
     }
 
-    fn curve(expr: &CurveExpr) -> Box<dyn NoiseFn<f64, 3>> {
-        fn invalid_inputs(control_points: &[ControlPointExpr]) -> bool {
-            debug_assert!(control_points.len() >= 4);
+    /// Returns the fractal, or a fractal using the custom octave weights when there are any.
+    fn noise<F, T>(&self, fractal_ty: FractalType, fractal: impl FnOnce() -> F) -> Fractal<F, T>
+    where
+        T: Default + Seedable,
+    {
+        if self.weights.is_empty() {
+            return Fractal::Persistence(fractal());
+        }
 
-            type Inputs = Vec<OrderedFloat<f64>>;
+        Fractal::Weights(WeightedFractal {
+            fractal_ty,
+            sources: self.sources(),
+            frequency: self.frequency.value(),
+            lacunarity: self.lacunarity.value(),
+            weights: self.octave_weights(),
+        })
+    }
 
-            thread_local! {
-                static INPUTS: RefCell<Option<Inputs>> = RefCell::new(Some(Vec::with_capacity(3)));
-            }
+    /// Returns the amplitude of each octave.
+    fn octave_weights(&self) -> Vec<f64> {
+        let persistence = self.persistence.value();
 
-            let mut inputs = INPUTS.take().unwrap();
+        (0..self.octaves.value().clamp(1, MAX_FRACTAL_OCTAVES) as usize)
+            .map(|octave| {
+                self.weights
+                    .get(octave)
+                    .copied()
+                    .unwrap_or_else(|| persistence.powi(octave as _))
+            })
+            .collect()
+    }
 
-            for ControlPointExpr { input_value, .. } in control_points {
-                let input_value = OrderedFloat(input_value.value());
-                if let Err(idx) = inputs.binary_search(&input_value) {
-                    if inputs.len() == 3 {
-                        inputs.clear();
-                        INPUTS.set(Some(inputs));
+    /// Returns the source of each octave, which uses the next seed.
+    fn sources<T>(&self) -> Vec<T>
+    where
+        T: Default + Seedable,
+    {
+        let seed = self.seed.value();
 
-                        return false;
-                    }
+        (0..self.octaves.value().clamp(1, MAX_FRACTAL_OCTAVES))
+            .map(|octave| T::default().set_seed(seed.wrapping_add(octave)))
+            .collect()
+    }
 
-                    inputs.insert(idx, input_value);
-                }
-            }
+    fn set_f64(&mut self, name: &str, value: f64) {
+        self.frequency.set_if_named(name, value);
+        self.lacunarity.set_if_named(name, value);
+        self.persistence.set_if_named(name, value);
+    }
+
+    fn set_u32(&mut self, name: &str, value: u32) {
+        self.seed.set_if_named(name, value);
+        self.octaves.set_if_named(name, value);
+    }
+
+    fn variables(&self, used_by: &'static str, vars: &mut Variables) {
+        vars.f64(used_by, &self.frequency);
+        vars.f64(used_by, &self.lacunarity);
+        vars.f64(used_by, &self.persistence);
+        vars.u32(used_by, &self.seed);
+        vars.u32(used_by, &self.octaves);
+    }
+}
+
+// Weights are compared and hashed by their bits, as variables are
+impl Eq for FractalExpr {}
+
+impl Hash for FractalExpr {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.source_ty.hash(state);
+        self.seed.hash(state);
+        self.octaves.hash(state);
+        self.frequency.hash(state);
+        self.lacunarity.hash(state);
+        self.persistence.hash(state);
+        self.weights.len().hash(state);
+        self.weights
+            .iter()
+            .for_each(|weight| weight.to_bits().hash(state));
+    }
+}
+
+impl PartialEq for FractalExpr {
+    fn eq(&self, other: &Self) -> bool {
+        self.source_ty == other.source_ty
+            && self.seed == other.seed
+            && self.octaves == other.octaves
+            && self.frequency == other.frequency
+            && self.lacunarity == other.lacunarity
+            && self.persistence == other.persistence
+            && self.weights.len() == other.weights.len()
+            && self
+                .weights
+                .iter()
+                .zip(&other.weights)
+                .all(|(lhs, rhs)| lhs.to_bits() == rhs.to_bits())
+    }
+}
+
+/// fBm of any source: each octave samples the source at a higher frequency and lower amplitude.
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct GenericFractalExpr {
+    pub source: Box<Expr>,
 
-            inputs.clear();
-            INPUTS.set(Some(inputs));
+    pub octaves: Variable<u32>,
+    pub frequency: Variable<f64>,
+    pub lacunarity: Variable<f64>,
+    pub persistence: Variable<f64>,
+}
 
-            true
+impl GenericFractalExpr {
+    fn noise<const DIM: usize>(
+        &self,
+        noise: fn(&Expr) -> Box<dyn NoiseFn<f64, DIM>>,
+    ) -> GenericFractal<DIM> {
+        GenericFractal {
+            source: noise(&self.source),
+            octaves: self.octaves.value().clamp(1, MAX_FRACTAL_OCTAVES),
+            frequency: self.frequency.value(),
+            lacunarity: self.lacunarity.value(),
+            persistence: self.persistence.value(),
         }
+    }
 
-        // Make sure the control points are valid (noise-rs panics!)
-        if expr.control_points.len() < 4 || invalid_inputs(&expr.control_points) {
-            return Box::new(Constant::new(0.0));
+    fn set_f64(&mut self, name: &str, value: f64) {
+        self.source.set_f64(name, value);
+        self.frequency.set_if_named(name, value);
+        self.lacunarity.set_if_named(name, value);
+        self.persistence.set_if_named(name, value);
+    }
+
+    fn set_u32(&mut self, name: &str, value: u32) {
+        self.source.set_u32(name, value);
+        self.octaves.set_if_named(name, value);
+    }
+
+    fn variables(&self, used_by: &'static str, vars: &mut Variables) {
+        vars.f64(used_by, &self.frequency);
+        vars.f64(used_by, &self.lacunarity);
+        vars.f64(used_by, &self.persistence);
+        vars.u32(used_by, &self.octaves);
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct GradientExpr {
+    pub gradient_ty: GradientType,
+    pub angle: Variable<f64>,
+    pub radius: Variable<f64>,
+}
+
+impl GradientExpr {
+    fn noise(&self) -> Gradient {
+        let angle = self.angle.value().to_radians();
+
+        Gradient {
+            gradient_ty: self.gradient_ty,
+            direction: [angle.cos(), angle.sin()],
+            radius: self.radius.value(),
         }
+    }
+
+    fn set_f64(&mut self, name: &str, value: f64) {
+        self.angle.set_if_named(name, value);
+        self.radius.set_if_named(name, value);
+    }
+
+    fn variables(&self, used_by: &'static str, vars: &mut Variables) {
+        vars.f64(used_by, &self.angle);
+        vars.f64(used_by, &self.radius);
+    }
+}
 
-        let mut res = Curve::new(expr.source.noise());
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, Serialize, Deserialize)]
+pub enum GradientType {
+    Linear,
+    Radial,
+}
 
-        for control_point in &expr.control_points {
-            res = res.add_control_point(
-                control_point.input_value.value(),
-                control_point.output_value.value(),
-            );
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub enum Expr {
+    Abs(Box<Expr>),
+    Add([Box<Expr>; 2]),
+    BasicMulti(FractalExpr),
+    BiasGain(BiasGainExpr),
+    Billow(FractalExpr),
+    Blend(BlendExpr),
+    BlueNoise(Variable<u32>),
+    Cache(Box<Expr>),
+    Checkerboard(Variable<u32>),
+    Clamp(ClampExpr),
+    CombineRgb(CombineRgbExpr),
+    Compare(CompareExpr),
+    Contrast(ContrastExpr),
+    Constant(Variable<f64>),
+    ConstantU32(Variable<u32>),
+    Crater(CraterExpr),
+    Curve(CurveExpr),
+    Cylinders(Variable<f64>),
+    Difference([Box<Expr>; 2]),
+    Displace(DisplaceExpr),
+    DomainWarp(DomainWarpExpr),
+    Ease(EaseExpr),
+    Erosion(ErosionExpr),
+    Exponent(ExponentExpr),
+    FaultLines(FaultLinesExpr),
+    Fbm(FractalExpr),
+    Formula(FormulaExpr),
+    GenericFractal(GenericFractalExpr),
+    Gradient(GradientExpr),
+    HybridMulti(FractalExpr),
+    ImageSource(ImageSourceExpr),
+    Jordan(FractalExpr),
+    Max([Box<Expr>; 2]),
+    Min([Box<Expr>; 2]),
+    MirrorPoint(MirrorPointExpr),
+    Mix(MixExpr),
+    Multiply([Box<Expr>; 2]),
+    Negate(Box<Expr>),
+    Normal(NormalExpr),
+    Normalize(NormalizeExpr),
+    OpenSimplex(Variable<u32>),
+    Perlin(Variable<u32>),
+    PerlinSurflet(Variable<u32>),
+    Power([Box<Expr>; 2]),
+    Remap(RemapExpr),
+    RidgedMulti(RigidFractalExpr),
+    RotateAxis(RotateAxisExpr),
+    RotatePoint(TransformExpr),
+    ScaleBias(ScaleBiasExpr),
+    ScalePoint(TransformExpr),
+    Script(ScriptExpr),
+    Select(SelectExpr),
+    Simplex(Variable<u32>),
+    Slope(SlopeExpr),
+    SuperSimplex(Variable<u32>),
+    Swiss(FractalExpr),
+    Switch(SwitchExpr),
+    Swizzle(SwizzleExpr),
+    Terrace(TerraceExpr),
+    TilePoint(TilePointExpr),
+    TranslatePoint(TransformExpr),
+    Turbulence(TurbulenceExpr),
+    Value(Variable<u32>),
+    WarpedFbm(WarpedFbmExpr),
+    Worley(WorleyExpr),
+}
+
+impl Expr {
+    fn basic_multi<T>(expr: &FractalExpr) -> Box<Fractal<BasicMulti<T>, T>>
+    where
+        T: Default + Seedable,
+    {
+        Box::new(expr.noise(FractalType::BasicMulti, || {
+            BasicMulti::<T>::new(expr.seed.value())
+                .set_octaves(expr.octaves.value().clamp(1, MAX_FRACTAL_OCTAVES) as _)
+                .set_frequency(expr.frequency.value())
+                .set_lacunarity(expr.lacunarity.value())
+                .set_persistence(expr.persistence.value())
+        }))
+    }
+
+    fn billow<T>(expr: &FractalExpr) -> Box<Fractal<Billow<T>, T>>
+    where
+        T: Default + Seedable,
+    {
+        Box::new(expr.noise(FractalType::Billow, || {
+            Billow::<T>::new(expr.seed.value())
+                .set_octaves(expr.octaves.value().clamp(1, MAX_FRACTAL_OCTAVES) as _)
+                .set_frequency(expr.frequency.value())
+                .set_lacunarity(expr.lacunarity.value())
+                .set_persistence(expr.persistence.value())
+        }))
+    }
+
+    /// Returns a cache of the source expression which is shared with every other cache of an
+    /// identical expression within the noise function being built, so that a branch feeding several
+    /// consumers is sampled once per point.
+    fn cache<const DIM: usize>(
+        source: &Self,
+        noise: fn(&Self) -> Box<dyn NoiseFn<f64, DIM>>,
+    ) -> Box<dyn NoiseFn<f64, DIM>> {
+        // Equal expressions are the same function; each dimension has its own cache
+        let cache = SHARED_CACHES
+            .with_borrow(|caches| {
+                caches
+                    .get(source)?
+                    .iter()
+                    .find_map(|cache| cache.clone().downcast().ok())
+            })
+            .unwrap_or_else(|| {
+                let cache = Rc::new(Cache::new(noise(source)));
+                SHARED_CACHES.with_borrow_mut(|caches| {
+                    caches
+                        .entry(source.clone())
+                        .or_default()
+                        .push(cache.clone())
+                });
+
+                cache
+            });
+
+        Box::new(SharedCache(cache))
+    }
+
+    fn curve<const DIM: usize>(
+        expr: &CurveExpr,
+        source: Box<dyn NoiseFn<f64, DIM>>,
+    ) -> Box<dyn NoiseFn<f64, DIM>> {
+        // Make sure the control points are valid (noise-rs panics!)
+        if !expr.is_valid() {
+            ExprError::Curve.record();
+
+            return Box::new(Constant::new(0.0));
         }
 
-        Box::new(res)
+        Box::new(expr.curve(source))
     }
 
-    fn fbm<T>(expr: &FractalExpr) -> Box<Fbm<T>>
+    fn fbm<T>(expr: &FractalExpr) -> Box<Fractal<Fbm<T>, T>>
     where
         T: Default + Seedable,
     {
-        Box::new(
+        Box::new(expr.noise(FractalType::Fbm, || {
             Fbm::<T>::new(expr.seed.value())
                 .set_octaves(expr.octaves.value().clamp(1, MAX_FRACTAL_OCTAVES) as _)
                 .set_frequency(expr.frequency.value())
                 .set_lacunarity(expr.lacunarity.value())
-                .set_persistence(expr.persistence.value()),
-        )
+                .set_persistence(expr.persistence.value())
+        }))
     }
 
-    fn hybrid_multi<T>(expr: &FractalExpr) -> Box<HybridMulti<T>>
+    fn hybrid_multi<T>(expr: &FractalExpr) -> Box<Fractal<HybridMulti<T>, T>>
     where
         T: Default + Seedable,
     {
-        Box::new(
+        Box::new(expr.noise(FractalType::HybridMulti, || {
             HybridMulti::<T>::new(expr.seed.value())
                 .set_octaves(expr.octaves.value().clamp(1, MAX_FRACTAL_OCTAVES) as _)
                 .set_frequency(expr.frequency.value())
                 .set_lacunarity(expr.lacunarity.value())
-                .set_persistence(expr.persistence.value()),
-        )
+                .set_persistence(expr.persistence.value())
+        }))
     }
 
-    pub fn noise(&self) -> Box<dyn NoiseFn<f64, 3>> {
+    fn jordan<T>(expr: &FractalExpr) -> Box<DampedFractal<T>>
+    where
+        T: Default + Seedable,
+    {
+        Box::new(expr.damped(DampingType::Jordan))
+    }
+
+    pub fn noise(&self) -> Box<dyn NoiseFn<f64, 3>> {
+        let _scope = SharedCacheScope::enter();
+
         match self {
             Self::Abs(expr) => Box::new(Abs::new(expr.noise())),
             Self::Add([source1, source2]) => Box::new(Add::new(source1.noise(), source2.noise())),
@@ -327,23 +1324,40 @@ impl Expr {
                 SourceType::Value => Self::billow::<Value>(expr),
                 SourceType::Worley => Self::billow::<Worley>(expr),
             },
+            Self::BiasGain(expr) => Box::new(expr.noise(Self::noise)),
             Self::Blend(expr) => Box::new(Blend::new(
                 expr.sources[0].noise(),
                 expr.sources[1].noise(),
                 expr.control.noise(),
             )),
+            Self::BlueNoise(seed) => Box::new(BlueNoise::new(seed.value())),
+            Self::Cache(source) => Self::cache(source, Self::noise),
             Self::Checkerboard(size) => Box::new(Checkerboard::new(size.value() as _)),
             Self::Clamp(expr) => Box::new(
                 Clamp::new(expr.source.noise())
                     .set_lower_bound(expr.lower_bound.value().min(expr.upper_bound.value()))
                     .set_upper_bound(expr.lower_bound.value().max(expr.upper_bound.value())),
             ),
+            Self::CombineRgb(expr) => Box::new(expr.noise(Self::noise)),
+            Self::Compare(expr) => Box::new(expr.noise(Self::noise)),
+            Self::Contrast(expr) => Box::new(expr.noise(Self::noise)),
             Self::Constant(value) => Box::new(Constant::new(value.value())),
             Self::ConstantU32(_) => unreachable!(),
-            Self::Curve(expr) => Self::curve(expr),
+            Self::Crater(expr) => Box::new(Crater {
+                source: expr.source.noise(),
+                hasher: PermutationTable::new(expr.seed.value()),
+                density: expr.density.value(),
+                radius: expr.radius.value().clamp(0.0, 1.0),
+                depth: expr.depth.value(),
+            }),
+            Self::Curve(expr) => Self::curve(expr, expr.source.noise()),
             Self::Cylinders(frequency) => {
                 Box::new(Cylinders::new().set_frequency(frequency.value()))
             }
+            Self::Difference([source1, source2]) => Box::new(Abs::new(Add::new(
+                source1.noise(),
+                Negate::new(source2.noise()),
+            ))),
             Self::Displace(expr) => Box::new(Displace::new(
                 expr.source.noise(),
                 expr.axes[0].noise(),
@@ -351,9 +1365,28 @@ impl Expr {
                 expr.axes[2].noise(),
                 expr.axes[3].noise(),
             )),
+            Self::DomainWarp(expr) => Box::new(expr.noise(Self::noise)),
+            Self::Ease(expr) => Box::new(Ease {
+                source: expr.source.noise(),
+                curve: expr.curve,
+            }),
+            Self::Erosion(expr) => match expr.source_ty {
+                SourceType::OpenSimplex => Self::erosion::<OpenSimplex>(expr),
+                SourceType::Perlin => Self::erosion::<Perlin>(expr),
+                SourceType::PerlinSurflet => Self::erosion::<PerlinSurflet>(expr),
+                SourceType::Simplex => Self::erosion::<Simplex>(expr),
+                SourceType::SuperSimplex => Self::erosion::<OpenSimplex>(expr),
+                SourceType::Value => Self::erosion::<Value>(expr),
+                SourceType::Worley => Self::erosion::<Worley>(expr),
+            },
             Self::Exponent(expr) => {
                 Box::new(Exponent::new(expr.source.noise()).set_exponent(expr.exponent.value()))
             }
+            Self::FaultLines(expr) => Box::new(FaultLines::new(
+                expr.seed.value(),
+                expr.iterations.value().min(MAX_FAULT_ITERATIONS),
+                expr.amplitude.value(),
+            )),
             Self::Fbm(expr) => match expr.source_ty {
                 SourceType::OpenSimplex => Self::fbm::<OpenSimplex>(expr),
                 SourceType::Perlin => Self::fbm::<Perlin>(expr),
@@ -363,6 +1396,9 @@ impl Expr {
                 SourceType::Value => Self::fbm::<Value>(expr),
                 SourceType::Worley => Self::fbm::<Worley>(expr),
             },
+            Self::Formula(expr) => Box::new(expr.noise(Self::noise)),
+            Self::GenericFractal(expr) => Box::new(expr.noise(Self::noise)),
+            Self::Gradient(expr) => Box::new(expr.noise()),
             Self::HybridMulti(expr) => match expr.source_ty {
                 SourceType::OpenSimplex => Self::hybrid_multi::<OpenSimplex>(expr),
                 SourceType::Perlin => Self::hybrid_multi::<Perlin>(expr),
@@ -372,18 +1408,37 @@ impl Expr {
                 SourceType::Value => Self::hybrid_multi::<Value>(expr),
                 SourceType::Worley => Self::hybrid_multi::<Worley>(expr),
             },
+            Self::Jordan(expr) => match expr.source_ty {
+                SourceType::OpenSimplex => Self::jordan::<OpenSimplex>(expr),
+                SourceType::Perlin => Self::jordan::<Perlin>(expr),
+                SourceType::PerlinSurflet => Self::jordan::<PerlinSurflet>(expr),
+                SourceType::Simplex => Self::jordan::<Simplex>(expr),
+                SourceType::SuperSimplex => Self::jordan::<OpenSimplex>(expr),
+                SourceType::Value => Self::jordan::<Value>(expr),
+                SourceType::Worley => Self::jordan::<Worley>(expr),
+            },
+            Self::ImageSource(expr) => Box::new(expr.noise()),
             Self::Max([source1, source2]) => Box::new(Max::new(source1.noise(), source2.noise())),
             Self::Min([source1, source2]) => Box::new(Min::new(source1.noise(), source2.noise())),
+            Self::MirrorPoint(expr) => Box::new(expr.noise(Self::noise)),
+            Self::Mix(expr) => Box::new(Blend::new(
+                expr.sources[0].noise(),
+                expr.sources[1].noise(),
+                Constant::new(expr.factor()),
+            )),
             Self::Multiply([source1, source2]) => {
                 Box::new(Multiply::new(source1.noise(), source2.noise()))
             }
             Self::Negate(expr) => Box::new(Negate::new(expr.noise())),
+            Self::Normal(expr) => Box::new(expr.noise(Self::noise)),
+            Self::Normalize(expr) => Self::normalize(expr, expr.source.noise()),
             Self::OpenSimplex(seed) => Box::new(OpenSimplex::new(seed.value())),
             Self::Perlin(seed) => Box::new(Perlin::new(seed.value())),
             Self::PerlinSurflet(seed) => Box::new(PerlinSurflet::new(seed.value())),
             Self::Power([source1, source2]) => {
                 Box::new(Power::new(source1.noise(), source2.noise()))
             }
+            Self::Remap(expr) => Self::remap(expr, expr.source.noise()),
             Self::RidgedMulti(expr) => match expr.source_ty {
                 SourceType::OpenSimplex => Self::rigid_multi::<OpenSimplex>(expr),
                 SourceType::Perlin => Self::rigid_multi::<Perlin>(expr),
@@ -393,6 +1448,7 @@ impl Expr {
                 SourceType::Value => Self::rigid_multi::<Value>(expr),
                 SourceType::Worley => Self::rigid_multi::<Worley>(expr),
             },
+            Self::RotateAxis(expr) => Box::new(expr.noise(Self::noise)),
             Self::RotatePoint(expr) => Box::new(RotatePoint::new(expr.source.noise()).set_angles(
                 expr.axes[0].value(),
                 expr.axes[1].value(),
@@ -412,6 +1468,7 @@ impl Expr {
                     expr.axes[3].value(),
                 ))
             }
+            Self::Script(expr) => expr.noise(Self::noise),
             Self::Select(expr) => Box::new(
                 Select::new(
                     expr.sources[0].noise(),
@@ -422,8 +1479,28 @@ impl Expr {
                 .set_falloff(expr.falloff.value()),
             ),
             Self::Simplex(seed) => Box::new(Simplex::new(seed.value())),
+            Self::Slope(expr) => Box::new(expr.noise(Self::noise)),
             Self::SuperSimplex(seed) => Box::new(SuperSimplex::new(seed.value())),
-            Self::Terrace(expr) => Self::terrace(expr),
+            Self::Swiss(expr) => match expr.source_ty {
+                SourceType::OpenSimplex => Self::swiss::<OpenSimplex>(expr),
+                SourceType::Perlin => Self::swiss::<Perlin>(expr),
+                SourceType::PerlinSurflet => Self::swiss::<PerlinSurflet>(expr),
+                SourceType::Simplex => Self::swiss::<Simplex>(expr),
+                SourceType::SuperSimplex => Self::swiss::<OpenSimplex>(expr),
+                SourceType::Value => Self::swiss::<Value>(expr),
+                SourceType::Worley => Self::swiss::<Worley>(expr),
+            },
+            Self::Switch(expr) => match expr.source() {
+                Some(source) => source.noise(),
+                None => {
+                    ExprError::Switch.record();
+
+                    Box::new(Constant::new(0.0))
+                }
+            },
+            Self::Swizzle(expr) => Box::new(expr.noise(Self::noise)),
+            Self::Terrace(expr) => Self::terrace(expr, expr.source.noise()),
+            Self::TilePoint(expr) => Box::new(expr.noise(Self::noise)),
             Self::TranslatePoint(expr) => Box::new(
                 TranslatePoint::new(expr.source.noise()).set_all_translations(
                     expr.axes[0].value(),
@@ -433,29 +1510,424 @@ impl Expr {
                 ),
             ),
             Self::Turbulence(expr) => match expr.source_ty {
-                SourceType::OpenSimplex => Self::turbulence::<OpenSimplex>(expr),
-                SourceType::Perlin => Self::turbulence::<Perlin>(expr),
-                SourceType::PerlinSurflet => Self::turbulence::<PerlinSurflet>(expr),
-                SourceType::Simplex => Self::turbulence::<Simplex>(expr),
-                SourceType::SuperSimplex => Self::turbulence::<OpenSimplex>(expr),
-                SourceType::Value => Self::turbulence::<Value>(expr),
-                SourceType::Worley => Self::turbulence::<Worley>(expr),
+                SourceType::OpenSimplex => {
+                    Self::turbulence::<_, OpenSimplex>(expr, expr.source.noise())
+                }
+                SourceType::Perlin => Self::turbulence::<_, Perlin>(expr, expr.source.noise()),
+                SourceType::PerlinSurflet => {
+                    Self::turbulence::<_, PerlinSurflet>(expr, expr.source.noise())
+                }
+                SourceType::Simplex => Self::turbulence::<_, Simplex>(expr, expr.source.noise()),
+                SourceType::SuperSimplex => {
+                    Self::turbulence::<_, OpenSimplex>(expr, expr.source.noise())
+                }
+                SourceType::Value => Self::turbulence::<_, Value>(expr, expr.source.noise()),
+                SourceType::Worley => Self::turbulence::<_, Worley>(expr, expr.source.noise()),
             },
             Self::Value(seed) => Box::new(Value::new(seed.value())),
-            Self::Worley(expr) => Box::new(
-                Worley::new(expr.seed.value())
-                    .set_frequency(expr.frequency.value())
-                    .set_distance_function(match expr.distance_fn {
-                        DistanceFunction::Chebyshev => chebyshev,
-                        DistanceFunction::Euclidean => euclidean,
-                        DistanceFunction::EuclideanSquared => euclidean_squared,
-                        DistanceFunction::Manhattan => manhattan,
-                    })
-                    .set_return_type(match expr.return_ty {
-                        ReturnType::Distance => worley::ReturnType::Distance,
-                        ReturnType::Value => worley::ReturnType::Value,
-                    }),
+            Self::WarpedFbm(expr) => match expr.source_ty {
+                SourceType::OpenSimplex => Self::warped_fbm::<OpenSimplex>(expr),
+                SourceType::Perlin => Self::warped_fbm::<Perlin>(expr),
+                SourceType::PerlinSurflet => Self::warped_fbm::<PerlinSurflet>(expr),
+                SourceType::Simplex => Self::warped_fbm::<Simplex>(expr),
+                SourceType::SuperSimplex => Self::warped_fbm::<OpenSimplex>(expr),
+                SourceType::Value => Self::warped_fbm::<Value>(expr),
+                SourceType::Worley => Self::warped_fbm::<Worley>(expr),
+            },
+            Self::Worley(expr) => Self::worley(expr),
+        }
+    }
+
+    /// Returns the red, green and blue sources of an expression which produces a color.
+    pub fn channels(&self) -> Option<&[Box<Expr>; 3]> {
+        if let Self::CombineRgb(expr) = self {
+            Some(&expr.channels)
+        } else {
+            None
+        }
+    }
+
+    /// Builds the noise function of this expression once so that it may be sampled many times.
+    #[allow(unused)]
+    pub fn compile(&self) -> CompiledExpr {
+        self.compile_at_w(None)
+    }
+
+    /// Builds the noise function of this expression once, as [`Expr::compile`] does, which when a
+    /// W coordinate is given samples the four dimensional noise function at that coordinate.
+    pub fn compile_at_w(&self, w: Option<f64>) -> CompiledExpr {
+        let noise = if let Some(w) = w {
+            CompiledNoise::SliceW(SliceW {
+                source: self.noise_4d(),
+                w,
+            })
+        } else {
+            CompiledNoise::Program(Program::new(self))
+        };
+
+        CompiledExpr { noise }
+    }
+
+    /// Samples the expression at a single point.
+    ///
+    /// This builds the whole noise function on every call; use [`Expr::compile`] to sample more
+    /// than a handful of points.
+    #[allow(unused)]
+    pub fn sample(&self, point: [f64; 3]) -> f64 {
+        self.noise().get(point)
+    }
+
+    /// Samples the expression at a single point of the XY plane, as previewed in the app.
+    ///
+    /// This builds the whole noise function on every call; use [`Expr::compile`] to sample more
+    /// than a handful of points.
+    #[allow(unused)]
+    pub fn sample_2d(&self, [x, y]: [f64; 2]) -> f64 {
+        self.sample([x, y, 0.0])
+    }
+
+    /// Returns the expression as a noise function, or the first problem found which would make
+    /// part of it sample as zero, such as a curve or terrace without enough control points.
+    ///
+    /// [`Expr::noise`] substitutes zero for those parts instead.
+    #[allow(unused)]
+    pub fn try_noise(&self) -> Result<Box<dyn NoiseFn<f64, 3>>, ExprError> {
+        BUILD_ERROR.take();
+
+        let noise = self.noise();
+
+        match BUILD_ERROR.take() {
+            Some(err) => Err(err),
+            None => Ok(noise),
+        }
+    }
+
+    /// Fills `out` with the row-major samples of the expression at the center of each cell of the
+    /// region.
+    ///
+    /// Rows are sampled in parallel when the `rayon` feature is enabled; each thread builds its own
+    /// noise function.
+    ///
+    /// `out` should hold exactly one sample for each cell of the region; debug builds panic if it
+    /// does not, while release builds only fill the cells which fit and leave any other samples
+    /// untouched.
+    #[allow(unused)]
+    pub fn fill_plane(&self, region: PlaneRegion, out: &mut [f64]) {
+        let [columns, rows] = region.resolution;
+
+        debug_assert_eq!(out.len(), columns * rows, "one sample is needed per cell");
+
+        let len = out.len().min(columns * rows);
+        let out = &mut out[..len];
+
+        if columns == 0 {
+            return;
+        }
+
+        #[cfg(feature = "rayon")]
+        {
+            use rayon::prelude::*;
+
+            out.par_chunks_mut(columns).enumerate().for_each_init(
+                || self.compile(),
+                |noise, (row, samples)| region.fill_row(noise, row, samples),
+            );
+        }
+
+        #[cfg(not(feature = "rayon"))]
+        {
+            let noise = self.compile();
+
+            for (row, samples) in out.chunks_mut(columns).enumerate() {
+                region.fill_row(&noise, row, samples);
+            }
+        }
+    }
+
+    /// Returns the expression as a noise function of the XYZ axes which, when a W coordinate is
+    /// given, samples the four dimensional noise function at that coordinate.
+    ///
+    /// Three dimensional noise functions are flattened, as by [`Expr::compile`].
+    pub fn noise_at_w(&self, w: Option<f64>) -> Box<dyn NoiseFn<f64, 3>> {
+        Box::new(self.compile_at_w(w))
+    }
+
+    /// Returns the expression as a four dimensional noise function.
+    ///
+    /// `SuperSimplex` has no four dimensional implementation so `OpenSimplex` is used in its place,
+    /// as is already done for the sources of fractals.
+    pub fn noise_4d(&self) -> Box<dyn NoiseFn<f64, 4>> {
+        let _scope = SharedCacheScope::enter();
+
+        match self {
+            Self::Abs(expr) => Box::new(Abs::new(expr.noise_4d())),
+            Self::Add([source1, source2]) => {
+                Box::new(Add::new(source1.noise_4d(), source2.noise_4d()))
+            }
+            Self::BasicMulti(expr) => match expr.source_ty {
+                SourceType::OpenSimplex => Self::basic_multi::<OpenSimplex>(expr),
+                SourceType::Perlin => Self::basic_multi::<Perlin>(expr),
+                SourceType::PerlinSurflet => Self::basic_multi::<PerlinSurflet>(expr),
+                SourceType::Simplex => Self::basic_multi::<Simplex>(expr),
+                SourceType::SuperSimplex => Self::basic_multi::<OpenSimplex>(expr),
+                SourceType::Value => Self::basic_multi::<Value>(expr),
+                SourceType::Worley => Self::basic_multi::<Worley>(expr),
+            },
+            Self::Billow(expr) => match expr.source_ty {
+                SourceType::OpenSimplex => Self::billow::<OpenSimplex>(expr),
+                SourceType::Perlin => Self::billow::<Perlin>(expr),
+                SourceType::PerlinSurflet => Self::billow::<PerlinSurflet>(expr),
+                SourceType::Simplex => Self::billow::<Simplex>(expr),
+                SourceType::SuperSimplex => Self::billow::<OpenSimplex>(expr),
+                SourceType::Value => Self::billow::<Value>(expr),
+                SourceType::Worley => Self::billow::<Worley>(expr),
+            },
+            Self::BiasGain(expr) => Box::new(expr.noise(Self::noise_4d)),
+            Self::Blend(expr) => Box::new(Blend::new(
+                expr.sources[0].noise_4d(),
+                expr.sources[1].noise_4d(),
+                expr.control.noise_4d(),
+            )),
+            Self::BlueNoise(seed) => Box::new(BlueNoise::new(seed.value())),
+            Self::Cache(source) => Self::cache(source, Self::noise_4d),
+            Self::Checkerboard(size) => Box::new(Checkerboard::new(size.value() as _)),
+            Self::Clamp(expr) => Box::new(
+                Clamp::new(expr.source.noise_4d())
+                    .set_lower_bound(expr.lower_bound.value().min(expr.upper_bound.value()))
+                    .set_upper_bound(expr.lower_bound.value().max(expr.upper_bound.value())),
+            ),
+            Self::CombineRgb(expr) => Box::new(expr.noise(Self::noise_4d)),
+            Self::Compare(expr) => Box::new(expr.noise(Self::noise_4d)),
+            Self::Contrast(expr) => Box::new(expr.noise(Self::noise_4d)),
+            Self::Constant(value) => Box::new(Constant::new(value.value())),
+            Self::ConstantU32(_) => unreachable!(),
+            Self::Crater(expr) => Box::new(Crater {
+                source: expr.source.noise_4d(),
+                hasher: PermutationTable::new(expr.seed.value()),
+                density: expr.density.value(),
+                radius: expr.radius.value().clamp(0.0, 1.0),
+                depth: expr.depth.value(),
+            }),
+            Self::Curve(expr) => Self::curve(expr, expr.source.noise_4d()),
+            Self::Cylinders(frequency) => {
+                Box::new(Cylinders::new().set_frequency(frequency.value()))
+            }
+            Self::Difference([source1, source2]) => Box::new(Abs::new(Add::new(
+                source1.noise_4d(),
+                Negate::new(source2.noise_4d()),
+            ))),
+            Self::Displace(expr) => Box::new(Displace::new(
+                expr.source.noise_4d(),
+                expr.axes[0].noise_4d(),
+                expr.axes[1].noise_4d(),
+                expr.axes[2].noise_4d(),
+                expr.axes[3].noise_4d(),
+            )),
+            Self::DomainWarp(expr) => Box::new(expr.noise(Self::noise_4d)),
+            Self::Ease(expr) => Box::new(Ease {
+                source: expr.source.noise_4d(),
+                curve: expr.curve,
+            }),
+            Self::Erosion(expr) => match expr.source_ty {
+                SourceType::OpenSimplex => Self::erosion::<OpenSimplex>(expr),
+                SourceType::Perlin => Self::erosion::<Perlin>(expr),
+                SourceType::PerlinSurflet => Self::erosion::<PerlinSurflet>(expr),
+                SourceType::Simplex => Self::erosion::<Simplex>(expr),
+                SourceType::SuperSimplex => Self::erosion::<OpenSimplex>(expr),
+                SourceType::Value => Self::erosion::<Value>(expr),
+                SourceType::Worley => Self::erosion::<Worley>(expr),
+            },
+            Self::Exponent(expr) => {
+                Box::new(Exponent::new(expr.source.noise_4d()).set_exponent(expr.exponent.value()))
+            }
+            Self::FaultLines(expr) => Box::new(FaultLines::new(
+                expr.seed.value(),
+                expr.iterations.value().min(MAX_FAULT_ITERATIONS),
+                expr.amplitude.value(),
+            )),
+            Self::Fbm(expr) => match expr.source_ty {
+                SourceType::OpenSimplex => Self::fbm::<OpenSimplex>(expr),
+                SourceType::Perlin => Self::fbm::<Perlin>(expr),
+                SourceType::PerlinSurflet => Self::fbm::<PerlinSurflet>(expr),
+                SourceType::Simplex => Self::fbm::<Simplex>(expr),
+                SourceType::SuperSimplex => Self::fbm::<OpenSimplex>(expr),
+                SourceType::Value => Self::fbm::<Value>(expr),
+                SourceType::Worley => Self::fbm::<Worley>(expr),
+            },
+            Self::Formula(expr) => Box::new(expr.noise(Self::noise_4d)),
+            Self::GenericFractal(expr) => Box::new(expr.noise(Self::noise_4d)),
+            Self::Gradient(expr) => Box::new(expr.noise()),
+            Self::HybridMulti(expr) => match expr.source_ty {
+                SourceType::OpenSimplex => Self::hybrid_multi::<OpenSimplex>(expr),
+                SourceType::Perlin => Self::hybrid_multi::<Perlin>(expr),
+                SourceType::PerlinSurflet => Self::hybrid_multi::<PerlinSurflet>(expr),
+                SourceType::Simplex => Self::hybrid_multi::<Simplex>(expr),
+                SourceType::SuperSimplex => Self::hybrid_multi::<OpenSimplex>(expr),
+                SourceType::Value => Self::hybrid_multi::<Value>(expr),
+                SourceType::Worley => Self::hybrid_multi::<Worley>(expr),
+            },
+            Self::Jordan(expr) => match expr.source_ty {
+                SourceType::OpenSimplex => Self::jordan::<OpenSimplex>(expr),
+                SourceType::Perlin => Self::jordan::<Perlin>(expr),
+                SourceType::PerlinSurflet => Self::jordan::<PerlinSurflet>(expr),
+                SourceType::Simplex => Self::jordan::<Simplex>(expr),
+                SourceType::SuperSimplex => Self::jordan::<OpenSimplex>(expr),
+                SourceType::Value => Self::jordan::<Value>(expr),
+                SourceType::Worley => Self::jordan::<Worley>(expr),
+            },
+            Self::ImageSource(expr) => Box::new(expr.noise()),
+            Self::Max([source1, source2]) => {
+                Box::new(Max::new(source1.noise_4d(), source2.noise_4d()))
+            }
+            Self::Min([source1, source2]) => {
+                Box::new(Min::new(source1.noise_4d(), source2.noise_4d()))
+            }
+            Self::MirrorPoint(expr) => Box::new(expr.noise(Self::noise_4d)),
+            Self::Mix(expr) => Box::new(Blend::new(
+                expr.sources[0].noise_4d(),
+                expr.sources[1].noise_4d(),
+                Constant::new(expr.factor()),
+            )),
+            Self::Multiply([source1, source2]) => {
+                Box::new(Multiply::new(source1.noise_4d(), source2.noise_4d()))
+            }
+            Self::Negate(expr) => Box::new(Negate::new(expr.noise_4d())),
+            Self::Normal(expr) => Box::new(expr.noise(Self::noise_4d)),
+            Self::Normalize(expr) => Self::normalize(expr, expr.source.noise_4d()),
+            Self::OpenSimplex(seed) => Box::new(OpenSimplex::new(seed.value())),
+            Self::Perlin(seed) => Box::new(Perlin::new(seed.value())),
+            Self::PerlinSurflet(seed) => Box::new(PerlinSurflet::new(seed.value())),
+            Self::Power([source1, source2]) => {
+                Box::new(Power::new(source1.noise_4d(), source2.noise_4d()))
+            }
+            Self::Remap(expr) => Self::remap(expr, expr.source.noise_4d()),
+            Self::RidgedMulti(expr) => match expr.source_ty {
+                SourceType::OpenSimplex => Self::rigid_multi::<OpenSimplex>(expr),
+                SourceType::Perlin => Self::rigid_multi::<Perlin>(expr),
+                SourceType::PerlinSurflet => Self::rigid_multi::<PerlinSurflet>(expr),
+                SourceType::Simplex => Self::rigid_multi::<Simplex>(expr),
+                SourceType::SuperSimplex => Self::rigid_multi::<OpenSimplex>(expr),
+                SourceType::Value => Self::rigid_multi::<Value>(expr),
+                SourceType::Worley => Self::rigid_multi::<Worley>(expr),
+            },
+            Self::RotateAxis(expr) => Box::new(expr.noise(Self::noise_4d)),
+            Self::RotatePoint(expr) => {
+                Box::new(RotatePoint::new(expr.source.noise_4d()).set_angles(
+                    expr.axes[0].value(),
+                    expr.axes[1].value(),
+                    expr.axes[2].value(),
+                    expr.axes[3].value(),
+                ))
+            }
+            Self::ScaleBias(expr) => Box::new(
+                ScaleBias::new(expr.source.noise_4d())
+                    .set_bias(expr.bias.value())
+                    .set_scale(expr.scale.value()),
+            ),
+            Self::ScalePoint(expr) => {
+                Box::new(ScalePoint::new(expr.source.noise_4d()).set_all_scales(
+                    expr.axes[0].value(),
+                    expr.axes[1].value(),
+                    expr.axes[2].value(),
+                    expr.axes[3].value(),
+                ))
+            }
+            Self::Script(expr) => expr.noise(Self::noise_4d),
+            Self::Select(expr) => Box::new(
+                Select::new(
+                    expr.sources[0].noise_4d(),
+                    expr.sources[1].noise_4d(),
+                    expr.control.noise_4d(),
+                )
+                .set_bounds(expr.lower_bound.value(), expr.upper_bound.value())
+                .set_falloff(expr.falloff.value()),
+            ),
+            Self::Simplex(seed) => Box::new(Simplex::new(seed.value())),
+            Self::Slope(expr) => Box::new(expr.noise(Self::noise_4d)),
+            Self::SuperSimplex(seed) => Box::new(OpenSimplex::new(seed.value())),
+            Self::Swiss(expr) => match expr.source_ty {
+                SourceType::OpenSimplex => Self::swiss::<OpenSimplex>(expr),
+                SourceType::Perlin => Self::swiss::<Perlin>(expr),
+                SourceType::PerlinSurflet => Self::swiss::<PerlinSurflet>(expr),
+                SourceType::Simplex => Self::swiss::<Simplex>(expr),
+                SourceType::SuperSimplex => Self::swiss::<OpenSimplex>(expr),
+                SourceType::Value => Self::swiss::<Value>(expr),
+                SourceType::Worley => Self::swiss::<Worley>(expr),
+            },
+            Self::Switch(expr) => match expr.source() {
+                Some(source) => source.noise_4d(),
+                None => {
+                    ExprError::Switch.record();
+
+                    Box::new(Constant::new(0.0))
+                }
+            },
+            Self::Swizzle(expr) => Box::new(expr.noise(Self::noise_4d)),
+            Self::Terrace(expr) => Self::terrace(expr, expr.source.noise_4d()),
+            Self::TilePoint(expr) => Box::new(expr.noise(Self::noise_4d)),
+            Self::TranslatePoint(expr) => Box::new(
+                TranslatePoint::new(expr.source.noise_4d()).set_all_translations(
+                    expr.axes[0].value(),
+                    expr.axes[1].value(),
+                    expr.axes[2].value(),
+                    expr.axes[3].value(),
+                ),
             ),
+            Self::Turbulence(expr) => match expr.source_ty {
+                SourceType::OpenSimplex => {
+                    Self::turbulence::<_, OpenSimplex>(expr, expr.source.noise_4d())
+                }
+                SourceType::Perlin => Self::turbulence::<_, Perlin>(expr, expr.source.noise_4d()),
+                SourceType::PerlinSurflet => {
+                    Self::turbulence::<_, PerlinSurflet>(expr, expr.source.noise_4d())
+                }
+                SourceType::Simplex => Self::turbulence::<_, Simplex>(expr, expr.source.noise_4d()),
+                SourceType::SuperSimplex => {
+                    Self::turbulence::<_, OpenSimplex>(expr, expr.source.noise_4d())
+                }
+                SourceType::Value => Self::turbulence::<_, Value>(expr, expr.source.noise_4d()),
+                SourceType::Worley => Self::turbulence::<_, Worley>(expr, expr.source.noise_4d()),
+            },
+            Self::Value(seed) => Box::new(Value::new(seed.value())),
+            Self::WarpedFbm(expr) => match expr.source_ty {
+                SourceType::OpenSimplex => Self::warped_fbm::<OpenSimplex>(expr),
+                SourceType::Perlin => Self::warped_fbm::<Perlin>(expr),
+                SourceType::PerlinSurflet => Self::warped_fbm::<PerlinSurflet>(expr),
+                SourceType::Simplex => Self::warped_fbm::<Simplex>(expr),
+                SourceType::SuperSimplex => Self::warped_fbm::<OpenSimplex>(expr),
+                SourceType::Value => Self::warped_fbm::<Value>(expr),
+                SourceType::Worley => Self::warped_fbm::<Worley>(expr),
+            },
+            Self::Worley(expr) => Self::worley(expr),
+        }
+    }
+
+    fn normalize<const DIM: usize>(
+        expr: &NormalizeExpr,
+        source: Box<dyn NoiseFn<f64, DIM>>,
+    ) -> Box<dyn NoiseFn<f64, DIM>> {
+        let (scale, bias) = expr.scale_bias();
+
+        Box::new(ScaleBias::new(source).set_scale(scale).set_bias(bias))
+    }
+
+    fn remap<const DIM: usize>(
+        expr: &RemapExpr,
+        source: Box<dyn NoiseFn<f64, DIM>>,
+    ) -> Box<dyn NoiseFn<f64, DIM>> {
+        let (scale, bias) = expr.scale_bias();
+        let res = ScaleBias::new(source).set_scale(scale).set_bias(bias);
+
+        if expr.clamped {
+            let (out_min, out_max) = (expr.out_min.value(), expr.out_max.value());
+
+            Box::new(
+                Clamp::new(res)
+                    .set_lower_bound(out_min.min(out_max))
+                    .set_upper_bound(out_min.max(out_max)),
+            )
+        } else {
+            Box::new(res)
         }
     }
 
@@ -473,58 +1945,571 @@ impl Expr {
         )
     }
 
+    fn swiss<T>(expr: &FractalExpr) -> Box<DampedFractal<T>>
+    where
+        T: Default + Seedable,
+    {
+        Box::new(expr.damped(DampingType::Swiss))
+    }
+
+    fn erosion<T>(expr: &ErosionExpr) -> Box<Erosion<T>>
+    where
+        T: Default + Seedable,
+    {
+        let seed = expr.seed.value();
+
+        Box::new(Erosion {
+            sources: (0..expr.octaves.value().clamp(1, MAX_FRACTAL_OCTAVES))
+                .map(|octave| T::default().set_seed(seed.wrapping_add(octave)))
+                .collect(),
+            frequency: expr.frequency.value(),
+            lacunarity: expr.lacunarity.value(),
+            persistence: expr.persistence.value(),
+            strength: expr.strength.value(),
+        })
+    }
+
+    /// Reads an expression from the compact postcard binary format; see [`Expr::to_bytes`].
     #[allow(unused)]
-    pub fn set_f64(&mut self, name: &str, value: f64) -> &mut Self {
+    #[cfg(feature = "postcard")]
+    pub fn from_bytes(bytes: &[u8]) -> postcard::Result<Self> {
+        postcard::from_bytes(bytes)
+    }
+
+    /// Reads an expression from JSON, such as the text copied by "Copy Expr (JSON)" in the app.
+    #[allow(unused)]
+    #[cfg(feature = "json")]
+    pub fn from_json(text: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(text)
+    }
+
+    /// Writes the expression in the compact postcard binary format, which is not self-describing:
+    /// bytes written by one version of this crate may not be readable by another.
+    #[allow(unused)]
+    #[cfg(feature = "postcard")]
+    pub fn to_bytes(&self) -> postcard::Result<Vec<u8>> {
+        postcard::to_allocvec(self)
+    }
+
+    /// Writes the expression as JSON.
+    ///
+    /// JSON has no infinite or NaN numbers, so variables with those values are written as `null`
+    /// and cannot be read back.
+    #[allow(unused)]
+    #[cfg(feature = "json")]
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string(self)
+    }
+
+    /// Returns every named variable of the expression, which may be replaced using
+    /// [`Expr::set_f64`] and [`Expr::set_u32`], in order of first use. Each expression is visited
+    /// before its sources.
+    #[allow(unused)]
+    pub fn variables(&self) -> Vec<VariableInfo> {
+        let mut vars = Variables::default();
+        self.collect_variables(&mut vars);
+
+        vars.infos
+    }
+
+    /// Collects the variables of this expression and then those of each of its sources.
+    fn collect_variables(&self, vars: &mut Variables) {
         match self {
-            Self::Abs(expr) | Self::Negate(expr) => {
-                expr.set_f64(name, value);
-            }
-            Self::Add(exprs)
-            | Self::Max(exprs)
-            | Self::Min(exprs)
-            | Self::Multiply(exprs)
-            | Self::Power(exprs) => exprs.iter_mut().for_each(|expr| {
-                expr.set_f64(name, value);
-            }),
-            Self::BasicMulti(expr)
-            | Self::Billow(expr)
-            | Self::Fbm(expr)
-            | Self::HybridMulti(expr) => expr.set_f64(name, value),
-            Self::Blend(expr) => expr.set_f64(name, value),
-            Self::Clamp(expr) => expr.set_f64(name, value),
-            Self::Constant(expr) | Self::Cylinders(expr) => expr.set_if_named(name, value),
-            Self::Curve(expr) => expr.set_f64(name, value),
-            Self::Displace(expr) => expr.set_f64(name, value),
-            Self::Exponent(expr) => expr.set_f64(name, value),
-            Self::RidgedMulti(expr) => expr.set_f64(name, value),
-            Self::RotatePoint(expr) | Self::ScalePoint(expr) | Self::TranslatePoint(expr) => {
-                expr.set_f64(name, value)
-            }
-            Self::ScaleBias(expr) => expr.set_f64(name, value),
-            Self::Select(expr) => expr.set_f64(name, value),
-            Self::Terrace(expr) => expr.set_f64(name, value),
-            Self::Turbulence(expr) => expr.set_f64(name, value),
-            Self::Worley(expr) => expr.set_f64(name, value),
-            Self::Checkerboard(_)
-            | Self::ConstantU32(_)
-            | Self::OpenSimplex(_)
-            | Self::Perlin(_)
-            | Self::PerlinSurflet(_)
-            | Self::Simplex(_)
-            | Self::SuperSimplex(_)
-            | Self::Value(_) => (),
+            Self::BasicMulti(expr) => expr.variables("BasicMulti", vars),
+            Self::BiasGain(expr) => expr.variables("BiasGain", vars),
+            Self::Billow(expr) => expr.variables("Billow", vars),
+            Self::BlueNoise(value) => vars.u32("BlueNoise", value),
+            Self::Checkerboard(value) => vars.u32("Checkerboard", value),
+            Self::Clamp(expr) => expr.variables("Clamp", vars),
+            Self::Compare(expr) => expr.variables("Compare", vars),
+            Self::Contrast(expr) => expr.variables("Contrast", vars),
+            Self::Constant(value) => vars.f64("Constant", value),
+            Self::ConstantU32(value) => vars.u32("ConstantU32", value),
+            Self::Crater(expr) => expr.variables("Crater", vars),
+            Self::Curve(expr) => expr.variables("Curve", vars),
+            Self::Cylinders(value) => vars.f64("Cylinders", value),
+            Self::DomainWarp(expr) => expr.variables("DomainWarp", vars),
+            Self::Erosion(expr) => expr.variables("Erosion", vars),
+            Self::Exponent(expr) => expr.variables("Exponent", vars),
+            Self::FaultLines(expr) => expr.variables("FaultLines", vars),
+            Self::Fbm(expr) => expr.variables("Fbm", vars),
+            Self::Formula(expr) => expr.variables("Formula", vars),
+            Self::GenericFractal(expr) => expr.variables("GenericFractal", vars),
+            Self::Gradient(expr) => expr.variables("Gradient", vars),
+            Self::HybridMulti(expr) => expr.variables("HybridMulti", vars),
+            Self::ImageSource(expr) => vars.f64("ImageSource", &expr.size),
+            Self::Jordan(expr) => expr.variables("Jordan", vars),
+            Self::Mix(expr) => expr.variables("Mix", vars),
+            Self::Normal(expr) => expr.variables("Normal", vars),
+            Self::OpenSimplex(value) => vars.u32("OpenSimplex", value),
+            Self::Perlin(value) => vars.u32("Perlin", value),
+            Self::PerlinSurflet(value) => vars.u32("PerlinSurflet", value),
+            Self::Remap(expr) => expr.variables("Remap", vars),
+            Self::RidgedMulti(expr) => expr.variables("RidgedMulti", vars),
+            Self::RotateAxis(expr) => expr.variables("RotateAxis", vars),
+            Self::RotatePoint(expr) => expr.variables("RotatePoint", vars),
+            Self::ScaleBias(expr) => expr.variables("ScaleBias", vars),
+            Self::ScalePoint(expr) => expr.variables("ScalePoint", vars),
+            Self::Script(expr) => expr.variables("Script", vars),
+            Self::Select(expr) => expr.variables("Select", vars),
+            Self::Simplex(value) => vars.u32("Simplex", value),
+            Self::Slope(expr) => expr.variables("Slope", vars),
+            Self::SuperSimplex(value) => vars.u32("SuperSimplex", value),
+            Self::Swiss(expr) => expr.variables("Swiss", vars),
+            Self::Switch(expr) => expr.variables("Switch", vars),
+            Self::Terrace(expr) => expr.variables("Terrace", vars),
+            Self::TilePoint(expr) => expr.variables("TilePoint", vars),
+            Self::TranslatePoint(expr) => expr.variables("TranslatePoint", vars),
+            Self::Turbulence(expr) => expr.variables("Turbulence", vars),
+            Self::Value(value) => vars.u32("Value", value),
+            Self::WarpedFbm(expr) => expr.variables("WarpedFbm", vars),
+            Self::Worley(expr) => expr.variables("Worley", vars),
+            Self::Abs(_)
+            | Self::Add(_)
+            | Self::Blend(_)
+            | Self::Cache(_)
+            | Self::CombineRgb(_)
+            | Self::Difference(_)
+            | Self::Displace(_)
+            | Self::Ease(_)
+            | Self::Max(_)
+            | Self::Min(_)
+            | Self::MirrorPoint(_)
+            | Self::Multiply(_)
+            | Self::Negate(_)
+            | Self::Normalize(_)
+            | Self::Power(_)
+            | Self::Swizzle(_) => (),
         }
 
-        self
+        for (idx, source) in self.sources().into_iter().enumerate() {
+            vars.path.push(idx);
+            source.collect_variables(vars);
+            vars.path.pop();
+        }
     }
 
+    /// Returns the expression reached by taking each source index of `path` in turn, such as the
+    /// path of a [`VariableUse`].
     #[allow(unused)]
-    pub fn set_u32(&mut self, name: &str, value: u32) -> &mut Self {
+    pub fn source_at(&self, path: &[usize]) -> Option<&Self> {
+        path.iter()
+            .try_fold(self, |expr, &idx| expr.sources().get(idx).copied())
+    }
+
+    /// Simplifies the expression without changing the values it samples: branches made only of
+    /// constants are folded into one constant, transforms which do nothing are removed, and
+    /// branches which appear more than once are cached so that they are sampled once per point.
+    ///
+    /// Named variables are never folded, so [`Expr::set_f64`] and [`Expr::set_u32`] still apply.
+    #[allow(unused)]
+    pub fn optimize(&mut self) -> &mut Self {
+        self.fold();
+
+        let mut counts = HashMap::new();
+        self.count_branches(&mut counts);
+
+        let repeated = counts
+            .into_iter()
+            .filter(|&(_, count)| count > 1)
+            .map(|(expr, _)| expr.clone())
+            .collect();
+        self.cache_branches(&repeated);
+
+        self
+    }
+
+    /// Folds the sources of this expression and then the expression itself.
+    fn fold(&mut self) {
+        let mut constant = true;
+
+        for source in self.sources_mut() {
+            source.fold();
+            constant &= matches!(source, Self::Constant(Variable::Anonymous(_)));
+        }
+
+        if let Some(expr) = self.folded(constant) {
+            *self = expr;
+        }
+    }
+
+    /// Returns a simpler expression which samples the same values, if there is one; `constant` is
+    /// `true` when every source is an anonymous constant.
+    fn folded(&self, constant: bool) -> Option<Self> {
+        fn is(value: &Variable<f64>, expected: f64) -> bool {
+            matches!(value, Variable::Anonymous(value) if *value == expected)
+        }
+
+        match self {
+            Self::Abs(_)
+            | Self::Add(_)
+            | Self::BiasGain(_)
+            | Self::Blend(_)
+            | Self::Clamp(_)
+            | Self::Compare(_)
+            | Self::Contrast(_)
+            | Self::Curve(_)
+            | Self::Difference(_)
+            | Self::Ease(_)
+            | Self::Exponent(_)
+            | Self::Max(_)
+            | Self::Min(_)
+            | Self::Mix(_)
+            | Self::Multiply(_)
+            | Self::Negate(_)
+            | Self::Power(_)
+            | Self::Remap(_)
+            | Self::ScaleBias(_)
+            | Self::Select(_)
+            | Self::Terrace(_)
+                if constant && self.variables().is_empty() =>
+            {
+                // Sampling at any point gives the value at every point
+                let value = self.try_noise().ok()?.get([0.0; 3]);
+
+                Some(Self::Constant(Variable::Anonymous(value)))
+            }
+            Self::Cache(source) if matches!(**source, Self::Cache(_) | Self::Constant(_)) => {
+                Some((**source).clone())
+            }
+            Self::Negate(source) => match &**source {
+                Self::Negate(source) => Some((**source).clone()),
+                _ => None,
+            },
+            Self::Displace(DisplaceExpr { source, .. })
+            | Self::DomainWarp(DomainWarpExpr { source, .. })
+            | Self::MirrorPoint(MirrorPointExpr { source, .. })
+            | Self::RotateAxis(RotateAxisExpr { source, .. })
+            | Self::RotatePoint(TransformExpr { source, .. })
+            | Self::ScalePoint(TransformExpr { source, .. })
+            | Self::Swizzle(SwizzleExpr { source, .. })
+            | Self::TilePoint(TilePointExpr { source, .. })
+            | Self::TranslatePoint(TransformExpr { source, .. })
+                if matches!(**source, Self::Constant(_)) =>
+            {
+                Some((**source).clone())
+            }
+            Self::RotatePoint(expr) | Self::TranslatePoint(expr)
+                if expr.axes.iter().all(|axis| is(axis, 0.0)) =>
+            {
+                Some((*expr.source).clone())
+            }
+            Self::ScalePoint(expr) if expr.axes.iter().all(|axis| is(axis, 1.0)) => {
+                Some((*expr.source).clone())
+            }
+            Self::ScaleBias(expr) if is(&expr.scale, 1.0) && is(&expr.bias, 0.0) => {
+                Some((*expr.source).clone())
+            }
+            Self::Max(sources) | Self::Min(sources) if sources[0] == sources[1] => {
+                Some((*sources[0]).clone())
+            }
+            Self::Blend(BlendExpr { sources, .. })
+            | Self::Mix(MixExpr { sources, .. })
+            | Self::Select(SelectExpr { sources, .. })
+                if sources[0] == sources[1] =>
+            {
+                Some((*sources[0]).clone())
+            }
+            Self::Switch(expr) if matches!(expr.selector, Variable::Anonymous(_)) => {
+                expr.source().cloned()
+            }
+            _ => None,
+        }
+    }
+
+    /// Counts each branch of the expression by its printed form; the branches of a repeated
+    /// branch are only counted once.
+    fn count_branches<'a>(&'a self, counts: &mut HashMap<&'a Self, usize>) {
+        for source in self.sources() {
+            let count = counts.entry(source).or_default();
+            *count += 1;
+
+            if *count == 1 {
+                source.count_branches(counts);
+            }
+        }
+    }
+
+    /// Caches each branch which appears more than once; identical caches share one cache when the
+    /// noise function is built.
+    fn cache_branches(&mut self, repeated: &HashSet<Self>) {
+        let cached = matches!(self, Self::Cache(_));
+
+        for source in self.sources_mut() {
+            let cache = !cached
+                && !matches!(
+                    source,
+                    Self::Cache(_) | Self::Constant(_) | Self::ConstantU32(_)
+                )
+                && repeated.contains(source);
+
+            source.cache_branches(repeated);
+
+            if cache {
+                let expr = replace(source, Self::Constant(Variable::Anonymous(0.0)));
+                *source = Self::Cache(Box::new(expr));
+            }
+        }
+    }
+
+    /// Returns the expressions sampled by this expression.
+    fn sources(&self) -> Vec<&Self> {
+        match self {
+            Self::Abs(source) | Self::Cache(source) | Self::Negate(source) => vec![&**source],
+            Self::Add(sources)
+            | Self::Difference(sources)
+            | Self::Max(sources)
+            | Self::Min(sources)
+            | Self::Multiply(sources)
+            | Self::Power(sources) => sources.iter().map(|source| &**source).collect(),
+            Self::BiasGain(expr) => vec![&*expr.source],
+            Self::Blend(expr) => expr
+                .sources
+                .iter()
+                .chain(once(&expr.control))
+                .map(|source| &**source)
+                .collect(),
+            Self::Clamp(expr) => vec![&*expr.source],
+            Self::CombineRgb(expr) => expr.channels.iter().map(|source| &**source).collect(),
+            Self::Compare(expr) => expr.sources.iter().map(|source| &**source).collect(),
+            Self::Contrast(expr) => vec![&*expr.source],
+            Self::Crater(expr) => vec![&*expr.source],
+            Self::Curve(expr) => vec![&*expr.source],
+            Self::Displace(expr) => once(&expr.source)
+                .chain(&expr.axes)
+                .map(|source| &**source)
+                .collect(),
+            Self::DomainWarp(expr) => vec![&*expr.source, &*expr.warp],
+            Self::Ease(expr) => vec![&*expr.source],
+            Self::Exponent(expr) => vec![&*expr.source],
+            Self::Formula(expr) => expr.sources.iter().collect(),
+            Self::GenericFractal(expr) => vec![&*expr.source],
+            Self::MirrorPoint(expr) => vec![&*expr.source],
+            Self::Mix(expr) => expr.sources.iter().map(|source| &**source).collect(),
+            Self::Normal(expr) => vec![&*expr.source],
+            Self::Normalize(expr) => vec![&*expr.source],
+            Self::Remap(expr) => vec![&*expr.source],
+            Self::RotateAxis(expr) => vec![&*expr.source],
+            Self::RotatePoint(expr) | Self::ScalePoint(expr) | Self::TranslatePoint(expr) => {
+                vec![&*expr.source]
+            }
+            Self::ScaleBias(expr) => vec![&*expr.source],
+            Self::Script(expr) => expr.sources.iter().collect(),
+            Self::Select(expr) => expr
+                .sources
+                .iter()
+                .chain(once(&expr.control))
+                .map(|source| &**source)
+                .collect(),
+            Self::Slope(expr) => vec![&*expr.source],
+            Self::Switch(expr) => expr.sources.iter().collect(),
+            Self::Swizzle(expr) => vec![&*expr.source],
+            Self::Terrace(expr) => vec![&*expr.source],
+            Self::TilePoint(expr) => vec![&*expr.source],
+            Self::Turbulence(expr) => vec![&*expr.source],
+            Self::BasicMulti(_)
+            | Self::Billow(_)
+            | Self::BlueNoise(_)
+            | Self::Checkerboard(_)
+            | Self::Constant(_)
+            | Self::ConstantU32(_)
+            | Self::Cylinders(_)
+            | Self::Erosion(_)
+            | Self::FaultLines(_)
+            | Self::Fbm(_)
+            | Self::Gradient(_)
+            | Self::HybridMulti(_)
+            | Self::ImageSource(_)
+            | Self::Jordan(_)
+            | Self::OpenSimplex(_)
+            | Self::Perlin(_)
+            | Self::PerlinSurflet(_)
+            | Self::RidgedMulti(_)
+            | Self::Simplex(_)
+            | Self::SuperSimplex(_)
+            | Self::Swiss(_)
+            | Self::Value(_)
+            | Self::WarpedFbm(_)
+            | Self::Worley(_) => vec![],
+        }
+    }
+
+    /// Returns the expressions sampled by this expression, mutably.
+    fn sources_mut(&mut self) -> Vec<&mut Self> {
+        match self {
+            Self::Abs(source) | Self::Cache(source) | Self::Negate(source) => vec![&mut **source],
+            Self::Add(sources)
+            | Self::Difference(sources)
+            | Self::Max(sources)
+            | Self::Min(sources)
+            | Self::Multiply(sources)
+            | Self::Power(sources) => sources.iter_mut().map(|source| &mut **source).collect(),
+            Self::BiasGain(expr) => vec![&mut *expr.source],
+            Self::Blend(expr) => expr
+                .sources
+                .iter_mut()
+                .chain(once(&mut expr.control))
+                .map(|source| &mut **source)
+                .collect(),
+            Self::Clamp(expr) => vec![&mut *expr.source],
+            Self::CombineRgb(expr) => expr
+                .channels
+                .iter_mut()
+                .map(|source| &mut **source)
+                .collect(),
+            Self::Compare(expr) => expr
+                .sources
+                .iter_mut()
+                .map(|source| &mut **source)
+                .collect(),
+            Self::Contrast(expr) => vec![&mut *expr.source],
+            Self::Crater(expr) => vec![&mut *expr.source],
+            Self::Curve(expr) => vec![&mut *expr.source],
+            Self::Displace(expr) => once(&mut expr.source)
+                .chain(&mut expr.axes)
+                .map(|source| &mut **source)
+                .collect(),
+            Self::DomainWarp(expr) => vec![&mut *expr.source, &mut *expr.warp],
+            Self::Ease(expr) => vec![&mut *expr.source],
+            Self::Exponent(expr) => vec![&mut *expr.source],
+            Self::Formula(expr) => expr.sources.iter_mut().collect(),
+            Self::GenericFractal(expr) => vec![&mut *expr.source],
+            Self::MirrorPoint(expr) => vec![&mut *expr.source],
+            Self::Mix(expr) => expr
+                .sources
+                .iter_mut()
+                .map(|source| &mut **source)
+                .collect(),
+            Self::Normal(expr) => vec![&mut *expr.source],
+            Self::Normalize(expr) => vec![&mut *expr.source],
+            Self::Remap(expr) => vec![&mut *expr.source],
+            Self::RotateAxis(expr) => vec![&mut *expr.source],
+            Self::RotatePoint(expr) | Self::ScalePoint(expr) | Self::TranslatePoint(expr) => {
+                vec![&mut *expr.source]
+            }
+            Self::ScaleBias(expr) => vec![&mut *expr.source],
+            Self::Script(expr) => expr.sources.iter_mut().collect(),
+            Self::Select(expr) => expr
+                .sources
+                .iter_mut()
+                .chain(once(&mut expr.control))
+                .map(|source| &mut **source)
+                .collect(),
+            Self::Slope(expr) => vec![&mut *expr.source],
+            Self::Switch(expr) => expr.sources.iter_mut().collect(),
+            Self::Swizzle(expr) => vec![&mut *expr.source],
+            Self::Terrace(expr) => vec![&mut *expr.source],
+            Self::TilePoint(expr) => vec![&mut *expr.source],
+            Self::Turbulence(expr) => vec![&mut *expr.source],
+            Self::BasicMulti(_)
+            | Self::Billow(_)
+            | Self::BlueNoise(_)
+            | Self::Checkerboard(_)
+            | Self::Constant(_)
+            | Self::ConstantU32(_)
+            | Self::Cylinders(_)
+            | Self::Erosion(_)
+            | Self::FaultLines(_)
+            | Self::Fbm(_)
+            | Self::Gradient(_)
+            | Self::HybridMulti(_)
+            | Self::ImageSource(_)
+            | Self::Jordan(_)
+            | Self::OpenSimplex(_)
+            | Self::Perlin(_)
+            | Self::PerlinSurflet(_)
+            | Self::RidgedMulti(_)
+            | Self::Simplex(_)
+            | Self::SuperSimplex(_)
+            | Self::Swiss(_)
+            | Self::Value(_)
+            | Self::WarpedFbm(_)
+            | Self::Worley(_) => vec![],
+        }
+    }
+
+    #[allow(unused)]
+    pub fn set_f64(&mut self, name: &str, value: f64) -> &mut Self {
+        match self {
+            Self::Abs(expr) | Self::Cache(expr) | Self::Negate(expr) => {
+                expr.set_f64(name, value);
+            }
+            Self::Add(exprs)
+            | Self::Difference(exprs)
+            | Self::Max(exprs)
+            | Self::Min(exprs)
+            | Self::Multiply(exprs)
+            | Self::Power(exprs) => exprs.iter_mut().for_each(|expr| {
+                expr.set_f64(name, value);
+            }),
+            Self::BasicMulti(expr)
+            | Self::Billow(expr)
+            | Self::Fbm(expr)
+            | Self::HybridMulti(expr)
+            | Self::Jordan(expr)
+            | Self::Swiss(expr) => expr.set_f64(name, value),
+            Self::BiasGain(expr) => expr.set_f64(name, value),
+            Self::Blend(expr) => expr.set_f64(name, value),
+            Self::Clamp(expr) => expr.set_f64(name, value),
+            Self::CombineRgb(expr) => expr.set_f64(name, value),
+            Self::Compare(expr) => expr.set_f64(name, value),
+            Self::Contrast(expr) => expr.set_f64(name, value),
+            Self::Constant(expr) | Self::Cylinders(expr) => expr.set_if_named(name, value),
+            Self::Crater(expr) => expr.set_f64(name, value),
+            Self::ImageSource(expr) => expr.size.set_if_named(name, value),
+            Self::Curve(expr) => expr.set_f64(name, value),
+            Self::Displace(expr) => expr.set_f64(name, value),
+            Self::DomainWarp(expr) => expr.set_f64(name, value),
+            Self::Ease(expr) => expr.set_f64(name, value),
+            Self::Erosion(expr) => expr.set_f64(name, value),
+            Self::Exponent(expr) => expr.set_f64(name, value),
+            Self::FaultLines(expr) => expr.set_f64(name, value),
+            Self::Formula(expr) => expr.set_f64(name, value),
+            Self::GenericFractal(expr) => expr.set_f64(name, value),
+            Self::Gradient(expr) => expr.set_f64(name, value),
+            Self::MirrorPoint(expr) => expr.set_f64(name, value),
+            Self::Mix(expr) => expr.set_f64(name, value),
+            Self::Normal(expr) => expr.set_f64(name, value),
+            Self::Normalize(expr) => expr.set_f64(name, value),
+            Self::Remap(expr) => expr.set_f64(name, value),
+            Self::RidgedMulti(expr) => expr.set_f64(name, value),
+            Self::RotateAxis(expr) => expr.set_f64(name, value),
+            Self::RotatePoint(expr) | Self::ScalePoint(expr) | Self::TranslatePoint(expr) => {
+                expr.set_f64(name, value)
+            }
+            Self::ScaleBias(expr) => expr.set_f64(name, value),
+            Self::Script(expr) => expr.set_f64(name, value),
+            Self::Select(expr) => expr.set_f64(name, value),
+            Self::Slope(expr) => expr.set_f64(name, value),
+            Self::Switch(expr) => expr.set_f64(name, value),
+            Self::Swizzle(expr) => expr.set_f64(name, value),
+            Self::Terrace(expr) => expr.set_f64(name, value),
+            Self::TilePoint(expr) => expr.set_f64(name, value),
+            Self::Turbulence(expr) => expr.set_f64(name, value),
+            Self::WarpedFbm(expr) => expr.set_f64(name, value),
+            Self::Worley(expr) => expr.set_f64(name, value),
+            Self::BlueNoise(_)
+            | Self::Checkerboard(_)
+            | Self::ConstantU32(_)
+            | Self::OpenSimplex(_)
+            | Self::Perlin(_)
+            | Self::PerlinSurflet(_)
+            | Self::Simplex(_)
+            | Self::SuperSimplex(_)
+            | Self::Value(_) => (),
+        }
+
+        self
+    }
+
+    #[allow(unused)]
+    pub fn set_u32(&mut self, name: &str, value: u32) -> &mut Self {
         match self {
-            Self::Abs(expr) | Self::Negate(expr) => {
+            Self::Abs(expr) | Self::Cache(expr) | Self::Negate(expr) => {
                 expr.set_u32(name, value);
             }
             Self::Add(exprs)
+            | Self::Difference(exprs)
             | Self::Max(exprs)
             | Self::Min(exprs)
             | Self::Multiply(exprs)
@@ -534,9 +2519,13 @@ impl Expr {
             Self::BasicMulti(expr)
             | Self::Billow(expr)
             | Self::Fbm(expr)
-            | Self::HybridMulti(expr) => expr.set_u32(name, value),
+            | Self::HybridMulti(expr)
+            | Self::Jordan(expr)
+            | Self::Swiss(expr) => expr.set_u32(name, value),
+            Self::BiasGain(expr) => expr.set_u32(name, value),
             Self::Blend(expr) => expr.set_u32(name, value),
-            Self::Checkerboard(expr)
+            Self::BlueNoise(expr)
+            | Self::Checkerboard(expr)
             | Self::ConstantU32(expr)
             | Self::OpenSimplex(expr)
             | Self::Perlin(expr)
@@ -545,69 +2534,2030 @@ impl Expr {
             | Self::SuperSimplex(expr)
             | Self::Value(expr) => expr.set_if_named(name, value),
             Self::Clamp(expr) => expr.set_u32(name, value),
+            Self::CombineRgb(expr) => expr.set_u32(name, value),
+            Self::Compare(expr) => expr.set_u32(name, value),
+            Self::Contrast(expr) => expr.set_u32(name, value),
+            Self::Crater(expr) => expr.set_u32(name, value),
             Self::Curve(expr) => expr.set_u32(name, value),
             Self::Displace(expr) => expr.set_u32(name, value),
+            Self::DomainWarp(expr) => expr.set_u32(name, value),
+            Self::Ease(expr) => expr.set_u32(name, value),
+            Self::Erosion(expr) => expr.set_u32(name, value),
             Self::Exponent(expr) => expr.set_u32(name, value),
+            Self::FaultLines(expr) => expr.set_u32(name, value),
+            Self::Formula(expr) => expr.set_u32(name, value),
+            Self::GenericFractal(expr) => expr.set_u32(name, value),
+            Self::MirrorPoint(expr) => expr.set_u32(name, value),
+            Self::Mix(expr) => expr.set_u32(name, value),
+            Self::Normal(expr) => expr.set_u32(name, value),
+            Self::Normalize(expr) => expr.set_u32(name, value),
+            Self::Remap(expr) => expr.set_u32(name, value),
             Self::RidgedMulti(expr) => expr.set_u32(name, value),
+            Self::RotateAxis(expr) => expr.set_u32(name, value),
             Self::RotatePoint(expr) | Self::ScalePoint(expr) | Self::TranslatePoint(expr) => {
                 expr.set_u32(name, value)
             }
             Self::Select(expr) => expr.set_u32(name, value),
             Self::ScaleBias(expr) => expr.set_u32(name, value),
+            Self::Script(expr) => expr.set_u32(name, value),
+            Self::Slope(expr) => expr.set_u32(name, value),
+            Self::Switch(expr) => expr.set_u32(name, value),
+            Self::Swizzle(expr) => expr.set_u32(name, value),
             Self::Terrace(expr) => expr.set_u32(name, value),
+            Self::TilePoint(expr) => expr.set_u32(name, value),
             Self::Turbulence(expr) => expr.set_u32(name, value),
+            Self::WarpedFbm(expr) => expr.set_u32(name, value),
             Self::Worley(expr) => expr.set_u32(name, value),
-            Self::Constant(_) | Self::Cylinders(_) => (),
+            Self::Constant(_) | Self::Cylinders(_) | Self::Gradient(_) | Self::ImageSource(_) => (),
         }
 
         self
     }
 
-    fn turbulence<T>(expr: &TurbulenceExpr) -> Box<Turbulence<Box<dyn NoiseFn<f64, 3>>, T>>
-    where
-        T: Default + Seedable,
-    {
-        Box::new(
-            Turbulence::<Box<dyn NoiseFn<f64, 3>>, T>::new(expr.source.noise())
-                .set_seed(expr.seed.value())
-                .set_frequency(expr.frequency.value())
-                .set_power(expr.power.value())
-                .set_roughness(expr.roughness.value() as _),
-        )
+    fn turbulence<S, T>(expr: &TurbulenceExpr, source: S) -> Box<Turbulence<S, T>>
+    where
+        T: Default + Seedable,
+    {
+        Box::new(
+            Turbulence::<S, T>::new(source)
+                .set_seed(expr.seed.value())
+                .set_frequency(expr.frequency.value())
+                .set_power(expr.power.value())
+                .set_roughness(expr.roughness.value() as _),
+        )
+    }
+
+    fn warped_fbm<T>(expr: &WarpedFbmExpr) -> Box<WarpedFbm<T>>
+    where
+        T: Default + Seedable,
+    {
+        let seed = expr.seed.value();
+
+        Box::new(WarpedFbm {
+            sources: (0..expr.octaves.value().clamp(1, MAX_FRACTAL_OCTAVES))
+                .map(|octave| T::default().set_seed(seed.wrapping_add(octave)))
+                .collect(),
+            frequency: expr.frequency.value(),
+            lacunarity: expr.lacunarity.value(),
+            persistence: expr.persistence.value(),
+            strength: expr.strength.value(),
+            iterations: expr.iterations.value().min(MAX_WARP_ITERATIONS),
+        })
+    }
+
+    fn terrace<const DIM: usize>(
+        expr: &TerraceExpr,
+        source: Box<dyn NoiseFn<f64, DIM>>,
+    ) -> Box<dyn NoiseFn<f64, DIM>> {
+        // Make sure the control points are valid (noise-rs panics!)
+        if !expr.is_valid() {
+            ExprError::Terrace.record();
+
+            return Box::new(Constant::new(0.0));
+        }
+
+        Box::new(expr.terrace(source))
+    }
+
+    fn worley<const DIM: usize>(expr: &WorleyExpr) -> Box<dyn NoiseFn<f64, DIM>>
+    where
+        Worley: NoiseFn<f64, DIM>,
+    {
+        let distance_fn: fn(&[f64], &[f64]) -> f64 = match expr.distance_fn {
+            DistanceFunction::Chebyshev => chebyshev,
+            DistanceFunction::Euclidean => euclidean,
+            DistanceFunction::EuclideanSquared => euclidean_squared,
+            DistanceFunction::Manhattan => manhattan,
+        };
+        let return_ty = match expr.return_ty {
+            ReturnType::Distance => worley::ReturnType::Distance,
+            ReturnType::Value => worley::ReturnType::Value,
+            return_ty => {
+                return Box::new(NeighbourWorley {
+                    hasher: PermutationTable::new(expr.seed.value()),
+                    frequency: expr.frequency.value(),
+                    distance_fn,
+                    return_ty,
+                })
+            }
+        };
+
+        Box::new(
+            Worley::new(expr.seed.value())
+                .set_frequency(expr.frequency.value())
+                .set_distance_function(distance_fn)
+                .set_return_type(return_ty),
+        )
+    }
+}
+
+thread_local! {
+    /// The caches of the noise function being built; see `Expr::cache`.
+    static SHARED_CACHES: RefCell<HashMap<Expr, Vec<Rc<dyn Any>>>> = Default::default();
+
+    /// The number of `Expr::noise` and `Expr::noise_4d` calls in progress.
+    static SHARED_CACHE_DEPTH: Cell<usize> = const { Cell::new(0) };
+
+    /// The first problem found since the last `Expr::try_noise` call began; see `ExprError`.
+    static BUILD_ERROR: RefCell<Option<ExprError>> = const { RefCell::new(None) };
+}
+
+/// A cache which may be shared by several consumers of one branch.
+struct SharedCache<const DIM: usize>(Rc<Cache<Box<dyn NoiseFn<f64, DIM>>>>);
+
+impl<const DIM: usize> NoiseFn<f64, DIM> for SharedCache<DIM> {
+    fn get(&self, point: [f64; DIM]) -> f64 {
+        self.0.get(point)
+    }
+}
+
+/// Clears the shared caches once the outermost noise function has been built so that caches are
+/// never shared between separately built noise functions.
+struct SharedCacheScope;
+
+impl SharedCacheScope {
+    fn enter() -> Self {
+        SHARED_CACHE_DEPTH.set(SHARED_CACHE_DEPTH.get() + 1);
+
+        Self
+    }
+}
+
+impl Drop for SharedCacheScope {
+    fn drop(&mut self) {
+        let depth = SHARED_CACHE_DEPTH.get() - 1;
+        SHARED_CACHE_DEPTH.set(depth);
+
+        if depth == 0 {
+            SHARED_CACHES.with_borrow_mut(HashMap::clear);
+        }
+    }
+}
+
+/// An instruction of a [`Program`]. Operands are the indices of values, or of points, written by
+/// earlier instructions.
+enum Instruction {
+    Abs(usize),
+    Add(usize, usize),
+
+    /// Interpolates from the lower to the upper value by the control value.
+    Blend([usize; 3]),
+    Clamp(usize, f64, f64),
+    Constant(f64),
+    Exponent(usize, f64),
+
+    /// Samples fBm of Perlin noise at a point, four points at a time.
+    Fbm(Box<FbmX4>, usize),
+    Max(usize, usize),
+    Min(usize, usize),
+    Multiply(usize, usize),
+    Negate(usize),
+
+    /// Samples a branch which is not flattened at a point.
+    Noise(Box<dyn NoiseFn<f64, 3>>, usize),
+    OpenSimplex(OpenSimplex, usize),
+
+    /// Samples Perlin noise at a point, four points at a time.
+    Perlin(PerlinX4, usize),
+    PerlinSurflet(PerlinSurflet, usize),
+    Power(usize, usize),
+    ScaleBias(usize, f64, f64),
+
+    /// Writes a point scaled by each of the X, Y and Z scales.
+    ScalePoint(usize, [f64; 3]),
+    Simplex(Simplex, usize),
+    SuperSimplex(SuperSimplex, usize),
+
+    /// Writes a point offset by each of the X, Y and Z translations.
+    TranslatePoint(usize, [f64; 3]),
+    Value(Value, usize),
+}
+
+/// An expression flattened into a list of instructions which are run in order over a batch of
+/// points, which avoids the virtual call made by each node of a boxed noise function.
+///
+/// Branches which have no instruction of their own, such as most fractals, are sampled through
+/// their boxed noise functions. Perlin noise and fBm of it are sampled four points at a time.
+struct Program {
+    instructions: Vec<Instruction>,
+    output: usize,
+
+    /// The number of values and points written by the instructions.
+    counts: [usize; 2],
+
+    /// The values and points of the batch being sampled, one per point in each register, which are
+    /// kept so that sampling does not allocate once the largest batch has been sampled.
+    registers: RefCell<(Vec<f64>, Vec<[f64; 3]>)>,
+}
+
+impl Program {
+    fn new(expr: &Expr) -> Self {
+        // Branches which are not flattened share caches, as they would within one noise function
+        let _scope = SharedCacheScope::enter();
+
+        let mut program = Self {
+            instructions: vec![],
+            output: 0,
+            counts: [0, 1],
+            registers: Default::default(),
+        };
+        program.output = program.flatten(expr, 0, &mut HashMap::new());
+        program.registers = RefCell::new((
+            Vec::with_capacity(program.counts[0]),
+            Vec::with_capacity(program.counts[1]),
+        ));
+
+        program
+    }
+
+    /// Appends the instructions which sample an expression at a point, returning the value which
+    /// holds the sample. Identical cached branches sampled at the same point share one value.
+    fn flatten<'a>(
+        &mut self,
+        expr: &'a Expr,
+        point: usize,
+        caches: &mut HashMap<(&'a Expr, usize), usize>,
+    ) -> usize {
+        let instruction = match expr {
+            Expr::Abs(source) => Instruction::Abs(self.flatten(source, point, caches)),
+            Expr::Add([source1, source2]) => Instruction::Add(
+                self.flatten(source1, point, caches),
+                self.flatten(source2, point, caches),
+            ),
+            Expr::Blend(expr) => Instruction::Blend([
+                self.flatten(&expr.sources[0], point, caches),
+                self.flatten(&expr.sources[1], point, caches),
+                self.flatten(&expr.control, point, caches),
+            ]),
+            Expr::Cache(source) => {
+                // Equal expressions are the same function, as in `Expr::cache`
+                let key = (&**source, point);
+
+                if let Some(&value) = caches.get(&key) {
+                    return value;
+                }
+
+                let value = self.flatten(source, point, caches);
+                caches.insert(key, value);
+
+                return value;
+            }
+            Expr::Clamp(expr) => Instruction::Clamp(
+                self.flatten(&expr.source, point, caches),
+                expr.lower_bound.value().min(expr.upper_bound.value()),
+                expr.lower_bound.value().max(expr.upper_bound.value()),
+            ),
+            Expr::Constant(value) => Instruction::Constant(value.value()),
+            Expr::Difference([source1, source2]) => {
+                let source1 = self.flatten(source1, point, caches);
+                let source2 = self.flatten(source2, point, caches);
+                let negated = self.push(Instruction::Negate(source2));
+                let sum = self.push(Instruction::Add(source1, negated));
+
+                Instruction::Abs(sum)
+            }
+            Expr::Exponent(expr) => Instruction::Exponent(
+                self.flatten(&expr.source, point, caches),
+                expr.exponent.value(),
+            ),
+            Expr::Fbm(expr) if expr.source_ty == SourceType::Perlin && expr.weights.is_empty() => {
+                Instruction::Fbm(
+                    Box::new(FbmX4::new(
+                        expr.seed.value(),
+                        expr.octaves.value().clamp(1, MAX_FRACTAL_OCTAVES) as _,
+                        expr.frequency.value(),
+                        expr.lacunarity.value(),
+                        expr.persistence.value(),
+                    )),
+                    point,
+                )
+            }
+            Expr::Max([source1, source2]) => Instruction::Max(
+                self.flatten(source1, point, caches),
+                self.flatten(source2, point, caches),
+            ),
+            Expr::Min([source1, source2]) => Instruction::Min(
+                self.flatten(source1, point, caches),
+                self.flatten(source2, point, caches),
+            ),
+            Expr::Mix(expr) => {
+                let lower = self.flatten(&expr.sources[0], point, caches);
+                let upper = self.flatten(&expr.sources[1], point, caches);
+                let control = self.push(Instruction::Constant(expr.factor()));
+
+                Instruction::Blend([lower, upper, control])
+            }
+            Expr::Multiply([source1, source2]) => Instruction::Multiply(
+                self.flatten(source1, point, caches),
+                self.flatten(source2, point, caches),
+            ),
+            Expr::Negate(source) => Instruction::Negate(self.flatten(source, point, caches)),
+            Expr::OpenSimplex(seed) => {
+                Instruction::OpenSimplex(OpenSimplex::new(seed.value()), point)
+            }
+            Expr::Perlin(seed) => Instruction::Perlin(PerlinX4::new(seed.value()), point),
+            Expr::PerlinSurflet(seed) => {
+                Instruction::PerlinSurflet(PerlinSurflet::new(seed.value()), point)
+            }
+            Expr::Power([source1, source2]) => Instruction::Power(
+                self.flatten(source1, point, caches),
+                self.flatten(source2, point, caches),
+            ),
+            Expr::ScaleBias(expr) => Instruction::ScaleBias(
+                self.flatten(&expr.source, point, caches),
+                expr.scale.value(),
+                expr.bias.value(),
+            ),
+            Expr::ScalePoint(expr) => {
+                let scales = array::from_fn(|axis| expr.axes[axis].value());
+                let point = self.push(Instruction::ScalePoint(point, scales));
+
+                return self.flatten(&expr.source, point, caches);
+            }
+            Expr::Simplex(seed) => Instruction::Simplex(Simplex::new(seed.value()), point),
+            Expr::SuperSimplex(seed) => {
+                Instruction::SuperSimplex(SuperSimplex::new(seed.value()), point)
+            }
+            Expr::Switch(switch) => match switch.source() {
+                Some(source) => return self.flatten(source, point, caches),
+                None => Instruction::Noise(expr.noise(), point),
+            },
+            Expr::TranslatePoint(expr) => {
+                let translations = array::from_fn(|axis| expr.axes[axis].value());
+                let point = self.push(Instruction::TranslatePoint(point, translations));
+
+                return self.flatten(&expr.source, point, caches);
+            }
+            Expr::Value(seed) => Instruction::Value(Value::new(seed.value()), point),
+            _ => Instruction::Noise(expr.noise(), point),
+        };
+
+        self.push(instruction)
+    }
+
+    /// Appends an instruction, returning the value or point it writes.
+    fn push(&mut self, instruction: Instruction) -> usize {
+        let count = match instruction {
+            Instruction::ScalePoint(..) | Instruction::TranslatePoint(..) => &mut self.counts[1],
+            _ => &mut self.counts[0],
+        };
+        let idx = *count;
+        *count += 1;
+
+        self.instructions.push(instruction);
+
+        idx
+    }
+}
+
+impl Program {
+    /// Samples the program at each point, running each instruction over every point before the
+    /// next so that the arithmetic of an instruction is one tight loop which may be vectorized.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is not exactly one sample for each point.
+    fn get_batch(&self, input: &[[f64; 3]], samples: &mut [f64]) {
+        assert_eq!(input.len(), samples.len(), "one sample is needed per point");
+
+        let lanes = input.len();
+        let mut registers = self.registers.borrow_mut();
+        let (values, points) = &mut *registers;
+
+        values.clear();
+        points.clear();
+        points.extend_from_slice(input);
+
+        // Each instruction matches the arithmetic of the noise function it replaces exactly
+        for instruction in &self.instructions {
+            match *instruction {
+                Instruction::ScalePoint(point, [x, y, z]) => {
+                    Self::write(points, lanes, |points, output| {
+                        for (output, &[px, py, pz]) in
+                            output.iter_mut().zip(&points[point * lanes..])
+                        {
+                            *output = [px * x, py * y, pz * z];
+                        }
+                    });
+
+                    continue;
+                }
+                Instruction::TranslatePoint(point, [x, y, z]) => {
+                    Self::write(points, lanes, |points, output| {
+                        for (output, &[px, py, pz]) in
+                            output.iter_mut().zip(&points[point * lanes..])
+                        {
+                            *output = [px + x, py + y, pz + z];
+                        }
+                    });
+
+                    continue;
+                }
+                _ => (),
+            }
+
+            Self::write(values, lanes, |values, output| {
+                let value = |idx: usize| &values[idx * lanes..][..lanes];
+                let point = |idx: usize| &points[idx * lanes..][..lanes];
+
+                match *instruction {
+                    Instruction::Abs(source) => Self::map(output, value(source), f64::abs),
+                    Instruction::Add(source1, source2) => {
+                        Self::map2(output, value(source1), value(source2), |lhs, rhs| lhs + rhs)
+                    }
+                    Instruction::Blend([lower, upper, control]) => {
+                        let sources = value(lower).iter().zip(value(upper)).zip(value(control));
+
+                        for (output, ((&lower, &upper), &control)) in output.iter_mut().zip(sources)
+                        {
+                            *output = control.mul_add(upper - lower, lower);
+                        }
+                    }
+                    Instruction::Clamp(source, lower, upper) => {
+                        Self::map(output, value(source), |value| value.clamp(lower, upper))
+                    }
+                    Instruction::Constant(constant) => output.fill(constant),
+                    Instruction::Exponent(source, exponent) => {
+                        Self::map(output, value(source), |value| {
+                            ((value + 1.0) / 2.0)
+                                .abs()
+                                .powf(exponent)
+                                .abs()
+                                .mul_add(2.0, -1.0)
+                        })
+                    }
+                    Instruction::Fbm(ref noise, idx) => noise.get_batch(point(idx), output),
+                    Instruction::Max(source1, source2) => {
+                        Self::map2(output, value(source1), value(source2), f64::max)
+                    }
+                    Instruction::Min(source1, source2) => {
+                        Self::map2(output, value(source1), value(source2), f64::min)
+                    }
+                    Instruction::Multiply(source1, source2) => {
+                        Self::map2(output, value(source1), value(source2), |lhs, rhs| lhs * rhs)
+                    }
+                    Instruction::Negate(source) => Self::map(output, value(source), |value| -value),
+                    Instruction::Noise(ref noise, idx) => Self::sample(output, point(idx), noise),
+                    Instruction::OpenSimplex(ref noise, idx) => {
+                        Self::sample(output, point(idx), noise)
+                    }
+                    Instruction::Perlin(ref noise, idx) => noise.get_batch(point(idx), output),
+                    Instruction::PerlinSurflet(ref noise, idx) => {
+                        Self::sample(output, point(idx), noise)
+                    }
+                    Instruction::Power(source1, source2) => {
+                        Self::map2(output, value(source1), value(source2), f64::powf)
+                    }
+                    Instruction::ScaleBias(source, scale, bias) => {
+                        Self::map(output, value(source), |value| value.mul_add(scale, bias))
+                    }
+                    Instruction::Simplex(ref noise, idx) => Self::sample(output, point(idx), noise),
+                    Instruction::SuperSimplex(ref noise, idx) => {
+                        Self::sample(output, point(idx), noise)
+                    }
+                    Instruction::Value(ref noise, idx) => Self::sample(output, point(idx), noise),
+                    Instruction::ScalePoint(..) | Instruction::TranslatePoint(..) => {
+                        unreachable!("points are written above")
+                    }
+                }
+            });
+        }
+
+        samples.copy_from_slice(&values[self.output * lanes..][..lanes]);
+    }
+
+    fn map(output: &mut [f64], source: &[f64], f: impl Fn(f64) -> f64) {
+        for (output, &value) in output.iter_mut().zip(source) {
+            *output = f(value);
+        }
+    }
+
+    fn map2(output: &mut [f64], source1: &[f64], source2: &[f64], f: impl Fn(f64, f64) -> f64) {
+        for (output, (&lhs, &rhs)) in output.iter_mut().zip(source1.iter().zip(source2)) {
+            *output = f(lhs, rhs);
+        }
+    }
+
+    fn sample<N>(output: &mut [f64], points: &[[f64; 3]], noise: &N)
+    where
+        N: NoiseFn<f64, 3> + ?Sized,
+    {
+        for (output, &point) in output.iter_mut().zip(points) {
+            *output = noise.get(point);
+        }
+    }
+
+    /// Appends a register holding one item per lane, written by `f` from the earlier registers.
+    fn write<T>(registers: &mut Vec<T>, lanes: usize, f: impl FnOnce(&[T], &mut [T]))
+    where
+        T: Copy + Default,
+    {
+        let len = registers.len();
+        registers.resize(len + lanes, T::default());
+
+        let (inputs, output) = registers.split_at_mut(len);
+        f(inputs, output);
+    }
+}
+
+impl NoiseFn<f64, 3> for Program {
+    fn get(&self, point: [f64; 3]) -> f64 {
+        let mut sample = [0.0];
+        self.get_batch(&[point], &mut sample);
+
+        sample[0]
+    }
+}
+
+/// Schlick's bias and gain curves applied to the source after mapping it from -1..1 to 0..1. See
+/// "Fast Alternatives to Perlin's Bias and Gain Functions" in Graphics Gems IV.
+struct BiasGain<const DIM: usize> {
+    source: Box<dyn NoiseFn<f64, DIM>>,
+    coefficients: [f64; 2],
+}
+
+impl<const DIM: usize> BiasGain<DIM> {
+    fn bias(value: f64, coefficient: f64) -> f64 {
+        value / (coefficient * (1.0 - value) + 1.0)
+    }
+}
+
+impl<const DIM: usize> NoiseFn<f64, DIM> for BiasGain<DIM> {
+    fn get(&self, point: [f64; DIM]) -> f64 {
+        let [bias, gain] = self.coefficients;
+        let value = Self::bias((self.source.get(point) * 0.5 + 0.5).clamp(0.0, 1.0), bias);
+
+        // Each half of the gain curve is a bias curve scaled into -1..0 or 0..1
+        if value < 0.5 {
+            Self::bias(value * 2.0, gain) - 1.0
+        } else {
+            1.0 - Self::bias(2.0 - value * 2.0, gain)
+        }
+    }
+}
+
+struct CombineRgb<const DIM: usize> {
+    channels: [Box<dyn NoiseFn<f64, DIM>>; 3],
+}
+
+impl<const DIM: usize> NoiseFn<f64, DIM> for CombineRgb<DIM> {
+    fn get(&self, point: [f64; DIM]) -> f64 {
+        CombineRgbExpr::luma(self.channels.each_ref().map(|channel| channel.get(point)))
+    }
+}
+
+/// A hard mask which is one where the sources compare as requested and zero elsewhere.
+///
+/// The sources must differ by more than the tolerance to be greater or less than each other.
+struct Compare<const DIM: usize> {
+    sources: [Box<dyn NoiseFn<f64, DIM>>; 2],
+    compare_ty: CompareType,
+    tolerance: f64,
+}
+
+impl<const DIM: usize> NoiseFn<f64, DIM> for Compare<DIM> {
+    fn get(&self, point: [f64; DIM]) -> f64 {
+        let (lhs, rhs) = (self.sources[0].get(point), self.sources[1].get(point));
+        let res = match self.compare_ty {
+            CompareType::Equal => (lhs - rhs).abs() <= self.tolerance,
+            CompareType::Greater => lhs > rhs + self.tolerance,
+            CompareType::Less => lhs < rhs - self.tolerance,
+        };
+
+        res as u8 as f64
+    }
+}
+
+/// Expands (or compresses, below one) the distance of the source from the pivot.
+struct Contrast<const DIM: usize> {
+    source: Box<dyn NoiseFn<f64, DIM>>,
+    contrast: f64,
+    pivot: f64,
+    clamped: bool,
+}
+
+impl<const DIM: usize> NoiseFn<f64, DIM> for Contrast<DIM> {
+    fn get(&self, point: [f64; DIM]) -> f64 {
+        let value = (self.source.get(point) - self.pivot) * self.contrast + self.pivot;
+
+        if self.clamped {
+            value.clamp(-1.0, 1.0)
+        } else {
+            value
+        }
+    }
+}
+
+/// Bowl-shaped craters with raised rims scattered across the XY plane and added to the source;
+/// other axes are ignored.
+///
+/// Each unit cell holds at most one crater, placed and sized by hashing the cell, so overlapping
+/// craters add together.
+struct Crater<const DIM: usize> {
+    source: Box<dyn NoiseFn<f64, DIM>>,
+    hasher: PermutationTable,
+    density: f64,
+    radius: f64,
+    depth: f64,
+}
+
+impl<const DIM: usize> Crater<DIM> {
+    /// The height of the rim above the source, relative to the depth of the bowl.
+    const RIM_HEIGHT: f64 = 0.2;
+
+    /// Returns the height of a crater at `distance` from its center, in radii of the crater.
+    fn profile(distance: f64) -> f64 {
+        let bowl = (distance * distance - 1.0).min(0.0);
+        let rim = Self::RIM_HEIGHT * (-((distance - 1.0) * 4.0).powi(2)).exp();
+
+        bowl + rim
+    }
+
+    /// Returns a value in the open range 0..1 for the given cell and purpose.
+    fn random(&self, cell: [isize; 2], idx: isize) -> f64 {
+        (self.hasher.hash(&[cell[0], cell[1], idx]) as f64 + 0.5) / 256.0
+    }
+}
+
+impl<const DIM: usize> NoiseFn<f64, DIM> for Crater<DIM> {
+    fn get(&self, point: [f64; DIM]) -> f64 {
+        let [x, y] = [point[0], point[1]];
+        let (cell_x, cell_y) = (x.floor() as isize, y.floor() as isize);
+        let mut height = 0.0;
+
+        // Rims reach twice the radius from the center, which is at most one cell
+        for neighbour_y in cell_y - 2..=cell_y + 2 {
+            for neighbour_x in cell_x - 2..=cell_x + 2 {
+                let cell = [neighbour_x, neighbour_y];
+
+                if self.random(cell, 0) >= self.density {
+                    continue;
+                }
+
+                let radius = self.radius * (0.5 + 0.5 * self.random(cell, 3));
+                if radius <= 0.0 {
+                    continue;
+                }
+
+                let center_x = neighbour_x as f64 + self.random(cell, 1);
+                let center_y = neighbour_y as f64 + self.random(cell, 2);
+                height += Self::profile((x - center_x).hypot(y - center_y) / radius);
+            }
+        }
+
+        self.source.get(point) + height * self.depth
+    }
+}
+
+/// Samples the source at points moved along each axis by the warp, which is itself sampled at a
+/// different offset for each axis so that the axes move independently.
+struct DomainWarp<const DIM: usize> {
+    source: Box<dyn NoiseFn<f64, DIM>>,
+    warp: Box<dyn NoiseFn<f64, DIM>>,
+    amplitude: f64,
+    frequency: f64,
+}
+
+impl<const DIM: usize> NoiseFn<f64, DIM> for DomainWarp<DIM> {
+    fn get(&self, point: [f64; DIM]) -> f64 {
+        let mut warped = point;
+
+        for (coord, offset) in warped.iter_mut().zip(DomainWarpExpr::AXIS_OFFSETS) {
+            let warp_point = point.map(|coord| coord * self.frequency + offset);
+            *coord += self.warp.get(warp_point) * self.amplitude;
+        }
+
+        self.source.get(warped)
+    }
+}
+
+/// Returns the one coordinate it is sampled at, so that a modifier may be sampled at any value.
+struct InputValue;
+
+impl NoiseFn<f64, 1> for InputValue {
+    fn get(&self, [input]: [f64; 1]) -> f64 {
+        input
+    }
+}
+
+/// Eases the source after mapping it from -1..1 onto 0..1, then maps the result back.
+struct Ease<const DIM: usize> {
+    source: Box<dyn NoiseFn<f64, DIM>>,
+    curve: EasingCurve,
+}
+
+impl<const DIM: usize> NoiseFn<f64, DIM> for Ease<DIM> {
+    fn get(&self, point: [f64; DIM]) -> f64 {
+        let t = (self.source.get(point) + 1.0) / 2.0;
+
+        self.curve.ease(t) * 2.0 - 1.0
+    }
+}
+
+/// Fractal noise whose octaves are damped by the slope accumulated from the octaves before them,
+/// which approximates hydraulic erosion: steep areas stay smooth while flat areas gain detail.
+///
+/// See "Improved fbm using derivatives" by Inigo Quilez.
+struct Erosion<T> {
+    sources: Vec<T>,
+    frequency: f64,
+    lacunarity: f64,
+    persistence: f64,
+    strength: f64,
+}
+
+impl<T, const DIM: usize> NoiseFn<f64, DIM> for Erosion<T>
+where
+    T: NoiseFn<f64, DIM>,
+{
+    fn get(&self, point: [f64; DIM]) -> f64 {
+        let mut point = point.map(|coord| coord * self.frequency);
+        let mut amplitude = 1.0;
+        let mut slope = [0.0; 2];
+        let (mut res, mut total) = (0.0, 0.0);
+
+        for source in &self.sources {
+            let [dx, dy] = Slope::gradient(source, point, Self::EPSILON);
+            slope[0] += dx;
+            slope[1] += dy;
+
+            let damping = 1.0 + self.strength * (slope[0] * slope[0] + slope[1] * slope[1]);
+            res += amplitude * source.get(point) / damping;
+            total += amplitude;
+
+            amplitude *= self.persistence;
+            point = point.map(|coord| coord * self.lacunarity);
+        }
+
+        res / total
+    }
+}
+
+impl<T> Erosion<T> {
+    /// The distance between the samples used to estimate the slope of each octave.
+    const EPSILON: f64 = 0.01;
+}
+
+/// Terrain made by the fault formation algorithm: the XY plane is repeatedly split along random
+/// lines passing near the origin, raising one side and lowering the other; other axes are ignored.
+///
+/// Earlier faults move the terrain further than later faults, so the result has large plateaus
+/// broken up by smaller steps.
+struct FaultLines {
+    /// The unit normal and distance from the origin of each fault.
+    faults: Vec<([f64; 2], f64)>,
+    amplitude: f64,
+}
+
+impl FaultLines {
+    fn new(seed: u32, iterations: u32, amplitude: f64) -> Self {
+        // SplitMix64, which gives well distributed values even for consecutive seeds
+        let mut state = seed as u64;
+        let mut random = || {
+            state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+            let mut value = state;
+            value = (value ^ (value >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+            value = (value ^ (value >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+
+            (value ^ (value >> 31)) as f64 / u64::MAX as f64
+        };
+
+        Self {
+            faults: (0..iterations)
+                .map(|_| {
+                    let (sin, cos) = (random() * TAU).sin_cos();
+
+                    ([cos, sin], random() * 2.0 - 1.0)
+                })
+                .collect(),
+            amplitude,
+        }
+    }
+
+    fn get_xy(&self, x: f64, y: f64) -> f64 {
+        let (mut res, mut total) = (0.0, 0.0);
+
+        for (idx, &([normal_x, normal_y], distance)) in self.faults.iter().enumerate() {
+            let weight = 1.0 - idx as f64 / self.faults.len() as f64;
+            let side = if x * normal_x + y * normal_y < distance {
+                -1.0
+            } else {
+                1.0
+            };
+
+            res += side * weight;
+            total += weight;
+        }
+
+        if total > 0.0 {
+            res / total * self.amplitude
+        } else {
+            0.0
+        }
+    }
+}
+
+impl NoiseFn<f64, 3> for FaultLines {
+    fn get(&self, [x, y, _]: [f64; 3]) -> f64 {
+        self.get_xy(x, y)
+    }
+}
+
+impl NoiseFn<f64, 4> for FaultLines {
+    fn get(&self, [x, y, ..]: [f64; 4]) -> f64 {
+        self.get_xy(x, y)
+    }
+}
+
+/// Evaluates the operations of a formula at the sampled point.
+struct Formula<const DIM: usize> {
+    op: FormulaOp,
+    sources: Vec<Box<dyn NoiseFn<f64, DIM>>>,
+    variables: Vec<f64>,
+}
+
+impl<const DIM: usize> Formula<DIM> {
+    fn eval(&self, op: &FormulaOp, point: [f64; DIM]) -> f64 {
+        match op {
+            FormulaOp::Add(ops) => self.eval(&ops[0], point) + self.eval(&ops[1], point),
+            FormulaOp::Call(func, ops) => {
+                if ops.len() != func.arity() {
+                    return 0.0;
+                }
+
+                let mut args = [0.0; FormulaFn::MAX_ARITY];
+
+                for (arg, op) in args.iter_mut().zip(ops) {
+                    *arg = self.eval(op, point);
+                }
+
+                func.eval(&args[..ops.len()])
+            }
+            &FormulaOp::Constant(value) => value,
+            &FormulaOp::Coord(axis) => point.get(axis).copied().unwrap_or_default(),
+            FormulaOp::Divide(ops) => {
+                let rhs = self.eval(&ops[1], point);
+
+                if rhs != 0.0 {
+                    self.eval(&ops[0], point) / rhs
+                } else {
+                    0.0
+                }
+            }
+            FormulaOp::Multiply(ops) => self.eval(&ops[0], point) * self.eval(&ops[1], point),
+            FormulaOp::Negate(op) => -self.eval(op, point),
+            FormulaOp::Power(ops) => self.eval(&ops[0], point).powf(self.eval(&ops[1], point)),
+            FormulaOp::Remainder(ops) => {
+                let rhs = self.eval(&ops[1], point);
+
+                if rhs != 0.0 {
+                    self.eval(&ops[0], point).rem_euclid(rhs)
+                } else {
+                    0.0
+                }
+            }
+            &FormulaOp::Source(idx) => self
+                .sources
+                .get(idx)
+                .map(|source| source.get(point))
+                .unwrap_or_default(),
+            FormulaOp::Subtract(ops) => self.eval(&ops[0], point) - self.eval(&ops[1], point),
+            &FormulaOp::Variable(idx) => self.variables.get(idx).copied().unwrap_or_default(),
+        }
+    }
+}
+
+impl<const DIM: usize> NoiseFn<f64, DIM> for Formula<DIM> {
+    fn get(&self, point: [f64; DIM]) -> f64 {
+        let value = self.eval(&self.op, point);
+
+        // Formulas such as `sqrt(x)` are undefined for parts of the plane
+        if value.is_finite() {
+            value
+        } else {
+            0.0
+        }
+    }
+}
+
+/// A grayscale image placed with its first pixel at the origin of the XY plane and sampled
+/// bilinearly; other axes are ignored.
+struct GenericFractal<const DIM: usize> {
+    source: Box<dyn NoiseFn<f64, DIM>>,
+    octaves: u32,
+    frequency: f64,
+    lacunarity: f64,
+    persistence: f64,
+}
+
+impl<const DIM: usize> GenericFractal<DIM> {
+    /// Moves each octave away from the last so that features of the source which sit at the origin
+    /// do not line up across every octave.
+    const OCTAVE_OFFSET: f64 = 0.618_034;
+}
+
+impl<const DIM: usize> NoiseFn<f64, DIM> for GenericFractal<DIM> {
+    fn get(&self, point: [f64; DIM]) -> f64 {
+        let mut frequency = self.frequency;
+        let mut amplitude = 1.0;
+        let (mut res, mut total) = (0.0, 0.0);
+
+        for octave in 0..self.octaves {
+            let offset = octave as f64 * Self::OCTAVE_OFFSET;
+            res += amplitude
+                * self
+                    .source
+                    .get(point.map(|coord| coord * frequency + offset));
+            total += amplitude;
+
+            amplitude *= self.persistence;
+            frequency *= self.lacunarity;
+        }
+
+        if total > 0.0 {
+            res / total
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Fractals whose octaves are warped and damped by the slopes of the octaves before them, after the
+/// Swiss and Jordan turbulence of Giliam de Carpentier; slopes are measured along X and Y.
+///
+/// Results are divided by the total weight so they stay within `-1..=1` whatever the weights are.
+struct DampedFractal<T> {
+    damping_ty: DampingType,
+    sources: Vec<T>,
+    frequency: f64,
+    lacunarity: f64,
+    weights: Vec<f64>,
+}
+
+impl<T> DampedFractal<T> {
+    /// The distance between the samples used to estimate the slope of each octave.
+    const EPSILON: f64 = 0.01;
+
+    /// How far the slopes of earlier Jordan octaves move the first and later octaves.
+    const JORDAN_WARP: [f64; 2] = [0.4, 0.35];
+
+    /// How strongly the slopes of earlier Jordan octaves damp later octaves.
+    const JORDAN_DAMP: [f64; 2] = [1.0, 0.8];
+
+    /// How far the slopes of earlier Swiss octaves move later octaves.
+    const SWISS_WARP: f64 = 0.15;
+}
+
+impl<T, const DIM: usize> NoiseFn<f64, DIM> for DampedFractal<T>
+where
+    T: NoiseFn<f64, DIM>,
+{
+    fn get(&self, point: [f64; DIM]) -> f64 {
+        let point = point.map(|coord| coord * self.frequency);
+        let mut scale = 1.0;
+        let mut damping = 1.0;
+        let (mut warp, mut slope) = ([0.0; 2], [0.0; 2]);
+        let (mut res, mut total) = (0.0, 0.0);
+
+        for (octave, (source, &weight)) in self.sources.iter().zip(&self.weights).enumerate() {
+            let mut octave_point = point;
+            match self.damping_ty {
+                // Jordan moves the scaled point
+                DampingType::Jordan => {
+                    octave_point = octave_point.map(|coord| coord * scale);
+                    octave_point[0] += warp[0];
+                    octave_point[1] += warp[1];
+                }
+                // Swiss moves the point before it is scaled
+                DampingType::Swiss => {
+                    octave_point[0] += Self::SWISS_WARP * warp[0];
+                    octave_point[1] += Self::SWISS_WARP * warp[1];
+                    octave_point = octave_point.map(|coord| coord * scale);
+                }
+            }
+
+            let signal = source.get(octave_point);
+            let [dx, dy] = Slope::gradient(source, octave_point, Self::EPSILON);
+            let amplitude = weight * damping;
+
+            match self.damping_ty {
+                DampingType::Jordan => {
+                    res += amplitude * signal * signal;
+
+                    // The slopes of the squared signal
+                    let (dx, dy) = (signal * dx, signal * dy);
+                    let idx = (octave > 0) as usize;
+                    warp[0] += Self::JORDAN_WARP[idx] * dx;
+                    warp[1] += Self::JORDAN_WARP[idx] * dy;
+                    slope[0] += Self::JORDAN_DAMP[idx] * dx;
+                    slope[1] += Self::JORDAN_DAMP[idx] * dy;
+
+                    // Flat areas get less detail than steep ones, from the third octave on
+                    if octave > 0 {
+                        let steepness = slope[0] * slope[0] + slope[1] * slope[1];
+                        damping = steepness / (1.0 + steepness);
+                    }
+                }
+                DampingType::Swiss => {
+                    res += amplitude * (1.0 - signal.abs());
+
+                    // The slopes of the ridges point away from their crests
+                    warp[0] -= amplitude * signal * dx;
+                    warp[1] -= amplitude * signal * dy;
+
+                    // Valleys get less detail than ridges
+                    damping *= res.clamp(0.0, 1.0);
+                }
+            }
+
+            total += amplitude.abs();
+            scale *= self.lacunarity;
+        }
+
+        if total > 0.0 {
+            res / total * 2.0 - 1.0
+        } else {
+            0.0
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum DampingType {
+    Jordan,
+    Swiss,
+}
+
+/// A noise-rs fractal, or the same fractal with custom octave weights.
+enum Fractal<F, T> {
+    Persistence(F),
+    Weights(WeightedFractal<T>),
+}
+
+impl<F, T, const DIM: usize> NoiseFn<f64, DIM> for Fractal<F, T>
+where
+    F: NoiseFn<f64, DIM>,
+    T: NoiseFn<f64, DIM>,
+{
+    fn get(&self, point: [f64; DIM]) -> f64 {
+        match self {
+            Self::Persistence(fractal) => fractal.get(point),
+            Self::Weights(fractal) => fractal.get(point),
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum FractalType {
+    BasicMulti,
+    Billow,
+    Fbm,
+    HybridMulti,
+}
+
+/// Matches the noise-rs fractals except that the amplitude of each octave is given by a weight
+/// instead of the persistence.
+///
+/// fBm and billow results are divided by the total weight so they stay within `-1..=1` whatever
+/// the weights are.
+struct WeightedFractal<T> {
+    fractal_ty: FractalType,
+    sources: Vec<T>,
+    frequency: f64,
+    lacunarity: f64,
+    weights: Vec<f64>,
+}
+
+impl<T, const DIM: usize> NoiseFn<f64, DIM> for WeightedFractal<T>
+where
+    T: NoiseFn<f64, DIM>,
+{
+    fn get(&self, point: [f64; DIM]) -> f64 {
+        let mut point = point.map(|coord| coord * self.frequency);
+        let (mut res, mut total, mut weight) = (0.0, 0.0, 1.0);
+
+        for (octave, (source, amplitude)) in self.sources.iter().zip(&self.weights).enumerate() {
+            let signal = source.get(point);
+
+            match self.fractal_ty {
+                FractalType::BasicMulti if octave == 0 => res = signal * amplitude,
+                FractalType::BasicMulti => res += signal * amplitude * res,
+                FractalType::Billow => res += (signal.abs() * 2.0 - 1.0) * amplitude,
+                FractalType::Fbm => res += signal * amplitude,
+                FractalType::HybridMulti if octave == 0 => {
+                    res = signal * amplitude;
+                    weight = res;
+                }
+                FractalType::HybridMulti => {
+                    let signal = signal * amplitude;
+                    weight = weight.max(1.0);
+                    res += weight * signal;
+                    weight *= signal;
+                }
+            }
+
+            total += amplitude.abs();
+            point = point.map(|coord| coord * self.lacunarity);
+        }
+
+        match self.fractal_ty {
+            FractalType::BasicMulti => res * 0.5,
+            FractalType::HybridMulti => res * 3.0,
+            FractalType::Billow | FractalType::Fbm if total > 0.0 => res / total,
+            FractalType::Billow | FractalType::Fbm => 0.0,
+        }
+    }
+}
+
+struct ImageSource {
+    width: usize,
+    height: usize,
+
+    /// The pixels of the image, row by row, mapped onto -1..1.
+    samples: Vec<f64>,
+
+    /// The number of pixels per unit of the XY plane.
+    density: f64,
+    tiling: ImageTiling,
+}
+
+impl ImageSource {
+    /// Returns the sample of the pixel at the given column and row, which are tiled onto the
+    /// image first.
+    fn sample(&self, col: isize, row: isize) -> f64 {
+        let (col, row) = match self.tiling {
+            ImageTiling::Clamp => (
+                col.clamp(0, self.width as isize - 1),
+                row.clamp(0, self.height as isize - 1),
+            ),
+            ImageTiling::Mirror => (
+                Self::mirror(col, self.width as isize),
+                Self::mirror(row, self.height as isize),
+            ),
+            ImageTiling::Repeat => (
+                col.rem_euclid(self.width as isize),
+                row.rem_euclid(self.height as isize),
+            ),
+        };
+
+        self.samples[row as usize * self.width + col as usize]
+    }
+
+    fn mirror(idx: isize, len: isize) -> isize {
+        let idx = idx.rem_euclid(2 * len);
+
+        if idx < len {
+            idx
+        } else {
+            2 * len - 1 - idx
+        }
+    }
+
+    fn get_xy(&self, x: f64, y: f64) -> f64 {
+        if self.samples.is_empty() {
+            return 0.0;
+        }
+
+        // Pixel centers are half a pixel in from the corner of each pixel
+        let (u, v) = (x * self.density - 0.5, y * self.density - 0.5);
+        let (col, row) = (u.floor(), v.floor());
+        let (tx, ty) = (u - col, v - row);
+        let (col, row) = (col as isize, row as isize);
+
+        let top = self.sample(col, row) * (1.0 - tx) + self.sample(col + 1, row) * tx;
+        let bottom = self.sample(col, row + 1) * (1.0 - tx) + self.sample(col + 1, row + 1) * tx;
+
+        top * (1.0 - ty) + bottom * ty
+    }
+}
+
+impl NoiseFn<f64, 3> for ImageSource {
+    fn get(&self, [x, y, _]: [f64; 3]) -> f64 {
+        self.get_xy(x, y)
+    }
+}
+
+impl NoiseFn<f64, 4> for ImageSource {
+    fn get(&self, [x, y, ..]: [f64; 4]) -> f64 {
+        self.get_xy(x, y)
+    }
+}
+
+/// Samples the source with the selected coordinates replaced by their absolute values, so the
+/// negative half of each selected axis reflects the positive half.
+struct MirrorPoint<const DIM: usize> {
+    source: Box<dyn NoiseFn<f64, DIM>>,
+    axes: [bool; 4],
+}
+
+impl<const DIM: usize> NoiseFn<f64, DIM> for MirrorPoint<DIM> {
+    fn get(&self, mut point: [f64; DIM]) -> f64 {
+        for (coord, &mirrored) in point.iter_mut().zip(&self.axes) {
+            if mirrored {
+                *coord = coord.abs();
+            }
+        }
+
+        self.source.get(point)
+    }
+}
+
+/// One component of the normal of the surface made by treating the source as a height field over
+/// the XY plane.
+struct Normal<const DIM: usize> {
+    source: Box<dyn NoiseFn<f64, DIM>>,
+    component: NormalComponent,
+    epsilon: f64,
+    strength: f64,
+}
+
+impl<const DIM: usize> NoiseFn<f64, DIM> for Normal<DIM> {
+    fn get(&self, point: [f64; DIM]) -> f64 {
+        let [dx, dy] = if self.epsilon > 0.0 {
+            Slope::gradient(&*self.source, point, self.epsilon)
+        } else {
+            [0.0; 2]
+        };
+        let normal = [-dx * self.strength, -dy * self.strength, 1.0];
+        let length = normal.iter().map(|coord| coord * coord).sum::<f64>().sqrt();
+
+        normal[self.component as usize] / length
+    }
+}
+
+/// Samples the source with the X, Y and Z coordinates multiplied by a rotation matrix; any other
+/// coordinates are unchanged.
+struct RotateAxis<const DIM: usize> {
+    source: Box<dyn NoiseFn<f64, DIM>>,
+    matrix: [[f64; 3]; 3],
+}
+
+impl<const DIM: usize> NoiseFn<f64, DIM> for RotateAxis<DIM> {
+    fn get(&self, mut point: [f64; DIM]) -> f64 {
+        let unrotated = point;
+
+        for (coord, row) in point.iter_mut().zip(&self.matrix) {
+            *coord = row.iter().zip(&unrotated).map(|(m, coord)| m * coord).sum();
+        }
+
+        self.source.get(point)
+    }
+}
+
+/// Evaluates a compiled Rhai script at the sampled point.
+#[cfg(feature = "script")]
+struct Script<const DIM: usize> {
+    engine: rhai::Engine,
+    ast: Rc<rhai::AST>,
+
+    /// Holds the variables, followed by the coordinates and sources of the current sample.
+    scope: RefCell<rhai::Scope<'static>>,
+    variable_count: usize,
+
+    sources: Vec<Box<dyn NoiseFn<f64, DIM>>>,
+}
+
+#[cfg(feature = "script")]
+impl<const DIM: usize> NoiseFn<f64, DIM> for Script<DIM> {
+    fn get(&self, point: [f64; DIM]) -> f64 {
+        let mut scope = self.scope.borrow_mut();
+        scope.rewind(self.variable_count);
+
+        for (idx, name) in ["x", "y", "z", "w"].into_iter().enumerate() {
+            scope.push_constant(name, point.get(idx).copied().unwrap_or_default());
+        }
+
+        let sources = self
+            .sources
+            .iter()
+            .map(|source| rhai::Dynamic::from(source.get(point)))
+            .collect::<rhai::Array>();
+        scope.push_constant(
+            "source",
+            sources
+                .first()
+                .and_then(|source| source.as_float().ok())
+                .unwrap_or_default(),
+        );
+        scope.push_constant("sources", sources);
+
+        // Scripts which fail, or which return something other than a number, are zero
+        self.engine
+            .eval_ast_with_scope::<rhai::Dynamic>(&mut scope, &self.ast)
+            .ok()
+            .and_then(|value| {
+                value
+                    .as_float()
+                    .ok()
+                    .or_else(|| value.as_int().ok().map(|value| value as f64))
+            })
+            .filter(|value| value.is_finite())
+            .unwrap_or_default()
+    }
+}
+
+/// The magnitude of the gradient of the source across the XY plane, estimated by central
+/// differences; flat areas are zero and steep areas are large.
+struct Slope<const DIM: usize> {
+    source: Box<dyn NoiseFn<f64, DIM>>,
+    epsilon: f64,
+}
+
+impl<const DIM: usize> Slope<DIM> {
+    /// Returns the partial derivatives of the source along the X and Y axes, estimated by central
+    /// differences; `epsilon` must be positive.
+    fn gradient(source: &dyn NoiseFn<f64, DIM>, point: [f64; DIM], epsilon: f64) -> [f64; 2] {
+        [0, 1].map(|axis| {
+            let (mut lower, mut upper) = (point, point);
+            lower[axis] -= epsilon;
+            upper[axis] += epsilon;
+
+            (source.get(upper) - source.get(lower)) / (2.0 * epsilon)
+        })
+    }
+}
+
+impl<const DIM: usize> NoiseFn<f64, DIM> for Slope<DIM> {
+    fn get(&self, point: [f64; DIM]) -> f64 {
+        if self.epsilon <= 0.0 {
+            return 0.0;
+        }
+
+        let [dx, dy] = Self::gradient(&*self.source, point, self.epsilon);
+
+        dx.hypot(dy)
+    }
+}
+
+/// Samples the source at a point built from the components of the sampled point, which may be
+/// reordered, repeated or zeroed.
+struct Swizzle<const DIM: usize> {
+    source: Box<dyn NoiseFn<f64, DIM>>,
+    components: [SwizzleComponent; 4],
+}
+
+impl<const DIM: usize> NoiseFn<f64, DIM> for Swizzle<DIM> {
+    fn get(&self, mut point: [f64; DIM]) -> f64 {
+        let unswizzled = point;
+
+        for (coord, &component) in point.iter_mut().zip(&self.components) {
+            *coord = match component {
+                SwizzleComponent::Zero => 0.0,
+                component => unswizzled
+                    .get(component as usize)
+                    .copied()
+                    .unwrap_or_default(),
+            };
+        }
+
+        self.source.get(point)
+    }
+}
+
+/// Samples the source with each tiled coordinate wrapped into `0..period`, which repeats the
+/// source along that axis.
+///
+/// When blended, the samples on either side of each seam are mixed by their distance across the
+/// period so that the result is continuous even if the source is not; this lowers the contrast in
+/// the middle of each tile.
+struct TilePoint<const DIM: usize> {
+    source: Box<dyn NoiseFn<f64, DIM>>,
+
+    /// The period of each of the X, Y, Z and W axes; axes with a period of zero or less are not
+    /// tiled.
+    periods: [f64; 4],
+    blended: bool,
+}
+
+impl<const DIM: usize> NoiseFn<f64, DIM> for TilePoint<DIM> {
+    fn get(&self, mut point: [f64; DIM]) -> f64 {
+        let mut tiled = 0usize;
+
+        for (axis, (coord, &period)) in point.iter_mut().zip(&self.periods).enumerate() {
+            if period > 0.0 {
+                *coord = coord.rem_euclid(period);
+                tiled |= 1 << axis;
+            }
+        }
+
+        if !self.blended || tiled == 0 {
+            return self.source.get(point);
+        }
+
+        // Each corner picks, for every tiled axis, either the wrapped coordinate or the same
+        // coordinate one period back; corners which move an axis that is not tiled are skipped
+        (0..1usize << DIM.min(4))
+            .filter(|corner| corner & !tiled == 0)
+            .map(|corner| {
+                let mut sample = point;
+                let mut weight = 1.0;
+
+                for (axis, coord) in sample.iter_mut().enumerate().take(4) {
+                    if tiled & 1 << axis == 0 {
+                        continue;
+                    }
+
+                    let period = self.periods[axis];
+                    let t = *coord / period;
+
+                    if corner & 1 << axis == 0 {
+                        weight *= 1.0 - t;
+                    } else {
+                        *coord -= period;
+                        weight *= t;
+                    }
+                }
+
+                weight * self.source.get(sample)
+            })
+            .sum()
+    }
+}
+
+/// The terrace modifier of noise-rs with a choice of step shape; see [`TerraceEasing`].
+///
+/// The control points are sorted and distinct, and there are at least two of them.
+struct EasedTerrace<const DIM: usize> {
+    source: Box<dyn NoiseFn<f64, DIM>>,
+    control_points: Vec<f64>,
+    inverted: bool,
+    easing: TerraceEasing,
+}
+
+impl<const DIM: usize> NoiseFn<f64, DIM> for EasedTerrace<DIM> {
+    fn get(&self, point: [f64; DIM]) -> f64 {
+        let value = self.source.get(point);
+        let last = self.control_points.len() - 1;
+        let idx = self
+            .control_points
+            .iter()
+            .position(|&control_point| control_point >= value)
+            .unwrap_or(last + 1);
+
+        // Values outside of the control points are clamped to the nearest one
+        if idx == 0 || idx > last {
+            return self.control_points[idx.min(last)];
+        }
+
+        let (mut input0, mut input1) = (self.control_points[idx - 1], self.control_points[idx]);
+        let mut alpha = (value - input0) / (input1 - input0);
+
+        if self.inverted {
+            alpha = 1.0 - alpha;
+            (input0, input1) = (input1, input0);
+        }
+
+        input0 + self.easing.ease(alpha) * (input1 - input0)
+    }
+}
+
+/// Fractal noise sampled at a point which is itself displaced by the same fractal noise, repeatedly:
+/// two iterations give the classic `fbm(p + fbm(p + fbm(p)))` marbled and swirling shapes.
+///
+/// Each iteration samples the fractal once per axis, shifted so the axes move independently. See
+/// "Domain Warping" by Inigo Quilez.
+struct WarpedFbm<T> {
+    sources: Vec<T>,
+    frequency: f64,
+    lacunarity: f64,
+    persistence: f64,
+    strength: f64,
+    iterations: u32,
+}
+
+impl<T> WarpedFbm<T> {
+    /// Added to every coordinate of the point sampled for each axis of the displacement.
+    const AXIS_OFFSETS: [f64; 4] = [0.0, 5.2, 1.7, 8.3];
+
+    fn fbm<const DIM: usize>(&self, point: [f64; DIM]) -> f64
+    where
+        T: NoiseFn<f64, DIM>,
+    {
+        let mut point = point.map(|coord| coord * self.frequency);
+        let mut amplitude = 1.0;
+        let (mut res, mut total) = (0.0, 0.0);
+
+        for source in &self.sources {
+            res += amplitude * source.get(point);
+            total += amplitude;
+
+            amplitude *= self.persistence;
+            point = point.map(|coord| coord * self.lacunarity);
+        }
+
+        res / total
+    }
+}
+
+impl<T, const DIM: usize> NoiseFn<f64, DIM> for WarpedFbm<T>
+where
+    T: NoiseFn<f64, DIM>,
+{
+    fn get(&self, point: [f64; DIM]) -> f64 {
+        let mut warp = [0.0; DIM];
+
+        for _ in 0..self.iterations {
+            let warped: [f64; DIM] =
+                array::from_fn(|axis| point[axis] + self.strength * warp[axis]);
+            warp = array::from_fn(|axis| {
+                self.fbm(warped.map(|coord| coord + Self::AXIS_OFFSETS[axis]))
+            });
+        }
+
+        self.fbm(array::from_fn(|axis| {
+            point[axis] + self.strength * warp[axis]
+        }))
+    }
+}
+
+/// Blue noise made from the R2 low-discrepancy sequence, holding one value per unit cell of the XY
+/// plane; other axes are ignored.
+///
+/// Neighbouring cells are as different from each other as possible, which makes the result
+/// suitable for dithering masks and scatter maps. See "The Unreasonable Effectiveness of
+/// Quasirandom Sequences" by Martin Roberts.
+pub struct BlueNoise {
+    offset: f64,
+}
+
+impl BlueNoise {
+    /// The reciprocals of the plastic number and of its square.
+    pub const ALPHA: [f64; 2] = [0.754_877_666_246_692_7, 0.569_840_290_998_053_2];
+
+    pub fn new(seed: u32) -> Self {
+        Self {
+            offset: Self::offset(seed),
+        }
+    }
+
+    /// The value of the sequence at the origin for the given seed.
+    pub fn offset(seed: u32) -> f64 {
+        // The fractional parts of multiples of the golden ratio spread consecutive seeds evenly
+        (seed as f64 * 0.618_033_988_749_894_9).fract()
+    }
+
+    fn get_xy(&self, x: f64, y: f64) -> f64 {
+        let value =
+            (self.offset + x.floor() * Self::ALPHA[0] + y.floor() * Self::ALPHA[1]).rem_euclid(1.0);
+
+        // A triangle wave removes the seam where the sequence wraps from one back to zero
+        1.0 - 4.0 * (value - 0.5).abs()
+    }
+}
+
+impl NoiseFn<f64, 3> for BlueNoise {
+    fn get(&self, [x, y, _]: [f64; 3]) -> f64 {
+        self.get_xy(x, y)
+    }
+}
+
+impl NoiseFn<f64, 4> for BlueNoise {
+    fn get(&self, [x, y, ..]: [f64; 4]) -> f64 {
+        self.get_xy(x, y)
+    }
+}
+
+/// A ramp across the XY plane; other axes are ignored so that it reads the same at any depth.
+///
+/// Linear gradients run from -1 to 1 across `radius` either side of the origin along `direction`
+/// while radial gradients fall from 1 at the origin to -1 at `radius` from it.
+struct Gradient {
+    gradient_ty: GradientType,
+    direction: [f64; 2],
+    radius: f64,
+}
+
+impl Gradient {
+    fn get_xy(&self, x: f64, y: f64) -> f64 {
+        if self.radius <= 0.0 {
+            return 0.0;
+        }
+
+        match self.gradient_ty {
+            GradientType::Linear => {
+                let distance = x * self.direction[0] + y * self.direction[1];
+
+                (distance / self.radius).clamp(-1.0, 1.0)
+            }
+            GradientType::Radial => {
+                let distance = x.hypot(y);
+
+                1.0 - 2.0 * (distance / self.radius).min(1.0)
+            }
+        }
+    }
+}
+
+impl NoiseFn<f64, 3> for Gradient {
+    fn get(&self, [x, y, _]: [f64; 3]) -> f64 {
+        self.get_xy(x, y)
+    }
+}
+
+impl NoiseFn<f64, 4> for Gradient {
+    fn get(&self, [x, y, ..]: [f64; 4]) -> f64 {
+        self.get_xy(x, y)
+    }
+}
+
+/// Worley noise which also finds the second closest feature point, searching every neighbouring
+/// cell; the `Worley` noise function only finds the closest and may miss it near cell corners.
+struct NeighbourWorley<const DIM: usize> {
+    hasher: PermutationTable,
+    frequency: f64,
+    distance_fn: fn(&[f64], &[f64]) -> f64,
+    return_ty: ReturnType,
+}
+
+impl<const DIM: usize> NoiseFn<f64, DIM> for NeighbourWorley<DIM> {
+    fn get(&self, point: [f64; DIM]) -> f64 {
+        let point = point.map(|coord| coord * self.frequency);
+        let cell = point.map(|coord| coord.floor() as isize);
+        let (mut distance1, mut distance2) = (f64::MAX, f64::MAX);
+        let mut closest_key = [0; 5];
+
+        for neighbour in 0..3usize.pow(DIM as _) {
+            let mut neighbour_cell = cell;
+            let mut offset = neighbour;
+
+            for coord in &mut neighbour_cell {
+                *coord += (offset % 3) as isize - 1;
+                offset /= 3;
+            }
+
+            // Each axis of the feature point is jittered by hashing the cell along with the axis
+            let mut key = [0; 5];
+            key[..DIM].copy_from_slice(&neighbour_cell);
+
+            let mut feature_point = [0.0; DIM];
+            for (axis, coord) in feature_point.iter_mut().enumerate() {
+                key[DIM] = axis as _;
+                *coord =
+                    neighbour_cell[axis] as f64 + self.hasher.hash(&key[..=DIM]) as f64 / 255.0;
+            }
+
+            let distance = (self.distance_fn)(&point, &feature_point);
+            if distance < distance1 {
+                distance2 = distance1;
+                distance1 = distance;
+                closest_key = key;
+            } else if distance < distance2 {
+                distance2 = distance;
+            }
+        }
+
+        let value = match self.return_ty {
+            ReturnType::CellId => {
+                // Two hashes give each cell one of 65536 values rather than the 256 of `Value`
+                closest_key[DIM] = DIM as _;
+                let high = self.hasher.hash(&closest_key[..=DIM]);
+                closest_key[DIM] += 1;
+                let low = self.hasher.hash(&closest_key[..=DIM]);
+
+                ((high << 8) | low) as f64 / 65535.0
+            }
+            ReturnType::Distance => distance1,
+            ReturnType::Distance2 => distance2,
+            ReturnType::Distance2Add => (distance1 + distance2) * 0.5,
+            ReturnType::Distance2Div => distance1 / distance2,
+            ReturnType::Distance2Mul => distance1 * distance2 * 0.5,
+            ReturnType::Distance2Sub => distance2 - distance1,
+            ReturnType::Value => unreachable!(),
+        };
+
+        value * 2.0 - 1.0
+    }
+}
+
+/// A three dimensional slice of a four dimensional noise function at a fixed W coordinate.
+struct SliceW {
+    source: Box<dyn NoiseFn<f64, 4>>,
+    w: f64,
+}
+
+impl NoiseFn<f64, 3> for SliceW {
+    fn get(&self, [x, y, z]: [f64; 3]) -> f64 {
+        self.source.get([x, y, z, self.w])
+    }
+}
+
+/// An expression along with a manifest of the named variables which may be replaced using
+/// `Expr::set_f64` and `Expr::set_u32`.
+#[allow(unused)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ExprBundle {
+    pub expr: Expr,
+    pub variables: Vec<NamedVariable>,
+}
+
+/// A problem with part of an expression which makes that part sample as zero; see
+/// [`Expr::try_noise`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum ExprError {
+    /// A curve has fewer than four control points with different inputs.
+    Curve,
+
+    /// An image source does not have one sample for each pixel.
+    ImageSize {
+        width: u32,
+        height: u32,
+        samples: usize,
+    },
+
+    /// A script does not compile, or scripts are not enabled.
+    Script(String),
+
+    /// A switch has no sources.
+    Switch,
+
+    /// A terrace has fewer than two control points with different values.
+    Terrace,
+}
+
+impl ExprError {
+    /// Records the first problem found while building a noise function for `Expr::try_noise`.
+    fn record(self) {
+        BUILD_ERROR.with_borrow_mut(|err| {
+            err.get_or_insert(self);
+        });
+    }
+}
+
+impl Display for ExprError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Curve => {
+                f.write_str("Curve needs at least 4 control points with different inputs")
+            }
+            Self::ImageSize {
+                width,
+                height,
+                samples,
+            } => write!(
+                f,
+                "Image source of {width}x{height} pixels has {samples} samples"
+            ),
+            Self::Script(message) => write!(f, "Script is not valid: {message}"),
+            Self::Switch => f.write_str("Switch needs at least 1 source"),
+            Self::Terrace => f.write_str("Terrace needs at least 2 different control points"),
+        }
+    }
+}
+
+impl Error for ExprError {}
+
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct ImageSourceExpr {
+    pub width: u32,
+    pub height: u32,
+
+    /// The grayscale pixels of the image, row by row, where zero is -1 and the largest value is 1.
+    pub samples: Vec<u16>,
+
+    /// The width of the image in units of the XY plane.
+    pub size: Variable<f64>,
+
+    pub tiling: ImageTiling,
+}
+
+impl ImageSourceExpr {
+    fn noise(&self) -> ImageSource {
+        let (width, height) = (self.width as usize, self.height as usize);
+        let samples = if width * height == self.samples.len() {
+            self.samples
+                .iter()
+                .map(|&sample| sample as f64 / u16::MAX as f64 * 2.0 - 1.0)
+                .collect()
+        } else {
+            ExprError::ImageSize {
+                width: self.width,
+                height: self.height,
+                samples: self.samples.len(),
+            }
+            .record();
+
+            vec![]
+        };
+        let size = self.size.value();
+
+        ImageSource {
+            width,
+            height,
+            samples,
+            density: if size > 0.0 { width as f64 / size } else { 0.0 },
+            tiling: self.tiling,
+        }
+    }
+}
+
+/// How an image is sampled outside of its bounds.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq, Serialize, Deserialize)]
+pub enum ImageTiling {
+    /// Repeats the pixels at the edges of the image.
+    #[default]
+    Clamp,
+
+    /// Repeats the image, flipping every other copy so that the edges meet.
+    Mirror,
+
+    /// Repeats the image.
+    Repeat,
+}
+
+impl ImageTiling {
+    pub const ALL: [Self; 3] = [Self::Clamp, Self::Mirror, Self::Repeat];
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct MirrorPointExpr {
+    pub source: Box<Expr>,
+
+    /// Whether each of the X, Y, Z and W coordinates is mirrored.
+    pub axes: [bool; 4],
+}
+
+impl MirrorPointExpr {
+    fn noise<const DIM: usize>(
+        &self,
+        noise: fn(&Expr) -> Box<dyn NoiseFn<f64, DIM>>,
+    ) -> MirrorPoint<DIM> {
+        MirrorPoint {
+            source: noise(&self.source),
+            axes: self.axes,
+        }
+    }
+
+    fn set_f64(&mut self, name: &str, value: f64) {
+        self.source.set_f64(name, value);
+    }
+
+    fn set_u32(&mut self, name: &str, value: u32) {
+        self.source.set_u32(name, value);
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct MixExpr {
+    pub sources: [Box<Expr>; 2],
+
+    pub factor: Variable<f64>,
+}
+
+impl MixExpr {
+    /// Returns the factor clamped to 0..1, where zero is the first source and one is the second.
+    pub fn factor(&self) -> f64 {
+        self.factor.value().clamp(0.0, 1.0)
+    }
+
+    fn set_f64(&mut self, name: &str, value: f64) {
+        self.sources.iter_mut().for_each(|expr| {
+            expr.set_f64(name, value);
+        });
+        self.factor.set_if_named(name, value);
+    }
+
+    fn set_u32(&mut self, name: &str, value: u32) {
+        self.sources.iter_mut().for_each(|expr| {
+            expr.set_u32(name, value);
+        });
+    }
+
+    fn variables(&self, used_by: &'static str, vars: &mut Variables) {
+        vars.f64(used_by, &self.factor);
+    }
+}
+
+#[allow(unused)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct NamedVariable {
+    pub name: String,
+
+    /// The type and current value of the variable.
+    pub value: VariableValue,
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, Serialize, Deserialize)]
+pub enum NormalComponent {
+    X,
+    Y,
+    Z,
+}
+
+impl NormalComponent {
+    pub const ALL: [Self; 3] = [Self::X, Self::Y, Self::Z];
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct NormalExpr {
+    pub source: Box<Expr>,
+
+    pub component: NormalComponent,
+
+    /// The distance between the samples used to estimate the gradient.
+    pub epsilon: Variable<f64>,
+
+    /// Scales the height of the source; larger values make the surface steeper.
+    pub strength: Variable<f64>,
+}
+
+impl NormalExpr {
+    fn noise<const DIM: usize>(
+        &self,
+        noise: fn(&Expr) -> Box<dyn NoiseFn<f64, DIM>>,
+    ) -> Normal<DIM> {
+        Normal {
+            source: noise(&self.source),
+            component: self.component,
+            epsilon: self.epsilon.value(),
+            strength: self.strength.value(),
+        }
+    }
+
+    fn set_f64(&mut self, name: &str, value: f64) {
+        self.source.set_f64(name, value);
+        self.epsilon.set_if_named(name, value);
+        self.strength.set_if_named(name, value);
+    }
+
+    fn set_u32(&mut self, name: &str, value: u32) {
+        self.source.set_u32(name, value);
+    }
+
+    fn variables(&self, used_by: &'static str, vars: &mut Variables) {
+        vars.f64(used_by, &self.epsilon);
+        vars.f64(used_by, &self.strength);
     }
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct NormalizeExpr {
+    pub source: Box<Expr>,
+
+    /// Maps the range found by sampling the source instead of the conventional -1..1 range.
+    pub estimated: bool,
+}
+
+impl NormalizeExpr {
+    /// The half-width of the cube of the source which is sampled to estimate its range.
+    const ESTIMATE_EXTENT: f64 = 8.0;
+
+    /// The number of samples along each axis of the estimate; odd so the samples do not line up
+    /// with the integer lattice of the generators.
+    const ESTIMATE_SAMPLES: usize = 17;
 
-    fn terrace(expr: &TerraceExpr) -> Box<dyn NoiseFn<f64, 3>> {
-        fn invalid_inputs(control_points: &[Variable<f64>]) -> bool {
-            debug_assert!(control_points.len() >= 2);
+    /// Returns the lowest and highest values of the source, either the conventional -1..1 range or
+    /// the range found by sampling a grid of points around the origin.
+    ///
+    /// The estimate is not a bound: other points of the source may fall outside of it.
+    pub fn range(&self) -> (f64, f64) {
+        if !self.estimated {
+            return (-1.0, 1.0);
+        }
+
+        let source = self.source.noise();
+        let step = 2.0 * Self::ESTIMATE_EXTENT / Self::ESTIMATE_SAMPLES as f64;
+        let coord = |idx: usize| (idx as f64 + 0.5) * step - Self::ESTIMATE_EXTENT;
+        let mut range = (f64::INFINITY, f64::NEG_INFINITY);
 
-            let first_input = OrderedFloat(control_points[0].value());
+        for x in 0..Self::ESTIMATE_SAMPLES {
+            for y in 0..Self::ESTIMATE_SAMPLES {
+                for z in 0..Self::ESTIMATE_SAMPLES {
+                    let value = source.get([coord(x), coord(y), coord(z)]);
 
-            for input_value in &control_points[1..] {
-                let input_value = OrderedFloat(input_value.value());
-                if input_value != first_input {
-                    return false;
+                    if value.is_finite() {
+                        range = (range.0.min(value), range.1.max(value));
+                    }
                 }
             }
-
-            true
         }
 
-        // Make sure the control points are valid (noise-rs panics!)
-        if expr.control_points.len() < 2 || invalid_inputs(&expr.control_points) {
-            return Box::new(Constant::new(0.0));
+        if range.0 > range.1 {
+            (-1.0, 1.0)
+        } else {
+            range
         }
+    }
 
-        let mut res = Terrace::new(expr.source.noise()).invert_terraces(expr.inverted);
+    /// Returns the scale and bias which map the range of the source onto 0..1.
+    pub fn scale_bias(&self) -> (f64, f64) {
+        let (min, max) = self.range();
 
-        for control_point in expr.control_points.iter() {
-            res = res.add_control_point(control_point.value());
-        }
+        // A constant source maps to the bottom of the output range
+        let scale = if max > min { 1.0 / (max - min) } else { 0.0 };
 
-        Box::new(res)
+        (scale, -min * scale)
+    }
+
+    fn set_f64(&mut self, name: &str, value: f64) {
+        self.source.set_f64(name, value);
+    }
+
+    fn set_u32(&mut self, name: &str, value: u32) {
+        self.source.set_u32(name, value);
     }
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, Serialize, Deserialize)]
 pub enum OpType {
     Add,
     Divide,
@@ -615,13 +4565,122 @@ pub enum OpType {
     Subtract,
 }
 
+/// A rectangle of the XY plane which is sampled on a regular grid by [`Expr::fill_plane`].
 #[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PlaneRegion {
+    /// The lowest X and Y coordinates of the rectangle.
+    pub origin: [f64; 2],
+
+    /// The width and height of the rectangle.
+    pub size: [f64; 2],
+
+    /// The number of columns and rows of samples.
+    pub resolution: [usize; 2],
+}
+
+impl PlaneRegion {
+    /// Samples the center of each cell of one row.
+    fn fill_row(&self, noise: &dyn NoiseFn<f64, 3>, row: usize, samples: &mut [f64]) {
+        let [columns, rows] = self.resolution;
+        let step_x = self.size[0] / columns as f64;
+        let y = self.origin[1] + (row as f64 + 0.5) * self.size[1] / rows as f64;
+
+        for (column, sample) in samples.iter_mut().enumerate() {
+            *sample = noise.get([self.origin[0] + (column as f64 + 0.5) * step_x, y, 0.0]);
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, Serialize, Deserialize)]
 pub enum ReturnType {
+    /// A random value for the cell of the closest feature point which is the same at every point
+    /// of the cell, found by the same search as the second distance types.
+    CellId,
+
+    /// The distance to the closest feature point.
     Distance,
+
+    /// The distance to the second closest feature point.
+    Distance2,
+
+    /// The mean of the distances to the closest two feature points.
+    Distance2Add,
+
+    /// The ratio of the distances to the closest two feature points.
+    Distance2Div,
+
+    /// Half the product of the distances to the closest two feature points.
+    Distance2Mul,
+
+    /// The difference of the distances to the closest two feature points, which is zero along the
+    /// cell edges.
+    Distance2Sub,
+
+    /// A random value for the cell of the closest feature point.
     Value,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+impl ReturnType {
+    pub const ALL: [Self; 8] = [
+        Self::CellId,
+        Self::Distance,
+        Self::Distance2,
+        Self::Distance2Add,
+        Self::Distance2Div,
+        Self::Distance2Mul,
+        Self::Distance2Sub,
+        Self::Value,
+    ];
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct RemapExpr {
+    pub source: Box<Expr>,
+
+    pub in_min: Variable<f64>,
+    pub in_max: Variable<f64>,
+    pub out_min: Variable<f64>,
+    pub out_max: Variable<f64>,
+    pub clamped: bool,
+}
+
+impl RemapExpr {
+    /// Returns the scale and bias which map the input range onto the output range.
+    pub fn scale_bias(&self) -> (f64, f64) {
+        let (in_min, in_max) = (self.in_min.value(), self.in_max.value());
+        let (out_min, out_max) = (self.out_min.value(), self.out_max.value());
+
+        // An empty input range maps everything to the start of the output range
+        let scale = if in_max != in_min {
+            (out_max - out_min) / (in_max - in_min)
+        } else {
+            0.0
+        };
+
+        (scale, out_min - in_min * scale)
+    }
+
+    fn set_f64(&mut self, name: &str, value: f64) {
+        self.source.set_f64(name, value);
+        self.in_min.set_if_named(name, value);
+        self.in_max.set_if_named(name, value);
+        self.out_min.set_if_named(name, value);
+        self.out_max.set_if_named(name, value);
+    }
+
+    fn set_u32(&mut self, name: &str, value: u32) {
+        self.source.set_u32(name, value);
+    }
+
+    fn variables(&self, used_by: &'static str, vars: &mut Variables) {
+        vars.f64(used_by, &self.in_min);
+        vars.f64(used_by, &self.in_max);
+        vars.f64(used_by, &self.out_min);
+        vars.f64(used_by, &self.out_max);
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
 pub struct RigidFractalExpr {
     pub source_ty: SourceType,
     pub seed: Variable<u32>,
@@ -644,9 +4703,80 @@ impl RigidFractalExpr {
         self.seed.set_if_named(name, value);
         self.octaves.set_if_named(name, value);
     }
+
+    fn variables(&self, used_by: &'static str, vars: &mut Variables) {
+        vars.f64(used_by, &self.frequency);
+        vars.f64(used_by, &self.lacunarity);
+        vars.f64(used_by, &self.persistence);
+        vars.f64(used_by, &self.attenuation);
+        vars.u32(used_by, &self.seed);
+        vars.u32(used_by, &self.octaves);
+    }
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct RotateAxisExpr {
+    pub source: Box<Expr>,
+
+    /// The X, Y and Z components of the axis of rotation, which need not be normalized.
+    pub axis: [Variable<f64>; 3],
+
+    /// The angle of rotation about the axis, in degrees.
+    pub angle: Variable<f64>,
+}
+
+impl RotateAxisExpr {
+    /// Returns the row-major matrix which rotates a point about the axis by the angle, or the
+    /// identity if the axis has no length.
+    pub fn matrix(&self) -> [[f64; 3]; 3] {
+        let [x, y, z] = self.axis.each_ref().map(|axis| axis.value());
+        let length = (x * x + y * y + z * z).sqrt();
+
+        if !length.is_normal() {
+            return [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+        }
+
+        // Rodrigues' rotation formula
+        let [x, y, z] = [x / length, y / length, z / length];
+        let (s, c) = self.angle.value().to_radians().sin_cos();
+        let t = 1.0 - c;
+
+        [
+            [t * x * x + c, t * x * y - s * z, t * x * z + s * y],
+            [t * x * y + s * z, t * y * y + c, t * y * z - s * x],
+            [t * x * z - s * y, t * y * z + s * x, t * z * z + c],
+        ]
+    }
+
+    fn noise<const DIM: usize>(
+        &self,
+        noise: fn(&Expr) -> Box<dyn NoiseFn<f64, DIM>>,
+    ) -> RotateAxis<DIM> {
+        RotateAxis {
+            source: noise(&self.source),
+            matrix: self.matrix(),
+        }
+    }
+
+    fn set_f64(&mut self, name: &str, value: f64) {
+        self.source.set_f64(name, value);
+        self.axis
+            .iter_mut()
+            .for_each(|axis| axis.set_if_named(name, value));
+        self.angle.set_if_named(name, value);
+    }
+
+    fn set_u32(&mut self, name: &str, value: u32) {
+        self.source.set_u32(name, value);
+    }
+
+    fn variables(&self, used_by: &'static str, vars: &mut Variables) {
+        self.axis.iter().for_each(|axis| vars.f64(used_by, axis));
+        vars.f64(used_by, &self.angle);
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
 pub struct ScaleBiasExpr {
     pub source: Box<Expr>,
 
@@ -664,9 +4794,142 @@ impl ScaleBiasExpr {
     fn set_u32(&mut self, name: &str, value: u32) {
         self.source.set_u32(name, value);
     }
+
+    fn variables(&self, used_by: &'static str, vars: &mut Variables) {
+        vars.f64(used_by, &self.scale);
+        vars.f64(used_by, &self.bias);
+    }
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct ScriptExpr {
+    /// A Rhai script evaluated for every sample with the constants `x`, `y`, `z`, `w`, `source`,
+    /// `sources` and each variable in scope; the value of the last statement is the result.
+    pub script: String,
+
+    pub sources: Vec<Expr>,
+    pub variables: Vec<Variable<f64>>,
+}
+
+impl ScriptExpr {
+    /// The most operations a script may run for each sample, so that endless loops do not hang.
+    #[cfg(feature = "script")]
+    const MAX_OPERATIONS: u64 = 100_000;
+
+    /// Returns the error message of a script which does not compile.
+    #[cfg(feature = "script")]
+    pub fn check(script: &str) -> Result<(), String> {
+        Self::engine()
+            .compile(script)
+            .map(|_| ())
+            .map_err(|err| err.to_string())
+    }
+
+    /// Scripts are only compiled when the `script` feature is enabled.
+    #[cfg(not(feature = "script"))]
+    pub fn check(_script: &str) -> Result<(), String> {
+        Err("Scripts require the `script` feature".to_owned())
+    }
+
+    #[cfg(feature = "script")]
+    fn engine() -> rhai::Engine {
+        let mut engine = rhai::Engine::new();
+        engine.set_max_operations(Self::MAX_OPERATIONS);
+
+        engine
+    }
+
+    #[cfg(feature = "script")]
+    fn noise<const DIM: usize>(
+        &self,
+        noise: fn(&Expr) -> Box<dyn NoiseFn<f64, DIM>>,
+    ) -> Box<dyn NoiseFn<f64, DIM>> {
+        thread_local! {
+            // Previews rebuild their noise functions often, so compiled scripts are kept
+            static ASTS: RefCell<HashMap<String, Rc<rhai::AST>>> = Default::default();
+        }
+
+        /// The most compiled scripts kept, which bounds memory while a script is being typed.
+        const MAX_ASTS: usize = 64;
+
+        let engine = Self::engine();
+        let ast = ASTS.with_borrow_mut(|asts| {
+            if let Some(ast) = asts.get(&self.script) {
+                return Some(Rc::clone(ast));
+            }
+
+            let ast = match engine.compile(&self.script) {
+                Ok(ast) => Rc::new(ast),
+                Err(err) => {
+                    ExprError::Script(err.to_string()).record();
+
+                    return None;
+                }
+            };
+
+            if asts.len() >= MAX_ASTS {
+                asts.clear();
+            }
+
+            asts.insert(self.script.clone(), Rc::clone(&ast));
+
+            Some(ast)
+        });
+
+        let Some(ast) = ast else {
+            return Box::new(Constant::new(0.0));
+        };
+
+        let mut scope = rhai::Scope::new();
+        for variable in &self.variables {
+            if let Variable::Named(name, _) = variable {
+                scope.push_constant(name.clone(), variable.value());
+            }
+        }
+
+        Box::new(Script {
+            engine,
+            ast,
+            variable_count: scope.len(),
+            scope: RefCell::new(scope),
+            sources: self.sources.iter().map(noise).collect(),
+        })
+    }
+
+    /// Scripts are only evaluated when the `script` feature is enabled.
+    #[cfg(not(feature = "script"))]
+    fn noise<const DIM: usize>(
+        &self,
+        _noise: fn(&Expr) -> Box<dyn NoiseFn<f64, DIM>>,
+    ) -> Box<dyn NoiseFn<f64, DIM>> {
+        ExprError::Script("Scripts require the `script` feature".to_owned()).record();
+
+        Box::new(Constant::new(0.0))
+    }
+
+    fn set_f64(&mut self, name: &str, value: f64) {
+        self.sources.iter_mut().for_each(|expr| {
+            expr.set_f64(name, value);
+        });
+        self.variables
+            .iter_mut()
+            .for_each(|variable| variable.set_if_named(name, value));
+    }
+
+    fn set_u32(&mut self, name: &str, value: u32) {
+        self.sources.iter_mut().for_each(|expr| {
+            expr.set_u32(name, value);
+        });
+    }
+
+    fn variables(&self, used_by: &'static str, vars: &mut Variables) {
+        self.variables
+            .iter()
+            .for_each(|variable| vars.f64(used_by, variable));
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
 pub struct SelectExpr {
     pub sources: [Box<Expr>; 2],
     pub control: Box<Expr>,
@@ -693,41 +4956,282 @@ impl SelectExpr {
         });
         self.control.set_u32(name, value);
     }
+
+    fn variables(&self, used_by: &'static str, vars: &mut Variables) {
+        vars.f64(used_by, &self.lower_bound);
+        vars.f64(used_by, &self.upper_bound);
+        vars.f64(used_by, &self.falloff);
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct SlopeExpr {
+    pub source: Box<Expr>,
+
+    /// The distance between the samples used to estimate the gradient.
+    pub epsilon: Variable<f64>,
+}
+
+impl SlopeExpr {
+    fn noise<const DIM: usize>(
+        &self,
+        noise: fn(&Expr) -> Box<dyn NoiseFn<f64, DIM>>,
+    ) -> Slope<DIM> {
+        Slope {
+            source: noise(&self.source),
+            epsilon: self.epsilon.value(),
+        }
+    }
+
+    fn set_f64(&mut self, name: &str, value: f64) {
+        self.source.set_f64(name, value);
+        self.epsilon.set_if_named(name, value);
+    }
+
+    fn set_u32(&mut self, name: &str, value: u32) {
+        self.source.set_u32(name, value);
+    }
+
+    fn variables(&self, used_by: &'static str, vars: &mut Variables) {
+        vars.f64(used_by, &self.epsilon);
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, Serialize, Deserialize)]
+pub enum SourceType {
+    OpenSimplex,
+    Perlin,
+    PerlinSurflet,
+    Simplex,
+    SuperSimplex,
+    Value,
+    Worley,
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct SwitchExpr {
+    pub sources: Vec<Expr>,
+
+    pub selector: Variable<u32>,
+}
+
+impl SwitchExpr {
+    /// Returns the source picked by the selector; selectors past the last source pick the last.
+    pub fn source(&self) -> Option<&Expr> {
+        let idx = (self.selector.value() as usize).min(self.sources.len().saturating_sub(1));
+
+        self.sources.get(idx)
+    }
+
+    fn set_f64(&mut self, name: &str, value: f64) {
+        self.sources.iter_mut().for_each(|expr| {
+            expr.set_f64(name, value);
+        });
+    }
+
+    fn set_u32(&mut self, name: &str, value: u32) {
+        self.sources.iter_mut().for_each(|expr| {
+            expr.set_u32(name, value);
+        });
+        self.selector.set_if_named(name, value);
+    }
+
+    fn variables(&self, used_by: &'static str, vars: &mut Variables) {
+        vars.u32(used_by, &self.selector);
+    }
+}
+
+/// A component of the sampled point, or zero.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, Serialize, Deserialize)]
+pub enum SwizzleComponent {
+    X,
+    Y,
+    Z,
+    W,
+    Zero,
+}
+
+impl SwizzleComponent {
+    pub const ALL: [Self; 5] = [Self::X, Self::Y, Self::Z, Self::W, Self::Zero];
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct SwizzleExpr {
+    pub source: Box<Expr>,
+
+    /// The component of the sampled point used for each of the X, Y, Z and W coordinates of the
+    /// source; components which are not sampled, such as W of 3D noise, are zero.
+    pub components: [SwizzleComponent; 4],
+}
+
+impl SwizzleExpr {
+    fn noise<const DIM: usize>(
+        &self,
+        noise: fn(&Expr) -> Box<dyn NoiseFn<f64, DIM>>,
+    ) -> Swizzle<DIM> {
+        Swizzle {
+            source: noise(&self.source),
+            components: self.components,
+        }
+    }
+
+    fn set_f64(&mut self, name: &str, value: f64) {
+        self.source.set_f64(name, value);
+    }
+
+    fn set_u32(&mut self, name: &str, value: u32) {
+        self.source.set_u32(name, value);
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct TerraceExpr {
+    pub source: Box<Expr>,
+
+    pub inverted: bool,
+    pub control_points: Vec<Variable<f64>>,
+
+    #[serde(default)]
+    pub easing: TerraceEasing,
+}
+
+impl TerraceExpr {
+    /// Returns `true` if there are at least two control points with different values, which
+    /// noise-rs requires of a terrace (it panics otherwise).
+    pub fn is_valid(&self) -> bool {
+        self.control_points.first().is_some_and(|first| {
+            let first = OrderedFloat(first.value());
+
+            self.control_points[1..]
+                .iter()
+                .any(|control_point| OrderedFloat(control_point.value()) != first)
+        })
+    }
+
+    /// Returns the output of the terrace for each of the given inputs, ignoring the source, or
+    /// `None` if the terrace is not valid.
+    pub fn sample(&self, inputs: impl IntoIterator<Item = f64>) -> Option<Vec<f64>> {
+        self.is_valid().then(|| {
+            let terrace = self.terrace(Box::new(InputValue));
+
+            inputs
+                .into_iter()
+                .map(|input| terrace.get([input]))
+                .collect()
+        })
+    }
+
+    fn terrace<const DIM: usize>(&self, source: Box<dyn NoiseFn<f64, DIM>>) -> EasedTerrace<DIM> {
+        let mut control_points = self
+            .control_points
+            .iter()
+            .map(|control_point| control_point.value())
+            .collect::<Vec<_>>();
+        control_points.sort_by(f64::total_cmp);
+        control_points.dedup_by(|a, b| (*a - *b).abs() < f64::EPSILON);
+
+        EasedTerrace {
+            source,
+            control_points,
+            inverted: self.inverted,
+            easing: self.easing,
+        }
+    }
+
+    fn set_f64(&mut self, name: &str, value: f64) {
+        self.source.set_f64(name, value);
+        self.control_points
+            .iter_mut()
+            .for_each(|control_point| control_point.set_if_named(name, value));
+    }
+
+    fn set_u32(&mut self, name: &str, value: u32) {
+        self.source.set_u32(name, value);
+    }
+
+    fn variables(&self, used_by: &'static str, vars: &mut Variables) {
+        self.control_points
+            .iter()
+            .for_each(|control_point| vars.f64(used_by, control_point));
+    }
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
-pub enum SourceType {
-    OpenSimplex,
-    Perlin,
-    PerlinSurflet,
-    Simplex,
-    SuperSimplex,
-    Value,
-    Worley,
+/// The shape of each step of a terrace, between one control point and the next.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq, Serialize, Deserialize)]
+pub enum TerraceEasing {
+    /// Rises in a straight line, which follows the source without forming shelves.
+    Linear,
+
+    /// Rises slowly from a flat shelf to a sharp edge.
+    #[default]
+    Quadratic,
+
+    /// Rises from a flat shelf and eases into the next one (smoothstep).
+    Smooth,
+
+    /// Like `Smooth` but with softer shelf edges, as the curvature is also continuous at each
+    /// control point (smootherstep).
+    Spline,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
-pub struct TerraceExpr {
+impl TerraceEasing {
+    pub const ALL: [Self; 4] = [Self::Linear, Self::Quadratic, Self::Smooth, Self::Spline];
+
+    /// Maps the position between two control points, from zero to one, onto the step.
+    fn ease(self, alpha: f64) -> f64 {
+        match self {
+            Self::Linear => alpha,
+            Self::Quadratic => alpha * alpha,
+            Self::Smooth => alpha * alpha * (3.0 - 2.0 * alpha),
+            Self::Spline => alpha * alpha * alpha * (alpha * (alpha * 6.0 - 15.0) + 10.0),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct TilePointExpr {
     pub source: Box<Expr>,
 
-    pub inverted: bool,
-    pub control_points: Vec<Variable<f64>>,
+    /// The period of each of the X, Y, Z and W axes; axes with a period of zero or less are not
+    /// tiled.
+    pub periods: [Variable<f64>; 4],
+
+    /// Mixes the samples across each seam instead of wrapping the coordinates alone, which tiles
+    /// sources that do not repeat by themselves without visible edges.
+    pub blended: bool,
 }
 
-impl TerraceExpr {
+impl TilePointExpr {
+    fn noise<const DIM: usize>(
+        &self,
+        noise: fn(&Expr) -> Box<dyn NoiseFn<f64, DIM>>,
+    ) -> TilePoint<DIM> {
+        TilePoint {
+            source: noise(&self.source),
+            periods: self.periods.each_ref().map(|period| period.value()),
+            blended: self.blended,
+        }
+    }
+
     fn set_f64(&mut self, name: &str, value: f64) {
         self.source.set_f64(name, value);
-        self.control_points
+        self.periods
             .iter_mut()
-            .for_each(|control_point| control_point.set_if_named(name, value));
+            .for_each(|period| period.set_if_named(name, value));
     }
 
     fn set_u32(&mut self, name: &str, value: u32) {
         self.source.set_u32(name, value);
     }
+
+    fn variables(&self, used_by: &'static str, vars: &mut Variables) {
+        self.periods
+            .iter()
+            .for_each(|period| vars.f64(used_by, period));
+    }
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
 pub struct TransformExpr {
     pub source: Box<Expr>,
 
@@ -745,9 +5249,13 @@ impl TransformExpr {
     fn set_u32(&mut self, name: &str, value: u32) {
         self.source.set_u32(name, value);
     }
+
+    fn variables(&self, used_by: &'static str, vars: &mut Variables) {
+        self.axes.iter().for_each(|axis| vars.f64(used_by, axis));
+    }
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
 pub struct TurbulenceExpr {
     pub source: Box<Expr>,
 
@@ -770,6 +5278,54 @@ impl TurbulenceExpr {
         self.seed.set_if_named(name, value);
         self.roughness.set_if_named(name, value);
     }
+
+    fn variables(&self, used_by: &'static str, vars: &mut Variables) {
+        vars.f64(used_by, &self.frequency);
+        vars.f64(used_by, &self.power);
+        vars.u32(used_by, &self.seed);
+        vars.u32(used_by, &self.roughness);
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct WarpedFbmExpr {
+    pub source_ty: SourceType,
+    pub seed: Variable<u32>,
+    pub octaves: Variable<u32>,
+    pub frequency: Variable<f64>,
+    pub lacunarity: Variable<f64>,
+    pub persistence: Variable<f64>,
+
+    /// How far the output of each iteration displaces the point sampled by the next.
+    pub strength: Variable<f64>,
+
+    /// How many times the point is displaced before the final sample; zero is plain fBm.
+    pub iterations: Variable<u32>,
+}
+
+impl WarpedFbmExpr {
+    fn set_f64(&mut self, name: &str, value: f64) {
+        self.frequency.set_if_named(name, value);
+        self.lacunarity.set_if_named(name, value);
+        self.persistence.set_if_named(name, value);
+        self.strength.set_if_named(name, value);
+    }
+
+    fn set_u32(&mut self, name: &str, value: u32) {
+        self.seed.set_if_named(name, value);
+        self.octaves.set_if_named(name, value);
+        self.iterations.set_if_named(name, value);
+    }
+
+    fn variables(&self, used_by: &'static str, vars: &mut Variables) {
+        vars.f64(used_by, &self.frequency);
+        vars.f64(used_by, &self.lacunarity);
+        vars.f64(used_by, &self.persistence);
+        vars.f64(used_by, &self.strength);
+        vars.u32(used_by, &self.seed);
+        vars.u32(used_by, &self.octaves);
+        vars.u32(used_by, &self.iterations);
+    }
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -784,6 +5340,23 @@ pub enum Variable<T> {
 }
 
 impl<T> Variable<T> {
+    /// Calls `f` with the name and value of this variable, or of each named variable an operation
+    /// is made of.
+    fn for_each_named(&self, f: &mut impl FnMut(&str, T))
+    where
+        T: Copy,
+    {
+        match self {
+            Self::Anonymous(_) => (),
+            Self::Named(name, value) => f(name, *value),
+            Self::Operation(vars, _) => {
+                for var in vars {
+                    var.for_each_named(f);
+                }
+            }
+        }
+    }
+
     fn set_if_named(&mut self, name: &str, value: T)
     where
         T: Copy,
@@ -801,10 +5374,83 @@ impl<T> Variable<T> {
             }
         }
     }
+
+    /// Compares variables by the keys of their values.
+    fn eq_by<K>(&self, other: &Self, key: fn(T) -> K) -> bool
+    where
+        T: Copy,
+        K: PartialEq,
+    {
+        match (self, other) {
+            (Self::Anonymous(lhs), Self::Anonymous(rhs)) => key(*lhs) == key(*rhs),
+            (Self::Named(lhs_name, lhs), Self::Named(rhs_name, rhs)) => {
+                lhs_name == rhs_name && key(*lhs) == key(*rhs)
+            }
+            (Self::Operation(lhs_vars, lhs_op), Self::Operation(rhs_vars, rhs_op)) => {
+                lhs_op == rhs_op
+                    && lhs_vars
+                        .iter()
+                        .zip(rhs_vars)
+                        .all(|(lhs, rhs)| lhs.eq_by(rhs, key))
+            }
+            _ => false,
+        }
+    }
+
+    /// Hashes a variable by the key of its value.
+    fn hash_by<K>(&self, state: &mut impl Hasher, key: fn(T) -> K)
+    where
+        T: Copy,
+        K: Hash,
+    {
+        discriminant(self).hash(state);
+
+        match self {
+            Self::Anonymous(value) => key(*value).hash(state),
+            Self::Named(name, value) => {
+                name.hash(state);
+                key(*value).hash(state);
+            }
+            Self::Operation(vars, op) => {
+                vars.iter().for_each(|var| var.hash_by(state, key));
+                op.hash(state);
+            }
+        }
+    }
+}
+
+// Floats are compared and hashed by their bits so that expressions may be used as map keys, which
+// tells apart expressions such as `1 / 0` and `1 / -0` that sample differently
+impl Eq for Variable<f64> {}
+
+impl Hash for Variable<f64> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.hash_by(state, f64::to_bits);
+    }
+}
+
+impl PartialEq for Variable<f64> {
+    fn eq(&self, other: &Self) -> bool {
+        self.eq_by(other, f64::to_bits)
+    }
+}
+
+impl Eq for Variable<u32> {}
+
+impl Hash for Variable<u32> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.hash_by(state, |value| value);
+    }
+}
+
+impl PartialEq for Variable<u32> {
+    fn eq(&self, other: &Self) -> bool {
+        self.eq_by(other, |value| value)
+    }
 }
 
 impl Variable<f64> {
-    fn value(&self) -> f64 {
+    pub(crate) fn value(&self) -> f64 {
         match self {
             Self::Anonymous(value) | Self::Named(_, value) => *value,
             Self::Operation(vars, op) => {
@@ -827,7 +5473,7 @@ impl Variable<f64> {
 }
 
 impl Variable<u32> {
-    fn value(&self) -> u32 {
+    pub(crate) fn value(&self) -> u32 {
         match self {
             Self::Anonymous(value) | Self::Named(_, value) => *value,
             Self::Operation(vars, op) => {
@@ -844,7 +5490,87 @@ impl Variable<u32> {
     }
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+/// A named variable of an expression along with the expressions which use it; see
+/// [`Expr::variables`].
+#[allow(unused)]
+#[derive(Clone, Debug, PartialEq)]
+pub struct VariableInfo {
+    pub name: String,
+
+    /// The type and current value of the variable.
+    pub value: VariableValue,
+
+    /// Each expression which uses the variable, in order of first use.
+    pub used_by: Vec<VariableUse>,
+}
+
+/// An expression which uses a named variable; see [`VariableInfo::used_by`].
+#[allow(unused)]
+#[derive(Clone, Debug, PartialEq)]
+pub struct VariableUse {
+    /// The kind of expression, such as `Fbm`.
+    pub kind: &'static str,
+
+    /// The index of each source taken from the root expression to reach the expression, which
+    /// may be resolved using [`Expr::source_at`].
+    pub path: Vec<usize>,
+}
+
+#[allow(unused)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum VariableValue {
+    F64(f64),
+    U32(u32),
+}
+
+/// The named variables found so far by `Expr::variables`; variables of different types are kept
+/// apart even when they share a name.
+#[derive(Default)]
+struct Variables {
+    infos: Vec<VariableInfo>,
+
+    /// The source path of the expression being visited.
+    path: Vec<usize>,
+}
+
+impl Variables {
+    fn f64(&mut self, used_by: &'static str, variable: &Variable<f64>) {
+        variable
+            .for_each_named(&mut |name, value| self.push(used_by, name, VariableValue::F64(value)));
+    }
+
+    fn u32(&mut self, used_by: &'static str, variable: &Variable<u32>) {
+        variable
+            .for_each_named(&mut |name, value| self.push(used_by, name, VariableValue::U32(value)));
+    }
+
+    fn push(&mut self, used_by: &'static str, name: &str, value: VariableValue) {
+        let idx = self
+            .infos
+            .iter()
+            .position(|info| info.name == name && discriminant(&info.value) == discriminant(&value))
+            .unwrap_or_else(|| {
+                self.infos.push(VariableInfo {
+                    name: name.to_owned(),
+                    value,
+                    used_by: vec![],
+                });
+
+                self.infos.len() - 1
+            });
+        let info = &mut self.infos[idx];
+        let used_by = VariableUse {
+            kind: used_by,
+            path: self.path.clone(),
+        };
+
+        if !info.used_by.contains(&used_by) {
+            info.used_by.push(used_by);
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
 pub struct WorleyExpr {
     pub seed: Variable<u32>,
     pub frequency: Variable<f64>,
@@ -860,4 +5586,469 @@ impl WorleyExpr {
     fn set_u32(&mut self, name: &str, value: u32) {
         self.seed.set_if_named(name, value);
     }
+
+    fn variables(&self, used_by: &'static str, vars: &mut Variables) {
+        vars.f64(used_by, &self.frequency);
+        vars.u32(used_by, &self.seed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn constant(value: f64) -> Box<Expr> {
+        Box::new(Expr::Constant(Variable::Anonymous(value)))
+    }
+
+    /// An expression with named variables, domain transforms and several sources.
+    fn graph() -> Expr {
+        let perlin = Expr::ScaleBias(ScaleBiasExpr {
+            source: Box::new(Expr::Perlin(Variable::Named("seed".to_owned(), 7))),
+            scale: Variable::Named("scale".to_owned(), 0.75),
+            bias: Variable::Anonymous(0.1),
+        });
+        let simplex = Expr::TranslatePoint(TransformExpr {
+            source: Box::new(Expr::Simplex(Variable::Anonymous(3))),
+            axes: [0.5, -1.25, 2.0, 0.0].map(Variable::Anonymous),
+        });
+
+        Expr::Clamp(ClampExpr {
+            source: Box::new(Expr::Blend(BlendExpr {
+                sources: [Box::new(perlin), Box::new(simplex)],
+                control: constant(0.25),
+            })),
+            lower_bound: Variable::Anonymous(-0.8),
+            upper_bound: Variable::Named("upper".to_owned(), 0.9),
+        })
+    }
+
+    /// Points spread over several cells of each axis, including negative ones, whose count is not
+    /// a multiple of four.
+    fn grid() -> Vec<[f64; 3]> {
+        (0..7)
+            .flat_map(|x| (0..5).flat_map(move |y| (0..3).map(move |z| (x, y, z))))
+            .map(|(x, y, z)| {
+                [
+                    x as f64 * 0.73 - 2.1,
+                    y as f64 * -1.37 + 0.4,
+                    z as f64 * 2.9 - 3.3,
+                ]
+            })
+            .collect()
+    }
+
+    fn assert_batch_matches(expr: &Expr, noise: &impl NoiseFn<f64, 3>) {
+        let points = grid();
+        let mut samples = vec![0.0; points.len()];
+        expr.compile().sample_batch(&points, &mut samples);
+
+        for (sample, point) in samples.into_iter().zip(points) {
+            assert_eq!(sample, noise.get(point));
+        }
+    }
+
+    /// Optimizes a copy of the expression, checking that it samples the same values over the grid.
+    fn optimized(expr: &Expr) -> Expr {
+        let mut optimized = expr.clone();
+        optimized.optimize();
+
+        let (expected, actual) = (expr.compile(), optimized.compile());
+
+        for point in grid() {
+            assert_eq!(expected.sample(point), actual.sample(point));
+        }
+
+        optimized
+    }
+
+    fn perlin(seed: u32) -> Box<Expr> {
+        Box::new(Expr::Perlin(Variable::Anonymous(seed)))
+    }
+
+    #[cfg(any(feature = "json", feature = "postcard"))]
+    fn assert_same_samples(expected: &Expr, actual: &Expr) {
+        let (expected, actual) = (expected.compile(), actual.compile());
+
+        for idx in 0..64 {
+            let point = [idx as f64 * 0.37 - 8.0, idx as f64 * 0.21, (idx % 5) as f64];
+
+            assert_eq!(expected.sample(point), actual.sample(point));
+        }
+    }
+
+    #[test]
+    fn clamp_set_upper_bound() {
+        let mut expr = Expr::Clamp(ClampExpr {
+            source: constant(0.9),
+            lower_bound: Variable::Anonymous(-1.0),
+            upper_bound: Variable::Named("upper".to_owned(), 1.0),
+        });
+
+        assert_eq!(expr.sample([0.0; 3]), 0.9);
+        assert_eq!(expr.set_f64("upper", 0.5).sample([0.0; 3]), 0.5);
+
+        let Expr::Clamp(clamp) = &expr else {
+            unreachable!();
+        };
+
+        assert_eq!(clamp.lower_bound.value(), -1.0);
+        assert_eq!(clamp.upper_bound.value(), 0.5);
+    }
+
+    #[test]
+    fn fbm_batch() {
+        let expr = Expr::Fbm(FractalExpr {
+            source_ty: SourceType::Perlin,
+            seed: Variable::Anonymous(11),
+            octaves: Variable::Anonymous(5),
+            frequency: Variable::Anonymous(1.3),
+            lacunarity: Variable::Anonymous(2.1),
+            persistence: Variable::Anonymous(0.45),
+            weights: vec![],
+        });
+        let noise = Fbm::<Perlin>::new(11)
+            .set_octaves(5)
+            .set_frequency(1.3)
+            .set_lacunarity(2.1)
+            .set_persistence(0.45);
+
+        assert_batch_matches(&expr, &noise);
+    }
+
+    #[test]
+    fn fill_plane() {
+        let expr = graph();
+        let region = PlaneRegion {
+            origin: [-1.5, 2.0],
+            size: [3.0, 1.0],
+            resolution: [5, 3],
+        };
+        let mut samples = vec![0.0; 15];
+        expr.fill_plane(region, &mut samples);
+
+        for (idx, sample) in samples.into_iter().enumerate() {
+            let (column, row) = (idx % 5, idx / 5);
+            let point = [
+                -1.5 + (column as f64 + 0.5) * (3.0 / 5.0),
+                2.0 + (row as f64 + 0.5) * 1.0 / 3.0,
+                0.0,
+            ];
+
+            assert_eq!(sample, expr.sample(point), "column {column}, row {row}");
+        }
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn json_round_trip() {
+        let expr = graph();
+        let mut read = Expr::from_json(&expr.to_json().unwrap()).unwrap();
+
+        assert_same_samples(&expr, &read);
+        assert_eq!(read.variables().len(), 3);
+        assert_same_samples(
+            graph().set_f64("scale", 0.5).set_u32("seed", 9),
+            read.set_f64("scale", 0.5).set_u32("seed", 9),
+        );
+    }
+
+    #[cfg(feature = "postcard")]
+    #[test]
+    fn postcard_round_trip() {
+        let expr = graph();
+        let mut read = Expr::from_bytes(&expr.to_bytes().unwrap()).unwrap();
+
+        assert_same_samples(&expr, &read);
+        assert_eq!(read.variables().len(), 3);
+        assert_same_samples(
+            graph().set_f64("scale", 0.5).set_u32("seed", 9),
+            read.set_f64("scale", 0.5).set_u32("seed", 9),
+        );
+    }
+
+    #[test]
+    fn optimize_caches_repeated_branches() {
+        let branch = Expr::ScaleBias(ScaleBiasExpr {
+            source: perlin(3),
+            scale: Variable::Anonymous(0.5),
+            bias: Variable::Anonymous(0.1),
+        });
+        let expr = Expr::Add([
+            Box::new(branch.clone()),
+            Box::new(Expr::Multiply([Box::new(branch.clone()), perlin(4)])),
+        ]);
+
+        let Expr::Add([source1, source2]) = optimized(&expr) else {
+            unreachable!();
+        };
+        let Expr::Multiply([source2, _]) = *source2 else {
+            unreachable!();
+        };
+
+        assert_eq!(*source1, Expr::Cache(Box::new(branch)));
+        assert_eq!(source1, source2);
+    }
+
+    #[test]
+    fn optimize_collapses_identical_sources() {
+        let control = constant(0.3);
+
+        for expr in [
+            Expr::Blend(BlendExpr {
+                sources: [perlin(5), perlin(5)],
+                control: control.clone(),
+            }),
+            Expr::Max([perlin(5), perlin(5)]),
+            Expr::Select(SelectExpr {
+                sources: [perlin(5), perlin(5)],
+                control,
+                lower_bound: Variable::Anonymous(0.0),
+                upper_bound: Variable::Anonymous(1.0),
+                falloff: Variable::Anonymous(0.1),
+            }),
+        ] {
+            assert_eq!(optimized(&expr), *perlin(5));
+        }
+
+        let expr = Expr::Max([perlin(5), perlin(6)]);
+
+        assert_eq!(optimized(&expr), expr);
+    }
+
+    #[test]
+    fn optimize_folds_constants() {
+        let expr = Expr::Clamp(ClampExpr {
+            source: Box::new(Expr::Add([
+                constant(0.25),
+                Box::new(Expr::Multiply([constant(0.5), constant(3.0)])),
+            ])),
+            lower_bound: Variable::Anonymous(-1.0),
+            upper_bound: Variable::Anonymous(1.0),
+        });
+
+        assert_eq!(optimized(&expr), *constant(1.0));
+
+        // Named values may still be changed, so neither they nor their consumers are folded
+        let expr = Expr::Add([
+            Box::new(Expr::Constant(Variable::Named("offset".to_owned(), 0.2))),
+            Box::new(Expr::Perlin(Variable::Named("seed".to_owned(), 1))),
+        ]);
+        let mut optimized = optimized(&expr);
+
+        assert_eq!(optimized, expr);
+        assert_eq!(
+            optimized
+                .set_u32("seed", 2)
+                .set_f64("offset", 0.4)
+                .sample([0.5; 3]),
+            0.4 + Perlin::new(2).get([0.5; 3])
+        );
+    }
+
+    #[test]
+    fn optimize_removes_identity_transforms() {
+        let expr = Expr::ScaleBias(ScaleBiasExpr {
+            source: perlin(7),
+            scale: Variable::Anonymous(1.0),
+            bias: Variable::Anonymous(0.0),
+        });
+
+        assert_eq!(optimized(&expr), *perlin(7));
+
+        let expr = Expr::TranslatePoint(TransformExpr {
+            source: perlin(7),
+            axes: [0.0; 4].map(Variable::Anonymous),
+        });
+
+        assert_eq!(optimized(&expr), *perlin(7));
+    }
+
+    #[test]
+    fn optimize_resolves_anonymous_switch() {
+        let sources = vec![*perlin(8), *perlin(9)];
+        let expr = Expr::Switch(SwitchExpr {
+            sources: sources.clone(),
+            selector: Variable::Anonymous(1),
+        });
+
+        assert_eq!(optimized(&expr), *perlin(9));
+
+        let expr = Expr::Switch(SwitchExpr {
+            sources,
+            selector: Variable::Named("selector".to_owned(), 1),
+        });
+
+        assert_eq!(optimized(&expr), expr);
+    }
+
+    #[test]
+    fn perlin_batch() {
+        for seed in [0, 7, u32::MAX] {
+            assert_batch_matches(&Expr::Perlin(Variable::Anonymous(seed)), &Perlin::new(seed));
+        }
+    }
+
+    #[test]
+    fn sample_batch() {
+        let expr = graph();
+        let compiled = expr.compile();
+        let points = [
+            [-1.5, 0.25, 0.0],
+            [0.0; 3],
+            [3.0, -7.5, 0.0],
+            [0.1, 0.2, 0.3],
+        ];
+        let mut samples = [0.0; 4];
+        compiled.sample_batch(&points, &mut samples);
+
+        for (sample, point) in samples.into_iter().zip(points) {
+            assert_eq!(sample, compiled.sample(point));
+            assert_eq!(sample, expr.sample(point));
+        }
+    }
+
+    #[test]
+    fn try_noise_curve() {
+        let expr = Expr::Curve(CurveExpr {
+            source: perlin(0),
+            control_points: [-1.0, 0.0, 1.0]
+                .map(|value| ControlPointExpr {
+                    input_value: Variable::Anonymous(value),
+                    output_value: Variable::Anonymous(value),
+                })
+                .to_vec(),
+        });
+
+        assert_eq!(expr.try_noise().err(), Some(ExprError::Curve));
+    }
+
+    #[test]
+    fn try_noise_image_size() {
+        let expr = Expr::ImageSource(ImageSourceExpr {
+            width: 2,
+            height: 2,
+            samples: vec![0; 3],
+            size: Variable::Anonymous(1.0),
+            tiling: ImageTiling::Clamp,
+        });
+
+        assert_eq!(
+            expr.try_noise().err(),
+            Some(ExprError::ImageSize {
+                width: 2,
+                height: 2,
+                samples: 3,
+            })
+        );
+    }
+
+    #[test]
+    fn try_noise_script() {
+        let expr = Expr::Script(ScriptExpr {
+            script: "x +".to_owned(),
+            sources: vec![],
+            variables: vec![],
+        });
+
+        assert!(matches!(expr.try_noise(), Err(ExprError::Script(_))));
+    }
+
+    #[test]
+    fn try_noise_switch() {
+        let switch = |sources, selector| {
+            Expr::Switch(SwitchExpr {
+                sources,
+                selector: Variable::Anonymous(selector),
+            })
+        };
+
+        assert_eq!(switch(vec![], 0).try_noise().err(), Some(ExprError::Switch));
+
+        // Selectors past the last source pick the last source rather than failing
+        let noise = switch(vec![*constant(0.25), *constant(0.5)], 7)
+            .try_noise()
+            .unwrap();
+
+        assert_eq!(noise.get([0.0; 3]), 0.5);
+    }
+
+    #[test]
+    fn try_noise_terrace() {
+        let expr = Expr::Terrace(TerraceExpr {
+            source: perlin(0),
+            inverted: false,
+            control_points: vec![Variable::Anonymous(0.5), Variable::Anonymous(0.5)],
+            easing: TerraceEasing::default(),
+        });
+
+        assert_eq!(expr.try_noise().err(), Some(ExprError::Terrace));
+    }
+
+    #[test]
+    fn try_noise_valid() {
+        let expr = graph();
+        let noise = expr.try_noise().unwrap();
+
+        for point in grid() {
+            assert_eq!(noise.get(point), expr.sample(point));
+        }
+    }
+
+    #[test]
+    fn variables_used_by() {
+        let clamp = |source| {
+            Box::new(Expr::Clamp(ClampExpr {
+                source,
+                lower_bound: Variable::Anonymous(-1.0),
+                upper_bound: Variable::Named("upper".to_owned(), 0.5),
+            }))
+        };
+        let expr = Expr::Add([clamp(perlin(1)), clamp(perlin(2))]);
+        let vars = expr.variables();
+
+        assert_eq!(vars.len(), 1);
+        assert_eq!(vars[0].name, "upper");
+        assert_eq!(vars[0].value, VariableValue::F64(0.5));
+        assert_eq!(
+            vars[0].used_by,
+            [
+                VariableUse {
+                    kind: "Clamp",
+                    path: vec![0],
+                },
+                VariableUse {
+                    kind: "Clamp",
+                    path: vec![1],
+                },
+            ]
+        );
+
+        for (used_by, seed) in vars[0].used_by.iter().zip([1, 2]) {
+            assert_eq!(expr.source_at(&used_by.path), Some(&*clamp(perlin(seed))));
+        }
+
+        let vars = graph().variables();
+        let uses = vars
+            .iter()
+            .map(|info| {
+                (
+                    info.name.as_str(),
+                    info.used_by[0].kind,
+                    &*info.used_by[0].path,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            uses,
+            [
+                ("upper", "Clamp", &[][..]),
+                ("scale", "ScaleBias", &[0, 0]),
+                ("seed", "Perlin", &[0, 0, 0]),
+            ]
+        );
+        assert_eq!(graph().source_at(&[0, 2]), Some(&*constant(0.25)));
+        assert_eq!(graph().source_at(&[0, 3]), None);
+    }
 }
@@ -0,0 +1,352 @@
+use {
+    super::expr::{FormulaFn, FormulaOp},
+    std::{
+        f64::consts::{PI, TAU},
+        fmt::{Display, Formatter},
+    },
+};
+
+/// The identifiers of the coordinates of the sampled point.
+const COORDS: [&str; 4] = ["x", "y", "z", "w"];
+
+/// The deepest operands may be nested, which keeps parsing and sampling from overflowing the stack.
+const MAX_DEPTH: usize = 64;
+
+/// An error found while parsing a formula.
+#[derive(Debug)]
+pub struct FormulaError {
+    /// The byte offset into the formula where the error was found.
+    pub position: usize,
+
+    pub message: String,
+}
+
+impl FormulaError {
+    fn new(position: usize, message: impl Into<String>) -> Self {
+        Self {
+            position,
+            message: message.into(),
+        }
+    }
+}
+
+impl Display for FormulaError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} (at {})", self.message, self.position + 1)
+    }
+}
+
+/// A parsed formula along with the names of the variables it refers to; `FormulaOp::Variable`
+/// indexes these names.
+pub struct Formula {
+    pub op: FormulaOp,
+
+    /// The name of each variable and the position of its first use.
+    pub variables: Vec<(String, usize)>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Token<'a> {
+    Ident(&'a str),
+    Number(f64),
+    Symbol(char),
+    End,
+}
+
+struct Parser<'a> {
+    tokens: Vec<(Token<'a>, usize)>,
+    next: usize,
+    variables: Vec<(String, usize)>,
+
+    /// The number of source pins which may be referred to.
+    source_count: usize,
+
+    /// The number of operands being parsed which contain the next token.
+    depth: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn tokenize(text: &'a str) -> Result<Vec<(Token<'a>, usize)>, FormulaError> {
+        let mut tokens = vec![];
+        let mut chars = text.char_indices().peekable();
+
+        while let Some(&(start, c)) = chars.peek() {
+            if c.is_whitespace() {
+                chars.next();
+            } else if c.is_ascii_digit() || c == '.' {
+                let mut end = start;
+
+                while let Some(&(idx, c)) = chars.peek() {
+                    // Exponents such as `1e-3` may contain a sign
+                    let is_exponent_sign = (c == '-' || c == '+')
+                        && text[start..idx].ends_with(['e', 'E'])
+                        && end > start;
+
+                    if !(c.is_ascii_alphanumeric() || c == '.' || is_exponent_sign) {
+                        break;
+                    }
+
+                    end = idx + c.len_utf8();
+                    chars.next();
+                }
+
+                let number = text[start..end]
+                    .parse()
+                    .map_err(|_| FormulaError::new(start, "Invalid number"))?;
+                tokens.push((Token::Number(number), start));
+            } else if c.is_alphabetic() || c == '_' {
+                let mut end = start;
+
+                while let Some(&(idx, c)) = chars.peek() {
+                    if !(c.is_alphanumeric() || c == '_') {
+                        break;
+                    }
+
+                    end = idx + c.len_utf8();
+                    chars.next();
+                }
+
+                tokens.push((Token::Ident(&text[start..end]), start));
+            } else if "+-*/%^(),".contains(c) {
+                tokens.push((Token::Symbol(c), start));
+                chars.next();
+            } else {
+                return Err(FormulaError::new(start, format!("Unexpected `{c}`")));
+            }
+        }
+
+        tokens.push((Token::End, text.len()));
+
+        Ok(tokens)
+    }
+
+    fn peek(&self) -> (Token<'a>, usize) {
+        self.tokens[self.next]
+    }
+
+    fn advance(&mut self) -> (Token<'a>, usize) {
+        let token = self.peek();
+
+        if token.0 != Token::End {
+            self.next += 1;
+        }
+
+        token
+    }
+
+    fn expect(&mut self, symbol: char) -> Result<(), FormulaError> {
+        match self.advance() {
+            (Token::Symbol(c), _) if c == symbol => Ok(()),
+            (_, position) => Err(FormulaError::new(position, format!("Expected `{symbol}`"))),
+        }
+    }
+
+    /// expression = term { ("+" | "-") term }
+    fn expression(&mut self) -> Result<FormulaOp, FormulaError> {
+        let mut lhs = self.term()?;
+
+        while let (Token::Symbol(op @ ('+' | '-')), _) = self.peek() {
+            self.advance();
+
+            let operands = Box::new([lhs, self.term()?]);
+            lhs = if op == '+' {
+                FormulaOp::Add(operands)
+            } else {
+                FormulaOp::Subtract(operands)
+            };
+        }
+
+        Ok(lhs)
+    }
+
+    /// term = unary { ("*" | "/" | "%") unary }
+    fn term(&mut self) -> Result<FormulaOp, FormulaError> {
+        let mut lhs = self.unary()?;
+
+        while let (Token::Symbol(op @ ('*' | '/' | '%')), _) = self.peek() {
+            self.advance();
+
+            let operands = Box::new([lhs, self.unary()?]);
+            lhs = match op {
+                '*' => FormulaOp::Multiply(operands),
+                '/' => FormulaOp::Divide(operands),
+                _ => FormulaOp::Remainder(operands),
+            };
+        }
+
+        Ok(lhs)
+    }
+
+    /// unary = "-" unary | power
+    ///
+    /// Every nested operand is parsed through here, so this is where the depth is limited.
+    fn unary(&mut self) -> Result<FormulaOp, FormulaError> {
+        if self.depth == MAX_DEPTH {
+            return Err(FormulaError::new(self.peek().1, "Nested too deeply"));
+        }
+
+        self.depth += 1;
+
+        let op = if let (Token::Symbol('-'), _) = self.peek() {
+            self.advance();
+            self.unary().map(|op| FormulaOp::Negate(Box::new(op)))
+        } else {
+            self.power()
+        };
+
+        self.depth -= 1;
+
+        op
+    }
+
+    /// power = primary [ "^" unary ], which makes `-a^b` equal to `-(a^b)` and `a^b^c` equal to
+    /// `a^(b^c)`
+    fn power(&mut self) -> Result<FormulaOp, FormulaError> {
+        let base = self.primary()?;
+
+        if let (Token::Symbol('^'), _) = self.peek() {
+            self.advance();
+
+            Ok(FormulaOp::Power(Box::new([base, self.unary()?])))
+        } else {
+            Ok(base)
+        }
+    }
+
+    /// primary = number | identifier | function "(" arguments ")" | "(" expression ")"
+    fn primary(&mut self) -> Result<FormulaOp, FormulaError> {
+        match self.advance() {
+            (Token::Number(number), _) => Ok(FormulaOp::Constant(number)),
+            (Token::Ident(name), position) => {
+                if let (Token::Symbol('('), _) = self.peek() {
+                    self.advance();
+                    self.call(name, position)
+                } else {
+                    self.identifier(name, position)
+                }
+            }
+            (Token::Symbol('('), _) => {
+                let op = self.expression()?;
+                self.expect(')')?;
+
+                Ok(op)
+            }
+            (Token::End, position) => Err(FormulaError::new(position, "Unexpected end")),
+            (Token::Symbol(c), position) => {
+                Err(FormulaError::new(position, format!("Unexpected `{c}`")))
+            }
+        }
+    }
+
+    /// Parses the arguments of a function call; the opening parenthesis has been consumed.
+    fn call(&mut self, name: &str, position: usize) -> Result<FormulaOp, FormulaError> {
+        let func = FormulaFn::ALL
+            .into_iter()
+            .find(|func| func.name() == name)
+            .ok_or_else(|| FormulaError::new(position, format!("Unknown function `{name}`")))?;
+        let mut args = vec![];
+
+        if self.peek().0 != Token::Symbol(')') {
+            loop {
+                args.push(self.expression()?);
+
+                if self.peek().0 == Token::Symbol(',') {
+                    self.advance();
+                } else {
+                    break;
+                }
+            }
+        }
+
+        self.expect(')')?;
+
+        if args.len() != func.arity() {
+            return Err(FormulaError::new(
+                position,
+                format!(
+                    "`{name}` takes {} argument{}",
+                    func.arity(),
+                    if func.arity() == 1 { "" } else { "s" }
+                ),
+            ));
+        }
+
+        Ok(FormulaOp::Call(func, args))
+    }
+
+    fn identifier(&mut self, name: &str, position: usize) -> Result<FormulaOp, FormulaError> {
+        if let Some(axis) = COORDS.iter().position(|&coord| coord == name) {
+            return Ok(FormulaOp::Coord(axis));
+        }
+
+        if let Some(source) = source_index(name) {
+            if source >= self.source_count {
+                return Err(FormulaError::new(
+                    position,
+                    format!(
+                        "`{name}` is not connected; there {} {} source{}",
+                        if self.source_count == 1 { "is" } else { "are" },
+                        self.source_count,
+                        if self.source_count == 1 { "" } else { "s" }
+                    ),
+                ));
+            }
+
+            return Ok(FormulaOp::Source(source));
+        }
+
+        Ok(match name {
+            "pi" => FormulaOp::Constant(PI),
+            "tau" => FormulaOp::Constant(TAU),
+            _ => {
+                let idx = self
+                    .variables
+                    .iter()
+                    .position(|(variable, _)| variable == name)
+                    .unwrap_or_else(|| {
+                        self.variables.push((name.to_owned(), position));
+                        self.variables.len() - 1
+                    });
+
+                FormulaOp::Variable(idx)
+            }
+        })
+    }
+}
+
+/// Returns the index of the source pin named by an identifier: `source` or `s0` for the first, `s1`
+/// for the second and so on.
+fn source_index(name: &str) -> Option<usize> {
+    if name == "source" {
+        return Some(0);
+    }
+
+    name.strip_prefix('s')
+        .filter(|idx| !idx.is_empty() && idx.bytes().all(|c| c.is_ascii_digit()))
+        .and_then(|idx| idx.parse().ok())
+}
+
+/// Parses a formula such as `sin(x * 3) * a + source`.
+///
+/// Formulas may use numbers, `+`, `-`, `*`, `/`, `%` and `^` (power), parentheses, the functions of
+/// `FormulaFn`, the coordinates `x`, `y`, `z` and `w`, the constants `pi` and `tau` and the sources
+/// `source` (or `s0`), `s1`, `s2` and so on up to one less than `source_count`. Any other name is a
+/// variable.
+pub fn parse(text: &str, source_count: usize) -> Result<Formula, FormulaError> {
+    let mut parser = Parser {
+        tokens: Parser::tokenize(text)?,
+        next: 0,
+        variables: vec![],
+        source_count,
+        depth: 0,
+    };
+    let op = parser.expression()?;
+
+    match parser.peek() {
+        (Token::End, _) => Ok(Formula {
+            op,
+            variables: parser.variables,
+        }),
+        (_, position) => Err(FormulaError::new(position, "Expected an operator")),
+    }
+}
@@ -0,0 +1,799 @@
+use {
+    super::expr::{
+        BlueNoise, CompareType, DomainWarpExpr, EasingCurve, Expr, FractalExpr, GradientType,
+        NormalComponent, SourceType, SwizzleComponent, MAX_FRACTAL_OCTAVES,
+    },
+    log::{info, warn},
+    noise::permutationtable::{NoiseHasher, PermutationTable},
+    std::{
+        fmt::Write,
+        future::Future,
+        pin::pin,
+        sync::Arc,
+        task::{Context, Poll, Wake, Waker},
+        thread::{current, park, Thread},
+    },
+    wgpu::{
+        util::{BufferInitDescriptor, DeviceExt},
+        BindGroupDescriptor, BindGroupEntry, BindGroupLayout, BindGroupLayoutDescriptor,
+        BindGroupLayoutEntry, BindingType, Buffer, BufferBindingType, BufferDescriptor,
+        BufferUsages, CommandEncoderDescriptor, ComputePassDescriptor, ComputePipeline,
+        ComputePipelineDescriptor, Device, DeviceDescriptor, DownlevelFlags, ErrorFilter, Instance,
+        Limits, Maintain, MapMode, PipelineLayout, PipelineLayoutDescriptor, Queue,
+        RequestAdapterOptions, ShaderModuleDescriptor, ShaderSource, ShaderStages,
+    },
+};
+
+/// Shared WGSL functions which generated node functions call into.
+///
+/// `perlin` matches `noise::core::perlin::perlin_3d`, sampling the permutation table which starts
+/// at the given offset of `perm`.
+const PRELUDE: &str = r#"
+struct Params {
+    origin: vec2<f32>,
+    delta: f32,
+    size: u32,
+}
+
+@group(0) @binding(0) var<uniform> params: Params;
+@group(0) @binding(1) var<storage, read> perm: array<u32>;
+@group(0) @binding(2) var<storage, read_write> samples: array<f32>;
+
+fn hash(table: u32, corner: vec3<i32>) -> u32 {
+    let x = perm[table + u32(corner.x & 255)];
+    let y = perm[table + (x ^ u32(corner.y & 255))];
+
+    return perm[table + (y ^ u32(corner.z & 255))];
+}
+
+fn gradient(h: u32, v: vec3<f32>) -> f32 {
+    switch h & 15u {
+        case 0u, 12u: { return v.x + v.y; }
+        case 1u, 13u: { return -v.x + v.y; }
+        case 2u: { return v.x - v.y; }
+        case 3u: { return -v.x - v.y; }
+        case 4u: { return v.x + v.z; }
+        case 5u: { return -v.x + v.z; }
+        case 6u: { return v.x - v.z; }
+        case 7u: { return -v.x - v.z; }
+        case 8u: { return v.y + v.z; }
+        case 9u, 14u: { return -v.y + v.z; }
+        case 10u: { return v.y - v.z; }
+        default: { return -v.y - v.z; }
+    }
+}
+
+fn perlin(table: u32, point: vec3<f32>) -> f32 {
+    let floored = floor(point);
+    let corner = vec3<i32>(floored);
+    let d = point - floored;
+
+    let g000 = gradient(hash(table, corner), d);
+    let g100 = gradient(hash(table, corner + vec3(1, 0, 0)), d - vec3(1.0, 0.0, 0.0));
+    let g010 = gradient(hash(table, corner + vec3(0, 1, 0)), d - vec3(0.0, 1.0, 0.0));
+    let g110 = gradient(hash(table, corner + vec3(1, 1, 0)), d - vec3(1.0, 1.0, 0.0));
+    let g001 = gradient(hash(table, corner + vec3(0, 0, 1)), d - vec3(0.0, 0.0, 1.0));
+    let g101 = gradient(hash(table, corner + vec3(1, 0, 1)), d - vec3(1.0, 0.0, 1.0));
+    let g011 = gradient(hash(table, corner + vec3(0, 1, 1)), d - vec3(0.0, 1.0, 1.0));
+    let g111 = gradient(hash(table, corner + vec3(1, 1, 1)), d - vec3(1.0, 1.0, 1.0));
+
+    let s = clamp(d, vec3(0.0), vec3(1.0));
+    let f = s * s * s * (s * (s * 6.0 - 15.0) + 10.0);
+
+    let k0 = g000;
+    let k1 = g100 - g000;
+    let k2 = g010 - g000;
+    let k3 = g001 - g000;
+    let k4 = g000 + g110 - g100 - g010;
+    let k5 = g000 + g101 - g100 - g001;
+    let k6 = g000 + g011 - g010 - g001;
+    let k7 = g100 + g010 + g001 + g111 - g000 - g110 - g101 - g011;
+
+    let result = k0 + k1 * f.x + k2 * f.y + k3 * f.z + k4 * f.x * f.y + k5 * f.x * f.z
+        + k6 * f.y * f.z + k7 * f.x * f.y * f.z;
+
+    return clamp(result * 1.1547005, -1.0, 1.0);
+}
+
+@compute @workgroup_size(8, 8)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    if id.x >= params.size || id.y >= params.size {
+        return;
+    }
+
+    let point = vec3(params.origin + vec2<f32>(id.xy) * params.delta, 0.0);
+    samples[id.x * params.size + id.y] = n0(point);
+}
+"#;
+
+/// Generates one WGSL function per expression node, named `n0`, `n1`, etc. with `n0` being the
+/// root node.
+#[derive(Default)]
+struct Compiler {
+    code: String,
+    node_count: usize,
+
+    /// Permutation tables of all Perlin sources, 256 entries each.
+    perm: Vec<u32>,
+}
+
+impl Compiler {
+    /// Returns the name of the function which samples the given node, or `None` if the node (or
+    /// any of its sources) cannot be evaluated on the GPU.
+    fn compile(&mut self, expr: &Expr) -> Option<String> {
+        let name = format!("n{}", self.node_count);
+        self.node_count += 1;
+
+        let body = match expr {
+            Expr::Abs(source) => format!("return abs({}(p));", self.compile(source)?),
+            Expr::Add([lhs, rhs]) => self.binary("{} + {}", lhs, rhs)?,
+            Expr::BiasGain(expr) => {
+                let [bias, gain] = expr.coefficients();
+
+                format!(
+                    "let t = clamp({}(p) * 0.5 + 0.5, 0.0, 1.0); \
+                    let b = t / ({} * (1.0 - t) + 1.0); \
+                    let s = select(2.0 - b * 2.0, b * 2.0, b < 0.5); \
+                    let g = s / ({} * (1.0 - s) + 1.0); \
+                    return select(1.0 - g, g - 1.0, b < 0.5);",
+                    self.compile(&expr.source)?,
+                    literal(bias)?,
+                    literal(gain)?,
+                )
+            }
+            Expr::Billow(expr) => self.fractal(expr, true)?,
+            Expr::Blend(expr) => format!(
+                "return mix({}(p), {}(p), {}(p));",
+                self.compile(&expr.sources[0])?,
+                self.compile(&expr.sources[1])?,
+                self.compile(&expr.control)?,
+            ),
+            Expr::BlueNoise(seed) => format!(
+                "let c = floor(p.xy); \
+                let v = fract({} + c.x * {} + c.y * {}); \
+                return 1.0 - 4.0 * abs(v - 0.5);",
+                literal(BlueNoise::offset(seed.value()))?,
+                literal(BlueNoise::ALPHA[0])?,
+                literal(BlueNoise::ALPHA[1])?,
+            ),
+            Expr::Cache(source) => format!("return {}(p);", self.compile(source)?),
+            Expr::Checkerboard(size) => {
+                let size = 1i32.checked_shl(size.value()).filter(|size| *size > 0)?;
+
+                format!(
+                    "let f = vec3<i32>(floor(p)); \
+                    let r = (((f.x & {size}) ^ (f.y & {size})) & {size}) ^ (f.z & {size}); \
+                    return select(1.0, -1.0, r > 0);"
+                )
+            }
+            Expr::Clamp(expr) => {
+                let (lower, upper) = (expr.lower_bound.value(), expr.upper_bound.value());
+
+                format!(
+                    "return clamp({}(p), {}, {});",
+                    self.compile(&expr.source)?,
+                    literal(lower.min(upper))?,
+                    literal(lower.max(upper))?,
+                )
+            }
+            Expr::Compare(expr) => {
+                let tolerance = literal(expr.tolerance.value().abs())?;
+                let condition = match expr.compare_ty {
+                    CompareType::Equal => format!("abs(a - b) <= {tolerance}"),
+                    CompareType::Greater => format!("a > b + {tolerance}"),
+                    CompareType::Less => format!("a < b - {tolerance}"),
+                };
+
+                format!(
+                    "let a = {}(p); \
+                    let b = {}(p); \
+                    return select(0.0, 1.0, {condition});",
+                    self.compile(&expr.sources[0])?,
+                    self.compile(&expr.sources[1])?,
+                )
+            }
+            Expr::Constant(value) => format!("return {};", literal(value.value())?),
+            Expr::Contrast(expr) => {
+                let pivot = literal(expr.pivot.value())?;
+                let value = format!(
+                    "({}(p) - {pivot}) * {} + {pivot}",
+                    self.compile(&expr.source)?,
+                    literal(expr.contrast.value())?,
+                );
+
+                if expr.clamped {
+                    format!("return clamp({value}, -1.0, 1.0);")
+                } else {
+                    format!("return {value};")
+                }
+            }
+            Expr::Cylinders(frequency) => format!(
+                "let d = length(p.xy * {}); \
+                let s = d - floor(d); \
+                return 1.0 - min(s, 1.0 - s) * 4.0;",
+                literal(frequency.value())?,
+            ),
+            Expr::Difference([lhs, rhs]) => self.binary("abs({} - {})", lhs, rhs)?,
+            Expr::DomainWarp(expr) => {
+                let (source, warp) = (self.compile(&expr.source)?, self.compile(&expr.warp)?);
+                let offsets = DomainWarpExpr::AXIS_OFFSETS;
+
+                format!(
+                    "let q = p * {}; \
+                    let d = vec3({warp}(q + {}), {warp}(q + {}), {warp}(q + {})); \
+                    return {source}(p + d * {});",
+                    literal(expr.frequency.value())?,
+                    literal(offsets[0])?,
+                    literal(offsets[1])?,
+                    literal(offsets[2])?,
+                    literal(expr.amplitude.value())?,
+                )
+            }
+            Expr::Ease(expr) => format!(
+                "let t = clamp({}(p) * 0.5 + 0.5, 0.0, 1.0); return {} * 2.0 - 1.0;",
+                self.compile(&expr.source)?,
+                easing(expr.curve),
+            ),
+            Expr::Exponent(expr) => format!(
+                "return pow(abs(({}(p) + 1.0) / 2.0), {}) * 2.0 - 1.0;",
+                self.compile(&expr.source)?,
+                literal(expr.exponent.value())?,
+            ),
+            Expr::Fbm(expr) => self.fractal(expr, false)?,
+            Expr::Gradient(expr) => {
+                let (angle, radius) = (expr.angle.value().to_radians(), expr.radius.value());
+
+                if radius <= 0.0 {
+                    "return 0.0;".to_owned()
+                } else if expr.gradient_ty == GradientType::Linear {
+                    format!(
+                        "return clamp(dot(p.xy, vec2({}, {})) / {}, -1.0, 1.0);",
+                        literal(angle.cos())?,
+                        literal(angle.sin())?,
+                        literal(radius)?,
+                    )
+                } else {
+                    format!(
+                        "return 1.0 - 2.0 * min(length(p.xy) / {}, 1.0);",
+                        literal(radius)?,
+                    )
+                }
+            }
+            Expr::Max([lhs, rhs]) => self.binary("max({}, {})", lhs, rhs)?,
+            Expr::Min([lhs, rhs]) => self.binary("min({}, {})", lhs, rhs)?,
+            Expr::MirrorPoint(expr) => {
+                let [x, y, z, _] = expr.axes;
+
+                format!(
+                    "return {}(select(p, abs(p), vec3({x}, {y}, {z})));",
+                    self.compile(&expr.source)?,
+                )
+            }
+            Expr::Mix(expr) => format!(
+                "return mix({}(p), {}(p), {});",
+                self.compile(&expr.sources[0])?,
+                self.compile(&expr.sources[1])?,
+                literal(expr.factor())?,
+            ),
+            Expr::Multiply([lhs, rhs]) => self.binary("{} * {}", lhs, rhs)?,
+            Expr::Negate(source) => format!("return -{}(p);", self.compile(source)?),
+            Expr::Normal(expr) => {
+                let (epsilon, strength) = (expr.epsilon.value(), expr.strength.value());
+                let component = match expr.component {
+                    NormalComponent::X => "x",
+                    NormalComponent::Y => "y",
+                    NormalComponent::Z => "z",
+                };
+
+                if epsilon <= 0.0 {
+                    format!("return vec3(0.0, 0.0, 1.0).{component};")
+                } else {
+                    let source = self.compile(&expr.source)?;
+
+                    format!(
+                        "let dx = vec3({epsilon}, 0.0, 0.0); \
+                        let dy = vec3(0.0, {epsilon}, 0.0); \
+                        let g = vec2({source}(p + dx) - {source}(p - dx), \
+                            {source}(p + dy) - {source}(p - dy)); \
+                        return normalize(vec3(g * {}, 1.0)).{component};",
+                        literal(-strength / (2.0 * epsilon))?,
+                        epsilon = literal(epsilon)?,
+                    )
+                }
+            }
+            Expr::Normalize(expr) => {
+                let (scale, bias) = expr.scale_bias();
+
+                format!(
+                    "return {}(p) * {} + {};",
+                    self.compile(&expr.source)?,
+                    literal(scale)?,
+                    literal(bias)?,
+                )
+            }
+            Expr::Perlin(seed) => format!("return perlin({}u, p);", self.table(seed.value())),
+            Expr::Remap(expr) => {
+                let (scale, bias) = expr.scale_bias();
+                let value = format!(
+                    "{}(p) * {} + {}",
+                    self.compile(&expr.source)?,
+                    literal(scale)?,
+                    literal(bias)?,
+                );
+
+                if expr.clamped {
+                    let (out_min, out_max) = (expr.out_min.value(), expr.out_max.value());
+
+                    format!(
+                        "return clamp({value}, {}, {});",
+                        literal(out_min.min(out_max))?,
+                        literal(out_min.max(out_max))?,
+                    )
+                } else {
+                    format!("return {value};")
+                }
+            }
+            Expr::RotateAxis(expr) => {
+                let matrix = expr.matrix();
+
+                // WGSL matrices are built from columns
+                let columns = (0..3)
+                    .map(|col| {
+                        let [x, y, z] = matrix.map(|row| literal(row[col]));
+
+                        Some(format!("vec3({}, {}, {})", x?, y?, z?))
+                    })
+                    .collect::<Option<Vec<_>>>()?;
+
+                format!(
+                    "return {}(mat3x3({}) * p);",
+                    self.compile(&expr.source)?,
+                    columns.join(", "),
+                )
+            }
+            Expr::ScaleBias(expr) => format!(
+                "return {}(p) * {} + {};",
+                self.compile(&expr.source)?,
+                literal(expr.scale.value())?,
+                literal(expr.bias.value())?,
+            ),
+            Expr::ScalePoint(expr) => format!(
+                "return {}(p * vec3({}, {}, {}));",
+                self.compile(&expr.source)?,
+                literal(expr.axes[0].value())?,
+                literal(expr.axes[1].value())?,
+                literal(expr.axes[2].value())?,
+            ),
+            Expr::Slope(expr) => {
+                let epsilon = expr.epsilon.value();
+
+                if epsilon <= 0.0 {
+                    "return 0.0;".to_owned()
+                } else {
+                    let source = self.compile(&expr.source)?;
+
+                    format!(
+                        "let dx = vec3({epsilon}, 0.0, 0.0); \
+                        let dy = vec3(0.0, {epsilon}, 0.0); \
+                        return length(vec2({source}(p + dx) - {source}(p - dx), \
+                            {source}(p + dy) - {source}(p - dy))) / {};",
+                        literal(2.0 * epsilon)?,
+                        epsilon = literal(epsilon)?,
+                    )
+                }
+            }
+            Expr::Switch(expr) => match expr.source() {
+                Some(source) => format!("return {}(p);", self.compile(source)?),
+                None => "return 0.0;".to_owned(),
+            },
+            Expr::Swizzle(expr) => {
+                // The W coordinate of the sampled point is always zero on the GPU
+                let coords = expr.components[..3]
+                    .iter()
+                    .map(|component| match component {
+                        SwizzleComponent::X => "p.x",
+                        SwizzleComponent::Y => "p.y",
+                        SwizzleComponent::Z => "p.z",
+                        SwizzleComponent::W | SwizzleComponent::Zero => "0.0",
+                    })
+                    .collect::<Vec<_>>();
+
+                format!(
+                    "return {}(vec3({}));",
+                    self.compile(&expr.source)?,
+                    coords.join(", "),
+                )
+            }
+            Expr::TilePoint(expr) => {
+                let source = self.compile(&expr.source)?;
+                let periods = [0, 1, 2].map(|axis| expr.periods[axis].value());
+                let tiled = [0, 1, 2]
+                    .into_iter()
+                    .filter(|&axis| periods[axis] > 0.0)
+                    .collect::<Vec<_>>();
+                let mut wrapped = ["p.x", "p.y", "p.z"].map(str::to_owned);
+
+                for &axis in &tiled {
+                    let coord = &wrapped[axis];
+                    let period = literal(periods[axis])?;
+                    wrapped[axis] = format!("{coord} - floor({coord} / {period}) * {period}");
+                }
+
+                let wrapped = format!("let q = vec3({});", wrapped.join(", "));
+
+                if !expr.blended || tiled.is_empty() {
+                    format!("{wrapped} return {source}(q);")
+                } else {
+                    // One sample for each corner of the tiled axes, as in the CPU version
+                    let mut samples = vec![];
+
+                    for corner in 0..1usize << tiled.len() {
+                        let mut coords = ["q.x", "q.y", "q.z"].map(str::to_owned);
+                        let mut weights = vec![];
+
+                        for (bit, &axis) in tiled.iter().enumerate() {
+                            let period = literal(periods[axis])?;
+                            let t = format!("{} / {period}", coords[axis]);
+
+                            if corner & 1 << bit == 0 {
+                                weights.push(format!("(1.0 - {t})"));
+                            } else {
+                                weights.push(format!("({t})"));
+                                coords[axis] = format!("{} - {period}", coords[axis]);
+                            }
+                        }
+
+                        samples.push(format!(
+                            "{} * {source}(vec3({}))",
+                            weights.join(" * "),
+                            coords.join(", "),
+                        ));
+                    }
+
+                    format!("{wrapped} return {};", samples.join(" + "))
+                }
+            }
+            Expr::TranslatePoint(expr) => format!(
+                "return {}(p + vec3({}, {}, {}));",
+                self.compile(&expr.source)?,
+                literal(expr.axes[0].value())?,
+                literal(expr.axes[1].value())?,
+                literal(expr.axes[2].value())?,
+            ),
+            _ => return None,
+        };
+
+        writeln!(self.code, "fn {name}(p: vec3<f32>) -> f32 {{ {body} }}").unwrap();
+
+        Some(name)
+    }
+
+    fn binary(&mut self, op: &str, lhs: &Expr, rhs: &Expr) -> Option<String> {
+        let lhs = format!("{}(p)", self.compile(lhs)?);
+        let rhs = format!("{}(p)", self.compile(rhs)?);
+
+        Some(format!(
+            "return {};",
+            op.replacen("{}", &lhs, 1).replacen("{}", &rhs, 1)
+        ))
+    }
+
+    /// Matches `noise::Fbm` and `noise::Billow`, which only differ in how each octave is shaped.
+    fn fractal(&mut self, expr: &FractalExpr, billow: bool) -> Option<String> {
+        if expr.source_ty != SourceType::Perlin || !expr.weights.is_empty() {
+            return None;
+        }
+
+        let seed = expr.seed.value();
+        let octaves = expr.octaves.value().clamp(1, MAX_FRACTAL_OCTAVES);
+        let persistence = expr.persistence.value();
+
+        // Each octave uses the next seed, so the tables of one fractal are stored contiguously
+        let table = self.table(seed);
+        for octave in 1..octaves {
+            self.table(seed.wrapping_add(octave));
+        }
+
+        Some(format!(
+            "var point = p * {frequency}; \
+            var result = 0.0; \
+            var amplitude = 1.0; \
+            for (var octave = 0u; octave < {octaves}u; octave++) {{ \
+                var signal = perlin({table}u + octave * 256u, point); \
+                {shape} \
+                result += signal * amplitude; \
+                amplitude *= {persistence}; \
+                point *= {lacunarity}; \
+            }} \
+            return result / {scale_factor};",
+            frequency = literal(expr.frequency.value())?,
+            shape = if billow {
+                "signal = abs(signal) * 2.0 - 1.0;"
+            } else {
+                ""
+            },
+            persistence = literal(persistence)?,
+            lacunarity = literal(expr.lacunarity.value())?,
+            scale_factor = literal(1.0 - persistence.powi(octaves as _))?,
+        ))
+    }
+
+    /// Appends the permutation table of a seed, returning its offset.
+    fn table(&mut self, seed: u32) -> usize {
+        let offset = self.perm.len();
+        let table = PermutationTable::new(seed);
+        self.perm
+            .extend((0..256).map(|idx| table.hash(&[idx]) as u32));
+
+        offset
+    }
+}
+
+/// A compiled expression which is ready to be sampled.
+pub struct Kernel {
+    perm: Buffer,
+    pipeline: ComputePipeline,
+}
+
+/// Evaluates supported expressions using a WGSL compute shader.
+pub struct Gpu {
+    bind_group_layout: BindGroupLayout,
+    device: Device,
+    pipeline_layout: PipelineLayout,
+    queue: Queue,
+}
+
+impl Gpu {
+    const WORKGROUP_SIZE: u32 = 8;
+
+    /// Returns `None` if no adapter with compute shader support is available.
+    pub fn new() -> Option<Self> {
+        let instance = Instance::new(Default::default());
+        let adapter = block_on(instance.request_adapter(&RequestAdapterOptions::default()))?;
+
+        if !adapter
+            .get_downlevel_capabilities()
+            .flags
+            .contains(DownlevelFlags::COMPUTE_SHADERS)
+        {
+            return None;
+        }
+
+        let (device, queue) = block_on(adapter.request_device(
+            &DeviceDescriptor {
+                label: Some("noise_gui"),
+                required_limits: Limits::downlevel_defaults().using_resolution(adapter.limits()),
+                ..Default::default()
+            },
+            None,
+        ))
+        .ok()?;
+
+        info!("GPU preview evaluation using {}", adapter.get_info().name);
+
+        let storage = |binding, read_only| BindGroupLayoutEntry {
+            binding,
+            visibility: ShaderStages::COMPUTE,
+            ty: BindingType::Buffer {
+                ty: BufferBindingType::Storage { read_only },
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        };
+        let bind_group_layout = device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            label: None,
+            entries: &[
+                BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStages::COMPUTE,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                storage(1, true),
+                storage(2, false),
+            ],
+        });
+        let pipeline_layout = device.create_pipeline_layout(&PipelineLayoutDescriptor {
+            label: None,
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        Some(Self {
+            bind_group_layout,
+            device,
+            pipeline_layout,
+            queue,
+        })
+    }
+
+    /// Returns `None` if the expression uses any node which has no WGSL implementation.
+    pub fn compile(&self, expr: &Expr) -> Option<Kernel> {
+        let mut compiler = Compiler::default();
+        compiler.compile(expr)?;
+
+        // The buffer may not be empty even if there are no Perlin sources
+        if compiler.perm.is_empty() {
+            compiler.perm.push(0);
+        }
+
+        let perm = self.device.create_buffer_init(&BufferInitDescriptor {
+            label: None,
+            contents: &compiler
+                .perm
+                .iter()
+                .flat_map(|value| value.to_ne_bytes())
+                .collect::<Vec<_>>(),
+            usage: BufferUsages::STORAGE,
+        });
+
+        // Invalid shaders are reported as errors instead of panicking on the device
+        self.device.push_error_scope(ErrorFilter::Validation);
+
+        let module = self.device.create_shader_module(ShaderModuleDescriptor {
+            label: None,
+            source: ShaderSource::Wgsl((compiler.code + PRELUDE).into()),
+        });
+        let pipeline = self
+            .device
+            .create_compute_pipeline(&ComputePipelineDescriptor {
+                label: None,
+                layout: Some(&self.pipeline_layout),
+                module: &module,
+                entry_point: Some("main"),
+                compilation_options: Default::default(),
+                cache: None,
+            });
+
+        if let Some(err) = block_on(self.device.pop_error_scope()) {
+            warn!("Unable to compile expression shader: {err}");
+
+            return None;
+        }
+
+        Some(Kernel { perm, pipeline })
+    }
+
+    /// Samples a square grid of `size` × `size` points on the XY plane, starting at `origin` and
+    /// stepping by `delta` along both axes.
+    ///
+    /// Samples are returned column-major, as used by image chunks.
+    pub fn sample(
+        &self,
+        kernel: &Kernel,
+        origin: [f64; 2],
+        delta: f64,
+        size: usize,
+    ) -> Option<Vec<f32>> {
+        let params = [origin[0] as f32, origin[1] as f32, delta as f32]
+            .into_iter()
+            .flat_map(f32::to_ne_bytes)
+            .chain((size as u32).to_ne_bytes())
+            .collect::<Vec<_>>();
+        let params = self.device.create_buffer_init(&BufferInitDescriptor {
+            label: None,
+            contents: &params,
+            usage: BufferUsages::UNIFORM,
+        });
+
+        let len = (size * size * size_of::<f32>()) as u64;
+        let samples = self.device.create_buffer(&BufferDescriptor {
+            label: None,
+            size: len,
+            usage: BufferUsages::STORAGE | BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+        let staging = self.device.create_buffer(&BufferDescriptor {
+            label: None,
+            size: len,
+            usage: BufferUsages::MAP_READ | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let bind_group = self.device.create_bind_group(&BindGroupDescriptor {
+            label: None,
+            layout: &self.bind_group_layout,
+            entries: &[
+                BindGroupEntry {
+                    binding: 0,
+                    resource: params.as_entire_binding(),
+                },
+                BindGroupEntry {
+                    binding: 1,
+                    resource: kernel.perm.as_entire_binding(),
+                },
+                BindGroupEntry {
+                    binding: 2,
+                    resource: samples.as_entire_binding(),
+                },
+            ],
+        });
+
+        let workgroups = (size as u32).div_ceil(Self::WORKGROUP_SIZE);
+        let mut encoder = self
+            .device
+            .create_command_encoder(&CommandEncoderDescriptor::default());
+
+        {
+            let mut pass = encoder.begin_compute_pass(&ComputePassDescriptor::default());
+            pass.set_pipeline(&kernel.pipeline);
+            pass.set_bind_group(0, &bind_group, &[]);
+            pass.dispatch_workgroups(workgroups, workgroups, 1);
+        }
+
+        encoder.copy_buffer_to_buffer(&samples, 0, &staging, 0, len);
+        self.queue.submit([encoder.finish()]);
+
+        let (tx, rx) = crossbeam_channel::bounded(1);
+        let slice = staging.slice(..);
+        slice.map_async(MapMode::Read, move |res| tx.send(res).unwrap());
+        self.device.poll(Maintain::Wait);
+        rx.recv().ok()?.ok()?;
+
+        let res = slice
+            .get_mapped_range()
+            .chunks_exact(size_of::<f32>())
+            .map(|bytes| f32::from_ne_bytes(bytes.try_into().unwrap()))
+            .collect();
+
+        Some(res)
+    }
+}
+
+/// Formats a value as a WGSL float literal, or `None` if it has no literal form.
+/// Returns the WGSL expression of the given easing curve of `t`; see `EasingCurve::ease`.
+fn easing(curve: EasingCurve) -> &'static str {
+    match curve {
+        EasingCurve::QuadIn => "t * t",
+        EasingCurve::QuadOut => "1.0 - (1.0 - t) * (1.0 - t)",
+        EasingCurve::QuadInOut => {
+            "select(1.0 - pow(2.0 - 2.0 * t, 2.0) / 2.0, 2.0 * t * t, t < 0.5)"
+        }
+        EasingCurve::CubicIn => "t * t * t",
+        EasingCurve::CubicOut => "1.0 - pow(1.0 - t, 3.0)",
+        EasingCurve::CubicInOut => {
+            "select(1.0 - pow(2.0 - 2.0 * t, 3.0) / 2.0, 4.0 * t * t * t, t < 0.5)"
+        }
+        EasingCurve::ExpoIn => "select(exp2(10.0 * t - 10.0), 0.0, t == 0.0)",
+        EasingCurve::ExpoOut => "select(1.0 - exp2(-10.0 * t), 1.0, t == 1.0)",
+        EasingCurve::ExpoInOut => {
+            "select(select((2.0 - exp2(10.0 - 20.0 * t)) / 2.0, exp2(20.0 * t - 10.0) / 2.0, \
+            t < 0.5), t, t == 0.0 || t == 1.0)"
+        }
+    }
+}
+
+fn literal(value: f64) -> Option<String> {
+    let value = value as f32;
+
+    // Parentheses keep negative values from forming `--` (decrement) with a preceding operator
+    value.is_finite().then(|| format!("({value:?})"))
+}
+
+/// Runs a future to completion on the current thread; `wgpu` futures are ready as soon as the
+/// device has been polled.
+fn block_on<F: Future>(future: F) -> F::Output {
+    struct ThreadWaker(Thread);
+
+    impl Wake for ThreadWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    let waker = Waker::from(Arc::new(ThreadWaker(current())));
+    let mut cx = Context::from_waker(&waker);
+    let mut future = pin!(future);
+
+    loop {
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => park(),
+        }
+    }
+}
@@ -0,0 +1,691 @@
+use {
+    super::{
+        expr::{DistanceFunction, ReturnType, SourceType},
+        node::{
+            CheckerboardNode, ClampNode, ControlPointNode, CurveNode, CylindersNode, ExponentNode,
+            FractalNode, GeneratorNode, NodeValue, NoiseNode, RigidFractalNode, ScaleBiasNode,
+            SelectNode, TerraceNode, TransformNode, TurbulenceNode, WorleyNode,
+        },
+    },
+    anyhow::{bail, Context},
+    egui::pos2,
+    egui_snarl::{InPinId, OutPinId, Snarl},
+    log::warn,
+    rfd::FileDialog,
+    roxmltree::{Document, Node},
+    serde::Deserialize,
+    std::{collections::HashMap, error::Error, fs::read_to_string, path::Path, str::FromStr},
+};
+
+/// Horizontal distance between columns of imported nodes.
+const COLUMN_WIDTH: f32 = 256.0;
+
+/// Vertical distance between rows of imported nodes.
+const ROW_HEIGHT: f32 = 320.0;
+
+/// A node which has been parsed but not yet inserted into a graph.
+struct PendingNode {
+    node: NoiseNode,
+
+    /// Pairs of input pin index and the pending node index connected to it.
+    inputs: Vec<(usize, usize)>,
+}
+
+fn attr<T>(module: Node<'_, '_>, name: &str, default: T) -> anyhow::Result<T>
+where
+    T: FromStr,
+    <T as FromStr>::Err: Error + Send + Sync + 'static,
+{
+    module
+        .attribute(name)
+        .map(|value| {
+            value
+                .trim()
+                .parse()
+                .with_context(|| format!("Parsing attribute `{name}`"))
+        })
+        .unwrap_or(Ok(default))
+}
+
+/// Inserts the pending nodes into a new graph, arranging them in columns by dependency depth.
+fn build(pending: Vec<PendingNode>) -> anyhow::Result<Snarl<NoiseNode>> {
+    fn depth(pending: &[PendingNode], depths: &mut [Option<usize>], idx: usize) -> Option<usize> {
+        const VISITING: usize = usize::MAX;
+
+        match depths[idx] {
+            Some(VISITING) => return None,
+            Some(depth) => return Some(depth),
+            None => (),
+        }
+
+        depths[idx] = Some(VISITING);
+
+        let mut res = 0;
+        for &(_, input_idx) in &pending[idx].inputs {
+            res = res.max(depth(pending, depths, input_idx)? + 1);
+        }
+
+        depths[idx] = Some(res);
+
+        Some(res)
+    }
+
+    let mut depths = vec![None; pending.len()];
+    for idx in 0..pending.len() {
+        if depth(&pending, &mut depths, idx).is_none() {
+            bail!("Cyclic module graph");
+        }
+    }
+
+    let mut rows = HashMap::<usize, usize>::new();
+    let mut snarl = Snarl::new();
+    let mut inputs = Vec::with_capacity(pending.len());
+    let mut node_ids = Vec::with_capacity(pending.len());
+
+    for (pending, depth) in pending.into_iter().zip(depths) {
+        let depth = depth.unwrap();
+        let row = rows.entry(depth).or_default();
+        let pos = pos2(depth as f32 * COLUMN_WIDTH, *row as f32 * ROW_HEIGHT);
+        *row += 1;
+
+        node_ids.push(snarl.insert_node(pos, pending.node));
+        inputs.push(pending.inputs);
+    }
+
+    for (to_idx, inputs) in inputs.into_iter().enumerate() {
+        let to = node_ids[to_idx];
+        for (input, from_idx) in inputs {
+            let from = node_ids[from_idx];
+            snarl.connect(
+                OutPinId {
+                    node: from,
+                    output: 0,
+                },
+                InPinId { node: to, input },
+            );
+
+            if input == 0 {
+                continue;
+            }
+
+            let control_point_node_ids = match snarl.get_node_mut(to).unwrap() {
+                NoiseNode::Curve(node) => &mut node.control_point_node_ids,
+                NoiseNode::Terrace(node) => &mut node.control_point_node_ids,
+                _ => continue,
+            };
+
+            let control_point_idx = input - 1;
+
+            while control_point_node_ids.len() <= control_point_idx {
+                control_point_node_ids.push(None);
+            }
+
+            control_point_node_ids[control_point_idx] = Some(from);
+        }
+    }
+
+    Ok(snarl)
+}
+
+fn fractal(module: Node<'_, '_>) -> anyhow::Result<FractalNode> {
+    let default = FractalNode::default();
+
+    Ok(FractalNode {
+        source_ty: SourceType::Perlin,
+        seed: NodeValue::Value(attr(module, "seed", 0)?),
+        octaves: NodeValue::Value(attr(module, "octaveCount", 6)?),
+        frequency: NodeValue::Value(attr(module, "frequency", 1.0)?),
+        lacunarity: NodeValue::Value(attr(module, "lacunarity", 2.0)?),
+        persistence: NodeValue::Value(attr(module, "persistence", 0.5)?),
+        ..default
+    })
+}
+
+pub fn libnoise_file_dialog() -> FileDialog {
+    FileDialog::new().add_filter("libnoise XML", &["xml"])
+}
+
+/// Reads a libnoise module description from an XML file.
+///
+/// Each `<module>` element requires unique `id` and `type` attributes; module parameters use the
+/// libnoise names (`frequency`, `octaveCount`, `lowerBound`, etc.) and sources are referenced by id
+/// using the `source0`, `source1` and `control` attributes. Terrace and Curve modules list their
+/// control points as child `<controlPoint value=".."/>` or `<controlPoint input=".." output=".."/>`
+/// elements.
+pub fn open_libnoise(path: impl AsRef<Path>) -> anyhow::Result<Snarl<NoiseNode>> {
+    let text = read_to_string(path).inspect_err(|_| warn!("Unable to open file"))?;
+
+    parse_libnoise(&text).inspect_err(|err| warn!("Unable to import file: {err:#}"))
+}
+
+pub fn parse_libnoise(text: &str) -> anyhow::Result<Snarl<NoiseNode>> {
+    let doc = Document::parse(text).context("Parsing XML")?;
+    let modules = doc
+        .descendants()
+        .filter(|node| node.has_tag_name("module"))
+        .collect::<Vec<_>>();
+
+    let mut module_indices = HashMap::with_capacity(modules.len());
+    for (idx, module) in modules.iter().enumerate() {
+        let id = module.attribute("id").context("Module without `id`")?;
+        if module_indices.insert(id, idx).is_some() {
+            bail!("Duplicate module id `{id}`");
+        }
+    }
+
+    let mut pending = Vec::with_capacity(modules.len());
+    let mut constants = vec![];
+
+    for &module in &modules {
+        let id = module.attribute("id").unwrap();
+        let ty = module
+            .attribute("type")
+            .with_context(|| format!("Module `{id}` without `type`"))?;
+
+        let (node, sources): (_, &[&str]) = match ty {
+            "Abs" => (NoiseNode::Abs(Default::default()), &["source0"]),
+            "Add" => (NoiseNode::Add(Default::default()), &["source0", "source1"]),
+            "Billow" => (NoiseNode::Billow(fractal(module)?), &[]),
+            "Blend" => (
+                NoiseNode::Blend(Default::default()),
+                &["source0", "source1", "control"],
+            ),
+            "Cache" => (NoiseNode::Cache(Default::default()), &["source0"]),
+            "Checkerboard" => (
+                NoiseNode::Checkerboard(CheckerboardNode {
+                    size: NodeValue::Value(attr(module, "size", 0)?),
+                    ..Default::default()
+                }),
+                &[],
+            ),
+            "Clamp" => (
+                NoiseNode::Clamp(ClampNode {
+                    lower_bound: NodeValue::Value(attr(module, "lowerBound", -1.0)?),
+                    upper_bound: NodeValue::Value(attr(module, "upperBound", 1.0)?),
+                    ..Default::default()
+                }),
+                &["source0"],
+            ),
+            "Curve" => (
+                NoiseNode::Curve(CurveNode {
+                    points: Vec::new(),
+                    editor: false,
+                    ..Default::default()
+                }),
+                &["source0"],
+            ),
+            "Cylinders" => (
+                NoiseNode::Cylinders(CylindersNode {
+                    frequency: NodeValue::Value(attr(module, "frequency", 1.0)?),
+                    ..Default::default()
+                }),
+                &[],
+            ),
+            "Displace" => (
+                NoiseNode::Displace(Default::default()),
+                &["source0", "xDisplace", "yDisplace", "zDisplace"],
+            ),
+            "Exponent" => (
+                NoiseNode::Exponent(ExponentNode {
+                    exponent: NodeValue::Value(attr(module, "exponent", 1.0)?),
+                    ..Default::default()
+                }),
+                &["source0"],
+            ),
+            "Invert" => (NoiseNode::Negate(Default::default()), &["source0"]),
+            "Max" => (NoiseNode::Max(Default::default()), &["source0", "source1"]),
+            "Min" => (NoiseNode::Min(Default::default()), &["source0", "source1"]),
+            "Multiply" => (
+                NoiseNode::Multiply(Default::default()),
+                &["source0", "source1"],
+            ),
+            "Perlin" => (NoiseNode::Fbm(fractal(module)?), &[]),
+            "Power" => (
+                NoiseNode::Power(Default::default()),
+                &["source0", "source1"],
+            ),
+            "RidgedMulti" => {
+                let default = RigidFractalNode::default();
+                (
+                    NoiseNode::RigidMulti(RigidFractalNode {
+                        source_ty: SourceType::Perlin,
+                        seed: NodeValue::Value(attr(module, "seed", 0)?),
+                        octaves: NodeValue::Value(attr(module, "octaveCount", 6)?),
+                        frequency: NodeValue::Value(attr(module, "frequency", 1.0)?),
+                        lacunarity: NodeValue::Value(attr(module, "lacunarity", 2.0)?),
+                        ..default
+                    }),
+                    &[],
+                )
+            }
+            "RotatePoint" => (
+                NoiseNode::RotatePoint(TransformNode {
+                    image: Default::default(),
+                    axes: [
+                        NodeValue::Value(attr(module, "xAngle", 0.0)?),
+                        NodeValue::Value(attr(module, "yAngle", 0.0)?),
+                        NodeValue::Value(attr(module, "zAngle", 0.0)?),
+                        NodeValue::Value(0.0),
+                    ],
+                    vector_node_id: None,
+                }),
+                &["source0"],
+            ),
+            "ScaleBias" => (
+                NoiseNode::ScaleBias(ScaleBiasNode {
+                    scale: NodeValue::Value(attr(module, "scale", 1.0)?),
+                    bias: NodeValue::Value(attr(module, "bias", 0.0)?),
+                    ..Default::default()
+                }),
+                &["source0"],
+            ),
+            "ScalePoint" => (
+                NoiseNode::ScalePoint(TransformNode {
+                    image: Default::default(),
+                    axes: [
+                        NodeValue::Value(attr(module, "xScale", 1.0)?),
+                        NodeValue::Value(attr(module, "yScale", 1.0)?),
+                        NodeValue::Value(attr(module, "zScale", 1.0)?),
+                        NodeValue::Value(1.0),
+                    ],
+                    vector_node_id: None,
+                }),
+                &["source0"],
+            ),
+            "Select" => (
+                NoiseNode::Select(SelectNode {
+                    lower_bound: NodeValue::Value(attr(module, "lowerBound", -1.0)?),
+                    upper_bound: NodeValue::Value(attr(module, "upperBound", 1.0)?),
+                    falloff: NodeValue::Value(attr(module, "edgeFalloff", 0.0)?),
+                    ..Default::default()
+                }),
+                &["source0", "source1", "control"],
+            ),
+            "Terrace" => (
+                NoiseNode::Terrace(TerraceNode {
+                    inverted: attr(module, "invert", false)?,
+                    points: module
+                        .children()
+                        .filter(|node| node.has_tag_name("controlPoint"))
+                        .map(|control_point| attr(control_point, "value", 0.0))
+                        .collect::<anyhow::Result<_>>()?,
+                    editor: false,
+                    ..Default::default()
+                }),
+                &["source0"],
+            ),
+            "TranslatePoint" => (
+                NoiseNode::TranslatePoint(TransformNode {
+                    image: Default::default(),
+                    axes: [
+                        NodeValue::Value(attr(module, "xTranslation", 0.0)?),
+                        NodeValue::Value(attr(module, "yTranslation", 0.0)?),
+                        NodeValue::Value(attr(module, "zTranslation", 0.0)?),
+                        NodeValue::Value(0.0),
+                    ],
+                    vector_node_id: None,
+                }),
+                &["source0"],
+            ),
+            "Turbulence" => (
+                NoiseNode::Turbulence(TurbulenceNode {
+                    source_ty: SourceType::Perlin,
+                    seed: NodeValue::Value(attr(module, "seed", 0)?),
+                    frequency: NodeValue::Value(attr(module, "frequency", 1.0)?),
+                    power: NodeValue::Value(attr(module, "power", 1.0)?),
+                    roughness: NodeValue::Value(attr(module, "roughness", 3)?),
+                    ..Default::default()
+                }),
+                &["source0"],
+            ),
+            "Voronoi" => (
+                NoiseNode::Worley(WorleyNode {
+                    seed: NodeValue::Value(attr(module, "seed", 0)?),
+                    frequency: NodeValue::Value(attr(module, "frequency", 1.0)?),
+                    distance_fn: DistanceFunction::Euclidean,
+                    return_ty: if attr(module, "enableDistance", false)? {
+                        ReturnType::Distance
+                    } else {
+                        ReturnType::Value
+                    },
+                    ..Default::default()
+                }),
+                &[],
+            ),
+            _ => bail!("Module `{id}` has unsupported type `{ty}`"),
+        };
+
+        let mut inputs = vec![];
+
+        for (input, source) in sources.iter().enumerate() {
+            if let Some(source_id) = module.attribute(*source) {
+                let &source_idx = module_indices
+                    .get(source_id)
+                    .with_context(|| format!("Module `{id}` references unknown `{source_id}`"))?;
+                inputs.push((input, source_idx));
+            }
+        }
+
+        // Curve control points become nodes connected after the source pin; terraces keep their
+        // control points in the node, as constant nodes would be named variables
+        let control_points = module
+            .children()
+            .filter(|node| node.has_tag_name("controlPoint"));
+        for (idx, control_point) in control_points.enumerate() {
+            let constant = match &node {
+                NoiseNode::Curve(_) => NoiseNode::ControlPoint(ControlPointNode {
+                    input: NodeValue::Value(attr(control_point, "input", 0.0)?),
+                    output: NodeValue::Value(attr(control_point, "output", 0.0)?),
+                    ..Default::default()
+                }),
+                NoiseNode::Terrace(_) => break,
+                _ => bail!("Module `{id}` does not support control points"),
+            };
+
+            inputs.push((idx + 1, modules.len() + constants.len()));
+            constants.push(PendingNode {
+                node: constant,
+                inputs: vec![],
+            });
+        }
+
+        pending.push(PendingNode { node, inputs });
+    }
+
+    pending.extend(constants);
+
+    build(pending)
+}
+
+#[derive(Clone, Copy, Deserialize)]
+enum FastNoiseLiteCellularDistanceFunction {
+    Euclidean,
+    EuclideanSq,
+    Manhattan,
+    Hybrid,
+}
+
+#[derive(Clone, Copy, Deserialize)]
+enum FastNoiseLiteCellularReturnType {
+    CellValue,
+    Distance,
+    Distance2,
+    Distance2Add,
+    Distance2Sub,
+    Distance2Mul,
+    Distance2Div,
+}
+
+#[derive(Clone, Copy, Deserialize)]
+enum FastNoiseLiteDomainWarpType {
+    OpenSimplex2,
+    OpenSimplex2Reduced,
+    BasicGrid,
+}
+
+#[derive(Clone, Copy, Deserialize, PartialEq)]
+enum FastNoiseLiteFractalType {
+    None,
+    FBm,
+    Ridged,
+    PingPong,
+    DomainWarpProgressive,
+    DomainWarpIndependent,
+}
+
+#[derive(Clone, Copy, Deserialize)]
+enum FastNoiseLiteNoiseType {
+    OpenSimplex2,
+    OpenSimplex2S,
+    Cellular,
+    Perlin,
+    ValueCubic,
+    Value,
+}
+
+/// FastNoiseLite settings, named after the corresponding `FastNoiseLite` setters.
+#[derive(Deserialize)]
+#[serde(default, rename_all = "PascalCase")]
+struct FastNoiseLite {
+    seed: i32,
+    frequency: f64,
+    noise_type: FastNoiseLiteNoiseType,
+    fractal_type: FastNoiseLiteFractalType,
+    fractal_octaves: u32,
+    fractal_lacunarity: f64,
+    fractal_gain: f64,
+    cellular_distance_function: FastNoiseLiteCellularDistanceFunction,
+    cellular_return_type: FastNoiseLiteCellularReturnType,
+    domain_warp_type: Option<FastNoiseLiteDomainWarpType>,
+    domain_warp_amp: f64,
+    domain_warp_frequency: Option<f64>,
+}
+
+impl FastNoiseLite {
+    fn has_domain_warp(&self) -> bool {
+        self.domain_warp_type.is_some() || self.has_fractal_domain_warp()
+    }
+
+    fn has_fractal_domain_warp(&self) -> bool {
+        matches!(
+            self.fractal_type,
+            FastNoiseLiteFractalType::DomainWarpProgressive
+                | FastNoiseLiteFractalType::DomainWarpIndependent
+        )
+    }
+
+    fn noise(&self, pending: &mut Vec<PendingNode>) -> usize {
+        let source_ty = match self.noise_type {
+            FastNoiseLiteNoiseType::OpenSimplex2 => SourceType::OpenSimplex,
+            FastNoiseLiteNoiseType::OpenSimplex2S => SourceType::SuperSimplex,
+            FastNoiseLiteNoiseType::Cellular => SourceType::Worley,
+            FastNoiseLiteNoiseType::Perlin => SourceType::Perlin,
+            FastNoiseLiteNoiseType::ValueCubic | FastNoiseLiteNoiseType::Value => SourceType::Value,
+        };
+        let seed = self.seed as u32;
+
+        match self.fractal_type {
+            FastNoiseLiteFractalType::FBm | FastNoiseLiteFractalType::PingPong => {
+                if self.fractal_type == FastNoiseLiteFractalType::PingPong {
+                    warn!("PingPong fractal imported as FBm");
+                }
+
+                Self::push(
+                    pending,
+                    NoiseNode::Fbm(self.fractal(source_ty, seed)),
+                    vec![],
+                )
+            }
+            FastNoiseLiteFractalType::Ridged => {
+                let fractal = self.fractal(source_ty, seed);
+                let node = NoiseNode::RigidMulti(RigidFractalNode {
+                    source_ty,
+                    seed: fractal.seed,
+                    octaves: fractal.octaves,
+                    frequency: fractal.frequency,
+                    lacunarity: fractal.lacunarity,
+                    persistence: fractal.persistence,
+                    ..Default::default()
+                });
+
+                Self::push(pending, node, vec![])
+            }
+            _ if source_ty == SourceType::Worley => {
+                let distance_fn = match self.cellular_distance_function {
+                    FastNoiseLiteCellularDistanceFunction::Euclidean => DistanceFunction::Euclidean,
+                    FastNoiseLiteCellularDistanceFunction::EuclideanSq => {
+                        DistanceFunction::EuclideanSquared
+                    }
+                    FastNoiseLiteCellularDistanceFunction::Manhattan => DistanceFunction::Manhattan,
+                    FastNoiseLiteCellularDistanceFunction::Hybrid => {
+                        warn!("Hybrid cellular distance imported as Euclidean");
+
+                        DistanceFunction::Euclidean
+                    }
+                };
+                let return_ty = match self.cellular_return_type {
+                    FastNoiseLiteCellularReturnType::CellValue => ReturnType::Value,
+                    FastNoiseLiteCellularReturnType::Distance => ReturnType::Distance,
+                    FastNoiseLiteCellularReturnType::Distance2 => ReturnType::Distance2,
+                    FastNoiseLiteCellularReturnType::Distance2Add => ReturnType::Distance2Add,
+                    FastNoiseLiteCellularReturnType::Distance2Sub => ReturnType::Distance2Sub,
+                    FastNoiseLiteCellularReturnType::Distance2Mul => ReturnType::Distance2Mul,
+                    FastNoiseLiteCellularReturnType::Distance2Div => ReturnType::Distance2Div,
+                };
+                let node = NoiseNode::Worley(WorleyNode {
+                    seed: NodeValue::Value(seed),
+                    frequency: NodeValue::Value(self.frequency),
+                    distance_fn,
+                    return_ty,
+                    ..Default::default()
+                });
+
+                Self::push(pending, node, vec![])
+            }
+            _ => Self::scaled_generator(pending, source_ty, seed, self.frequency),
+        }
+    }
+
+    fn fractal(&self, source_ty: SourceType, seed: u32) -> FractalNode {
+        FractalNode {
+            image: Default::default(),
+            source_ty,
+            seed: NodeValue::Value(seed),
+            octaves: NodeValue::Value(self.fractal_octaves),
+            frequency: NodeValue::Value(self.frequency),
+            lacunarity: NodeValue::Value(self.fractal_lacunarity),
+            persistence: NodeValue::Value(self.fractal_gain),
+            weights: Vec::new(),
+        }
+    }
+
+    fn push(pending: &mut Vec<PendingNode>, node: NoiseNode, inputs: Vec<(usize, usize)>) -> usize {
+        pending.push(PendingNode { node, inputs });
+        pending.len() - 1
+    }
+
+    /// Generator nodes have no frequency so they are wrapped in a `ScalePoint` node.
+    fn scaled_generator(
+        pending: &mut Vec<PendingNode>,
+        source_ty: SourceType,
+        seed: u32,
+        frequency: f64,
+    ) -> usize {
+        let generator = GeneratorNode {
+            image: Default::default(),
+            seed: NodeValue::Value(seed),
+        };
+        let generator = Self::push(
+            pending,
+            match source_ty {
+                SourceType::OpenSimplex => NoiseNode::OpenSimplex(generator),
+                SourceType::Perlin => NoiseNode::Perlin(generator),
+                SourceType::PerlinSurflet => NoiseNode::PerlinSurflet(generator),
+                SourceType::Simplex => NoiseNode::Simplex(generator),
+                SourceType::SuperSimplex => NoiseNode::SuperSimplex(generator),
+                SourceType::Value => NoiseNode::Value(generator),
+                SourceType::Worley => NoiseNode::Worley(WorleyNode {
+                    seed: generator.seed,
+                    ..Default::default()
+                }),
+            },
+            vec![],
+        );
+        let node = NoiseNode::ScalePoint(TransformNode {
+            image: Default::default(),
+            axes: [
+                NodeValue::Value(frequency),
+                NodeValue::Value(frequency),
+                NodeValue::Value(frequency),
+                NodeValue::Value(1.0),
+            ],
+            vector_node_id: None,
+        });
+
+        Self::push(pending, node, vec![(0, generator)])
+    }
+
+    /// Domain warping offsets the X and Y coordinates using two additional noise sources which are
+    /// scaled by the warp amplitude.
+    fn warp(&self, pending: &mut Vec<PendingNode>, source: usize) -> usize {
+        let source_ty = match self
+            .domain_warp_type
+            .unwrap_or(FastNoiseLiteDomainWarpType::OpenSimplex2)
+        {
+            FastNoiseLiteDomainWarpType::OpenSimplex2
+            | FastNoiseLiteDomainWarpType::OpenSimplex2Reduced => SourceType::OpenSimplex,
+            FastNoiseLiteDomainWarpType::BasicGrid => SourceType::Value,
+        };
+        let frequency = self.domain_warp_frequency.unwrap_or(self.frequency);
+
+        let mut inputs = vec![(0, source)];
+
+        for axis in 0..2 {
+            let seed = (self.seed as u32).wrapping_add(axis as u32);
+            let offset = if self.has_fractal_domain_warp() {
+                let fractal = FractalNode {
+                    frequency: NodeValue::Value(frequency),
+                    ..self.fractal(source_ty, seed)
+                };
+
+                Self::push(pending, NoiseNode::Fbm(fractal), vec![])
+            } else {
+                Self::scaled_generator(pending, source_ty, seed, frequency)
+            };
+            let node = NoiseNode::ScaleBias(ScaleBiasNode {
+                scale: NodeValue::Value(self.domain_warp_amp),
+                bias: NodeValue::Value(0.0),
+                ..Default::default()
+            });
+
+            inputs.push((axis + 1, Self::push(pending, node, vec![(0, offset)])));
+        }
+
+        Self::push(pending, NoiseNode::Displace(Default::default()), inputs)
+    }
+}
+
+impl Default for FastNoiseLite {
+    fn default() -> Self {
+        Self {
+            seed: 1337,
+            frequency: 0.01,
+            noise_type: FastNoiseLiteNoiseType::OpenSimplex2,
+            fractal_type: FastNoiseLiteFractalType::None,
+            fractal_octaves: 3,
+            fractal_lacunarity: 2.0,
+            fractal_gain: 0.5,
+            cellular_distance_function: FastNoiseLiteCellularDistanceFunction::EuclideanSq,
+            cellular_return_type: FastNoiseLiteCellularReturnType::Distance,
+            domain_warp_type: None,
+            domain_warp_amp: 1.0,
+            domain_warp_frequency: None,
+        }
+    }
+}
+
+pub fn fast_noise_lite_file_dialog() -> FileDialog {
+    FileDialog::new().add_filter("FastNoiseLite JSON", &["json"])
+}
+
+/// Reads FastNoiseLite settings from a JSON file.
+///
+/// Keys are named after the `FastNoiseLite` setters (`Seed`, `Frequency`, `NoiseType`,
+/// `FractalType`, `DomainWarpAmp`, etc.) and missing keys use the FastNoiseLite defaults.
+pub fn open_fast_noise_lite(path: impl AsRef<Path>) -> anyhow::Result<Snarl<NoiseNode>> {
+    let text = read_to_string(path).inspect_err(|_| warn!("Unable to open file"))?;
+
+    parse_fast_noise_lite(&text).inspect_err(|err| warn!("Unable to import file: {err:#}"))
+}
+
+fn parse_fast_noise_lite(text: &str) -> anyhow::Result<Snarl<NoiseNode>> {
+    let settings: FastNoiseLite = serde_json::from_str(text).context("Parsing JSON")?;
+    let mut pending = vec![];
+    let noise = settings.noise(&mut pending);
+
+    if settings.has_domain_warp() {
+        settings.warp(&mut pending, noise);
+    }
+
+    build(pending)
+}
@@ -0,0 +1,719 @@
+use {
+    super::{
+        expr::{
+            CompareType, DistanceFunction, EasingCurve, FormulaFn, GradientType, ImageTiling,
+            NormalComponent, ReturnType, SourceType, SwizzleComponent, TerraceEasing,
+            MAX_FAULT_ITERATIONS, MAX_FRACTAL_OCTAVES, MAX_WARP_ITERATIONS,
+        },
+        formula,
+        node::{ColorRamp, ConstantOpNode, NodeValue, NoiseNode, TimeNode},
+    },
+    egui::{
+        color_picker, Button, Color32, ComboBox, DragValue, ScrollArea, Slider, SliderClamping,
+        TextEdit, Ui,
+    },
+    egui_snarl::{NodeId, Snarl},
+    noise::{BasicMulti, Perlin},
+    std::{collections::HashSet, fmt::Debug, ops::RangeInclusive},
+};
+
+const AXES: [&str; 4] = ["X", "Y", "Z", "W"];
+
+const SOURCE_TYPES: [SourceType; 7] = [
+    SourceType::OpenSimplex,
+    SourceType::Perlin,
+    SourceType::PerlinSurflet,
+    SourceType::Simplex,
+    SourceType::SuperSimplex,
+    SourceType::Value,
+    SourceType::Worley,
+];
+
+/// Shows a combo box filling the width of the panel; returns `true` if the value was changed.
+fn combo_box<T>(ui: &mut Ui, label: &str, value: &mut T, values: &[T]) -> bool
+where
+    T: Copy + Debug + PartialEq,
+{
+    let mut changed = false;
+
+    ui.label(label);
+    ComboBox::from_id_salt(label)
+        .selected_text(format!("{value:?}"))
+        .width(ui.available_width())
+        .show_ui(ui, |ui| {
+            for &option in values {
+                changed |= ui
+                    .selectable_value(value, option, format!("{option:?}"))
+                    .changed();
+            }
+        });
+
+    changed
+}
+
+/// Shows a slider, or a drag value if `range` is `None`, for a value which may instead be connected
+/// to a constant node; returns `true` if the value was changed.
+fn f64_value(
+    ui: &mut Ui,
+    label: &str,
+    value: &mut NodeValue<f64>,
+    range: Option<RangeInclusive<f64>>,
+) -> bool {
+    ui.label(label);
+
+    let NodeValue::Value(value) = value else {
+        ui.weak("Connected to a constant node");
+
+        return false;
+    };
+
+    if let Some(range) = range {
+        slider(
+            ui,
+            Slider::new(value, range).clamping(SliderClamping::Never),
+        )
+    } else {
+        drag_value(
+            ui,
+            DragValue::new(value)
+                .min_decimals(2)
+                .max_decimals(2)
+                .speed(0.01),
+        )
+    }
+}
+
+/// See `f64_value`.
+fn u32_value(
+    ui: &mut Ui,
+    label: &str,
+    value: &mut NodeValue<u32>,
+    range: Option<RangeInclusive<u32>>,
+) -> bool {
+    ui.label(label);
+
+    let NodeValue::Value(value) = value else {
+        ui.weak("Connected to a constant node");
+
+        return false;
+    };
+
+    if let Some(range) = range {
+        slider(ui, Slider::new(value, range))
+    } else {
+        drag_value(ui, DragValue::new(value))
+    }
+}
+
+fn drag_value(ui: &mut Ui, drag_value: DragValue<'_>) -> bool {
+    ui.add_sized(
+        [ui.available_width(), ui.spacing().interact_size.y],
+        drag_value,
+    )
+    .changed()
+}
+
+fn slider(ui: &mut Ui, slider: Slider<'_>) -> bool {
+    // Leaves room for the text box showing the value
+    ui.spacing_mut().slider_width =
+        (ui.available_width() - ui.spacing().interact_size.x - ui.spacing().item_spacing.x)
+            .max(0.0);
+
+    ui.add(slider).changed()
+}
+
+/// Shows every parameter of the given node using widgets which fill the width of the panel.
+///
+/// The node is added to `updated_node_ids` when a parameter which affects its output is changed.
+pub fn show(
+    ui: &mut Ui,
+    node_id: NodeId,
+    snarl: &mut Snarl<NoiseNode>,
+    updated_node_ids: &mut HashSet<NodeId>,
+) {
+    let Some(node) = snarl.get_node_mut(node_id) else {
+        return;
+    };
+
+    ScrollArea::vertical().show(ui, |ui| show_node(ui, node_id, node, updated_node_ids));
+}
+
+fn show_node(
+    ui: &mut Ui,
+    node_id: NodeId,
+    node: &mut NoiseNode,
+    updated_node_ids: &mut HashSet<NodeId>,
+) {
+    ui.heading(node.name());
+
+    if let Some(title) = node.title_mut() {
+        let mut text = title.clone().unwrap_or_default();
+
+        ui.label("Title");
+        if ui
+            .add(TextEdit::singleline(&mut text).desired_width(f32::INFINITY))
+            .changed()
+        {
+            *title = (!text.is_empty()).then_some(text);
+        }
+    }
+
+    ui.separator();
+
+    let mut changed = false;
+
+    match node {
+        NoiseNode::BasicMulti(node)
+        | NoiseNode::Billow(node)
+        | NoiseNode::Fbm(node)
+        | NoiseNode::HybridMulti(node)
+        | NoiseNode::Jordan(node)
+        | NoiseNode::Swiss(node) => {
+            changed |= combo_box(ui, "Source", &mut node.source_ty, &SOURCE_TYPES);
+            changed |= u32_value(ui, "Seed", &mut node.seed, None);
+            changed |= u32_value(
+                ui,
+                "Octaves",
+                &mut node.octaves,
+                Some(1..=MAX_FRACTAL_OCTAVES),
+            );
+            changed |= f64_value(ui, "Frequency", &mut node.frequency, Some(0.0..=16.0));
+            changed |= f64_value(ui, "Lacunarity", &mut node.lacunarity, Some(0.0..=4.0));
+            changed |= f64_value(ui, "Persistence", &mut node.persistence, Some(0.0..=1.0));
+
+            // Octaves without a weight of their own follow the persistence
+            let persistence = match node.persistence {
+                NodeValue::Node(_) => BasicMulti::<Perlin>::DEFAULT_PERSISTENCE,
+                NodeValue::Value(persistence) => persistence,
+            };
+            let octave_weight = |weights: &[f64], octave: usize| {
+                weights
+                    .get(octave)
+                    .copied()
+                    .unwrap_or_else(|| persistence.powi(octave as _))
+            };
+            let octaves = match node.octaves {
+                NodeValue::Node(_) => node.weights.len(),
+                NodeValue::Value(octaves) => octaves.clamp(1, MAX_FRACTAL_OCTAVES) as usize,
+            };
+
+            let mut weighted = !node.weights.is_empty();
+            if ui.checkbox(&mut weighted, "Octave Weights").changed() {
+                node.weights = if weighted {
+                    (0..octaves.max(1))
+                        .map(|octave| octave_weight(&[], octave))
+                        .collect()
+                } else {
+                    Vec::new()
+                };
+                changed = true;
+            }
+
+            if weighted {
+                for octave in 0..octaves {
+                    let mut weight = octave_weight(&node.weights, octave);
+
+                    ui.horizontal(|ui| {
+                        ui.label(format!("Octave {}", octave + 1));
+
+                        if ui
+                            .add(DragValue::new(&mut weight).max_decimals(3).speed(0.01))
+                            .changed()
+                        {
+                            while node.weights.len() <= octave {
+                                node.weights
+                                    .push(octave_weight(&node.weights, node.weights.len()));
+                            }
+
+                            node.weights[octave] = weight;
+                            changed = true;
+                        }
+                    });
+                }
+
+                ui.weak("fBm and Billow results are divided by the total weight");
+            }
+        }
+        NoiseNode::GenericFractal(node) => {
+            changed |= u32_value(
+                ui,
+                "Octaves",
+                &mut node.octaves,
+                Some(1..=MAX_FRACTAL_OCTAVES),
+            );
+            changed |= f64_value(ui, "Frequency", &mut node.frequency, Some(0.0..=16.0));
+            changed |= f64_value(ui, "Lacunarity", &mut node.lacunarity, Some(0.0..=4.0));
+            changed |= f64_value(ui, "Persistence", &mut node.persistence, Some(0.0..=1.0));
+        }
+        NoiseNode::RigidMulti(node) => {
+            changed |= combo_box(ui, "Source", &mut node.source_ty, &SOURCE_TYPES);
+            changed |= u32_value(ui, "Seed", &mut node.seed, None);
+            changed |= u32_value(
+                ui,
+                "Octaves",
+                &mut node.octaves,
+                Some(1..=MAX_FRACTAL_OCTAVES),
+            );
+            changed |= f64_value(ui, "Frequency", &mut node.frequency, Some(0.0..=16.0));
+            changed |= f64_value(ui, "Lacunarity", &mut node.lacunarity, Some(0.0..=4.0));
+            changed |= f64_value(ui, "Persistence", &mut node.persistence, Some(0.0..=1.0));
+            changed |= f64_value(ui, "Attenuation", &mut node.attenuation, Some(0.0..=4.0));
+        }
+        NoiseNode::BlueNoise(node)
+        | NoiseNode::OpenSimplex(node)
+        | NoiseNode::Perlin(node)
+        | NoiseNode::PerlinSurflet(node)
+        | NoiseNode::Simplex(node)
+        | NoiseNode::SuperSimplex(node)
+        | NoiseNode::Value(node) => {
+            changed |= u32_value(ui, "Seed", &mut node.seed, None);
+        }
+        NoiseNode::BiasGain(node) => {
+            changed |= f64_value(ui, "Bias", &mut node.bias, Some(0.0..=1.0));
+            changed |= f64_value(ui, "Gain", &mut node.gain, Some(0.0..=1.0));
+        }
+        NoiseNode::Checkerboard(node) => {
+            changed |= u32_value(ui, "Size", &mut node.size, Some(0..=8));
+        }
+        NoiseNode::Clamp(node) => {
+            changed |= f64_value(ui, "Lower Bound", &mut node.lower_bound, Some(-1.0..=1.0));
+            changed |= f64_value(ui, "Upper Bound", &mut node.upper_bound, Some(-1.0..=1.0));
+        }
+        NoiseNode::ColorRamp(node) => {
+            let mut removed = None;
+
+            for idx in 0..node.ramp.stops().len() {
+                ui.label(format!("Stop {}", idx + 1));
+                ui.horizontal(|ui| {
+                    changed |= ui
+                        .color_edit_button_srgba(&mut node.ramp.stops_mut()[idx].color)
+                        .changed();
+
+                    let mut position = node.ramp.stops()[idx].position;
+                    if ui
+                        .add(DragValue::new(&mut position).range(0.0..=1.0).speed(0.01))
+                        .changed()
+                    {
+                        node.ramp.set_position(idx, position);
+                        changed = true;
+                    }
+
+                    if ui
+                        .add_enabled(node.ramp.stops().len() > 2, Button::new("Remove"))
+                        .clicked()
+                    {
+                        removed = Some(idx);
+                    }
+                });
+            }
+
+            if let Some(idx) = removed {
+                node.ramp.remove(idx);
+                changed = true;
+            }
+
+            if ui
+                .add_enabled(
+                    node.ramp.stops().len() < ColorRamp::MAX_STOPS,
+                    Button::new("Add Stop"),
+                )
+                .clicked()
+            {
+                // The new stop splits the widest gap between two stops
+                let (start, end) = node
+                    .ramp
+                    .stops()
+                    .windows(2)
+                    .map(|pair| (pair[0].position, pair[1].position))
+                    .max_by(|lhs, rhs| (lhs.1 - lhs.0).total_cmp(&(rhs.1 - rhs.0)))
+                    .unwrap();
+                changed |= node.ramp.insert((start + end) / 2.0).is_some();
+            }
+        }
+        NoiseNode::Comment(node) => {
+            ui.label("Color");
+            color_picker::color_edit_button_srgba(
+                ui,
+                &mut node.color,
+                color_picker::Alpha::OnlyBlend,
+            );
+            ui.label("Text");
+            ui.add(TextEdit::multiline(&mut node.text).desired_width(f32::INFINITY));
+        }
+        NoiseNode::Compare(node) => {
+            changed |= combo_box(ui, "Comparison", &mut node.compare_ty, &CompareType::ALL);
+            changed |= f64_value(ui, "Tolerance", &mut node.tolerance, Some(0.0..=1.0));
+        }
+        NoiseNode::Contrast(node) => {
+            changed |= f64_value(ui, "Contrast", &mut node.contrast, Some(0.0..=4.0));
+            changed |= f64_value(ui, "Pivot", &mut node.pivot, Some(-1.0..=1.0));
+            changed |= ui.checkbox(&mut node.clamped, "Clamped").changed();
+        }
+        NoiseNode::ControlPoint(node) => {
+            changed |= f64_value(ui, "Input", &mut node.input, Some(-1.0..=1.0));
+            changed |= f64_value(ui, "Output", &mut node.output, Some(-1.0..=1.0));
+        }
+        NoiseNode::Crater(node) => {
+            changed |= u32_value(ui, "Seed", &mut node.seed, None);
+            changed |= f64_value(ui, "Density", &mut node.density, Some(0.0..=1.0));
+            changed |= f64_value(ui, "Radius", &mut node.radius, Some(0.0..=1.0));
+            changed |= f64_value(ui, "Depth", &mut node.depth, Some(0.0..=2.0));
+        }
+        NoiseNode::Curve(node) => {
+            ui.checkbox(&mut node.editor, "Editor")
+                .on_hover_text("Shows a plot of the curve with draggable points in the node");
+
+            let mut removed = None;
+
+            for (idx, [input, output]) in node.points.iter_mut().enumerate() {
+                ui.horizontal(|ui| {
+                    ui.label(format!("Point {}", idx + 1));
+                    changed |= ui
+                        .add(DragValue::new(input).max_decimals(3).speed(0.01))
+                        .changed();
+                    changed |= ui
+                        .add(DragValue::new(output).max_decimals(3).speed(0.01))
+                        .changed();
+
+                    if ui.small_button("-").on_hover_text("Remove").clicked() {
+                        removed = Some(idx);
+                    }
+                });
+            }
+
+            if let Some(idx) = removed {
+                node.points.remove(idx);
+                changed = true;
+            }
+
+            if ui.button("Add Point").clicked() {
+                node.points.push([0.0, 0.0]);
+                changed = true;
+            }
+
+            ui.weak("Connected control point nodes are used along with these points");
+        }
+        NoiseNode::Cylinders(node) => {
+            changed |= f64_value(ui, "Frequency", &mut node.frequency, Some(0.0..=16.0));
+        }
+        NoiseNode::DomainWarp(node) => {
+            changed |= f64_value(ui, "Amplitude", &mut node.amplitude, Some(0.0..=4.0));
+            changed |= f64_value(ui, "Frequency", &mut node.frequency, Some(0.0..=16.0));
+        }
+        NoiseNode::Ease(node) => {
+            changed |= combo_box(ui, "Curve", &mut node.curve, &EasingCurve::ALL);
+        }
+        NoiseNode::Erosion(node) => {
+            changed |= combo_box(ui, "Source", &mut node.source_ty, &SOURCE_TYPES);
+            changed |= u32_value(ui, "Seed", &mut node.seed, None);
+            changed |= u32_value(
+                ui,
+                "Octaves",
+                &mut node.octaves,
+                Some(1..=MAX_FRACTAL_OCTAVES),
+            );
+            changed |= f64_value(ui, "Frequency", &mut node.frequency, Some(0.0..=16.0));
+            changed |= f64_value(ui, "Lacunarity", &mut node.lacunarity, Some(0.0..=4.0));
+            changed |= f64_value(ui, "Persistence", &mut node.persistence, Some(0.0..=1.0));
+            changed |= f64_value(ui, "Strength", &mut node.strength, Some(0.0..=4.0));
+        }
+        NoiseNode::Exponent(node) => {
+            changed |= f64_value(ui, "Exponent", &mut node.exponent, Some(0.0..=4.0));
+        }
+        NoiseNode::FaultLines(node) => {
+            changed |= u32_value(ui, "Seed", &mut node.seed, None);
+            changed |= u32_value(
+                ui,
+                "Iterations",
+                &mut node.iterations,
+                Some(0..=MAX_FAULT_ITERATIONS),
+            );
+            changed |= f64_value(ui, "Amplitude", &mut node.amplitude, Some(0.0..=4.0));
+        }
+        NoiseNode::F64(node) => {
+            ui.label("Name");
+            ui.add(TextEdit::singleline(&mut node.name).desired_width(f32::INFINITY));
+            ui.label("Value");
+            changed |= drag_value(
+                ui,
+                DragValue::new(&mut node.value)
+                    .min_decimals(2)
+                    .max_decimals(2)
+                    .speed(0.01),
+            );
+        }
+        NoiseNode::F64Operation(ConstantOpNode { inputs, .. }) => {
+            for (label, input) in ["Left", "Right"].into_iter().zip(inputs) {
+                changed |= f64_value(ui, label, input, None);
+            }
+        }
+        NoiseNode::Formula(node) => {
+            ui.label("Formula");
+            changed |= ui
+                .add(
+                    TextEdit::multiline(&mut node.formula)
+                        .code_editor()
+                        .desired_rows(2)
+                        .desired_width(f32::INFINITY),
+                )
+                .changed();
+
+            if let Err(err) = formula::parse(&node.formula, node.source_count) {
+                ui.colored_label(Color32::RED, err.to_string());
+            }
+
+            ui.weak(format!(
+                "Use x, y, z, w, pi, tau, source (or s0), s1, …, the names of decimal \
+                constants and {}",
+                FormulaFn::ALL.map(FormulaFn::name).join(", ")
+            ));
+        }
+        NoiseNode::Gradient(node) => {
+            changed |= combo_box(
+                ui,
+                "Gradient Type",
+                &mut node.gradient_ty,
+                &[GradientType::Linear, GradientType::Radial],
+            );
+            changed |= f64_value(ui, "Angle", &mut node.angle, Some(-180.0..=180.0));
+            changed |= f64_value(ui, "Radius", &mut node.radius, Some(0.0..=16.0));
+        }
+        NoiseNode::ImageSource(node) => {
+            ui.label("Image");
+            if node.samples.is_empty() {
+                ui.weak("No image loaded");
+            } else {
+                ui.label(format!(
+                    "{} ({}×{})",
+                    node.file_name, node.width, node.height
+                ));
+            }
+            changed |= f64_value(ui, "Size", &mut node.size, Some(0.0..=16.0));
+            changed |= combo_box(ui, "Tiling", &mut node.tiling, &ImageTiling::ALL);
+        }
+        NoiseNode::MirrorPoint(node) => {
+            for (label, mirrored) in AXES.into_iter().zip(&mut node.axes) {
+                changed |= ui.checkbox(mirrored, format!("Mirror {label}")).changed();
+            }
+        }
+        NoiseNode::Mix(node) => {
+            changed |= f64_value(ui, "Factor", &mut node.factor, Some(0.0..=1.0));
+        }
+        NoiseNode::Normal(node) => {
+            changed |= combo_box(ui, "Component", &mut node.component, &NormalComponent::ALL);
+            changed |= f64_value(ui, "Epsilon", &mut node.epsilon, Some(0.001..=0.1));
+            changed |= f64_value(ui, "Strength", &mut node.strength, Some(0.0..=4.0));
+        }
+        NoiseNode::Normalize(node) => {
+            changed |= ui
+                .checkbox(&mut node.estimated, "Estimated Range")
+                .on_hover_text("Maps the range found by sampling the source instead of -1..1")
+                .changed();
+        }
+        NoiseNode::RandomConstant(node) => {
+            changed |= u32_value(ui, "Seed", &mut node.seed, None);
+            changed |= u32_value(ui, "Index", &mut node.index, None);
+            changed |= f64_value(ui, "Min", &mut node.min, None);
+            changed |= f64_value(ui, "Max", &mut node.max, None);
+        }
+        NoiseNode::Remap(node) => {
+            changed |= f64_value(ui, "In Min", &mut node.in_min, Some(-1.0..=1.0));
+            changed |= f64_value(ui, "In Max", &mut node.in_max, Some(-1.0..=1.0));
+            changed |= f64_value(ui, "Out Min", &mut node.out_min, Some(-1.0..=1.0));
+            changed |= f64_value(ui, "Out Max", &mut node.out_max, Some(-1.0..=1.0));
+            changed |= ui.checkbox(&mut node.clamped, "Clamped").changed();
+        }
+        NoiseNode::RotateAxis(node) => {
+            for (label, axis) in AXES.into_iter().zip(&mut node.axis) {
+                changed |= f64_value(ui, &format!("Axis {label}"), axis, Some(-1.0..=1.0));
+            }
+            changed |= f64_value(ui, "Angle", &mut node.angle, Some(-180.0..=180.0));
+        }
+        NoiseNode::RotatePoint(node) => {
+            if node.vector_node_id.is_some() {
+                ui.weak("Connected to a vector node, which overrides these axes");
+            }
+
+            for (label, axis) in AXES.into_iter().zip(&mut node.axes) {
+                changed |= f64_value(ui, label, axis, Some(-180.0..=180.0));
+            }
+        }
+        NoiseNode::ScaleBias(node) => {
+            changed |= f64_value(ui, "Scale", &mut node.scale, Some(-4.0..=4.0));
+            changed |= f64_value(ui, "Bias", &mut node.bias, Some(-1.0..=1.0));
+        }
+        NoiseNode::ScalePoint(node) | NoiseNode::TranslatePoint(node) => {
+            if node.vector_node_id.is_some() {
+                ui.weak("Connected to a vector node, which overrides these axes");
+            }
+
+            for (label, axis) in AXES.into_iter().zip(&mut node.axes) {
+                changed |= f64_value(ui, label, axis, None);
+            }
+        }
+        NoiseNode::Script(node) => {
+            ui.label("Script");
+            changed |= ui
+                .add(
+                    TextEdit::multiline(&mut node.script)
+                        .code_editor()
+                        .desired_rows(6)
+                        .desired_width(f32::INFINITY),
+                )
+                .changed();
+
+            if let Err(err) = node.check() {
+                ui.colored_label(Color32::RED, err);
+            }
+
+            ui.weak(
+                "Rhai script run for every sample with x, y, z, w, source, sources and the \
+                decimal constants it names. Scripts are much slower than other nodes.",
+            );
+        }
+        NoiseNode::Select(node) => {
+            changed |= f64_value(ui, "Lower Bound", &mut node.lower_bound, Some(-1.0..=1.0));
+            changed |= f64_value(ui, "Upper Bound", &mut node.upper_bound, Some(-1.0..=1.0));
+            changed |= f64_value(ui, "Falloff", &mut node.falloff, Some(0.0..=1.0));
+        }
+        NoiseNode::Slope(node) => {
+            changed |= f64_value(ui, "Epsilon", &mut node.epsilon, Some(0.001..=0.1));
+        }
+        NoiseNode::Switch(node) => {
+            let last_source = node.source_count.saturating_sub(1) as u32;
+            changed |= u32_value(ui, "Selector", &mut node.selector, Some(0..=last_source));
+        }
+        NoiseNode::Swizzle(node) => {
+            for (label, component) in AXES.into_iter().zip(&mut node.components) {
+                changed |= combo_box(ui, label, component, &SwizzleComponent::ALL);
+            }
+        }
+        NoiseNode::Terrace(node) => {
+            changed |= combo_box(ui, "Easing", &mut node.easing, &TerraceEasing::ALL);
+            changed |= ui.checkbox(&mut node.inverted, "Inverted").changed();
+            ui.checkbox(&mut node.editor, "Editor")
+                .on_hover_text("Shows a plot of the terrace with draggable points in the node");
+
+            let mut removed = None;
+
+            for (idx, value) in node.points.iter_mut().enumerate() {
+                ui.horizontal(|ui| {
+                    ui.label(format!("Point {}", idx + 1));
+                    changed |= ui
+                        .add(DragValue::new(value).max_decimals(3).speed(0.01))
+                        .changed();
+
+                    if ui.small_button("-").on_hover_text("Remove").clicked() {
+                        removed = Some(idx);
+                    }
+                });
+            }
+
+            if let Some(idx) = removed {
+                node.points.remove(idx);
+                changed = true;
+            }
+
+            if ui.button("Add Point").clicked() {
+                node.points.push(0.0);
+                changed = true;
+            }
+
+            ui.weak("Connected control point nodes are used along with these points");
+        }
+        NoiseNode::TilePoint(node) => {
+            for (label, period) in AXES.into_iter().zip(&mut node.periods) {
+                changed |= f64_value(ui, label, period, Some(0.0..=16.0));
+            }
+            changed |= ui.checkbox(&mut node.blended, "Blended").changed();
+        }
+        NoiseNode::Time(_) => {
+            ui.weak(format!(
+                "Follows the timeline; exported expressions name this value `{}`",
+                TimeNode::NAME
+            ));
+        }
+        NoiseNode::Turbulence(node) => {
+            changed |= combo_box(ui, "Source", &mut node.source_ty, &SOURCE_TYPES);
+            changed |= u32_value(ui, "Seed", &mut node.seed, None);
+            changed |= f64_value(ui, "Frequency", &mut node.frequency, Some(0.0..=16.0));
+            changed |= f64_value(ui, "Power", &mut node.power, Some(0.0..=1.0));
+            changed |= u32_value(ui, "Roughness", &mut node.roughness, Some(1..=8));
+        }
+        NoiseNode::U32(node) => {
+            ui.label("Name");
+            ui.add(TextEdit::singleline(&mut node.name).desired_width(f32::INFINITY));
+            ui.label("Value");
+            changed |= drag_value(ui, DragValue::new(&mut node.value));
+        }
+        NoiseNode::U32Operation(ConstantOpNode { inputs, .. }) => {
+            for (label, input) in ["Left", "Right"].into_iter().zip(inputs) {
+                changed |= u32_value(ui, label, input, None);
+            }
+        }
+        NoiseNode::Vector(node) => {
+            for (label, axis) in AXES.into_iter().zip(&mut node.axes) {
+                changed |= f64_value(ui, label, axis, None);
+            }
+        }
+        NoiseNode::WarpedFbm(node) => {
+            changed |= combo_box(ui, "Source", &mut node.source_ty, &SOURCE_TYPES);
+            changed |= u32_value(ui, "Seed", &mut node.seed, None);
+            changed |= u32_value(
+                ui,
+                "Octaves",
+                &mut node.octaves,
+                Some(1..=MAX_FRACTAL_OCTAVES),
+            );
+            changed |= f64_value(ui, "Frequency", &mut node.frequency, Some(0.0..=16.0));
+            changed |= f64_value(ui, "Lacunarity", &mut node.lacunarity, Some(0.0..=4.0));
+            changed |= f64_value(ui, "Persistence", &mut node.persistence, Some(0.0..=1.0));
+            changed |= f64_value(ui, "Strength", &mut node.strength, Some(0.0..=4.0));
+            changed |= u32_value(
+                ui,
+                "Iterations",
+                &mut node.iterations,
+                Some(0..=MAX_WARP_ITERATIONS),
+            );
+        }
+        NoiseNode::Worley(node) => {
+            changed |= u32_value(ui, "Seed", &mut node.seed, None);
+            changed |= f64_value(ui, "Frequency", &mut node.frequency, Some(0.0..=16.0));
+            changed |= combo_box(
+                ui,
+                "Distance Function",
+                &mut node.distance_fn,
+                &[
+                    DistanceFunction::Chebyshev,
+                    DistanceFunction::Euclidean,
+                    DistanceFunction::EuclideanSquared,
+                    DistanceFunction::Manhattan,
+                ],
+            );
+            changed |= combo_box(ui, "Return Type", &mut node.return_ty, &ReturnType::ALL);
+        }
+        NoiseNode::Abs(_)
+        | NoiseNode::Add(_)
+        | NoiseNode::Blend(_)
+        | NoiseNode::Cache(_)
+        | NoiseNode::CombineRgb(_)
+        | NoiseNode::Difference(_)
+        | NoiseNode::Displace(_)
+        | NoiseNode::Group(_)
+        | NoiseNode::Max(_)
+        | NoiseNode::Min(_)
+        | NoiseNode::Multiply(_)
+        | NoiseNode::Negate(_)
+        | NoiseNode::Operation(_)
+        | NoiseNode::Power(_)
+        | NoiseNode::Subgraph(_)
+        | NoiseNode::SubgraphInput(_)
+        | NoiseNode::SubgraphOutput(_) => {
+            ui.weak("This node has no parameters; its inputs are connected from other nodes");
+        }
+    }
+
+    if changed {
+        updated_node_ids.insert(node_id);
+    }
+}
@@ -0,0 +1,188 @@
+use {
+    eframe::{get_value, set_value, Storage},
+    egui::{Context, Event, Grid, Key, KeyboardShortcut, Modifiers, Window},
+    serde::{Deserialize, Serialize},
+    std::collections::BTreeMap,
+};
+
+/// An editor command which may be bound to a keyboard shortcut.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Ord, PartialEq, PartialOrd, Serialize)]
+pub enum Action {
+    Delete,
+    Duplicate,
+    FrameAll,
+    OpenPalette,
+    Search,
+    Undo,
+}
+
+impl Action {
+    pub const ALL: [Self; 6] = [
+        Self::Delete,
+        Self::Duplicate,
+        Self::FrameAll,
+        Self::OpenPalette,
+        Self::Search,
+        Self::Undo,
+    ];
+
+    fn default_shortcut(self) -> KeyboardShortcut {
+        match self {
+            Self::Delete => KeyboardShortcut::new(Modifiers::NONE, Key::Delete),
+            Self::Duplicate => KeyboardShortcut::new(Modifiers::COMMAND, Key::D),
+            Self::FrameAll => KeyboardShortcut::new(Modifiers::NONE, Key::F),
+            Self::OpenPalette => KeyboardShortcut::new(Modifiers::NONE, Key::Space),
+            Self::Search => KeyboardShortcut::new(Modifiers::COMMAND, Key::F),
+            Self::Undo => KeyboardShortcut::new(Modifiers::COMMAND, Key::Z),
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Delete => "Remove Selected Nodes",
+            Self::Duplicate => "Duplicate Selected Nodes",
+            Self::FrameAll => "Frame All Nodes",
+            Self::OpenPalette => "Add Node (Search)",
+            Self::Search => "Find Nodes",
+            Self::Undo => "Undo",
+        }
+    }
+}
+
+/// The keyboard shortcut bound to each action, stored with the application settings.
+#[derive(Deserialize, Serialize)]
+pub struct Keymap {
+    /// Actions which have been unbound map to `None`.
+    shortcuts: BTreeMap<Action, Option<KeyboardShortcut>>,
+}
+
+impl Keymap {
+    pub const KEY: &'static str = "keymap";
+
+    pub fn load(storage: Option<&dyn Storage>) -> Self {
+        let mut res: Self = storage
+            .and_then(|storage| get_value(storage, Self::KEY))
+            .unwrap_or_default();
+
+        // Actions added since the settings were stored use their default shortcut
+        for action in Action::ALL {
+            res.shortcuts
+                .entry(action)
+                .or_insert_with(|| Some(action.default_shortcut()));
+        }
+
+        res
+    }
+
+    /// Returns the first action whose shortcut was pressed this frame, consuming the key press.
+    pub fn pressed(&self, ctx: &Context) -> Option<Action> {
+        ctx.input_mut(|input| {
+            self.shortcuts.iter().find_map(|(&action, shortcut)| {
+                shortcut
+                    .is_some_and(|shortcut| input.consume_shortcut(&shortcut))
+                    .then_some(action)
+            })
+        })
+    }
+
+    pub fn save(&self, storage: &mut dyn Storage) {
+        set_value(storage, Self::KEY, self);
+    }
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self {
+            shortcuts: Action::ALL
+                .into_iter()
+                .map(|action| (action, Some(action.default_shortcut())))
+                .collect(),
+        }
+    }
+}
+
+/// A window listing every action which allows its shortcut to be changed.
+///
+/// Shortcuts are not handled while the window is open, so that recording a new shortcut does not
+/// also run the action it was previously bound to.
+#[derive(Default)]
+pub struct KeymapWindow {
+    /// The action whose new shortcut is the next key pressed.
+    recording: Option<Action>,
+}
+
+impl KeymapWindow {
+    /// Returns `false` once the window has been closed.
+    pub fn show(&mut self, ctx: &Context, keymap: &mut Keymap) -> bool {
+        let mut open = true;
+
+        if let Some(action) = self.recording {
+            let shortcut = ctx.input(|input| {
+                input.events.iter().find_map(|event| match *event {
+                    Event::Key {
+                        key,
+                        pressed: true,
+                        modifiers,
+                        ..
+                    } => Some(KeyboardShortcut::new(modifiers, key)),
+                    _ => None,
+                })
+            });
+
+            if let Some(shortcut) = shortcut {
+                // Escape cancels recording instead of becoming a shortcut
+                if shortcut.logical_key != Key::Escape {
+                    keymap.shortcuts.insert(action, Some(shortcut));
+                }
+
+                self.recording = None;
+            }
+        }
+
+        Window::new("Keyboard Shortcuts")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                Grid::new("keymap_window").num_columns(3).show(ui, |ui| {
+                    for action in Action::ALL {
+                        ui.label(action.label());
+
+                        let shortcut = keymap.shortcuts.get(&action).copied().flatten();
+                        let text = if self.recording == Some(action) {
+                            "Press a key...".to_owned()
+                        } else {
+                            shortcut.map_or_else(
+                                || "Unbound".to_owned(),
+                                |shortcut| ctx.format_shortcut(&shortcut),
+                            )
+                        };
+
+                        if ui
+                            .selectable_label(self.recording == Some(action), text)
+                            .on_hover_text("Click, then press the new shortcut (Escape cancels)")
+                            .clicked()
+                        {
+                            self.recording = Some(action);
+                        }
+
+                        if ui.button("Clear").clicked() {
+                            keymap.shortcuts.insert(action, None);
+                            self.recording = None;
+                        }
+
+                        ui.end_row();
+                    }
+                });
+
+                ui.separator();
+
+                if ui.button("Reset to Defaults").clicked() {
+                    *keymap = Default::default();
+                    self.recording = None;
+                }
+            });
+
+        open
+    }
+}
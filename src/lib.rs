@@ -1,3 +1,4 @@
 mod expr;
+mod simd;
 
 pub use self::expr::*;
@@ -6,9 +6,36 @@ mod rand {
 }
 
 mod app;
+mod clipboard;
+mod diagnostics;
+#[cfg(not(target_arch = "wasm32"))]
+mod examples;
+#[cfg(not(target_arch = "wasm32"))]
+mod export;
 mod expr;
+mod formula;
+#[cfg(all(feature = "gpu", not(target_arch = "wasm32")))]
+mod gpu;
+#[cfg(not(target_arch = "wasm32"))]
+mod import;
+mod inspector;
+mod keymap;
+mod minimap;
 mod node;
+mod palette;
+mod plot;
+mod presets;
+mod preview;
+#[cfg(not(target_arch = "wasm32"))]
+mod project;
+#[cfg(not(target_arch = "wasm32"))]
+mod recent;
+mod search;
+mod simd;
+mod terrain;
 mod thread;
+mod timeline;
+mod variables;
 mod view;
 
 use self::app::App;
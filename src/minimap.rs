@@ -0,0 +1,89 @@
+use {
+    super::node::NoiseNode,
+    egui::{vec2, Color32, Id, Pos2, Rect, Sense, Stroke, Ui, Vec2},
+    egui_snarl::Snarl,
+};
+
+/// The size of the minimap on screen.
+const SIZE: Vec2 = vec2(192.0, 128.0);
+
+/// The distance between the minimap and the corner of the canvas.
+const MARGIN: f32 = 8.0;
+
+/// Returns the approximate size of a node in graph units.
+///
+/// Snarl does not expose the size of each node, so it is estimated from the preview image, which is
+/// shown beside the pins of image nodes.
+pub fn node_size(node: &NoiseNode) -> Vec2 {
+    if let NoiseNode::Comment(node) = node {
+        return node.size;
+    }
+
+    let image_size = node
+        .image()
+        .filter(|image| !image.disabled)
+        .map_or(0.0, |image| image.resolution.size() as f32);
+
+    vec2(160.0 + image_size, (image_size + 24.0).max(48.0))
+}
+
+/// Draws an overview of every node and of the visible part of the graph in the bottom-right corner
+/// of the canvas.
+///
+/// Clicking the minimap centers the view on the clicked position and dragging it moves the view
+/// along with the pointer. The view cannot be moved from outside of snarl, so this returns the
+/// offset which every node should be moved by instead.
+pub fn show(ui: &mut Ui, canvas: Rect, viewport: Rect, snarl: &Snarl<NoiseNode>) -> Option<Vec2> {
+    let node_rects = snarl
+        .nodes_pos()
+        .map(|(pos, node)| Rect::from_min_size(pos, node_size(node)))
+        .collect::<Vec<_>>();
+
+    if node_rects.is_empty() {
+        return None;
+    }
+
+    let rect = Rect::from_min_size(canvas.right_bottom() - SIZE - Vec2::splat(MARGIN), SIZE);
+    let response = ui
+        .interact(rect, Id::new("minimap"), Sense::click_and_drag())
+        .on_hover_text("Click or drag to move the view");
+
+    // The whole graph and the viewport fit within the minimap, keeping their aspect ratio
+    let bounds = node_rects
+        .iter()
+        .fold(viewport, |bounds, node_rect| bounds.union(*node_rect))
+        .expand(32.0);
+    let scale = (rect.width() / bounds.width()).min(rect.height() / bounds.height());
+    let offset = rect.center() - bounds.center().to_vec2() * scale;
+    let to_screen = |pos: Pos2| offset + pos.to_vec2() * scale;
+    let to_screen_rect = |rect: Rect| Rect::from_min_max(to_screen(rect.min), to_screen(rect.max));
+
+    let visuals = ui.visuals();
+    let painter = ui.painter_at(rect);
+    painter.rect(
+        rect,
+        4.0,
+        visuals.extreme_bg_color.gamma_multiply(0.9),
+        visuals.window_stroke,
+    );
+
+    for node_rect in node_rects {
+        painter.rect_filled(to_screen_rect(node_rect), 1.0, visuals.weak_text_color());
+    }
+
+    painter.rect_stroke(
+        to_screen_rect(viewport).intersect(rect),
+        0.0,
+        Stroke::new(1.5, Color32::from_rgb(96, 160, 224)),
+    );
+
+    if response.dragged() {
+        Some(-response.drag_delta() / scale)
+    } else if response.clicked() {
+        response
+            .interact_pointer_pos()
+            .map(|pos| viewport.center() - ((pos - offset) / scale).to_pos2())
+    } else {
+        None
+    }
+}
@@ -1,10 +1,17 @@
 use {
     super::expr::{
-        BlendExpr, ClampExpr, ControlPointExpr, CurveExpr, DisplaceExpr, DistanceFunction,
-        ExponentExpr, Expr, FractalExpr, OpType, ReturnType, RigidFractalExpr, ScaleBiasExpr,
-        SelectExpr, SourceType, TerraceExpr, TransformExpr, TurbulenceExpr, Variable, WorleyExpr,
+        BiasGainExpr, BlendExpr, ClampExpr, CombineRgbExpr, CompareExpr, CompareType, ContrastExpr,
+        ControlPointExpr, CraterExpr, CurveExpr, DisplaceExpr, DistanceFunction, DomainWarpExpr,
+        EaseExpr, EasingCurve, ErosionExpr, ExponentExpr, Expr, FaultLinesExpr, FormulaExpr,
+        FormulaOp, FractalExpr, GenericFractalExpr, GradientExpr, GradientType, ImageSourceExpr,
+        ImageTiling, MirrorPointExpr, MixExpr, NormalComponent, NormalExpr, NormalizeExpr, OpType,
+        RemapExpr, ReturnType, RigidFractalExpr, RotateAxisExpr, ScaleBiasExpr, ScriptExpr,
+        SelectExpr, SlopeExpr, SourceType, SwitchExpr, SwizzleComponent, SwizzleExpr,
+        TerraceEasing, TerraceExpr, TilePointExpr, TransformExpr, TurbulenceExpr, Variable,
+        WarpedFbmExpr, WorleyExpr,
     },
-    egui::TextureHandle,
+    super::formula::{self, FormulaError},
+    egui::{vec2, Color32, Pos2, TextureHandle, Vec2},
     egui_snarl::{InPinId, NodeId, OutPinId, Snarl},
     noise::{
         BasicMulti as Fractal, Cylinders, Perlin as AnySeedable, RidgedMulti as RigidFractal,
@@ -14,6 +21,9 @@ use {
     std::{cell::RefCell, collections::HashSet},
 };
 
+#[cfg(not(target_arch = "wasm32"))]
+use {image::imageops::FilterType, std::path::Path};
+
 fn constant(value: f64) -> Box<Expr> {
     Box::new(Expr::Constant(Variable::Anonymous(value)))
 }
@@ -64,6 +74,34 @@ where
     remotes.first().map(|remote| f(remote.node))
 }
 
+#[derive(Clone, Serialize, Deserialize)]
+pub struct BiasGainNode {
+    pub image: Image,
+
+    pub bias: NodeValue<f64>,
+    pub gain: NodeValue<f64>,
+}
+
+impl BiasGainNode {
+    fn expr(&self, node_id: NodeId, snarl: &Snarl<NoiseNode>) -> BiasGainExpr {
+        BiasGainExpr {
+            source: in_pin_expr_or_const(snarl, node_id, 0, 0.0),
+            bias: self.bias.var(snarl),
+            gain: self.gain.var(snarl),
+        }
+    }
+}
+
+impl Default for BiasGainNode {
+    fn default() -> Self {
+        Self {
+            image: Default::default(),
+            bias: NodeValue::Value(0.5),
+            gain: NodeValue::Value(0.5),
+        }
+    }
+}
+
 #[derive(Clone, Default, Serialize, Deserialize)]
 pub struct BlendNode {
     pub image: Image,
@@ -116,6 +154,33 @@ impl ClampNode {
     }
 }
 
+/// Packs three sources into the red, green and blue channels of its preview and export.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct CombineRgbNode {
+    pub image: Image,
+}
+
+impl CombineRgbNode {
+    fn expr(&self, node_id: NodeId, snarl: &Snarl<NoiseNode>) -> CombineRgbExpr {
+        CombineRgbExpr {
+            channels: (0..3)
+                .map(|input| in_pin_expr_or_const(snarl, node_id, input, 0.0))
+                .collect::<Vec<_>>()
+                .try_into()
+                .unwrap(),
+        }
+    }
+}
+
+/// Passes its source on unchanged; previews of this node, and of the nodes it feeds, are colored
+/// with the ramp instead of their colormap.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct ColorRampNode {
+    pub image: Image,
+
+    pub ramp: ColorRamp,
+}
+
 #[derive(Clone, Default, Serialize, Deserialize)]
 pub struct CombinerNode {
     pub image: Image,
@@ -136,10 +201,129 @@ impl CombinerNode {
     }
 }
 
+/// A colored note for annotating the graph; comments have no pins and are not part of any
+/// expression.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct CommentNode {
+    pub color: Color32,
+
+    /// The size of the text box at a graph scale of `1`.
+    pub size: Vec2,
+
+    pub text: String,
+}
+
+impl Default for CommentNode {
+    fn default() -> Self {
+        Self {
+            color: Color32::from_rgb(250, 226, 120),
+            size: vec2(192.0, 96.0),
+            text: String::new(),
+        }
+    }
+}
+
+/// A named, colored frame around other nodes which moves and collapses its members together.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct GroupNode {
+    pub collapsed: bool,
+    pub color: Color32,
+    pub node_ids: Vec<NodeId>,
+
+    /// The position of the group node when members were last moved along with it.
+    #[serde(skip)]
+    pub pos: Option<Pos2>,
+
+    /// A title shown in the node header instead of the type of node.
+    #[serde(default)]
+    pub title: Option<String>,
+}
+
+impl GroupNode {
+    pub fn new(node_ids: Vec<NodeId>) -> Self {
+        Self {
+            collapsed: false,
+            color: Color32::from_rgb(96, 160, 224),
+            node_ids,
+            pos: None,
+            title: None,
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct CompareNode {
+    pub image: Image,
+
+    pub compare_ty: CompareType,
+    pub tolerance: NodeValue<f64>,
+}
+
+impl CompareNode {
+    fn expr(&self, node_id: NodeId, snarl: &Snarl<NoiseNode>) -> CompareExpr {
+        CompareExpr {
+            sources: (0..2)
+                .map(|input| in_pin_expr_or_const(snarl, node_id, input, 0.0))
+                .collect::<Vec<_>>()
+                .try_into()
+                .unwrap(),
+            compare_ty: self.compare_ty,
+            tolerance: self.tolerance.var(snarl),
+        }
+    }
+}
+
+impl Default for CompareNode {
+    fn default() -> Self {
+        Self {
+            image: Default::default(),
+            compare_ty: CompareType::Greater,
+            tolerance: NodeValue::Value(0.01),
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ContrastNode {
+    pub image: Image,
+
+    pub contrast: NodeValue<f64>,
+    pub pivot: NodeValue<f64>,
+
+    /// Clamps the result to -1..1, as values far from the pivot are pushed past it.
+    pub clamped: bool,
+}
+
+impl ContrastNode {
+    fn expr(&self, node_id: NodeId, snarl: &Snarl<NoiseNode>) -> ContrastExpr {
+        ContrastExpr {
+            source: in_pin_expr_or_const(snarl, node_id, 0, 0.0),
+            contrast: self.contrast.var(snarl),
+            pivot: self.pivot.var(snarl),
+            clamped: self.clamped,
+        }
+    }
+}
+
+impl Default for ContrastNode {
+    fn default() -> Self {
+        Self {
+            image: Default::default(),
+            contrast: NodeValue::Value(1.5),
+            pivot: NodeValue::Value(0.0),
+            clamped: true,
+        }
+    }
+}
+
 #[derive(Clone, Serialize, Deserialize)]
 pub struct ConstantNode<T> {
     pub name: String,
 
+    /// A title shown in the node header instead of the type of node.
+    #[serde(default)]
+    pub title: Option<String>,
+
     pub value: T,
 }
 
@@ -150,6 +334,7 @@ where
     fn default() -> Self {
         Self {
             name: "name".to_owned(),
+            title: None,
             value: Default::default(),
         }
     }
@@ -160,6 +345,10 @@ pub struct ConstantOpNode<T> {
     pub inputs: [NodeValue<T>; 2],
 
     pub op_ty: OpType,
+
+    /// A title shown in the node header instead of the operation.
+    #[serde(default)]
+    pub title: Option<String>,
 }
 
 impl<T> ConstantOpNode<T> {
@@ -170,6 +359,7 @@ impl<T> ConstantOpNode<T> {
         Self {
             inputs: [NodeValue::Value(value); 2],
             op_ty,
+            title: None,
         }
     }
 }
@@ -192,36 +382,102 @@ impl ConstantOpNode<f64> {
 pub struct ControlPointNode {
     pub input: NodeValue<f64>,
     pub output: NodeValue<f64>,
+
+    /// A title shown in the node header instead of the type of node.
+    #[serde(default)]
+    pub title: Option<String>,
 }
 
-#[derive(Clone, Default, Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
+pub struct CraterNode {
+    pub image: Image,
+
+    pub seed: NodeValue<u32>,
+    pub density: NodeValue<f64>,
+    pub radius: NodeValue<f64>,
+    pub depth: NodeValue<f64>,
+}
+
+impl CraterNode {
+    fn expr(&self, node_id: NodeId, snarl: &Snarl<NoiseNode>) -> CraterExpr {
+        CraterExpr {
+            source: in_pin_expr_or_const(snarl, node_id, 0, 0.0),
+            seed: self.seed.var(snarl),
+            density: self.density.var(snarl),
+            radius: self.radius.var(snarl),
+            depth: self.depth.var(snarl),
+        }
+    }
+}
+
+impl Default for CraterNode {
+    fn default() -> Self {
+        Self {
+            image: Default::default(),
+            seed: NodeValue::Value(0),
+            density: NodeValue::Value(0.5),
+            radius: NodeValue::Value(0.5),
+            depth: NodeValue::Value(0.5),
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
 pub struct CurveNode {
     pub image: Image,
 
     pub control_point_node_ids: Vec<Option<NodeId>>,
+
+    /// The input and output of each control point placed with the in-node editor; these are used
+    /// along with any connected control point nodes.
+    #[serde(default)]
+    pub points: Vec<[f64; 2]>,
+
+    /// Shows the in-node editor, which plots the curve and allows dragging its points.
+    #[serde(default)]
+    pub editor: bool,
 }
 
 impl CurveNode {
+    /// Returns the connected control points followed by those placed with the in-node editor.
+    pub fn control_points(&self, snarl: &Snarl<NoiseNode>) -> Vec<ControlPointExpr> {
+        self.control_point_node_ids
+            .iter()
+            .copied()
+            .filter_map(|node_id| {
+                node_id.map(|node_id| {
+                    snarl
+                        .get_node(node_id)
+                        .and_then(NoiseNode::as_control_point)
+                        .map(|control_point| ControlPointExpr {
+                            input_value: control_point.input.var(snarl),
+                            output_value: control_point.output.var(snarl),
+                        })
+                        .unwrap()
+                })
+            })
+            .chain(self.points.iter().map(|&[input, output]| ControlPointExpr {
+                input_value: Variable::Anonymous(input),
+                output_value: Variable::Anonymous(output),
+            }))
+            .collect()
+    }
+
     fn expr(&self, node_id: NodeId, snarl: &Snarl<NoiseNode>) -> CurveExpr {
         CurveExpr {
             source: in_pin_expr_or_const(snarl, node_id, 0, 0.0),
-            control_points: self
-                .control_point_node_ids
-                .iter()
-                .copied()
-                .filter_map(|node_id| {
-                    node_id.map(|node_id| {
-                        snarl
-                            .get_node(node_id)
-                            .and_then(NoiseNode::as_control_point)
-                            .map(|control_point| ControlPointExpr {
-                                input_value: control_point.input.var(snarl),
-                                output_value: control_point.output.var(snarl),
-                            })
-                            .unwrap()
-                    })
-                })
-                .collect(),
+            control_points: self.control_points(snarl),
+        }
+    }
+}
+
+impl Default for CurveNode {
+    fn default() -> Self {
+        Self {
+            image: Default::default(),
+            control_point_node_ids: Default::default(),
+            points: vec![[-1.0, -1.0], [-0.5, -0.5], [0.5, 0.5], [1.0, 1.0]],
+            editor: true,
         }
     }
 }
@@ -261,32 +517,53 @@ impl DisplaceNode {
 }
 
 #[derive(Clone, Serialize, Deserialize)]
-pub struct ExponentNode {
+pub struct DomainWarpNode {
     pub image: Image,
 
-    pub exponent: NodeValue<f64>,
+    pub amplitude: NodeValue<f64>,
+    pub frequency: NodeValue<f64>,
 }
 
-impl ExponentNode {
-    fn expr(&self, node_id: NodeId, snarl: &Snarl<NoiseNode>) -> ExponentExpr {
-        ExponentExpr {
+impl DomainWarpNode {
+    fn expr(&self, node_id: NodeId, snarl: &Snarl<NoiseNode>) -> DomainWarpExpr {
+        DomainWarpExpr {
             source: in_pin_expr_or_const(snarl, node_id, 0, 0.0),
-            exponent: self.exponent.var(snarl),
+            warp: in_pin_expr_or_const(snarl, node_id, 1, 0.0),
+            amplitude: self.amplitude.var(snarl),
+            frequency: self.frequency.var(snarl),
         }
     }
 }
 
-impl Default for ExponentNode {
+impl Default for DomainWarpNode {
     fn default() -> Self {
         Self {
             image: Default::default(),
-            exponent: NodeValue::Value(1.0),
+            amplitude: NodeValue::Value(1.0),
+            frequency: NodeValue::Value(1.0),
+        }
+    }
+}
+
+/// Shapes the source with a standard easing curve, treating -1..1 as the 0..1 range of the curve.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct EaseNode {
+    pub image: Image,
+
+    pub curve: EasingCurve,
+}
+
+impl EaseNode {
+    fn expr(&self, node_id: NodeId, snarl: &Snarl<NoiseNode>) -> EaseExpr {
+        EaseExpr {
+            source: in_pin_expr_or_const(snarl, node_id, 0, 0.0),
+            curve: self.curve,
         }
     }
 }
 
 #[derive(Clone, Serialize, Deserialize)]
-pub struct FractalNode {
+pub struct ErosionNode {
     pub image: Image,
 
     pub source_ty: SourceType,
@@ -295,22 +572,24 @@ pub struct FractalNode {
     pub frequency: NodeValue<f64>,
     pub lacunarity: NodeValue<f64>,
     pub persistence: NodeValue<f64>,
+    pub strength: NodeValue<f64>,
 }
 
-impl FractalNode {
-    fn expr(&self, snarl: &Snarl<NoiseNode>) -> FractalExpr {
-        FractalExpr {
+impl ErosionNode {
+    fn expr(&self, snarl: &Snarl<NoiseNode>) -> ErosionExpr {
+        ErosionExpr {
             source_ty: self.source_ty,
             seed: self.seed.var(snarl),
             octaves: self.octaves.var(snarl),
             frequency: self.frequency.var(snarl),
             lacunarity: self.lacunarity.var(snarl),
             persistence: self.persistence.var(snarl),
+            strength: self.strength.var(snarl),
         }
     }
 }
 
-impl Default for FractalNode {
+impl Default for ErosionNode {
     fn default() -> Self {
         Self {
             image: Default::default(),
@@ -320,84 +599,666 @@ impl Default for FractalNode {
             frequency: NodeValue::Value(Fractal::<AnySeedable>::DEFAULT_FREQUENCY),
             lacunarity: NodeValue::Value(Fractal::<AnySeedable>::DEFAULT_LACUNARITY),
             persistence: NodeValue::Value(Fractal::<AnySeedable>::DEFAULT_PERSISTENCE),
+            strength: NodeValue::Value(1.0),
         }
     }
 }
 
-#[derive(Clone, Default, Serialize, Deserialize)]
-pub struct GeneratorNode {
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ExponentNode {
     pub image: Image,
 
-    pub seed: NodeValue<u32>,
+    pub exponent: NodeValue<f64>,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
-pub struct Image {
-    pub scale: f64,
+impl ExponentNode {
+    fn expr(&self, node_id: NodeId, snarl: &Snarl<NoiseNode>) -> ExponentExpr {
+        ExponentExpr {
+            source: in_pin_expr_or_const(snarl, node_id, 0, 0.0),
+            exponent: self.exponent.var(snarl),
+        }
+    }
+}
 
-    #[serde(skip)]
-    pub texture: Option<TextureHandle>,
+impl Default for ExponentNode {
+    fn default() -> Self {
+        Self {
+            image: Default::default(),
+            exponent: NodeValue::Value(1.0),
+        }
+    }
+}
 
-    #[serde(skip)]
-    pub version: usize,
+#[derive(Clone, Serialize, Deserialize)]
+pub struct FaultLinesNode {
+    pub image: Image,
 
-    pub x: f64,
-    pub y: f64,
+    pub seed: NodeValue<u32>,
+    pub iterations: NodeValue<u32>,
+    pub amplitude: NodeValue<f64>,
 }
 
-impl Default for Image {
+impl FaultLinesNode {
+    fn expr(&self, snarl: &Snarl<NoiseNode>) -> FaultLinesExpr {
+        FaultLinesExpr {
+            seed: self.seed.var(snarl),
+            iterations: self.iterations.var(snarl),
+            amplitude: self.amplitude.var(snarl),
+        }
+    }
+}
+
+impl Default for FaultLinesNode {
     fn default() -> Self {
         Self {
-            scale: 4.0,
-            texture: None,
-            version: 0,
-            x: 0.0,
-            y: 0.0,
+            image: Default::default(),
+            seed: NodeValue::Value(0),
+            iterations: NodeValue::Value(64),
+            amplitude: NodeValue::Value(1.0),
         }
     }
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
-pub enum NodeValue<T> {
-    Node(NodeId),
-    Value(T),
+/// Evaluates a formula typed by the user, such as `sin(x * 3) * a + source`; names which are not
+/// coordinates, sources or functions refer to the decimal constant of the same name.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct FormulaNode {
+    pub image: Image,
+
+    pub formula: String,
+
+    /// The number of source pins; one more pin is always shown so that sources may be added.
+    pub source_count: usize,
 }
 
-impl<T> NodeValue<T> {
-    pub fn as_node_id(&self) -> Option<NodeId> {
-        if let &Self::Node(node_id) = self {
-            Some(node_id)
-        } else {
-            None
+impl FormulaNode {
+    pub const MIN_SOURCE_COUNT: usize = 1;
+
+    fn expr(&self, node_id: NodeId, snarl: &Snarl<NoiseNode>) -> Expr {
+        match self.parse(snarl) {
+            Ok((op, variables)) => Expr::Formula(FormulaExpr {
+                op,
+                sources: (0..self.source_count)
+                    .map(|idx| *in_pin_expr_or_const(snarl, node_id, idx, 0.0))
+                    .collect(),
+                variables,
+            }),
+            Err(_) => Expr::Constant(Variable::Anonymous(0.0)),
         }
     }
 
-    pub fn as_value_mut(&mut self) -> Option<&mut T> {
-        if let Self::Value(value) = self {
-            Some(value)
-        } else {
-            None
-        }
+    /// Parses the formula and looks up the decimal constant named by each variable.
+    pub fn parse(
+        &self,
+        snarl: &Snarl<NoiseNode>,
+    ) -> Result<(FormulaOp, Vec<Variable<f64>>), FormulaError> {
+        let formula = formula::parse(&self.formula, self.source_count)?;
+        let variables = formula
+            .variables
+            .into_iter()
+            .map(|(name, position)| {
+                snarl
+                    .node_ids()
+                    .find_map(|(_, node)| match node {
+                        NoiseNode::F64(node) if node.name == name => {
+                            Some(Variable::Named(name.clone(), node.value))
+                        }
+                        _ => None,
+                    })
+                    .ok_or_else(|| FormulaError {
+                        position,
+                        message: format!("Unknown constant `{name}`"),
+                    })
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok((formula.op, variables))
     }
 
-    pub fn is_node_id(&self) -> bool {
-        self.as_node_id().is_some()
+    /// Returns `true` if the formula refers to the constant with the given name.
+    pub fn references(&self, name: &str) -> bool {
+        formula::parse(&self.formula, self.source_count).is_ok_and(|formula| {
+            formula
+                .variables
+                .iter()
+                .any(|(variable, _)| variable == name)
+        })
     }
 }
 
-impl NodeValue<f64> {
-    fn eval(self, snarl: &Snarl<NoiseNode>) -> f64 {
-        match self {
-            Self::Node(node_id) => snarl.get_node(node_id).unwrap().eval_f64(snarl),
-            Self::Value(value) => value,
+impl Default for FormulaNode {
+    fn default() -> Self {
+        Self {
+            image: Default::default(),
+            formula: "source".to_owned(),
+            source_count: Self::MIN_SOURCE_COUNT,
         }
     }
+}
 
-    fn var(self, snarl: &Snarl<NoiseNode>) -> Variable<f64> {
-        match self {
-            Self::Node(node_id) => match snarl.get_node(node_id).unwrap() {
+#[derive(Clone, Serialize, Deserialize)]
+pub struct FractalNode {
+    pub image: Image,
+
+    pub source_ty: SourceType,
+    pub seed: NodeValue<u32>,
+    pub octaves: NodeValue<u32>,
+    pub frequency: NodeValue<f64>,
+    pub lacunarity: NodeValue<f64>,
+    pub persistence: NodeValue<f64>,
+
+    /// Custom amplitudes of the first octaves, which replace the persistence when not empty.
+    #[serde(default)]
+    pub weights: Vec<f64>,
+}
+
+impl FractalNode {
+    fn expr(&self, snarl: &Snarl<NoiseNode>) -> FractalExpr {
+        FractalExpr {
+            source_ty: self.source_ty,
+            seed: self.seed.var(snarl),
+            octaves: self.octaves.var(snarl),
+            frequency: self.frequency.var(snarl),
+            lacunarity: self.lacunarity.var(snarl),
+            persistence: self.persistence.var(snarl),
+            weights: self.weights.clone(),
+        }
+    }
+}
+
+impl Default for FractalNode {
+    fn default() -> Self {
+        Self {
+            image: Default::default(),
+            source_ty: Default::default(),
+            seed: NodeValue::Value(Fractal::<AnySeedable>::DEFAULT_SEED),
+            octaves: NodeValue::Value(Fractal::<AnySeedable>::DEFAULT_OCTAVES as _),
+            frequency: NodeValue::Value(Fractal::<AnySeedable>::DEFAULT_FREQUENCY),
+            lacunarity: NodeValue::Value(Fractal::<AnySeedable>::DEFAULT_LACUNARITY),
+            persistence: NodeValue::Value(Fractal::<AnySeedable>::DEFAULT_PERSISTENCE),
+            weights: Vec::new(),
+        }
+    }
+}
+
+/// Layers octaves of its source like a fractal generator layers octaves of gradient noise.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct GenericFractalNode {
+    pub image: Image,
+
+    pub octaves: NodeValue<u32>,
+    pub frequency: NodeValue<f64>,
+    pub lacunarity: NodeValue<f64>,
+    pub persistence: NodeValue<f64>,
+}
+
+impl GenericFractalNode {
+    fn expr(&self, node_id: NodeId, snarl: &Snarl<NoiseNode>) -> GenericFractalExpr {
+        GenericFractalExpr {
+            source: in_pin_expr_or_const(snarl, node_id, 0, 0.0),
+            octaves: self.octaves.var(snarl),
+            frequency: self.frequency.var(snarl),
+            lacunarity: self.lacunarity.var(snarl),
+            persistence: self.persistence.var(snarl),
+        }
+    }
+}
+
+impl Default for GenericFractalNode {
+    fn default() -> Self {
+        Self {
+            image: Default::default(),
+            octaves: NodeValue::Value(Fractal::<AnySeedable>::DEFAULT_OCTAVES as _),
+            frequency: NodeValue::Value(Fractal::<AnySeedable>::DEFAULT_FREQUENCY),
+            lacunarity: NodeValue::Value(Fractal::<AnySeedable>::DEFAULT_LACUNARITY),
+            persistence: NodeValue::Value(Fractal::<AnySeedable>::DEFAULT_PERSISTENCE),
+        }
+    }
+}
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct GeneratorNode {
+    pub image: Image,
+
+    pub seed: NodeValue<u32>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct GradientNode {
+    pub image: Image,
+
+    pub gradient_ty: GradientType,
+
+    /// The direction of a linear gradient, in degrees counter-clockwise from the X axis.
+    pub angle: NodeValue<f64>,
+
+    /// The distance from the origin at which the gradient reaches its extent.
+    pub radius: NodeValue<f64>,
+}
+
+impl GradientNode {
+    fn expr(&self, snarl: &Snarl<NoiseNode>) -> GradientExpr {
+        GradientExpr {
+            gradient_ty: self.gradient_ty,
+            angle: self.angle.var(snarl),
+            radius: self.radius.var(snarl),
+        }
+    }
+}
+
+impl Default for GradientNode {
+    fn default() -> Self {
+        Self {
+            image: Default::default(),
+            gradient_ty: GradientType::Linear,
+            angle: NodeValue::Value(0.0),
+            radius: NodeValue::Value(1.0),
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Image {
+    #[serde(default)]
+    pub colormap: Colormap,
+
+    /// The value interval between contour lines drawn over the preview, or `None` to hide them.
+    #[serde(default)]
+    pub contours: Option<f64>,
+
+    /// Set when preview generation has been turned off for this node; the image is not rendered
+    /// until it is turned back on.
+    #[serde(default)]
+    pub disabled: bool,
+
+    #[serde(default)]
+    pub resolution: ImageResolution,
+
+    pub scale: f64,
+
+    /// Lights the preview as a surface so that ridges and bumps are visible.
+    #[serde(default)]
+    pub shaded: bool,
+
+    #[serde(skip)]
+    pub stats: ImageStats,
+
+    #[serde(skip)]
+    pub texture: Option<TextureHandle>,
+
+    /// A title shown in the node header instead of the type of node.
+    #[serde(default)]
+    pub title: Option<String>,
+
+    #[serde(skip)]
+    pub version: usize,
+
+    pub x: f64,
+    pub y: f64,
+}
+
+impl Default for Image {
+    fn default() -> Self {
+        Self {
+            colormap: Default::default(),
+            contours: None,
+            disabled: false,
+            resolution: Default::default(),
+            scale: 4.0,
+            shaded: false,
+            stats: Default::default(),
+            texture: None,
+            title: None,
+            version: 0,
+            x: 0.0,
+            y: 0.0,
+        }
+    }
+}
+
+/// Statistics of the raw (unclamped) samples of a node preview image.
+#[derive(Clone, Copy, Debug)]
+pub struct ImageStats {
+    pub count: usize,
+    pub max: f64,
+    pub min: f64,
+    pub sum: f64,
+}
+
+impl ImageStats {
+    pub fn insert(&mut self, sample: f64) {
+        self.count += 1;
+        self.max = self.max.max(sample);
+        self.min = self.min.min(sample);
+        self.sum += sample;
+    }
+
+    /// Returns `true` if any sample is outside of the `-1..=1` range shown by previews.
+    pub fn is_clipped(&self) -> bool {
+        self.min < -1.0 || self.max > 1.0
+    }
+
+    pub fn mean(&self) -> f64 {
+        self.sum / self.count as f64
+    }
+
+    pub fn merge(&mut self, other: &Self) {
+        self.count += other.count;
+        self.max = self.max.max(other.max);
+        self.min = self.min.min(other.min);
+        self.sum += other.sum;
+    }
+}
+
+impl Default for ImageStats {
+    fn default() -> Self {
+        Self {
+            count: 0,
+            max: f64::NEG_INFINITY,
+            min: f64::INFINITY,
+            sum: 0.0,
+        }
+    }
+}
+
+/// A gradient of colors which the samples of a node preview image are mapped to.
+///
+/// The stops are stored inline, which limits their number but allows every preview request to
+/// carry a copy of the ramp.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(from = "Vec<ColorStop>", into = "Vec<ColorStop>")]
+pub struct ColorRamp {
+    len: usize,
+    stops: [ColorStop; Self::MAX_STOPS],
+}
+
+impl ColorRamp {
+    pub const MAX_STOPS: usize = 8;
+
+    /// Returns the color of a sample which has been mapped to `0..=1` (values outside of this
+    /// range are clamped).
+    pub fn color(&self, value: f64) -> Color32 {
+        let stops = self.stops();
+        let value = value.clamp(0.0, 1.0);
+
+        match stops.iter().position(|stop| stop.position >= value) {
+            Some(0) => stops[0].color,
+            Some(idx) => {
+                let (start, end) = (stops[idx - 1], stops[idx]);
+                let t = (value - start.position) / (end.position - start.position);
+
+                if t.is_finite() {
+                    start.color.lerp_to_gamma(end.color, t as f32)
+                } else {
+                    end.color
+                }
+            }
+            None => stops[stops.len() - 1].color,
+        }
+    }
+
+    /// Adds a stop of the color currently found at the position, returning its index or `None` if
+    /// the ramp is full.
+    pub fn insert(&mut self, position: f64) -> Option<usize> {
+        if self.len == Self::MAX_STOPS {
+            return None;
+        }
+
+        let position = position.clamp(0.0, 1.0);
+        let color = self.color(position);
+        let idx = self
+            .stops()
+            .iter()
+            .position(|stop| stop.position > position)
+            .unwrap_or(self.len);
+
+        self.stops.copy_within(idx..self.len, idx + 1);
+        self.stops[idx] = ColorStop { color, position };
+        self.len += 1;
+
+        Some(idx)
+    }
+
+    /// Removes a stop; the last two stops of a ramp cannot be removed.
+    pub fn remove(&mut self, idx: usize) {
+        if self.len > 2 {
+            self.stops.copy_within(idx + 1..self.len, idx);
+            self.len -= 1;
+        }
+    }
+
+    /// Moves a stop, keeping it between its neighbors so that the stops remain in order.
+    pub fn set_position(&mut self, idx: usize, position: f64) {
+        let min = idx
+            .checked_sub(1)
+            .map_or(0.0, |idx| self.stops[idx].position);
+        let max = if idx + 1 < self.len {
+            self.stops[idx + 1].position
+        } else {
+            1.0
+        };
+
+        self.stops[idx].position = position.clamp(min, max);
+    }
+
+    /// The stops of the ramp, ordered by position.
+    pub fn stops(&self) -> &[ColorStop] {
+        &self.stops[..self.len]
+    }
+
+    pub fn stops_mut(&mut self) -> &mut [ColorStop] {
+        &mut self.stops[..self.len]
+    }
+}
+
+impl Default for ColorRamp {
+    fn default() -> Self {
+        vec![
+            ColorStop {
+                color: Color32::BLACK,
+                position: 0.0,
+            },
+            ColorStop {
+                color: Color32::WHITE,
+                position: 1.0,
+            },
+        ]
+        .into()
+    }
+}
+
+impl From<Vec<ColorStop>> for ColorRamp {
+    fn from(mut stops: Vec<ColorStop>) -> Self {
+        stops.truncate(Self::MAX_STOPS);
+        stops.sort_by(|lhs, rhs| lhs.position.total_cmp(&rhs.position));
+
+        while stops.len() < 2 {
+            stops.push(stops.last().copied().unwrap_or(ColorStop {
+                color: Color32::WHITE,
+                position: 1.0,
+            }));
+        }
+
+        let mut res = Self {
+            len: stops.len(),
+            stops: [stops[0]; Self::MAX_STOPS],
+        };
+        res.stops[..stops.len()].copy_from_slice(&stops);
+
+        res
+    }
+}
+
+impl From<ColorRamp> for Vec<ColorStop> {
+    fn from(ramp: ColorRamp) -> Self {
+        ramp.stops().to_vec()
+    }
+}
+
+/// A color of a color ramp and the position, in `0..=1`, where it is found.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ColorStop {
+    pub color: Color32,
+    pub position: f64,
+}
+
+/// The colors which the samples of a node preview image are mapped to.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Colormap {
+    #[default]
+    Grayscale,
+    Magma,
+    Terrain,
+    Viridis,
+}
+
+impl Colormap {
+    pub const ALL: [Self; 4] = [Self::Grayscale, Self::Viridis, Self::Magma, Self::Terrain];
+
+    const MAGMA: [(f64, [u8; 3]); 9] = [
+        (0.0, [0, 0, 4]),
+        (0.125, [28, 16, 68]),
+        (0.25, [79, 18, 123]),
+        (0.375, [129, 37, 129]),
+        (0.5, [181, 54, 122]),
+        (0.625, [229, 80, 100]),
+        (0.75, [251, 135, 97]),
+        (0.875, [254, 194, 135]),
+        (1.0, [252, 253, 191]),
+    ];
+
+    const TERRAIN: [(f64, [u8; 3]); 6] = [
+        (0.0, [51, 51, 153]),
+        (0.15, [0, 153, 255]),
+        (0.25, [0, 204, 102]),
+        (0.5, [255, 255, 153]),
+        (0.75, [128, 92, 84]),
+        (1.0, [255, 255, 255]),
+    ];
+
+    const VIRIDIS: [(f64, [u8; 3]); 9] = [
+        (0.0, [68, 1, 84]),
+        (0.125, [71, 44, 122]),
+        (0.25, [59, 81, 139]),
+        (0.375, [44, 113, 142]),
+        (0.5, [33, 144, 141]),
+        (0.625, [39, 173, 129]),
+        (0.75, [92, 200, 99]),
+        (0.875, [170, 220, 50]),
+        (1.0, [253, 231, 37]),
+    ];
+
+    /// Returns the color of a sample which has been mapped to `0..=1` (values outside of this
+    /// range are clamped).
+    pub fn color(self, value: f64) -> Color32 {
+        let stops: &[(f64, [u8; 3])] = match self {
+            Self::Grayscale => return Color32::from_gray((value * 255.0) as u8),
+            Self::Magma => &Self::MAGMA,
+            Self::Terrain => &Self::TERRAIN,
+            Self::Viridis => &Self::VIRIDIS,
+        };
+
+        let value = value.clamp(0.0, 1.0);
+        let idx = stops
+            .iter()
+            .position(|(stop, _)| *stop >= value)
+            .unwrap_or(stops.len() - 1)
+            .max(1);
+        let (start, start_color) = stops[idx - 1];
+        let (end, end_color) = stops[idx];
+        let t = (value - start) / (end - start);
+        let [r, g, b] = [0, 1, 2].map(|c| {
+            (start_color[c] as f64 + (end_color[c] as f64 - start_color[c] as f64) * t) as u8
+        });
+
+        Color32::from_rgb(r, g, b)
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Grayscale => "Grayscale",
+            Self::Magma => "Magma",
+            Self::Terrain => "Terrain",
+            Self::Viridis => "Viridis",
+        }
+    }
+}
+
+/// The number of pixels along each side of a node preview image.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ImageResolution {
+    X64,
+    #[default]
+    X128,
+    X256,
+    X512,
+}
+
+impl ImageResolution {
+    pub const ALL: [Self; 4] = [Self::X64, Self::X128, Self::X256, Self::X512];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::X64 => "64 × 64",
+            Self::X128 => "128 × 128",
+            Self::X256 => "256 × 256",
+            Self::X512 => "512 × 512",
+        }
+    }
+
+    pub fn size(self) -> usize {
+        match self {
+            Self::X64 => 64,
+            Self::X128 => 128,
+            Self::X256 => 256,
+            Self::X512 => 512,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum NodeValue<T> {
+    Node(NodeId),
+    Value(T),
+}
+
+impl<T> NodeValue<T> {
+    pub fn as_node_id(&self) -> Option<NodeId> {
+        if let &Self::Node(node_id) = self {
+            Some(node_id)
+        } else {
+            None
+        }
+    }
+
+    pub fn as_value_mut(&mut self) -> Option<&mut T> {
+        if let Self::Value(value) = self {
+            Some(value)
+        } else {
+            None
+        }
+    }
+
+    pub fn is_node_id(&self) -> bool {
+        self.as_node_id().is_some()
+    }
+}
+
+impl NodeValue<f64> {
+    pub fn eval(self, snarl: &Snarl<NoiseNode>) -> f64 {
+        match self {
+            Self::Node(node_id) => snarl.get_node(node_id).unwrap().eval_f64(snarl),
+            Self::Value(value) => value,
+        }
+    }
+
+    fn var(self, snarl: &Snarl<NoiseNode>) -> Variable<f64> {
+        match self {
+            Self::Node(node_id) => match snarl.get_node(node_id).unwrap() {
                 NoiseNode::F64(node) => Variable::Named(node.name.clone(), node.value),
                 NoiseNode::F64Operation(node) => node.var(snarl),
+                NoiseNode::RandomConstant(node) => Variable::Anonymous(node.eval(snarl)),
+                NoiseNode::Time(_) => TimeNode::var(),
                 _ => unreachable!(),
             },
             Self::Value(value) => Variable::Anonymous(value),
@@ -406,7 +1267,7 @@ impl NodeValue<f64> {
 }
 
 impl NodeValue<u32> {
-    fn eval(self, snarl: &Snarl<NoiseNode>) -> u32 {
+    pub fn eval(self, snarl: &Snarl<NoiseNode>) -> u32 {
         match self {
             Self::Node(node_id) => snarl.get_node(node_id).unwrap().eval_u32(snarl),
             Self::Value(value) => value,
@@ -447,45 +1308,92 @@ pub enum NoiseNode {
     Abs(UnaryNode),
     Add(CombinerNode),
     BasicMulti(FractalNode),
+    BiasGain(BiasGainNode),
     Billow(FractalNode),
     Blend(BlendNode),
+    BlueNoise(GeneratorNode),
+    Cache(UnaryNode),
     Clamp(ClampNode),
+    ColorRamp(ColorRampNode),
+    CombineRgb(CombineRgbNode),
+    Compare(CompareNode),
     Checkerboard(CheckerboardNode),
+    Comment(CommentNode),
+    Contrast(ContrastNode),
     ControlPoint(ControlPointNode),
+    Crater(CraterNode),
     Curve(CurveNode),
     Cylinders(CylindersNode),
+    Difference(CombinerNode),
     Displace(DisplaceNode),
+    DomainWarp(DomainWarpNode),
+    Ease(EaseNode),
+    Erosion(ErosionNode),
     Exponent(ExponentNode),
     F64(ConstantNode<f64>),
     F64Operation(ConstantOpNode<f64>),
+    FaultLines(FaultLinesNode),
     Fbm(FractalNode),
+    Formula(FormulaNode),
+    GenericFractal(GenericFractalNode),
+    Gradient(GradientNode),
+    Group(GroupNode),
     HybridMulti(FractalNode),
+    ImageSource(ImageSourceNode),
+    Jordan(FractalNode),
     Max(CombinerNode),
     Min(CombinerNode),
+    MirrorPoint(MirrorPointNode),
+    Mix(MixNode),
     Multiply(CombinerNode),
     Negate(UnaryNode),
+    Normal(NormalNode),
+    Normalize(NormalizeNode),
     OpenSimplex(GeneratorNode),
     Operation(ConstantOpNode<()>),
     Perlin(GeneratorNode),
     PerlinSurflet(GeneratorNode),
     Power(CombinerNode),
+    RandomConstant(RandomConstantNode),
+    Remap(RemapNode),
     RigidMulti(RigidFractalNode),
+    RotateAxis(RotateAxisNode),
     RotatePoint(TransformNode),
     ScaleBias(ScaleBiasNode),
     ScalePoint(TransformNode),
+    Script(ScriptNode),
     Select(SelectNode),
     Simplex(GeneratorNode),
+    Slope(SlopeNode),
+    Subgraph(SubgraphNode),
+    SubgraphInput(SubgraphInputNode),
+    SubgraphOutput(SubgraphOutputNode),
     SuperSimplex(GeneratorNode),
+    Swiss(FractalNode),
+    Switch(SwitchNode),
+    Swizzle(SwizzleNode),
     Terrace(TerraceNode),
+    Time(TimeNode),
+    TilePoint(TilePointNode),
     TranslatePoint(TransformNode),
     Turbulence(TurbulenceNode),
     U32(ConstantNode<u32>),
     U32Operation(ConstantOpNode<u32>),
     Value(GeneratorNode),
+    Vector(VectorNode),
+    WarpedFbm(WarpedFbmNode),
     Worley(WorleyNode),
 }
 
 impl NoiseNode {
+    pub fn as_bias_gain_mut(&mut self) -> Option<&mut BiasGainNode> {
+        if let Self::BiasGain(node) = self {
+            Some(node)
+        } else {
+            None
+        }
+    }
+
     pub fn as_checkerboard_mut(&mut self) -> Option<&mut CheckerboardNode> {
         if let Self::Checkerboard(node) = self {
             Some(node)
@@ -502,163 +1410,342 @@ impl NoiseNode {
         }
     }
 
-    pub fn as_const_op_f64(&self) -> Option<&ConstantOpNode<f64>> {
-        if let Self::F64Operation(node) = self {
+    pub fn as_const_op_f64(&self) -> Option<&ConstantOpNode<f64>> {
+        if let Self::F64Operation(node) = self {
+            Some(node)
+        } else {
+            None
+        }
+    }
+
+    pub fn as_const_op_f64_mut(&mut self) -> Option<&mut ConstantOpNode<f64>> {
+        if let Self::F64Operation(node) = self {
+            Some(node)
+        } else {
+            None
+        }
+    }
+
+    pub fn as_const_op_tuple(&self) -> Option<&ConstantOpNode<()>> {
+        if let Self::Operation(node) = self {
+            Some(node)
+        } else {
+            None
+        }
+    }
+
+    pub fn as_const_op_tuple_mut(&mut self) -> Option<&mut ConstantOpNode<()>> {
+        if let Self::Operation(node) = self {
+            Some(node)
+        } else {
+            None
+        }
+    }
+
+    pub fn as_const_op_u32(&self) -> Option<&ConstantOpNode<u32>> {
+        if let Self::U32Operation(node) = self {
+            Some(node)
+        } else {
+            None
+        }
+    }
+
+    pub fn as_const_op_u32_mut(&mut self) -> Option<&mut ConstantOpNode<u32>> {
+        if let Self::U32Operation(node) = self {
+            Some(node)
+        } else {
+            None
+        }
+    }
+
+    pub fn as_compare_mut(&mut self) -> Option<&mut CompareNode> {
+        if let Self::Compare(node) = self {
+            Some(node)
+        } else {
+            None
+        }
+    }
+
+    pub fn as_contrast_mut(&mut self) -> Option<&mut ContrastNode> {
+        if let Self::Contrast(node) = self {
+            Some(node)
+        } else {
+            None
+        }
+    }
+
+    pub fn as_control_point(&self) -> Option<&ControlPointNode> {
+        if let Self::ControlPoint(node) = self {
+            Some(node)
+        } else {
+            None
+        }
+    }
+
+    pub fn as_control_point_mut(&mut self) -> Option<&mut ControlPointNode> {
+        if let Self::ControlPoint(node) = self {
+            Some(node)
+        } else {
+            None
+        }
+    }
+
+    pub fn as_crater_mut(&mut self) -> Option<&mut CraterNode> {
+        if let Self::Crater(node) = self {
+            Some(node)
+        } else {
+            None
+        }
+    }
+
+    pub fn as_curve_mut(&mut self) -> Option<&mut CurveNode> {
+        if let Self::Curve(node) = self {
+            Some(node)
+        } else {
+            None
+        }
+    }
+
+    pub fn as_cylinders_mut(&mut self) -> Option<&mut CylindersNode> {
+        if let Self::Cylinders(node) = self {
+            Some(node)
+        } else {
+            None
+        }
+    }
+
+    pub fn as_domain_warp_mut(&mut self) -> Option<&mut DomainWarpNode> {
+        if let Self::DomainWarp(node) = self {
+            Some(node)
+        } else {
+            None
+        }
+    }
+
+    pub fn as_erosion_mut(&mut self) -> Option<&mut ErosionNode> {
+        if let Self::Erosion(node) = self {
+            Some(node)
+        } else {
+            None
+        }
+    }
+
+    pub fn as_exponent_mut(&mut self) -> Option<&mut ExponentNode> {
+        if let Self::Exponent(node) = self {
+            Some(node)
+        } else {
+            None
+        }
+    }
+
+    pub fn as_fault_lines_mut(&mut self) -> Option<&mut FaultLinesNode> {
+        if let Self::FaultLines(node) = self {
+            Some(node)
+        } else {
+            None
+        }
+    }
+
+    pub fn as_formula_mut(&mut self) -> Option<&mut FormulaNode> {
+        if let Self::Formula(node) = self {
+            Some(node)
+        } else {
+            None
+        }
+    }
+
+    pub fn as_generic_fractal_mut(&mut self) -> Option<&mut GenericFractalNode> {
+        if let Self::GenericFractal(node) = self {
+            Some(node)
+        } else {
+            None
+        }
+    }
+
+    pub fn as_fractal_mut(&mut self) -> Option<&mut FractalNode> {
+        if let Self::BasicMulti(node)
+        | Self::Billow(node)
+        | Self::Fbm(node)
+        | Self::HybridMulti(node)
+        | Self::Jordan(node)
+        | Self::Swiss(node) = self
+        {
+            Some(node)
+        } else {
+            None
+        }
+    }
+
+    pub fn as_image_source_mut(&mut self) -> Option<&mut ImageSourceNode> {
+        if let Self::ImageSource(node) = self {
+            Some(node)
+        } else {
+            None
+        }
+    }
+
+    pub fn as_generator_mut(&mut self) -> Option<&mut GeneratorNode> {
+        if let Self::BlueNoise(node)
+        | Self::OpenSimplex(node)
+        | Self::Perlin(node)
+        | Self::PerlinSurflet(node)
+        | Self::Simplex(node)
+        | Self::SuperSimplex(node)
+        | Self::Value(node) = self
+        {
+            Some(node)
+        } else {
+            None
+        }
+    }
+
+    pub fn as_gradient_mut(&mut self) -> Option<&mut GradientNode> {
+        if let Self::Gradient(node) = self {
             Some(node)
         } else {
             None
         }
     }
 
-    pub fn as_const_op_f64_mut(&mut self) -> Option<&mut ConstantOpNode<f64>> {
-        if let Self::F64Operation(node) = self {
+    pub fn as_mix_mut(&mut self) -> Option<&mut MixNode> {
+        if let Self::Mix(node) = self {
             Some(node)
         } else {
             None
         }
     }
 
-    pub fn as_const_op_tuple(&self) -> Option<&ConstantOpNode<()>> {
-        if let Self::Operation(node) = self {
+    pub fn as_normal_mut(&mut self) -> Option<&mut NormalNode> {
+        if let Self::Normal(node) = self {
             Some(node)
         } else {
             None
         }
     }
 
-    pub fn as_const_op_tuple_mut(&mut self) -> Option<&mut ConstantOpNode<()>> {
-        if let Self::Operation(node) = self {
+    pub fn as_random_constant_mut(&mut self) -> Option<&mut RandomConstantNode> {
+        if let Self::RandomConstant(node) = self {
             Some(node)
         } else {
             None
         }
     }
 
-    pub fn as_const_op_u32(&self) -> Option<&ConstantOpNode<u32>> {
-        if let Self::U32Operation(node) = self {
+    pub fn as_remap_mut(&mut self) -> Option<&mut RemapNode> {
+        if let Self::Remap(node) = self {
             Some(node)
         } else {
             None
         }
     }
 
-    pub fn as_const_op_u32_mut(&mut self) -> Option<&mut ConstantOpNode<u32>> {
-        if let Self::U32Operation(node) = self {
+    pub fn as_rigid_fractal_mut(&mut self) -> Option<&mut RigidFractalNode> {
+        if let Self::RigidMulti(node) = self {
             Some(node)
         } else {
             None
         }
     }
 
-    pub fn as_control_point(&self) -> Option<&ControlPointNode> {
-        if let Self::ControlPoint(node) = self {
+    pub fn as_rotate_axis_mut(&mut self) -> Option<&mut RotateAxisNode> {
+        if let Self::RotateAxis(node) = self {
             Some(node)
         } else {
             None
         }
     }
 
-    pub fn as_control_point_mut(&mut self) -> Option<&mut ControlPointNode> {
-        if let Self::ControlPoint(node) = self {
+    pub fn as_scale_bias_mut(&mut self) -> Option<&mut ScaleBiasNode> {
+        if let Self::ScaleBias(node) = self {
             Some(node)
         } else {
             None
         }
     }
 
-    pub fn as_curve_mut(&mut self) -> Option<&mut CurveNode> {
-        if let Self::Curve(node) = self {
+    pub fn as_script_mut(&mut self) -> Option<&mut ScriptNode> {
+        if let Self::Script(node) = self {
             Some(node)
         } else {
             None
         }
     }
 
-    pub fn as_cylinders_mut(&mut self) -> Option<&mut CylindersNode> {
-        if let Self::Cylinders(node) = self {
+    pub fn as_select_mut(&mut self) -> Option<&mut SelectNode> {
+        if let Self::Select(node) = self {
             Some(node)
         } else {
             None
         }
     }
 
-    pub fn as_exponent_mut(&mut self) -> Option<&mut ExponentNode> {
-        if let Self::Exponent(node) = self {
+    pub fn as_slope_mut(&mut self) -> Option<&mut SlopeNode> {
+        if let Self::Slope(node) = self {
             Some(node)
         } else {
             None
         }
     }
 
-    pub fn as_fractal_mut(&mut self) -> Option<&mut FractalNode> {
-        if let Self::BasicMulti(node)
-        | Self::Billow(node)
-        | Self::Fbm(node)
-        | Self::HybridMulti(node) = self
-        {
+    pub fn as_switch_mut(&mut self) -> Option<&mut SwitchNode> {
+        if let Self::Switch(node) = self {
             Some(node)
         } else {
             None
         }
     }
 
-    pub fn as_generator_mut(&mut self) -> Option<&mut GeneratorNode> {
-        if let Self::OpenSimplex(node)
-        | Self::Perlin(node)
-        | Self::PerlinSurflet(node)
-        | Self::Simplex(node)
-        | Self::SuperSimplex(node)
-        | Self::Value(node) = self
-        {
+    pub fn as_terrace_mut(&mut self) -> Option<&mut TerraceNode> {
+        if let Self::Terrace(node) = self {
             Some(node)
         } else {
             None
         }
     }
 
-    pub fn as_rigid_fractal_mut(&mut self) -> Option<&mut RigidFractalNode> {
-        if let Self::RigidMulti(node) = self {
+    pub fn as_tile_point_mut(&mut self) -> Option<&mut TilePointNode> {
+        if let Self::TilePoint(node) = self {
             Some(node)
         } else {
             None
         }
     }
 
-    pub fn as_scale_bias_mut(&mut self) -> Option<&mut ScaleBiasNode> {
-        if let Self::ScaleBias(node) = self {
+    pub fn as_transform_mut(&mut self) -> Option<&mut TransformNode> {
+        if let Self::RotatePoint(node) | Self::ScalePoint(node) | Self::TranslatePoint(node) = self
+        {
             Some(node)
         } else {
             None
         }
     }
 
-    pub fn as_select_mut(&mut self) -> Option<&mut SelectNode> {
-        if let Self::Select(node) = self {
+    pub fn as_turbulence_mut(&mut self) -> Option<&mut TurbulenceNode> {
+        if let Self::Turbulence(node) = self {
             Some(node)
         } else {
             None
         }
     }
 
-    pub fn as_terrace_mut(&mut self) -> Option<&mut TerraceNode> {
-        if let Self::Terrace(node) = self {
+    pub fn as_vector(&self) -> Option<&VectorNode> {
+        if let Self::Vector(node) = self {
             Some(node)
         } else {
             None
         }
     }
 
-    pub fn as_transform_mut(&mut self) -> Option<&mut TransformNode> {
-        if let Self::RotatePoint(node) | Self::ScalePoint(node) | Self::TranslatePoint(node) = self
-        {
+    pub fn as_vector_mut(&mut self) -> Option<&mut VectorNode> {
+        if let Self::Vector(node) = self {
             Some(node)
         } else {
             None
         }
     }
 
-    pub fn as_turbulence_mut(&mut self) -> Option<&mut TurbulenceNode> {
-        if let Self::Turbulence(node) = self {
+    pub fn as_warped_fbm_mut(&mut self) -> Option<&mut WarpedFbmNode> {
+        if let Self::WarpedFbm(node) = self {
             Some(node)
         } else {
             None
@@ -691,6 +1778,8 @@ impl NoiseNode {
                     OpType::Subtract => lhs - rhs,
                 }
             }
+            Self::RandomConstant(node) => node.eval(snarl),
+            Self::Time(_) => 0.0,
             _ => unreachable!(),
         }
     }
@@ -717,41 +1806,100 @@ impl NoiseNode {
             Self::Abs(node) => Expr::Abs(node.expr(node_id, snarl)),
             Self::Add(node) => Expr::Add(node.expr(node_id, snarl, 0.0)),
             Self::BasicMulti(node) => Expr::BasicMulti(node.expr(snarl)),
+            Self::BiasGain(node) => Expr::BiasGain(node.expr(node_id, snarl)),
             Self::Billow(node) => Expr::Billow(node.expr(snarl)),
             Self::Blend(node) => Expr::Blend(node.expr(node_id, snarl)),
+            Self::BlueNoise(node) => Expr::BlueNoise(node.seed.var(snarl)),
+            Self::Cache(node) => Expr::Cache(node.expr(node_id, snarl)),
             Self::Checkerboard(node) => Expr::Checkerboard(node.size.var(snarl)),
             Self::Clamp(node) => Expr::Clamp(node.expr(node_id, snarl)),
+            Self::ColorRamp(_) => *in_pin_expr_or_const(snarl, node_id, 0, 0.0),
+            Self::CombineRgb(node) => Expr::CombineRgb(node.expr(node_id, snarl)),
+            Self::Compare(node) => Expr::Compare(node.expr(node_id, snarl)),
+            Self::Contrast(node) => Expr::Contrast(node.expr(node_id, snarl)),
+            Self::Crater(node) => Expr::Crater(node.expr(node_id, snarl)),
             Self::Curve(node) => Expr::Curve(node.expr(node_id, snarl)),
             Self::Cylinders(node) => Expr::Cylinders(node.frequency.var(snarl)),
+            Self::Difference(node) => Expr::Difference(node.expr(node_id, snarl, 0.0)),
             Self::Displace(node) => Expr::Displace(node.expr(node_id, snarl)),
+            Self::DomainWarp(node) => Expr::DomainWarp(node.expr(node_id, snarl)),
+            Self::Ease(node) => Expr::Ease(node.expr(node_id, snarl)),
+            Self::Erosion(node) => Expr::Erosion(node.expr(snarl)),
             Self::Exponent(node) => Expr::Exponent(node.expr(node_id, snarl)),
             Self::F64(node) => Expr::Constant(Variable::Named(node.name.clone(), node.value)),
             Self::F64Operation(node) => Expr::Constant(node.var(snarl)),
+            Self::FaultLines(node) => Expr::FaultLines(node.expr(snarl)),
             Self::Fbm(node) => Expr::Fbm(node.expr(snarl)),
+            Self::Formula(node) => node.expr(node_id, snarl),
+            Self::GenericFractal(node) => Expr::GenericFractal(node.expr(node_id, snarl)),
+            Self::Gradient(node) => Expr::Gradient(node.expr(snarl)),
             Self::HybridMulti(node) => Expr::HybridMulti(node.expr(snarl)),
+            Self::Jordan(node) => Expr::Jordan(node.expr(snarl)),
+            Self::ImageSource(node) => Expr::ImageSource(node.expr(snarl)),
             Self::Max(node) => Expr::Max(node.expr(node_id, snarl, 1.0)),
             Self::Min(node) => Expr::Min(node.expr(node_id, snarl, -1.0)),
+            Self::MirrorPoint(node) => Expr::MirrorPoint(node.expr(node_id, snarl)),
+            Self::Mix(node) => Expr::Mix(node.expr(node_id, snarl)),
             Self::Multiply(node) => Expr::Multiply(node.expr(node_id, snarl, 1.0)),
             Self::Negate(node) => Expr::Negate(node.expr(node_id, snarl)),
+            Self::Normal(node) => Expr::Normal(node.expr(node_id, snarl)),
+            Self::Normalize(node) => Expr::Normalize(node.expr(node_id, snarl)),
             Self::OpenSimplex(node) => Expr::OpenSimplex(node.seed.var(snarl)),
             Self::Perlin(node) => Expr::Perlin(node.seed.var(snarl)),
             Self::PerlinSurflet(node) => Expr::PerlinSurflet(node.seed.var(snarl)),
             Self::Power(node) => Expr::Power(node.expr(node_id, snarl, 1.0)),
+            Self::RandomConstant(node) => Expr::Constant(Variable::Anonymous(node.eval(snarl))),
+            Self::Remap(node) => Expr::Remap(node.expr(node_id, snarl)),
             Self::RigidMulti(node) => Expr::RidgedMulti(node.expr(snarl)),
+            Self::RotateAxis(node) => Expr::RotateAxis(node.expr(node_id, snarl)),
             Self::RotatePoint(node) => Expr::RotatePoint(node.expr(node_id, snarl)),
             Self::ScaleBias(node) => Expr::ScaleBias(node.expr(node_id, snarl)),
             Self::ScalePoint(node) => Expr::ScalePoint(node.expr(node_id, snarl)),
+            Self::Script(node) => Expr::Script(node.expr(node_id, snarl)),
             Self::Select(node) => Expr::Select(node.expr(node_id, snarl)),
             Self::Simplex(node) => Expr::Simplex(node.seed.var(snarl)),
+            Self::Slope(node) => Expr::Slope(node.expr(node_id, snarl)),
+            Self::Subgraph(node) => node.expr(node_id, snarl),
+            Self::SubgraphInput(node) => node.expr(),
             Self::SuperSimplex(node) => Expr::SuperSimplex(node.seed.var(snarl)),
+            Self::Swiss(node) => Expr::Swiss(node.expr(snarl)),
+            Self::Switch(node) => Expr::Switch(node.expr(node_id, snarl)),
+            Self::Swizzle(node) => Expr::Swizzle(node.expr(node_id, snarl)),
+            Self::Time(_) => Expr::Constant(TimeNode::var()),
             Self::Terrace(node) => Expr::Terrace(node.expr(node_id, snarl)),
+            Self::TilePoint(node) => Expr::TilePoint(node.expr(node_id, snarl)),
             Self::TranslatePoint(node) => Expr::TranslatePoint(node.expr(node_id, snarl)),
             Self::Turbulence(node) => Expr::Turbulence(node.expr(node_id, snarl)),
             Self::Value(node) => Expr::Value(node.seed.var(snarl)),
+            Self::WarpedFbm(node) => Expr::WarpedFbm(node.expr(snarl)),
             Self::Worley(node) => Expr::Worley(node.expr(snarl)),
-            Self::ControlPoint(_) | Self::Operation(_) | Self::U32(_) | Self::U32Operation(_) => {
-                unreachable!()
+            Self::Comment(_)
+            | Self::ControlPoint(_)
+            | Self::Group(_)
+            | Self::Operation(_)
+            | Self::SubgraphOutput(_)
+            | Self::U32(_)
+            | Self::U32Operation(_)
+            | Self::Vector(_) => unreachable!(),
+        }
+    }
+
+    /// Returns the ramp which the preview of a node is colored with: that of the first color ramp
+    /// node found by following the first input of each node upstream, if any.
+    pub fn color_ramp(mut node_id: NodeId, snarl: &Snarl<Self>) -> Option<ColorRamp> {
+        loop {
+            if let Self::ColorRamp(node) = snarl.get_node(node_id)? {
+                return Some(node.ramp);
             }
+
+            node_id = snarl
+                .in_pin(InPinId {
+                    node: node_id,
+                    input: 0,
+                })
+                .remotes
+                .first()?
+                .node;
         }
     }
 
@@ -762,88 +1910,455 @@ impl NoiseNode {
     pub fn image(&self) -> Option<&Image> {
         match self {
             Self::Abs(UnaryNode { image, .. })
+            | Self::Cache(UnaryNode { image, .. })
             | Self::Add(CombinerNode { image, .. })
             | Self::BasicMulti(FractalNode { image, .. })
+            | Self::BiasGain(BiasGainNode { image, .. })
             | Self::Billow(FractalNode { image, .. })
             | Self::Blend(BlendNode { image, .. })
+            | Self::BlueNoise(GeneratorNode { image, .. })
             | Self::Checkerboard(CheckerboardNode { image, .. })
             | Self::Clamp(ClampNode { image, .. })
+            | Self::ColorRamp(ColorRampNode { image, .. })
+            | Self::CombineRgb(CombineRgbNode { image, .. })
+            | Self::Compare(CompareNode { image, .. })
+            | Self::Contrast(ContrastNode { image, .. })
+            | Self::Crater(CraterNode { image, .. })
             | Self::Curve(CurveNode { image, .. })
             | Self::Cylinders(CylindersNode { image, .. })
+            | Self::Difference(CombinerNode { image, .. })
             | Self::Displace(DisplaceNode { image, .. })
+            | Self::DomainWarp(DomainWarpNode { image, .. })
+            | Self::Ease(EaseNode { image, .. })
+            | Self::Erosion(ErosionNode { image, .. })
             | Self::Exponent(ExponentNode { image, .. })
+            | Self::FaultLines(FaultLinesNode { image, .. })
             | Self::Fbm(FractalNode { image, .. })
+            | Self::Formula(FormulaNode { image, .. })
+            | Self::GenericFractal(GenericFractalNode { image, .. })
+            | Self::Gradient(GradientNode { image, .. })
             | Self::HybridMulti(FractalNode { image, .. })
+            | Self::ImageSource(ImageSourceNode { image, .. })
+            | Self::Jordan(FractalNode { image, .. })
             | Self::Max(CombinerNode { image, .. })
             | Self::Min(CombinerNode { image, .. })
+            | Self::MirrorPoint(MirrorPointNode { image, .. })
+            | Self::Mix(MixNode { image, .. })
             | Self::Multiply(CombinerNode { image, .. })
             | Self::Negate(UnaryNode { image, .. })
+            | Self::Normal(NormalNode { image, .. })
+            | Self::Normalize(NormalizeNode { image, .. })
             | Self::OpenSimplex(GeneratorNode { image, .. })
             | Self::Perlin(GeneratorNode { image, .. })
             | Self::PerlinSurflet(GeneratorNode { image, .. })
             | Self::Power(CombinerNode { image, .. })
+            | Self::Remap(RemapNode { image, .. })
             | Self::RigidMulti(RigidFractalNode { image, .. })
+            | Self::RotateAxis(RotateAxisNode { image, .. })
             | Self::RotatePoint(TransformNode { image, .. })
             | Self::ScaleBias(ScaleBiasNode { image, .. })
             | Self::ScalePoint(TransformNode { image, .. })
+            | Self::Script(ScriptNode { image, .. })
             | Self::Select(SelectNode { image, .. })
             | Self::Simplex(GeneratorNode { image, .. })
+            | Self::Slope(SlopeNode { image, .. })
+            | Self::Subgraph(SubgraphNode { image, .. })
             | Self::SuperSimplex(GeneratorNode { image, .. })
+            | Self::Swiss(FractalNode { image, .. })
+            | Self::Switch(SwitchNode { image, .. })
+            | Self::Swizzle(SwizzleNode { image, .. })
             | Self::Terrace(TerraceNode { image, .. })
+            | Self::TilePoint(TilePointNode { image, .. })
             | Self::TranslatePoint(TransformNode { image, .. })
             | Self::Turbulence(TurbulenceNode { image, .. })
             | Self::Value(GeneratorNode { image, .. })
+            | Self::WarpedFbm(WarpedFbmNode { image, .. })
             | Self::Worley(WorleyNode { image, .. }) => Some(image),
-            Self::ControlPoint(_)
+            Self::Comment(_)
+            | Self::ControlPoint(_)
             | Self::F64(_)
             | Self::F64Operation(_)
+            | Self::Group(_)
             | Self::Operation(_)
+            | Self::RandomConstant(_)
+            | Self::SubgraphInput(_)
+            | Self::SubgraphOutput(_)
+            | Self::Time(_)
             | Self::U32(_)
-            | Self::U32Operation(_) => None,
+            | Self::U32Operation(_)
+            | Self::Vector(_) => None,
         }
     }
 
     pub fn image_mut(&mut self) -> Option<&mut Image> {
         match self {
             Self::Abs(UnaryNode { image, .. })
+            | Self::Cache(UnaryNode { image, .. })
             | Self::Add(CombinerNode { image, .. })
             | Self::BasicMulti(FractalNode { image, .. })
+            | Self::BiasGain(BiasGainNode { image, .. })
             | Self::Billow(FractalNode { image, .. })
             | Self::Blend(BlendNode { image, .. })
+            | Self::BlueNoise(GeneratorNode { image, .. })
             | Self::Checkerboard(CheckerboardNode { image, .. })
             | Self::Clamp(ClampNode { image, .. })
+            | Self::ColorRamp(ColorRampNode { image, .. })
+            | Self::CombineRgb(CombineRgbNode { image, .. })
+            | Self::Compare(CompareNode { image, .. })
+            | Self::Contrast(ContrastNode { image, .. })
+            | Self::Crater(CraterNode { image, .. })
             | Self::Curve(CurveNode { image, .. })
             | Self::Cylinders(CylindersNode { image, .. })
+            | Self::Difference(CombinerNode { image, .. })
             | Self::Displace(DisplaceNode { image, .. })
+            | Self::DomainWarp(DomainWarpNode { image, .. })
+            | Self::Ease(EaseNode { image, .. })
+            | Self::Erosion(ErosionNode { image, .. })
             | Self::Exponent(ExponentNode { image, .. })
+            | Self::FaultLines(FaultLinesNode { image, .. })
             | Self::Fbm(FractalNode { image, .. })
+            | Self::Formula(FormulaNode { image, .. })
+            | Self::GenericFractal(GenericFractalNode { image, .. })
+            | Self::Gradient(GradientNode { image, .. })
             | Self::HybridMulti(FractalNode { image, .. })
+            | Self::ImageSource(ImageSourceNode { image, .. })
+            | Self::Jordan(FractalNode { image, .. })
             | Self::Max(CombinerNode { image, .. })
             | Self::Min(CombinerNode { image, .. })
+            | Self::MirrorPoint(MirrorPointNode { image, .. })
+            | Self::Mix(MixNode { image, .. })
             | Self::Multiply(CombinerNode { image, .. })
             | Self::Negate(UnaryNode { image, .. })
+            | Self::Normal(NormalNode { image, .. })
+            | Self::Normalize(NormalizeNode { image, .. })
             | Self::OpenSimplex(GeneratorNode { image, .. })
             | Self::Perlin(GeneratorNode { image, .. })
             | Self::PerlinSurflet(GeneratorNode { image, .. })
             | Self::Power(CombinerNode { image, .. })
+            | Self::Remap(RemapNode { image, .. })
             | Self::RigidMulti(RigidFractalNode { image, .. })
+            | Self::RotateAxis(RotateAxisNode { image, .. })
             | Self::RotatePoint(TransformNode { image, .. })
             | Self::ScaleBias(ScaleBiasNode { image, .. })
             | Self::ScalePoint(TransformNode { image, .. })
+            | Self::Script(ScriptNode { image, .. })
             | Self::Select(SelectNode { image, .. })
             | Self::Simplex(GeneratorNode { image, .. })
+            | Self::Slope(SlopeNode { image, .. })
+            | Self::Subgraph(SubgraphNode { image, .. })
             | Self::SuperSimplex(GeneratorNode { image, .. })
+            | Self::Swiss(FractalNode { image, .. })
+            | Self::Switch(SwitchNode { image, .. })
+            | Self::Swizzle(SwizzleNode { image, .. })
             | Self::Terrace(TerraceNode { image, .. })
+            | Self::TilePoint(TilePointNode { image, .. })
             | Self::TranslatePoint(TransformNode { image, .. })
             | Self::Turbulence(TurbulenceNode { image, .. })
             | Self::Value(GeneratorNode { image, .. })
+            | Self::WarpedFbm(WarpedFbmNode { image, .. })
             | Self::Worley(WorleyNode { image, .. }) => Some(image),
-            Self::ControlPoint(_)
+            Self::Comment(_)
+            | Self::ControlPoint(_)
             | Self::F64(_)
             | Self::F64Operation(_)
+            | Self::Group(_)
             | Self::Operation(_)
+            | Self::RandomConstant(_)
+            | Self::SubgraphInput(_)
+            | Self::SubgraphOutput(_)
+            | Self::Time(_)
             | Self::U32(_)
-            | Self::U32Operation(_) => None,
+            | Self::U32Operation(_)
+            | Self::Vector(_) => None,
+        }
+    }
+
+    /// Returns every value of this node which may be connected to another node.
+    pub fn node_values_mut(&mut self) -> NodeValuesMut<'_> {
+        let mut values = NodeValuesMut::default();
+
+        match self {
+            Self::BasicMulti(node)
+            | Self::Billow(node)
+            | Self::Fbm(node)
+            | Self::HybridMulti(node)
+            | Self::Jordan(node)
+            | Self::Swiss(node) => {
+                values.u32s.extend([&mut node.seed, &mut node.octaves]);
+                values.f64s.extend([
+                    &mut node.frequency,
+                    &mut node.lacunarity,
+                    &mut node.persistence,
+                ]);
+            }
+            Self::BiasGain(node) => values.f64s.extend([&mut node.bias, &mut node.gain]),
+            Self::Checkerboard(node) => values.u32s.push(&mut node.size),
+            Self::Comment(_)
+            | Self::Group(_)
+            | Self::Subgraph(_)
+            | Self::SubgraphInput(_)
+            | Self::SubgraphOutput(_) => (),
+            Self::Clamp(node) => values
+                .f64s
+                .extend([&mut node.lower_bound, &mut node.upper_bound]),
+            Self::Compare(node) => values.f64s.push(&mut node.tolerance),
+            Self::Contrast(node) => values.f64s.extend([&mut node.contrast, &mut node.pivot]),
+            Self::ControlPoint(node) => values.f64s.extend([&mut node.input, &mut node.output]),
+            Self::Crater(node) => {
+                values.u32s.push(&mut node.seed);
+                values
+                    .f64s
+                    .extend([&mut node.density, &mut node.radius, &mut node.depth]);
+            }
+            Self::Curve(CurveNode {
+                control_point_node_ids,
+                ..
+            })
+            | Self::Terrace(TerraceNode {
+                control_point_node_ids,
+                ..
+            }) => values
+                .control_point_node_ids
+                .extend(control_point_node_ids.iter_mut()),
+            Self::Cylinders(node) => values.f64s.push(&mut node.frequency),
+            Self::DomainWarp(node) => values
+                .f64s
+                .extend([&mut node.amplitude, &mut node.frequency]),
+            Self::Erosion(node) => {
+                values.u32s.extend([&mut node.seed, &mut node.octaves]);
+                values.f64s.extend([
+                    &mut node.frequency,
+                    &mut node.lacunarity,
+                    &mut node.persistence,
+                    &mut node.strength,
+                ]);
+            }
+            Self::Exponent(node) => values.f64s.push(&mut node.exponent),
+            Self::FaultLines(node) => {
+                values.u32s.extend([&mut node.seed, &mut node.iterations]);
+                values.f64s.push(&mut node.amplitude);
+            }
+            Self::F64Operation(node) => values.f64s.extend(node.inputs.iter_mut()),
+            Self::GenericFractal(node) => {
+                values.u32s.push(&mut node.octaves);
+                values.f64s.extend([
+                    &mut node.frequency,
+                    &mut node.lacunarity,
+                    &mut node.persistence,
+                ]);
+            }
+            Self::Gradient(node) => values.f64s.extend([&mut node.angle, &mut node.radius]),
+            Self::ImageSource(node) => values.f64s.push(&mut node.size),
+            Self::Mix(node) => values.f64s.push(&mut node.factor),
+            Self::Normal(node) => values.f64s.extend([&mut node.epsilon, &mut node.strength]),
+            Self::BlueNoise(node)
+            | Self::OpenSimplex(node)
+            | Self::Perlin(node)
+            | Self::PerlinSurflet(node)
+            | Self::Simplex(node)
+            | Self::SuperSimplex(node)
+            | Self::Value(node) => values.u32s.push(&mut node.seed),
+            Self::Vector(node) => values.f64s.extend(node.axes.iter_mut()),
+            Self::Operation(node) => values.tuples.extend(node.inputs.iter_mut()),
+            Self::RandomConstant(node) => {
+                values.u32s.extend([&mut node.seed, &mut node.index]);
+                values.f64s.extend([&mut node.min, &mut node.max]);
+            }
+            Self::Remap(node) => values.f64s.extend([
+                &mut node.in_min,
+                &mut node.in_max,
+                &mut node.out_min,
+                &mut node.out_max,
+            ]),
+            Self::RigidMulti(node) => {
+                values.u32s.extend([&mut node.seed, &mut node.octaves]);
+                values.f64s.extend([
+                    &mut node.frequency,
+                    &mut node.lacunarity,
+                    &mut node.persistence,
+                    &mut node.attenuation,
+                ]);
+            }
+            Self::RotateAxis(node) => {
+                values.f64s.extend(node.axis.iter_mut());
+                values.f64s.push(&mut node.angle);
+            }
+            Self::RotatePoint(node) | Self::ScalePoint(node) | Self::TranslatePoint(node) => {
+                values.f64s.extend(node.axes.iter_mut());
+                values.vector_node_ids.push(&mut node.vector_node_id);
+            }
+            Self::ScaleBias(node) => values.f64s.extend([&mut node.scale, &mut node.bias]),
+            Self::Select(node) => values.f64s.extend([
+                &mut node.lower_bound,
+                &mut node.upper_bound,
+                &mut node.falloff,
+            ]),
+            Self::Slope(node) => values.f64s.push(&mut node.epsilon),
+            Self::Switch(node) => values.u32s.push(&mut node.selector),
+            Self::TilePoint(node) => values.f64s.extend(node.periods.iter_mut()),
+            Self::Turbulence(node) => {
+                values.u32s.extend([&mut node.seed, &mut node.roughness]);
+                values.f64s.extend([&mut node.frequency, &mut node.power]);
+            }
+            Self::U32Operation(node) => values.u32s.extend(node.inputs.iter_mut()),
+            Self::WarpedFbm(node) => {
+                values
+                    .u32s
+                    .extend([&mut node.seed, &mut node.octaves, &mut node.iterations]);
+                values.f64s.extend([
+                    &mut node.frequency,
+                    &mut node.lacunarity,
+                    &mut node.persistence,
+                    &mut node.strength,
+                ]);
+            }
+            Self::Worley(node) => {
+                values.u32s.push(&mut node.seed);
+                values.f64s.push(&mut node.frequency);
+            }
+            Self::Abs(_)
+            | Self::Add(_)
+            | Self::Blend(_)
+            | Self::Cache(_)
+            | Self::ColorRamp(_)
+            | Self::CombineRgb(_)
+            | Self::Difference(_)
+            | Self::Displace(_)
+            | Self::Ease(_)
+            | Self::F64(_)
+            | Self::Formula(_)
+            | Self::Max(_)
+            | Self::Min(_)
+            | Self::MirrorPoint(_)
+            | Self::Multiply(_)
+            | Self::Negate(_)
+            | Self::Normalize(_)
+            | Self::Power(_)
+            | Self::Script(_)
+            | Self::Swizzle(_)
+            | Self::Time(_)
+            | Self::U32(_) => (),
+        }
+
+        values
+    }
+
+    /// The display name of this type of node.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Abs(_) => "Abs",
+            Self::Add(_) => "Add",
+            Self::BasicMulti(_) => "Basic Multi",
+            Self::BiasGain(_) => "Bias + Gain",
+            Self::Billow(_) => "Billow",
+            Self::Blend(_) => "Blend",
+            Self::BlueNoise(_) => "Blue Noise",
+            Self::Cache(_) => "Cache",
+            Self::Checkerboard(_) => "Checkerboard",
+            Self::Clamp(_) => "Clamp",
+            Self::ColorRamp(_) => "Color Ramp",
+            Self::CombineRgb(_) => "Combine RGB",
+            Self::Comment(_) => "Comment",
+            Self::Compare(_) => "Compare",
+            Self::Contrast(_) => "Contrast",
+            Self::ControlPoint(_) => "Control Point",
+            Self::Crater(_) => "Crater",
+            Self::Curve(_) => "Curve",
+            Self::Cylinders(_) => "Cylinders",
+            Self::Difference(_) => "Difference",
+            Self::Displace(_) => "Displace",
+            Self::DomainWarp(_) => "Domain Warp",
+            Self::Ease(_) => "Ease",
+            Self::Erosion(_) => "Erosion",
+            Self::Exponent(_) => "Exponent",
+            Self::F64(_) => "Decimal",
+            Self::F64Operation(_) => "Decimal Operation",
+            Self::FaultLines(_) => "Fault Lines",
+            Self::Fbm(_) => "fBm",
+            Self::Formula(_) => "Formula",
+            Self::GenericFractal(_) => "Generic Fractal",
+            Self::Gradient(_) => "Gradient",
+            Self::Group(_) => "Group",
+            Self::HybridMulti(_) => "Hybrid Multi",
+            Self::ImageSource(_) => "Image Source",
+            Self::Jordan(_) => "Jordan Turbulence",
+            Self::Max(_) => "Max",
+            Self::Min(_) => "Min",
+            Self::MirrorPoint(_) => "Mirror Point",
+            Self::Mix(_) => "Mix",
+            Self::Multiply(_) => "Multiply",
+            Self::Negate(_) => "Negate",
+            Self::Normal(_) => "Normal",
+            Self::Normalize(_) => "Normalize",
+            Self::OpenSimplex(_) => "Open Simplex",
+            Self::Operation(_) => "Operation",
+            Self::Perlin(_) => "Perlin",
+            Self::PerlinSurflet(_) => "Perlin Surflet",
+            Self::Power(_) => "Power",
+            Self::RandomConstant(_) => "Random Constant",
+            Self::Remap(_) => "Remap",
+            Self::RigidMulti(_) => "Rigid Multi",
+            Self::RotateAxis(_) => "Rotate Axis",
+            Self::RotatePoint(_) => "Rotate Point",
+            Self::ScaleBias(_) => "Scale + Bias",
+            Self::ScalePoint(_) => "Scale Point",
+            Self::Script(_) => "Script",
+            Self::Select(_) => "Select",
+            Self::Simplex(_) => "Simplex",
+            Self::Slope(_) => "Slope",
+            Self::Subgraph(_) => "Subgraph",
+            Self::SubgraphInput(_) => "Subgraph Input",
+            Self::SubgraphOutput(_) => "Subgraph Output",
+            Self::SuperSimplex(_) => "Super Simplex",
+            Self::Swiss(_) => "Swiss Turbulence",
+            Self::Switch(_) => "Switch",
+            Self::Swizzle(_) => "Swizzle",
+            Self::Terrace(_) => "Terrace",
+            Self::Time(_) => "Time",
+            Self::TilePoint(_) => "Tile Point",
+            Self::TranslatePoint(_) => "Translate Point",
+            Self::Turbulence(_) => "Turbulence",
+            Self::U32(_) => "Integer",
+            Self::U32Operation(_) => "Integer Operation",
+            Self::Value(_) => "Value",
+            Self::Vector(_) => "Vector",
+            Self::WarpedFbm(_) => "Warped fBm",
+            Self::Worley(_) => "Worley",
+        }
+    }
+
+    /// The custom title shown in the node header, if any.
+    pub fn title(&self) -> Option<&str> {
+        match self {
+            Self::ControlPoint(ControlPointNode { title, .. })
+            | Self::F64(ConstantNode { title, .. })
+            | Self::F64Operation(ConstantOpNode { title, .. })
+            | Self::Group(GroupNode { title, .. })
+            | Self::Operation(ConstantOpNode { title, .. })
+            | Self::RandomConstant(RandomConstantNode { title, .. })
+            | Self::Time(TimeNode { title, .. })
+            | Self::U32(ConstantNode { title, .. })
+            | Self::U32Operation(ConstantOpNode { title, .. })
+            | Self::Vector(VectorNode { title, .. }) => title.as_deref(),
+            _ => self.image().and_then(|image| image.title.as_deref()),
+        }
+    }
+
+    /// Returns `None` for comments, which are not titled.
+    pub fn title_mut(&mut self) -> Option<&mut Option<String>> {
+        match self {
+            Self::Comment(_) => None,
+            Self::ControlPoint(ControlPointNode { title, .. })
+            | Self::F64(ConstantNode { title, .. })
+            | Self::F64Operation(ConstantOpNode { title, .. })
+            | Self::Group(GroupNode { title, .. })
+            | Self::Operation(ConstantOpNode { title, .. })
+            | Self::RandomConstant(RandomConstantNode { title, .. })
+            | Self::Time(TimeNode { title, .. })
+            | Self::U32(ConstantNode { title, .. })
+            | Self::U32Operation(ConstantOpNode { title, .. })
+            | Self::Vector(VectorNode { title, .. }) => Some(title),
+            _ => self.image_mut().map(|image| &mut image.title),
         }
     }
 
@@ -886,6 +2401,7 @@ impl NoiseNode {
                             .try_into()
                             .unwrap(),
                         op_ty: op.op_ty,
+                        title: op.title,
                     });
                 } else {
                     unreachable!();
@@ -949,6 +2465,7 @@ impl NoiseNode {
                     .try_into()
                     .unwrap(),
                 op_ty: op.op_ty,
+                title: op.title,
             });
         }
 
@@ -1007,6 +2524,7 @@ impl NoiseNode {
                     .try_into()
                     .unwrap(),
                 op_ty: op.op_ty,
+                title: op.title,
             });
         }
 
@@ -1024,45 +2542,351 @@ impl NoiseNode {
         let mut node_ids = NODE_IDS.take().unwrap();
         node_ids.push(node_id);
 
-        while let Some(node_id) = node_ids.pop() {
-            if child_node_ids.insert(node_id) {
-                node_ids.extend(
-                    snarl
-                        .out_pin(OutPinId {
-                            node: node_id,
-                            output: 0,
-                        })
-                        .remotes
-                        .iter()
-                        .map(|remote| remote.node),
-                );
+        while let Some(node_id) = node_ids.pop() {
+            if child_node_ids.insert(node_id) {
+                node_ids.extend(
+                    snarl
+                        .out_pin(OutPinId {
+                            node: node_id,
+                            output: 0,
+                        })
+                        .remotes
+                        .iter()
+                        .map(|remote| remote.node),
+                );
+
+                if let node @ Self::Operation(_) = snarl.get_node_mut(node_id).unwrap() {
+                    let op = node.as_const_op_tuple().unwrap().clone();
+                    node_ids.extend(op.inputs.iter().filter_map(|input| input.as_node_id()));
+
+                    *node = NoiseNode::U32Operation(ConstantOpNode {
+                        inputs: op
+                            .inputs
+                            .iter()
+                            .copied()
+                            .map(|input| {
+                                input.as_node_id().map(NodeValue::Node).unwrap_or_default()
+                            })
+                            .collect::<Vec<_>>()
+                            .try_into()
+                            .unwrap(),
+                        op_ty: op.op_ty,
+                        title: op.title,
+                    });
+                } else {
+                    unreachable!();
+                }
+            }
+        }
+
+        child_node_ids.clear();
+        CHILD_NODE_IDS.set(Some(child_node_ids));
+        NODE_IDS.set(Some(node_ids));
+    }
+
+    /// Returns the current seed of nodes which have one.
+    #[allow(unused)]
+    pub fn seed(&self, snarl: &Snarl<Self>) -> Option<u32> {
+        match self {
+            Self::BasicMulti(FractalNode { seed, .. })
+            | Self::Billow(FractalNode { seed, .. })
+            | Self::BlueNoise(GeneratorNode { seed, .. })
+            | Self::Crater(CraterNode { seed, .. })
+            | Self::Erosion(ErosionNode { seed, .. })
+            | Self::FaultLines(FaultLinesNode { seed, .. })
+            | Self::Fbm(FractalNode { seed, .. })
+            | Self::HybridMulti(FractalNode { seed, .. })
+            | Self::Jordan(FractalNode { seed, .. })
+            | Self::OpenSimplex(GeneratorNode { seed, .. })
+            | Self::Perlin(GeneratorNode { seed, .. })
+            | Self::PerlinSurflet(GeneratorNode { seed, .. })
+            | Self::RigidMulti(RigidFractalNode { seed, .. })
+            | Self::Simplex(GeneratorNode { seed, .. })
+            | Self::SuperSimplex(GeneratorNode { seed, .. })
+            | Self::Swiss(FractalNode { seed, .. })
+            | Self::Turbulence(TurbulenceNode { seed, .. })
+            | Self::Value(GeneratorNode { seed, .. })
+            | Self::WarpedFbm(WarpedFbmNode { seed, .. })
+            | Self::Worley(WorleyNode { seed, .. }) => Some(seed.eval(snarl)),
+            _ => None,
+        }
+    }
+}
+
+/// A grayscale image, such as a real-world heightmap, which is stored in the node so that projects
+/// and exported expressions do not depend on the image file.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ImageSourceNode {
+    pub image: Image,
+
+    /// The name of the file the image was loaded from, or empty if none has been loaded.
+    pub file_name: String,
+
+    pub width: u32,
+    pub height: u32,
+    pub samples: Vec<u16>,
+
+    pub size: NodeValue<f64>,
+    pub tiling: ImageTiling,
+}
+
+impl ImageSourceNode {
+    /// Images larger than this are scaled down when loaded, which keeps projects small and
+    /// previews fast.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub const MAX_SIZE: u32 = 1024;
+
+    fn expr(&self, snarl: &Snarl<NoiseNode>) -> ImageSourceExpr {
+        ImageSourceExpr {
+            width: self.width,
+            height: self.height,
+            samples: self.samples.clone(),
+            size: self.size.var(snarl),
+            tiling: self.tiling,
+        }
+    }
+
+    /// Replaces the image with the luminance of the given image file.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn load(&mut self, path: &Path) -> anyhow::Result<()> {
+        let mut image = image::open(path)?;
+
+        if image.width() > Self::MAX_SIZE || image.height() > Self::MAX_SIZE {
+            image = image.resize(Self::MAX_SIZE, Self::MAX_SIZE, FilterType::Triangle);
+        }
+
+        let image = image.into_luma16();
+
+        self.file_name = path
+            .file_name()
+            .map(|file_name| file_name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        self.width = image.width();
+        self.height = image.height();
+        self.samples = image.into_raw();
+
+        Ok(())
+    }
+}
+
+impl Default for ImageSourceNode {
+    fn default() -> Self {
+        Self {
+            image: Default::default(),
+            file_name: String::new(),
+            width: 0,
+            height: 0,
+            samples: vec![],
+            size: NodeValue::Value(4.0),
+            tiling: ImageTiling::Clamp,
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct MirrorPointNode {
+    pub image: Image,
+
+    /// Whether each of the X, Y, Z and W coordinates is mirrored.
+    pub axes: [bool; 4],
+}
+
+impl MirrorPointNode {
+    fn expr(&self, node_id: NodeId, snarl: &Snarl<NoiseNode>) -> MirrorPointExpr {
+        MirrorPointExpr {
+            source: in_pin_expr_or_const(snarl, node_id, 0, 0.0),
+            axes: self.axes,
+        }
+    }
+}
+
+impl Default for MirrorPointNode {
+    fn default() -> Self {
+        Self {
+            image: Default::default(),
+            axes: [true, true, false, false],
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct MixNode {
+    pub image: Image,
+
+    pub factor: NodeValue<f64>,
+}
+
+impl MixNode {
+    fn expr(&self, node_id: NodeId, snarl: &Snarl<NoiseNode>) -> MixExpr {
+        MixExpr {
+            sources: (0..2)
+                .map(|input| in_pin_expr_or_const(snarl, node_id, input, 0.0))
+                .collect::<Vec<_>>()
+                .try_into()
+                .unwrap(),
+            factor: self.factor.var(snarl),
+        }
+    }
+}
+
+impl Default for MixNode {
+    fn default() -> Self {
+        Self {
+            image: Default::default(),
+            factor: NodeValue::Value(0.5),
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct NormalNode {
+    pub image: Image,
+
+    pub component: NormalComponent,
+    pub epsilon: NodeValue<f64>,
+    pub strength: NodeValue<f64>,
+}
+
+impl NormalNode {
+    fn expr(&self, node_id: NodeId, snarl: &Snarl<NoiseNode>) -> NormalExpr {
+        NormalExpr {
+            source: in_pin_expr_or_const(snarl, node_id, 0, 0.0),
+            component: self.component,
+            epsilon: self.epsilon.var(snarl),
+            strength: self.strength.var(snarl),
+        }
+    }
+}
+
+impl Default for NormalNode {
+    fn default() -> Self {
+        Self {
+            image: Default::default(),
+            component: NormalComponent::X,
+            epsilon: NodeValue::Value(0.01),
+            strength: NodeValue::Value(1.0),
+        }
+    }
+}
+
+/// Maps the source from the conventional -1..1 range of noise, or an estimate of its actual range,
+/// onto 0..1.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct NormalizeNode {
+    pub image: Image,
+
+    pub estimated: bool,
+}
+
+impl NormalizeNode {
+    fn expr(&self, node_id: NodeId, snarl: &Snarl<NoiseNode>) -> NormalizeExpr {
+        NormalizeExpr {
+            source: in_pin_expr_or_const(snarl, node_id, 0, 0.0),
+            estimated: self.estimated,
+        }
+    }
+}
+
+/// Mutable references to every value of a node which may be connected to another node (see
+/// `NoiseNode::node_values_mut`).
+#[derive(Default)]
+pub struct NodeValuesMut<'a> {
+    /// The nodes connected to each control point input of a curve or terrace.
+    pub control_point_node_ids: Vec<&'a mut Option<NodeId>>,
+
+    pub f64s: Vec<&'a mut NodeValue<f64>>,
+
+    /// The inputs of an operation which has not yet been connected to a decimal or integer value.
+    pub tuples: Vec<&'a mut NodeValue<()>>,
+
+    pub u32s: Vec<&'a mut NodeValue<u32>>,
+
+    /// The node connected to the vector input of a transform.
+    pub vector_node_ids: Vec<&'a mut Option<NodeId>>,
+}
+
+/// A pseudo-random decimal between two bounds which only changes with its seed and index, so that
+/// one master seed may drive several decorrelated parameters.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct RandomConstantNode {
+    /// A title shown in the node header instead of the type of node.
+    #[serde(default)]
+    pub title: Option<String>,
+
+    pub seed: NodeValue<u32>,
+
+    /// Picks one of the values derived from the seed; nodes sharing a seed should use different
+    /// indices.
+    pub index: NodeValue<u32>,
+
+    pub min: NodeValue<f64>,
+    pub max: NodeValue<f64>,
+}
+
+impl RandomConstantNode {
+    pub fn eval(&self, snarl: &Snarl<NoiseNode>) -> f64 {
+        // SplitMix64 of the seed and index, which gives well distributed values even for
+        // consecutive seeds and indices
+        let mut value = ((self.seed.eval(snarl) as u64) << 32 | self.index.eval(snarl) as u64)
+            .wrapping_add(0x9e37_79b9_7f4a_7c15);
+        value = (value ^ (value >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        value = (value ^ (value >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        value ^= value >> 31;
+
+        // The top 53 bits give every representable value in 0..1 the same chance
+        let t = (value >> 11) as f64 / (1u64 << 53) as f64;
+        let (min, max) = (self.min.eval(snarl), self.max.eval(snarl));
+
+        min + (max - min) * t
+    }
+}
+
+impl Default for RandomConstantNode {
+    fn default() -> Self {
+        Self {
+            title: None,
+            seed: NodeValue::Value(0),
+            index: NodeValue::Value(0),
+            min: NodeValue::Value(0.0),
+            max: NodeValue::Value(1.0),
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct RemapNode {
+    pub image: Image,
 
-                if let node @ Self::Operation(_) = snarl.get_node_mut(node_id).unwrap() {
-                    let op = node.as_const_op_tuple().unwrap().clone();
-                    node_ids.extend(op.inputs.iter().filter_map(|input| input.as_node_id()));
+    pub in_min: NodeValue<f64>,
+    pub in_max: NodeValue<f64>,
+    pub out_min: NodeValue<f64>,
+    pub out_max: NodeValue<f64>,
+    pub clamped: bool,
+}
 
-                    *node = NoiseNode::U32Operation(ConstantOpNode {
-                        inputs: op
-                            .inputs
-                            .iter()
-                            .copied()
-                            .map(|input| {
-                                input.as_node_id().map(NodeValue::Node).unwrap_or_default()
-                            })
-                            .collect::<Vec<_>>()
-                            .try_into()
-                            .unwrap(),
-                        op_ty: op.op_ty,
-                    });
-                } else {
-                    unreachable!();
-                }
-            }
+impl RemapNode {
+    fn expr(&self, node_id: NodeId, snarl: &Snarl<NoiseNode>) -> RemapExpr {
+        RemapExpr {
+            source: in_pin_expr_or_const(snarl, node_id, 0, 0.0),
+            in_min: self.in_min.var(snarl),
+            in_max: self.in_max.var(snarl),
+            out_min: self.out_min.var(snarl),
+            out_max: self.out_max.var(snarl),
+            clamped: self.clamped,
         }
+    }
+}
 
-        child_node_ids.clear();
-        CHILD_NODE_IDS.set(Some(child_node_ids));
-        NODE_IDS.set(Some(node_ids));
+impl Default for RemapNode {
+    fn default() -> Self {
+        Self {
+            image: Default::default(),
+            in_min: NodeValue::Value(-1.0),
+            in_max: NodeValue::Value(1.0),
+            out_min: NodeValue::Value(0.0),
+            out_max: NodeValue::Value(1.0),
+            clamped: false,
+        }
     }
 }
 
@@ -1108,6 +2932,38 @@ impl Default for RigidFractalNode {
     }
 }
 
+#[derive(Clone, Serialize, Deserialize)]
+pub struct RotateAxisNode {
+    pub image: Image,
+
+    pub axis: [NodeValue<f64>; 3],
+    pub angle: NodeValue<f64>,
+}
+
+impl RotateAxisNode {
+    fn expr(&self, node_id: NodeId, snarl: &Snarl<NoiseNode>) -> RotateAxisExpr {
+        RotateAxisExpr {
+            source: in_pin_expr_or_const(snarl, node_id, 0, 0.0),
+            axis: self.axis.each_ref().map(|axis| axis.var(snarl)),
+            angle: self.angle.var(snarl),
+        }
+    }
+}
+
+impl Default for RotateAxisNode {
+    fn default() -> Self {
+        Self {
+            image: Default::default(),
+            axis: [
+                NodeValue::Value(0.0),
+                NodeValue::Value(0.0),
+                NodeValue::Value(1.0),
+            ],
+            angle: NodeValue::Value(0.0),
+        }
+    }
+}
+
 #[derive(Clone, Default, Serialize, Deserialize)]
 pub struct ScaleBiasNode {
     pub image: Image,
@@ -1126,6 +2982,70 @@ impl ScaleBiasNode {
     }
 }
 
+/// Evaluates a Rhai script for every sample, for operations which no other node provides; scripts
+/// are much slower than other nodes.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ScriptNode {
+    pub image: Image,
+
+    pub script: String,
+
+    /// The number of source pins; one more pin is always shown so that sources may be added.
+    pub source_count: usize,
+}
+
+impl ScriptNode {
+    pub const MIN_SOURCE_COUNT: usize = 1;
+
+    fn expr(&self, node_id: NodeId, snarl: &Snarl<NoiseNode>) -> ScriptExpr {
+        let mut variables = Vec::<Variable<f64>>::new();
+
+        for (_, node) in snarl.node_ids() {
+            if let NoiseNode::F64(node) = node {
+                if self.references(&node.name)
+                    && !variables
+                        .iter()
+                        .any(|variable| matches!(variable, Variable::Named(name, _) if *name == node.name))
+                {
+                    variables.push(Variable::Named(node.name.clone(), node.value));
+                }
+            }
+        }
+
+        ScriptExpr {
+            script: self.script.clone(),
+            sources: (0..self.source_count)
+                .map(|idx| *in_pin_expr_or_const(snarl, node_id, idx, 0.0))
+                .collect(),
+            variables,
+        }
+    }
+
+    /// Returns the error message of a script which does not compile.
+    pub fn check(&self) -> Result<(), String> {
+        ScriptExpr::check(&self.script)
+    }
+
+    /// Returns `true` if the script uses the given name, which is how it refers to constants.
+    pub fn references(&self, name: &str) -> bool {
+        !name.is_empty()
+            && self
+                .script
+                .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+                .any(|word| word == name)
+    }
+}
+
+impl Default for ScriptNode {
+    fn default() -> Self {
+        Self {
+            image: Default::default(),
+            script: "source".to_owned(),
+            source_count: Self::MIN_SOURCE_COUNT,
+        }
+    }
+}
+
 #[derive(Clone, Serialize, Deserialize)]
 pub struct SelectNode {
     pub image: Image,
@@ -1162,46 +3082,298 @@ impl Default for SelectNode {
     }
 }
 
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SlopeNode {
+    pub image: Image,
+
+    pub epsilon: NodeValue<f64>,
+}
+
+impl SlopeNode {
+    fn expr(&self, node_id: NodeId, snarl: &Snarl<NoiseNode>) -> SlopeExpr {
+        SlopeExpr {
+            source: in_pin_expr_or_const(snarl, node_id, 0, 0.0),
+            epsilon: self.epsilon.var(snarl),
+        }
+    }
+}
+
+impl Default for SlopeNode {
+    fn default() -> Self {
+        Self {
+            image: Default::default(),
+            epsilon: NodeValue::Value(0.01),
+        }
+    }
+}
+
 impl Default for SourceType {
     fn default() -> Self {
         Self::Perlin
     }
 }
 
+/// A reusable sub-graph shown as a single node.
+///
+/// Each input of the subgraph node feeds the `SubgraphInput` node with the same index inside the
+/// inner graph, and the subgraph node outputs whatever is connected to the `SubgraphOutput` node.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SubgraphNode {
+    pub image: Image,
+
+    pub input_count: usize,
+    pub snarl: Snarl<NoiseNode>,
+}
+
+impl SubgraphNode {
+    /// Gives each `SubgraphInput` node of the inner graph the expression connected to the matching
+    /// input of the subgraph node.
+    pub fn bind_inputs(
+        inner_snarl: &mut Snarl<NoiseNode>,
+        node_id: NodeId,
+        snarl: &Snarl<NoiseNode>,
+    ) {
+        for node in inner_snarl.nodes_mut() {
+            if let NoiseNode::SubgraphInput(input) = node {
+                input.expr = in_pin_expr(snarl, node_id, input.index);
+            }
+        }
+    }
+
+    fn expr(&self, node_id: NodeId, snarl: &Snarl<NoiseNode>) -> Expr {
+        let mut inner_snarl = self.snarl.clone();
+        Self::bind_inputs(&mut inner_snarl, node_id, snarl);
+
+        let output_node_id = inner_snarl
+            .node_ids()
+            .find(|(_, node)| matches!(node, NoiseNode::SubgraphOutput(_)))
+            .map(|(output_node_id, _)| output_node_id);
+
+        match output_node_id {
+            Some(output_node_id) => *in_pin_expr_or_const(&inner_snarl, output_node_id, 0, 0.0),
+            None => Expr::Constant(Variable::Anonymous(0.0)),
+        }
+    }
+}
+
+/// Stands in for one input of the subgraph node inside the inner graph.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SubgraphInputNode {
+    pub index: usize,
+
+    /// The expression connected to the matching input of the subgraph node, if any.
+    #[serde(skip)]
+    pub expr: Option<Box<Expr>>,
+}
+
+impl SubgraphInputNode {
+    fn expr(&self) -> Expr {
+        self.expr
+            .as_deref()
+            .cloned()
+            .unwrap_or(Expr::Constant(Variable::Anonymous(0.0)))
+    }
+}
+
+/// Receives the output of the inner graph of a subgraph node.
 #[derive(Clone, Default, Serialize, Deserialize)]
+pub struct SubgraphOutputNode;
+
+/// Passes through the source picked by the selector, so that graph variants may stay wired up
+/// while flipping between them.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SwitchNode {
+    pub image: Image,
+
+    pub selector: NodeValue<u32>,
+
+    /// The number of source pins; one more pin is always shown so that sources may be added.
+    pub source_count: usize,
+}
+
+impl SwitchNode {
+    pub const MIN_SOURCE_COUNT: usize = 2;
+
+    fn expr(&self, node_id: NodeId, snarl: &Snarl<NoiseNode>) -> SwitchExpr {
+        SwitchExpr {
+            sources: (0..self.source_count)
+                .map(|idx| *in_pin_expr_or_const(snarl, node_id, idx + 1, 0.0))
+                .collect(),
+            selector: self.selector.var(snarl),
+        }
+    }
+}
+
+impl Default for SwitchNode {
+    fn default() -> Self {
+        Self {
+            image: Default::default(),
+            selector: NodeValue::Value(0),
+            source_count: Self::MIN_SOURCE_COUNT,
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SwizzleNode {
+    pub image: Image,
+
+    /// The component of the sampled point used for each of the X, Y, Z and W coordinates of the
+    /// source.
+    pub components: [SwizzleComponent; 4],
+}
+
+impl SwizzleNode {
+    fn expr(&self, node_id: NodeId, snarl: &Snarl<NoiseNode>) -> SwizzleExpr {
+        SwizzleExpr {
+            source: in_pin_expr_or_const(snarl, node_id, 0, 0.0),
+            components: self.components,
+        }
+    }
+}
+
+impl Default for SwizzleNode {
+    fn default() -> Self {
+        Self {
+            image: Default::default(),
+            components: [
+                SwizzleComponent::X,
+                SwizzleComponent::Y,
+                SwizzleComponent::Z,
+                SwizzleComponent::W,
+            ],
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
 pub struct TerraceNode {
     pub image: Image,
 
     pub inverted: bool,
     pub control_point_node_ids: Vec<Option<NodeId>>,
+
+    #[serde(default)]
+    pub easing: TerraceEasing,
+
+    /// The value of each control point placed with the in-node editor; these are used along with
+    /// any connected control point nodes.
+    #[serde(default)]
+    pub points: Vec<f64>,
+
+    /// Shows the in-node editor, which plots the terrace and allows dragging its points.
+    #[serde(default)]
+    pub editor: bool,
 }
 
 impl TerraceNode {
+    /// Returns the connected control points followed by those placed with the in-node editor.
+    pub fn control_points(&self, snarl: &Snarl<NoiseNode>) -> Vec<Variable<f64>> {
+        self.control_point_node_ids
+            .iter()
+            .copied()
+            .filter_map(|node_id| {
+                node_id.map(|node_id| match snarl.get_node(node_id).unwrap() {
+                    NoiseNode::F64(node) => Variable::Named(node.name.clone(), node.value),
+                    NoiseNode::F64Operation(node) => node.var(snarl),
+                    _ => unreachable!(),
+                })
+            })
+            .chain(self.points.iter().copied().map(Variable::Anonymous))
+            .collect()
+    }
+
     fn expr(&self, node_id: NodeId, snarl: &Snarl<NoiseNode>) -> TerraceExpr {
         TerraceExpr {
             source: in_pin_expr_or_const(snarl, node_id, 0, 0.0),
             inverted: self.inverted,
-            control_points: self
-                .control_point_node_ids
+            control_points: self.control_points(snarl),
+            easing: self.easing,
+        }
+    }
+}
+
+impl Default for TerraceNode {
+    fn default() -> Self {
+        Self {
+            image: Default::default(),
+            inverted: false,
+            control_point_node_ids: Default::default(),
+            easing: Default::default(),
+            points: vec![-1.0, -0.5, 0.0, 0.5, 1.0],
+            editor: true,
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct TilePointNode {
+    pub image: Image,
+
+    pub periods: [NodeValue<f64>; 4],
+    pub blended: bool,
+}
+
+impl TilePointNode {
+    fn expr(&self, node_id: NodeId, snarl: &Snarl<NoiseNode>) -> TilePointExpr {
+        TilePointExpr {
+            source: in_pin_expr_or_const(snarl, node_id, 0, 0.0),
+            periods: self
+                .periods
                 .iter()
-                .copied()
-                .filter_map(|node_id| {
-                    node_id.map(|node_id| match snarl.get_node(node_id).unwrap() {
-                        NoiseNode::F64(node) => Variable::Named(node.name.clone(), node.value),
-                        NoiseNode::F64Operation(node) => node.var(snarl),
-                        _ => unreachable!(),
-                    })
-                })
-                .collect(),
+                .map(|period| period.var(snarl))
+                .collect::<Vec<_>>()
+                .try_into()
+                .unwrap(),
+            blended: self.blended,
+        }
+    }
+}
+
+impl Default for TilePointNode {
+    fn default() -> Self {
+        // Only the axes of the preview are tiled so that blending takes four samples, not sixteen
+        Self {
+            image: Default::default(),
+            periods: [
+                NodeValue::Value(4.0),
+                NodeValue::Value(4.0),
+                NodeValue::Value(0.0),
+                NodeValue::Value(0.0),
+            ],
+            blended: false,
         }
     }
 }
 
+/// The time of the timeline, which animates previews while playing and is zero otherwise.
+///
+/// Exported expressions name the value `time` so that it may be set like any other variable.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct TimeNode {
+    /// A title shown in the node header instead of the type of node.
+    #[serde(default)]
+    pub title: Option<String>,
+}
+
+impl TimeNode {
+    /// The name of the variable set by the timeline.
+    pub const NAME: &'static str = "time";
+
+    fn var() -> Variable<f64> {
+        Variable::Named(Self::NAME.to_owned(), 0.0)
+    }
+}
+
 #[derive(Clone, Serialize, Deserialize)]
 pub struct TransformNode {
     pub image: Image,
 
     pub axes: [NodeValue<f64>; 4],
+
+    /// A vector node which provides every axis instead of `axes`.
+    #[serde(default)]
+    pub vector_node_id: Option<NodeId>,
 }
 
 impl TransformNode {
@@ -1209,14 +3381,21 @@ impl TransformNode {
         Self {
             image: Default::default(),
             axes: [NodeValue::Value(value); 4],
+            vector_node_id: None,
         }
     }
 
     fn expr(&self, node_id: NodeId, snarl: &Snarl<NoiseNode>) -> TransformExpr {
+        let axes = self
+            .vector_node_id
+            .and_then(|node_id| snarl.get_node(node_id))
+            .and_then(NoiseNode::as_vector)
+            .map(|vector| &vector.axes)
+            .unwrap_or(&self.axes);
+
         TransformExpr {
             source: in_pin_expr_or_const(snarl, node_id, 0, 0.0),
-            axes: self
-                .axes
+            axes: axes
                 .iter()
                 .map(|axis| axis.var(snarl))
                 .collect::<Vec<_>>()
@@ -1284,6 +3463,61 @@ impl UnaryNode {
     }
 }
 
+/// Four decimals which may be connected to a transform at once instead of wiring each axis.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct VectorNode {
+    pub axes: [NodeValue<f64>; 4],
+
+    /// A title shown in the node header instead of the type of node.
+    #[serde(default)]
+    pub title: Option<String>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct WarpedFbmNode {
+    pub image: Image,
+
+    pub source_ty: SourceType,
+    pub seed: NodeValue<u32>,
+    pub octaves: NodeValue<u32>,
+    pub frequency: NodeValue<f64>,
+    pub lacunarity: NodeValue<f64>,
+    pub persistence: NodeValue<f64>,
+    pub strength: NodeValue<f64>,
+    pub iterations: NodeValue<u32>,
+}
+
+impl WarpedFbmNode {
+    fn expr(&self, snarl: &Snarl<NoiseNode>) -> WarpedFbmExpr {
+        WarpedFbmExpr {
+            source_ty: self.source_ty,
+            seed: self.seed.var(snarl),
+            octaves: self.octaves.var(snarl),
+            frequency: self.frequency.var(snarl),
+            lacunarity: self.lacunarity.var(snarl),
+            persistence: self.persistence.var(snarl),
+            strength: self.strength.var(snarl),
+            iterations: self.iterations.var(snarl),
+        }
+    }
+}
+
+impl Default for WarpedFbmNode {
+    fn default() -> Self {
+        Self {
+            image: Default::default(),
+            source_ty: Default::default(),
+            seed: NodeValue::Value(Fractal::<AnySeedable>::DEFAULT_SEED),
+            octaves: NodeValue::Value(Fractal::<AnySeedable>::DEFAULT_OCTAVES as _),
+            frequency: NodeValue::Value(Fractal::<AnySeedable>::DEFAULT_FREQUENCY),
+            lacunarity: NodeValue::Value(Fractal::<AnySeedable>::DEFAULT_LACUNARITY),
+            persistence: NodeValue::Value(Fractal::<AnySeedable>::DEFAULT_PERSISTENCE),
+            strength: NodeValue::Value(1.0),
+            iterations: NodeValue::Value(2),
+        }
+    }
+}
+
 #[derive(Clone, Serialize, Deserialize)]
 pub struct WorleyNode {
     pub image: Image,
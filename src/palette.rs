@@ -0,0 +1,429 @@
+use {
+    super::{
+        expr::OpType,
+        node::{ConstantOpNode, NoiseNode, TransformNode},
+    },
+    egui::{Context, Id, Key, Pos2, RichText, TextEdit, Window},
+    egui_snarl::{NodeId, Snarl},
+    std::collections::HashSet,
+};
+
+/// A type of node which may be added to the graph.
+pub struct NodeType {
+    pub category: &'static str,
+    pub name: &'static str,
+    pub new: fn() -> NoiseNode,
+}
+
+impl NodeType {
+    /// Every type of node, in the order shown by the graph menu.
+    pub const ALL: [Self; 75] = [
+        Self::new("Combiners", "Add", || NoiseNode::Add(Default::default())),
+        Self::new("Combiners", "Combine RGB", || {
+            NoiseNode::CombineRgb(Default::default())
+        }),
+        Self::new("Combiners", "Difference", || {
+            NoiseNode::Difference(Default::default())
+        }),
+        Self::new("Combiners", "Formula", || {
+            NoiseNode::Formula(Default::default())
+        }),
+        Self::new("Combiners", "Min", || NoiseNode::Min(Default::default())),
+        Self::new("Combiners", "Max", || NoiseNode::Max(Default::default())),
+        Self::new("Combiners", "Mix", || NoiseNode::Mix(Default::default())),
+        Self::new("Combiners", "Multiply", || {
+            NoiseNode::Multiply(Default::default())
+        }),
+        Self::new(
+            "Combiners",
+            "Power",
+            || NoiseNode::Power(Default::default()),
+        ),
+        Self::new("Combiners", "Script", || {
+            NoiseNode::Script(Default::default())
+        }),
+        Self::new("Generators", "Blue Noise", || {
+            NoiseNode::BlueNoise(Default::default())
+        }),
+        Self::new("Generators", "Checkerboard", || {
+            NoiseNode::Checkerboard(Default::default())
+        }),
+        Self::new("Generators", "Cylinders", || {
+            NoiseNode::Cylinders(Default::default())
+        }),
+        Self::new("Generators", "Fault Lines", || {
+            NoiseNode::FaultLines(Default::default())
+        }),
+        Self::new("Generators", "Gradient", || {
+            NoiseNode::Gradient(Default::default())
+        }),
+        Self::new("Generators", "Image Source", || {
+            NoiseNode::ImageSource(Default::default())
+        }),
+        Self::new("Generators", "Open Simplex", || {
+            NoiseNode::OpenSimplex(Default::default())
+        }),
+        Self::new("Generators", "Perlin", || {
+            NoiseNode::Perlin(Default::default())
+        }),
+        Self::new("Generators", "Perlin Surflet", || {
+            NoiseNode::PerlinSurflet(Default::default())
+        }),
+        Self::new("Generators", "Simplex", || {
+            NoiseNode::Simplex(Default::default())
+        }),
+        Self::new("Generators", "Super Simplex", || {
+            NoiseNode::SuperSimplex(Default::default())
+        }),
+        Self::new("Generators", "Value", || {
+            NoiseNode::Value(Default::default())
+        }),
+        Self::new("Generators", "Worley", || {
+            NoiseNode::Worley(Default::default())
+        }),
+        Self::new("Fractals", "Basic Multi", || {
+            NoiseNode::BasicMulti(Default::default())
+        }),
+        Self::new("Fractals", "Hybrid Multi", || {
+            NoiseNode::HybridMulti(Default::default())
+        }),
+        Self::new("Fractals", "Rigid Multi", || {
+            NoiseNode::RigidMulti(Default::default())
+        }),
+        Self::new("Fractals", "Billow", || {
+            NoiseNode::Billow(Default::default())
+        }),
+        Self::new("Fractals", "fBm", || NoiseNode::Fbm(Default::default())),
+        Self::new("Fractals", "Erosion", || {
+            NoiseNode::Erosion(Default::default())
+        }),
+        Self::new("Fractals", "Generic Fractal", || {
+            NoiseNode::GenericFractal(Default::default())
+        }),
+        Self::new("Fractals", "Swiss Turbulence", || {
+            NoiseNode::Swiss(Default::default())
+        }),
+        Self::new("Fractals", "Jordan Turbulence", || {
+            NoiseNode::Jordan(Default::default())
+        }),
+        Self::new("Fractals", "Warped fBm", || {
+            NoiseNode::WarpedFbm(Default::default())
+        }),
+        Self::new("Modifiers", "Abs", || NoiseNode::Abs(Default::default())),
+        Self::new("Modifiers", "Bias + Gain", || {
+            NoiseNode::BiasGain(Default::default())
+        }),
+        Self::new(
+            "Modifiers",
+            "Cache",
+            || NoiseNode::Cache(Default::default()),
+        ),
+        Self::new(
+            "Modifiers",
+            "Clamp",
+            || NoiseNode::Clamp(Default::default()),
+        ),
+        Self::new("Modifiers", "Color Ramp", || {
+            NoiseNode::ColorRamp(Default::default())
+        }),
+        Self::new("Modifiers", "Contrast", || {
+            NoiseNode::Contrast(Default::default())
+        }),
+        Self::new("Modifiers", "Crater", || {
+            NoiseNode::Crater(Default::default())
+        }),
+        Self::new(
+            "Modifiers",
+            "Curve",
+            || NoiseNode::Curve(Default::default()),
+        ),
+        Self::new("Modifiers", "Ease", || NoiseNode::Ease(Default::default())),
+        Self::new("Modifiers", "Exponent", || {
+            NoiseNode::Exponent(Default::default())
+        }),
+        Self::new("Modifiers", "Negate", || {
+            NoiseNode::Negate(Default::default())
+        }),
+        Self::new("Modifiers", "Normal", || {
+            NoiseNode::Normal(Default::default())
+        }),
+        Self::new("Modifiers", "Normalize", || {
+            NoiseNode::Normalize(Default::default())
+        }),
+        Self::new(
+            "Modifiers",
+            "Remap",
+            || NoiseNode::Remap(Default::default()),
+        ),
+        Self::new("Modifiers", "Scale + Bias", || {
+            NoiseNode::ScaleBias(Default::default())
+        }),
+        Self::new(
+            "Modifiers",
+            "Slope",
+            || NoiseNode::Slope(Default::default()),
+        ),
+        Self::new("Modifiers", "Terrace", || {
+            NoiseNode::Terrace(Default::default())
+        }),
+        Self::new(
+            "Selectors",
+            "Blend",
+            || NoiseNode::Blend(Default::default()),
+        ),
+        Self::new("Selectors", "Compare", || {
+            NoiseNode::Compare(Default::default())
+        }),
+        Self::new("Selectors", "Select", || {
+            NoiseNode::Select(Default::default())
+        }),
+        Self::new("Selectors", "Switch", || {
+            NoiseNode::Switch(Default::default())
+        }),
+        Self::new("Transformers", "Displace", || {
+            NoiseNode::Displace(Default::default())
+        }),
+        Self::new("Transformers", "Domain Warp", || {
+            NoiseNode::DomainWarp(Default::default())
+        }),
+        Self::new("Transformers", "Mirror Point", || {
+            NoiseNode::MirrorPoint(Default::default())
+        }),
+        Self::new("Transformers", "Rotate Axis", || {
+            NoiseNode::RotateAxis(Default::default())
+        }),
+        Self::new("Transformers", "Rotate Point", || {
+            NoiseNode::RotatePoint(TransformNode::zero())
+        }),
+        Self::new("Transformers", "Scale Point", || {
+            NoiseNode::ScalePoint(TransformNode::one())
+        }),
+        Self::new("Transformers", "Swizzle", || {
+            NoiseNode::Swizzle(Default::default())
+        }),
+        Self::new("Transformers", "Tile Point", || {
+            NoiseNode::TilePoint(Default::default())
+        }),
+        Self::new("Transformers", "Translate Point", || {
+            NoiseNode::TranslatePoint(TransformNode::zero())
+        }),
+        Self::new("Transformers", "Turbulence", || {
+            NoiseNode::Turbulence(Default::default())
+        }),
+        Self::new("Constants", "Control Point", || {
+            NoiseNode::ControlPoint(Default::default())
+        }),
+        Self::new(
+            "Constants",
+            "Decimal",
+            || NoiseNode::F64(Default::default()),
+        ),
+        Self::new(
+            "Constants",
+            "Integer",
+            || NoiseNode::U32(Default::default()),
+        ),
+        Self::new("Constants", "Random Constant", || {
+            NoiseNode::RandomConstant(Default::default())
+        }),
+        Self::new("Constants", "Time", || NoiseNode::Time(Default::default())),
+        Self::new("Constants", "Vector", || {
+            NoiseNode::Vector(Default::default())
+        }),
+        Self::new("Operations", "Add", || {
+            NoiseNode::Operation(ConstantOpNode::new(OpType::Add, ()))
+        }),
+        Self::new("Operations", "Divide", || {
+            NoiseNode::Operation(ConstantOpNode::new(OpType::Divide, ()))
+        }),
+        Self::new("Operations", "Multiply", || {
+            NoiseNode::Operation(ConstantOpNode::new(OpType::Multiply, ()))
+        }),
+        Self::new("Operations", "Subtract", || {
+            NoiseNode::Operation(ConstantOpNode::new(OpType::Subtract, ()))
+        }),
+        Self::new("Annotations", "Comment", || {
+            NoiseNode::Comment(Default::default())
+        }),
+    ];
+
+    const fn new(category: &'static str, name: &'static str, new: fn() -> NoiseNode) -> Self {
+        Self {
+            category,
+            name,
+            new,
+        }
+    }
+
+    /// Inserts a new node of this type; image nodes are added to `updated_node_ids` so that their
+    /// previews are rendered.
+    pub fn insert(
+        &self,
+        snarl: &mut Snarl<NoiseNode>,
+        pos: Pos2,
+        updated_node_ids: &mut HashSet<NodeId>,
+    ) -> NodeId {
+        let node = (self.new)();
+        let has_image = node.has_image();
+        let node_id = snarl.insert_node(pos, node);
+
+        if has_image {
+            updated_node_ids.insert(node_id);
+        }
+
+        node_id
+    }
+}
+
+/// A popup which adds a node by typing part of its name instead of browsing the graph menu.
+pub struct NodePalette {
+    /// The graph position new nodes are inserted at.
+    pos: Pos2,
+
+    query: String,
+
+    /// The screen position of the popup.
+    screen_pos: Pos2,
+
+    /// The index of the highlighted search result.
+    selected: usize,
+}
+
+impl NodePalette {
+    const MAX_RESULTS: usize = 12;
+
+    pub fn new(screen_pos: Pos2, pos: Pos2) -> Self {
+        Self {
+            pos,
+            query: String::new(),
+            screen_pos,
+            selected: 0,
+        }
+    }
+
+    /// Scores how closely `query` matches `name`, lower being closer, or returns `None` if the
+    /// characters of `query` do not all appear in order within `name`.
+    fn fuzzy_score(query: &str, name: &str) -> Option<usize> {
+        let mut chars = name
+            .chars()
+            .map(|char| char.to_ascii_lowercase())
+            .enumerate();
+        let mut last_idx = None;
+        let mut score = 0;
+
+        for query_char in query
+            .chars()
+            .filter(|char| !char.is_whitespace())
+            .map(|char| char.to_ascii_lowercase())
+        {
+            let (idx, _) = chars.find(|&(_, char)| char == query_char)?;
+
+            // Skipped characters count against the match, so prefixes and runs score best
+            score += idx - last_idx.map(|last_idx| last_idx + 1).unwrap_or_default();
+            last_idx = Some(idx);
+        }
+
+        Some(score)
+    }
+
+    fn results(&self) -> Vec<&'static NodeType> {
+        let mut results = NodeType::ALL
+            .iter()
+            .filter_map(|node_type| {
+                Self::fuzzy_score(&self.query, node_type.name).map(|score| (score, node_type))
+            })
+            .collect::<Vec<_>>();
+
+        // Stable, so equal scores keep the graph menu order
+        results.sort_by_key(|&(score, _)| score);
+        results.truncate(Self::MAX_RESULTS);
+
+        results
+            .into_iter()
+            .map(|(_, node_type)| node_type)
+            .collect()
+    }
+
+    /// Returns `false` once a node has been added or the palette has been dismissed.
+    pub fn show(
+        &mut self,
+        ctx: &Context,
+        snarl: &mut Snarl<NoiseNode>,
+        updated_node_ids: &mut HashSet<NodeId>,
+    ) -> bool {
+        let mut open = true;
+        let mut inserted = None;
+
+        let window = Window::new("Add Node")
+            .id(Id::new("node_palette"))
+            .fixed_pos(self.screen_pos)
+            .title_bar(false)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                let response = ui.add(
+                    TextEdit::singleline(&mut self.query)
+                        .hint_text("Search nodes")
+                        .desired_width(192.0),
+                );
+                response.request_focus();
+
+                if response.changed() {
+                    self.selected = 0;
+                }
+
+                let results = self.results();
+
+                ui.input(|input| {
+                    if input.key_pressed(Key::ArrowDown) {
+                        self.selected += 1;
+                    }
+
+                    if input.key_pressed(Key::ArrowUp) {
+                        self.selected = self.selected.saturating_sub(1);
+                    }
+
+                    if input.key_pressed(Key::Enter) {
+                        inserted = results.get(self.selected).copied();
+                    }
+
+                    if input.key_pressed(Key::Escape) {
+                        open = false;
+                    }
+                });
+
+                self.selected = self.selected.min(results.len().saturating_sub(1));
+
+                ui.separator();
+
+                if results.is_empty() {
+                    ui.weak("No matching nodes");
+                }
+
+                for (idx, node_type) in results.into_iter().enumerate() {
+                    ui.horizontal(|ui| {
+                        if ui
+                            .selectable_label(idx == self.selected, node_type.name)
+                            .clicked()
+                        {
+                            inserted = Some(node_type);
+                        }
+
+                        ui.label(RichText::new(node_type.category).weak().small());
+                    });
+                }
+            });
+
+        if window.is_some_and(|window| window.response.clicked_elsewhere()) {
+            open = false;
+        }
+
+        if let Some(node_type) = inserted {
+            node_type.insert(snarl, self.pos, updated_node_ids);
+
+            return false;
+        }
+
+        open
+    }
+}
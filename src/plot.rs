@@ -0,0 +1,144 @@
+use {
+    super::{
+        expr::Expr,
+        node::{Image, NoiseNode},
+    },
+    egui::{
+        pos2, Align2, Color32, Context, FontId, Id, Sense, Shape, Slider, Stroke, Vec2, Window,
+    },
+    egui_snarl::{NodeId, Snarl},
+};
+
+/// A window plotting the values of one image node along a horizontal line through its preview.
+///
+/// A value-vs-x curve shows the effect of `Curve` and `Terrace` control points far more clearly
+/// than the grayscale preview image does.
+pub struct PlotWindow {
+    pub node_id: NodeId,
+
+    /// The vertical position of the sampled line within the preview image, from `0` at the top to
+    /// `1` at the bottom.
+    pub row: f64,
+
+    /// The image version which the samples were last taken for.
+    pub version: Option<usize>,
+
+    samples: Vec<f64>,
+}
+
+impl PlotWindow {
+    const SAMPLE_COUNT: usize = 256;
+
+    pub fn new(node_id: NodeId) -> Self {
+        Self {
+            node_id,
+            row: 0.5,
+            version: None,
+            samples: vec![],
+        }
+    }
+
+    /// Samples the expression along the line, at the same coordinates as the preview image.
+    pub fn sample(&mut self, expr: &Expr, image: &Image, w: Option<f64>) {
+        let noise = expr.noise_at_w(w);
+        let step = 1.0 / Self::SAMPLE_COUNT as f64;
+        let eval_x = (self.row + image.y) * image.scale;
+
+        self.samples.clear();
+        self.samples.extend((0..Self::SAMPLE_COUNT).map(|idx| {
+            let eval_y = (idx as f64 * step + step / 2.0 + image.x) * image.scale;
+            noise.get([eval_x, eval_y, 0.0])
+        }));
+    }
+
+    /// Returns `false` once the window has been closed or its node has been removed.
+    pub fn show(&mut self, ctx: &Context, snarl: &Snarl<NoiseNode>) -> bool {
+        let Some(node) = snarl.get_node(self.node_id) else {
+            return false;
+        };
+
+        let mut open = true;
+
+        Window::new(format!("Plot: {}", node.name()))
+            .id(Id::new("plot_window"))
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(true)
+            .default_size(Vec2::new(512.0, 256.0))
+            .show(ctx, |ui| {
+                // The new line is sampled on the next frame
+                if ui
+                    .add(Slider::new(&mut self.row, 0.0..=1.0).text("Row"))
+                    .changed()
+                {
+                    self.version = None;
+                }
+
+                let size = Vec2::new(ui.available_width(), ui.available_height().max(128.0));
+                let (rect, response) = ui.allocate_exact_size(size, Sense::hover());
+                let painter = ui.painter_at(rect);
+
+                // The plot always includes the -1..=1 range shown by previews
+                let (min, max) = self
+                    .samples
+                    .iter()
+                    .fold((-1.0f64, 1.0f64), |(min, max), &sample| {
+                        (min.min(sample), max.max(sample))
+                    });
+
+                // Leaves room for the value labels above and below the curve
+                let area = rect.shrink2(Vec2::new(0.0, 12.0));
+                let value_to_y = |value: f64| {
+                    area.bottom() - ((value - min) / (max - min)) as f32 * area.height()
+                };
+
+                let text_color = ui.visuals().weak_text_color();
+                for value in [-1.0, 0.0, 1.0] {
+                    let y = value_to_y(value);
+                    painter.hline(rect.x_range(), y, Stroke::new(1.0, text_color));
+                    painter.text(
+                        pos2(rect.left() + 2.0, y),
+                        Align2::LEFT_BOTTOM,
+                        format!("{value}"),
+                        FontId::monospace(10.0),
+                        text_color,
+                    );
+                }
+
+                if self.samples.len() > 1 {
+                    let step = rect.width() / (self.samples.len() - 1) as f32;
+                    let points = self
+                        .samples
+                        .iter()
+                        .enumerate()
+                        .map(|(idx, &sample)| {
+                            pos2(rect.left() + idx as f32 * step, value_to_y(sample))
+                        })
+                        .collect();
+
+                    painter.add(Shape::line(
+                        points,
+                        Stroke::new(1.5, ui.visuals().strong_text_color()),
+                    ));
+
+                    if let Some(pos) = response.hover_pos() {
+                        let idx = (((pos.x - rect.left()) / step).round() as usize)
+                            .min(self.samples.len() - 1);
+                        let sample = self.samples[idx];
+                        let point = pos2(rect.left() + idx as f32 * step, value_to_y(sample));
+
+                        painter.circle_filled(point, 3.0, Color32::RED);
+                        painter.text(
+                            pos2(point.x, rect.top()),
+                            Align2::CENTER_TOP,
+                            format!("{sample:.4}"),
+                            FontId::monospace(12.0),
+                            ui.visuals().text_color(),
+                        );
+                    }
+                }
+            });
+
+        open
+    }
+}
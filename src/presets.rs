@@ -0,0 +1,67 @@
+use {
+    super::{clipboard::detached_node, node::NoiseNode},
+    eframe::{get_value, set_value, Storage},
+    egui::Pos2,
+    egui_snarl::{NodeId, Snarl},
+    serde::{Deserialize, Serialize},
+    std::collections::HashSet,
+};
+
+/// A configured node saved by the user so that it can be added again from the graph menu.
+#[derive(Deserialize, Serialize)]
+pub struct Preset {
+    pub name: String,
+    node: NoiseNode,
+}
+
+impl Preset {
+    /// Inserts a copy of the preset node; image nodes are added to `updated_node_ids` so that their
+    /// previews are rendered.
+    pub fn insert(
+        &self,
+        snarl: &mut Snarl<NoiseNode>,
+        pos: Pos2,
+        updated_node_ids: &mut HashSet<NodeId>,
+    ) -> NodeId {
+        let has_image = self.node.has_image();
+        let node_id = snarl.insert_node(pos, self.node.clone());
+
+        if has_image {
+            updated_node_ids.insert(node_id);
+        }
+
+        node_id
+    }
+}
+
+/// Every preset, stored with the application settings instead of with the project.
+#[derive(Default, Deserialize, Serialize)]
+pub struct Presets {
+    pub presets: Vec<Preset>,
+}
+
+impl Presets {
+    pub const KEY: &'static str = "presets";
+
+    pub fn load(storage: Option<&dyn Storage>) -> Self {
+        storage
+            .and_then(|storage| get_value(storage, Self::KEY))
+            .unwrap_or_default()
+    }
+
+    /// Saves a copy of the node, replacing any preset with the same name.
+    ///
+    /// Values connected from other nodes are saved as their current value.
+    pub fn insert(&mut self, name: String, node_id: NodeId, snarl: &Snarl<NoiseNode>) {
+        let node = detached_node(snarl, node_id, &HashSet::from([node_id]));
+
+        self.presets.retain(|preset| preset.name != name);
+        self.presets.push(Preset { name, node });
+        self.presets
+            .sort_by_key(|preset| preset.name.to_lowercase());
+    }
+
+    pub fn save(&self, storage: &mut dyn Storage) {
+        set_value(storage, Self::KEY, self);
+    }
+}
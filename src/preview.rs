@@ -0,0 +1,213 @@
+use {
+    super::node::{Image as ImageNode, ImageResolution, NoiseNode},
+    egui::{
+        pos2, vec2, Button, Color32, ComboBox, Context, DragValue, Grid, Id, Image, Rect, Sense,
+        Shape, Stroke, TextureHandle, Vec2, Window,
+    },
+    egui_snarl::{NodeId, Snarl},
+    std::collections::HashSet,
+};
+
+/// Settings used to preview a world rectangle of one image node instead of its current region.
+///
+/// Coordinates are those passed to the noise function, as shown when hovering a preview. Previews
+/// are square, so a rectangle which is not square is centered within the smallest square region
+/// which contains it.
+pub struct RegionDialog {
+    pub node_id: NodeId,
+
+    x0: f64,
+    x1: f64,
+    y0: f64,
+    y1: f64,
+}
+
+impl RegionDialog {
+    pub fn new(node_id: NodeId, image: &ImageNode) -> Self {
+        // Preview rows run along the X axis of the noise function and are offset by `y`
+        Self {
+            node_id,
+            x0: image.y * image.scale,
+            x1: (image.y + 1.0) * image.scale,
+            y0: image.x * image.scale,
+            y1: (image.x + 1.0) * image.scale,
+        }
+    }
+
+    /// Returns `false` once the dialog has been closed or its node has been removed.
+    pub fn show(
+        &mut self,
+        ctx: &Context,
+        snarl: &mut Snarl<NoiseNode>,
+        updated_node_ids: &mut HashSet<NodeId>,
+    ) -> bool {
+        let Some(node) = snarl.get_node(self.node_id) else {
+            return false;
+        };
+
+        let mut open = true;
+        let mut close = false;
+
+        Window::new(format!("Preview Region: {}", node.name()))
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                Grid::new("region_dialog").num_columns(3).show(ui, |ui| {
+                    ui.label("");
+                    ui.label("From");
+                    ui.label("To");
+                    ui.end_row();
+
+                    ui.label("X");
+                    ui.add(DragValue::new(&mut self.x0).speed(0.01));
+                    ui.add(DragValue::new(&mut self.x1).speed(0.01));
+                    ui.end_row();
+
+                    ui.label("Y");
+                    ui.add(DragValue::new(&mut self.y0).speed(0.01));
+                    ui.add(DragValue::new(&mut self.y1).speed(0.01));
+                    ui.end_row();
+                });
+
+                let scale = (self.x1 - self.x0).max(self.y1 - self.y0);
+
+                ui.separator();
+
+                ui.horizontal(|ui| {
+                    if ui
+                        .add_enabled(self.x1 > self.x0 && self.y1 > self.y0, Button::new("Apply"))
+                        .clicked()
+                    {
+                        if let Some(image) = snarl
+                            .get_node_mut(self.node_id)
+                            .and_then(NoiseNode::image_mut)
+                        {
+                            image.scale = scale;
+                            image.x = (self.y0 + self.y1) / 2.0 / scale - 0.5;
+                            image.y = (self.x0 + self.x1) / 2.0 / scale - 0.5;
+                            updated_node_ids.insert(self.node_id);
+                        }
+
+                        close = true;
+                    }
+
+                    if ui.button("Cancel").clicked() {
+                        close = true;
+                    }
+                });
+            });
+
+        open && !close
+    }
+}
+
+/// A large, resizable window showing the preview of one image node at a higher resolution.
+///
+/// The window keeps its own texture which is rendered by the worker threads whenever the node
+/// image is updated (see `ImageInfo::detached`).
+pub struct PreviewWindow {
+    pub node_id: NodeId,
+
+    /// Set once fine chunks of the current version have arrived; later coarse chunks are ignored.
+    pub refined: bool,
+
+    pub resolution: ImageResolution,
+    pub texture: Option<TextureHandle>,
+
+    /// Shows the image repeated 2 × 2 with the seams marked, to check whether it tiles.
+    pub tiled: bool,
+
+    /// The image version which the texture was last requested for.
+    pub version: Option<usize>,
+}
+
+impl PreviewWindow {
+    pub fn new(node_id: NodeId) -> Self {
+        Self {
+            node_id,
+            refined: false,
+            resolution: ImageResolution::X512,
+            texture: None,
+            tiled: false,
+            version: None,
+        }
+    }
+
+    /// Returns `false` once the window has been closed or its node has been removed.
+    pub fn show(&mut self, ctx: &Context, snarl: &Snarl<NoiseNode>) -> bool {
+        let Some(node) = snarl.get_node(self.node_id) else {
+            return false;
+        };
+
+        let mut open = true;
+
+        Window::new(format!("Preview: {}", node.name()))
+            .id(Id::new("preview_window"))
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(true)
+            .default_size(Vec2::splat(512.0))
+            .show(ctx, |ui| {
+                let resolution = self.resolution;
+
+                ComboBox::from_label("Resolution")
+                    .selected_text(self.resolution.label())
+                    .show_ui(ui, |ui| {
+                        for resolution in ImageResolution::ALL {
+                            ui.selectable_value(
+                                &mut self.resolution,
+                                resolution,
+                                resolution.label(),
+                            );
+                        }
+                    });
+
+                ui.checkbox(&mut self.tiled, "Tiling Check (2 × 2)");
+
+                // The new resolution is requested on the next frame
+                if self.resolution != resolution {
+                    self.version = None;
+                }
+
+                if let Some(texture) = &self.texture {
+                    let size = ui.available_size().min_elem().max(64.0);
+
+                    if self.tiled {
+                        let (rect, _) = ui.allocate_exact_size(Vec2::splat(size), Sense::hover());
+                        let painter = ui.painter_at(rect);
+                        let tile_size = Vec2::splat(size / 2.0);
+                        let uv = Rect::from_min_max(pos2(0.0, 0.0), pos2(1.0, 1.0));
+
+                        for tile in [
+                            vec2(0.0, 0.0),
+                            vec2(1.0, 0.0),
+                            vec2(0.0, 1.0),
+                            vec2(1.0, 1.0),
+                        ] {
+                            painter.image(
+                                texture.id(),
+                                Rect::from_min_size(rect.min + tile * tile_size, tile_size),
+                                uv,
+                                Color32::WHITE,
+                            );
+                        }
+
+                        // Dashed so that the pixels on either side of each seam remain visible
+                        let stroke = Stroke::new(1.0, Color32::RED);
+                        let center = rect.center();
+                        for points in [
+                            [pos2(center.x, rect.top()), pos2(center.x, rect.bottom())],
+                            [pos2(rect.left(), center.y), pos2(rect.right(), center.y)],
+                        ] {
+                            painter.extend(Shape::dashed_line(&points, stroke, 4.0, 8.0));
+                        }
+                    } else {
+                        ui.add(Image::from_texture(texture).fit_to_exact_size(Vec2::splat(size)));
+                    }
+                }
+            });
+
+        open
+    }
+}
@@ -0,0 +1,79 @@
+use {
+    super::node::NoiseNode,
+    anyhow::{bail, Context},
+    egui_snarl::Snarl,
+    log::warn,
+    serde::{de::DeserializeOwned, Deserialize, Serialize},
+};
+
+/// The current project file format version.
+///
+/// Files written before the format was versioned contain a bare `Snarl<NoiseNode>` and are treated
+/// as version `0`; version `1` moved the graph into a versioned envelope with no changes to the
+/// nodes. When a change to the node structs cannot be handled by `#[serde(default)]` alone,
+/// increment this value and upgrade graphs of the previous version in [`read`].
+pub const VERSION: u32 = 1;
+
+#[derive(Clone, Copy)]
+pub enum Format {
+    Json,
+    Ron,
+}
+
+impl Format {
+    fn deserialize<T>(self, s: &str) -> anyhow::Result<T>
+    where
+        T: DeserializeOwned,
+    {
+        Ok(match self {
+            Self::Json => serde_json::from_str(s)?,
+            Self::Ron => ron::de::from_str(s)?,
+        })
+    }
+}
+
+/// Used to read the version of a project file without deserializing the graph.
+#[derive(Deserialize)]
+struct Header {
+    #[serde(default)]
+    version: u32,
+}
+
+/// The versioned on-disk representation of a project.
+#[derive(Deserialize, Serialize)]
+pub struct Project<S> {
+    pub version: u32,
+    pub snarl: S,
+}
+
+impl<'a> Project<&'a Snarl<NoiseNode>> {
+    pub fn new(snarl: &'a Snarl<NoiseNode>) -> Self {
+        Self {
+            version: VERSION,
+            snarl,
+        }
+    }
+}
+
+/// Reads a project file of any known version and migrates it to the current version.
+pub fn read(s: &str, format: Format) -> anyhow::Result<Snarl<NoiseNode>> {
+    let version = format
+        .deserialize::<Header>(s)
+        .context("Reading project header")?
+        .version;
+
+    if version > VERSION {
+        bail!("Project version {version} is newer than the supported version {VERSION}");
+    }
+
+    let snarl = if version == 0 {
+        format.deserialize(s)
+    } else {
+        format
+            .deserialize::<Project<Snarl<NoiseNode>>>(s)
+            .map(|project| project.snarl)
+    }
+    .inspect_err(|_| warn!("Unable to read version {version} project"))?;
+
+    Ok(snarl)
+}
@@ -0,0 +1,152 @@
+use {
+    super::{export::sample_gray, node::NoiseNode},
+    eframe::{get_value, set_value, Storage},
+    egui::{vec2, Button, ColorImage, TextureHandle, Ui},
+    egui_snarl::{NodeId, OutPinId, Snarl},
+    serde::{Deserialize, Serialize},
+    std::path::{Path, PathBuf},
+};
+
+#[derive(Deserialize, Serialize)]
+struct RecentFile {
+    path: PathBuf,
+
+    /// Grayscale pixels of the final node preview, or empty if the graph has no image nodes.
+    thumbnail: Vec<u8>,
+
+    #[serde(skip)]
+    texture: Option<TextureHandle>,
+}
+
+/// Recently opened or saved projects, most recent first.
+#[derive(Default, Deserialize, Serialize)]
+pub struct RecentFiles {
+    files: Vec<RecentFile>,
+}
+
+impl RecentFiles {
+    pub const KEY: &'static str = "recent_files";
+
+    const MAX_COUNT: usize = 8;
+    const THUMBNAIL_SIZE: usize = 32;
+
+    /// Returns the image node which is not connected to any other node, preferring the most
+    /// recently added node if there are several.
+    fn final_node_id(snarl: &Snarl<NoiseNode>) -> Option<NodeId> {
+        snarl
+            .node_ids()
+            .filter(|(node_id, node)| {
+                node.has_image()
+                    && snarl
+                        .out_pin(OutPinId {
+                            node: *node_id,
+                            output: 0,
+                        })
+                        .remotes
+                        .is_empty()
+            })
+            .map(|(node_id, _)| node_id)
+            .max()
+    }
+
+    pub fn insert(&mut self, path: impl AsRef<Path>, snarl: &Snarl<NoiseNode>) {
+        let path = path.as_ref();
+
+        self.remove(path);
+        self.files.insert(
+            0,
+            RecentFile {
+                path: path.to_path_buf(),
+                thumbnail: Self::thumbnail(snarl),
+                texture: None,
+            },
+        );
+        self.files.truncate(Self::MAX_COUNT);
+    }
+
+    pub fn load(storage: Option<&dyn Storage>) -> Self {
+        storage
+            .and_then(|storage| get_value(storage, Self::KEY))
+            .unwrap_or_default()
+    }
+
+    pub fn remove(&mut self, path: impl AsRef<Path>) {
+        let path = path.as_ref();
+
+        self.files.retain(|file| file.path != path);
+    }
+
+    pub fn save(&self, storage: &mut dyn Storage) {
+        set_value(storage, Self::KEY, self);
+    }
+
+    /// Shows the recent files as menu buttons and returns the path of the clicked file, if any.
+    pub fn show(&mut self, ui: &mut Ui) -> Option<PathBuf> {
+        if self.files.is_empty() {
+            ui.label("No recent files");
+
+            return None;
+        }
+
+        let mut res = None;
+
+        for (idx, file) in self.files.iter_mut().enumerate() {
+            let name = file
+                .path
+                .file_name()
+                .unwrap_or(file.path.as_os_str())
+                .to_string_lossy()
+                .to_string();
+
+            let button = if file.thumbnail.is_empty() {
+                Button::new(name)
+            } else {
+                let texture = file.texture.get_or_insert_with(|| {
+                    ui.ctx().load_texture(
+                        format!("recent{idx}"),
+                        ColorImage::from_gray(
+                            [Self::THUMBNAIL_SIZE, Self::THUMBNAIL_SIZE],
+                            &file.thumbnail,
+                        ),
+                        Default::default(),
+                    )
+                });
+
+                Button::image_and_text((texture.id(), vec2(32.0, 32.0)), name)
+            };
+
+            if ui
+                .add(button)
+                .on_hover_text(file.path.display().to_string())
+                .clicked()
+            {
+                res = Some(file.path.clone());
+            }
+        }
+
+        ui.separator();
+
+        if ui.button("Clear Recent Files").clicked() {
+            self.files.clear();
+            ui.close_menu();
+        }
+
+        res
+    }
+
+    /// Samples the final node of a graph the same way node previews are rendered.
+    fn thumbnail(snarl: &Snarl<NoiseNode>) -> Vec<u8> {
+        let Some(node_id) = Self::final_node_id(snarl) else {
+            return vec![];
+        };
+
+        let node = snarl.get_node(node_id).unwrap();
+
+        sample_gray(
+            &node.expr(node_id, snarl),
+            node.image().unwrap(),
+            Self::THUMBNAIL_SIZE,
+            0.0,
+        )
+    }
+}
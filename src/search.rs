@@ -0,0 +1,119 @@
+use {
+    super::node::{ConstantNode, NoiseNode},
+    egui::{Context, Id, Key, RichText, TextEdit, Window},
+    egui_snarl::{NodeId, Snarl},
+};
+
+/// A window which finds nodes by title, type or constant name so that the view can be centered on
+/// them.
+#[derive(Default)]
+pub struct GraphSearch {
+    /// Set when a result has been chosen; the view is centered on this node.
+    pub found_node_id: Option<NodeId>,
+
+    query: String,
+
+    /// The index of the highlighted search result.
+    selected: usize,
+}
+
+impl GraphSearch {
+    const MAX_RESULTS: usize = 20;
+
+    /// Returns the text of the node which matched the query, or `None` if it does not match.
+    fn matches<'a>(query: &str, node: &'a NoiseNode) -> Option<&'a str> {
+        let constant_name = match node {
+            NoiseNode::F64(ConstantNode { name, .. })
+            | NoiseNode::U32(ConstantNode { name, .. }) => Some(name.as_str()),
+            _ => None,
+        };
+
+        [node.title(), constant_name, Some(node.name())]
+            .into_iter()
+            .flatten()
+            .find(|text| text.to_lowercase().contains(query))
+    }
+
+    fn results<'a>(&self, snarl: &'a Snarl<NoiseNode>) -> Vec<(NodeId, &'a NoiseNode, &'a str)> {
+        let query = self.query.trim().to_lowercase();
+
+        if query.is_empty() {
+            return vec![];
+        }
+
+        snarl
+            .node_ids()
+            .filter_map(|(node_id, node)| {
+                Self::matches(&query, node).map(|text| (node_id, node, text))
+            })
+            .take(Self::MAX_RESULTS)
+            .collect()
+    }
+
+    /// Returns `false` once the window has been closed.
+    pub fn show(&mut self, ctx: &Context, snarl: &Snarl<NoiseNode>) -> bool {
+        let mut open = true;
+
+        Window::new("Find Nodes")
+            .id(Id::new("graph_search"))
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                let response = ui.add(
+                    TextEdit::singleline(&mut self.query)
+                        .hint_text("Title, type or constant name")
+                        .desired_width(224.0),
+                );
+
+                if response.changed() {
+                    self.selected = 0;
+                }
+
+                let results = self.results(snarl);
+
+                // Enter moves the view to the highlighted result
+                if response.lost_focus() && ui.input(|input| input.key_pressed(Key::Enter)) {
+                    self.found_node_id = results.get(self.selected).map(|&(node_id, ..)| node_id);
+                    response.request_focus();
+                }
+
+                ui.input(|input| {
+                    if input.key_pressed(Key::ArrowDown) {
+                        self.selected += 1;
+                    }
+
+                    if input.key_pressed(Key::ArrowUp) {
+                        self.selected = self.selected.saturating_sub(1);
+                    }
+                });
+
+                self.selected = self.selected.min(results.len().saturating_sub(1));
+
+                if self.query.trim().is_empty() {
+                    return;
+                }
+
+                ui.separator();
+
+                if results.is_empty() {
+                    ui.weak("No matching nodes");
+                }
+
+                for (idx, (node_id, node, text)) in results.into_iter().enumerate() {
+                    ui.horizontal(|ui| {
+                        if ui.selectable_label(idx == self.selected, text).clicked() {
+                            self.selected = idx;
+                            self.found_node_id = Some(node_id);
+                        }
+
+                        if text != node.name() {
+                            ui.label(RichText::new(node.name()).weak().small());
+                        }
+                    });
+                }
+            });
+
+        open
+    }
+}
@@ -0,0 +1,183 @@
+use {
+    noise::permutationtable::{NoiseHasher, PermutationTable},
+    std::array,
+    wide::f64x4,
+};
+
+/// 2/sqrt(3), which scales Perlin noise into `-1..=1` as noise-rs does.
+const PERLIN_SCALE: f64 = 1.154_700_538_379_251_5;
+
+/// The gradient picked by the low four bits of each corner hash, in the order noise-rs uses.
+const GRADIENTS: [[f64; 3]; 16] = [
+    [1.0, 1.0, 0.0],
+    [-1.0, 1.0, 0.0],
+    [1.0, -1.0, 0.0],
+    [-1.0, -1.0, 0.0],
+    [1.0, 0.0, 1.0],
+    [-1.0, 0.0, 1.0],
+    [1.0, 0.0, -1.0],
+    [-1.0, 0.0, -1.0],
+    [0.0, 1.0, 1.0],
+    [0.0, -1.0, 1.0],
+    [0.0, 1.0, -1.0],
+    [0.0, -1.0, -1.0],
+    [1.0, 1.0, 0.0],
+    [-1.0, 1.0, 0.0],
+    [0.0, -1.0, 1.0],
+    [0.0, -1.0, -1.0],
+];
+
+/// Three dimensional fBm of Perlin noise which samples four points at once, giving the same values
+/// as `Fbm<Perlin>`.
+pub struct FbmX4 {
+    /// The source of each octave, which uses the next seed.
+    sources: Vec<PerlinX4>,
+    frequency: f64,
+    lacunarity: f64,
+    persistence: f64,
+}
+
+impl FbmX4 {
+    pub fn new(
+        seed: u32,
+        octaves: usize,
+        frequency: f64,
+        lacunarity: f64,
+        persistence: f64,
+    ) -> Self {
+        Self {
+            sources: (0..octaves)
+                .map(|octave| PerlinX4::new(seed.wrapping_add(octave as _)))
+                .collect(),
+            frequency,
+            lacunarity,
+            persistence,
+        }
+    }
+
+    /// Samples each point, four at a time.
+    pub fn get_batch(&self, points: &[[f64; 3]], samples: &mut [f64]) {
+        batch(points, samples, |point| self.get_x4(point));
+    }
+
+    fn get_x4(&self, point: [f64x4; 3]) -> f64x4 {
+        let mut point = point.map(|coord| coord * self.frequency);
+        let mut res = f64x4::ZERO;
+
+        for (octave, source) in self.sources.iter().enumerate() {
+            res += source.get_x4(point) * self.persistence.powi(octave as _);
+            point = point.map(|coord| coord * self.lacunarity);
+        }
+
+        res / (1.0 - self.persistence.powi(self.sources.len() as _))
+    }
+}
+
+/// Three dimensional Perlin noise which samples four points at once, giving the same values as
+/// `Perlin`.
+pub struct PerlinX4 {
+    /// The permutation table of the seed, which noise-rs keeps private.
+    table: [u8; 256],
+}
+
+impl PerlinX4 {
+    pub fn new(seed: u32) -> Self {
+        // Hashing a single coordinate returns its entry of the table
+        let table = PermutationTable::new(seed);
+
+        Self {
+            table: array::from_fn(|idx| table.hash(&[idx as _]) as _),
+        }
+    }
+
+    /// Samples each point, four at a time.
+    pub fn get_batch(&self, points: &[[f64; 3]], samples: &mut [f64]) {
+        batch(points, samples, |point| self.get_x4(point));
+    }
+
+    /// Hashes the coordinates of a corner as `PermutationTable` does.
+    fn hash(&self, corner: [isize; 3]) -> usize {
+        corner
+            .into_iter()
+            .map(|coord| (coord & 0xff) as usize)
+            .reduce(|idx, coord| self.table[idx] as usize ^ coord)
+            .map_or(0, |idx| self.table[idx] as usize)
+    }
+
+    fn get_x4(&self, point: [f64x4; 3]) -> f64x4 {
+        let floored = point.map(f64x4::floor);
+        let distance: [_; 3] = array::from_fn(|axis| point[axis] - floored[axis]);
+        let corner = floored.map(|coords| coords.to_array().map(|coord| coord as isize));
+
+        // The gradient of each corner is looked up one lane at a time; the rest is vectorized
+        let gradient = |offset: [isize; 3]| {
+            let mut gradients = [[0.0; 4]; 3];
+
+            for lane in 0..4 {
+                let hash = self.hash(array::from_fn(|axis| {
+                    corner[axis][lane].wrapping_add(offset[axis])
+                }));
+
+                for (axis, gradient) in gradients.iter_mut().enumerate() {
+                    gradient[lane] = GRADIENTS[hash & 0b1111][axis];
+                }
+            }
+
+            let [x, y, z] = gradients.map(f64x4::new);
+            let [dx, dy, dz]: [_; 3] = array::from_fn(|axis| distance[axis] - offset[axis] as f64);
+
+            x * dx + y * dy + z * dz
+        };
+
+        let g000 = gradient([0, 0, 0]);
+        let g100 = gradient([1, 0, 0]);
+        let g010 = gradient([0, 1, 0]);
+        let g110 = gradient([1, 1, 0]);
+        let g001 = gradient([0, 0, 1]);
+        let g101 = gradient([1, 0, 1]);
+        let g011 = gradient([0, 1, 1]);
+        let g111 = gradient([1, 1, 1]);
+
+        let [a, b, c] = distance.map(|distance| {
+            let t = distance.max(f64x4::ZERO).min(f64x4::ONE);
+
+            t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+        });
+
+        let k0 = g000;
+        let k1 = g100 - g000;
+        let k2 = g010 - g000;
+        let k3 = g001 - g000;
+        let k4 = g000 + g110 - g100 - g010;
+        let k5 = g000 + g101 - g100 - g001;
+        let k6 = g000 + g011 - g010 - g001;
+        let k7 = g100 + g010 + g001 + g111 - g000 - g110 - g101 - g011;
+
+        let res =
+            k0 + k1 * a + k2 * b + k3 * c + k4 * a * b + k5 * a * c + k6 * b * c + k7 * a * b * c;
+
+        (res * PERLIN_SCALE).max(f64x4::splat(-1.0)).min(f64x4::ONE)
+    }
+}
+
+/// Samples each point using a function of four points at once; the last points are padded with
+/// zeros when there are fewer than four left.
+fn batch(points: &[[f64; 3]], samples: &mut [f64], f: impl Fn([f64x4; 3]) -> f64x4) {
+    assert_eq!(
+        points.len(),
+        samples.len(),
+        "one sample is needed per point"
+    );
+
+    for (points, samples) in points.chunks(4).zip(samples.chunks_mut(4)) {
+        let mut lanes = [[0.0; 4]; 3];
+
+        for (lane, point) in points.iter().enumerate() {
+            for (axis, coords) in lanes.iter_mut().enumerate() {
+                coords[lane] = point[axis];
+            }
+        }
+
+        samples.copy_from_slice(&f(lanes.map(f64x4::new)).to_array()[..samples.len()]);
+    }
+}
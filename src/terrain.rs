@@ -0,0 +1,241 @@
+use {
+    super::{node::NoiseNode, thread::Threads},
+    egui::{
+        vec2, Color32, Context, Id, Mesh, Pos2, Rgba, Sense, Shape, SidePanel, Slider, Ui, Window,
+    },
+    egui_snarl::{NodeId, Snarl},
+    std::f32::consts::{FRAC_PI_2, PI, TAU},
+};
+
+/// The surface which heights are sampled on and displaced from.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Projection {
+    /// A square of the XY plane matching the node preview image.
+    Plane,
+
+    /// The surface of a sphere centered on the origin with a radius of the image scale, as used
+    /// for planet generation.
+    Sphere,
+}
+
+/// A lit 3D view of the heightfield produced by one image node.
+///
+/// Heights are evaluated on the worker threads (see `Threads::send_terrain`) and the grid mesh is
+/// projected and depth-sorted on the CPU so that it can be drawn with the regular egui painter.
+pub struct TerrainPreview {
+    pub node_id: NodeId,
+    pub projection: Projection,
+
+    /// The image version which heights were last requested for.
+    pub version: Option<usize>,
+
+    docked: bool,
+    heights: Vec<f32>,
+    height_scale: f32,
+    pitch: f32,
+    yaw: f32,
+}
+
+impl TerrainPreview {
+    const DEFAULT_PITCH: f32 = 0.7;
+    const DEFAULT_YAW: f32 = 0.6;
+    const HIGH_COLOR: Rgba = Rgba::from_rgb(0.92, 0.92, 0.88);
+    const LIGHT_DIR: [f32; 3] = [0.4, 0.8, -0.45];
+    const LOW_COLOR: Rgba = Rgba::from_rgb(0.2, 0.32, 0.16);
+
+    pub fn new(node_id: NodeId) -> Self {
+        Self {
+            node_id,
+            projection: Projection::Plane,
+            version: None,
+            docked: true,
+            heights: vec![0.5; Threads::TERRAIN_SIZE * Threads::TERRAIN_SIZE],
+            height_scale: 0.25,
+            pitch: Self::DEFAULT_PITCH,
+            yaw: Self::DEFAULT_YAW,
+        }
+    }
+
+    /// Builds the projected grid mesh with triangles ordered back-to-front.
+    fn mesh(&self, center: Pos2, radius: f32) -> Mesh {
+        let size = Threads::TERRAIN_SIZE;
+        let spacing = 1.0 / (size - 1) as f32;
+        let height = |row: usize, col: usize| self.heights[row * size + col];
+
+        let (sin_yaw, cos_yaw) = self.yaw.sin_cos();
+        let (sin_pitch, cos_pitch) = self.pitch.sin_cos();
+        let light_len = Self::LIGHT_DIR.iter().map(|v| v * v).sum::<f32>().sqrt();
+        let [light_x, light_y, light_z] = Self::LIGHT_DIR.map(|v| v / light_len);
+
+        let mut mesh = Mesh::default();
+        let mut depths = Vec::with_capacity(size * size);
+
+        for row in 0..size {
+            let (up, down) = (row.saturating_sub(1), (row + 1).min(size - 1));
+            for col in 0..size {
+                let (left, right) = (col.saturating_sub(1), (col + 1).min(size - 1));
+                let h = height(row, col).clamp(0.0, 1.0);
+                let color = Self::LOW_COLOR * (1.0 - h) + Self::HIGH_COLOR * h;
+
+                let (diffuse, [x, y, z]) = match self.projection {
+                    Projection::Plane => {
+                        // Lambert lighting using the world-space slope of the displaced grid
+                        let dx = (height(row, right) - height(row, left)) * self.height_scale
+                            / ((right - left) as f32 * spacing);
+                        let dz = (height(down, col) - height(up, col)) * self.height_scale
+                            / ((down - up) as f32 * spacing);
+                        let normal_len = (dx * dx + dz * dz + 1.0).sqrt();
+
+                        (
+                            (-dx * light_x + light_y - dz * light_z) / normal_len,
+                            [
+                                col as f32 * spacing - 0.5,
+                                (h - 0.5) * self.height_scale,
+                                row as f32 * spacing - 0.5,
+                            ],
+                        )
+                    }
+                    Projection::Sphere => {
+                        // Matches the latitude and longitude used by the worker threads; lighting
+                        // uses the undisplaced surface normal
+                        let (sin_lat, cos_lat) = (FRAC_PI_2 - row as f32 * spacing * PI).sin_cos();
+                        let (sin_lon, cos_lon) = (col as f32 * spacing * TAU).sin_cos();
+                        let normal = [cos_lat * cos_lon, sin_lat, cos_lat * sin_lon];
+                        let radius = 0.45 * (1.0 + (h - 0.5) * self.height_scale);
+
+                        (
+                            normal[0] * light_x + normal[1] * light_y + normal[2] * light_z,
+                            normal.map(|v| v * radius),
+                        )
+                    }
+                };
+                let shade = 0.3 + 0.7 * diffuse.max(0.0);
+
+                // Rotate around the vertical axis (yaw) and then tilt towards the viewer (pitch)
+                let (x, z) = (x * cos_yaw - z * sin_yaw, x * sin_yaw + z * cos_yaw);
+                let (y, z) = (y * cos_pitch + z * sin_pitch, z * cos_pitch - y * sin_pitch);
+
+                depths.push(z);
+                mesh.colored_vertex(center + vec2(x, -y) * radius, (color * shade).into());
+            }
+        }
+
+        let mut quads = (0..size - 1)
+            .flat_map(|row| (0..size - 1).map(move |col| (row * size + col) as u32))
+            .collect::<Vec<_>>();
+        let quad_depth = |idx: u32| {
+            let idx = idx as usize;
+            depths[idx] + depths[idx + 1] + depths[idx + size] + depths[idx + size + 1]
+        };
+
+        // Painter's algorithm: the farthest quads are drawn first
+        quads.sort_by(|lhs, rhs| quad_depth(*rhs).total_cmp(&quad_depth(*lhs)));
+
+        let row_stride = size as u32;
+        for idx in quads {
+            mesh.add_triangle(idx, idx + 1, idx + row_stride);
+            mesh.add_triangle(idx + 1, idx + row_stride + 1, idx + row_stride);
+        }
+
+        mesh
+    }
+
+    /// Stores heights returned by the worker threads for one band of the preview.
+    pub fn set_band(&mut self, band: u8, heights: &[f32]) {
+        let start = band as usize * Threads::TERRAIN_BAND_SIZE;
+
+        self.heights[start..start + Threads::TERRAIN_BAND_SIZE].copy_from_slice(heights);
+    }
+
+    /// Returns `false` once the preview has been closed or its node has been removed.
+    pub fn show(&mut self, ctx: &Context, snarl: &Snarl<NoiseNode>) -> bool {
+        let Some(node) = snarl.get_node(self.node_id) else {
+            return false;
+        };
+
+        let title = format!("Terrain Preview: {}", node.name());
+        let mut open = true;
+        let mut close = false;
+
+        if self.docked {
+            SidePanel::right("terrain_preview")
+                .resizable(true)
+                .default_width(320.0)
+                .show(ctx, |ui| {
+                    ui.heading(&title);
+                    ui.separator();
+
+                    close = self.ui(ui);
+                });
+        } else {
+            Window::new(title)
+                .id(Id::new("terrain_preview"))
+                .open(&mut open)
+                .collapsible(false)
+                .default_width(320.0)
+                .show(ctx, |ui| close = self.ui(ui));
+        }
+
+        open && !close
+    }
+
+    /// Returns `true` if the close button was clicked.
+    fn ui(&mut self, ui: &mut Ui) -> bool {
+        let mut close = false;
+
+        ui.horizontal(|ui| {
+            ui.label("Projection");
+
+            let projection = self.projection;
+            ui.selectable_value(&mut self.projection, Projection::Plane, "Plane");
+            ui.selectable_value(&mut self.projection, Projection::Sphere, "Sphere");
+
+            // Heights for the new projection are requested on the next frame
+            if self.projection != projection {
+                self.version = None;
+            }
+        });
+
+        ui.horizontal(|ui| {
+            ui.label("Height");
+            ui.add(Slider::new(&mut self.height_scale, 0.0..=1.0));
+        });
+
+        ui.horizontal(|ui| {
+            if ui
+                .button(if self.docked { "Undock" } else { "Dock" })
+                .clicked()
+            {
+                self.docked = !self.docked;
+            }
+
+            if ui.button("Reset View").clicked() {
+                self.pitch = Self::DEFAULT_PITCH;
+                self.yaw = Self::DEFAULT_YAW;
+            }
+
+            if ui.button("Close").clicked() {
+                close = true;
+            }
+        });
+
+        let size = ui.available_width().max(64.0);
+        let (rect, response) = ui.allocate_exact_size(vec2(size, size), Sense::drag());
+
+        // Dragging horizontally spins the terrain and dragging vertically tilts it
+        if response.dragged() {
+            let delta = response.drag_delta();
+            self.yaw += delta.x * 0.01;
+            self.pitch = (self.pitch + delta.y * 0.01).clamp(0.05, 1.5);
+        }
+
+        let painter = ui.painter_at(rect);
+        painter.rect_filled(rect, 4.0, Color32::from_gray(24));
+
+        // A unit square spins within a circle of radius 1/sqrt(2), so this keeps it in view
+        let mesh = self.mesh(rect.center(), size * 0.68);
+        painter.add(Shape::mesh(mesh));
+
+        close
+    }
+}
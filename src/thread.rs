@@ -1,9 +1,16 @@
 use {
-    super::{app::NodeExprs, expr::Expr},
+    super::{
+        app::NodeExprs,
+        expr::{CombineRgbExpr, Expr},
+        node::{ColorRamp, Colormap, ImageStats},
+        terrain::Projection,
+    },
     crossbeam_channel::{unbounded, Receiver, Sender},
+    egui::{Color32, ColorImage},
     egui_snarl::NodeId,
     std::{
         collections::HashMap,
+        f64::consts::{FRAC_PI_2, PI, TAU},
         sync::{Arc, RwLock},
     },
 };
@@ -15,12 +22,89 @@ use std::{
     thread::{available_parallelism, spawn, JoinHandle},
 };
 
+#[cfg(all(feature = "gpu", not(target_arch = "wasm32")))]
+use super::gpu::{Gpu, Kernel};
+
+type ImageResponse = (NodeId, usize, ImageInfo, ColorImage, ImageStats);
 type NodeExprsCache = HashMap<NodeId, (usize, Arc<Expr>)>;
+type TerrainResponse = (
+    NodeId,
+    usize,
+    TerrainInfo,
+    [f32; Threads::TERRAIN_BAND_SIZE],
+);
 
 #[derive(Clone, Copy)]
 pub struct ImageInfo {
+    /// Set for the first, coarse pass of an image where each sub-image is filled using a single
+    /// sample so that slow expressions show a recognizable image almost instantly.
+    pub coarse: bool,
+
+    pub colormap: Colormap,
+
+    /// The value interval between contour lines drawn over the image, if any.
+    pub contours: Option<f64>,
+
     pub coord: u8,
+
+    /// Set for requests from the detached preview window, which has its own texture, instead of
+    /// the node itself.
+    pub detached: bool,
+
+    /// Set while a value is being dragged so that the image is sampled at a quarter of its
+    /// resolution, with each sample filling `DRAFT_BLOCK` × `DRAFT_BLOCK` pixels.
+    pub draft: bool,
+
+    /// Colors the image instead of `colormap` when set (see `NoiseNode::color_ramp`).
+    pub ramp: Option<ColorRamp>,
+
+    pub scale: f64,
+
+    /// Set to light the image as a surface so that small changes in slope are visible.
+    pub shaded: bool,
+
+    /// The number of pixels along any one side of the whole image.
+    pub size: usize,
+
+    /// The W coordinate to sample four dimensional noise at, or `None` for three dimensional noise.
+    pub w: Option<f64>,
+
+    pub x: f64,
+    pub y: f64,
+}
+
+impl ImageInfo {
+    /// Returns the color of a sample, which is expected to be in `-1..=1`.
+    fn color(&self, sample: f64) -> Color32 {
+        let value = (sample + 1.0) / 2.0;
+
+        self.ramp
+            .map_or_else(|| self.colormap.color(value), |ramp| ramp.color(value))
+    }
+
+    /// Returns the number of samples along any one side of a sub-image.
+    ///
+    /// Contours and shading need one extra row and column of samples (from the neighboring
+    /// sub-images) so that lines and slopes along the edges of the sub-image are not missed.
+    fn stride(&self) -> usize {
+        Threads::chunk_size(self.size) + (self.contours.is_some() || self.shaded) as usize
+    }
+}
+
+enum Request {
+    Image(NodeId, usize, ImageInfo),
+    Terrain(NodeId, usize, TerrainInfo),
+}
+
+#[derive(Clone, Copy)]
+pub struct TerrainInfo {
+    pub band: u8,
+    pub projection: Projection,
     pub scale: f64,
+
+    /// The W coordinate to sample four dimensional noise at, or `None` for three dimensional noise.
+    pub w: Option<f64>,
+
     pub x: f64,
     pub y: f64,
 }
@@ -32,19 +116,32 @@ pub struct Threads {
     #[cfg(not(target_arch = "wasm32"))]
     workers: Vec<JoinHandle<()>>,
 
-    rx: Receiver<(NodeId, usize, u8, [u8; Self::IMAGE_SIZE * Self::IMAGE_SIZE])>,
-    tx: Sender<Option<(NodeId, usize, ImageInfo)>>,
+    rx: Receiver<ImageResponse>,
+    terrain_rx: Receiver<TerrainResponse>,
+    tx: Sender<Option<Request>>,
 }
 
 impl Threads {
+    const CONTOUR_COLOR: Color32 = Color32::RED;
+
+    /// The number of pixels along any one side of a block filled by one draft sample; sub-images
+    /// are always a multiple of this size.
+    const DRAFT_BLOCK: usize = 4;
+
     /// The number of image coordinates along any one side of an image.
     ///
     /// We use 16 because an image is chunked into 256 sub-images (16 x 16). This coordinate allows
     /// threads to send and receive the location of a sub-image easily.
     pub const IMAGE_COORDS: u8 = 16;
 
-    /// The number of pixels along any one side of a sub-image.
-    pub const IMAGE_SIZE: usize = 8;
+    /// The number of height samples along any one side of a terrain preview.
+    ///
+    /// Terrain is requested as `IMAGE_COORDS` horizontal bands so that it fills in progressively.
+    pub const TERRAIN_SIZE: usize = 64;
+
+    /// The number of height samples in one band of a terrain preview.
+    pub const TERRAIN_BAND_SIZE: usize =
+        Self::TERRAIN_SIZE * Self::TERRAIN_SIZE / Self::IMAGE_COORDS as usize;
 
     #[cfg(target_arch = "wasm32")]
     const REQUESTS_PER_FRAME: usize = 64;
@@ -52,22 +149,31 @@ impl Threads {
     pub fn new(node_exprs: &NodeExprs) -> Self {
         let (tx, thread_rx) = unbounded();
         let (thread_tx, rx) = unbounded();
+        let (thread_terrain_tx, terrain_rx) = unbounded();
 
         #[cfg(target_arch = "wasm32")]
         let worker = {
             let node_exprs = Arc::clone(node_exprs);
-            let (tx, rx) = (thread_tx.clone(), thread_rx.clone());
+            let (tx, terrain_tx, rx) = (
+                thread_tx.clone(),
+                thread_terrain_tx.clone(),
+                thread_rx.clone(),
+            );
 
             Box::new(move || {
-                Self::web_worker(&node_exprs, &rx, &tx);
+                Self::web_worker(&node_exprs, &rx, &tx, &terrain_tx);
             })
         };
 
         #[cfg(not(target_arch = "wasm32"))]
         let workers = repeat_with(|| {
             let node_exprs = Arc::clone(node_exprs);
-            let (tx, rx) = (thread_tx.clone(), thread_rx.clone());
-            spawn(|| Self::thread_worker(node_exprs, rx, tx))
+            let (tx, terrain_tx, rx) = (
+                thread_tx.clone(),
+                thread_terrain_tx.clone(),
+                thread_rx.clone(),
+            );
+            spawn(|| Self::thread_worker(node_exprs, rx, tx, terrain_tx))
         })
         .take(
             available_parallelism()
@@ -75,7 +181,27 @@ impl Threads {
                 .unwrap_or_default()
                 .max(1),
         )
-        .collect();
+        .collect::<Vec<_>>();
+
+        // The GPU worker takes requests from the same queue as the CPU workers
+        #[cfg(all(feature = "gpu", not(target_arch = "wasm32")))]
+        let workers = {
+            let mut workers = workers;
+
+            if let Some(gpu) = Gpu::new() {
+                let node_exprs = Arc::clone(node_exprs);
+                let (tx, terrain_tx, rx) = (
+                    thread_tx.clone(),
+                    thread_terrain_tx.clone(),
+                    thread_rx.clone(),
+                );
+                workers.push(spawn(|| {
+                    Self::gpu_worker(gpu, node_exprs, rx, tx, terrain_tx)
+                }));
+            }
+
+            workers
+        };
 
         Self {
             #[cfg(target_arch = "wasm32")]
@@ -85,50 +211,372 @@ impl Threads {
             workers,
 
             rx,
+            terrain_rx,
             tx,
         }
     }
 
-    pub fn coord_to_row_col(coord: u8) -> [usize; 2] {
-        let row = (coord / Self::IMAGE_COORDS) as usize * Self::IMAGE_SIZE;
-        let col = (coord % Self::IMAGE_COORDS) as usize * Self::IMAGE_SIZE;
+    /// Returns the number of pixels along any one side of a sub-image of an image with the given
+    /// size.
+    pub fn chunk_size(size: usize) -> usize {
+        size / Self::IMAGE_COORDS as usize
+    }
+
+    /// Returns a sub-image of the given samples (see `ImageInfo::stride`) along with their
+    /// statistics.
+    /// Colors the samples of a sub-image; `colors` holds the red, green and blue channels of each
+    /// sample for expressions which produce a color.
+    fn chunk_image(
+        samples: &[f64],
+        colors: Option<&[[f64; 3]]>,
+        image_info: &ImageInfo,
+    ) -> (ColorImage, ImageStats) {
+        let chunk_size = Self::chunk_size(image_info.size);
+        let stride = image_info.stride();
+        let mut image = ColorImage::new([chunk_size, chunk_size], Color32::BLACK);
+        let mut stats = ImageStats::default();
+
+        // Slopes are measured in world units so shading does not depend on the resolution
+        let pixel_size = image_info.scale / image_info.size as f64;
+
+        for image_x in 0..chunk_size {
+            for image_y in 0..chunk_size {
+                let sample = samples[image_x * stride + image_y];
+                stats.insert(sample);
+
+                let mut color = colors.map_or_else(
+                    || image_info.color(sample),
+                    |colors| Self::rgb_color(colors[image_x * stride + image_y]),
+                );
+
+                // Sub-image rows run down the image and columns run to the right
+                if image_info.shaded {
+                    let right = (samples[image_x * stride + image_y + 1] - sample) / pixel_size;
+                    let down = (samples[(image_x + 1) * stride + image_y] - sample) / pixel_size;
+                    color = Self::shade(color, right, down);
+                }
+
+                image.pixels[image_x * chunk_size + image_y] = color;
+            }
+        }
+
+        if let Some(interval) = image_info.contours {
+            let band = |image_x: usize, image_y: usize| {
+                (samples[image_x * stride + image_y] / interval).floor()
+            };
+
+            for image_x in 0..chunk_size {
+                for image_y in 0..chunk_size {
+                    let current = band(image_x, image_y);
+                    if current != band(image_x + 1, image_y)
+                        || current != band(image_x, image_y + 1)
+                    {
+                        image.pixels[image_x * chunk_size + image_y] = Self::CONTOUR_COLOR;
+                    }
+                }
+            }
+        }
+
+        (image, stats)
+    }
+
+    /// Returns the color of red, green and blue channels which are expected to be in `-1..=1`.
+    fn rgb_color(channels: [f64; 3]) -> Color32 {
+        let [r, g, b] =
+            channels.map(|channel| ((channel + 1.0) / 2.0 * 255.0).clamp(0.0, 255.0) as u8);
+
+        Color32::from_rgb(r, g, b)
+    }
+
+    /// Lights a color as a surface with the given slopes, using a light above the top-left corner
+    /// of the image; flat surfaces keep their original color.
+    fn shade(color: Color32, right: f64, down: f64) -> Color32 {
+        let len = (right * right + down * down + 1.0).sqrt();
+        let [light_x, light_y, light_z] = [-1.0, -1.0, 1.0].map(|v| v / 3f64.sqrt());
+        let diffuse = (-right * light_x - down * light_y + light_z) / len;
+        let brightness = (diffuse / light_z).clamp(0.0, 1.5);
+        let [r, g, b] = [color.r(), color.g(), color.b()]
+            .map(|c| (c as f64 * brightness).min(u8::MAX as f64) as u8);
+
+        Color32::from_rgb(r, g, b)
+    }
+
+    pub fn coord_to_row_col(coord: u8, chunk_size: usize) -> [usize; 2] {
+        let row = (coord / Self::IMAGE_COORDS) as usize * chunk_size;
+        let col = (coord % Self::IMAGE_COORDS) as usize * chunk_size;
 
         [row, col]
     }
 
+    /// Returns the expression of a node only if it is still the given version.
+    fn current_expr(
+        node_exprs: &Arc<RwLock<NodeExprsCache>>,
+        node_id: NodeId,
+        version: usize,
+    ) -> Option<Arc<Expr>> {
+        node_exprs
+            .read()
+            .unwrap()
+            .get(&node_id)
+            .filter(|(current_version, _)| *current_version == version)
+            .map(|(_, expr)| Arc::clone(expr))
+    }
+
     fn process_request(
+        node_exprs: &Arc<RwLock<NodeExprsCache>>,
+        request: Request,
+        tx: &Sender<ImageResponse>,
+        terrain_tx: &Sender<TerrainResponse>,
+    ) -> bool {
+        match request {
+            Request::Image(node_id, version, image_info) => {
+                Self::process_image_request(node_exprs, node_id, version, image_info, tx)
+            }
+            Request::Terrain(node_id, version, terrain_info) => Self::process_terrain_request(
+                node_exprs,
+                node_id,
+                version,
+                terrain_info,
+                terrain_tx,
+            ),
+        }
+    }
+
+    fn process_image_request(
         node_exprs: &Arc<RwLock<NodeExprsCache>>,
         node_id: NodeId,
         version: usize,
         image_info: ImageInfo,
-        tx: &Sender<(NodeId, usize, u8, [u8; Self::IMAGE_SIZE * Self::IMAGE_SIZE])>,
+        tx: &Sender<ImageResponse>,
     ) -> bool {
-        let ImageInfo { coord, scale, x, y } = image_info;
+        let ImageInfo {
+            coarse,
+            coord,
+            scale,
+            size,
+            w,
+            x,
+            y,
+            ..
+        } = image_info;
 
         // Double-check that the expression is still the current version (it may have been
         // updated by the time we receive this request)
-        if let Some(expr) = node_exprs
-            .read()
-            .unwrap()
-            .get(&node_id)
-            .filter(|(current_version, _)| *current_version == version)
-            .map(|(_, expr)| Arc::clone(expr))
-        {
-            let [row, col] = Self::coord_to_row_col(coord);
-            let step = 1.0 / (Self::IMAGE_SIZE * 16) as f64;
+        if let Some(expr) = Self::current_expr(node_exprs, node_id, version) {
+            let chunk_size = Self::chunk_size(size);
+            let [row, col] = Self::coord_to_row_col(coord, chunk_size);
+            let step = 1.0 / size as f64;
             let half_step = step / 2.0;
-            let mut image = [0u8; Self::IMAGE_SIZE * Self::IMAGE_SIZE];
 
-            for image_y in 0..Self::IMAGE_SIZE {
+            // Coarse samples are not counted in the stats because they are sampled again later
+            if coarse {
+                let center = (chunk_size / 2) as f64;
+                let eval_y = ((row as f64 + center) * step + x) * scale;
+                let eval_x = ((col as f64 + center) * step + y) * scale;
+                let point = [eval_x, eval_y, 0.0];
+                let color = if let Some(channels) = expr.channels() {
+                    Self::rgb_color(
+                        channels
+                            .each_ref()
+                            .map(|channel| channel.noise_at_w(w).get(point)),
+                    )
+                } else {
+                    image_info.color(expr.noise_at_w(w).get(point))
+                };
+                let image = ColorImage::new([chunk_size, chunk_size], color);
+
+                tx.send((node_id, version, image_info, image, ImageStats::default()))
+                    .unwrap();
+
+                return true;
+            }
+
+            // Building the noise function creates the permutation tables of every source, which
+            // costs far more than sampling it, so one instance samples every row of the sub-image
+            // and each row is sampled as one batch
+            let stride = image_info.stride();
+            let step_x = if image_info.draft {
+                Self::DRAFT_BLOCK
+            } else {
+                1
+            };
+            let mut samples = vec![0.0; stride * stride];
+            let mut points = Vec::with_capacity(stride);
+            let mut batches = [vec![], vec![], vec![]];
+
+            // Colors sample each channel on its own and use their brightness as the sample
+            let (noise, channels, mut colors) = if let Some(channels) = expr.channels() {
+                (
+                    None,
+                    Some(channels.each_ref().map(|channel| channel.compile_at_w(w))),
+                    Some(vec![[0.0; 3]; stride * stride]),
+                )
+            } else {
+                (Some(expr.compile_at_w(w)), None, None)
+            };
+
+            for image_y in 0..stride {
+                // Drafts copy the samples at the corner of each block into the rest of it
+                let block_y = image_y % Self::DRAFT_BLOCK;
+                if image_info.draft && block_y != 0 {
+                    for image_x in 0..stride {
+                        let idx = image_x * stride + image_y;
+                        samples[idx] = samples[idx - block_y];
+
+                        if let Some(colors) = &mut colors {
+                            colors[idx] = colors[idx - block_y];
+                        }
+                    }
+
+                    continue;
+                }
+
                 let eval_y = ((row + image_y) as f64 * step + half_step + x) * scale;
-                for image_x in 0..Self::IMAGE_SIZE {
+                points.clear();
+                points.extend((0..stride).step_by(step_x).map(|image_x| {
                     let eval_x = ((col + image_x) as f64 * step + half_step + y) * scale;
-                    let sample = (expr.noise().get([eval_x, eval_y, 0.0]) + 1.0) / 2.0;
-                    image[image_x * Self::IMAGE_SIZE + image_y] = (sample * 255.0) as u8;
+                    [eval_x, eval_y, 0.0]
+                }));
+
+                if let Some(channels) = &channels {
+                    for (channel, batch) in channels.iter().zip(&mut batches) {
+                        batch.resize(points.len(), 0.0);
+                        channel.sample_batch(&points, batch);
+                    }
+                } else {
+                    batches[0].resize(points.len(), 0.0);
+                    noise
+                        .as_ref()
+                        .unwrap()
+                        .sample_batch(&points, &mut batches[0]);
+                }
+
+                for image_x in 0..stride {
+                    let idx = image_x * stride + image_y;
+                    let sample = image_x / step_x;
+
+                    samples[idx] = if let Some(colors) = &mut colors {
+                        colors[idx] = batches.each_ref().map(|batch| batch[sample]);
+
+                        CombineRgbExpr::luma(colors[idx])
+                    } else {
+                        batches[0][sample]
+                    };
                 }
             }
 
-            tx.send((node_id, version, coord, image)).unwrap();
+            let (image, stats) = Self::chunk_image(&samples, colors.as_deref(), &image_info);
+
+            tx.send((node_id, version, image_info, image, stats))
+                .unwrap();
+
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Samples a fine image request using a compiled kernel, returning `false` if the GPU failed
+    /// to produce samples.
+    #[cfg(all(feature = "gpu", not(target_arch = "wasm32")))]
+    fn process_gpu_image_request(
+        gpu: &Gpu,
+        kernel: &Kernel,
+        node_id: NodeId,
+        version: usize,
+        image_info: ImageInfo,
+        tx: &Sender<ImageResponse>,
+    ) -> bool {
+        let ImageInfo {
+            coord,
+            scale,
+            size,
+            x,
+            y,
+            ..
+        } = image_info;
+
+        // Matches the sample points of `process_image_request`
+        let chunk_size = Self::chunk_size(size);
+        let [row, col] = Self::coord_to_row_col(coord, chunk_size);
+        let step = 1.0 / size as f64;
+        let half_step = step / 2.0;
+        let origin = [
+            (col as f64 * step + half_step + y) * scale,
+            (row as f64 * step + half_step + x) * scale,
+        ];
+
+        let Some(samples) = gpu.sample(kernel, origin, step * scale, image_info.stride()) else {
+            return false;
+        };
+
+        let samples = samples
+            .into_iter()
+            .map(|sample| sample as f64)
+            .collect::<Vec<_>>();
+        let (image, stats) = Self::chunk_image(&samples, None, &image_info);
+
+        tx.send((node_id, version, image_info, image, stats))
+            .unwrap();
+
+        true
+    }
+
+    fn process_terrain_request(
+        node_exprs: &Arc<RwLock<NodeExprsCache>>,
+        node_id: NodeId,
+        version: usize,
+        terrain_info: TerrainInfo,
+        tx: &Sender<TerrainResponse>,
+    ) -> bool {
+        let TerrainInfo {
+            band,
+            projection,
+            scale,
+            w,
+            x,
+            y,
+        } = terrain_info;
+
+        if let Some(expr) = Self::current_expr(node_exprs, node_id, version) {
+            let rows = Self::TERRAIN_SIZE / Self::IMAGE_COORDS as usize;
+            let step = 1.0 / Self::TERRAIN_SIZE as f64;
+            let half_step = step / 2.0;
+            let edge_step = 1.0 / (Self::TERRAIN_SIZE - 1) as f64;
+            let mut heights = [0f32; Self::TERRAIN_BAND_SIZE];
+            let noise = expr.noise_at_w(w);
+
+            for band_row in 0..rows {
+                let row = band as usize * rows + band_row;
+                for col in 0..Self::TERRAIN_SIZE {
+                    let point = match projection {
+                        // Heights are sampled with the same orientation as exported images
+                        Projection::Plane => [
+                            (row as f64 * step + half_step + y) * scale,
+                            (col as f64 * step + half_step + x) * scale,
+                            0.0,
+                        ],
+
+                        // Rows run from pole to pole and columns run around the equator, with the
+                        // last column repeating the first so that any seam would be visible
+                        Projection::Sphere => {
+                            let (sin_lat, cos_lat) =
+                                (FRAC_PI_2 - row as f64 * edge_step * PI).sin_cos();
+                            let (sin_lon, cos_lon) = (col as f64 * edge_step * TAU).sin_cos();
+
+                            [
+                                cos_lat * cos_lon * scale,
+                                sin_lat * scale,
+                                cos_lat * sin_lon * scale,
+                            ]
+                        }
+                    };
+                    let sample = (noise.get(point) + 1.0) / 2.0;
+                    heights[band_row * Self::TERRAIN_SIZE + col] = sample as f32;
+                }
+            }
+
+            tx.send((node_id, version, terrain_info, heights)).unwrap();
 
             true
         } else {
@@ -137,28 +585,81 @@ impl Threads {
     }
 
     pub fn send(&self, node: NodeId, version: usize, image_info: ImageInfo) {
-        self.tx.send(Some((node, version, image_info))).unwrap();
+        self.tx
+            .send(Some(Request::Image(node, version, image_info)))
+            .unwrap();
+    }
+
+    pub fn send_terrain(&self, node: NodeId, version: usize, terrain_info: TerrainInfo) {
+        self.tx
+            .send(Some(Request::Terrain(node, version, terrain_info)))
+            .unwrap();
+    }
+
+    #[cfg(all(feature = "gpu", not(target_arch = "wasm32")))]
+    fn gpu_worker(
+        gpu: Gpu,
+        node_exprs: NodeExprs,
+        rx: Receiver<Option<Request>>,
+        tx: Sender<ImageResponse>,
+        terrain_tx: Sender<TerrainResponse>,
+    ) {
+        // Kernels are compiled once per node version; `None` marks expressions which use nodes
+        // without a WGSL implementation and are sampled on the CPU instead
+        let mut kernels = HashMap::<NodeId, (usize, Option<Kernel>)>::new();
+
+        while let Some(request) = rx.recv().unwrap() {
+            let Request::Image(node_id, version, image_info) = request else {
+                Self::process_request(&node_exprs, request, &tx, &terrain_tx);
+                continue;
+            };
+
+            // Coarse chunks and drafts need few samples and are quicker on the CPU; kernels only
+            // implement three dimensional noise
+            if image_info.coarse || image_info.draft || image_info.w.is_some() {
+                Self::process_request(&node_exprs, request, &tx, &terrain_tx);
+                continue;
+            }
+
+            let Some(expr) = Self::current_expr(&node_exprs, node_id, version) else {
+                continue;
+            };
+
+            if kernels.get(&node_id).map(|(version, _)| *version) != Some(version) {
+                kernels.insert(node_id, (version, gpu.compile(&expr)));
+            }
+
+            let sampled = kernels[&node_id].1.as_ref().is_some_and(|kernel| {
+                Self::process_gpu_image_request(&gpu, kernel, node_id, version, image_info, &tx)
+            });
+
+            if !sampled {
+                Self::process_request(&node_exprs, request, &tx, &terrain_tx);
+            }
+        }
     }
 
     #[cfg(not(target_arch = "wasm32"))]
     fn thread_worker(
         node_exprs: NodeExprs,
-        rx: Receiver<Option<(NodeId, usize, ImageInfo)>>,
-        tx: Sender<(NodeId, usize, u8, [u8; Self::IMAGE_SIZE * Self::IMAGE_SIZE])>,
+        rx: Receiver<Option<Request>>,
+        tx: Sender<ImageResponse>,
+        terrain_tx: Sender<TerrainResponse>,
     ) {
         // Receive the next versioned node request from the main thread
-        while let Some((node_id, version, image_info)) = rx.recv().unwrap() {
-            Self::process_request(&node_exprs, node_id, version, image_info, &tx);
+        while let Some(request) = rx.recv().unwrap() {
+            Self::process_request(&node_exprs, request, &tx, &terrain_tx);
         }
     }
 
-    pub fn try_recv_iter(
-        &self,
-    ) -> impl Iterator<Item = (NodeId, usize, u8, [u8; Self::IMAGE_SIZE * Self::IMAGE_SIZE])> + '_
-    {
+    pub fn try_recv_iter(&self) -> impl Iterator<Item = ImageResponse> + '_ {
         self.rx.try_iter()
     }
 
+    pub fn try_recv_terrain_iter(&self) -> impl Iterator<Item = TerrainResponse> + '_ {
+        self.terrain_rx.try_iter()
+    }
+
     #[cfg(target_arch = "wasm32")]
     pub fn update(&self) {
         self.worker.as_ref()();
@@ -167,16 +668,17 @@ impl Threads {
     #[cfg(target_arch = "wasm32")]
     fn web_worker(
         node_exprs: &NodeExprs,
-        rx: &Receiver<Option<(NodeId, usize, ImageInfo)>>,
-        tx: &Sender<(NodeId, usize, u8, [u8; Self::IMAGE_SIZE * Self::IMAGE_SIZE])>,
+        rx: &Receiver<Option<Request>>,
+        tx: &Sender<ImageResponse>,
+        terrain_tx: &Sender<TerrainResponse>,
     ) {
         // On web we only process a small number of requests, always checking to only count
         // requests which are actually processed (and not stale ones)
         let mut processed = 0;
 
         // Receive the next versioned node request
-        while let Some((node_id, version, image_info)) = rx.try_recv().ok().flatten() {
-            if Self::process_request(&node_exprs, node_id, version, image_info, &tx) {
+        while let Some(request) = rx.try_recv().ok().flatten() {
+            if Self::process_request(&node_exprs, request, &tx, &terrain_tx) {
                 processed += 1;
 
                 if processed == Self::REQUESTS_PER_FRAME {
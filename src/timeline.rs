@@ -0,0 +1,208 @@
+use {
+    super::{
+        expr::{CombineRgbExpr, Expr, TransformExpr, Variable},
+        node::{NoiseNode, TimeNode},
+    },
+    egui::{Button, ComboBox, Context, DragValue, Ui},
+    egui_snarl::Snarl,
+    std::{collections::BTreeSet, f64::consts::TAU},
+};
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum State {
+    Paused,
+    Playing,
+    Stopped,
+}
+
+/// What the time value drives.
+#[derive(Clone, PartialEq)]
+enum Target {
+    /// Sets every variable with the given name.
+    Variable(String),
+
+    /// Translates the Z axis.
+    Z,
+
+    /// Moves around a unit circle of the ZW plane once per unit of time, so that animations of
+    /// four dimensional noise loop seamlessly.
+    ZwCircle,
+}
+
+/// A global time value which animates node previews by driving the Z axis or a named variable.
+///
+/// While stopped the graph is previewed exactly as it is; once played (or the time is edited) every
+/// preview expression is evaluated at the current time.
+///
+/// The timeline also holds the W coordinate which previews are sampled at when four dimensional
+/// noise is turned on.
+pub struct Timeline {
+    speed: f64,
+    state: State,
+    target: Target,
+    time: f64,
+    w: Option<f64>,
+}
+
+impl Timeline {
+    /// Advances the time while playing, returning `true` if previews need to be updated.
+    pub fn advance(&mut self, ctx: &Context) -> bool {
+        if self.state != State::Playing {
+            return false;
+        }
+
+        self.time += ctx.input(|input| input.stable_dt) as f64 * self.speed;
+        ctx.request_repaint();
+
+        true
+    }
+
+    /// Returns the expression evaluated at the current time.
+    pub fn apply(&self, mut expr: Expr) -> Expr {
+        if self.state == State::Stopped {
+            return expr;
+        }
+
+        // Time nodes follow the timeline whatever it drives
+        expr.set_f64(TimeNode::NAME, self.time);
+
+        let [z, w] = match &self.target {
+            Target::Variable(name) => {
+                expr.set_f64(name, self.time)
+                    .set_u32(name, self.time.round().max(0.0) as u32);
+
+                return expr;
+            }
+            Target::Z => [self.time, 0.0],
+            Target::ZwCircle => {
+                let (sin, cos) = (self.time * TAU).sin_cos();
+
+                [cos, sin]
+            }
+        };
+
+        let translate = |source| {
+            Box::new(Expr::TranslatePoint(TransformExpr {
+                source,
+                axes: [
+                    Variable::Anonymous(0.0),
+                    Variable::Anonymous(0.0),
+                    Variable::Anonymous(z),
+                    Variable::Anonymous(w),
+                ],
+            }))
+        };
+
+        match expr {
+            // Colors are translated per channel so that previews can still tell them apart
+            Expr::CombineRgb(CombineRgbExpr { channels }) => Expr::CombineRgb(CombineRgbExpr {
+                channels: channels.map(translate),
+            }),
+            expr => *translate(Box::new(expr)),
+        }
+    }
+
+    /// Shows the playback controls, returning `true` if previews need to be updated.
+    pub fn show(&mut self, ui: &mut Ui, snarl: &Snarl<NoiseNode>) -> bool {
+        let state = self.state;
+        let target = self.target.clone();
+        let time = self.time;
+        let w = self.w;
+
+        let playing = self.state == State::Playing;
+        if ui.button(if playing { "Pause" } else { "Play" }).clicked() {
+            self.state = if playing {
+                State::Paused
+            } else {
+                State::Playing
+            };
+        }
+
+        if ui
+            .add_enabled(self.state != State::Stopped, Button::new("Stop"))
+            .clicked()
+        {
+            self.state = State::Stopped;
+            self.time = 0.0;
+        }
+
+        ui.label("Time");
+        if ui.add(DragValue::new(&mut self.time).speed(0.01)).changed()
+            && self.state == State::Stopped
+        {
+            self.state = State::Paused;
+        }
+
+        ui.label("Speed");
+        ui.add(DragValue::new(&mut self.speed).speed(0.01));
+
+        ComboBox::from_id_salt("timeline_target")
+            .selected_text(match &self.target {
+                Target::Variable(name) => name.as_str(),
+                Target::Z => "Z Axis",
+                Target::ZwCircle => "ZW Loop",
+            })
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut self.target, Target::Z, "Z Axis");
+
+                if self.w.is_some() {
+                    ui.selectable_value(&mut self.target, Target::ZwCircle, "ZW Loop");
+                }
+
+                // Names are not unique so each one is only listed once
+                let names = snarl
+                    .nodes()
+                    .filter_map(|node| match node {
+                        NoiseNode::F64(node) => Some(&node.name),
+                        NoiseNode::U32(node) => Some(&node.name),
+                        _ => None,
+                    })
+                    .collect::<BTreeSet<_>>();
+
+                for name in names {
+                    ui.selectable_value(&mut self.target, Target::Variable(name.clone()), name);
+                }
+            });
+
+        ui.separator();
+
+        let mut four_d = self.w.is_some();
+        if ui
+            .checkbox(&mut four_d, "4D")
+            .on_hover_text("Samples previews using four dimensional noise")
+            .changed()
+        {
+            self.w = four_d.then_some(0.0);
+
+            if !four_d && self.target == Target::ZwCircle {
+                self.target = Target::Z;
+            }
+        }
+
+        if let Some(w) = &mut self.w {
+            ui.label("W");
+            ui.add(DragValue::new(w).speed(0.01));
+        }
+
+        self.state != state
+            || self.w != w
+            || (self.state != State::Stopped && (self.target != target || self.time != time))
+    }
+
+    /// Returns the W coordinate previews are sampled at, or `None` for three dimensional noise.
+    pub fn w(&self) -> Option<f64> {
+        self.w
+    }
+}
+
+impl Default for Timeline {
+    fn default() -> Self {
+        Self {
+            speed: 1.0,
+            state: State::Stopped,
+            target: Target::Z,
+            time: 0.0,
+            w: None,
+        }
+    }
+}
@@ -0,0 +1,250 @@
+use {
+    super::node::NoiseNode,
+    egui::{Context, DragValue, Grid, Id, ScrollArea, TextEdit, Ui, Window},
+    egui_snarl::{NodeId, Snarl},
+    std::collections::{BTreeMap, HashMap, HashSet},
+};
+
+/// A window listing every decimal and integer constant by name.
+///
+/// Exported expressions set variables by name, so constants sharing a name are shown as one row
+/// and editing the row edits all of them.
+#[derive(Default)]
+pub struct VariablesWindow {
+    /// Set when a definition or a node using it has been clicked; the view is centered on its
+    /// node.
+    pub found_node_id: Option<NodeId>,
+
+    filter: String,
+}
+
+impl VariablesWindow {
+    /// Returns the constants of the graph grouped by name and type, sorted by name.
+    fn variables(snarl: &Snarl<NoiseNode>) -> BTreeMap<(String, bool), Vec<NodeId>> {
+        let mut res = BTreeMap::<_, Vec<_>>::new();
+
+        for (node_id, node) in snarl.node_ids() {
+            let key = match node {
+                NoiseNode::F64(node) => (node.name.clone(), true),
+                NoiseNode::U32(node) => (node.name.clone(), false),
+                _ => continue,
+            };
+
+            res.entry(key).or_default().push(node_id);
+        }
+
+        res
+    }
+
+    /// Returns `false` once the window has been closed.
+    pub fn show(
+        &mut self,
+        ctx: &Context,
+        snarl: &mut Snarl<NoiseNode>,
+        updated_node_ids: &mut HashSet<NodeId>,
+    ) -> bool {
+        let mut open = true;
+
+        Window::new("Variables")
+            .id(Id::new("variables"))
+            .open(&mut open)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                ui.add(
+                    TextEdit::singleline(&mut self.filter)
+                        .hint_text("Filter by name")
+                        .desired_width(f32::INFINITY),
+                );
+                ui.separator();
+
+                let filter = self.filter.trim().to_lowercase();
+                let variables = Self::variables(snarl)
+                    .into_iter()
+                    .filter(|((name, _), _)| name.to_lowercase().contains(&filter))
+                    .collect::<Vec<_>>();
+
+                if variables.is_empty() {
+                    ui.weak("No decimal or integer constants");
+
+                    return;
+                }
+
+                let mut consumers = HashMap::<_, Vec<_>>::new();
+                for (out_pin, in_pin) in snarl.wires() {
+                    consumers.entry(out_pin.node).or_default().push(in_pin.node);
+                }
+
+                ScrollArea::vertical().max_height(384.0).show(ui, |ui| {
+                    Grid::new("variables_grid")
+                        .num_columns(5)
+                        .striped(true)
+                        .show(ui, |ui| {
+                            ui.strong("Name");
+                            ui.strong("Type");
+                            ui.strong("Value");
+                            ui.strong("Used By");
+                            ui.strong("Definitions");
+                            ui.end_row();
+
+                            for ((name, is_f64), node_ids) in variables {
+                                // The first node keeps the widget ids stable while renaming
+                                ui.push_id(node_ids[0], |ui| {
+                                    Self::show_name(ui, name, &node_ids, snarl);
+                                });
+                                ui.label(if is_f64 { "Decimal" } else { "Integer" });
+                                ui.push_id((node_ids[0], "value"), |ui| {
+                                    Self::show_value(ui, &node_ids, snarl, updated_node_ids);
+                                });
+                                if let Some(node_id) =
+                                    Self::show_consumers(ui, &node_ids, &consumers, snarl)
+                                {
+                                    self.found_node_id = Some(node_id);
+                                }
+
+                                ui.horizontal(|ui| {
+                                    for (idx, &node_id) in node_ids.iter().enumerate() {
+                                        if ui
+                                            .small_button((idx + 1).to_string())
+                                            .on_hover_text("Moves the view to this constant")
+                                            .clicked()
+                                        {
+                                            self.found_node_id = Some(node_id);
+                                        }
+                                    }
+                                });
+                                ui.end_row();
+                            }
+                        });
+                });
+            });
+
+        open
+    }
+
+    /// Lists the nodes using any of the constants; returns the node which has been clicked.
+    fn show_consumers(
+        ui: &mut Ui,
+        node_ids: &[NodeId],
+        consumers: &HashMap<NodeId, Vec<NodeId>>,
+        snarl: &Snarl<NoiseNode>,
+    ) -> Option<NodeId> {
+        let consumers = node_ids
+            .iter()
+            .filter_map(|node_id| consumers.get(node_id))
+            .flatten()
+            .filter_map(|&node_id| {
+                let node = snarl.get_node(node_id)?;
+
+                Some((node_id, node.title().unwrap_or(node.name())))
+            })
+            .collect::<Vec<_>>();
+
+        if consumers.is_empty() {
+            ui.weak("Unused");
+
+            return None;
+        }
+
+        let mut found_node_id = None;
+
+        ui.push_id((node_ids[0], "consumers"), |ui| {
+            ui.menu_button(
+                format!(
+                    "{} node{}",
+                    consumers.len(),
+                    if consumers.len() == 1 { "" } else { "s" }
+                ),
+                |ui| {
+                    for (node_id, name) in consumers {
+                        if ui
+                            .button(name)
+                            .on_hover_text("Moves the view to this node")
+                            .clicked()
+                        {
+                            found_node_id = Some(node_id);
+                            ui.close_menu();
+                        }
+                    }
+                },
+            );
+        });
+
+        found_node_id
+    }
+
+    fn show_name(ui: &mut Ui, mut name: String, node_ids: &[NodeId], snarl: &mut Snarl<NoiseNode>) {
+        if ui
+            .add(TextEdit::singleline(&mut name).desired_width(128.0))
+            .changed()
+        {
+            for &node_id in node_ids {
+                match snarl.get_node_mut(node_id) {
+                    Some(NoiseNode::F64(node)) => node.name.clone_from(&name),
+                    Some(NoiseNode::U32(node)) => node.name.clone_from(&name),
+                    _ => unreachable!(),
+                }
+            }
+        }
+    }
+
+    /// Shows the value of the first constant; changing it sets every constant with the same name.
+    fn show_value(
+        ui: &mut Ui,
+        node_ids: &[NodeId],
+        snarl: &mut Snarl<NoiseNode>,
+        updated_node_ids: &mut HashSet<NodeId>,
+    ) {
+        let mixed = node_ids.windows(2).any(|pair| {
+            match (snarl.get_node(pair[0]), snarl.get_node(pair[1])) {
+                (Some(NoiseNode::F64(lhs)), Some(NoiseNode::F64(rhs))) => lhs.value != rhs.value,
+                (Some(NoiseNode::U32(lhs)), Some(NoiseNode::U32(rhs))) => lhs.value != rhs.value,
+                _ => false,
+            }
+        });
+
+        let response = match snarl.get_node_mut(node_ids[0]) {
+            Some(NoiseNode::F64(node)) => {
+                let response = ui.add(
+                    DragValue::new(&mut node.value)
+                        .min_decimals(2)
+                        .max_decimals(2)
+                        .speed(0.01),
+                );
+                let value = node.value;
+
+                if response.changed() {
+                    for &node_id in node_ids {
+                        if let Some(NoiseNode::F64(node)) = snarl.get_node_mut(node_id) {
+                            node.value = value;
+                        }
+                    }
+                }
+
+                response
+            }
+            Some(NoiseNode::U32(node)) => {
+                let response = ui.add(DragValue::new(&mut node.value));
+                let value = node.value;
+
+                if response.changed() {
+                    for &node_id in node_ids {
+                        if let Some(NoiseNode::U32(node)) = snarl.get_node_mut(node_id) {
+                            node.value = value;
+                        }
+                    }
+                }
+
+                response
+            }
+            _ => unreachable!(),
+        };
+
+        if response.changed() {
+            updated_node_ids.extend(node_ids);
+        }
+
+        if mixed {
+            response.on_hover_text("These constants have different values; editing sets them all");
+        }
+    }
+}
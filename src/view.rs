@@ -1,28 +1,58 @@
 use {
     super::{
-        expr::{DistanceFunction, OpType, ReturnType, SourceType, MAX_FRACTAL_OCTAVES},
+        clipboard::Clipboard,
+        expr::{
+            CompareType, ControlPointExpr, CurveExpr, DistanceFunction, EasingCurve, Expr,
+            GradientType, ImageTiling, NormalComponent, OpType, ReturnType, SourceType,
+            SwizzleComponent, TerraceEasing, TerraceExpr, Variable, MAX_FRACTAL_OCTAVES,
+        },
         node::{
-            CheckerboardNode, ClampNode, ConstantOpNode, ControlPointNode, CylindersNode,
-            ExponentNode, FractalNode, GeneratorNode,
+            BiasGainNode, CheckerboardNode, ClampNode, ColorRamp, Colormap, CompareNode,
+            ConstantOpNode, ContrastNode, ControlPointNode, CraterNode, CurveNode, CylindersNode,
+            DomainWarpNode, ErosionNode, ExponentNode, FaultLinesNode, FormulaNode, FractalNode,
+            GeneratorNode, GenericFractalNode, GradientNode, GroupNode, ImageResolution,
+            ImageSourceNode, ImageStats, MixNode,
             NodeValue::{Node, Value},
-            NoiseNode, RigidFractalNode, ScaleBiasNode, SelectNode, TransformNode, TurbulenceNode,
-            WorleyNode,
+            NoiseNode, NormalNode, RandomConstantNode, RemapNode, RigidFractalNode, RotateAxisNode,
+            ScaleBiasNode, ScriptNode, SelectNode, SlopeNode, SubgraphInputNode, SubgraphNode,
+            SubgraphOutputNode, SwitchNode, TerraceNode, TurbulenceNode, WarpedFbmNode, WorleyNode,
         },
+        palette::NodeType,
+        plot::PlotWindow,
+        presets::Presets,
+        preview::{PreviewWindow, RegionDialog},
+        terrain::TerrainPreview,
+    },
+    egui::{
+        pos2, vec2, Align, Align2, Button, Color32, ComboBox, Context, DragValue, FontId, Frame,
+        Grid, Id, Image, Layout, Pos2, Rect, Resize, Response, RichText, Sense, Shape, Stroke,
+        TextEdit, TextWrapMode, Ui, Vec2,
     },
-    egui::{Align, Color32, ComboBox, DragValue, Layout, Pos2, Stroke, TextEdit, TextWrapMode, Ui},
     egui_snarl::{
-        ui::{PinInfo, PinShape, SnarlViewer},
-        InPin, NodeId, OutPin, OutPinId, Snarl,
+        ui::{get_selected_nodes, PinInfo, PinShape, SnarlViewer},
+        InPin, InPinId, NodeId, OutPin, OutPinId, Snarl,
     },
     log::debug,
-    std::{cell::RefCell, collections::HashSet},
+    std::{
+        cell::RefCell,
+        collections::{hash_map::RandomState, HashSet},
+        fmt::Debug,
+        hash::{BuildHasher, Hash},
+    },
 };
 
-#[cfg(debug_assertions)]
-use {egui::RichText, egui_snarl::InPinId};
-
 #[cfg(not(target_arch = "wasm32"))]
-use super::app::App;
+use {
+    super::{
+        app::App,
+        export::{
+            expr_bundle, png_file_dialog, AnimationExport, BatchExport, NormalMapExport, RawExport,
+        },
+    },
+    log::warn,
+    rfd::{MessageDialog, MessageLevel},
+    ron::ser::{to_string_pretty, PrettyConfig},
+};
 
 #[cfg(debug_assertions)]
 fn in_pin_remote_node<T>(snarl: &Snarl<T>, pin_id: InPinId) -> Option<NodeId> {
@@ -33,14 +63,518 @@ fn in_pin_remote_node<T>(snarl: &Snarl<T>, pin_id: InPinId) -> Option<NodeId> {
         .map(|remote| remote.node)
 }
 
+/// The nodes feeding into and consuming the selected nodes, which are highlighted while every other
+/// node is dimmed.
+pub struct DependencyChain {
+    downstream: HashSet<NodeId>,
+    selected: HashSet<NodeId>,
+    upstream: HashSet<NodeId>,
+}
+
+impl DependencyChain {
+    const DIMMED_OPACITY: f32 = 0.3;
+    const DOWNSTREAM_COLOR: Color32 = Color32::from_rgb(96, 192, 128);
+    const UPSTREAM_COLOR: Color32 = Color32::from_rgb(224, 160, 96);
+
+    /// Returns `None` if the selected nodes are not connected to any other node.
+    pub fn new(selected_node_ids: &[NodeId], snarl: &Snarl<NoiseNode>) -> Option<Self> {
+        let selected = selected_node_ids.iter().copied().collect::<HashSet<_>>();
+        let wires = snarl
+            .wires()
+            .map(|(out_pin, in_pin)| (out_pin.node, in_pin.node))
+            .collect::<Vec<_>>();
+        let downstream = Self::connected(&selected, wires.iter().copied());
+        let upstream = Self::connected(&selected, wires.iter().map(|&(from, to)| (to, from)));
+
+        (!downstream.is_empty() || !upstream.is_empty()).then_some(Self {
+            downstream,
+            selected,
+            upstream,
+        })
+    }
+
+    /// Returns every node reachable from the selected nodes by following `edges`.
+    fn connected(
+        selected: &HashSet<NodeId>,
+        edges: impl Iterator<Item = (NodeId, NodeId)> + Clone,
+    ) -> HashSet<NodeId> {
+        let mut res = HashSet::new();
+        let mut stack = selected.iter().copied().collect::<Vec<_>>();
+
+        while let Some(node_id) = stack.pop() {
+            for (_, to) in edges.clone().filter(|&(from, _)| from == node_id) {
+                if !selected.contains(&to) && res.insert(to) {
+                    stack.push(to);
+                }
+            }
+        }
+
+        res
+    }
+
+    fn frame(&self, default: Frame, node_id: NodeId) -> Frame {
+        if self.upstream.contains(&node_id) {
+            default.stroke(Stroke::new(2.0, Self::UPSTREAM_COLOR))
+        } else if self.downstream.contains(&node_id) {
+            default.stroke(Stroke::new(2.0, Self::DOWNSTREAM_COLOR))
+        } else if self.selected.contains(&node_id) {
+            default
+        } else {
+            default.multiply_with_opacity(Self::DIMMED_OPACITY)
+        }
+    }
+}
+
 pub struct Viewer<'a> {
+    #[cfg(not(target_arch = "wasm32"))]
+    pub animation_export: &'a mut Option<AnimationExport>,
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub batch_export: &'a mut Option<BatchExport>,
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub normal_map_export: &'a mut Option<NormalMapExport>,
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub raw_export: &'a mut Option<RawExport>,
+
+    pub clipboard: &'a mut Clipboard,
+
+    /// Set while the selected nodes are connected to other nodes.
+    pub dependency_chain: Option<DependencyChain>,
+
+    /// Set when the inner graph of a subgraph node should be opened for editing.
+    pub edit_subgraph: Option<NodeId>,
+
+    /// The screen position of the graph origin and the graph scale, as of the last node shown.
+    pub graph_transform: &'a mut Option<(Pos2, f32)>,
+
+    /// Set when panning or zooming one preview moves every preview to the same region.
+    pub linked_previews: bool,
+
+    pub plot_window: &'a mut Option<PlotWindow>,
+    pub presets: &'a mut Presets,
+    pub preview_window: &'a mut Option<PreviewWindow>,
+    pub region_dialog: &'a mut Option<RegionDialog>,
     pub removed_node_ids: &'a mut HashSet<NodeId>,
+    pub terrain_preview: &'a mut Option<TerrainPreview>,
     pub updated_node_ids: &'a mut HashSet<NodeId>,
+
+    /// The W coordinate previews are sampled at, or `None` for three dimensional noise.
+    pub w: Option<f64>,
 }
 
 impl<'a> Viewer<'a> {
     const AXES: [&'static str; 4] = ["X", "Y", "Z", "W"];
 
+    /// The distance between a new group node and the top of its members, at a graph scale of `1`.
+    const GROUP_OFFSET: f32 = 48.0;
+
+    /// The distance between the inputs, nodes and output of a new subgraph.
+    const SUBGRAPH_SPACING: f32 = 192.0;
+
+    /// Shows a gradient bar with a handle below each stop of the ramp and a color button for each
+    /// stop; dragging a handle moves its stop, right-clicking it removes the stop and clicking the
+    /// bar adds a stop. Returns `true` if the ramp has changed.
+    fn color_ramp_editor(ui: &mut Ui, ramp: &mut ColorRamp, scale: f32) -> bool {
+        const STRIPS: usize = 64;
+
+        let mut changed = false;
+        let handle_size = 8.0 * scale;
+        let (rect, response) = ui.allocate_exact_size(
+            vec2(160.0, 16.0) * scale + vec2(0.0, handle_size),
+            Sense::click(),
+        );
+        let bar = Rect::from_min_max(rect.min, pos2(rect.max.x, rect.max.y - handle_size));
+        let painter = ui.painter();
+
+        for strip in 0..STRIPS {
+            let left = strip as f32 / STRIPS as f32;
+            let right = (strip + 1) as f32 / STRIPS as f32;
+            painter.rect_filled(
+                Rect::from_x_y_ranges(
+                    bar.lerp_inside(vec2(left, 0.0)).x..=bar.lerp_inside(vec2(right, 0.0)).x,
+                    bar.y_range(),
+                ),
+                0.0,
+                ramp.color(((left + right) / 2.0) as f64),
+            );
+        }
+
+        painter.rect_stroke(bar, 0.0, Stroke::new(1.0, Color32::GRAY));
+
+        let mut removed = None;
+
+        for idx in 0..ramp.stops().len() {
+            let stop = ramp.stops()[idx];
+            let x = bar.left() + stop.position as f32 * bar.width();
+            let handle = Rect::from_center_size(
+                pos2(x, bar.bottom() + handle_size / 2.0),
+                Vec2::splat(handle_size),
+            );
+            let response = ui
+                .interact(handle, ui.id().with(("stop", idx)), Sense::click_and_drag())
+                .on_hover_text("Drag to move; right-click to remove");
+
+            if response.dragged() {
+                ramp.set_position(
+                    idx,
+                    stop.position + (response.drag_delta().x / bar.width()) as f64,
+                );
+                changed = true;
+            }
+
+            if response.secondary_clicked() {
+                removed = Some(idx);
+            }
+
+            ui.painter().rect(
+                handle,
+                0.0,
+                stop.color,
+                Stroke::new(
+                    1.0,
+                    if response.hovered() || response.dragged() {
+                        Color32::WHITE
+                    } else {
+                        Color32::GRAY
+                    },
+                ),
+            );
+        }
+
+        if let Some(idx) = removed {
+            ramp.remove(idx);
+            changed = true;
+        }
+
+        if let Some(pos) = response
+            .clicked()
+            .then(|| response.interact_pointer_pos())
+            .flatten()
+        {
+            changed |= ramp
+                .insert(((pos.x - bar.left()) / bar.width()) as f64)
+                .is_some();
+        }
+
+        ui.horizontal(|ui| {
+            for stop in ramp.stops_mut() {
+                changed |= ui.color_edit_button_srgba(&mut stop.color).changed();
+            }
+        });
+
+        changed
+    }
+
+    /// Shows a plot of the curve over -1..1 with a handle on each point placed in the node; dragging
+    /// a handle moves its point, right-clicking it removes the point and clicking the plot adds a
+    /// point. Connected control points are drawn without handles. Returns `true` if the points
+    /// have changed.
+    fn curve_editor(
+        ui: &mut Ui,
+        node: &mut CurveNode,
+        mut control_points: Vec<ControlPointExpr>,
+        scale: f32,
+    ) -> bool {
+        const SAMPLES: usize = 64;
+
+        let mut changed = false;
+        let handle_size = 8.0 * scale;
+        let (rect, response) = ui.allocate_exact_size(vec2(160.0, 120.0) * scale, Sense::click());
+        let to_screen = |[input, output]: [f64; 2]| {
+            rect.lerp_inside(vec2(
+                (input as f32 + 1.0) / 2.0,
+                (1.0 - output as f32) / 2.0,
+            ))
+        };
+        let from_screen = |pos: Pos2| {
+            let t = (pos - rect.min) / rect.size();
+
+            [
+                (t.x as f64 * 2.0 - 1.0).clamp(-1.0, 1.0),
+                (1.0 - t.y as f64 * 2.0).clamp(-1.0, 1.0),
+            ]
+        };
+        let connected = control_points.len() - node.points.len();
+        let mut hovered = Vec::with_capacity(node.points.len());
+        let mut removed = None;
+
+        for idx in 0..node.points.len() {
+            let handle =
+                Rect::from_center_size(to_screen(node.points[idx]), Vec2::splat(handle_size));
+            let response = ui
+                .interact(
+                    handle,
+                    ui.id().with(("point", idx)),
+                    Sense::click_and_drag(),
+                )
+                .on_hover_text("Drag to move; right-click to remove");
+
+            if let Some(pos) = response
+                .dragged()
+                .then(|| response.interact_pointer_pos())
+                .flatten()
+            {
+                node.points[idx] = from_screen(pos);
+                changed = true;
+            }
+
+            if response.secondary_clicked() {
+                removed = Some(idx);
+            }
+
+            hovered.push(response.hovered() || response.dragged());
+        }
+
+        if let Some(idx) = removed {
+            node.points.remove(idx);
+            hovered.remove(idx);
+            changed = true;
+        }
+
+        if let Some(pos) = response
+            .clicked()
+            .then(|| response.interact_pointer_pos())
+            .flatten()
+        {
+            node.points.push(from_screen(pos));
+            hovered.push(false);
+            changed = true;
+        }
+
+        control_points.truncate(connected);
+        control_points.extend(node.points.iter().map(|&[input, output]| ControlPointExpr {
+            input_value: Variable::Anonymous(input),
+            output_value: Variable::Anonymous(output),
+        }));
+
+        let curve = CurveExpr {
+            source: Box::new(Expr::Constant(Variable::Anonymous(0.0))),
+            control_points,
+        };
+        let painter = ui.painter_at(rect);
+        let grid = Stroke::new(1.0, Color32::from_gray(64));
+
+        painter.rect_filled(rect, 0.0, Color32::from_gray(32));
+        painter.line_segment([to_screen([-1.0, 0.0]), to_screen([1.0, 0.0])], grid);
+        painter.line_segment([to_screen([0.0, -1.0]), to_screen([0.0, 1.0])], grid);
+
+        let inputs = (0..=SAMPLES).map(|idx| idx as f64 / SAMPLES as f64 * 2.0 - 1.0);
+
+        if let Some(outputs) = curve.sample(inputs.clone()) {
+            painter.add(Shape::line(
+                inputs
+                    .zip(outputs)
+                    .map(|(input, output)| to_screen([input, output]))
+                    .collect(),
+                Stroke::new(1.5, Color32::LIGHT_BLUE),
+            ));
+        } else {
+            painter.text(
+                rect.center(),
+                Align2::CENTER_CENTER,
+                "Needs 4 points with different inputs",
+                FontId::proportional(10.0 * scale),
+                Color32::GRAY,
+            );
+        }
+
+        painter.rect_stroke(rect, 0.0, Stroke::new(1.0, Color32::GRAY));
+
+        for control_point in &curve.control_points[..connected] {
+            ui.painter().circle_stroke(
+                to_screen([
+                    control_point.input_value.value(),
+                    control_point.output_value.value(),
+                ]),
+                handle_size / 2.0,
+                Stroke::new(1.0, Color32::GRAY),
+            );
+        }
+
+        for (&point, hovered) in node.points.iter().zip(hovered) {
+            ui.painter().circle(
+                to_screen(point),
+                handle_size / 2.0,
+                Color32::LIGHT_BLUE,
+                Stroke::new(
+                    1.0,
+                    if hovered {
+                        Color32::WHITE
+                    } else {
+                        Color32::GRAY
+                    },
+                ),
+            );
+        }
+
+        changed
+    }
+
+    fn terrace_editor(
+        ui: &mut Ui,
+        node: &mut TerraceNode,
+        mut control_points: Vec<Variable<f64>>,
+        scale: f32,
+    ) -> bool {
+        const SAMPLES: usize = 64;
+
+        let mut changed = false;
+        let handle_size = 8.0 * scale;
+        let (rect, response) = ui.allocate_exact_size(vec2(160.0, 120.0) * scale, Sense::click());
+        let to_screen = |[input, output]: [f64; 2]| {
+            rect.lerp_inside(vec2(
+                (input as f32 + 1.0) / 2.0,
+                (1.0 - output as f32) / 2.0,
+            ))
+        };
+        let from_screen = |pos: Pos2| {
+            let t = (pos.x - rect.min.x) / rect.width();
+
+            (t as f64 * 2.0 - 1.0).clamp(-1.0, 1.0)
+        };
+        let connected = control_points.len() - node.points.len();
+        let mut hovered = Vec::with_capacity(node.points.len());
+        let mut removed = None;
+
+        for idx in 0..node.points.len() {
+            let value = node.points[idx];
+            let handle =
+                Rect::from_center_size(to_screen([value, value]), Vec2::splat(handle_size));
+            let response = ui
+                .interact(
+                    handle,
+                    ui.id().with(("point", idx)),
+                    Sense::click_and_drag(),
+                )
+                .on_hover_text("Drag to move; right-click to remove");
+
+            if let Some(pos) = response
+                .dragged()
+                .then(|| response.interact_pointer_pos())
+                .flatten()
+            {
+                node.points[idx] = from_screen(pos);
+                changed = true;
+            }
+
+            if response.secondary_clicked() {
+                removed = Some(idx);
+            }
+
+            hovered.push(response.hovered() || response.dragged());
+        }
+
+        if let Some(idx) = removed {
+            node.points.remove(idx);
+            hovered.remove(idx);
+            changed = true;
+        }
+
+        if let Some(pos) = response
+            .clicked()
+            .then(|| response.interact_pointer_pos())
+            .flatten()
+        {
+            node.points.push(from_screen(pos));
+            hovered.push(false);
+            changed = true;
+        }
+
+        control_points.truncate(connected);
+        control_points.extend(node.points.iter().copied().map(Variable::Anonymous));
+
+        let terrace = TerraceExpr {
+            source: Box::new(Expr::Constant(Variable::Anonymous(0.0))),
+            inverted: node.inverted,
+            control_points,
+            easing: node.easing,
+        };
+        let painter = ui.painter_at(rect);
+        let grid = Stroke::new(1.0, Color32::from_gray(64));
+
+        painter.rect_filled(rect, 0.0, Color32::from_gray(32));
+        painter.line_segment([to_screen([-1.0, 0.0]), to_screen([1.0, 0.0])], grid);
+        painter.line_segment([to_screen([0.0, -1.0]), to_screen([0.0, 1.0])], grid);
+
+        for control_point in &terrace.control_points {
+            let value = control_point.value();
+
+            painter.line_segment(
+                [to_screen([value, -1.0]), to_screen([value, 1.0])],
+                Stroke::new(1.0, Color32::from_gray(96)),
+            );
+        }
+
+        let inputs = (0..=SAMPLES).map(|idx| idx as f64 / SAMPLES as f64 * 2.0 - 1.0);
+
+        if let Some(outputs) = terrace.sample(inputs.clone()) {
+            painter.add(Shape::line(
+                inputs
+                    .zip(outputs)
+                    .map(|(input, output)| to_screen([input, output]))
+                    .collect(),
+                Stroke::new(1.5, Color32::LIGHT_BLUE),
+            ));
+        } else {
+            painter.text(
+                rect.center(),
+                Align2::CENTER_CENTER,
+                "Needs 2 different points",
+                FontId::proportional(10.0 * scale),
+                Color32::GRAY,
+            );
+        }
+
+        painter.rect_stroke(rect, 0.0, Stroke::new(1.0, Color32::GRAY));
+
+        for control_point in &terrace.control_points[..connected] {
+            let value = control_point.value();
+
+            ui.painter().circle_stroke(
+                to_screen([value, value]),
+                handle_size / 2.0,
+                Stroke::new(1.0, Color32::GRAY),
+            );
+        }
+
+        for (&value, hovered) in node.points.iter().zip(hovered) {
+            ui.painter().circle(
+                to_screen([value, value]),
+                handle_size / 2.0,
+                Color32::LIGHT_BLUE,
+                Stroke::new(
+                    1.0,
+                    if hovered {
+                        Color32::WHITE
+                    } else {
+                        Color32::GRAY
+                    },
+                ),
+            );
+        }
+
+        changed
+    }
+
+    fn compare_ty_combo_box(&mut self, ui: &mut Ui, compare_ty: &mut CompareType, node_id: NodeId) {
+        ComboBox::from_id_salt(0)
+            .selected_text(format!("{compare_ty:?}"))
+            .show_ui(ui, |ui| {
+                ui.style_mut().wrap_mode = Some(TextWrapMode::Extend);
+                ui.set_min_width(60.0);
+                for value in CompareType::ALL {
+                    if ui
+                        .selectable_value(compare_ty, value, format!("{value:?}"))
+                        .changed()
+                    {
+                        self.updated_node_ids.insert(node_id);
+                    }
+                }
+            });
+    }
+
     fn control_point_pin_info(is_input: bool, filled: bool) -> PinInfo {
         let fill = Color32::from_rgb(132, 80, 24);
 
@@ -110,6 +644,24 @@ impl<'a> Viewer<'a> {
         );
     }
 
+    /// Shows a seed along with a button which replaces it with a random seed.
+    fn drag_value_seed(&mut self, ui: &mut Ui, scale: f32, value: &mut u32, node_id: NodeId) {
+        ui.with_layout(
+            Layout::right_to_left(Align::Min).with_cross_align(Align::Center),
+            |ui| {
+                ui.set_height(16.0 * scale);
+                if ui.small_button("🎲").on_hover_text("Random seed").clicked() {
+                    *value = Self::random_seed(ui);
+                    self.updated_node_ids.insert(node_id);
+                }
+
+                if ui.add(DragValue::new(value)).changed() {
+                    self.updated_node_ids.insert(node_id);
+                }
+            },
+        );
+    }
+
     fn drag_value_u32(&mut self, ui: &mut Ui, scale: f32, value: &mut u32, node_id: NodeId) {
         ui.with_layout(
             Layout::right_to_left(Align::Min).with_cross_align(Align::Center),
@@ -122,12 +674,117 @@ impl<'a> Viewer<'a> {
         );
     }
 
+    /// Shows a combo box which picks one of `values` by name.
+    fn enum_combo_box<T>(
+        &mut self,
+        ui: &mut Ui,
+        id_salt: impl Hash,
+        selected: &mut T,
+        values: &[T],
+        node_id: NodeId,
+    ) where
+        T: Copy + Debug + PartialEq,
+    {
+        ComboBox::from_id_salt(id_salt)
+            .selected_text(format!("{selected:?}"))
+            .show_ui(ui, |ui| {
+                ui.style_mut().wrap_mode = Some(TextWrapMode::Extend);
+                ui.set_min_width(60.0);
+                for &value in values {
+                    if ui
+                        .selectable_value(selected, value, format!("{value:?}"))
+                        .changed()
+                    {
+                        self.updated_node_ids.insert(node_id);
+                    }
+                }
+            });
+    }
+
     fn f64_pin_info(is_input: bool, filled: bool) -> PinInfo {
         let fill = Color32::from_rgb(128, 64, 192);
 
         Self::scalar_pin_info(is_input, filled, fill)
     }
 
+    fn gradient_ty_combo_box(
+        &mut self,
+        ui: &mut Ui,
+        gradient_ty: &mut GradientType,
+        node_id: NodeId,
+    ) {
+        ComboBox::from_id_salt(0)
+            .selected_text(format!("{gradient_ty:?}"))
+            .show_ui(ui, |ui| {
+                ui.style_mut().wrap_mode = Some(TextWrapMode::Extend);
+                ui.set_min_width(60.0);
+                for value in [GradientType::Linear, GradientType::Radial] {
+                    if ui
+                        .selectable_value(gradient_ty, value, format!("{value:?}"))
+                        .changed()
+                    {
+                        self.updated_node_ids.insert(node_id);
+                    }
+                }
+            });
+    }
+
+    /// Shows the min, mean and max of the raw samples over the bottom of a preview image, in yellow
+    /// if any samples are outside of the `-1..=1` range which previews display, along with the
+    /// exact value of the hovered pixel (if any) when hovered.
+    fn image_stats(
+        ui: &Ui,
+        response: Response,
+        stats: &ImageStats,
+        scale: f32,
+        hovered_sample: Option<([f64; 2], f64)>,
+    ) {
+        let hovered_text = hovered_sample
+            .map(|([x, y], value)| format!("X: {x}\nY: {y}\nValue: {value}"))
+            .unwrap_or_default();
+
+        if stats.count == 0 {
+            if hovered_sample.is_some() {
+                response.on_hover_text(hovered_text);
+            }
+
+            return;
+        }
+
+        let color = if stats.is_clipped() {
+            Color32::YELLOW
+        } else {
+            Color32::WHITE
+        };
+        let painter = ui.painter_at(response.rect);
+        let galley = painter.layout_no_wrap(
+            format!("{:.2} / {:.2} / {:.2}", stats.min, stats.mean(), stats.max),
+            FontId::monospace(8.0 * scale),
+            color,
+        );
+        let pos = response.rect.left_bottom() + vec2(2.0, -2.0 - galley.size().y) * scale;
+
+        painter.rect_filled(
+            Rect::from_min_size(pos, galley.size()).expand(scale),
+            2.0 * scale,
+            Color32::from_black_alpha(160),
+        );
+        painter.galley(pos, galley, color);
+
+        response.on_hover_text(format!(
+            "{hovered_text}{}Min: {}\nMean: {}\nMax: {}{}",
+            if hovered_sample.is_some() { "\n\n" } else { "" },
+            stats.min,
+            stats.mean(),
+            stats.max,
+            if stats.is_clipped() {
+                "\n\nSome values are outside of -1..=1 and are clipped in this preview"
+            } else {
+                ""
+            }
+        ));
+    }
+
     fn image_pin_info(is_input: bool, filled: bool) -> PinInfo {
         PinInfo::default()
             .with_fill(Color32::from_gray(if is_input { 192 } else { 128 }))
@@ -138,6 +795,28 @@ impl<'a> Viewer<'a> {
             .with_shape(PinShape::Square)
     }
 
+    fn normal_component_combo_box(
+        &mut self,
+        ui: &mut Ui,
+        component: &mut NormalComponent,
+        node_id: NodeId,
+    ) {
+        ComboBox::from_id_salt(0)
+            .selected_text(format!("{component:?}"))
+            .show_ui(ui, |ui| {
+                ui.style_mut().wrap_mode = Some(TextWrapMode::Extend);
+                ui.set_min_width(60.0);
+                for value in NormalComponent::ALL {
+                    if ui
+                        .selectable_value(component, value, format!("{value:?}"))
+                        .changed()
+                    {
+                        self.updated_node_ids.insert(node_id);
+                    }
+                }
+            });
+    }
+
     fn operation_pin_info(is_input: bool, filled: bool) -> PinInfo {
         let fill = Color32::from_gray(127);
 
@@ -151,7 +830,7 @@ impl<'a> Viewer<'a> {
             .show_ui(ui, |ui| {
                 ui.style_mut().wrap_mode = Some(TextWrapMode::Extend);
                 ui.set_min_width(60.0);
-                for value in [ReturnType::Distance, ReturnType::Value] {
+                for value in ReturnType::ALL {
                     if ui
                         .selectable_value(return_ty, value, format!("{value:?}"))
                         .changed()
@@ -188,621 +867,2688 @@ impl<'a> Viewer<'a> {
             });
     }
 
-    fn scalar_pin_info(_is_input: bool, filled: bool, fill: Color32) -> PinInfo {
-        let (r, g, b, _) = fill.to_tuple();
+    /// Asks for an image file and loads it into the node, showing an error if it cannot be read.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn open_image_source(&mut self, node: &mut ImageSourceNode, node_id: NodeId) {
+        let Some(path) = png_file_dialog().pick_file() else {
+            return;
+        };
 
-        PinInfo::default()
-            .with_fill(fill)
-            .with_stroke(Stroke::new(
-                1.5,
-                Color32::from_rgba_unmultiplied(r, g, b, if filled { 192 } else { 128 }),
-            ))
-            .with_shape(PinShape::Triangle)
+        match node.load(&path) {
+            Ok(()) => {
+                self.updated_node_ids.insert(node_id);
+            }
+            Err(err) => {
+                warn!("Unable to load image: {err:#}");
+
+                MessageDialog::new()
+                    .set_level(MessageLevel::Error)
+                    .set_title("Unable to load image")
+                    .set_description(format!("{err:#}"))
+                    .show();
+            }
+        }
     }
 
-    fn u32_pin_info(is_input: bool, filled: bool) -> PinInfo {
-        let fill = Color32::from_rgb(64, 192, 176);
-
-        Self::scalar_pin_info(is_input, filled, fill)
+    /// Returns a different seed each time it is called.
+    fn random_seed(ui: &Ui) -> u32 {
+        // The standard library has no random number generator, but each `RandomState` is keyed
+        // differently
+        RandomState::new().hash_one(ui.input(|input| input.time).to_bits()) as u32
     }
-}
-
-impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
-    #[inline]
-    fn connect(&mut self, from: &OutPin, to: &InPin, snarl: &mut Snarl<NoiseNode>) {
-        // Make sure this connection is not to the same node
-        if from.id.node == to.id.node {
-            debug!(
-                "Not connecting #{:?} to #{:?} (Same)",
-                from.id.node, to.id.node
-            );
-
-            return;
-        }
-
-        // Make sure this connection does not create a cyclic node graph
-        {
-            thread_local! {
-                static NODE_IDS: RefCell<Option<Vec<NodeId>>> = RefCell::new(Some(Default::default()));
-            }
 
-            let mut node_ids = NODE_IDS.take().unwrap();
-            node_ids.push(to.id.node);
-
-            while let Some(node_id) = node_ids.pop() {
-                for node_id in snarl
-                    .out_pin(OutPinId {
-                        node: node_id,
-                        output: 0,
-                    })
-                    .remotes
-                    .iter()
-                    .map(|remote| remote.node)
-                {
-                    if node_id == from.id.node {
-                        node_ids.clear();
-                        NODE_IDS.set(Some(node_ids));
-
-                        debug!(
-                            "Not connecting #{:?} to #{:?} (Cyclic)",
-                            from.id.node, to.id.node
-                        );
-
-                        // We found a cycle
-                        return;
-                    }
-
-                    node_ids.push(node_id);
-                }
-            }
-
-            node_ids.clear();
-            NODE_IDS.set(Some(node_ids));
-        }
-
-        // Handle operation nodes (automatically change types based on inputs/ouputs)
-        {
-            if let NoiseNode::Operation(_) = snarl.get_node(from.id.node).unwrap() {
-                match (to.id.input, snarl.get_node(to.id.node).unwrap()) {
-                    (
-                        0,
-                        NoiseNode::Abs(_)
-                        | NoiseNode::Clamp(_)
-                        | NoiseNode::ControlPoint(_)
-                        | NoiseNode::Curve(_)
-                        | NoiseNode::Cylinders(_)
-                        | NoiseNode::Displace(_)
-                        | NoiseNode::Exponent(_)
-                        | NoiseNode::Negate(_)
-                        | NoiseNode::RotatePoint(_)
-                        | NoiseNode::ScaleBias(_)
-                        | NoiseNode::ScalePoint(_)
-                        | NoiseNode::Terrace(_)
-                        | NoiseNode::TranslatePoint(_)
-                        | NoiseNode::Turbulence(_),
-                    ) => {
-                        NoiseNode::propagate_f64_from_tuple_op(from.id.node, snarl);
-                    }
-                    (
-                        0,
-                        NoiseNode::BasicMulti(_)
-                        | NoiseNode::Billow(_)
-                        | NoiseNode::Checkerboard(_)
-                        | NoiseNode::Fbm(_)
-                        | NoiseNode::HybridMulti(_)
-                        | NoiseNode::OpenSimplex(_)
-                        | NoiseNode::Perlin(_)
-                        | NoiseNode::PerlinSurflet(_)
-                        | NoiseNode::RigidMulti(_)
-                        | NoiseNode::Simplex(_)
-                        | NoiseNode::SuperSimplex(_)
-                        | NoiseNode::Value(_)
-                        | NoiseNode::Worley(_),
-                    ) => {
-                        NoiseNode::propagate_u32_from_tuple_op(from.id.node, snarl);
-                    }
-                    (
-                        0 | 1,
-                        NoiseNode::Add(_)
-                        | NoiseNode::Blend(_)
-                        | NoiseNode::F64Operation(_)
-                        | NoiseNode::Min(_)
-                        | NoiseNode::Max(_)
-                        | NoiseNode::Multiply(_)
-                        | NoiseNode::Power(_)
-                        | NoiseNode::Select(_),
-                    ) => {
-                        NoiseNode::propagate_f64_from_tuple_op(from.id.node, snarl);
-                    }
-                    (0 | 1, NoiseNode::U32Operation(_)) => {
-                        NoiseNode::propagate_u32_from_tuple_op(from.id.node, snarl);
-                    }
-                    (
-                        1,
-                        NoiseNode::Clamp(_)
-                        | NoiseNode::ControlPoint(_)
-                        | NoiseNode::Exponent(_)
-                        | NoiseNode::ScaleBias(_)
-                        | NoiseNode::Worley(_),
-                    ) => {
-                        NoiseNode::propagate_f64_from_tuple_op(from.id.node, snarl);
-                    }
-                    (
-                        1,
-                        NoiseNode::BasicMulti(_)
-                        | NoiseNode::Billow(_)
-                        | NoiseNode::Fbm(_)
-                        | NoiseNode::HybridMulti(_)
-                        | NoiseNode::RigidMulti(_)
-                        | NoiseNode::Turbulence(_),
-                    ) => {
-                        NoiseNode::propagate_u32_from_tuple_op(from.id.node, snarl);
-                    }
-                    (
-                        1..=4,
-                        NoiseNode::Displace(_)
-                        | NoiseNode::RotatePoint(_)
-                        | NoiseNode::ScalePoint(_)
-                        | NoiseNode::TranslatePoint(_),
-                    ) => {
-                        NoiseNode::propagate_f64_from_tuple_op(from.id.node, snarl);
-                    }
-                    (
-                        2,
-                        NoiseNode::BasicMulti(_)
-                        | NoiseNode::Billow(_)
-                        | NoiseNode::Blend(_)
-                        | NoiseNode::Clamp(_)
-                        | NoiseNode::Fbm(_)
-                        | NoiseNode::HybridMulti(_)
-                        | NoiseNode::RigidMulti(_)
-                        | NoiseNode::ScaleBias(_)
-                        | NoiseNode::Select(_)
-                        | NoiseNode::Turbulence(_),
-                    ) => {
-                        NoiseNode::propagate_f64_from_tuple_op(from.id.node, snarl);
-                    }
-                    (
-                        3,
-                        NoiseNode::BasicMulti(_)
-                        | NoiseNode::Billow(_)
-                        | NoiseNode::Fbm(_)
-                        | NoiseNode::HybridMulti(_)
-                        | NoiseNode::RigidMulti(_)
-                        | NoiseNode::Select(_)
-                        | NoiseNode::Turbulence(_),
-                    ) => {
-                        NoiseNode::propagate_f64_from_tuple_op(from.id.node, snarl);
-                    }
-
-                    (
-                        4,
-                        NoiseNode::BasicMulti(_)
-                        | NoiseNode::Billow(_)
-                        | NoiseNode::Fbm(_)
-                        | NoiseNode::HybridMulti(_)
-                        | NoiseNode::RigidMulti(_)
-                        | NoiseNode::Select(_),
-                    ) => {
-                        NoiseNode::propagate_f64_from_tuple_op(from.id.node, snarl);
-                    }
-                    (4, NoiseNode::Turbulence(_)) => {
-                        NoiseNode::propagate_u32_from_tuple_op(from.id.node, snarl);
-                    }
-                    (5, NoiseNode::RigidMulti(_) | NoiseNode::Select(_)) => {
-                        NoiseNode::propagate_f64_from_tuple_op(from.id.node, snarl);
-                    }
-                    (_, NoiseNode::Terrace(_)) => {
-                        NoiseNode::propagate_f64_from_tuple_op(from.id.node, snarl);
-                    }
-                    _ => (),
-                }
-            }
-
-            if let NoiseNode::Operation(_) = snarl.get_node(to.id.node).unwrap() {
-                match snarl.get_node(from.id.node).unwrap() {
-                    NoiseNode::Abs(_)
-                    | NoiseNode::Add(_)
-                    | NoiseNode::BasicMulti(_)
+    /// Removes a node, replacing the values it was connected to with its current value.
+    fn remove_node(&mut self, node_id: NodeId, snarl: &mut Snarl<NoiseNode>) {
+        let inputs = snarl
+            .wires()
+            .filter(|(_, to)| to.node == node_id)
+            .map(|(from, _)| from.node)
+            .collect::<Vec<_>>();
+        let outputs = snarl
+            .wires()
+            .filter(|(from, _)| from.node == node_id)
+            .map(|(_, to)| to)
+            .collect::<Vec<_>>();
+
+        self.removed_node_ids.insert(node_id);
+
+        for remote in &outputs {
+            self.updated_node_ids.insert(remote.node);
+            match (remote.input, snarl.get_node(remote.node).unwrap()) {
+                (
+                    0,
+                    NoiseNode::BasicMulti(_)
                     | NoiseNode::Billow(_)
-                    | NoiseNode::Blend(_)
-                    | NoiseNode::Clamp(_)
-                    | NoiseNode::Checkerboard(_)
-                    | NoiseNode::ControlPoint(_)
-                    | NoiseNode::Curve(_)
-                    | NoiseNode::Cylinders(_)
-                    | NoiseNode::Displace(_)
-                    | NoiseNode::Exponent(_)
                     | NoiseNode::Fbm(_)
                     | NoiseNode::HybridMulti(_)
-                    | NoiseNode::Max(_)
-                    | NoiseNode::Min(_)
-                    | NoiseNode::Multiply(_)
-                    | NoiseNode::Negate(_)
+                    | NoiseNode::Jordan(_)
+                    | NoiseNode::Swiss(_),
+                ) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .and_then(NoiseNode::as_fractal_mut)
+                        .unwrap()
+                        .seed = Value(snarl.get_node(node_id).unwrap().eval_u32(snarl));
+                }
+                (0, NoiseNode::Checkerboard(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .and_then(NoiseNode::as_checkerboard_mut)
+                        .unwrap()
+                        .size = Value(snarl.get_node(node_id).unwrap().eval_u32(snarl));
+                }
+                (0, NoiseNode::ControlPoint(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .and_then(NoiseNode::as_control_point_mut)
+                        .unwrap()
+                        .input = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                }
+                (0, NoiseNode::Cylinders(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .and_then(NoiseNode::as_cylinders_mut)
+                        .unwrap()
+                        .frequency = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                }
+                (0, NoiseNode::Gradient(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .and_then(NoiseNode::as_gradient_mut)
+                        .unwrap()
+                        .angle = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                }
+                (
+                    0,
+                    NoiseNode::BlueNoise(_)
                     | NoiseNode::OpenSimplex(_)
-                    | NoiseNode::Operation(_)
                     | NoiseNode::Perlin(_)
                     | NoiseNode::PerlinSurflet(_)
-                    | NoiseNode::Power(_)
-                    | NoiseNode::RigidMulti(_)
-                    | NoiseNode::RotatePoint(_)
-                    | NoiseNode::ScaleBias(_)
-                    | NoiseNode::ScalePoint(_)
-                    | NoiseNode::Select(_)
                     | NoiseNode::Simplex(_)
                     | NoiseNode::SuperSimplex(_)
-                    | NoiseNode::Terrace(_)
-                    | NoiseNode::TranslatePoint(_)
-                    | NoiseNode::Turbulence(_)
-                    | NoiseNode::Value(_)
-                    | NoiseNode::Worley(_) => (),
-                    NoiseNode::F64(_) | NoiseNode::F64Operation(_) => {
-                        NoiseNode::propagate_f64_from_tuple_op(to.id.node, snarl)
-                    }
-                    NoiseNode::U32(_) | NoiseNode::U32Operation(_) => {
-                        NoiseNode::propagate_u32_from_tuple_op(to.id.node, snarl)
-                    }
+                    | NoiseNode::Value(_),
+                ) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .and_then(NoiseNode::as_generator_mut)
+                        .unwrap()
+                        .seed = Value(snarl.get_node(node_id).unwrap().eval_u32(snarl));
                 }
-            }
-        }
-        let from_node = snarl.get_node(from.id.node).unwrap().clone();
-        let to_node = snarl.get_node_mut(to.id.node).unwrap();
-
-        match (from_node, to.id.input, to_node) {
-            (
-                NoiseNode::Abs(_)
-                | NoiseNode::Add(_)
-                | NoiseNode::BasicMulti(_)
-                | NoiseNode::Billow(_)
-                | NoiseNode::Blend(_)
-                | NoiseNode::Checkerboard(_)
-                | NoiseNode::Clamp(_)
-                | NoiseNode::ControlPoint(_)
-                | NoiseNode::Curve(_)
-                | NoiseNode::Cylinders(_)
-                | NoiseNode::Displace(_)
-                | NoiseNode::Exponent(_)
+                (0, NoiseNode::Erosion(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .and_then(NoiseNode::as_erosion_mut)
+                        .unwrap()
+                        .seed = Value(snarl.get_node(node_id).unwrap().eval_u32(snarl));
+                }
+                (0, NoiseNode::ImageSource(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .and_then(NoiseNode::as_image_source_mut)
+                        .unwrap()
+                        .size = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                }
+                (0, NoiseNode::RandomConstant(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .and_then(NoiseNode::as_random_constant_mut)
+                        .unwrap()
+                        .seed = Value(snarl.get_node(node_id).unwrap().eval_u32(snarl));
+                }
+                (0, NoiseNode::RigidMulti(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .and_then(NoiseNode::as_rigid_fractal_mut)
+                        .unwrap()
+                        .seed = Value(snarl.get_node(node_id).unwrap().eval_u32(snarl));
+                }
+                (0, NoiseNode::FaultLines(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .and_then(NoiseNode::as_fault_lines_mut)
+                        .unwrap()
+                        .seed = Value(snarl.get_node(node_id).unwrap().eval_u32(snarl));
+                }
+                (0, NoiseNode::Switch(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .and_then(NoiseNode::as_switch_mut)
+                        .unwrap()
+                        .selector = Value(snarl.get_node(node_id).unwrap().eval_u32(snarl));
+                }
+                (0, NoiseNode::WarpedFbm(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .and_then(NoiseNode::as_warped_fbm_mut)
+                        .unwrap()
+                        .seed = Value(snarl.get_node(node_id).unwrap().eval_u32(snarl));
+                }
+                (0, NoiseNode::Worley(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .and_then(NoiseNode::as_worley_mut)
+                        .unwrap()
+                        .seed = Value(snarl.get_node(node_id).unwrap().eval_u32(snarl));
+                }
+                (0 | 1, NoiseNode::F64Operation(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .and_then(NoiseNode::as_const_op_f64_mut)
+                        .unwrap()
+                        .inputs[remote.input] =
+                        Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                }
+                (0 | 1, NoiseNode::Operation(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .and_then(NoiseNode::as_const_op_tuple_mut)
+                        .unwrap()
+                        .inputs[remote.input] = Default::default();
+                }
+                (0 | 1, NoiseNode::U32Operation(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .and_then(NoiseNode::as_const_op_u32_mut)
+                        .unwrap()
+                        .inputs[remote.input] =
+                        Value(snarl.get_node(node_id).unwrap().eval_u32(snarl));
+                }
+                (
+                    1,
+                    NoiseNode::BasicMulti(_)
+                    | NoiseNode::Billow(_)
+                    | NoiseNode::Fbm(_)
+                    | NoiseNode::HybridMulti(_)
+                    | NoiseNode::Jordan(_)
+                    | NoiseNode::Swiss(_),
+                ) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .and_then(NoiseNode::as_fractal_mut)
+                        .unwrap()
+                        .octaves = Value(snarl.get_node(node_id).unwrap().eval_u32(snarl));
+                }
+                (1, NoiseNode::GenericFractal(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .and_then(NoiseNode::as_generic_fractal_mut)
+                        .unwrap()
+                        .octaves = Value(snarl.get_node(node_id).unwrap().eval_u32(snarl));
+                }
+                (1, NoiseNode::BiasGain(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .and_then(NoiseNode::as_bias_gain_mut)
+                        .unwrap()
+                        .bias = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                }
+                (1, NoiseNode::Clamp(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .and_then(NoiseNode::as_clamp_mut)
+                        .unwrap()
+                        .lower_bound = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                }
+                (1, NoiseNode::Contrast(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .and_then(NoiseNode::as_contrast_mut)
+                        .unwrap()
+                        .contrast = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                }
+                (1, NoiseNode::ControlPoint(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .and_then(NoiseNode::as_control_point_mut)
+                        .unwrap()
+                        .output = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                }
+                (1, NoiseNode::Exponent(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .and_then(NoiseNode::as_exponent_mut)
+                        .unwrap()
+                        .exponent = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                }
+                (1, NoiseNode::FaultLines(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .and_then(NoiseNode::as_fault_lines_mut)
+                        .unwrap()
+                        .iterations = Value(snarl.get_node(node_id).unwrap().eval_u32(snarl));
+                }
+                (1, NoiseNode::Gradient(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .and_then(NoiseNode::as_gradient_mut)
+                        .unwrap()
+                        .radius = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                }
+                (1, NoiseNode::Normal(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .and_then(NoiseNode::as_normal_mut)
+                        .unwrap()
+                        .epsilon = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                }
+                (1, NoiseNode::RandomConstant(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .and_then(NoiseNode::as_random_constant_mut)
+                        .unwrap()
+                        .index = Value(snarl.get_node(node_id).unwrap().eval_u32(snarl));
+                }
+                (1, NoiseNode::Remap(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .and_then(NoiseNode::as_remap_mut)
+                        .unwrap()
+                        .in_min = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                }
+                (1, NoiseNode::Erosion(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .and_then(NoiseNode::as_erosion_mut)
+                        .unwrap()
+                        .octaves = Value(snarl.get_node(node_id).unwrap().eval_u32(snarl));
+                }
+                (1, NoiseNode::RigidMulti(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .and_then(NoiseNode::as_rigid_fractal_mut)
+                        .unwrap()
+                        .octaves = Value(snarl.get_node(node_id).unwrap().eval_u32(snarl));
+                }
+                (1, NoiseNode::ScaleBias(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .and_then(NoiseNode::as_scale_bias_mut)
+                        .unwrap()
+                        .scale = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                }
+                (1, NoiseNode::Slope(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .and_then(NoiseNode::as_slope_mut)
+                        .unwrap()
+                        .epsilon = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                }
+                (1, NoiseNode::Crater(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .and_then(NoiseNode::as_crater_mut)
+                        .unwrap()
+                        .seed = Value(snarl.get_node(node_id).unwrap().eval_u32(snarl));
+                }
+                (1, NoiseNode::Turbulence(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .and_then(NoiseNode::as_turbulence_mut)
+                        .unwrap()
+                        .seed = Value(snarl.get_node(node_id).unwrap().eval_u32(snarl));
+                }
+                (1, NoiseNode::WarpedFbm(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .and_then(NoiseNode::as_warped_fbm_mut)
+                        .unwrap()
+                        .octaves = Value(snarl.get_node(node_id).unwrap().eval_u32(snarl));
+                }
+                (1, NoiseNode::Worley(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .and_then(NoiseNode::as_worley_mut)
+                        .unwrap()
+                        .frequency = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                }
+                (
+                    1..=4,
+                    NoiseNode::RotatePoint(_)
+                    | NoiseNode::ScalePoint(_)
+                    | NoiseNode::TranslatePoint(_),
+                ) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .and_then(NoiseNode::as_transform_mut)
+                        .unwrap()
+                        .axes[remote.input - 1] =
+                        Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                }
+                (
+                    5,
+                    NoiseNode::RotatePoint(_)
+                    | NoiseNode::ScalePoint(_)
+                    | NoiseNode::TranslatePoint(_),
+                ) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .and_then(NoiseNode::as_transform_mut)
+                        .unwrap()
+                        .vector_node_id = None;
+                }
+                (0..=3, NoiseNode::Vector(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .and_then(NoiseNode::as_vector_mut)
+                        .unwrap()
+                        .axes[remote.input] =
+                        Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                }
+                (1..=3, NoiseNode::RotateAxis(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .and_then(NoiseNode::as_rotate_axis_mut)
+                        .unwrap()
+                        .axis[remote.input - 1] =
+                        Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                }
+                (1..=4, NoiseNode::TilePoint(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .and_then(NoiseNode::as_tile_point_mut)
+                        .unwrap()
+                        .periods[remote.input - 1] =
+                        Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                }
+                (
+                    2,
+                    NoiseNode::BasicMulti(_)
+                    | NoiseNode::Billow(_)
+                    | NoiseNode::Fbm(_)
+                    | NoiseNode::HybridMulti(_)
+                    | NoiseNode::Jordan(_)
+                    | NoiseNode::Swiss(_),
+                ) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .and_then(NoiseNode::as_fractal_mut)
+                        .unwrap()
+                        .frequency = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                }
+                (2, NoiseNode::GenericFractal(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .and_then(NoiseNode::as_generic_fractal_mut)
+                        .unwrap()
+                        .frequency = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                }
+                (2, NoiseNode::BiasGain(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .and_then(NoiseNode::as_bias_gain_mut)
+                        .unwrap()
+                        .gain = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                }
+                (2, NoiseNode::Clamp(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .and_then(NoiseNode::as_clamp_mut)
+                        .unwrap()
+                        .upper_bound = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                }
+                (2, NoiseNode::Compare(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .and_then(NoiseNode::as_compare_mut)
+                        .unwrap()
+                        .tolerance = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                }
+                (2, NoiseNode::Contrast(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .and_then(NoiseNode::as_contrast_mut)
+                        .unwrap()
+                        .pivot = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                }
+                (2, NoiseNode::Crater(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .and_then(NoiseNode::as_crater_mut)
+                        .unwrap()
+                        .density = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                }
+                (2, NoiseNode::DomainWarp(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .and_then(NoiseNode::as_domain_warp_mut)
+                        .unwrap()
+                        .amplitude = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                }
+                (2, NoiseNode::FaultLines(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .and_then(NoiseNode::as_fault_lines_mut)
+                        .unwrap()
+                        .amplitude = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                }
+                (2, NoiseNode::Mix(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .and_then(NoiseNode::as_mix_mut)
+                        .unwrap()
+                        .factor = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                }
+                (2, NoiseNode::Normal(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .and_then(NoiseNode::as_normal_mut)
+                        .unwrap()
+                        .strength = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                }
+                (2, NoiseNode::RandomConstant(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .and_then(NoiseNode::as_random_constant_mut)
+                        .unwrap()
+                        .min = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                }
+                (2, NoiseNode::Remap(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .and_then(NoiseNode::as_remap_mut)
+                        .unwrap()
+                        .in_max = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                }
+                (2, NoiseNode::Erosion(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .and_then(NoiseNode::as_erosion_mut)
+                        .unwrap()
+                        .frequency = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                }
+                (2, NoiseNode::RigidMulti(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .and_then(NoiseNode::as_rigid_fractal_mut)
+                        .unwrap()
+                        .frequency = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                }
+                (2, NoiseNode::ScaleBias(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .and_then(NoiseNode::as_scale_bias_mut)
+                        .unwrap()
+                        .bias = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                }
+                (2, NoiseNode::Turbulence(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .and_then(NoiseNode::as_turbulence_mut)
+                        .unwrap()
+                        .frequency = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                }
+                (2, NoiseNode::WarpedFbm(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .and_then(NoiseNode::as_warped_fbm_mut)
+                        .unwrap()
+                        .frequency = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                }
+                (
+                    3,
+                    NoiseNode::BasicMulti(_)
+                    | NoiseNode::Billow(_)
+                    | NoiseNode::Fbm(_)
+                    | NoiseNode::HybridMulti(_)
+                    | NoiseNode::Jordan(_)
+                    | NoiseNode::Swiss(_),
+                ) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .and_then(NoiseNode::as_fractal_mut)
+                        .unwrap()
+                        .lacunarity = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                }
+                (3, NoiseNode::GenericFractal(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .and_then(NoiseNode::as_generic_fractal_mut)
+                        .unwrap()
+                        .lacunarity = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                }
+                (3, NoiseNode::Crater(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .and_then(NoiseNode::as_crater_mut)
+                        .unwrap()
+                        .radius = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                }
+                (3, NoiseNode::DomainWarp(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .and_then(NoiseNode::as_domain_warp_mut)
+                        .unwrap()
+                        .frequency = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                }
+                (3, NoiseNode::RandomConstant(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .and_then(NoiseNode::as_random_constant_mut)
+                        .unwrap()
+                        .max = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                }
+                (3, NoiseNode::Remap(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .and_then(NoiseNode::as_remap_mut)
+                        .unwrap()
+                        .out_min = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                }
+                (3, NoiseNode::Erosion(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .and_then(NoiseNode::as_erosion_mut)
+                        .unwrap()
+                        .lacunarity = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                }
+                (3, NoiseNode::RigidMulti(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .and_then(NoiseNode::as_rigid_fractal_mut)
+                        .unwrap()
+                        .lacunarity = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                }
+                (3, NoiseNode::Select(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .and_then(NoiseNode::as_select_mut)
+                        .unwrap()
+                        .lower_bound = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                }
+                (3, NoiseNode::Turbulence(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .and_then(NoiseNode::as_turbulence_mut)
+                        .unwrap()
+                        .power = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                }
+                (3, NoiseNode::WarpedFbm(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .and_then(NoiseNode::as_warped_fbm_mut)
+                        .unwrap()
+                        .lacunarity = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                }
+                (
+                    4,
+                    NoiseNode::BasicMulti(_)
+                    | NoiseNode::Billow(_)
+                    | NoiseNode::Fbm(_)
+                    | NoiseNode::HybridMulti(_)
+                    | NoiseNode::Jordan(_)
+                    | NoiseNode::Swiss(_),
+                ) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .and_then(NoiseNode::as_fractal_mut)
+                        .unwrap()
+                        .persistence = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                }
+                (4, NoiseNode::GenericFractal(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .and_then(NoiseNode::as_generic_fractal_mut)
+                        .unwrap()
+                        .persistence = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                }
+                (4, NoiseNode::Crater(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .and_then(NoiseNode::as_crater_mut)
+                        .unwrap()
+                        .depth = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                }
+                (4, NoiseNode::Remap(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .and_then(NoiseNode::as_remap_mut)
+                        .unwrap()
+                        .out_max = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                }
+                (4, NoiseNode::Erosion(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .and_then(NoiseNode::as_erosion_mut)
+                        .unwrap()
+                        .persistence = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                }
+                (4, NoiseNode::RigidMulti(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .and_then(NoiseNode::as_rigid_fractal_mut)
+                        .unwrap()
+                        .persistence = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                }
+                (4, NoiseNode::RotateAxis(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .and_then(NoiseNode::as_rotate_axis_mut)
+                        .unwrap()
+                        .angle = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                }
+                (4, NoiseNode::Select(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .and_then(NoiseNode::as_select_mut)
+                        .unwrap()
+                        .upper_bound = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                }
+                (4, NoiseNode::Turbulence(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .and_then(NoiseNode::as_turbulence_mut)
+                        .unwrap()
+                        .roughness = Value(snarl.get_node(node_id).unwrap().eval_u32(snarl));
+                }
+                (4, NoiseNode::WarpedFbm(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .and_then(NoiseNode::as_warped_fbm_mut)
+                        .unwrap()
+                        .persistence = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                }
+                (5, NoiseNode::Erosion(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .and_then(NoiseNode::as_erosion_mut)
+                        .unwrap()
+                        .strength = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                }
+                (5, NoiseNode::RigidMulti(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .and_then(NoiseNode::as_rigid_fractal_mut)
+                        .unwrap()
+                        .attenuation = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                }
+                (5, NoiseNode::Select(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .and_then(NoiseNode::as_select_mut)
+                        .unwrap()
+                        .falloff = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                }
+                (5, NoiseNode::WarpedFbm(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .and_then(NoiseNode::as_warped_fbm_mut)
+                        .unwrap()
+                        .strength = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                }
+                (6, NoiseNode::WarpedFbm(_)) => {
+                    snarl
+                        .get_node_mut(remote.node)
+                        .and_then(NoiseNode::as_warped_fbm_mut)
+                        .unwrap()
+                        .iterations = Value(snarl.get_node(node_id).unwrap().eval_u32(snarl));
+                }
+                (control_point_idx, NoiseNode::Curve(_)) if control_point_idx > 0 => {
+                    let node = snarl
+                        .get_node_mut(remote.node)
+                        .and_then(NoiseNode::as_curve_mut)
+                        .unwrap();
+                    node.control_point_node_ids[control_point_idx - 1] = None;
+
+                    while let Some(None) = node.control_point_node_ids.last() {
+                        node.control_point_node_ids.pop();
+                    }
+                }
+                (control_point_idx, NoiseNode::Terrace(_)) if control_point_idx > 0 => {
+                    let node = snarl
+                        .get_node_mut(remote.node)
+                        .and_then(NoiseNode::as_terrace_mut)
+                        .unwrap();
+                    node.control_point_node_ids[control_point_idx - 1] = None;
+
+                    while let Some(None) = node.control_point_node_ids.last() {
+                        node.control_point_node_ids.pop();
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        for node_id in inputs
+            .into_iter()
+            .chain(outputs.iter().map(|remote| remote.node))
+        {
+            NoiseNode::propagate_tuple_from_f64_op(node_id, snarl);
+            NoiseNode::propagate_tuple_from_u32_op(node_id, snarl);
+        }
+
+        snarl.remove_node(node_id);
+    }
+
+    /// Removes every selected node, as when each one is removed using its node menu.
+    /// Replaces the nodes with a subgraph node which contains them.
+    ///
+    /// Each source of noise connected to the nodes from the rest of the graph becomes an input of
+    /// the subgraph node; decimal and integer values from the rest of the graph are copied in.
+    fn collapse_to_subgraph(
+        &mut self,
+        node_ids: &HashSet<NodeId>,
+        output_node_id: Option<NodeId>,
+        snarl: &mut Snarl<NoiseNode>,
+    ) {
+        let mut inputs = Vec::<OutPinId>::new();
+        let mut input_wires = vec![];
+
+        for (from, to) in snarl
+            .wires()
+            .filter(|(from, to)| !node_ids.contains(&from.node) && node_ids.contains(&to.node))
+            .collect::<Vec<_>>()
+        {
+            if Self::is_value_wire(from.node, to.node, snarl) {
+                continue;
+            }
+
+            let input = inputs
+                .iter()
+                .position(|&input| input == from)
+                .unwrap_or_else(|| {
+                    inputs.push(from);
+                    inputs.len() - 1
+                });
+            input_wires.push((input, to));
+        }
+
+        let output_wires = snarl
+            .wires()
+            .filter(|(from, to)| Some(from.node) == output_node_id && !node_ids.contains(&to.node))
+            .map(|(_, to)| to)
+            .collect::<Vec<_>>();
+
+        let node_ids = node_ids.iter().copied().collect::<Vec<_>>();
+        let pos = node_ids
+            .iter()
+            .map(|&node_id| snarl.get_node_info(node_id).unwrap().pos)
+            .fold(Pos2::new(f32::INFINITY, f32::INFINITY), Pos2::min);
+
+        // The inner graph reads from an input node on the left and writes to an output node on the
+        // right of the copied nodes
+        let mut clipboard = Clipboard::default();
+        clipboard.copy(snarl, &node_ids);
+
+        let mut inner_snarl = Snarl::new();
+        let inner_node_ids = clipboard.paste(&mut inner_snarl, pos2(Self::SUBGRAPH_SPACING, 0.0));
+        let input_node_ids = (0..inputs.len())
+            .map(|index| {
+                inner_snarl.insert_node(
+                    pos2(0.0, index as f32 * Self::SUBGRAPH_SPACING),
+                    NoiseNode::SubgraphInput(SubgraphInputNode { index, expr: None }),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        for (input, to) in input_wires {
+            inner_snarl.connect(
+                OutPinId {
+                    node: input_node_ids[input],
+                    output: 0,
+                },
+                InPinId {
+                    node: inner_node_ids[&to.node],
+                    input: to.input,
+                },
+            );
+        }
+
+        let max_x = inner_snarl
+            .nodes_pos()
+            .map(|(pos, _)| pos.x)
+            .fold(0.0, f32::max);
+        let inner_output_node_id = inner_snarl.insert_node(
+            pos2(max_x + Self::SUBGRAPH_SPACING, 0.0),
+            NoiseNode::SubgraphOutput(SubgraphOutputNode),
+        );
+
+        if let Some(output_node_id) = output_node_id {
+            inner_snarl.connect(
+                OutPinId {
+                    node: inner_node_ids[&output_node_id],
+                    output: 0,
+                },
+                InPinId {
+                    node: inner_output_node_id,
+                    input: 0,
+                },
+            );
+        }
+
+        for node_id in node_ids {
+            self.remove_node(node_id, snarl);
+        }
+
+        let subgraph_node_id = snarl.insert_node(
+            pos,
+            NoiseNode::Subgraph(SubgraphNode {
+                image: Default::default(),
+                input_count: inputs.len(),
+                snarl: inner_snarl,
+            }),
+        );
+        self.updated_node_ids.insert(subgraph_node_id);
+
+        for (input, from) in inputs.into_iter().enumerate() {
+            self.connect(
+                &snarl.out_pin(from),
+                &snarl.in_pin(InPinId {
+                    node: subgraph_node_id,
+                    input,
+                }),
+                snarl,
+            );
+        }
+
+        for to in output_wires {
+            self.connect(
+                &snarl.out_pin(OutPinId {
+                    node: subgraph_node_id,
+                    output: 0,
+                }),
+                &snarl.in_pin(to),
+                snarl,
+            );
+        }
+    }
+
+    /// Returns `true` if the wire connects a decimal, integer, control point or vector value of the
+    /// node rather than a noise input.
+    fn is_value_wire(
+        from_node_id: NodeId,
+        to_node_id: NodeId,
+        snarl: &mut Snarl<NoiseNode>,
+    ) -> bool {
+        let values = snarl.get_node_mut(to_node_id).unwrap().node_values_mut();
+
+        values
+            .control_point_node_ids
+            .iter()
+            .any(|node_id| **node_id == Some(from_node_id))
+            || values
+                .f64s
+                .iter()
+                .any(|value| value.as_node_id() == Some(from_node_id))
+            || values
+                .tuples
+                .iter()
+                .any(|value| value.as_node_id() == Some(from_node_id))
+            || values
+                .u32s
+                .iter()
+                .any(|value| value.as_node_id() == Some(from_node_id))
+            || values
+                .vector_node_ids
+                .iter()
+                .any(|node_id| **node_id == Some(from_node_id))
+    }
+
+    /// Returns the node which provides the output of a subgraph made of the nodes, or an
+    /// explanation of why the nodes cannot be collapsed into a subgraph.
+    ///
+    /// Without any connections to the rest of the graph the right-most noise node which is not
+    /// connected to anything is used.
+    fn subgraph_output_node_id(
+        node_ids: &HashSet<NodeId>,
+        snarl: &Snarl<NoiseNode>,
+    ) -> Result<Option<NodeId>, &'static str> {
+        if node_ids.iter().any(|&node_id| {
+            matches!(
+                snarl.get_node(node_id),
+                Some(NoiseNode::SubgraphInput(_) | NoiseNode::SubgraphOutput(_))
+            )
+        }) {
+            return Err("Subgraph inputs and outputs cannot be part of another subgraph");
+        }
+
+        let used_node_ids = snarl
+            .wires()
+            .filter(|(from, to)| node_ids.contains(&from.node) && !node_ids.contains(&to.node))
+            .map(|(from, _)| from.node)
+            .collect::<HashSet<_>>();
+
+        match used_node_ids.len() {
+            0 => Ok(node_ids
+                .iter()
+                .copied()
+                .filter(|&node_id| {
+                    snarl.get_node(node_id).unwrap().has_image()
+                        && snarl.wires().all(|(from, _)| from.node != node_id)
+                })
+                .max_by(|&lhs, &rhs| {
+                    let pos = |node_id| snarl.get_node_info(node_id).unwrap().pos.x;
+                    pos(lhs).total_cmp(&pos(rhs))
+                })),
+            1 => used_node_ids
+                .into_iter()
+                .find(|&node_id| snarl.get_node(node_id).unwrap().has_image())
+                .map(Some)
+                .ok_or("Only noise may be connected from a subgraph to the rest of the graph"),
+            _ => Err("Only one node may be connected from a subgraph to the rest of the graph"),
+        }
+    }
+
+    /// Adds the nodes to a new group, removing them from any group they were already part of.
+    fn group_nodes(node_ids: &[NodeId], snarl: &mut Snarl<NoiseNode>) {
+        let node_ids = node_ids
+            .iter()
+            .copied()
+            .filter(|&node_id| {
+                snarl
+                    .get_node(node_id)
+                    .is_some_and(|node| !matches!(node, NoiseNode::Group(_)))
+            })
+            .collect::<Vec<_>>();
+
+        if node_ids.is_empty() {
+            return;
+        }
+
+        Self::ungroup_nodes(&node_ids, snarl);
+
+        // The group node sits just above the top-left member
+        let pos = node_ids
+            .iter()
+            .map(|&node_id| snarl.get_node_info(node_id).unwrap().pos)
+            .fold(Pos2::new(f32::INFINITY, f32::INFINITY), Pos2::min);
+        snarl.insert_node(
+            pos - vec2(0.0, Self::GROUP_OFFSET),
+            NoiseNode::Group(GroupNode::new(node_ids)),
+        );
+    }
+
+    /// Returns the group which the node is a member of, if any.
+    fn group_node_id(node_id: NodeId, snarl: &Snarl<NoiseNode>) -> Option<NodeId> {
+        snarl
+            .node_ids()
+            .find_map(|(group_node_id, node)| match node {
+                NoiseNode::Group(group) if group.node_ids.contains(&node_id) => Some(group_node_id),
+                _ => None,
+            })
+    }
+
+    fn ungroup_nodes(node_ids: &[NodeId], snarl: &mut Snarl<NoiseNode>) {
+        for node in snarl.nodes_mut() {
+            if let NoiseNode::Group(group) = node {
+                group.node_ids.retain(|node_id| !node_ids.contains(node_id));
+            }
+        }
+    }
+
+    pub fn remove_selected_nodes(&mut self, ctx: &Context, snarl: &mut Snarl<NoiseNode>) {
+        for node_id in get_selected_nodes(Id::new("snarl"), ctx) {
+            if snarl.get_node(node_id).is_some() {
+                self.remove_node(node_id, snarl);
+            }
+        }
+    }
+
+    fn show_node_type_buttons(
+        &mut self,
+        pos: Pos2,
+        ui: &mut Ui,
+        snarl: &mut Snarl<NoiseNode>,
+        category: &str,
+    ) {
+        for node_type in NodeType::ALL
+            .iter()
+            .filter(|node_type| node_type.category == category)
+        {
+            if ui.button(node_type.name).clicked() {
+                node_type.insert(snarl, pos, self.updated_node_ids);
+                ui.close_menu();
+            }
+        }
+    }
+
+    fn scalar_pin_info(_is_input: bool, filled: bool, fill: Color32) -> PinInfo {
+        let (r, g, b, _) = fill.to_tuple();
+
+        PinInfo::default()
+            .with_fill(fill)
+            .with_stroke(Stroke::new(
+                1.5,
+                Color32::from_rgba_unmultiplied(r, g, b, if filled { 192 } else { 128 }),
+            ))
+            .with_shape(PinShape::Triangle)
+    }
+
+    fn u32_pin_info(is_input: bool, filled: bool) -> PinInfo {
+        let fill = Color32::from_rgb(64, 192, 176);
+
+        Self::scalar_pin_info(is_input, filled, fill)
+    }
+
+    fn vector_pin_info(is_input: bool, filled: bool) -> PinInfo {
+        let fill = Color32::from_rgb(192, 160, 48);
+
+        Self::scalar_pin_info(is_input, filled, fill)
+    }
+}
+
+impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
+    #[inline]
+    fn connect(&mut self, from: &OutPin, to: &InPin, snarl: &mut Snarl<NoiseNode>) {
+        // Make sure this connection is not to the same node
+        if from.id.node == to.id.node {
+            debug!(
+                "Not connecting #{:?} to #{:?} (Same)",
+                from.id.node, to.id.node
+            );
+
+            return;
+        }
+
+        // Make sure this connection does not create a cyclic node graph
+        {
+            thread_local! {
+                static NODE_IDS: RefCell<Option<Vec<NodeId>>> = RefCell::new(Some(Default::default()));
+            }
+
+            let mut node_ids = NODE_IDS.take().unwrap();
+            node_ids.push(to.id.node);
+
+            while let Some(node_id) = node_ids.pop() {
+                for node_id in snarl
+                    .out_pin(OutPinId {
+                        node: node_id,
+                        output: 0,
+                    })
+                    .remotes
+                    .iter()
+                    .map(|remote| remote.node)
+                {
+                    if node_id == from.id.node {
+                        node_ids.clear();
+                        NODE_IDS.set(Some(node_ids));
+
+                        debug!(
+                            "Not connecting #{:?} to #{:?} (Cyclic)",
+                            from.id.node, to.id.node
+                        );
+
+                        // We found a cycle
+                        return;
+                    }
+
+                    node_ids.push(node_id);
+                }
+            }
+
+            node_ids.clear();
+            NODE_IDS.set(Some(node_ids));
+        }
+
+        // Handle operation nodes (automatically change types based on inputs/ouputs)
+        {
+            if let NoiseNode::Operation(_) = snarl.get_node(from.id.node).unwrap() {
+                match (to.id.input, snarl.get_node(to.id.node).unwrap()) {
+                    (
+                        0,
+                        NoiseNode::Abs(_)
+                        | NoiseNode::Cache(_)
+                        | NoiseNode::Clamp(_)
+                        | NoiseNode::ColorRamp(_)
+                        | NoiseNode::ControlPoint(_)
+                        | NoiseNode::Crater(_)
+                        | NoiseNode::Curve(_)
+                        | NoiseNode::Cylinders(_)
+                        | NoiseNode::Displace(_)
+                        | NoiseNode::Ease(_)
+                        | NoiseNode::Exponent(_)
+                        | NoiseNode::GenericFractal(_)
+                        | NoiseNode::Gradient(_)
+                        | NoiseNode::ImageSource(_)
+                        | NoiseNode::MirrorPoint(_)
+                        | NoiseNode::Negate(_)
+                        | NoiseNode::Normalize(_)
+                        | NoiseNode::RotateAxis(_)
+                        | NoiseNode::RotatePoint(_)
+                        | NoiseNode::ScaleBias(_)
+                        | NoiseNode::ScalePoint(_)
+                        | NoiseNode::Slope(_)
+                        | NoiseNode::Swizzle(_)
+                        | NoiseNode::Terrace(_)
+                        | NoiseNode::TilePoint(_)
+                        | NoiseNode::TranslatePoint(_)
+                        | NoiseNode::Turbulence(_),
+                    ) => {
+                        NoiseNode::propagate_f64_from_tuple_op(from.id.node, snarl);
+                    }
+                    (
+                        0,
+                        NoiseNode::BasicMulti(_)
+                        | NoiseNode::Billow(_)
+                        | NoiseNode::BlueNoise(_)
+                        | NoiseNode::Checkerboard(_)
+                        | NoiseNode::Erosion(_)
+                        | NoiseNode::FaultLines(_)
+                        | NoiseNode::Fbm(_)
+                        | NoiseNode::HybridMulti(_)
+                        | NoiseNode::Jordan(_)
+                        | NoiseNode::OpenSimplex(_)
+                        | NoiseNode::Perlin(_)
+                        | NoiseNode::PerlinSurflet(_)
+                        | NoiseNode::RigidMulti(_)
+                        | NoiseNode::Simplex(_)
+                        | NoiseNode::SuperSimplex(_)
+                        | NoiseNode::Swiss(_)
+                        | NoiseNode::Switch(_)
+                        | NoiseNode::Value(_)
+                        | NoiseNode::WarpedFbm(_)
+                        | NoiseNode::Worley(_),
+                    ) => {
+                        NoiseNode::propagate_u32_from_tuple_op(from.id.node, snarl);
+                    }
+                    (
+                        0 | 1,
+                        NoiseNode::Add(_)
+                        | NoiseNode::Blend(_)
+                        | NoiseNode::CombineRgb(_)
+                        | NoiseNode::Compare(_)
+                        | NoiseNode::Difference(_)
+                        | NoiseNode::F64Operation(_)
+                        | NoiseNode::Min(_)
+                        | NoiseNode::Max(_)
+                        | NoiseNode::Mix(_)
+                        | NoiseNode::Multiply(_)
+                        | NoiseNode::Power(_)
+                        | NoiseNode::Select(_),
+                    ) => {
+                        NoiseNode::propagate_f64_from_tuple_op(from.id.node, snarl);
+                    }
+                    (0 | 1, NoiseNode::U32Operation(_)) => {
+                        NoiseNode::propagate_u32_from_tuple_op(from.id.node, snarl);
+                    }
+                    (0 | 1, NoiseNode::RandomConstant(_)) => {
+                        NoiseNode::propagate_u32_from_tuple_op(from.id.node, snarl);
+                    }
+                    (2 | 3, NoiseNode::RandomConstant(_)) => {
+                        NoiseNode::propagate_f64_from_tuple_op(from.id.node, snarl);
+                    }
+                    (
+                        1,
+                        NoiseNode::Clamp(_)
+                        | NoiseNode::ControlPoint(_)
+                        | NoiseNode::Exponent(_)
+                        | NoiseNode::Gradient(_)
+                        | NoiseNode::ScaleBias(_)
+                        | NoiseNode::Slope(_)
+                        | NoiseNode::Worley(_),
+                    ) => {
+                        NoiseNode::propagate_f64_from_tuple_op(from.id.node, snarl);
+                    }
+                    (
+                        1,
+                        NoiseNode::BasicMulti(_)
+                        | NoiseNode::Billow(_)
+                        | NoiseNode::Crater(_)
+                        | NoiseNode::Erosion(_)
+                        | NoiseNode::FaultLines(_)
+                        | NoiseNode::Fbm(_)
+                        | NoiseNode::GenericFractal(_)
+                        | NoiseNode::HybridMulti(_)
+                        | NoiseNode::Jordan(_)
+                        | NoiseNode::RigidMulti(_)
+                        | NoiseNode::Swiss(_)
+                        | NoiseNode::Turbulence(_)
+                        | NoiseNode::WarpedFbm(_),
+                    ) => {
+                        NoiseNode::propagate_u32_from_tuple_op(from.id.node, snarl);
+                    }
+                    (
+                        1..=4,
+                        NoiseNode::Displace(_)
+                        | NoiseNode::RotateAxis(_)
+                        | NoiseNode::RotatePoint(_)
+                        | NoiseNode::ScalePoint(_)
+                        | NoiseNode::TilePoint(_)
+                        | NoiseNode::TranslatePoint(_),
+                    ) => {
+                        NoiseNode::propagate_f64_from_tuple_op(from.id.node, snarl);
+                    }
+                    (
+                        2,
+                        NoiseNode::BasicMulti(_)
+                        | NoiseNode::Billow(_)
+                        | NoiseNode::Blend(_)
+                        | NoiseNode::Clamp(_)
+                        | NoiseNode::CombineRgb(_)
+                        | NoiseNode::Compare(_)
+                        | NoiseNode::Crater(_)
+                        | NoiseNode::Erosion(_)
+                        | NoiseNode::FaultLines(_)
+                        | NoiseNode::Fbm(_)
+                        | NoiseNode::GenericFractal(_)
+                        | NoiseNode::HybridMulti(_)
+                        | NoiseNode::Jordan(_)
+                        | NoiseNode::Mix(_)
+                        | NoiseNode::RigidMulti(_)
+                        | NoiseNode::ScaleBias(_)
+                        | NoiseNode::Select(_)
+                        | NoiseNode::Swiss(_)
+                        | NoiseNode::Turbulence(_)
+                        | NoiseNode::WarpedFbm(_),
+                    ) => {
+                        NoiseNode::propagate_f64_from_tuple_op(from.id.node, snarl);
+                    }
+                    (
+                        3,
+                        NoiseNode::BasicMulti(_)
+                        | NoiseNode::Billow(_)
+                        | NoiseNode::Crater(_)
+                        | NoiseNode::Erosion(_)
+                        | NoiseNode::Fbm(_)
+                        | NoiseNode::GenericFractal(_)
+                        | NoiseNode::HybridMulti(_)
+                        | NoiseNode::Jordan(_)
+                        | NoiseNode::RigidMulti(_)
+                        | NoiseNode::Select(_)
+                        | NoiseNode::Swiss(_)
+                        | NoiseNode::Turbulence(_)
+                        | NoiseNode::WarpedFbm(_),
+                    ) => {
+                        NoiseNode::propagate_f64_from_tuple_op(from.id.node, snarl);
+                    }
+
+                    (
+                        4,
+                        NoiseNode::BasicMulti(_)
+                        | NoiseNode::Billow(_)
+                        | NoiseNode::Crater(_)
+                        | NoiseNode::Erosion(_)
+                        | NoiseNode::Fbm(_)
+                        | NoiseNode::GenericFractal(_)
+                        | NoiseNode::HybridMulti(_)
+                        | NoiseNode::Jordan(_)
+                        | NoiseNode::RigidMulti(_)
+                        | NoiseNode::Select(_)
+                        | NoiseNode::Swiss(_)
+                        | NoiseNode::WarpedFbm(_),
+                    ) => {
+                        NoiseNode::propagate_f64_from_tuple_op(from.id.node, snarl);
+                    }
+                    (4, NoiseNode::Turbulence(_)) => {
+                        NoiseNode::propagate_u32_from_tuple_op(from.id.node, snarl);
+                    }
+                    (0..=3, NoiseNode::DomainWarp(_) | NoiseNode::Vector(_)) => {
+                        NoiseNode::propagate_f64_from_tuple_op(from.id.node, snarl);
+                    }
+                    (0..=4, NoiseNode::Remap(_)) => {
+                        NoiseNode::propagate_f64_from_tuple_op(from.id.node, snarl);
+                    }
+                    (0..=2, NoiseNode::BiasGain(_)) => {
+                        NoiseNode::propagate_f64_from_tuple_op(from.id.node, snarl);
+                    }
+                    (0..=2, NoiseNode::Contrast(_)) => {
+                        NoiseNode::propagate_f64_from_tuple_op(from.id.node, snarl);
+                    }
+                    (0..=2, NoiseNode::Normal(_)) => {
+                        NoiseNode::propagate_f64_from_tuple_op(from.id.node, snarl);
+                    }
+                    (
+                        5,
+                        NoiseNode::Erosion(_)
+                        | NoiseNode::RigidMulti(_)
+                        | NoiseNode::Select(_)
+                        | NoiseNode::WarpedFbm(_),
+                    ) => {
+                        NoiseNode::propagate_f64_from_tuple_op(from.id.node, snarl);
+                    }
+                    (6, NoiseNode::WarpedFbm(_)) => {
+                        NoiseNode::propagate_u32_from_tuple_op(from.id.node, snarl);
+                    }
+                    (
+                        _,
+                        NoiseNode::Subgraph(_)
+                        | NoiseNode::SubgraphOutput(_)
+                        | NoiseNode::Terrace(_),
+                    ) => {
+                        NoiseNode::propagate_f64_from_tuple_op(from.id.node, snarl);
+                    }
+                    _ => (),
+                }
+            }
+
+            if let NoiseNode::Operation(_) = snarl.get_node(to.id.node).unwrap() {
+                match snarl.get_node(from.id.node).unwrap() {
+                    NoiseNode::Abs(_)
+                    | NoiseNode::Add(_)
+                    | NoiseNode::BasicMulti(_)
+                    | NoiseNode::BiasGain(_)
+                    | NoiseNode::Billow(_)
+                    | NoiseNode::Blend(_)
+                    | NoiseNode::BlueNoise(_)
+                    | NoiseNode::Cache(_)
+                    | NoiseNode::Clamp(_)
+                    | NoiseNode::Checkerboard(_)
+                    | NoiseNode::ColorRamp(_)
+                    | NoiseNode::CombineRgb(_)
+                    | NoiseNode::Comment(_)
+                    | NoiseNode::Compare(_)
+                    | NoiseNode::Contrast(_)
+                    | NoiseNode::ControlPoint(_)
+                    | NoiseNode::Crater(_)
+                    | NoiseNode::Curve(_)
+                    | NoiseNode::Cylinders(_)
+                    | NoiseNode::Difference(_)
+                    | NoiseNode::Displace(_)
+                    | NoiseNode::DomainWarp(_)
+                    | NoiseNode::Ease(_)
+                    | NoiseNode::Erosion(_)
+                    | NoiseNode::Exponent(_)
+                    | NoiseNode::FaultLines(_)
+                    | NoiseNode::Fbm(_)
+                    | NoiseNode::Formula(_)
+                    | NoiseNode::GenericFractal(_)
+                    | NoiseNode::Gradient(_)
+                    | NoiseNode::Group(_)
+                    | NoiseNode::HybridMulti(_)
+                    | NoiseNode::ImageSource(_)
+                    | NoiseNode::Jordan(_)
+                    | NoiseNode::Max(_)
+                    | NoiseNode::Min(_)
+                    | NoiseNode::MirrorPoint(_)
+                    | NoiseNode::Mix(_)
+                    | NoiseNode::Multiply(_)
+                    | NoiseNode::Negate(_)
+                    | NoiseNode::Normal(_)
+                    | NoiseNode::Normalize(_)
+                    | NoiseNode::OpenSimplex(_)
+                    | NoiseNode::Operation(_)
+                    | NoiseNode::Perlin(_)
+                    | NoiseNode::PerlinSurflet(_)
+                    | NoiseNode::Power(_)
+                    | NoiseNode::Remap(_)
+                    | NoiseNode::RigidMulti(_)
+                    | NoiseNode::RotateAxis(_)
+                    | NoiseNode::RotatePoint(_)
+                    | NoiseNode::ScaleBias(_)
+                    | NoiseNode::ScalePoint(_)
+                    | NoiseNode::Script(_)
+                    | NoiseNode::Select(_)
+                    | NoiseNode::Simplex(_)
+                    | NoiseNode::Slope(_)
+                    | NoiseNode::Subgraph(_)
+                    | NoiseNode::SubgraphInput(_)
+                    | NoiseNode::SubgraphOutput(_)
+                    | NoiseNode::SuperSimplex(_)
+                    | NoiseNode::Swiss(_)
+                    | NoiseNode::Switch(_)
+                    | NoiseNode::Swizzle(_)
+                    | NoiseNode::Terrace(_)
+                    | NoiseNode::TilePoint(_)
+                    | NoiseNode::TranslatePoint(_)
+                    | NoiseNode::Turbulence(_)
+                    | NoiseNode::Value(_)
+                    | NoiseNode::Vector(_)
+                    | NoiseNode::WarpedFbm(_)
+                    | NoiseNode::Worley(_) => (),
+                    NoiseNode::F64(_)
+                    | NoiseNode::F64Operation(_)
+                    | NoiseNode::RandomConstant(_)
+                    | NoiseNode::Time(_) => {
+                        NoiseNode::propagate_f64_from_tuple_op(to.id.node, snarl)
+                    }
+                    NoiseNode::U32(_) | NoiseNode::U32Operation(_) => {
+                        NoiseNode::propagate_u32_from_tuple_op(to.id.node, snarl)
+                    }
+                }
+            }
+        }
+        let from_node = snarl.get_node(from.id.node).unwrap().clone();
+        let to_node = snarl.get_node_mut(to.id.node).unwrap();
+
+        match (from_node, to.id.input, to_node) {
+            (
+                NoiseNode::Abs(_)
+                | NoiseNode::Add(_)
+                | NoiseNode::BasicMulti(_)
+                | NoiseNode::BiasGain(_)
+                | NoiseNode::Billow(_)
+                | NoiseNode::Blend(_)
+                | NoiseNode::BlueNoise(_)
+                | NoiseNode::Cache(_)
+                | NoiseNode::Checkerboard(_)
+                | NoiseNode::Clamp(_)
+                | NoiseNode::ColorRamp(_)
+                | NoiseNode::CombineRgb(_)
+                | NoiseNode::Compare(_)
+                | NoiseNode::Contrast(_)
+                | NoiseNode::ControlPoint(_)
+                | NoiseNode::Crater(_)
+                | NoiseNode::Curve(_)
+                | NoiseNode::Cylinders(_)
+                | NoiseNode::Difference(_)
+                | NoiseNode::Displace(_)
+                | NoiseNode::DomainWarp(_)
+                | NoiseNode::Ease(_)
+                | NoiseNode::Erosion(_)
+                | NoiseNode::Exponent(_)
+                | NoiseNode::F64(_)
+                | NoiseNode::F64Operation(_)
+                | NoiseNode::FaultLines(_)
+                | NoiseNode::Fbm(_)
+                | NoiseNode::Formula(_)
+                | NoiseNode::GenericFractal(_)
+                | NoiseNode::Gradient(_)
+                | NoiseNode::HybridMulti(_)
+                | NoiseNode::ImageSource(_)
+                | NoiseNode::Jordan(_)
+                | NoiseNode::Max(_)
+                | NoiseNode::Min(_)
+                | NoiseNode::MirrorPoint(_)
+                | NoiseNode::Mix(_)
+                | NoiseNode::Multiply(_)
+                | NoiseNode::Negate(_)
+                | NoiseNode::Normal(_)
+                | NoiseNode::Normalize(_)
+                | NoiseNode::OpenSimplex(_)
+                | NoiseNode::Perlin(_)
+                | NoiseNode::PerlinSurflet(_)
+                | NoiseNode::Power(_)
+                | NoiseNode::RandomConstant(_)
+                | NoiseNode::Remap(_)
+                | NoiseNode::RigidMulti(_)
+                | NoiseNode::RotateAxis(_)
+                | NoiseNode::RotatePoint(_)
+                | NoiseNode::ScaleBias(_)
+                | NoiseNode::ScalePoint(_)
+                | NoiseNode::Script(_)
+                | NoiseNode::Select(_)
+                | NoiseNode::Simplex(_)
+                | NoiseNode::Slope(_)
+                | NoiseNode::Subgraph(_)
+                | NoiseNode::SubgraphInput(_)
+                | NoiseNode::SuperSimplex(_)
+                | NoiseNode::Swiss(_)
+                | NoiseNode::Switch(_)
+                | NoiseNode::Swizzle(_)
+                | NoiseNode::Terrace(_)
+                | NoiseNode::TilePoint(_)
+                | NoiseNode::TranslatePoint(_)
+                | NoiseNode::Turbulence(_)
+                | NoiseNode::Value(_)
+                | NoiseNode::WarpedFbm(_)
+                | NoiseNode::Worley(_),
+                0,
+                NoiseNode::Abs(_)
+                | NoiseNode::BiasGain(_)
+                | NoiseNode::Cache(_)
+                | NoiseNode::Clamp(_)
+                | NoiseNode::ColorRamp(_)
+                | NoiseNode::Contrast(_)
+                | NoiseNode::Crater(_)
+                | NoiseNode::Curve(_)
+                | NoiseNode::Displace(_)
+                | NoiseNode::DomainWarp(_)
+                | NoiseNode::Ease(_)
+                | NoiseNode::Exponent(_)
+                | NoiseNode::GenericFractal(_)
+                | NoiseNode::MirrorPoint(_)
+                | NoiseNode::Negate(_)
+                | NoiseNode::Normal(_)
+                | NoiseNode::Normalize(_)
+                | NoiseNode::Remap(_)
+                | NoiseNode::RotateAxis(_)
+                | NoiseNode::RotatePoint(_)
+                | NoiseNode::ScaleBias(_)
+                | NoiseNode::ScalePoint(_)
+                | NoiseNode::Slope(_)
+                | NoiseNode::Swizzle(_)
+                | NoiseNode::Terrace(_)
+                | NoiseNode::TilePoint(_)
+                | NoiseNode::TranslatePoint(_)
+                | NoiseNode::Turbulence(_),
+            ) => {}
+            (
+                NoiseNode::F64(_)
+                | NoiseNode::F64Operation(_)
+                | NoiseNode::RandomConstant(_)
+                | NoiseNode::Time(_),
+                0,
+                NoiseNode::ControlPoint(node),
+            ) => {
+                node.input = Node(from.id.node);
+            }
+            (
+                NoiseNode::F64(_)
+                | NoiseNode::F64Operation(_)
+                | NoiseNode::RandomConstant(_)
+                | NoiseNode::Time(_),
+                0,
+                NoiseNode::Cylinders(node),
+            ) => {
+                node.frequency = Node(from.id.node);
+            }
+            (
+                NoiseNode::F64(_)
+                | NoiseNode::F64Operation(_)
+                | NoiseNode::RandomConstant(_)
+                | NoiseNode::Time(_),
+                0,
+                NoiseNode::Gradient(node),
+            ) => {
+                node.angle = Node(from.id.node);
+            }
+            (NoiseNode::U32(_) | NoiseNode::U32Operation(_), 0, NoiseNode::Checkerboard(node)) => {
+                node.size = Node(from.id.node);
+            }
+            (
+                NoiseNode::U32(_) | NoiseNode::U32Operation(_),
+                0,
+                NoiseNode::BasicMulti(FractalNode { seed, .. })
+                | NoiseNode::Billow(FractalNode { seed, .. })
+                | NoiseNode::BlueNoise(GeneratorNode { seed, .. })
+                | NoiseNode::Erosion(ErosionNode { seed, .. })
+                | NoiseNode::FaultLines(FaultLinesNode { seed, .. })
+                | NoiseNode::Fbm(FractalNode { seed, .. })
+                | NoiseNode::HybridMulti(FractalNode { seed, .. })
+                | NoiseNode::Jordan(FractalNode { seed, .. })
+                | NoiseNode::OpenSimplex(GeneratorNode { seed, .. })
+                | NoiseNode::Perlin(GeneratorNode { seed, .. })
+                | NoiseNode::PerlinSurflet(GeneratorNode { seed, .. })
+                | NoiseNode::RigidMulti(RigidFractalNode { seed, .. })
+                | NoiseNode::Simplex(GeneratorNode { seed, .. })
+                | NoiseNode::SuperSimplex(GeneratorNode { seed, .. })
+                | NoiseNode::Swiss(FractalNode { seed, .. })
+                | NoiseNode::Value(GeneratorNode { seed, .. })
+                | NoiseNode::WarpedFbm(WarpedFbmNode { seed, .. })
+                | NoiseNode::Worley(WorleyNode { seed, .. }),
+            ) => {
+                *seed = Node(from.id.node);
+            }
+            (
+                NoiseNode::F64(_)
+                | NoiseNode::F64Operation(_)
+                | NoiseNode::RandomConstant(_)
+                | NoiseNode::Time(_),
+                0 | 1,
+                NoiseNode::F64Operation(node),
+            ) => {
+                node.inputs[to.id.input] = Node(from.id.node);
+            }
+            (NoiseNode::Operation(_), 0 | 1, NoiseNode::Operation(node)) => {
+                node.inputs[to.id.input] = Node(from.id.node);
+            }
+            (
+                NoiseNode::U32(_) | NoiseNode::U32Operation(_),
+                0 | 1,
+                NoiseNode::U32Operation(node),
+            ) => {
+                node.inputs[to.id.input] = Node(from.id.node);
+            }
+            (
+                NoiseNode::Abs(_)
+                | NoiseNode::Add(_)
+                | NoiseNode::BasicMulti(_)
+                | NoiseNode::BiasGain(_)
+                | NoiseNode::Billow(_)
+                | NoiseNode::Blend(_)
+                | NoiseNode::BlueNoise(_)
+                | NoiseNode::Cache(_)
+                | NoiseNode::Checkerboard(_)
+                | NoiseNode::Clamp(_)
+                | NoiseNode::ColorRamp(_)
+                | NoiseNode::CombineRgb(_)
+                | NoiseNode::Compare(_)
+                | NoiseNode::Contrast(_)
+                | NoiseNode::ControlPoint(_)
+                | NoiseNode::Crater(_)
+                | NoiseNode::Curve(_)
+                | NoiseNode::Cylinders(_)
+                | NoiseNode::Difference(_)
+                | NoiseNode::Displace(_)
+                | NoiseNode::DomainWarp(_)
+                | NoiseNode::Ease(_)
+                | NoiseNode::Erosion(_)
+                | NoiseNode::Exponent(_)
+                | NoiseNode::F64(_)
+                | NoiseNode::F64Operation(_)
+                | NoiseNode::FaultLines(_)
+                | NoiseNode::Fbm(_)
+                | NoiseNode::Formula(_)
+                | NoiseNode::GenericFractal(_)
+                | NoiseNode::Gradient(_)
+                | NoiseNode::HybridMulti(_)
+                | NoiseNode::ImageSource(_)
+                | NoiseNode::Jordan(_)
+                | NoiseNode::Max(_)
+                | NoiseNode::Min(_)
+                | NoiseNode::MirrorPoint(_)
+                | NoiseNode::Mix(_)
+                | NoiseNode::Multiply(_)
+                | NoiseNode::Negate(_)
+                | NoiseNode::Normal(_)
+                | NoiseNode::Normalize(_)
+                | NoiseNode::OpenSimplex(_)
+                | NoiseNode::Perlin(_)
+                | NoiseNode::PerlinSurflet(_)
+                | NoiseNode::Power(_)
+                | NoiseNode::RandomConstant(_)
+                | NoiseNode::Remap(_)
+                | NoiseNode::RigidMulti(_)
+                | NoiseNode::RotateAxis(_)
+                | NoiseNode::RotatePoint(_)
+                | NoiseNode::ScaleBias(_)
+                | NoiseNode::ScalePoint(_)
+                | NoiseNode::Script(_)
+                | NoiseNode::Select(_)
+                | NoiseNode::Simplex(_)
+                | NoiseNode::Slope(_)
+                | NoiseNode::Subgraph(_)
+                | NoiseNode::SubgraphInput(_)
+                | NoiseNode::SuperSimplex(_)
+                | NoiseNode::Swiss(_)
+                | NoiseNode::Switch(_)
+                | NoiseNode::Swizzle(_)
+                | NoiseNode::Terrace(_)
+                | NoiseNode::TilePoint(_)
+                | NoiseNode::TranslatePoint(_)
+                | NoiseNode::Turbulence(_)
+                | NoiseNode::Value(_)
+                | NoiseNode::WarpedFbm(_)
+                | NoiseNode::Worley(_),
+                0 | 1,
+                NoiseNode::Add(_)
+                | NoiseNode::Difference(_)
+                | NoiseNode::Min(_)
+                | NoiseNode::Max(_)
+                | NoiseNode::Multiply(_)
+                | NoiseNode::Power(_),
+            ) => {}
+            (
+                NoiseNode::Abs(_)
+                | NoiseNode::Add(_)
+                | NoiseNode::BasicMulti(_)
+                | NoiseNode::BiasGain(_)
+                | NoiseNode::Billow(_)
+                | NoiseNode::Blend(_)
+                | NoiseNode::BlueNoise(_)
+                | NoiseNode::Cache(_)
+                | NoiseNode::Checkerboard(_)
+                | NoiseNode::Clamp(_)
+                | NoiseNode::ColorRamp(_)
+                | NoiseNode::CombineRgb(_)
+                | NoiseNode::Compare(_)
+                | NoiseNode::Contrast(_)
+                | NoiseNode::ControlPoint(_)
+                | NoiseNode::Crater(_)
+                | NoiseNode::Curve(_)
+                | NoiseNode::Cylinders(_)
+                | NoiseNode::Difference(_)
+                | NoiseNode::Displace(_)
+                | NoiseNode::DomainWarp(_)
+                | NoiseNode::Ease(_)
+                | NoiseNode::Erosion(_)
+                | NoiseNode::Exponent(_)
+                | NoiseNode::F64(_)
+                | NoiseNode::F64Operation(_)
+                | NoiseNode::FaultLines(_)
+                | NoiseNode::Fbm(_)
+                | NoiseNode::Formula(_)
+                | NoiseNode::GenericFractal(_)
+                | NoiseNode::Gradient(_)
+                | NoiseNode::HybridMulti(_)
+                | NoiseNode::ImageSource(_)
+                | NoiseNode::Jordan(_)
+                | NoiseNode::Max(_)
+                | NoiseNode::Min(_)
+                | NoiseNode::MirrorPoint(_)
+                | NoiseNode::Mix(_)
+                | NoiseNode::Multiply(_)
+                | NoiseNode::Negate(_)
+                | NoiseNode::Normal(_)
+                | NoiseNode::Normalize(_)
+                | NoiseNode::OpenSimplex(_)
+                | NoiseNode::Perlin(_)
+                | NoiseNode::PerlinSurflet(_)
+                | NoiseNode::Power(_)
+                | NoiseNode::RandomConstant(_)
+                | NoiseNode::Remap(_)
+                | NoiseNode::RigidMulti(_)
+                | NoiseNode::RotateAxis(_)
+                | NoiseNode::RotatePoint(_)
+                | NoiseNode::ScaleBias(_)
+                | NoiseNode::ScalePoint(_)
+                | NoiseNode::Script(_)
+                | NoiseNode::Select(_)
+                | NoiseNode::Simplex(_)
+                | NoiseNode::Slope(_)
+                | NoiseNode::Subgraph(_)
+                | NoiseNode::SubgraphInput(_)
+                | NoiseNode::SuperSimplex(_)
+                | NoiseNode::Swiss(_)
+                | NoiseNode::Switch(_)
+                | NoiseNode::Swizzle(_)
+                | NoiseNode::Terrace(_)
+                | NoiseNode::TilePoint(_)
+                | NoiseNode::TranslatePoint(_)
+                | NoiseNode::Turbulence(_)
+                | NoiseNode::Value(_)
+                | NoiseNode::WarpedFbm(_)
+                | NoiseNode::Worley(_),
+                0 | 1,
+                NoiseNode::Blend(_)
+                | NoiseNode::CombineRgb(_)
+                | NoiseNode::Compare(_)
+                | NoiseNode::DomainWarp(_)
+                | NoiseNode::Mix(_)
+                | NoiseNode::Select(_),
+            ) => {}
+            (
+                NoiseNode::F64(_)
+                | NoiseNode::F64Operation(_)
+                | NoiseNode::RandomConstant(_)
+                | NoiseNode::Time(_),
+                0,
+                NoiseNode::ImageSource(node),
+            ) => {
+                node.size = Node(from.id.node);
+            }
+            (
+                NoiseNode::U32(_) | NoiseNode::U32Operation(_),
+                0,
+                NoiseNode::RandomConstant(node),
+            ) => {
+                node.seed = Node(from.id.node);
+            }
+            (NoiseNode::U32(_) | NoiseNode::U32Operation(_), 0, NoiseNode::Switch(node)) => {
+                node.selector = Node(from.id.node);
+            }
+            (
+                NoiseNode::F64(_)
+                | NoiseNode::F64Operation(_)
+                | NoiseNode::RandomConstant(_)
+                | NoiseNode::Time(_),
+                1,
+                NoiseNode::BiasGain(node),
+            ) => {
+                node.bias = Node(from.id.node);
+            }
+            (
+                NoiseNode::F64(_)
+                | NoiseNode::F64Operation(_)
+                | NoiseNode::RandomConstant(_)
+                | NoiseNode::Time(_),
+                1,
+                NoiseNode::Clamp(node),
+            ) => {
+                node.lower_bound = Node(from.id.node);
+            }
+            (
+                NoiseNode::F64(_)
+                | NoiseNode::F64Operation(_)
+                | NoiseNode::RandomConstant(_)
+                | NoiseNode::Time(_),
+                1,
+                NoiseNode::Contrast(node),
+            ) => {
+                node.contrast = Node(from.id.node);
+            }
+            (
+                NoiseNode::F64(_)
+                | NoiseNode::F64Operation(_)
+                | NoiseNode::RandomConstant(_)
+                | NoiseNode::Time(_),
+                1,
+                NoiseNode::ControlPoint(node),
+            ) => {
+                node.output = Node(from.id.node);
+            }
+            (
+                NoiseNode::U32(_) | NoiseNode::U32Operation(_),
+                1,
+                NoiseNode::BasicMulti(FractalNode { octaves, .. })
+                | NoiseNode::Billow(FractalNode { octaves, .. })
+                | NoiseNode::Erosion(ErosionNode { octaves, .. })
+                | NoiseNode::Fbm(FractalNode { octaves, .. })
+                | NoiseNode::GenericFractal(GenericFractalNode { octaves, .. })
+                | NoiseNode::HybridMulti(FractalNode { octaves, .. })
+                | NoiseNode::Jordan(FractalNode { octaves, .. })
+                | NoiseNode::RigidMulti(RigidFractalNode { octaves, .. })
+                | NoiseNode::Swiss(FractalNode { octaves, .. })
+                | NoiseNode::WarpedFbm(WarpedFbmNode { octaves, .. }),
+            ) => {
+                *octaves = Node(from.id.node);
+            }
+            (
+                NoiseNode::F64(_)
+                | NoiseNode::F64Operation(_)
+                | NoiseNode::RandomConstant(_)
+                | NoiseNode::Time(_),
+                1,
+                NoiseNode::Exponent(node),
+            ) => {
+                node.exponent = Node(from.id.node);
+            }
+            (NoiseNode::U32(_) | NoiseNode::U32Operation(_), 1, NoiseNode::FaultLines(node)) => {
+                node.iterations = Node(from.id.node);
+            }
+            (
+                NoiseNode::F64(_)
+                | NoiseNode::F64Operation(_)
+                | NoiseNode::RandomConstant(_)
+                | NoiseNode::Time(_),
+                1,
+                NoiseNode::Gradient(node),
+            ) => {
+                node.radius = Node(from.id.node);
+            }
+            (
+                NoiseNode::F64(_)
+                | NoiseNode::F64Operation(_)
+                | NoiseNode::RandomConstant(_)
+                | NoiseNode::Time(_),
+                1,
+                NoiseNode::Normal(node),
+            ) => {
+                node.epsilon = Node(from.id.node);
+            }
+            (
+                NoiseNode::U32(_) | NoiseNode::U32Operation(_),
+                1,
+                NoiseNode::RandomConstant(node),
+            ) => {
+                node.index = Node(from.id.node);
+            }
+            (
+                NoiseNode::F64(_)
+                | NoiseNode::F64Operation(_)
+                | NoiseNode::RandomConstant(_)
+                | NoiseNode::Time(_),
+                1,
+                NoiseNode::Remap(node),
+            ) => {
+                node.in_min = Node(from.id.node);
+            }
+            (
+                NoiseNode::F64(_)
+                | NoiseNode::F64Operation(_)
+                | NoiseNode::RandomConstant(_)
+                | NoiseNode::Time(_),
+                1,
+                NoiseNode::ScaleBias(node),
+            ) => {
+                node.scale = Node(from.id.node);
+            }
+            (
+                NoiseNode::F64(_)
+                | NoiseNode::F64Operation(_)
+                | NoiseNode::RandomConstant(_)
+                | NoiseNode::Time(_),
+                1,
+                NoiseNode::Slope(node),
+            ) => {
+                node.epsilon = Node(from.id.node);
+            }
+            (
+                NoiseNode::F64(_)
+                | NoiseNode::F64Operation(_)
+                | NoiseNode::RandomConstant(_)
+                | NoiseNode::Time(_),
+                1,
+                NoiseNode::Worley(node),
+            ) => {
+                node.frequency = Node(from.id.node);
+            }
+            (NoiseNode::U32(_) | NoiseNode::U32Operation(_), 1, NoiseNode::Crater(node)) => {
+                node.seed = Node(from.id.node);
+            }
+            (NoiseNode::U32(_) | NoiseNode::U32Operation(_), 1, NoiseNode::Turbulence(node)) => {
+                node.seed = Node(from.id.node);
+            }
+            (
+                NoiseNode::Abs(_)
+                | NoiseNode::Add(_)
+                | NoiseNode::BasicMulti(_)
+                | NoiseNode::BiasGain(_)
+                | NoiseNode::Billow(_)
+                | NoiseNode::Blend(_)
+                | NoiseNode::BlueNoise(_)
+                | NoiseNode::Cache(_)
+                | NoiseNode::Checkerboard(_)
+                | NoiseNode::Clamp(_)
+                | NoiseNode::ColorRamp(_)
+                | NoiseNode::CombineRgb(_)
+                | NoiseNode::Compare(_)
+                | NoiseNode::Contrast(_)
+                | NoiseNode::ControlPoint(_)
+                | NoiseNode::Crater(_)
+                | NoiseNode::Curve(_)
+                | NoiseNode::Cylinders(_)
+                | NoiseNode::Difference(_)
+                | NoiseNode::Displace(_)
+                | NoiseNode::DomainWarp(_)
+                | NoiseNode::Ease(_)
+                | NoiseNode::Erosion(_)
+                | NoiseNode::Exponent(_)
                 | NoiseNode::F64(_)
                 | NoiseNode::F64Operation(_)
+                | NoiseNode::FaultLines(_)
                 | NoiseNode::Fbm(_)
+                | NoiseNode::Formula(_)
+                | NoiseNode::GenericFractal(_)
+                | NoiseNode::Gradient(_)
                 | NoiseNode::HybridMulti(_)
+                | NoiseNode::ImageSource(_)
+                | NoiseNode::Jordan(_)
                 | NoiseNode::Max(_)
                 | NoiseNode::Min(_)
+                | NoiseNode::MirrorPoint(_)
+                | NoiseNode::Mix(_)
                 | NoiseNode::Multiply(_)
                 | NoiseNode::Negate(_)
+                | NoiseNode::Normal(_)
+                | NoiseNode::Normalize(_)
                 | NoiseNode::OpenSimplex(_)
                 | NoiseNode::Perlin(_)
                 | NoiseNode::PerlinSurflet(_)
                 | NoiseNode::Power(_)
+                | NoiseNode::RandomConstant(_)
+                | NoiseNode::Remap(_)
                 | NoiseNode::RigidMulti(_)
+                | NoiseNode::RotateAxis(_)
                 | NoiseNode::RotatePoint(_)
                 | NoiseNode::ScaleBias(_)
                 | NoiseNode::ScalePoint(_)
+                | NoiseNode::Script(_)
                 | NoiseNode::Select(_)
                 | NoiseNode::Simplex(_)
+                | NoiseNode::Slope(_)
+                | NoiseNode::Subgraph(_)
+                | NoiseNode::SubgraphInput(_)
                 | NoiseNode::SuperSimplex(_)
+                | NoiseNode::Swiss(_)
+                | NoiseNode::Switch(_)
+                | NoiseNode::Swizzle(_)
                 | NoiseNode::Terrace(_)
+                | NoiseNode::TilePoint(_)
                 | NoiseNode::TranslatePoint(_)
                 | NoiseNode::Turbulence(_)
                 | NoiseNode::Value(_)
+                | NoiseNode::WarpedFbm(_)
                 | NoiseNode::Worley(_),
-                0,
-                NoiseNode::Abs(_)
-                | NoiseNode::Clamp(_)
-                | NoiseNode::Curve(_)
-                | NoiseNode::Displace(_)
-                | NoiseNode::Exponent(_)
-                | NoiseNode::Negate(_)
-                | NoiseNode::RotatePoint(_)
-                | NoiseNode::ScaleBias(_)
-                | NoiseNode::ScalePoint(_)
-                | NoiseNode::Terrace(_)
-                | NoiseNode::TranslatePoint(_)
-                | NoiseNode::Turbulence(_),
+                1..=4,
+                NoiseNode::Displace(_),
             ) => {}
-            (NoiseNode::F64(_) | NoiseNode::F64Operation(_), 0, NoiseNode::ControlPoint(node)) => {
-                node.input = Node(from.id.node);
-            }
-            (NoiseNode::F64(_) | NoiseNode::F64Operation(_), 0, NoiseNode::Cylinders(node)) => {
-                node.frequency = Node(from.id.node);
-            }
-            (NoiseNode::U32(_) | NoiseNode::U32Operation(_), 0, NoiseNode::Checkerboard(node)) => {
-                node.size = Node(from.id.node);
+            (
+                NoiseNode::F64(_)
+                | NoiseNode::F64Operation(_)
+                | NoiseNode::RandomConstant(_)
+                | NoiseNode::Time(_),
+                1..=4,
+                NoiseNode::RotatePoint(node)
+                | NoiseNode::ScalePoint(node)
+                | NoiseNode::TranslatePoint(node),
+            ) => {
+                node.axes[to.id.input - 1] = Node(from.id.node);
             }
             (
-                NoiseNode::U32(_) | NoiseNode::U32Operation(_),
-                0,
-                NoiseNode::BasicMulti(FractalNode { seed, .. })
-                | NoiseNode::Billow(FractalNode { seed, .. })
-                | NoiseNode::Fbm(FractalNode { seed, .. })
-                | NoiseNode::HybridMulti(FractalNode { seed, .. })
-                | NoiseNode::OpenSimplex(GeneratorNode { seed, .. })
-                | NoiseNode::Perlin(GeneratorNode { seed, .. })
-                | NoiseNode::PerlinSurflet(GeneratorNode { seed, .. })
-                | NoiseNode::RigidMulti(RigidFractalNode { seed, .. })
-                | NoiseNode::Simplex(GeneratorNode { seed, .. })
-                | NoiseNode::SuperSimplex(GeneratorNode { seed, .. })
-                | NoiseNode::Value(GeneratorNode { seed, .. })
-                | NoiseNode::Worley(WorleyNode { seed, .. }),
+                NoiseNode::F64(_)
+                | NoiseNode::F64Operation(_)
+                | NoiseNode::RandomConstant(_)
+                | NoiseNode::Time(_),
+                0..=3,
+                NoiseNode::Vector(node),
             ) => {
-                *seed = Node(from.id.node);
+                node.axes[to.id.input] = Node(from.id.node);
             }
             (
-                NoiseNode::F64(_) | NoiseNode::F64Operation(_),
-                0 | 1,
-                NoiseNode::F64Operation(node),
+                NoiseNode::Vector(_),
+                5,
+                NoiseNode::RotatePoint(node)
+                | NoiseNode::ScalePoint(node)
+                | NoiseNode::TranslatePoint(node),
             ) => {
-                node.inputs[to.id.input] = Node(from.id.node);
+                node.vector_node_id = Some(from.id.node);
             }
-            (NoiseNode::Operation(_), 0 | 1, NoiseNode::Operation(node)) => {
-                node.inputs[to.id.input] = Node(from.id.node);
+            (
+                NoiseNode::F64(_)
+                | NoiseNode::F64Operation(_)
+                | NoiseNode::RandomConstant(_)
+                | NoiseNode::Time(_),
+                1..=3,
+                NoiseNode::RotateAxis(node),
+            ) => {
+                node.axis[to.id.input - 1] = Node(from.id.node);
             }
             (
-                NoiseNode::U32(_) | NoiseNode::U32Operation(_),
-                0 | 1,
-                NoiseNode::U32Operation(node),
+                NoiseNode::F64(_)
+                | NoiseNode::F64Operation(_)
+                | NoiseNode::RandomConstant(_)
+                | NoiseNode::Time(_),
+                1..=4,
+                NoiseNode::TilePoint(node),
             ) => {
-                node.inputs[to.id.input] = Node(from.id.node);
+                node.periods[to.id.input - 1] = Node(from.id.node);
             }
             (
                 NoiseNode::Abs(_)
                 | NoiseNode::Add(_)
                 | NoiseNode::BasicMulti(_)
+                | NoiseNode::BiasGain(_)
                 | NoiseNode::Billow(_)
                 | NoiseNode::Blend(_)
+                | NoiseNode::BlueNoise(_)
+                | NoiseNode::Cache(_)
                 | NoiseNode::Checkerboard(_)
                 | NoiseNode::Clamp(_)
+                | NoiseNode::ColorRamp(_)
+                | NoiseNode::CombineRgb(_)
+                | NoiseNode::Compare(_)
+                | NoiseNode::Contrast(_)
                 | NoiseNode::ControlPoint(_)
+                | NoiseNode::Crater(_)
                 | NoiseNode::Curve(_)
                 | NoiseNode::Cylinders(_)
+                | NoiseNode::Difference(_)
                 | NoiseNode::Displace(_)
+                | NoiseNode::DomainWarp(_)
+                | NoiseNode::Ease(_)
+                | NoiseNode::Erosion(_)
                 | NoiseNode::Exponent(_)
                 | NoiseNode::F64(_)
                 | NoiseNode::F64Operation(_)
+                | NoiseNode::FaultLines(_)
                 | NoiseNode::Fbm(_)
+                | NoiseNode::Formula(_)
+                | NoiseNode::GenericFractal(_)
+                | NoiseNode::Gradient(_)
                 | NoiseNode::HybridMulti(_)
+                | NoiseNode::ImageSource(_)
+                | NoiseNode::Jordan(_)
                 | NoiseNode::Max(_)
                 | NoiseNode::Min(_)
+                | NoiseNode::MirrorPoint(_)
+                | NoiseNode::Mix(_)
                 | NoiseNode::Multiply(_)
                 | NoiseNode::Negate(_)
+                | NoiseNode::Normal(_)
+                | NoiseNode::Normalize(_)
                 | NoiseNode::OpenSimplex(_)
                 | NoiseNode::Perlin(_)
                 | NoiseNode::PerlinSurflet(_)
                 | NoiseNode::Power(_)
+                | NoiseNode::RandomConstant(_)
+                | NoiseNode::Remap(_)
                 | NoiseNode::RigidMulti(_)
+                | NoiseNode::RotateAxis(_)
                 | NoiseNode::RotatePoint(_)
                 | NoiseNode::ScaleBias(_)
                 | NoiseNode::ScalePoint(_)
+                | NoiseNode::Script(_)
                 | NoiseNode::Select(_)
                 | NoiseNode::Simplex(_)
+                | NoiseNode::Slope(_)
+                | NoiseNode::Subgraph(_)
+                | NoiseNode::SubgraphInput(_)
                 | NoiseNode::SuperSimplex(_)
+                | NoiseNode::Swiss(_)
+                | NoiseNode::Switch(_)
+                | NoiseNode::Swizzle(_)
                 | NoiseNode::Terrace(_)
+                | NoiseNode::TilePoint(_)
                 | NoiseNode::TranslatePoint(_)
                 | NoiseNode::Turbulence(_)
                 | NoiseNode::Value(_)
+                | NoiseNode::WarpedFbm(_)
                 | NoiseNode::Worley(_),
-                0 | 1,
-                NoiseNode::Add(_)
-                | NoiseNode::Min(_)
-                | NoiseNode::Max(_)
-                | NoiseNode::Multiply(_)
-                | NoiseNode::Power(_),
+                2,
+                NoiseNode::Blend(_) | NoiseNode::CombineRgb(_) | NoiseNode::Select(_),
             ) => {}
+            (
+                NoiseNode::F64(_)
+                | NoiseNode::F64Operation(_)
+                | NoiseNode::RandomConstant(_)
+                | NoiseNode::Time(_),
+                2,
+                NoiseNode::BasicMulti(FractalNode { frequency, .. })
+                | NoiseNode::Billow(FractalNode { frequency, .. })
+                | NoiseNode::Erosion(ErosionNode { frequency, .. })
+                | NoiseNode::Fbm(FractalNode { frequency, .. })
+                | NoiseNode::GenericFractal(GenericFractalNode { frequency, .. })
+                | NoiseNode::HybridMulti(FractalNode { frequency, .. })
+                | NoiseNode::Jordan(FractalNode { frequency, .. })
+                | NoiseNode::RigidMulti(RigidFractalNode { frequency, .. })
+                | NoiseNode::Swiss(FractalNode { frequency, .. })
+                | NoiseNode::Turbulence(TurbulenceNode { frequency, .. })
+                | NoiseNode::WarpedFbm(WarpedFbmNode { frequency, .. }),
+            ) => {
+                *frequency = Node(from.id.node);
+            }
+            (
+                NoiseNode::F64(_)
+                | NoiseNode::F64Operation(_)
+                | NoiseNode::RandomConstant(_)
+                | NoiseNode::Time(_),
+                2,
+                NoiseNode::BiasGain(node),
+            ) => {
+                node.gain = Node(from.id.node);
+            }
+            (
+                NoiseNode::F64(_)
+                | NoiseNode::F64Operation(_)
+                | NoiseNode::RandomConstant(_)
+                | NoiseNode::Time(_),
+                2,
+                NoiseNode::Clamp(node),
+            ) => {
+                node.upper_bound = Node(from.id.node);
+            }
+            (
+                NoiseNode::F64(_)
+                | NoiseNode::F64Operation(_)
+                | NoiseNode::RandomConstant(_)
+                | NoiseNode::Time(_),
+                2,
+                NoiseNode::Compare(node),
+            ) => {
+                node.tolerance = Node(from.id.node);
+            }
+            (
+                NoiseNode::F64(_)
+                | NoiseNode::F64Operation(_)
+                | NoiseNode::RandomConstant(_)
+                | NoiseNode::Time(_),
+                2,
+                NoiseNode::Contrast(node),
+            ) => {
+                node.pivot = Node(from.id.node);
+            }
+            (
+                NoiseNode::F64(_)
+                | NoiseNode::F64Operation(_)
+                | NoiseNode::RandomConstant(_)
+                | NoiseNode::Time(_),
+                2,
+                NoiseNode::Crater(node),
+            ) => {
+                node.density = Node(from.id.node);
+            }
+            (
+                NoiseNode::F64(_)
+                | NoiseNode::F64Operation(_)
+                | NoiseNode::RandomConstant(_)
+                | NoiseNode::Time(_),
+                2,
+                NoiseNode::DomainWarp(node),
+            ) => {
+                node.amplitude = Node(from.id.node);
+            }
+            (
+                NoiseNode::F64(_)
+                | NoiseNode::F64Operation(_)
+                | NoiseNode::RandomConstant(_)
+                | NoiseNode::Time(_),
+                2,
+                NoiseNode::FaultLines(node),
+            ) => {
+                node.amplitude = Node(from.id.node);
+            }
+            (
+                NoiseNode::F64(_)
+                | NoiseNode::F64Operation(_)
+                | NoiseNode::RandomConstant(_)
+                | NoiseNode::Time(_),
+                2,
+                NoiseNode::Mix(node),
+            ) => {
+                node.factor = Node(from.id.node);
+            }
+            (
+                NoiseNode::F64(_)
+                | NoiseNode::F64Operation(_)
+                | NoiseNode::RandomConstant(_)
+                | NoiseNode::Time(_),
+                2,
+                NoiseNode::Normal(node),
+            ) => {
+                node.strength = Node(from.id.node);
+            }
+            (
+                NoiseNode::F64(_)
+                | NoiseNode::F64Operation(_)
+                | NoiseNode::RandomConstant(_)
+                | NoiseNode::Time(_),
+                2,
+                NoiseNode::RandomConstant(node),
+            ) => {
+                node.min = Node(from.id.node);
+            }
+            (
+                NoiseNode::F64(_)
+                | NoiseNode::F64Operation(_)
+                | NoiseNode::RandomConstant(_)
+                | NoiseNode::Time(_),
+                2,
+                NoiseNode::Remap(node),
+            ) => {
+                node.in_max = Node(from.id.node);
+            }
+            (
+                NoiseNode::F64(_)
+                | NoiseNode::F64Operation(_)
+                | NoiseNode::RandomConstant(_)
+                | NoiseNode::Time(_),
+                2,
+                NoiseNode::ScaleBias(node),
+            ) => {
+                node.bias = Node(from.id.node);
+            }
+            (
+                NoiseNode::F64(_)
+                | NoiseNode::F64Operation(_)
+                | NoiseNode::RandomConstant(_)
+                | NoiseNode::Time(_),
+                3,
+                NoiseNode::BasicMulti(FractalNode { lacunarity, .. })
+                | NoiseNode::Billow(FractalNode { lacunarity, .. })
+                | NoiseNode::Erosion(ErosionNode { lacunarity, .. })
+                | NoiseNode::Fbm(FractalNode { lacunarity, .. })
+                | NoiseNode::GenericFractal(GenericFractalNode { lacunarity, .. })
+                | NoiseNode::HybridMulti(FractalNode { lacunarity, .. })
+                | NoiseNode::Jordan(FractalNode { lacunarity, .. })
+                | NoiseNode::RigidMulti(RigidFractalNode { lacunarity, .. })
+                | NoiseNode::Swiss(FractalNode { lacunarity, .. })
+                | NoiseNode::WarpedFbm(WarpedFbmNode { lacunarity, .. }),
+            ) => {
+                *lacunarity = Node(from.id.node);
+            }
+            (
+                NoiseNode::F64(_)
+                | NoiseNode::F64Operation(_)
+                | NoiseNode::RandomConstant(_)
+                | NoiseNode::Time(_),
+                3,
+                NoiseNode::Crater(node),
+            ) => {
+                node.radius = Node(from.id.node);
+            }
+            (
+                NoiseNode::F64(_)
+                | NoiseNode::F64Operation(_)
+                | NoiseNode::RandomConstant(_)
+                | NoiseNode::Time(_),
+                3,
+                NoiseNode::DomainWarp(node),
+            ) => {
+                node.frequency = Node(from.id.node);
+            }
+            (
+                NoiseNode::F64(_)
+                | NoiseNode::F64Operation(_)
+                | NoiseNode::RandomConstant(_)
+                | NoiseNode::Time(_),
+                3,
+                NoiseNode::RandomConstant(node),
+            ) => {
+                node.max = Node(from.id.node);
+            }
+            (
+                NoiseNode::F64(_)
+                | NoiseNode::F64Operation(_)
+                | NoiseNode::RandomConstant(_)
+                | NoiseNode::Time(_),
+                3,
+                NoiseNode::Remap(node),
+            ) => {
+                node.out_min = Node(from.id.node);
+            }
+            (
+                NoiseNode::F64(_)
+                | NoiseNode::F64Operation(_)
+                | NoiseNode::RandomConstant(_)
+                | NoiseNode::Time(_),
+                3,
+                NoiseNode::Select(node),
+            ) => {
+                node.lower_bound = Node(from.id.node);
+            }
+            (
+                NoiseNode::F64(_)
+                | NoiseNode::F64Operation(_)
+                | NoiseNode::RandomConstant(_)
+                | NoiseNode::Time(_),
+                3,
+                NoiseNode::Turbulence(node),
+            ) => {
+                node.power = Node(from.id.node);
+            }
+            (
+                NoiseNode::F64(_)
+                | NoiseNode::F64Operation(_)
+                | NoiseNode::RandomConstant(_)
+                | NoiseNode::Time(_),
+                4,
+                NoiseNode::BasicMulti(FractalNode { persistence, .. })
+                | NoiseNode::Billow(FractalNode { persistence, .. })
+                | NoiseNode::Erosion(ErosionNode { persistence, .. })
+                | NoiseNode::Fbm(FractalNode { persistence, .. })
+                | NoiseNode::GenericFractal(GenericFractalNode { persistence, .. })
+                | NoiseNode::HybridMulti(FractalNode { persistence, .. })
+                | NoiseNode::Jordan(FractalNode { persistence, .. })
+                | NoiseNode::RigidMulti(RigidFractalNode { persistence, .. })
+                | NoiseNode::Swiss(FractalNode { persistence, .. })
+                | NoiseNode::WarpedFbm(WarpedFbmNode { persistence, .. }),
+            ) => {
+                *persistence = Node(from.id.node);
+            }
+            (
+                NoiseNode::F64(_)
+                | NoiseNode::F64Operation(_)
+                | NoiseNode::RandomConstant(_)
+                | NoiseNode::Time(_),
+                4,
+                NoiseNode::Crater(node),
+            ) => {
+                node.depth = Node(from.id.node);
+            }
+            (
+                NoiseNode::F64(_)
+                | NoiseNode::F64Operation(_)
+                | NoiseNode::RandomConstant(_)
+                | NoiseNode::Time(_),
+                4,
+                NoiseNode::Remap(node),
+            ) => {
+                node.out_max = Node(from.id.node);
+            }
+            (
+                NoiseNode::F64(_)
+                | NoiseNode::F64Operation(_)
+                | NoiseNode::RandomConstant(_)
+                | NoiseNode::Time(_),
+                4,
+                NoiseNode::RotateAxis(node),
+            ) => {
+                node.angle = Node(from.id.node);
+            }
+            (
+                NoiseNode::F64(_)
+                | NoiseNode::F64Operation(_)
+                | NoiseNode::RandomConstant(_)
+                | NoiseNode::Time(_),
+                4,
+                NoiseNode::Select(node),
+            ) => {
+                node.upper_bound = Node(from.id.node);
+            }
+            (NoiseNode::U32(_) | NoiseNode::U32Operation(_), 4, NoiseNode::Turbulence(node)) => {
+                node.roughness = Node(from.id.node);
+            }
+            (
+                NoiseNode::F64(_)
+                | NoiseNode::F64Operation(_)
+                | NoiseNode::RandomConstant(_)
+                | NoiseNode::Time(_),
+                5,
+                NoiseNode::Erosion(node),
+            ) => {
+                node.strength = Node(from.id.node);
+            }
+            (
+                NoiseNode::F64(_)
+                | NoiseNode::F64Operation(_)
+                | NoiseNode::RandomConstant(_)
+                | NoiseNode::Time(_),
+                5,
+                NoiseNode::RigidMulti(node),
+            ) => {
+                node.attenuation = Node(from.id.node);
+            }
+            (
+                NoiseNode::F64(_)
+                | NoiseNode::F64Operation(_)
+                | NoiseNode::RandomConstant(_)
+                | NoiseNode::Time(_),
+                5,
+                NoiseNode::Select(node),
+            ) => {
+                node.falloff = Node(from.id.node);
+            }
+            (
+                NoiseNode::F64(_)
+                | NoiseNode::F64Operation(_)
+                | NoiseNode::RandomConstant(_)
+                | NoiseNode::Time(_),
+                5,
+                NoiseNode::WarpedFbm(node),
+            ) => {
+                node.strength = Node(from.id.node);
+            }
+            (NoiseNode::U32(_) | NoiseNode::U32Operation(_), 6, NoiseNode::WarpedFbm(node)) => {
+                node.iterations = Node(from.id.node);
+            }
             (
                 NoiseNode::Abs(_)
                 | NoiseNode::Add(_)
                 | NoiseNode::BasicMulti(_)
+                | NoiseNode::BiasGain(_)
                 | NoiseNode::Billow(_)
                 | NoiseNode::Blend(_)
+                | NoiseNode::BlueNoise(_)
+                | NoiseNode::Cache(_)
                 | NoiseNode::Checkerboard(_)
                 | NoiseNode::Clamp(_)
+                | NoiseNode::ColorRamp(_)
+                | NoiseNode::CombineRgb(_)
+                | NoiseNode::Compare(_)
+                | NoiseNode::Contrast(_)
                 | NoiseNode::ControlPoint(_)
+                | NoiseNode::Crater(_)
                 | NoiseNode::Curve(_)
                 | NoiseNode::Cylinders(_)
+                | NoiseNode::Difference(_)
                 | NoiseNode::Displace(_)
+                | NoiseNode::DomainWarp(_)
+                | NoiseNode::Ease(_)
+                | NoiseNode::Erosion(_)
                 | NoiseNode::Exponent(_)
                 | NoiseNode::F64(_)
                 | NoiseNode::F64Operation(_)
+                | NoiseNode::FaultLines(_)
                 | NoiseNode::Fbm(_)
+                | NoiseNode::Formula(_)
+                | NoiseNode::GenericFractal(_)
+                | NoiseNode::Gradient(_)
                 | NoiseNode::HybridMulti(_)
+                | NoiseNode::ImageSource(_)
+                | NoiseNode::Jordan(_)
                 | NoiseNode::Max(_)
                 | NoiseNode::Min(_)
+                | NoiseNode::MirrorPoint(_)
+                | NoiseNode::Mix(_)
                 | NoiseNode::Multiply(_)
                 | NoiseNode::Negate(_)
+                | NoiseNode::Normal(_)
+                | NoiseNode::Normalize(_)
                 | NoiseNode::OpenSimplex(_)
                 | NoiseNode::Perlin(_)
                 | NoiseNode::PerlinSurflet(_)
                 | NoiseNode::Power(_)
+                | NoiseNode::RandomConstant(_)
+                | NoiseNode::Remap(_)
                 | NoiseNode::RigidMulti(_)
+                | NoiseNode::RotateAxis(_)
                 | NoiseNode::RotatePoint(_)
                 | NoiseNode::ScaleBias(_)
                 | NoiseNode::ScalePoint(_)
+                | NoiseNode::Script(_)
                 | NoiseNode::Select(_)
                 | NoiseNode::Simplex(_)
+                | NoiseNode::Slope(_)
+                | NoiseNode::Subgraph(_)
+                | NoiseNode::SubgraphInput(_)
                 | NoiseNode::SuperSimplex(_)
+                | NoiseNode::Swiss(_)
+                | NoiseNode::Switch(_)
+                | NoiseNode::Swizzle(_)
                 | NoiseNode::Terrace(_)
+                | NoiseNode::TilePoint(_)
                 | NoiseNode::TranslatePoint(_)
                 | NoiseNode::Turbulence(_)
                 | NoiseNode::Value(_)
+                | NoiseNode::WarpedFbm(_)
                 | NoiseNode::Worley(_),
-                0 | 1,
-                NoiseNode::Blend(_) | NoiseNode::Select(_),
+                _,
+                NoiseNode::Subgraph(_) | NoiseNode::SubgraphOutput(_),
             ) => {}
-            (NoiseNode::F64(_) | NoiseNode::F64Operation(_), 1, NoiseNode::Clamp(node)) => {
-                node.lower_bound = Node(from.id.node);
-            }
-            (NoiseNode::F64(_) | NoiseNode::F64Operation(_), 1, NoiseNode::ControlPoint(node)) => {
-                node.output = Node(from.id.node);
-            }
-            (
-                NoiseNode::U32(_) | NoiseNode::U32Operation(_),
-                1,
-                NoiseNode::BasicMulti(FractalNode { octaves, .. })
-                | NoiseNode::Billow(FractalNode { octaves, .. })
-                | NoiseNode::Fbm(FractalNode { octaves, .. })
-                | NoiseNode::HybridMulti(FractalNode { octaves, .. })
-                | NoiseNode::RigidMulti(RigidFractalNode { octaves, .. }),
-            ) => {
-                *octaves = Node(from.id.node);
-            }
-            (NoiseNode::F64(_) | NoiseNode::F64Operation(_), 1, NoiseNode::Exponent(node)) => {
-                node.exponent = Node(from.id.node);
-            }
-            (NoiseNode::F64(_) | NoiseNode::F64Operation(_), 1, NoiseNode::ScaleBias(node)) => {
-                node.scale = Node(from.id.node);
-            }
-            (NoiseNode::F64(_) | NoiseNode::F64Operation(_), 1, NoiseNode::Worley(node)) => {
-                node.frequency = Node(from.id.node);
-            }
-            (NoiseNode::U32(_) | NoiseNode::U32Operation(_), 1, NoiseNode::Turbulence(node)) => {
-                node.seed = Node(from.id.node);
-            }
             (
                 NoiseNode::Abs(_)
                 | NoiseNode::Add(_)
                 | NoiseNode::BasicMulti(_)
+                | NoiseNode::BiasGain(_)
                 | NoiseNode::Billow(_)
                 | NoiseNode::Blend(_)
+                | NoiseNode::BlueNoise(_)
+                | NoiseNode::Cache(_)
                 | NoiseNode::Checkerboard(_)
                 | NoiseNode::Clamp(_)
+                | NoiseNode::ColorRamp(_)
+                | NoiseNode::CombineRgb(_)
+                | NoiseNode::Compare(_)
+                | NoiseNode::Contrast(_)
                 | NoiseNode::ControlPoint(_)
+                | NoiseNode::Crater(_)
                 | NoiseNode::Curve(_)
                 | NoiseNode::Cylinders(_)
+                | NoiseNode::Difference(_)
                 | NoiseNode::Displace(_)
+                | NoiseNode::DomainWarp(_)
+                | NoiseNode::Ease(_)
+                | NoiseNode::Erosion(_)
                 | NoiseNode::Exponent(_)
                 | NoiseNode::F64(_)
                 | NoiseNode::F64Operation(_)
+                | NoiseNode::FaultLines(_)
                 | NoiseNode::Fbm(_)
+                | NoiseNode::Formula(_)
+                | NoiseNode::GenericFractal(_)
+                | NoiseNode::Gradient(_)
                 | NoiseNode::HybridMulti(_)
+                | NoiseNode::ImageSource(_)
+                | NoiseNode::Jordan(_)
                 | NoiseNode::Max(_)
                 | NoiseNode::Min(_)
+                | NoiseNode::MirrorPoint(_)
+                | NoiseNode::Mix(_)
                 | NoiseNode::Multiply(_)
                 | NoiseNode::Negate(_)
+                | NoiseNode::Normal(_)
+                | NoiseNode::Normalize(_)
                 | NoiseNode::OpenSimplex(_)
                 | NoiseNode::Perlin(_)
                 | NoiseNode::PerlinSurflet(_)
                 | NoiseNode::Power(_)
+                | NoiseNode::RandomConstant(_)
+                | NoiseNode::Remap(_)
                 | NoiseNode::RigidMulti(_)
+                | NoiseNode::RotateAxis(_)
                 | NoiseNode::RotatePoint(_)
                 | NoiseNode::ScaleBias(_)
                 | NoiseNode::ScalePoint(_)
+                | NoiseNode::Script(_)
                 | NoiseNode::Select(_)
                 | NoiseNode::Simplex(_)
+                | NoiseNode::Slope(_)
+                | NoiseNode::Subgraph(_)
+                | NoiseNode::SubgraphInput(_)
                 | NoiseNode::SuperSimplex(_)
+                | NoiseNode::Swiss(_)
+                | NoiseNode::Switch(_)
+                | NoiseNode::Swizzle(_)
                 | NoiseNode::Terrace(_)
+                | NoiseNode::TilePoint(_)
+                | NoiseNode::Time(_)
                 | NoiseNode::TranslatePoint(_)
                 | NoiseNode::Turbulence(_)
                 | NoiseNode::Value(_)
+                | NoiseNode::WarpedFbm(_)
                 | NoiseNode::Worley(_),
-                1..=4,
-                NoiseNode::Displace(_),
-            ) => {}
-            (
-                NoiseNode::F64(_) | NoiseNode::F64Operation(_),
-                1..=4,
-                NoiseNode::RotatePoint(node)
-                | NoiseNode::ScalePoint(node)
-                | NoiseNode::TranslatePoint(node),
+                source_idx,
+                NoiseNode::Formula(FormulaNode { source_count, .. })
+                | NoiseNode::Script(ScriptNode { source_count, .. }),
             ) => {
-                node.axes[to.id.input - 1] = Node(from.id.node);
+                *source_count = (*source_count).max(source_idx + 1);
             }
             (
                 NoiseNode::Abs(_)
                 | NoiseNode::Add(_)
                 | NoiseNode::BasicMulti(_)
+                | NoiseNode::BiasGain(_)
                 | NoiseNode::Billow(_)
                 | NoiseNode::Blend(_)
+                | NoiseNode::BlueNoise(_)
+                | NoiseNode::Cache(_)
                 | NoiseNode::Checkerboard(_)
                 | NoiseNode::Clamp(_)
+                | NoiseNode::ColorRamp(_)
+                | NoiseNode::CombineRgb(_)
+                | NoiseNode::Compare(_)
+                | NoiseNode::Contrast(_)
                 | NoiseNode::ControlPoint(_)
+                | NoiseNode::Crater(_)
                 | NoiseNode::Curve(_)
                 | NoiseNode::Cylinders(_)
+                | NoiseNode::Difference(_)
                 | NoiseNode::Displace(_)
+                | NoiseNode::DomainWarp(_)
+                | NoiseNode::Ease(_)
+                | NoiseNode::Erosion(_)
                 | NoiseNode::Exponent(_)
                 | NoiseNode::F64(_)
                 | NoiseNode::F64Operation(_)
+                | NoiseNode::FaultLines(_)
                 | NoiseNode::Fbm(_)
+                | NoiseNode::Formula(_)
+                | NoiseNode::GenericFractal(_)
+                | NoiseNode::Gradient(_)
                 | NoiseNode::HybridMulti(_)
+                | NoiseNode::ImageSource(_)
+                | NoiseNode::Jordan(_)
                 | NoiseNode::Max(_)
                 | NoiseNode::Min(_)
+                | NoiseNode::MirrorPoint(_)
+                | NoiseNode::Mix(_)
                 | NoiseNode::Multiply(_)
                 | NoiseNode::Negate(_)
+                | NoiseNode::Normal(_)
+                | NoiseNode::Normalize(_)
                 | NoiseNode::OpenSimplex(_)
                 | NoiseNode::Perlin(_)
                 | NoiseNode::PerlinSurflet(_)
                 | NoiseNode::Power(_)
+                | NoiseNode::RandomConstant(_)
+                | NoiseNode::Remap(_)
                 | NoiseNode::RigidMulti(_)
+                | NoiseNode::RotateAxis(_)
                 | NoiseNode::RotatePoint(_)
                 | NoiseNode::ScaleBias(_)
                 | NoiseNode::ScalePoint(_)
+                | NoiseNode::Script(_)
                 | NoiseNode::Select(_)
                 | NoiseNode::Simplex(_)
+                | NoiseNode::Slope(_)
+                | NoiseNode::Subgraph(_)
+                | NoiseNode::SubgraphInput(_)
                 | NoiseNode::SuperSimplex(_)
+                | NoiseNode::Swiss(_)
+                | NoiseNode::Switch(_)
+                | NoiseNode::Swizzle(_)
                 | NoiseNode::Terrace(_)
+                | NoiseNode::TilePoint(_)
+                | NoiseNode::Time(_)
                 | NoiseNode::TranslatePoint(_)
                 | NoiseNode::Turbulence(_)
                 | NoiseNode::Value(_)
+                | NoiseNode::WarpedFbm(_)
                 | NoiseNode::Worley(_),
-                2,
-                NoiseNode::Blend(_) | NoiseNode::Select(_),
-            ) => {}
-            (
-                NoiseNode::F64(_) | NoiseNode::F64Operation(_),
-                2,
-                NoiseNode::BasicMulti(FractalNode { frequency, .. })
-                | NoiseNode::Billow(FractalNode { frequency, .. })
-                | NoiseNode::Fbm(FractalNode { frequency, .. })
-                | NoiseNode::HybridMulti(FractalNode { frequency, .. })
-                | NoiseNode::RigidMulti(RigidFractalNode { frequency, .. })
-                | NoiseNode::Turbulence(TurbulenceNode { frequency, .. }),
-            ) => {
-                *frequency = Node(from.id.node);
-            }
-            (NoiseNode::F64(_) | NoiseNode::F64Operation(_), 2, NoiseNode::Clamp(node)) => {
-                node.upper_bound = Node(from.id.node);
-            }
-            (NoiseNode::F64(_) | NoiseNode::F64Operation(_), 2, NoiseNode::ScaleBias(node)) => {
-                node.bias = Node(from.id.node);
-            }
-            (
-                NoiseNode::F64(_) | NoiseNode::F64Operation(_),
-                3,
-                NoiseNode::BasicMulti(FractalNode { lacunarity, .. })
-                | NoiseNode::Billow(FractalNode { lacunarity, .. })
-                | NoiseNode::Fbm(FractalNode { lacunarity, .. })
-                | NoiseNode::HybridMulti(FractalNode { lacunarity, .. })
-                | NoiseNode::RigidMulti(RigidFractalNode { lacunarity, .. }),
-            ) => {
-                *lacunarity = Node(from.id.node);
-            }
-            (NoiseNode::F64(_) | NoiseNode::F64Operation(_), 3, NoiseNode::Select(node)) => {
-                node.lower_bound = Node(from.id.node);
-            }
-            (NoiseNode::F64(_) | NoiseNode::F64Operation(_), 3, NoiseNode::Turbulence(node)) => {
-                node.power = Node(from.id.node);
-            }
-            (
-                NoiseNode::F64(_) | NoiseNode::F64Operation(_),
-                4,
-                NoiseNode::BasicMulti(FractalNode { persistence, .. })
-                | NoiseNode::Billow(FractalNode { persistence, .. })
-                | NoiseNode::Fbm(FractalNode { persistence, .. })
-                | NoiseNode::HybridMulti(FractalNode { persistence, .. })
-                | NoiseNode::RigidMulti(RigidFractalNode { persistence, .. }),
-            ) => {
-                *persistence = Node(from.id.node);
-            }
-            (NoiseNode::F64(_) | NoiseNode::F64Operation(_), 4, NoiseNode::Select(node)) => {
-                node.upper_bound = Node(from.id.node);
-            }
-            (NoiseNode::U32(_) | NoiseNode::U32Operation(_), 4, NoiseNode::Turbulence(node)) => {
-                node.roughness = Node(from.id.node);
-            }
-            (NoiseNode::F64(_) | NoiseNode::F64Operation(_), 5, NoiseNode::RigidMulti(node)) => {
-                node.attenuation = Node(from.id.node);
-            }
-            (NoiseNode::F64(_) | NoiseNode::F64Operation(_), 5, NoiseNode::Select(node)) => {
-                node.falloff = Node(from.id.node);
+                source_idx,
+                NoiseNode::Switch(node),
+            ) if source_idx > 0 => {
+                node.source_count = node.source_count.max(source_idx);
             }
             (NoiseNode::ControlPoint(_), to_input, NoiseNode::Curve(node)) => {
                 let control_point_idx = to_input - 1;
@@ -814,7 +3560,10 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                 node.control_point_node_ids[control_point_idx] = Some(from.id.node);
             }
             (
-                NoiseNode::F64(_) | NoiseNode::F64Operation(_),
+                NoiseNode::F64(_)
+                | NoiseNode::F64Operation(_)
+                | NoiseNode::RandomConstant(_)
+                | NoiseNode::Time(_),
                 to_input,
                 NoiseNode::Terrace(node),
             ) => {
@@ -883,7 +3632,25 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
         #[cfg(debug_assertions)]
         ui.label(RichText::new(format!("#{node_id:?}")).color(Color32::DEBUG_COLOR));
 
+        // The header is drawn at the node position, which ties screen space to graph space
+        let pos = snarl.get_node_info(node_id).unwrap().pos;
+        *self.graph_transform = Some((ui.max_rect().min - pos.to_vec2() * scale, scale));
+
+        // Random constants show their value, which depends on the nodes wired into them
+        let random_value = match snarl.get_node(node_id).unwrap() {
+            NoiseNode::RandomConstant(node) => Some(node.eval(snarl)),
+            _ => None,
+        };
+
         let node = snarl.get_node_mut(node_id).unwrap();
+        let mut collapse = None;
+
+        // Custom titles replace the type of node, which remains visible when hovered
+        let title = node.title().map(str::to_owned);
+        let label = |ui: &mut Ui, name: &str| match &title {
+            Some(title) => ui.label(RichText::new(title).strong()).on_hover_text(name),
+            None => ui.label(name),
+        };
 
         ui.set_height(16.0 * scale);
         ui.set_width(128.0 * scale);
@@ -893,49 +3660,124 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                 ui.add_space(20.0 * scale);
                 match node {
                     NoiseNode::Abs(_) => {
-                        ui.label("Abs");
+                        label(ui, "Abs");
                     }
                     NoiseNode::Add(_) => {
-                        ui.label("Add");
+                        label(ui, "Add");
                     }
                     NoiseNode::BasicMulti(node) => {
-                        ui.label("Basic Multi");
+                        label(ui, "Basic Multi");
                         self.source_ty_combo_box(ui, &mut node.source_ty, node_id);
                     }
+                    NoiseNode::BiasGain(_) => {
+                        label(ui, "Bias + Gain");
+                    }
                     NoiseNode::Billow(node) => {
-                        ui.label("Billow");
+                        label(ui, "Billow");
                         self.source_ty_combo_box(ui, &mut node.source_ty, node_id);
                     }
                     NoiseNode::Blend(_) => {
-                        ui.label("Blend");
+                        label(ui, "Blend");
+                    }
+                    NoiseNode::BlueNoise(_) => {
+                        label(ui, "Blue Noise");
+                    }
+                    NoiseNode::Cache(_) => {
+                        label(ui, "Cache");
                     }
                     NoiseNode::Checkerboard(_) => {
-                        ui.label("Checkerboard");
+                        label(ui, "Checkerboard");
                     }
                     NoiseNode::Clamp(_) => {
-                        ui.label("Clamp");
+                        label(ui, "Clamp");
+                    }
+                    NoiseNode::ColorRamp(_) => {
+                        label(ui, "Color Ramp");
+                    }
+                    NoiseNode::CombineRgb(_) => {
+                        label(ui, "Combine RGB");
+                    }
+                    NoiseNode::Comment(node) => {
+                        label(ui, "Comment");
+                        ui.color_edit_button_srgba(&mut node.color);
+                    }
+                    NoiseNode::Compare(node) => {
+                        label(ui, "Compare");
+                        self.compare_ty_combo_box(ui, &mut node.compare_ty, node_id);
+                    }
+                    NoiseNode::Contrast(node) => {
+                        label(ui, "Contrast");
+                        if ui.checkbox(&mut node.clamped, "Clamped").changed() {
+                            self.updated_node_ids.insert(node_id);
+                        }
                     }
                     NoiseNode::ControlPoint(_) => {
-                        ui.label("Control Point");
+                        label(ui, "Control Point");
+                    }
+                    NoiseNode::Crater(_) => {
+                        label(ui, "Crater");
                     }
                     NoiseNode::Curve(node) => {
-                        ui.label("Curve");
+                        label(ui, "Curve");
+                        ui.checkbox(&mut node.editor, "Editor");
 
                         while let Some(None) = node.control_point_node_ids.last() {
                             node.control_point_node_ids.pop();
                         }
                     }
                     NoiseNode::Cylinders(_) => {
-                        ui.label("Cylinders");
+                        label(ui, "Cylinders");
+                    }
+                    NoiseNode::Difference(_) => {
+                        label(ui, "Difference");
                     }
                     NoiseNode::Displace(_) => {
-                        ui.label("Displace");
+                        label(ui, "Displace");
+                    }
+                    NoiseNode::DomainWarp(_) => {
+                        label(ui, "Domain Warp");
+                    }
+                    NoiseNode::Ease(node) => {
+                        label(ui, "Ease");
+                        self.enum_combo_box(ui, 0, &mut node.curve, &EasingCurve::ALL, node_id);
+                    }
+                    NoiseNode::Erosion(node) => {
+                        label(ui, "Erosion");
+                        self.source_ty_combo_box(ui, &mut node.source_ty, node_id);
                     }
                     NoiseNode::Exponent(_) => {
-                        ui.label("Exponent");
+                        label(ui, "Exponent");
+                    }
+                    NoiseNode::FaultLines(_) => {
+                        label(ui, "Fault Lines");
+                    }
+                    NoiseNode::Formula(_) => {
+                        label(ui, "Formula");
+                    }
+                    NoiseNode::GenericFractal(_) => {
+                        label(ui, "Generic Fractal");
+                    }
+                    NoiseNode::Gradient(node) => {
+                        label(ui, "Gradient");
+                        self.gradient_ty_combo_box(ui, &mut node.gradient_ty, node_id);
+                    }
+                    NoiseNode::Group(node) => {
+                        label(ui, "Group");
+
+                        if ui
+                            .small_button(if node.collapsed { "⏵" } else { "⏷" })
+                            .on_hover_text(if node.collapsed { "Expand" } else { "Collapse" })
+                            .clicked()
+                        {
+                            node.collapsed = !node.collapsed;
+                            collapse = Some((node.collapsed, node.node_ids.clone()));
+                        }
+
+                        ui.color_edit_button_srgba(&mut node.color);
+                        ui.weak(format!("{} nodes", node.node_ids.len()));
                     }
                     NoiseNode::F64(node) => {
-                        ui.label("Decimal");
+                        label(ui, "Decimal");
                         ui.add(TextEdit::singleline(&mut node.name).desired_width(50.0 * scale));
 
                         if ui
@@ -953,292 +3795,913 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                     NoiseNode::F64Operation(ConstantOpNode { op_ty, .. })
                     | NoiseNode::Operation(ConstantOpNode { op_ty, .. })
                     | NoiseNode::U32Operation(ConstantOpNode { op_ty, .. }) => {
-                        ui.label(match op_ty {
-                            OpType::Add => "Add",
-                            OpType::Divide => "Divide",
-                            OpType::Multiply => "Multiply",
-                            OpType::Subtract => "Subtract",
-                        });
+                        label(
+                            ui,
+                            match op_ty {
+                                OpType::Add => "Add",
+                                OpType::Divide => "Divide",
+                                OpType::Multiply => "Multiply",
+                                OpType::Subtract => "Subtract",
+                            },
+                        );
                     }
                     NoiseNode::Fbm(node) => {
-                        ui.label("fBm");
+                        label(ui, "fBm");
                         self.source_ty_combo_box(ui, &mut node.source_ty, node_id);
                     }
                     NoiseNode::HybridMulti(node) => {
-                        ui.label("Hybrid Multi");
+                        label(ui, "Hybrid Multi");
+                        self.source_ty_combo_box(ui, &mut node.source_ty, node_id);
+                    }
+                    NoiseNode::ImageSource(node) => {
+                        label(ui, "Image Source");
+                        #[cfg(not(target_arch = "wasm32"))]
+                        if ui
+                            .small_button("📂")
+                            .on_hover_text(if node.file_name.is_empty() {
+                                "Open a grayscale image"
+                            } else {
+                                node.file_name.as_str()
+                            })
+                            .clicked()
+                        {
+                            self.open_image_source(node, node_id);
+                        }
+
+                        self.enum_combo_box(ui, 0, &mut node.tiling, &ImageTiling::ALL, node_id);
+                    }
+                    NoiseNode::Jordan(node) => {
+                        label(ui, "Jordan Turbulence");
                         self.source_ty_combo_box(ui, &mut node.source_ty, node_id);
                     }
                     NoiseNode::Min(_) => {
-                        ui.label("Min");
+                        label(ui, "Min");
                     }
                     NoiseNode::Max(_) => {
-                        ui.label("Max");
+                        label(ui, "Max");
+                    }
+                    NoiseNode::MirrorPoint(node) => {
+                        label(ui, "Mirror Point");
+                        for (label, mirrored) in Self::AXES.into_iter().zip(&mut node.axes) {
+                            if ui.toggle_value(mirrored, label).changed() {
+                                self.updated_node_ids.insert(node_id);
+                            }
+                        }
+                    }
+                    NoiseNode::Mix(_) => {
+                        label(ui, "Mix");
                     }
                     NoiseNode::Multiply(_) => {
-                        ui.label("Multiply");
+                        label(ui, "Multiply");
                     }
                     NoiseNode::Negate(_) => {
-                        ui.label("Negate");
+                        label(ui, "Negate");
+                    }
+                    NoiseNode::Normal(node) => {
+                        label(ui, "Normal");
+                        self.normal_component_combo_box(ui, &mut node.component, node_id);
+                    }
+                    NoiseNode::Normalize(node) => {
+                        label(ui, "Normalize");
+                        if ui.checkbox(&mut node.estimated, "Estimated").changed() {
+                            self.updated_node_ids.insert(node_id);
+                        }
                     }
                     NoiseNode::OpenSimplex(_) => {
-                        ui.label("Open Simplex");
+                        label(ui, "Open Simplex");
                     }
                     NoiseNode::Perlin(_) => {
-                        ui.label("Perlin");
+                        label(ui, "Perlin");
                     }
                     NoiseNode::PerlinSurflet(_) => {
-                        ui.label("Perlin Surflet");
+                        label(ui, "Perlin Surflet");
                     }
                     NoiseNode::Power(_) => {
-                        ui.label("Power");
+                        label(ui, "Power");
+                    }
+                    NoiseNode::RandomConstant(_) => {
+                        label(ui, "Random Constant");
+
+                        if let Some(value) = random_value {
+                            ui.weak(format!("{value:.2}"));
+                        }
+                    }
+                    NoiseNode::Remap(node) => {
+                        label(ui, "Remap");
+                        if ui.checkbox(&mut node.clamped, "Clamped").changed() {
+                            self.updated_node_ids.insert(node_id);
+                        }
                     }
                     NoiseNode::RigidMulti(node) => {
-                        ui.label("Rigid Multi");
+                        label(ui, "Rigid Multi");
                         self.source_ty_combo_box(ui, &mut node.source_ty, node_id);
                     }
+                    NoiseNode::RotateAxis(_) => {
+                        label(ui, "Rotate Axis");
+                    }
                     NoiseNode::RotatePoint(_) => {
-                        ui.label("Rotate Point");
+                        label(ui, "Rotate Point");
                     }
                     NoiseNode::ScaleBias(_) => {
-                        ui.label("Scale + Bias");
+                        label(ui, "Scale + Bias");
                     }
                     NoiseNode::ScalePoint(_) => {
-                        ui.label("Scale Point");
+                        label(ui, "Scale Point");
+                    }
+                    NoiseNode::Script(_) => {
+                        label(ui, "Script");
                     }
                     NoiseNode::Select(_) => {
-                        ui.label("Select");
+                        label(ui, "Select");
                     }
                     NoiseNode::Simplex(_) => {
-                        ui.label("Simplex");
+                        label(ui, "Simplex");
+                    }
+                    NoiseNode::Slope(_) => {
+                        label(ui, "Slope");
+                    }
+                    NoiseNode::Subgraph(_) => {
+                        label(ui, "Subgraph");
+                    }
+                    NoiseNode::SubgraphInput(node) => {
+                        label(ui, "Subgraph Input");
+                        ui.weak(format!("{}", node.index + 1));
+                    }
+                    NoiseNode::SubgraphOutput(_) => {
+                        label(ui, "Subgraph Output");
                     }
                     NoiseNode::SuperSimplex(_) => {
-                        ui.label("Super Simplex");
+                        label(ui, "Super Simplex");
+                    }
+                    NoiseNode::Swiss(node) => {
+                        label(ui, "Swiss Turbulence");
+                        self.source_ty_combo_box(ui, &mut node.source_ty, node_id);
+                    }
+                    NoiseNode::Switch(_) => {
+                        label(ui, "Switch");
+                    }
+                    NoiseNode::Swizzle(_) => {
+                        label(ui, "Swizzle");
                     }
                     NoiseNode::Terrace(node) => {
-                        ui.label("Terrace");
+                        label(ui, "Terrace");
+                        self.enum_combo_box(ui, 0, &mut node.easing, &TerraceEasing::ALL, node_id);
                         if ui.checkbox(&mut node.inverted, "Inverted").changed() {
                             self.updated_node_ids.insert(node_id);
                         }
+                        ui.checkbox(&mut node.editor, "Editor");
+
+                        while let Some(None) = node.control_point_node_ids.last() {
+                            node.control_point_node_ids.pop();
+                        }
+                    }
+                    NoiseNode::TilePoint(node) => {
+                        label(ui, "Tile Point");
+                        if ui.checkbox(&mut node.blended, "Blended").changed() {
+                            self.updated_node_ids.insert(node_id);
+                        }
+                    }
+                    NoiseNode::Time(_) => {
+                        label(ui, "Time");
+                    }
+                    NoiseNode::TranslatePoint(_) => {
+                        label(ui, "Translate Point");
+                    }
+                    NoiseNode::Turbulence(node) => {
+                        label(ui, "Turbulence");
+                        self.source_ty_combo_box(ui, &mut node.source_ty, node_id);
+                    }
+                    NoiseNode::U32(node) => {
+                        label(ui, "Integer");
+                        ui.add(TextEdit::singleline(&mut node.name).desired_width(50.0 * scale));
+
+                        if ui.add(DragValue::new(&mut node.value)).changed() {
+                            self.updated_node_ids.insert(node_id);
+                        }
+                    }
+                    NoiseNode::Value(_) => {
+                        label(ui, "Value");
+                    }
+                    NoiseNode::Vector(_) => {
+                        label(ui, "Vector");
+                    }
+                    NoiseNode::WarpedFbm(node) => {
+                        label(ui, "Warped fBm");
+                        self.source_ty_combo_box(ui, &mut node.source_ty, node_id);
+                    }
+                    NoiseNode::Worley(node) => {
+                        label(ui, "Worley");
+                        self.distance_fn_combo_box(ui, &mut node.distance_fn, node_id);
+                        self.return_ty_combo_box(ui, &mut node.return_ty, node_id);
+                    }
+                }
+
+                if let Some(image) = node.image_mut() {
+                    ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+                        let mut enabled = !image.disabled;
+                        if ui
+                            .checkbox(&mut enabled, "")
+                            .on_hover_text("Preview")
+                            .changed()
+                        {
+                            image.disabled = !enabled;
+
+                            // Re-enabled previews are rendered from scratch
+                            if enabled {
+                                self.updated_node_ids.insert(node_id);
+                            }
+                        }
+                    });
+                }
+            },
+        );
+
+        // Collapsing a group collapses each of its members
+        if let Some((collapsed, node_ids)) = collapse {
+            for node_id in node_ids {
+                if snarl.get_node(node_id).is_some() {
+                    snarl.open_node(node_id, !collapsed);
+                }
+            }
+        }
+    }
+
+    fn inputs(&mut self, node: &NoiseNode) -> usize {
+        match node {
+            NoiseNode::Comment(_)
+            | NoiseNode::F64(_)
+            | NoiseNode::Group(_)
+            | NoiseNode::SubgraphInput(_)
+            | NoiseNode::Time(_)
+            | NoiseNode::U32(_) => 0,
+            NoiseNode::Abs(_)
+            | NoiseNode::BlueNoise(_)
+            | NoiseNode::Cache(_)
+            | NoiseNode::Checkerboard(_)
+            | NoiseNode::ColorRamp(_)
+            | NoiseNode::Cylinders(_)
+            | NoiseNode::Ease(_)
+            | NoiseNode::ImageSource(_)
+            | NoiseNode::MirrorPoint(_)
+            | NoiseNode::Normalize(_)
+            | NoiseNode::OpenSimplex(_)
+            | NoiseNode::Perlin(_)
+            | NoiseNode::PerlinSurflet(_)
+            | NoiseNode::Negate(_)
+            | NoiseNode::Simplex(_)
+            | NoiseNode::SubgraphOutput(_)
+            | NoiseNode::SuperSimplex(_)
+            | NoiseNode::Swizzle(_)
+            | NoiseNode::Value(_) => 1,
+            NoiseNode::Add(_)
+            | NoiseNode::ControlPoint(_)
+            | NoiseNode::Difference(_)
+            | NoiseNode::Exponent(_)
+            | NoiseNode::F64Operation(_)
+            | NoiseNode::Gradient(_)
+            | NoiseNode::Min(_)
+            | NoiseNode::Max(_)
+            | NoiseNode::Multiply(_)
+            | NoiseNode::Operation(_)
+            | NoiseNode::Power(_)
+            | NoiseNode::Slope(_)
+            | NoiseNode::U32Operation(_)
+            | NoiseNode::Worley(_) => 2,
+            NoiseNode::BiasGain(_)
+            | NoiseNode::Blend(_)
+            | NoiseNode::Clamp(_)
+            | NoiseNode::CombineRgb(_)
+            | NoiseNode::Compare(_)
+            | NoiseNode::Contrast(_)
+            | NoiseNode::FaultLines(_)
+            | NoiseNode::Mix(_)
+            | NoiseNode::Normal(_)
+            | NoiseNode::ScaleBias(_) => 3,
+            NoiseNode::DomainWarp(_) | NoiseNode::RandomConstant(_) | NoiseNode::Vector(_) => 4,
+            NoiseNode::BasicMulti(_)
+            | NoiseNode::Billow(_)
+            | NoiseNode::Crater(_)
+            | NoiseNode::Displace(_)
+            | NoiseNode::Fbm(_)
+            | NoiseNode::GenericFractal(_)
+            | NoiseNode::HybridMulti(_)
+            | NoiseNode::Jordan(_)
+            | NoiseNode::Remap(_)
+            | NoiseNode::RotateAxis(_)
+            | NoiseNode::Swiss(_)
+            | NoiseNode::TilePoint(_)
+            | NoiseNode::Turbulence(_) => 5,
+            NoiseNode::Erosion(_)
+            | NoiseNode::RigidMulti(_)
+            | NoiseNode::RotatePoint(_)
+            | NoiseNode::ScalePoint(_)
+            | NoiseNode::Select(_)
+            | NoiseNode::TranslatePoint(_) => 6,
+            NoiseNode::WarpedFbm(_) => 7,
+            NoiseNode::Subgraph(node) => node.input_count,
+            NoiseNode::Formula(node) => node.source_count + 1,
+            NoiseNode::Script(node) => node.source_count + 1,
+            NoiseNode::Switch(node) => node.source_count + 2,
+            NoiseNode::Curve(node) => {
+                (node.control_point_node_ids.len()
+                    + node.control_point_node_ids.iter().all(Option::is_some) as usize)
+                    .max(4usize.saturating_sub(node.points.len()))
+                    + 1
+            }
+            NoiseNode::Terrace(node) => {
+                (node.control_point_node_ids.len()
+                    + node.control_point_node_ids.iter().all(Option::is_some) as usize)
+                    .max(2usize.saturating_sub(node.points.len()))
+                    + 1
+            }
+        }
+    }
 
-                        while let Some(None) = node.control_point_node_ids.last() {
-                            node.control_point_node_ids.pop();
-                        }
-                    }
-                    NoiseNode::TranslatePoint(_) => {
-                        ui.label("Translate Point");
-                    }
-                    NoiseNode::Turbulence(node) => {
-                        ui.label("Turbulence");
-                        self.source_ty_combo_box(ui, &mut node.source_ty, node_id);
-                    }
-                    NoiseNode::U32(node) => {
-                        ui.label("Integer");
-                        ui.add(TextEdit::singleline(&mut node.name).desired_width(50.0 * scale));
+    fn outputs(&mut self, node: &NoiseNode) -> usize {
+        match node {
+            NoiseNode::Comment(_) | NoiseNode::Group(_) | NoiseNode::SubgraphOutput(_) => 0,
+            _ => 1,
+        }
+    }
 
-                        if ui.add(DragValue::new(&mut node.value)).changed() {
-                            self.updated_node_ids.insert(node_id);
-                        }
-                    }
-                    NoiseNode::Value(_) => {
-                        ui.label("Value");
-                    }
-                    NoiseNode::Worley(node) => {
-                        ui.label("Worley");
-                        self.distance_fn_combo_box(ui, &mut node.distance_fn, node_id);
-                        self.return_ty_combo_box(ui, &mut node.return_ty, node_id);
-                    }
+    fn show_input(
+        &mut self,
+        pin: &InPin,
+        ui: &mut Ui,
+        scale: f32,
+        snarl: &mut Snarl<NoiseNode>,
+    ) -> PinInfo {
+        // TODO: This comment is inaccurate and the code should be moved to disconnect
+        // and drop_inputs/drop_outputs
+        // Handle disconnections by resetting node pins to the value of the previous node
+        // This happens when you right-click on a wire (snarl does not tell use about that)
+        if pin.remotes.is_empty() {
+            match (pin.id.input, snarl.get_node(pin.id.node).unwrap()) {
+                (
+                    0,
+                    &NoiseNode::BasicMulti(FractalNode {
+                        seed: Node(node_id),
+                        ..
+                    })
+                    | &NoiseNode::Billow(FractalNode {
+                        seed: Node(node_id),
+                        ..
+                    })
+                    | &NoiseNode::Fbm(FractalNode {
+                        seed: Node(node_id),
+                        ..
+                    })
+                    | &NoiseNode::HybridMulti(FractalNode {
+                        seed: Node(node_id),
+                        ..
+                    })
+                    | &NoiseNode::Jordan(FractalNode {
+                        seed: Node(node_id),
+                        ..
+                    })
+                    | &NoiseNode::Swiss(FractalNode {
+                        seed: Node(node_id),
+                        ..
+                    }),
+                ) => {
+                    snarl
+                        .get_node_mut(pin.id.node)
+                        .and_then(NoiseNode::as_fractal_mut)
+                        .unwrap()
+                        .seed = Value(snarl.get_node(node_id).unwrap().eval_u32(snarl));
+                    NoiseNode::propagate_tuple_from_u32_op(node_id, snarl);
+                }
+                (
+                    0,
+                    &NoiseNode::Checkerboard(CheckerboardNode {
+                        size: Node(node_id),
+                        ..
+                    }),
+                ) => {
+                    snarl
+                        .get_node_mut(pin.id.node)
+                        .and_then(NoiseNode::as_checkerboard_mut)
+                        .unwrap()
+                        .size = Value(snarl.get_node(node_id).unwrap().eval_u32(snarl));
+                    NoiseNode::propagate_tuple_from_u32_op(node_id, snarl);
+                }
+                (
+                    0,
+                    &NoiseNode::ControlPoint(ControlPointNode {
+                        input: Node(node_id),
+                        ..
+                    }),
+                ) => {
+                    snarl
+                        .get_node_mut(pin.id.node)
+                        .and_then(NoiseNode::as_control_point_mut)
+                        .unwrap()
+                        .input = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                    NoiseNode::propagate_tuple_from_f64_op(node_id, snarl);
+                }
+                (
+                    0,
+                    &NoiseNode::Cylinders(CylindersNode {
+                        frequency: Node(node_id),
+                        ..
+                    }),
+                ) => {
+                    snarl
+                        .get_node_mut(pin.id.node)
+                        .and_then(NoiseNode::as_cylinders_mut)
+                        .unwrap()
+                        .frequency = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                    NoiseNode::propagate_tuple_from_f64_op(node_id, snarl);
+                }
+                (
+                    0,
+                    &NoiseNode::Gradient(GradientNode {
+                        angle: Node(node_id),
+                        ..
+                    }),
+                ) => {
+                    snarl
+                        .get_node_mut(pin.id.node)
+                        .and_then(NoiseNode::as_gradient_mut)
+                        .unwrap()
+                        .angle = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                    NoiseNode::propagate_tuple_from_f64_op(node_id, snarl);
+                }
+                (
+                    0,
+                    &NoiseNode::BlueNoise(GeneratorNode {
+                        seed: Node(node_id),
+                        ..
+                    })
+                    | &NoiseNode::OpenSimplex(GeneratorNode {
+                        seed: Node(node_id),
+                        ..
+                    })
+                    | &NoiseNode::Perlin(GeneratorNode {
+                        seed: Node(node_id),
+                        ..
+                    })
+                    | &NoiseNode::PerlinSurflet(GeneratorNode {
+                        seed: Node(node_id),
+                        ..
+                    })
+                    | &NoiseNode::Simplex(GeneratorNode {
+                        seed: Node(node_id),
+                        ..
+                    })
+                    | &NoiseNode::SuperSimplex(GeneratorNode {
+                        seed: Node(node_id),
+                        ..
+                    })
+                    | &NoiseNode::Value(GeneratorNode {
+                        seed: Node(node_id),
+                        ..
+                    }),
+                ) => {
+                    snarl
+                        .get_node_mut(pin.id.node)
+                        .and_then(NoiseNode::as_generator_mut)
+                        .unwrap()
+                        .seed = Value(snarl.get_node(node_id).unwrap().eval_u32(snarl));
+                    NoiseNode::propagate_tuple_from_u32_op(node_id, snarl);
+                }
+                (
+                    0,
+                    &NoiseNode::Erosion(ErosionNode {
+                        seed: Node(node_id),
+                        ..
+                    }),
+                ) => {
+                    snarl
+                        .get_node_mut(pin.id.node)
+                        .and_then(NoiseNode::as_erosion_mut)
+                        .unwrap()
+                        .seed = Value(snarl.get_node(node_id).unwrap().eval_u32(snarl));
+                    NoiseNode::propagate_tuple_from_u32_op(node_id, snarl);
+                }
+                (
+                    0,
+                    &NoiseNode::ImageSource(ImageSourceNode {
+                        size: Node(node_id),
+                        ..
+                    }),
+                ) => {
+                    snarl
+                        .get_node_mut(pin.id.node)
+                        .and_then(NoiseNode::as_image_source_mut)
+                        .unwrap()
+                        .size = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                    NoiseNode::propagate_tuple_from_f64_op(node_id, snarl);
+                }
+                (
+                    0,
+                    &NoiseNode::RandomConstant(RandomConstantNode {
+                        seed: Node(node_id),
+                        ..
+                    }),
+                ) => {
+                    snarl
+                        .get_node_mut(pin.id.node)
+                        .and_then(NoiseNode::as_random_constant_mut)
+                        .unwrap()
+                        .seed = Value(snarl.get_node(node_id).unwrap().eval_u32(snarl));
+                    NoiseNode::propagate_tuple_from_u32_op(node_id, snarl);
+                }
+                (
+                    0,
+                    &NoiseNode::RigidMulti(RigidFractalNode {
+                        seed: Node(node_id),
+                        ..
+                    }),
+                ) => {
+                    snarl
+                        .get_node_mut(pin.id.node)
+                        .and_then(NoiseNode::as_rigid_fractal_mut)
+                        .unwrap()
+                        .seed = Value(snarl.get_node(node_id).unwrap().eval_u32(snarl));
+                    NoiseNode::propagate_tuple_from_u32_op(node_id, snarl);
+                }
+                (
+                    0,
+                    &NoiseNode::FaultLines(FaultLinesNode {
+                        seed: Node(node_id),
+                        ..
+                    }),
+                ) => {
+                    snarl
+                        .get_node_mut(pin.id.node)
+                        .and_then(NoiseNode::as_fault_lines_mut)
+                        .unwrap()
+                        .seed = Value(snarl.get_node(node_id).unwrap().eval_u32(snarl));
+                    NoiseNode::propagate_tuple_from_u32_op(node_id, snarl);
+                }
+                (
+                    0,
+                    &NoiseNode::Switch(SwitchNode {
+                        selector: Node(node_id),
+                        ..
+                    }),
+                ) => {
+                    snarl
+                        .get_node_mut(pin.id.node)
+                        .and_then(NoiseNode::as_switch_mut)
+                        .unwrap()
+                        .selector = Value(snarl.get_node(node_id).unwrap().eval_u32(snarl));
+                    NoiseNode::propagate_tuple_from_u32_op(node_id, snarl);
+                }
+                (
+                    0,
+                    &NoiseNode::WarpedFbm(WarpedFbmNode {
+                        seed: Node(node_id),
+                        ..
+                    }),
+                ) => {
+                    snarl
+                        .get_node_mut(pin.id.node)
+                        .and_then(NoiseNode::as_warped_fbm_mut)
+                        .unwrap()
+                        .seed = Value(snarl.get_node(node_id).unwrap().eval_u32(snarl));
+                    NoiseNode::propagate_tuple_from_u32_op(node_id, snarl);
+                }
+                (
+                    0,
+                    &NoiseNode::Worley(WorleyNode {
+                        seed: Node(node_id),
+                        ..
+                    }),
+                ) => {
+                    snarl
+                        .get_node_mut(pin.id.node)
+                        .and_then(NoiseNode::as_worley_mut)
+                        .unwrap()
+                        .seed = Value(snarl.get_node(node_id).unwrap().eval_u32(snarl));
+                    NoiseNode::propagate_tuple_from_u32_op(node_id, snarl);
                 }
-            },
-        );
-    }
-
-    fn inputs(&mut self, node: &NoiseNode) -> usize {
-        match node {
-            NoiseNode::F64(_) | NoiseNode::U32(_) => 0,
-            NoiseNode::Abs(_)
-            | NoiseNode::Checkerboard(_)
-            | NoiseNode::Cylinders(_)
-            | NoiseNode::OpenSimplex(_)
-            | NoiseNode::Perlin(_)
-            | NoiseNode::PerlinSurflet(_)
-            | NoiseNode::Negate(_)
-            | NoiseNode::Simplex(_)
-            | NoiseNode::SuperSimplex(_)
-            | NoiseNode::Value(_) => 1,
-            NoiseNode::Add(_)
-            | NoiseNode::ControlPoint(_)
-            | NoiseNode::Exponent(_)
-            | NoiseNode::F64Operation(_)
-            | NoiseNode::Min(_)
-            | NoiseNode::Max(_)
-            | NoiseNode::Multiply(_)
-            | NoiseNode::Operation(_)
-            | NoiseNode::Power(_)
-            | NoiseNode::U32Operation(_)
-            | NoiseNode::Worley(_) => 2,
-            NoiseNode::Blend(_) | NoiseNode::Clamp(_) | NoiseNode::ScaleBias(_) => 3,
-            NoiseNode::BasicMulti(_)
-            | NoiseNode::Billow(_)
-            | NoiseNode::Displace(_)
-            | NoiseNode::Fbm(_)
-            | NoiseNode::HybridMulti(_)
-            | NoiseNode::RotatePoint(_)
-            | NoiseNode::ScalePoint(_)
-            | NoiseNode::TranslatePoint(_)
-            | NoiseNode::Turbulence(_) => 5,
-            NoiseNode::RigidMulti(_) | NoiseNode::Select(_) => 6,
-            NoiseNode::Curve(node) => {
-                (node.control_point_node_ids.len()
-                    + node.control_point_node_ids.iter().all(Option::is_some) as usize)
-                    .max(4)
-                    + 1
-            }
-            NoiseNode::Terrace(node) => {
-                (node.control_point_node_ids.len()
-                    + node.control_point_node_ids.iter().all(Option::is_some) as usize)
-                    .max(2)
-                    + 1
-            }
-        }
-    }
+                (0 | 1, NoiseNode::F64Operation(node))
+                    if node.inputs[pin.id.input].is_node_id() =>
+                {
+                    let node_id = node.inputs[pin.id.input].as_node_id().unwrap();
+                    snarl
+                        .get_node_mut(pin.id.node)
+                        .and_then(NoiseNode::as_const_op_f64_mut)
+                        .unwrap()
+                        .inputs[pin.id.input] =
+                        Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
 
-    fn outputs(&mut self, _node: &NoiseNode) -> usize {
-        1
-    }
+                    NoiseNode::propagate_tuple_from_f64_op(node_id, snarl);
+                    NoiseNode::propagate_tuple_from_f64_op(pin.id.node, snarl);
+                }
+                (0 | 1, NoiseNode::Operation(node)) if node.inputs[pin.id.input].is_node_id() => {
+                    snarl
+                        .get_node_mut(pin.id.node)
+                        .and_then(NoiseNode::as_const_op_tuple_mut)
+                        .unwrap()
+                        .inputs[pin.id.input] = Default::default();
+                }
+                (0 | 1, NoiseNode::U32Operation(node))
+                    if node.inputs[pin.id.input].is_node_id() =>
+                {
+                    let node_id = node.inputs[pin.id.input].as_node_id().unwrap();
+                    snarl
+                        .get_node_mut(pin.id.node)
+                        .and_then(NoiseNode::as_const_op_u32_mut)
+                        .unwrap()
+                        .inputs[pin.id.input] =
+                        Value(snarl.get_node(node_id).unwrap().eval_u32(snarl));
 
-    fn show_input(
-        &mut self,
-        pin: &InPin,
-        ui: &mut Ui,
-        scale: f32,
-        snarl: &mut Snarl<NoiseNode>,
-    ) -> PinInfo {
-        // TODO: This comment is inaccurate and the code should be moved to disconnect
-        // and drop_inputs/drop_outputs
-        // Handle disconnections by resetting node pins to the value of the previous node
-        // This happens when you right-click on a wire (snarl does not tell use about that)
-        if pin.remotes.is_empty() {
-            match (pin.id.input, snarl.get_node(pin.id.node).unwrap()) {
+                    NoiseNode::propagate_tuple_from_u32_op(node_id, snarl);
+                    NoiseNode::propagate_tuple_from_u32_op(pin.id.node, snarl);
+                }
                 (
-                    0,
+                    1,
                     &NoiseNode::BasicMulti(FractalNode {
-                        seed: Node(node_id),
+                        octaves: Node(node_id),
                         ..
                     })
                     | &NoiseNode::Billow(FractalNode {
-                        seed: Node(node_id),
+                        octaves: Node(node_id),
                         ..
                     })
                     | &NoiseNode::Fbm(FractalNode {
-                        seed: Node(node_id),
+                        octaves: Node(node_id),
                         ..
                     })
                     | &NoiseNode::HybridMulti(FractalNode {
-                        seed: Node(node_id),
+                        octaves: Node(node_id),
+                        ..
+                    })
+                    | &NoiseNode::Jordan(FractalNode {
+                        octaves: Node(node_id),
+                        ..
+                    })
+                    | &NoiseNode::Swiss(FractalNode {
+                        octaves: Node(node_id),
+                        ..
+                    }),
+                ) => {
+                    snarl
+                        .get_node_mut(pin.id.node)
+                        .and_then(NoiseNode::as_fractal_mut)
+                        .unwrap()
+                        .octaves = Value(snarl.get_node(node_id).unwrap().eval_u32(snarl));
+                    NoiseNode::propagate_tuple_from_u32_op(node_id, snarl);
+                }
+                (
+                    1,
+                    &NoiseNode::GenericFractal(GenericFractalNode {
+                        octaves: Node(node_id),
+                        ..
+                    }),
+                ) => {
+                    snarl
+                        .get_node_mut(pin.id.node)
+                        .and_then(NoiseNode::as_generic_fractal_mut)
+                        .unwrap()
+                        .octaves = Value(snarl.get_node(node_id).unwrap().eval_u32(snarl));
+                    NoiseNode::propagate_tuple_from_u32_op(node_id, snarl);
+                }
+                (
+                    1,
+                    &NoiseNode::BiasGain(BiasGainNode {
+                        bias: Node(node_id),
+                        ..
+                    }),
+                ) => {
+                    snarl
+                        .get_node_mut(pin.id.node)
+                        .and_then(NoiseNode::as_bias_gain_mut)
+                        .unwrap()
+                        .bias = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                    NoiseNode::propagate_tuple_from_f64_op(node_id, snarl);
+                }
+                (
+                    1,
+                    &NoiseNode::Clamp(ClampNode {
+                        lower_bound: Node(node_id),
+                        ..
+                    }),
+                ) => {
+                    snarl
+                        .get_node_mut(pin.id.node)
+                        .and_then(NoiseNode::as_clamp_mut)
+                        .unwrap()
+                        .lower_bound = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                    NoiseNode::propagate_tuple_from_f64_op(node_id, snarl);
+                }
+                (
+                    1,
+                    &NoiseNode::Contrast(ContrastNode {
+                        contrast: Node(node_id),
+                        ..
+                    }),
+                ) => {
+                    snarl
+                        .get_node_mut(pin.id.node)
+                        .and_then(NoiseNode::as_contrast_mut)
+                        .unwrap()
+                        .contrast = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                    NoiseNode::propagate_tuple_from_f64_op(node_id, snarl);
+                }
+                (
+                    1,
+                    &NoiseNode::ControlPoint(ControlPointNode {
+                        output: Node(node_id),
+                        ..
+                    }),
+                ) => {
+                    snarl
+                        .get_node_mut(pin.id.node)
+                        .and_then(NoiseNode::as_control_point_mut)
+                        .unwrap()
+                        .output = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                    NoiseNode::propagate_tuple_from_f64_op(node_id, snarl);
+                }
+                (
+                    1,
+                    &NoiseNode::Exponent(ExponentNode {
+                        exponent: Node(node_id),
+                        ..
+                    }),
+                ) => {
+                    snarl
+                        .get_node_mut(pin.id.node)
+                        .and_then(NoiseNode::as_exponent_mut)
+                        .unwrap()
+                        .exponent = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                    NoiseNode::propagate_tuple_from_f64_op(node_id, snarl);
+                }
+                (
+                    1,
+                    &NoiseNode::FaultLines(FaultLinesNode {
+                        iterations: Node(node_id),
+                        ..
+                    }),
+                ) => {
+                    snarl
+                        .get_node_mut(pin.id.node)
+                        .and_then(NoiseNode::as_fault_lines_mut)
+                        .unwrap()
+                        .iterations = Value(snarl.get_node(node_id).unwrap().eval_u32(snarl));
+                    NoiseNode::propagate_tuple_from_u32_op(node_id, snarl);
+                }
+                (
+                    1,
+                    &NoiseNode::Gradient(GradientNode {
+                        radius: Node(node_id),
+                        ..
+                    }),
+                ) => {
+                    snarl
+                        .get_node_mut(pin.id.node)
+                        .and_then(NoiseNode::as_gradient_mut)
+                        .unwrap()
+                        .radius = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                    NoiseNode::propagate_tuple_from_f64_op(node_id, snarl);
+                }
+                (
+                    1,
+                    &NoiseNode::Normal(NormalNode {
+                        epsilon: Node(node_id),
+                        ..
+                    }),
+                ) => {
+                    snarl
+                        .get_node_mut(pin.id.node)
+                        .and_then(NoiseNode::as_normal_mut)
+                        .unwrap()
+                        .epsilon = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                    NoiseNode::propagate_tuple_from_f64_op(node_id, snarl);
+                }
+                (
+                    1,
+                    &NoiseNode::RandomConstant(RandomConstantNode {
+                        index: Node(node_id),
+                        ..
+                    }),
+                ) => {
+                    snarl
+                        .get_node_mut(pin.id.node)
+                        .and_then(NoiseNode::as_random_constant_mut)
+                        .unwrap()
+                        .index = Value(snarl.get_node(node_id).unwrap().eval_u32(snarl));
+                    NoiseNode::propagate_tuple_from_u32_op(node_id, snarl);
+                }
+                (
+                    1,
+                    &NoiseNode::Remap(RemapNode {
+                        in_min: Node(node_id),
+                        ..
+                    }),
+                ) => {
+                    snarl
+                        .get_node_mut(pin.id.node)
+                        .and_then(NoiseNode::as_remap_mut)
+                        .unwrap()
+                        .in_min = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                    NoiseNode::propagate_tuple_from_f64_op(node_id, snarl);
+                }
+                (
+                    1,
+                    &NoiseNode::Erosion(ErosionNode {
+                        octaves: Node(node_id),
                         ..
                     }),
                 ) => {
                     snarl
                         .get_node_mut(pin.id.node)
-                        .and_then(NoiseNode::as_fractal_mut)
+                        .and_then(NoiseNode::as_erosion_mut)
                         .unwrap()
-                        .seed = Value(snarl.get_node(node_id).unwrap().eval_u32(snarl));
+                        .octaves = Value(snarl.get_node(node_id).unwrap().eval_u32(snarl));
                     NoiseNode::propagate_tuple_from_u32_op(node_id, snarl);
                 }
                 (
-                    0,
-                    &NoiseNode::Checkerboard(CheckerboardNode {
-                        size: Node(node_id),
+                    1,
+                    &NoiseNode::RigidMulti(RigidFractalNode {
+                        octaves: Node(node_id),
                         ..
                     }),
                 ) => {
                     snarl
                         .get_node_mut(pin.id.node)
-                        .and_then(NoiseNode::as_checkerboard_mut)
+                        .and_then(NoiseNode::as_rigid_fractal_mut)
                         .unwrap()
-                        .size = Value(snarl.get_node(node_id).unwrap().eval_u32(snarl));
+                        .octaves = Value(snarl.get_node(node_id).unwrap().eval_u32(snarl));
                     NoiseNode::propagate_tuple_from_u32_op(node_id, snarl);
                 }
                 (
-                    0,
-                    &NoiseNode::ControlPoint(ControlPointNode {
-                        input: Node(node_id),
+                    1,
+                    &NoiseNode::ScaleBias(ScaleBiasNode {
+                        scale: Node(node_id),
                         ..
                     }),
                 ) => {
                     snarl
                         .get_node_mut(pin.id.node)
-                        .and_then(NoiseNode::as_control_point_mut)
+                        .and_then(NoiseNode::as_scale_bias_mut)
                         .unwrap()
-                        .input = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                        .scale = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
                     NoiseNode::propagate_tuple_from_f64_op(node_id, snarl);
                 }
                 (
-                    0,
-                    &NoiseNode::Cylinders(CylindersNode {
-                        frequency: Node(node_id),
+                    1,
+                    &NoiseNode::Slope(SlopeNode {
+                        epsilon: Node(node_id),
                         ..
                     }),
                 ) => {
                     snarl
                         .get_node_mut(pin.id.node)
-                        .and_then(NoiseNode::as_cylinders_mut)
+                        .and_then(NoiseNode::as_slope_mut)
                         .unwrap()
-                        .frequency = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                        .epsilon = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
                     NoiseNode::propagate_tuple_from_f64_op(node_id, snarl);
                 }
                 (
-                    0,
-                    &NoiseNode::OpenSimplex(GeneratorNode {
-                        seed: Node(node_id),
-                        ..
-                    })
-                    | &NoiseNode::Perlin(GeneratorNode {
-                        seed: Node(node_id),
-                        ..
-                    })
-                    | &NoiseNode::PerlinSurflet(GeneratorNode {
-                        seed: Node(node_id),
-                        ..
-                    })
-                    | &NoiseNode::Simplex(GeneratorNode {
-                        seed: Node(node_id),
-                        ..
-                    })
-                    | &NoiseNode::SuperSimplex(GeneratorNode {
-                        seed: Node(node_id),
-                        ..
-                    })
-                    | &NoiseNode::Value(GeneratorNode {
+                    1,
+                    &NoiseNode::Crater(CraterNode {
                         seed: Node(node_id),
                         ..
                     }),
                 ) => {
                     snarl
                         .get_node_mut(pin.id.node)
-                        .and_then(NoiseNode::as_generator_mut)
+                        .and_then(NoiseNode::as_crater_mut)
                         .unwrap()
                         .seed = Value(snarl.get_node(node_id).unwrap().eval_u32(snarl));
                     NoiseNode::propagate_tuple_from_u32_op(node_id, snarl);
                 }
                 (
-                    0,
-                    &NoiseNode::RigidMulti(RigidFractalNode {
+                    1,
+                    &NoiseNode::Turbulence(TurbulenceNode {
                         seed: Node(node_id),
                         ..
                     }),
                 ) => {
                     snarl
                         .get_node_mut(pin.id.node)
-                        .and_then(NoiseNode::as_rigid_fractal_mut)
+                        .and_then(NoiseNode::as_turbulence_mut)
                         .unwrap()
                         .seed = Value(snarl.get_node(node_id).unwrap().eval_u32(snarl));
                     NoiseNode::propagate_tuple_from_u32_op(node_id, snarl);
                 }
                 (
-                    0,
+                    1,
+                    &NoiseNode::WarpedFbm(WarpedFbmNode {
+                        octaves: Node(node_id),
+                        ..
+                    }),
+                ) => {
+                    snarl
+                        .get_node_mut(pin.id.node)
+                        .and_then(NoiseNode::as_warped_fbm_mut)
+                        .unwrap()
+                        .octaves = Value(snarl.get_node(node_id).unwrap().eval_u32(snarl));
+                    NoiseNode::propagate_tuple_from_u32_op(node_id, snarl);
+                }
+                (
+                    1,
                     &NoiseNode::Worley(WorleyNode {
-                        seed: Node(node_id),
+                        frequency: Node(node_id),
                         ..
                     }),
                 ) => {
@@ -1246,60 +4709,94 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                         .get_node_mut(pin.id.node)
                         .and_then(NoiseNode::as_worley_mut)
                         .unwrap()
-                        .seed = Value(snarl.get_node(node_id).unwrap().eval_u32(snarl));
-                    NoiseNode::propagate_tuple_from_u32_op(node_id, snarl);
+                        .frequency = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                    NoiseNode::propagate_tuple_from_f64_op(node_id, snarl);
                 }
-                (0 | 1, NoiseNode::F64Operation(node))
-                    if node.inputs[pin.id.input].is_node_id() =>
-                {
-                    let node_id = node.inputs[pin.id.input].as_node_id().unwrap();
+                (
+                    1..=4,
+                    NoiseNode::RotatePoint(node)
+                    | NoiseNode::ScalePoint(node)
+                    | NoiseNode::TranslatePoint(node),
+                ) if node.axes[pin.id.input - 1].is_node_id() => {
+                    let node_id = node.axes[pin.id.input - 1].as_node_id().unwrap();
                     snarl
                         .get_node_mut(pin.id.node)
-                        .and_then(NoiseNode::as_const_op_f64_mut)
+                        .and_then(NoiseNode::as_transform_mut)
                         .unwrap()
-                        .inputs[pin.id.input] =
+                        .axes[pin.id.input - 1] =
                         Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
-
                     NoiseNode::propagate_tuple_from_f64_op(node_id, snarl);
-                    NoiseNode::propagate_tuple_from_f64_op(pin.id.node, snarl);
                 }
-                (0 | 1, NoiseNode::Operation(node)) if node.inputs[pin.id.input].is_node_id() => {
+                (
+                    5,
+                    NoiseNode::RotatePoint(node)
+                    | NoiseNode::ScalePoint(node)
+                    | NoiseNode::TranslatePoint(node),
+                ) if node.vector_node_id.is_some() => {
                     snarl
                         .get_node_mut(pin.id.node)
-                        .and_then(NoiseNode::as_const_op_tuple_mut)
+                        .and_then(NoiseNode::as_transform_mut)
                         .unwrap()
-                        .inputs[pin.id.input] = Default::default();
+                        .vector_node_id = None;
                 }
-                (0 | 1, NoiseNode::U32Operation(node))
-                    if node.inputs[pin.id.input].is_node_id() =>
+                (0..=3, NoiseNode::Vector(node)) if node.axes[pin.id.input].is_node_id() => {
+                    let node_id = node.axes[pin.id.input].as_node_id().unwrap();
+                    snarl
+                        .get_node_mut(pin.id.node)
+                        .and_then(NoiseNode::as_vector_mut)
+                        .unwrap()
+                        .axes[pin.id.input] =
+                        Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                    NoiseNode::propagate_tuple_from_f64_op(node_id, snarl);
+                }
+                (1..=3, NoiseNode::RotateAxis(node))
+                    if node.axis[pin.id.input - 1].is_node_id() =>
                 {
-                    let node_id = node.inputs[pin.id.input].as_node_id().unwrap();
+                    let node_id = node.axis[pin.id.input - 1].as_node_id().unwrap();
                     snarl
                         .get_node_mut(pin.id.node)
-                        .and_then(NoiseNode::as_const_op_u32_mut)
+                        .and_then(NoiseNode::as_rotate_axis_mut)
                         .unwrap()
-                        .inputs[pin.id.input] =
-                        Value(snarl.get_node(node_id).unwrap().eval_u32(snarl));
-
-                    NoiseNode::propagate_tuple_from_u32_op(node_id, snarl);
-                    NoiseNode::propagate_tuple_from_u32_op(pin.id.node, snarl);
+                        .axis[pin.id.input - 1] =
+                        Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                    NoiseNode::propagate_tuple_from_f64_op(node_id, snarl);
+                }
+                (1..=4, NoiseNode::TilePoint(node))
+                    if node.periods[pin.id.input - 1].is_node_id() =>
+                {
+                    let node_id = node.periods[pin.id.input - 1].as_node_id().unwrap();
+                    snarl
+                        .get_node_mut(pin.id.node)
+                        .and_then(NoiseNode::as_tile_point_mut)
+                        .unwrap()
+                        .periods[pin.id.input - 1] =
+                        Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                    NoiseNode::propagate_tuple_from_f64_op(node_id, snarl);
                 }
                 (
-                    1,
+                    2,
                     &NoiseNode::BasicMulti(FractalNode {
-                        octaves: Node(node_id),
+                        frequency: Node(node_id),
                         ..
                     })
                     | &NoiseNode::Billow(FractalNode {
-                        octaves: Node(node_id),
+                        frequency: Node(node_id),
                         ..
                     })
                     | &NoiseNode::Fbm(FractalNode {
-                        octaves: Node(node_id),
+                        frequency: Node(node_id),
                         ..
                     })
                     | &NoiseNode::HybridMulti(FractalNode {
-                        octaves: Node(node_id),
+                        frequency: Node(node_id),
+                        ..
+                    })
+                    | &NoiseNode::Jordan(FractalNode {
+                        frequency: Node(node_id),
+                        ..
+                    })
+                    | &NoiseNode::Swiss(FractalNode {
+                        frequency: Node(node_id),
                         ..
                     }),
                 ) => {
@@ -1307,55 +4804,194 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                         .get_node_mut(pin.id.node)
                         .and_then(NoiseNode::as_fractal_mut)
                         .unwrap()
-                        .octaves = Value(snarl.get_node(node_id).unwrap().eval_u32(snarl));
-                    NoiseNode::propagate_tuple_from_u32_op(node_id, snarl);
+                        .frequency = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                    NoiseNode::propagate_tuple_from_f64_op(node_id, snarl);
+                }
+                (
+                    2,
+                    &NoiseNode::GenericFractal(GenericFractalNode {
+                        frequency: Node(node_id),
+                        ..
+                    }),
+                ) => {
+                    snarl
+                        .get_node_mut(pin.id.node)
+                        .and_then(NoiseNode::as_generic_fractal_mut)
+                        .unwrap()
+                        .frequency = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                    NoiseNode::propagate_tuple_from_f64_op(node_id, snarl);
+                }
+                (
+                    2,
+                    &NoiseNode::BiasGain(BiasGainNode {
+                        gain: Node(node_id),
+                        ..
+                    }),
+                ) => {
+                    snarl
+                        .get_node_mut(pin.id.node)
+                        .and_then(NoiseNode::as_bias_gain_mut)
+                        .unwrap()
+                        .gain = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                    NoiseNode::propagate_tuple_from_f64_op(node_id, snarl);
+                }
+                (
+                    2,
+                    &NoiseNode::Clamp(ClampNode {
+                        upper_bound: Node(node_id),
+                        ..
+                    }),
+                ) => {
+                    snarl
+                        .get_node_mut(pin.id.node)
+                        .and_then(NoiseNode::as_clamp_mut)
+                        .unwrap()
+                        .upper_bound = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                    NoiseNode::propagate_tuple_from_f64_op(node_id, snarl);
+                }
+                (
+                    2,
+                    &NoiseNode::Compare(CompareNode {
+                        tolerance: Node(node_id),
+                        ..
+                    }),
+                ) => {
+                    snarl
+                        .get_node_mut(pin.id.node)
+                        .and_then(NoiseNode::as_compare_mut)
+                        .unwrap()
+                        .tolerance = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                    NoiseNode::propagate_tuple_from_f64_op(node_id, snarl);
+                }
+                (
+                    2,
+                    &NoiseNode::Contrast(ContrastNode {
+                        pivot: Node(node_id),
+                        ..
+                    }),
+                ) => {
+                    snarl
+                        .get_node_mut(pin.id.node)
+                        .and_then(NoiseNode::as_contrast_mut)
+                        .unwrap()
+                        .pivot = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                    NoiseNode::propagate_tuple_from_f64_op(node_id, snarl);
+                }
+                (
+                    2,
+                    &NoiseNode::Crater(CraterNode {
+                        density: Node(node_id),
+                        ..
+                    }),
+                ) => {
+                    snarl
+                        .get_node_mut(pin.id.node)
+                        .and_then(NoiseNode::as_crater_mut)
+                        .unwrap()
+                        .density = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                    NoiseNode::propagate_tuple_from_f64_op(node_id, snarl);
+                }
+                (
+                    2,
+                    &NoiseNode::DomainWarp(DomainWarpNode {
+                        amplitude: Node(node_id),
+                        ..
+                    }),
+                ) => {
+                    snarl
+                        .get_node_mut(pin.id.node)
+                        .and_then(NoiseNode::as_domain_warp_mut)
+                        .unwrap()
+                        .amplitude = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                    NoiseNode::propagate_tuple_from_f64_op(node_id, snarl);
+                }
+                (
+                    2,
+                    &NoiseNode::FaultLines(FaultLinesNode {
+                        amplitude: Node(node_id),
+                        ..
+                    }),
+                ) => {
+                    snarl
+                        .get_node_mut(pin.id.node)
+                        .and_then(NoiseNode::as_fault_lines_mut)
+                        .unwrap()
+                        .amplitude = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                    NoiseNode::propagate_tuple_from_f64_op(node_id, snarl);
+                }
+                (
+                    2,
+                    &NoiseNode::Mix(MixNode {
+                        factor: Node(node_id),
+                        ..
+                    }),
+                ) => {
+                    snarl
+                        .get_node_mut(pin.id.node)
+                        .and_then(NoiseNode::as_mix_mut)
+                        .unwrap()
+                        .factor = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                    NoiseNode::propagate_tuple_from_f64_op(node_id, snarl);
+                }
+                (
+                    2,
+                    &NoiseNode::Normal(NormalNode {
+                        strength: Node(node_id),
+                        ..
+                    }),
+                ) => {
+                    snarl
+                        .get_node_mut(pin.id.node)
+                        .and_then(NoiseNode::as_normal_mut)
+                        .unwrap()
+                        .strength = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                    NoiseNode::propagate_tuple_from_f64_op(node_id, snarl);
                 }
                 (
-                    1,
-                    &NoiseNode::Clamp(ClampNode {
-                        lower_bound: Node(node_id),
-                        ..
+                    2,
+                    &NoiseNode::RandomConstant(RandomConstantNode {
+                        min: Node(node_id), ..
                     }),
                 ) => {
                     snarl
                         .get_node_mut(pin.id.node)
-                        .and_then(NoiseNode::as_clamp_mut)
+                        .and_then(NoiseNode::as_random_constant_mut)
                         .unwrap()
-                        .lower_bound = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                        .min = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
                     NoiseNode::propagate_tuple_from_f64_op(node_id, snarl);
                 }
                 (
-                    1,
-                    &NoiseNode::ControlPoint(ControlPointNode {
-                        output: Node(node_id),
+                    2,
+                    &NoiseNode::Remap(RemapNode {
+                        in_max: Node(node_id),
                         ..
                     }),
                 ) => {
                     snarl
                         .get_node_mut(pin.id.node)
-                        .and_then(NoiseNode::as_control_point_mut)
+                        .and_then(NoiseNode::as_remap_mut)
                         .unwrap()
-                        .output = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                        .in_max = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
                     NoiseNode::propagate_tuple_from_f64_op(node_id, snarl);
                 }
                 (
-                    1,
-                    &NoiseNode::Exponent(ExponentNode {
-                        exponent: Node(node_id),
+                    2,
+                    &NoiseNode::Erosion(ErosionNode {
+                        frequency: Node(node_id),
                         ..
                     }),
                 ) => {
                     snarl
                         .get_node_mut(pin.id.node)
-                        .and_then(NoiseNode::as_exponent_mut)
+                        .and_then(NoiseNode::as_erosion_mut)
                         .unwrap()
-                        .exponent = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                        .frequency = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
                     NoiseNode::propagate_tuple_from_f64_op(node_id, snarl);
                 }
                 (
-                    1,
+                    2,
                     &NoiseNode::RigidMulti(RigidFractalNode {
-                        octaves: Node(node_id),
+                        frequency: Node(node_id),
                         ..
                     }),
                 ) => {
@@ -1363,13 +4999,13 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                         .get_node_mut(pin.id.node)
                         .and_then(NoiseNode::as_rigid_fractal_mut)
                         .unwrap()
-                        .octaves = Value(snarl.get_node(node_id).unwrap().eval_u32(snarl));
-                    NoiseNode::propagate_tuple_from_u32_op(node_id, snarl);
+                        .frequency = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                    NoiseNode::propagate_tuple_from_f64_op(node_id, snarl);
                 }
                 (
-                    1,
+                    2,
                     &NoiseNode::ScaleBias(ScaleBiasNode {
-                        scale: Node(node_id),
+                        bias: Node(node_id),
                         ..
                     }),
                 ) => {
@@ -1377,13 +5013,13 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                         .get_node_mut(pin.id.node)
                         .and_then(NoiseNode::as_scale_bias_mut)
                         .unwrap()
-                        .scale = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                        .bias = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
                     NoiseNode::propagate_tuple_from_f64_op(node_id, snarl);
                 }
                 (
-                    1,
+                    2,
                     &NoiseNode::Turbulence(TurbulenceNode {
-                        seed: Node(node_id),
+                        frequency: Node(node_id),
                         ..
                     }),
                 ) => {
@@ -1391,54 +5027,47 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                         .get_node_mut(pin.id.node)
                         .and_then(NoiseNode::as_turbulence_mut)
                         .unwrap()
-                        .seed = Value(snarl.get_node(node_id).unwrap().eval_u32(snarl));
-                    NoiseNode::propagate_tuple_from_u32_op(node_id, snarl);
+                        .frequency = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                    NoiseNode::propagate_tuple_from_f64_op(node_id, snarl);
                 }
                 (
-                    1,
-                    &NoiseNode::Worley(WorleyNode {
+                    2,
+                    &NoiseNode::WarpedFbm(WarpedFbmNode {
                         frequency: Node(node_id),
                         ..
                     }),
                 ) => {
                     snarl
                         .get_node_mut(pin.id.node)
-                        .and_then(NoiseNode::as_worley_mut)
+                        .and_then(NoiseNode::as_warped_fbm_mut)
                         .unwrap()
                         .frequency = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
                     NoiseNode::propagate_tuple_from_f64_op(node_id, snarl);
                 }
                 (
-                    1..=4,
-                    NoiseNode::RotatePoint(node)
-                    | NoiseNode::ScalePoint(node)
-                    | NoiseNode::TranslatePoint(node),
-                ) if node.axes[pin.id.input - 1].is_node_id() => {
-                    let node_id = node.axes[pin.id.input - 1].as_node_id().unwrap();
-                    snarl
-                        .get_node_mut(pin.id.node)
-                        .and_then(NoiseNode::as_transform_mut)
-                        .unwrap()
-                        .axes[pin.id.input - 1] =
-                        Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
-                    NoiseNode::propagate_tuple_from_f64_op(node_id, snarl);
-                }
-                (
-                    2,
+                    3,
                     &NoiseNode::BasicMulti(FractalNode {
-                        frequency: Node(node_id),
+                        lacunarity: Node(node_id),
                         ..
                     })
                     | &NoiseNode::Billow(FractalNode {
-                        frequency: Node(node_id),
+                        lacunarity: Node(node_id),
                         ..
                     })
                     | &NoiseNode::Fbm(FractalNode {
-                        frequency: Node(node_id),
+                        lacunarity: Node(node_id),
                         ..
                     })
                     | &NoiseNode::HybridMulti(FractalNode {
-                        frequency: Node(node_id),
+                        lacunarity: Node(node_id),
+                        ..
+                    })
+                    | &NoiseNode::Jordan(FractalNode {
+                        lacunarity: Node(node_id),
+                        ..
+                    })
+                    | &NoiseNode::Swiss(FractalNode {
+                        lacunarity: Node(node_id),
                         ..
                     }),
                 ) => {
@@ -1446,87 +5075,88 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                         .get_node_mut(pin.id.node)
                         .and_then(NoiseNode::as_fractal_mut)
                         .unwrap()
-                        .frequency = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                        .lacunarity = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
                     NoiseNode::propagate_tuple_from_f64_op(node_id, snarl);
                 }
                 (
-                    2,
-                    &NoiseNode::Clamp(ClampNode {
-                        upper_bound: Node(node_id),
+                    3,
+                    &NoiseNode::GenericFractal(GenericFractalNode {
+                        lacunarity: Node(node_id),
                         ..
                     }),
                 ) => {
                     snarl
                         .get_node_mut(pin.id.node)
-                        .and_then(NoiseNode::as_clamp_mut)
+                        .and_then(NoiseNode::as_generic_fractal_mut)
                         .unwrap()
-                        .upper_bound = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                        .lacunarity = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
                     NoiseNode::propagate_tuple_from_f64_op(node_id, snarl);
                 }
                 (
-                    2,
-                    &NoiseNode::RigidMulti(RigidFractalNode {
+                    3,
+                    &NoiseNode::Crater(CraterNode {
+                        radius: Node(node_id),
+                        ..
+                    }),
+                ) => {
+                    snarl
+                        .get_node_mut(pin.id.node)
+                        .and_then(NoiseNode::as_crater_mut)
+                        .unwrap()
+                        .radius = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                    NoiseNode::propagate_tuple_from_f64_op(node_id, snarl);
+                }
+                (
+                    3,
+                    &NoiseNode::DomainWarp(DomainWarpNode {
                         frequency: Node(node_id),
                         ..
                     }),
                 ) => {
                     snarl
                         .get_node_mut(pin.id.node)
-                        .and_then(NoiseNode::as_rigid_fractal_mut)
+                        .and_then(NoiseNode::as_domain_warp_mut)
                         .unwrap()
                         .frequency = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
                     NoiseNode::propagate_tuple_from_f64_op(node_id, snarl);
                 }
                 (
-                    2,
-                    &NoiseNode::ScaleBias(ScaleBiasNode {
-                        bias: Node(node_id),
-                        ..
+                    3,
+                    &NoiseNode::RandomConstant(RandomConstantNode {
+                        max: Node(node_id), ..
                     }),
                 ) => {
                     snarl
                         .get_node_mut(pin.id.node)
-                        .and_then(NoiseNode::as_scale_bias_mut)
+                        .and_then(NoiseNode::as_random_constant_mut)
                         .unwrap()
-                        .bias = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                        .max = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
                     NoiseNode::propagate_tuple_from_f64_op(node_id, snarl);
                 }
                 (
-                    2,
-                    &NoiseNode::Turbulence(TurbulenceNode {
-                        frequency: Node(node_id),
+                    3,
+                    &NoiseNode::Remap(RemapNode {
+                        out_min: Node(node_id),
                         ..
                     }),
                 ) => {
                     snarl
                         .get_node_mut(pin.id.node)
-                        .and_then(NoiseNode::as_turbulence_mut)
+                        .and_then(NoiseNode::as_remap_mut)
                         .unwrap()
-                        .frequency = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                        .out_min = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
                     NoiseNode::propagate_tuple_from_f64_op(node_id, snarl);
                 }
                 (
                     3,
-                    &NoiseNode::BasicMulti(FractalNode {
-                        lacunarity: Node(node_id),
-                        ..
-                    })
-                    | &NoiseNode::Billow(FractalNode {
-                        lacunarity: Node(node_id),
-                        ..
-                    })
-                    | &NoiseNode::Fbm(FractalNode {
-                        lacunarity: Node(node_id),
-                        ..
-                    })
-                    | &NoiseNode::HybridMulti(FractalNode {
+                    &NoiseNode::Erosion(ErosionNode {
                         lacunarity: Node(node_id),
                         ..
                     }),
                 ) => {
                     snarl
                         .get_node_mut(pin.id.node)
-                        .and_then(NoiseNode::as_fractal_mut)
+                        .and_then(NoiseNode::as_erosion_mut)
                         .unwrap()
                         .lacunarity = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
                     NoiseNode::propagate_tuple_from_f64_op(node_id, snarl);
@@ -1573,6 +5203,20 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                         .power = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
                     NoiseNode::propagate_tuple_from_f64_op(node_id, snarl);
                 }
+                (
+                    3,
+                    &NoiseNode::WarpedFbm(WarpedFbmNode {
+                        lacunarity: Node(node_id),
+                        ..
+                    }),
+                ) => {
+                    snarl
+                        .get_node_mut(pin.id.node)
+                        .and_then(NoiseNode::as_warped_fbm_mut)
+                        .unwrap()
+                        .lacunarity = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                    NoiseNode::propagate_tuple_from_f64_op(node_id, snarl);
+                }
                 (
                     4,
                     &NoiseNode::BasicMulti(FractalNode {
@@ -1590,6 +5234,14 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                     | &NoiseNode::HybridMulti(FractalNode {
                         persistence: Node(node_id),
                         ..
+                    })
+                    | &NoiseNode::Jordan(FractalNode {
+                        persistence: Node(node_id),
+                        ..
+                    })
+                    | &NoiseNode::Swiss(FractalNode {
+                        persistence: Node(node_id),
+                        ..
                     }),
                 ) => {
                     snarl
@@ -1599,6 +5251,62 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                         .persistence = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
                     NoiseNode::propagate_tuple_from_f64_op(node_id, snarl);
                 }
+                (
+                    4,
+                    &NoiseNode::GenericFractal(GenericFractalNode {
+                        persistence: Node(node_id),
+                        ..
+                    }),
+                ) => {
+                    snarl
+                        .get_node_mut(pin.id.node)
+                        .and_then(NoiseNode::as_generic_fractal_mut)
+                        .unwrap()
+                        .persistence = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                    NoiseNode::propagate_tuple_from_f64_op(node_id, snarl);
+                }
+                (
+                    4,
+                    &NoiseNode::Crater(CraterNode {
+                        depth: Node(node_id),
+                        ..
+                    }),
+                ) => {
+                    snarl
+                        .get_node_mut(pin.id.node)
+                        .and_then(NoiseNode::as_crater_mut)
+                        .unwrap()
+                        .depth = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                    NoiseNode::propagate_tuple_from_f64_op(node_id, snarl);
+                }
+                (
+                    4,
+                    &NoiseNode::Remap(RemapNode {
+                        out_max: Node(node_id),
+                        ..
+                    }),
+                ) => {
+                    snarl
+                        .get_node_mut(pin.id.node)
+                        .and_then(NoiseNode::as_remap_mut)
+                        .unwrap()
+                        .out_max = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                    NoiseNode::propagate_tuple_from_f64_op(node_id, snarl);
+                }
+                (
+                    4,
+                    &NoiseNode::Erosion(ErosionNode {
+                        persistence: Node(node_id),
+                        ..
+                    }),
+                ) => {
+                    snarl
+                        .get_node_mut(pin.id.node)
+                        .and_then(NoiseNode::as_erosion_mut)
+                        .unwrap()
+                        .persistence = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                    NoiseNode::propagate_tuple_from_f64_op(node_id, snarl);
+                }
                 (
                     4,
                     &NoiseNode::RigidMulti(RigidFractalNode {
@@ -1613,6 +5321,20 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                         .persistence = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
                     NoiseNode::propagate_tuple_from_f64_op(node_id, snarl);
                 }
+                (
+                    4,
+                    &NoiseNode::RotateAxis(RotateAxisNode {
+                        angle: Node(node_id),
+                        ..
+                    }),
+                ) => {
+                    snarl
+                        .get_node_mut(pin.id.node)
+                        .and_then(NoiseNode::as_rotate_axis_mut)
+                        .unwrap()
+                        .angle = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                    NoiseNode::propagate_tuple_from_f64_op(node_id, snarl);
+                }
                 (
                     4,
                     &NoiseNode::Select(SelectNode {
@@ -1641,6 +5363,34 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                         .roughness = Value(snarl.get_node(node_id).unwrap().eval_u32(snarl));
                     NoiseNode::propagate_tuple_from_u32_op(node_id, snarl);
                 }
+                (
+                    4,
+                    &NoiseNode::WarpedFbm(WarpedFbmNode {
+                        persistence: Node(node_id),
+                        ..
+                    }),
+                ) => {
+                    snarl
+                        .get_node_mut(pin.id.node)
+                        .and_then(NoiseNode::as_warped_fbm_mut)
+                        .unwrap()
+                        .persistence = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                    NoiseNode::propagate_tuple_from_f64_op(node_id, snarl);
+                }
+                (
+                    5,
+                    &NoiseNode::Erosion(ErosionNode {
+                        strength: Node(node_id),
+                        ..
+                    }),
+                ) => {
+                    snarl
+                        .get_node_mut(pin.id.node)
+                        .and_then(NoiseNode::as_erosion_mut)
+                        .unwrap()
+                        .strength = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                    NoiseNode::propagate_tuple_from_f64_op(node_id, snarl);
+                }
                 (
                     5,
                     &NoiseNode::RigidMulti(RigidFractalNode {
@@ -1669,7 +5419,52 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                         .falloff = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
                     NoiseNode::propagate_tuple_from_f64_op(node_id, snarl);
                 }
-                (control_point_idx, NoiseNode::Curve(node)) if control_point_idx > 0 => {
+                (
+                    5,
+                    &NoiseNode::WarpedFbm(WarpedFbmNode {
+                        strength: Node(node_id),
+                        ..
+                    }),
+                ) => {
+                    snarl
+                        .get_node_mut(pin.id.node)
+                        .and_then(NoiseNode::as_warped_fbm_mut)
+                        .unwrap()
+                        .strength = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
+                    NoiseNode::propagate_tuple_from_f64_op(node_id, snarl);
+                }
+                (
+                    6,
+                    &NoiseNode::WarpedFbm(WarpedFbmNode {
+                        iterations: Node(node_id),
+                        ..
+                    }),
+                ) => {
+                    snarl
+                        .get_node_mut(pin.id.node)
+                        .and_then(NoiseNode::as_warped_fbm_mut)
+                        .unwrap()
+                        .iterations = Value(snarl.get_node(node_id).unwrap().eval_u32(snarl));
+                    NoiseNode::propagate_tuple_from_u32_op(node_id, snarl);
+                }
+                (control_point_idx, NoiseNode::Curve(node)) if control_point_idx > 0 => {
+                    let control_point_idx = control_point_idx - 1;
+
+                    if node
+                        .control_point_node_ids
+                        .get(control_point_idx)
+                        .copied()
+                        .flatten()
+                        .is_some()
+                    {
+                        snarl
+                            .get_node_mut(pin.id.node)
+                            .and_then(NoiseNode::as_curve_mut)
+                            .unwrap()
+                            .control_point_node_ids[control_point_idx] = None;
+                    }
+                }
+                (control_point_idx, NoiseNode::Terrace(node)) if control_point_idx > 0 => {
                     let control_point_idx = control_point_idx - 1;
 
                     if node
@@ -1681,53 +5476,341 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                     {
                         snarl
                             .get_node_mut(pin.id.node)
-                            .and_then(NoiseNode::as_curve_mut)
+                            .and_then(NoiseNode::as_terrace_mut)
                             .unwrap()
                             .control_point_node_ids[control_point_idx] = None;
                     }
                 }
-                (control_point_idx, NoiseNode::Terrace(node)) if control_point_idx > 0 => {
-                    let control_point_idx = control_point_idx - 1;
+                (source_idx, NoiseNode::Formula(node))
+                    if source_idx >= FormulaNode::MIN_SOURCE_COUNT
+                        && source_idx + 1 == node.source_count =>
+                {
+                    // Trailing sources shrink away once disconnected
+                    snarl
+                        .get_node_mut(pin.id.node)
+                        .and_then(NoiseNode::as_formula_mut)
+                        .unwrap()
+                        .source_count -= 1;
+                }
+                (source_idx, NoiseNode::Script(node))
+                    if source_idx >= ScriptNode::MIN_SOURCE_COUNT
+                        && source_idx + 1 == node.source_count =>
+                {
+                    snarl
+                        .get_node_mut(pin.id.node)
+                        .and_then(NoiseNode::as_script_mut)
+                        .unwrap()
+                        .source_count -= 1;
+                }
+                (source_idx, NoiseNode::Switch(node))
+                    if source_idx > SwitchNode::MIN_SOURCE_COUNT
+                        && source_idx == node.source_count =>
+                {
+                    // Trailing sources shrink away once disconnected
+                    snarl
+                        .get_node_mut(pin.id.node)
+                        .and_then(NoiseNode::as_switch_mut)
+                        .unwrap()
+                        .source_count -= 1;
+                }
+                _ => {}
+            }
+        }
+
+        ui.set_height(16.0 * scale);
+        ui.set_width(128.0 * scale);
+        ui.with_layout(
+            Layout::left_to_right(Align::Min).with_cross_align(Align::Center),
+            |ui| {
+                ui.add_space(20.0 * scale);
+                match (pin.id.input, snarl.get_node_mut(pin.id.node).unwrap()) {
+                    (
+                        0,
+                        NoiseNode::Abs(_)
+                        | NoiseNode::BiasGain(_)
+                        | NoiseNode::Cache(_)
+                        | NoiseNode::Clamp(_)
+                        | NoiseNode::ColorRamp(_)
+                        | NoiseNode::Contrast(_)
+                        | NoiseNode::Crater(_)
+                        | NoiseNode::Curve(_)
+                        | NoiseNode::Displace(_)
+                        | NoiseNode::DomainWarp(_)
+                        | NoiseNode::Ease(_)
+                        | NoiseNode::Exponent(_)
+                        | NoiseNode::GenericFractal(_)
+                        | NoiseNode::MirrorPoint(_)
+                        | NoiseNode::Negate(_)
+                        | NoiseNode::Normal(_)
+                        | NoiseNode::Normalize(_)
+                        | NoiseNode::Remap(_)
+                        | NoiseNode::RotateAxis(_)
+                        | NoiseNode::RotatePoint(_)
+                        | NoiseNode::ScaleBias(_)
+                        | NoiseNode::ScalePoint(_)
+                        | NoiseNode::Slope(_)
+                        | NoiseNode::Swizzle(_)
+                        | NoiseNode::Terrace(_)
+                        | NoiseNode::TilePoint(_)
+                        | NoiseNode::TranslatePoint(_)
+                        | NoiseNode::Turbulence(_),
+                    ) => {
+                        ui.label("Source");
+
+                        #[cfg(debug_assertions)]
+                        ui.label(
+                            RichText::new(format!("#{:?}", in_pin_remote_node(snarl, pin.id)))
+                                .color(Color32::DEBUG_COLOR),
+                        );
+
+                        Self::image_pin_info(true, !snarl.in_pin(pin.id).remotes.is_empty())
+                    }
+                    (
+                        0,
+                        NoiseNode::BasicMulti(FractalNode { seed, .. })
+                        | NoiseNode::Billow(FractalNode { seed, .. })
+                        | NoiseNode::BlueNoise(GeneratorNode { seed, .. })
+                        | NoiseNode::Erosion(ErosionNode { seed, .. })
+                        | NoiseNode::FaultLines(FaultLinesNode { seed, .. })
+                        | NoiseNode::Fbm(FractalNode { seed, .. })
+                        | NoiseNode::HybridMulti(FractalNode { seed, .. })
+                        | NoiseNode::Jordan(FractalNode { seed, .. })
+                        | NoiseNode::OpenSimplex(GeneratorNode { seed, .. })
+                        | NoiseNode::Perlin(GeneratorNode { seed, .. })
+                        | NoiseNode::PerlinSurflet(GeneratorNode { seed, .. })
+                        | NoiseNode::RigidMulti(RigidFractalNode { seed, .. })
+                        | NoiseNode::Simplex(GeneratorNode { seed, .. })
+                        | NoiseNode::SuperSimplex(GeneratorNode { seed, .. })
+                        | NoiseNode::Swiss(FractalNode { seed, .. })
+                        | NoiseNode::Value(GeneratorNode { seed, .. })
+                        | NoiseNode::WarpedFbm(WarpedFbmNode { seed, .. })
+                        | NoiseNode::Worley(WorleyNode { seed, .. }),
+                    ) => {
+                        ui.label("Seed");
+
+                        if let Some(value) = seed.as_value_mut() {
+                            self.drag_value_seed(ui, scale, value, pin.id.node);
+
+                            Self::u32_pin_info(true, false)
+                        } else {
+                            #[cfg(debug_assertions)]
+                            ui.label(
+                                RichText::new(format!("#{:?}", seed.as_node_id().unwrap()))
+                                    .color(Color32::DEBUG_COLOR),
+                            );
+
+                            Self::u32_pin_info(true, true)
+                        }
+                    }
+                    (0, NoiseNode::Checkerboard(CheckerboardNode { size, .. })) => {
+                        ui.label("Size");
+
+                        if let Some(value) = size.as_value_mut() {
+                            self.drag_value_u32(ui, scale, value, pin.id.node);
+
+                            Self::u32_pin_info(true, false)
+                        } else {
+                            #[cfg(debug_assertions)]
+                            ui.label(
+                                RichText::new(format!("#{:?}", size.as_node_id().unwrap()))
+                                    .color(Color32::DEBUG_COLOR),
+                            );
+
+                            Self::u32_pin_info(true, true)
+                        }
+                    }
+                    (0, NoiseNode::ControlPoint(node)) => {
+                        ui.label("Input");
+
+                        if let Some(value) = node.input.as_value_mut() {
+                            self.drag_value_f64(ui, scale, value, pin.id.node);
+
+                            Self::f64_pin_info(true, false)
+                        } else {
+                            #[cfg(debug_assertions)]
+                            ui.label(
+                                RichText::new(format!("#{:?}", node.input.as_node_id().unwrap()))
+                                    .color(Color32::DEBUG_COLOR),
+                            );
+
+                            Self::f64_pin_info(true, true)
+                        }
+                    }
+                    (0, NoiseNode::Cylinders(node)) => {
+                        ui.label("Frequency");
+
+                        if let Some(value) = node.frequency.as_value_mut() {
+                            self.drag_value_f64(ui, scale, value, pin.id.node);
+
+                            Self::f64_pin_info(true, false)
+                        } else {
+                            #[cfg(debug_assertions)]
+                            ui.label(
+                                RichText::new(format!(
+                                    "#{:?}",
+                                    node.frequency.as_node_id().unwrap()
+                                ))
+                                .color(Color32::DEBUG_COLOR),
+                            );
+
+                            Self::f64_pin_info(true, true)
+                        }
+                    }
+                    (0, NoiseNode::Gradient(node)) => {
+                        ui.label("Angle");
+
+                        if let Some(value) = node.angle.as_value_mut() {
+                            self.drag_value_f64(ui, scale, value, pin.id.node);
+
+                            Self::f64_pin_info(true, false)
+                        } else {
+                            #[cfg(debug_assertions)]
+                            ui.label(
+                                RichText::new(format!("#{:?}", node.angle.as_node_id().unwrap()))
+                                    .color(Color32::DEBUG_COLOR),
+                            );
+
+                            Self::f64_pin_info(true, true)
+                        }
+                    }
+                    (
+                        0 | 1,
+                        NoiseNode::Add(_)
+                        | NoiseNode::Difference(_)
+                        | NoiseNode::Min(_)
+                        | NoiseNode::Max(_)
+                        | NoiseNode::Multiply(_)
+                        | NoiseNode::Power(_),
+                    ) => {
+                        ui.label("Source");
+
+                        #[cfg(debug_assertions)]
+                        ui.label(
+                            RichText::new(format!("#{:?}", in_pin_remote_node(snarl, pin.id)))
+                                .color(Color32::DEBUG_COLOR),
+                        );
+
+                        Self::image_pin_info(true, !snarl.in_pin(pin.id).remotes.is_empty())
+                    }
+                    (0, NoiseNode::ImageSource(node)) => {
+                        ui.label("Size");
+
+                        if let Some(value) = node.size.as_value_mut() {
+                            self.drag_value_f64(ui, scale, value, pin.id.node);
+
+                            Self::f64_pin_info(true, false)
+                        } else {
+                            #[cfg(debug_assertions)]
+                            ui.label(
+                                RichText::new(format!("#{:?}", node.size.as_node_id().unwrap()))
+                                    .color(Color32::DEBUG_COLOR),
+                            );
+
+                            Self::f64_pin_info(true, true)
+                        }
+                    }
+                    (0, NoiseNode::RandomConstant(node)) => {
+                        ui.label("Seed");
+
+                        if let Some(value) = node.seed.as_value_mut() {
+                            self.drag_value_u32(ui, scale, value, pin.id.node);
+
+                            Self::u32_pin_info(true, false)
+                        } else {
+                            #[cfg(debug_assertions)]
+                            ui.label(
+                                RichText::new(format!("#{:?}", node.seed.as_node_id().unwrap()))
+                                    .color(Color32::DEBUG_COLOR),
+                            );
+
+                            Self::u32_pin_info(true, true)
+                        }
+                    }
+                    (0, NoiseNode::Switch(node)) => {
+                        ui.label("Selector");
+
+                        if let Some(value) = node.selector.as_value_mut() {
+                            self.drag_value_u32(ui, scale, value, pin.id.node);
+
+                            Self::u32_pin_info(true, false)
+                        } else {
+                            #[cfg(debug_assertions)]
+                            ui.label(
+                                RichText::new(format!(
+                                    "#{:?}",
+                                    node.selector.as_node_id().unwrap()
+                                ))
+                                .color(Color32::DEBUG_COLOR),
+                            );
+
+                            Self::u32_pin_info(true, true)
+                        }
+                    }
+                    (1, NoiseNode::BiasGain(node)) => {
+                        ui.label("Bias");
+
+                        if let Some(value) = node.bias.as_value_mut() {
+                            self.drag_value_f64(ui, scale, value, pin.id.node);
+
+                            Self::f64_pin_info(true, false)
+                        } else {
+                            #[cfg(debug_assertions)]
+                            ui.label(
+                                RichText::new(format!("#{:?}", node.bias.as_node_id().unwrap()))
+                                    .color(Color32::DEBUG_COLOR),
+                            );
+
+                            Self::f64_pin_info(true, true)
+                        }
+                    }
+                    (1, NoiseNode::Contrast(node)) => {
+                        ui.label("Contrast");
+
+                        if let Some(value) = node.contrast.as_value_mut() {
+                            self.drag_value_f64(ui, scale, value, pin.id.node);
+
+                            Self::f64_pin_info(true, false)
+                        } else {
+                            #[cfg(debug_assertions)]
+                            ui.label(
+                                RichText::new(format!(
+                                    "#{:?}",
+                                    node.contrast.as_node_id().unwrap()
+                                ))
+                                .color(Color32::DEBUG_COLOR),
+                            );
+
+                            Self::f64_pin_info(true, true)
+                        }
+                    }
+                    (1, NoiseNode::DomainWarp(_)) => {
+                        ui.label("Warp");
+
+                        #[cfg(debug_assertions)]
+                        ui.label(
+                            RichText::new(format!("#{:?}", in_pin_remote_node(snarl, pin.id)))
+                                .color(Color32::DEBUG_COLOR),
+                        );
 
-                    if node
-                        .control_point_node_ids
-                        .get(control_point_idx)
-                        .copied()
-                        .flatten()
-                        .is_some()
-                    {
-                        snarl
-                            .get_node_mut(pin.id.node)
-                            .and_then(NoiseNode::as_terrace_mut)
-                            .unwrap()
-                            .control_point_node_ids[control_point_idx] = None;
+                        Self::image_pin_info(true, !snarl.in_pin(pin.id).remotes.is_empty())
                     }
-                }
-                _ => {}
-            }
-        }
+                    (0..=2, NoiseNode::CombineRgb(_)) => {
+                        ui.label(["Red", "Green", "Blue"][pin.id.input]);
 
-        ui.set_height(16.0 * scale);
-        ui.set_width(128.0 * scale);
-        ui.with_layout(
-            Layout::left_to_right(Align::Min).with_cross_align(Align::Center),
-            |ui| {
-                ui.add_space(20.0 * scale);
-                match (pin.id.input, snarl.get_node_mut(pin.id.node).unwrap()) {
+                        #[cfg(debug_assertions)]
+                        ui.label(
+                            RichText::new(format!("#{:?}", in_pin_remote_node(snarl, pin.id)))
+                                .color(Color32::DEBUG_COLOR),
+                        );
+
+                        Self::image_pin_info(true, !snarl.in_pin(pin.id).remotes.is_empty())
+                    }
                     (
-                        0,
-                        NoiseNode::Abs(_)
-                        | NoiseNode::Clamp(_)
-                        | NoiseNode::Curve(_)
-                        | NoiseNode::Displace(_)
-                        | NoiseNode::Exponent(_)
-                        | NoiseNode::Negate(_)
-                        | NoiseNode::RotatePoint(_)
-                        | NoiseNode::ScaleBias(_)
-                        | NoiseNode::ScalePoint(_)
-                        | NoiseNode::Terrace(_)
-                        | NoiseNode::TranslatePoint(_)
-                        | NoiseNode::Turbulence(_),
+                        0 | 1,
+                        NoiseNode::Blend(_)
+                        | NoiseNode::Compare(_)
+                        | NoiseNode::Mix(_)
+                        | NoiseNode::Select(_),
                     ) => {
                         ui.label("Source");
 
@@ -1739,75 +5822,320 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
 
                         Self::image_pin_info(true, !snarl.in_pin(pin.id).remotes.is_empty())
                     }
+                    (0 | 1, NoiseNode::F64Operation(node)) => {
+                        ui.label("Input");
+
+                        if let Some(value) = node.inputs[pin.id.input].as_value_mut() {
+                            self.drag_value_f64(ui, scale, value, pin.id.node);
+
+                            Self::f64_pin_info(true, false)
+                        } else {
+                            #[cfg(debug_assertions)]
+                            ui.label(
+                                RichText::new(format!(
+                                    "#{:?}",
+                                    node.inputs[pin.id.input].as_node_id().unwrap()
+                                ))
+                                .color(Color32::DEBUG_COLOR),
+                            );
+
+                            Self::f64_pin_info(true, true)
+                        }
+                    }
+                    (0 | 1, NoiseNode::Operation(node)) => {
+                        ui.label("Input");
+
+                        if node.inputs[pin.id.input].as_node_id().is_none() {
+                            Self::operation_pin_info(true, false)
+                        } else {
+                            #[cfg(debug_assertions)]
+                            ui.label(
+                                RichText::new(format!(
+                                    "#{:?}",
+                                    node.inputs[pin.id.input].as_node_id().unwrap()
+                                ))
+                                .color(Color32::DEBUG_COLOR),
+                            );
+
+                            Self::operation_pin_info(true, true)
+                        }
+                    }
+                    (0 | 1, NoiseNode::U32Operation(node)) => {
+                        ui.label("Input");
+
+                        if let Some(value) = node.inputs[pin.id.input].as_value_mut() {
+                            self.drag_value_u32(ui, scale, value, pin.id.node);
+
+                            Self::u32_pin_info(true, false)
+                        } else {
+                            #[cfg(debug_assertions)]
+                            ui.label(
+                                RichText::new(format!(
+                                    "#{:?}",
+                                    node.inputs[pin.id.input].as_node_id().unwrap()
+                                ))
+                                .color(Color32::DEBUG_COLOR),
+                            );
+
+                            Self::u32_pin_info(true, true)
+                        }
+                    }
+                    (1, NoiseNode::ControlPoint(node)) => {
+                        ui.label("Output");
+
+                        if let Some(value) = node.output.as_value_mut() {
+                            self.drag_value_f64(ui, scale, value, pin.id.node);
+
+                            Self::f64_pin_info(true, false)
+                        } else {
+                            #[cfg(debug_assertions)]
+                            ui.label(
+                                RichText::new(format!("#{:?}", node.output.as_node_id().unwrap()))
+                                    .color(Color32::DEBUG_COLOR),
+                            );
+
+                            Self::f64_pin_info(true, true)
+                        }
+                    }
                     (
-                        0,
-                        NoiseNode::BasicMulti(FractalNode { seed, .. })
-                        | NoiseNode::Billow(FractalNode { seed, .. })
-                        | NoiseNode::Fbm(FractalNode { seed, .. })
-                        | NoiseNode::HybridMulti(FractalNode { seed, .. })
-                        | NoiseNode::OpenSimplex(GeneratorNode { seed, .. })
-                        | NoiseNode::Perlin(GeneratorNode { seed, .. })
-                        | NoiseNode::PerlinSurflet(GeneratorNode { seed, .. })
-                        | NoiseNode::RigidMulti(RigidFractalNode { seed, .. })
-                        | NoiseNode::Simplex(GeneratorNode { seed, .. })
-                        | NoiseNode::SuperSimplex(GeneratorNode { seed, .. })
-                        | NoiseNode::Value(GeneratorNode { seed, .. })
-                        | NoiseNode::Worley(WorleyNode { seed, .. }),
+                        1,
+                        NoiseNode::BasicMulti(FractalNode { octaves, .. })
+                        | NoiseNode::Billow(FractalNode { octaves, .. })
+                        | NoiseNode::Erosion(ErosionNode { octaves, .. })
+                        | NoiseNode::Fbm(FractalNode { octaves, .. })
+                        | NoiseNode::GenericFractal(GenericFractalNode { octaves, .. })
+                        | NoiseNode::HybridMulti(FractalNode { octaves, .. })
+                        | NoiseNode::Jordan(FractalNode { octaves, .. })
+                        | NoiseNode::RigidMulti(RigidFractalNode { octaves, .. })
+                        | NoiseNode::Swiss(FractalNode { octaves, .. })
+                        | NoiseNode::WarpedFbm(WarpedFbmNode { octaves, .. }),
                     ) => {
-                        ui.label("Seed");
+                        ui.label("Octaves");
+
+                        if let Some(value) = octaves.as_value_mut() {
+                            self.drag_value_octaves(ui, scale, value, pin.id.node);
+
+                            Self::u32_pin_info(true, false)
+                        } else {
+                            #[cfg(debug_assertions)]
+                            ui.label(
+                                RichText::new(format!("#{:?}", octaves.as_node_id().unwrap()))
+                                    .color(Color32::DEBUG_COLOR),
+                            );
+
+                            Self::u32_pin_info(true, true)
+                        }
+                    }
+                    (1, NoiseNode::Clamp(node)) => {
+                        ui.label("Lower Bound");
+
+                        if let Some(value) = node.lower_bound.as_value_mut() {
+                            self.drag_value_f64(ui, scale, value, pin.id.node);
+
+                            Self::f64_pin_info(true, false)
+                        } else {
+                            #[cfg(debug_assertions)]
+                            ui.label(
+                                RichText::new(format!(
+                                    "#{:?}",
+                                    node.lower_bound.as_node_id().unwrap()
+                                ))
+                                .color(Color32::DEBUG_COLOR),
+                            );
+
+                            Self::f64_pin_info(true, true)
+                        }
+                    }
+                    (1, NoiseNode::Exponent(node)) => {
+                        ui.label("Exponent");
+
+                        if let Some(value) = node.exponent.as_value_mut() {
+                            self.drag_value_f64(ui, scale, value, pin.id.node);
+
+                            Self::f64_pin_info(true, false)
+                        } else {
+                            #[cfg(debug_assertions)]
+                            ui.label(
+                                RichText::new(format!(
+                                    "#{:?}",
+                                    node.exponent.as_node_id().unwrap()
+                                ))
+                                .color(Color32::DEBUG_COLOR),
+                            );
+
+                            Self::f64_pin_info(true, true)
+                        }
+                    }
+                    (1, NoiseNode::FaultLines(node)) => {
+                        ui.label("Iterations");
+
+                        if let Some(value) = node.iterations.as_value_mut() {
+                            self.drag_value_u32(ui, scale, value, pin.id.node);
+
+                            Self::u32_pin_info(true, false)
+                        } else {
+                            #[cfg(debug_assertions)]
+                            ui.label(
+                                RichText::new(format!(
+                                    "#{:?}",
+                                    node.iterations.as_node_id().unwrap()
+                                ))
+                                .color(Color32::DEBUG_COLOR),
+                            );
+
+                            Self::u32_pin_info(true, true)
+                        }
+                    }
+                    (1, NoiseNode::Gradient(node)) => {
+                        ui.label("Radius");
+
+                        if let Some(value) = node.radius.as_value_mut() {
+                            self.drag_value_f64(ui, scale, value, pin.id.node);
+
+                            Self::f64_pin_info(true, false)
+                        } else {
+                            #[cfg(debug_assertions)]
+                            ui.label(
+                                RichText::new(format!("#{:?}", node.radius.as_node_id().unwrap()))
+                                    .color(Color32::DEBUG_COLOR),
+                            );
+
+                            Self::f64_pin_info(true, true)
+                        }
+                    }
+                    (1, NoiseNode::Normal(node)) => {
+                        ui.label("Epsilon");
+
+                        if let Some(value) = node.epsilon.as_value_mut() {
+                            self.drag_value_f64(ui, scale, value, pin.id.node);
+
+                            Self::f64_pin_info(true, false)
+                        } else {
+                            #[cfg(debug_assertions)]
+                            ui.label(
+                                RichText::new(format!("#{:?}", node.epsilon.as_node_id().unwrap()))
+                                    .color(Color32::DEBUG_COLOR),
+                            );
+
+                            Self::f64_pin_info(true, true)
+                        }
+                    }
+                    (1, NoiseNode::RandomConstant(node)) => {
+                        ui.label("Index");
+
+                        if let Some(value) = node.index.as_value_mut() {
+                            self.drag_value_u32(ui, scale, value, pin.id.node);
+
+                            Self::u32_pin_info(true, false)
+                        } else {
+                            #[cfg(debug_assertions)]
+                            ui.label(
+                                RichText::new(format!("#{:?}", node.index.as_node_id().unwrap()))
+                                    .color(Color32::DEBUG_COLOR),
+                            );
+
+                            Self::u32_pin_info(true, true)
+                        }
+                    }
+                    (1, NoiseNode::Remap(node)) => {
+                        ui.label("In Min");
 
-                        if let Some(value) = seed.as_value_mut() {
-                            self.drag_value_u32(ui, scale, value, pin.id.node);
+                        if let Some(value) = node.in_min.as_value_mut() {
+                            self.drag_value_f64(ui, scale, value, pin.id.node);
 
-                            Self::u32_pin_info(true, false)
+                            Self::f64_pin_info(true, false)
                         } else {
                             #[cfg(debug_assertions)]
                             ui.label(
-                                RichText::new(format!("#{:?}", seed.as_node_id().unwrap()))
+                                RichText::new(format!("#{:?}", node.in_min.as_node_id().unwrap()))
                                     .color(Color32::DEBUG_COLOR),
                             );
 
-                            Self::u32_pin_info(true, true)
+                            Self::f64_pin_info(true, true)
                         }
                     }
-                    (0, NoiseNode::Checkerboard(CheckerboardNode { size, .. })) => {
-                        ui.label("Size");
+                    (1, NoiseNode::Slope(node)) => {
+                        ui.label("Epsilon");
 
-                        if let Some(value) = size.as_value_mut() {
-                            self.drag_value_u32(ui, scale, value, pin.id.node);
+                        if let Some(value) = node.epsilon.as_value_mut() {
+                            self.drag_value_f64(ui, scale, value, pin.id.node);
+
+                            Self::f64_pin_info(true, false)
+                        } else {
+                            #[cfg(debug_assertions)]
+                            ui.label(
+                                RichText::new(format!("#{:?}", node.epsilon.as_node_id().unwrap()))
+                                    .color(Color32::DEBUG_COLOR),
+                            );
+
+                            Self::f64_pin_info(true, true)
+                        }
+                    }
+                    (1, NoiseNode::Crater(node)) => {
+                        ui.label("Seed");
+
+                        if let Some(value) = node.seed.as_value_mut() {
+                            self.drag_value_seed(ui, scale, value, pin.id.node);
 
                             Self::u32_pin_info(true, false)
                         } else {
                             #[cfg(debug_assertions)]
                             ui.label(
-                                RichText::new(format!("#{:?}", size.as_node_id().unwrap()))
+                                RichText::new(format!("#{:?}", node.seed.as_node_id().unwrap()))
                                     .color(Color32::DEBUG_COLOR),
                             );
 
                             Self::u32_pin_info(true, true)
                         }
                     }
-                    (0, NoiseNode::ControlPoint(node)) => {
-                        ui.label("Input");
+                    (1, NoiseNode::Turbulence(node)) => {
+                        ui.label("Seed");
 
-                        if let Some(value) = node.input.as_value_mut() {
-                            self.drag_value_f64(ui, scale, value, pin.id.node);
+                        if let Some(value) = node.seed.as_value_mut() {
+                            self.drag_value_seed(ui, scale, value, pin.id.node);
 
-                            Self::f64_pin_info(true, false)
+                            Self::u32_pin_info(true, false)
                         } else {
                             #[cfg(debug_assertions)]
                             ui.label(
-                                RichText::new(format!("#{:?}", node.input.as_node_id().unwrap()))
+                                RichText::new(format!("#{:?}", node.seed.as_node_id().unwrap()))
                                     .color(Color32::DEBUG_COLOR),
                             );
 
-                            Self::f64_pin_info(true, true)
+                            Self::u32_pin_info(true, true)
                         }
                     }
-                    (0, NoiseNode::Cylinders(node)) => {
-                        ui.label("Frequency");
+                    (1..=4, NoiseNode::Displace(_)) => {
+                        ui.label(Self::AXES[pin.id.input - 1]);
 
-                        if let Some(value) = node.frequency.as_value_mut() {
+                        #[cfg(debug_assertions)]
+                        ui.label(
+                            RichText::new(format!("#{:?}", in_pin_remote_node(snarl, pin.id)))
+                                .color(Color32::DEBUG_COLOR),
+                        );
+
+                        Self::image_pin_info(true, !snarl.in_pin(pin.id).remotes.is_empty())
+                    }
+                    (
+                        1..=4,
+                        NoiseNode::RotatePoint(node)
+                        | NoiseNode::ScalePoint(node)
+                        | NoiseNode::TranslatePoint(node),
+                    ) if node.vector_node_id.is_some() => {
+                        ui.label(Self::AXES[pin.id.input - 1]);
+                        ui.weak("Vector");
+
+                        Self::f64_pin_info(true, node.axes[pin.id.input - 1].is_node_id())
+                    }
+                    (
+                        1..=4,
+                        NoiseNode::RotatePoint(node)
+                        | NoiseNode::ScalePoint(node)
+                        | NoiseNode::TranslatePoint(node),
+                    ) => {
+                        ui.label(Self::AXES[pin.id.input - 1]);
+
+                        if let Some(value) = node.axes[pin.id.input - 1].as_value_mut() {
                             self.drag_value_f64(ui, scale, value, pin.id.node);
 
                             Self::f64_pin_info(true, false)
@@ -1816,7 +6144,7 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                             ui.label(
                                 RichText::new(format!(
                                     "#{:?}",
-                                    node.frequency.as_node_id().unwrap()
+                                    node.axes[pin.id.input - 1].as_node_id().unwrap()
                                 ))
                                 .color(Color32::DEBUG_COLOR),
                             );
@@ -1825,38 +6153,45 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                         }
                     }
                     (
-                        0 | 1,
-                        NoiseNode::Add(_)
-                        | NoiseNode::Min(_)
-                        | NoiseNode::Max(_)
-                        | NoiseNode::Multiply(_)
-                        | NoiseNode::Power(_),
+                        5,
+                        NoiseNode::RotatePoint(node)
+                        | NoiseNode::ScalePoint(node)
+                        | NoiseNode::TranslatePoint(node),
                     ) => {
-                        ui.label("Source");
+                        ui.label("Vector");
 
                         #[cfg(debug_assertions)]
                         ui.label(
-                            RichText::new(format!("#{:?}", in_pin_remote_node(snarl, pin.id)))
+                            RichText::new(format!("#{:?}", node.vector_node_id))
                                 .color(Color32::DEBUG_COLOR),
                         );
 
-                        Self::image_pin_info(true, !snarl.in_pin(pin.id).remotes.is_empty())
+                        Self::vector_pin_info(true, node.vector_node_id.is_some())
                     }
-                    (0 | 1, NoiseNode::Blend(_) | NoiseNode::Select(_)) => {
-                        ui.label("Source");
+                    (0..=3, NoiseNode::Vector(node)) => {
+                        ui.label(Self::AXES[pin.id.input]);
 
-                        #[cfg(debug_assertions)]
-                        ui.label(
-                            RichText::new(format!("#{:?}", in_pin_remote_node(snarl, pin.id)))
+                        if let Some(value) = node.axes[pin.id.input].as_value_mut() {
+                            self.drag_value_f64(ui, scale, value, pin.id.node);
+
+                            Self::f64_pin_info(true, false)
+                        } else {
+                            #[cfg(debug_assertions)]
+                            ui.label(
+                                RichText::new(format!(
+                                    "#{:?}",
+                                    node.axes[pin.id.input].as_node_id().unwrap()
+                                ))
                                 .color(Color32::DEBUG_COLOR),
-                        );
+                            );
 
-                        Self::image_pin_info(true, !snarl.in_pin(pin.id).remotes.is_empty())
+                            Self::f64_pin_info(true, true)
+                        }
                     }
-                    (0 | 1, NoiseNode::F64Operation(node)) => {
-                        ui.label("Input");
+                    (1..=3, NoiseNode::RotateAxis(node)) => {
+                        ui.label(format!("Axis {}", Self::AXES[pin.id.input - 1]));
 
-                        if let Some(value) = node.inputs[pin.id.input].as_value_mut() {
+                        if let Some(value) = node.axis[pin.id.input - 1].as_value_mut() {
                             self.drag_value_f64(ui, scale, value, pin.id.node);
 
                             Self::f64_pin_info(true, false)
@@ -1865,7 +6200,7 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                             ui.label(
                                 RichText::new(format!(
                                     "#{:?}",
-                                    node.inputs[pin.id.input].as_node_id().unwrap()
+                                    node.axis[pin.id.input - 1].as_node_id().unwrap()
                                 ))
                                 .color(Color32::DEBUG_COLOR),
                             );
@@ -1873,109 +6208,225 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                             Self::f64_pin_info(true, true)
                         }
                     }
-                    (0 | 1, NoiseNode::Operation(node)) => {
-                        ui.label("Input");
+                    (1..=4, NoiseNode::TilePoint(node)) => {
+                        ui.label(Self::AXES[pin.id.input - 1])
+                            .on_hover_text("The period of the axis; zero leaves it untiled");
 
-                        if node.inputs[pin.id.input].as_node_id().is_none() {
-                            Self::operation_pin_info(true, false)
+                        if let Some(value) = node.periods[pin.id.input - 1].as_value_mut() {
+                            self.drag_value_f64(ui, scale, value, pin.id.node);
+
+                            Self::f64_pin_info(true, false)
                         } else {
                             #[cfg(debug_assertions)]
                             ui.label(
                                 RichText::new(format!(
                                     "#{:?}",
-                                    node.inputs[pin.id.input].as_node_id().unwrap()
+                                    node.periods[pin.id.input - 1].as_node_id().unwrap()
                                 ))
                                 .color(Color32::DEBUG_COLOR),
                             );
 
-                            Self::operation_pin_info(true, true)
+                            Self::f64_pin_info(true, true)
                         }
                     }
-                    (0 | 1, NoiseNode::U32Operation(node)) => {
-                        ui.label("Input");
+                    (1, NoiseNode::ScaleBias(node)) => {
+                        ui.label("Scale");
 
-                        if let Some(value) = node.inputs[pin.id.input].as_value_mut() {
-                            self.drag_value_u32(ui, scale, value, pin.id.node);
+                        if let Some(value) = node.scale.as_value_mut() {
+                            self.drag_value_f64(ui, scale, value, pin.id.node);
 
-                            Self::u32_pin_info(true, false)
+                            Self::f64_pin_info(true, false)
+                        } else {
+                            #[cfg(debug_assertions)]
+                            ui.label(
+                                RichText::new(format!("#{:?}", node.scale.as_node_id().unwrap()))
+                                    .color(Color32::DEBUG_COLOR),
+                            );
+
+                            Self::f64_pin_info(true, true)
+                        }
+                    }
+                    (1, NoiseNode::Worley(node)) => {
+                        ui.label("Frequency");
+
+                        if let Some(value) = node.frequency.as_value_mut() {
+                            self.drag_value_f64(ui, scale, value, pin.id.node);
+
+                            Self::f64_pin_info(true, false)
                         } else {
                             #[cfg(debug_assertions)]
                             ui.label(
                                 RichText::new(format!(
                                     "#{:?}",
-                                    node.inputs[pin.id.input].as_node_id().unwrap()
+                                    node.frequency.as_node_id().unwrap()
                                 ))
                                 .color(Color32::DEBUG_COLOR),
                             );
 
-                            Self::u32_pin_info(true, true)
+                            Self::f64_pin_info(true, true)
                         }
                     }
-                    (1, NoiseNode::ControlPoint(node)) => {
-                        ui.label("Output");
+                    (
+                        2,
+                        NoiseNode::BasicMulti(FractalNode { frequency, .. })
+                        | NoiseNode::Billow(FractalNode { frequency, .. })
+                        | NoiseNode::Erosion(ErosionNode { frequency, .. })
+                        | NoiseNode::Fbm(FractalNode { frequency, .. })
+                        | NoiseNode::GenericFractal(GenericFractalNode { frequency, .. })
+                        | NoiseNode::HybridMulti(FractalNode { frequency, .. })
+                        | NoiseNode::Jordan(FractalNode { frequency, .. })
+                        | NoiseNode::RigidMulti(RigidFractalNode { frequency, .. })
+                        | NoiseNode::Swiss(FractalNode { frequency, .. })
+                        | NoiseNode::WarpedFbm(WarpedFbmNode { frequency, .. }),
+                    ) => {
+                        ui.label("Frequency");
 
-                        if let Some(value) = node.output.as_value_mut() {
+                        if let Some(value) = frequency.as_value_mut() {
                             self.drag_value_f64(ui, scale, value, pin.id.node);
 
                             Self::f64_pin_info(true, false)
                         } else {
                             #[cfg(debug_assertions)]
                             ui.label(
-                                RichText::new(format!("#{:?}", node.output.as_node_id().unwrap()))
+                                RichText::new(format!("#{:?}", frequency.as_node_id().unwrap()))
                                     .color(Color32::DEBUG_COLOR),
                             );
 
                             Self::f64_pin_info(true, true)
                         }
                     }
-                    (
-                        1,
-                        NoiseNode::BasicMulti(FractalNode { octaves, .. })
-                        | NoiseNode::Billow(FractalNode { octaves, .. })
-                        | NoiseNode::Fbm(FractalNode { octaves, .. })
-                        | NoiseNode::HybridMulti(FractalNode { octaves, .. })
-                        | NoiseNode::RigidMulti(RigidFractalNode { octaves, .. }),
-                    ) => {
-                        ui.label("Octaves");
+                    (2, NoiseNode::BiasGain(node)) => {
+                        ui.label("Gain");
 
-                        if let Some(value) = octaves.as_value_mut() {
-                            self.drag_value_octaves(ui, scale, value, pin.id.node);
+                        if let Some(value) = node.gain.as_value_mut() {
+                            self.drag_value_f64(ui, scale, value, pin.id.node);
 
-                            Self::u32_pin_info(true, false)
+                            Self::f64_pin_info(true, false)
                         } else {
                             #[cfg(debug_assertions)]
                             ui.label(
-                                RichText::new(format!("#{:?}", octaves.as_node_id().unwrap()))
+                                RichText::new(format!("#{:?}", node.gain.as_node_id().unwrap()))
                                     .color(Color32::DEBUG_COLOR),
                             );
 
-                            Self::u32_pin_info(true, true)
+                            Self::f64_pin_info(true, true)
                         }
                     }
-                    (1, NoiseNode::Clamp(node)) => {
-                        ui.label("Lower Bound");
+                    (2, NoiseNode::Compare(node)) => {
+                        ui.label("Tolerance");
 
-                        if let Some(value) = node.lower_bound.as_value_mut() {
+                        if let Some(value) = node.tolerance.as_value_mut() {
+                            self.drag_value_f64(ui, scale, value, pin.id.node);
+
+                            Self::f64_pin_info(true, false)
+                        } else {
+                            #[cfg(debug_assertions)]
+                            ui.label(
+                                RichText::new(format!(
+                                    "#{:?}",
+                                    node.tolerance.as_node_id().unwrap()
+                                ))
+                                .color(Color32::DEBUG_COLOR),
+                            );
+
+                            Self::f64_pin_info(true, true)
+                        }
+                    }
+                    (2, NoiseNode::Contrast(node)) => {
+                        ui.label("Pivot");
+
+                        if let Some(value) = node.pivot.as_value_mut() {
+                            self.drag_value_f64(ui, scale, value, pin.id.node);
+
+                            Self::f64_pin_info(true, false)
+                        } else {
+                            #[cfg(debug_assertions)]
+                            ui.label(
+                                RichText::new(format!("#{:?}", node.pivot.as_node_id().unwrap()))
+                                    .color(Color32::DEBUG_COLOR),
+                            );
+
+                            Self::f64_pin_info(true, true)
+                        }
+                    }
+                    (2, NoiseNode::Crater(node)) => {
+                        ui.label("Density");
+
+                        if let Some(value) = node.density.as_value_mut() {
+                            self.drag_value_f64(ui, scale, value, pin.id.node);
+
+                            Self::f64_pin_info(true, false)
+                        } else {
+                            #[cfg(debug_assertions)]
+                            ui.label(
+                                RichText::new(format!("#{:?}", node.density.as_node_id().unwrap()))
+                                    .color(Color32::DEBUG_COLOR),
+                            );
+
+                            Self::f64_pin_info(true, true)
+                        }
+                    }
+                    (2, NoiseNode::DomainWarp(node)) => {
+                        ui.label("Amplitude");
+
+                        if let Some(value) = node.amplitude.as_value_mut() {
+                            self.drag_value_f64(ui, scale, value, pin.id.node);
+
+                            Self::f64_pin_info(true, false)
+                        } else {
+                            #[cfg(debug_assertions)]
+                            ui.label(
+                                RichText::new(format!(
+                                    "#{:?}",
+                                    node.amplitude.as_node_id().unwrap()
+                                ))
+                                .color(Color32::DEBUG_COLOR),
+                            );
+
+                            Self::f64_pin_info(true, true)
+                        }
+                    }
+                    (2, NoiseNode::FaultLines(node)) => {
+                        ui.label("Amplitude");
+
+                        if let Some(value) = node.amplitude.as_value_mut() {
+                            self.drag_value_f64(ui, scale, value, pin.id.node);
+
+                            Self::f64_pin_info(true, false)
+                        } else {
+                            #[cfg(debug_assertions)]
+                            ui.label(
+                                RichText::new(format!(
+                                    "#{:?}",
+                                    node.amplitude.as_node_id().unwrap()
+                                ))
+                                .color(Color32::DEBUG_COLOR),
+                            );
+
+                            Self::f64_pin_info(true, true)
+                        }
+                    }
+                    (2, NoiseNode::Mix(node)) => {
+                        ui.label("Factor");
+
+                        if let Some(value) = node.factor.as_value_mut() {
                             self.drag_value_f64(ui, scale, value, pin.id.node);
 
                             Self::f64_pin_info(true, false)
                         } else {
                             #[cfg(debug_assertions)]
                             ui.label(
-                                RichText::new(format!(
-                                    "#{:?}",
-                                    node.lower_bound.as_node_id().unwrap()
-                                ))
-                                .color(Color32::DEBUG_COLOR),
+                                RichText::new(format!("#{:?}", node.factor.as_node_id().unwrap()))
+                                    .color(Color32::DEBUG_COLOR),
                             );
 
                             Self::f64_pin_info(true, true)
                         }
                     }
-                    (1, NoiseNode::Exponent(node)) => {
-                        ui.label("Exponent");
+                    (2, NoiseNode::Normal(node)) => {
+                        ui.label("Strength");
 
-                        if let Some(value) = node.exponent.as_value_mut() {
+                        if let Some(value) = node.strength.as_value_mut() {
                             self.drag_value_f64(ui, scale, value, pin.id.node);
 
                             Self::f64_pin_info(true, false)
@@ -1984,7 +6435,7 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                             ui.label(
                                 RichText::new(format!(
                                     "#{:?}",
-                                    node.exponent.as_node_id().unwrap()
+                                    node.strength.as_node_id().unwrap()
                                 ))
                                 .color(Color32::DEBUG_COLOR),
                             );
@@ -1992,77 +6443,58 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                             Self::f64_pin_info(true, true)
                         }
                     }
-                    (1, NoiseNode::Turbulence(node)) => {
-                        ui.label("Seed");
+                    (2, NoiseNode::RandomConstant(node)) => {
+                        ui.label("Min");
 
-                        if let Some(value) = node.seed.as_value_mut() {
-                            self.drag_value_u32(ui, scale, value, pin.id.node);
+                        if let Some(value) = node.min.as_value_mut() {
+                            self.drag_value_f64(ui, scale, value, pin.id.node);
 
-                            Self::u32_pin_info(true, false)
+                            Self::f64_pin_info(true, false)
                         } else {
                             #[cfg(debug_assertions)]
                             ui.label(
-                                RichText::new(format!("#{:?}", node.seed.as_node_id().unwrap()))
+                                RichText::new(format!("#{:?}", node.min.as_node_id().unwrap()))
                                     .color(Color32::DEBUG_COLOR),
                             );
 
-                            Self::u32_pin_info(true, true)
+                            Self::f64_pin_info(true, true)
                         }
                     }
-                    (1..=4, NoiseNode::Displace(_)) => {
-                        ui.label(Self::AXES[pin.id.input - 1]);
-
-                        #[cfg(debug_assertions)]
-                        ui.label(
-                            RichText::new(format!("#{:?}", in_pin_remote_node(snarl, pin.id)))
-                                .color(Color32::DEBUG_COLOR),
-                        );
-
-                        Self::image_pin_info(true, !snarl.in_pin(pin.id).remotes.is_empty())
-                    }
-                    (
-                        1..=4,
-                        NoiseNode::RotatePoint(node)
-                        | NoiseNode::ScalePoint(node)
-                        | NoiseNode::TranslatePoint(node),
-                    ) => {
-                        ui.label(Self::AXES[pin.id.input - 1]);
+                    (2, NoiseNode::Remap(node)) => {
+                        ui.label("In Max");
 
-                        if let Some(value) = node.axes[pin.id.input - 1].as_value_mut() {
+                        if let Some(value) = node.in_max.as_value_mut() {
                             self.drag_value_f64(ui, scale, value, pin.id.node);
 
                             Self::f64_pin_info(true, false)
                         } else {
                             #[cfg(debug_assertions)]
                             ui.label(
-                                RichText::new(format!(
-                                    "#{:?}",
-                                    node.axes[pin.id.input - 1].as_node_id().unwrap()
-                                ))
-                                .color(Color32::DEBUG_COLOR),
+                                RichText::new(format!("#{:?}", node.in_max.as_node_id().unwrap()))
+                                    .color(Color32::DEBUG_COLOR),
                             );
 
                             Self::f64_pin_info(true, true)
                         }
                     }
-                    (1, NoiseNode::ScaleBias(node)) => {
-                        ui.label("Scale");
+                    (3, NoiseNode::Crater(node)) => {
+                        ui.label("Radius");
 
-                        if let Some(value) = node.scale.as_value_mut() {
+                        if let Some(value) = node.radius.as_value_mut() {
                             self.drag_value_f64(ui, scale, value, pin.id.node);
 
                             Self::f64_pin_info(true, false)
                         } else {
                             #[cfg(debug_assertions)]
                             ui.label(
-                                RichText::new(format!("#{:?}", node.scale.as_node_id().unwrap()))
+                                RichText::new(format!("#{:?}", node.radius.as_node_id().unwrap()))
                                     .color(Color32::DEBUG_COLOR),
                             );
 
                             Self::f64_pin_info(true, true)
                         }
                     }
-                    (1, NoiseNode::Worley(node)) => {
+                    (3, NoiseNode::DomainWarp(node)) => {
                         ui.label("Frequency");
 
                         if let Some(value) = node.frequency.as_value_mut() {
@@ -2082,30 +6514,6 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                             Self::f64_pin_info(true, true)
                         }
                     }
-                    (
-                        2,
-                        NoiseNode::BasicMulti(FractalNode { frequency, .. })
-                        | NoiseNode::Billow(FractalNode { frequency, .. })
-                        | NoiseNode::Fbm(FractalNode { frequency, .. })
-                        | NoiseNode::HybridMulti(FractalNode { frequency, .. })
-                        | NoiseNode::RigidMulti(RigidFractalNode { frequency, .. }),
-                    ) => {
-                        ui.label("Frequency");
-
-                        if let Some(value) = frequency.as_value_mut() {
-                            self.drag_value_f64(ui, scale, value, pin.id.node);
-
-                            Self::f64_pin_info(true, false)
-                        } else {
-                            #[cfg(debug_assertions)]
-                            ui.label(
-                                RichText::new(format!("#{:?}", frequency.as_node_id().unwrap()))
-                                    .color(Color32::DEBUG_COLOR),
-                            );
-
-                            Self::f64_pin_info(true, true)
-                        }
-                    }
                     (2, NoiseNode::Blend(_) | NoiseNode::Select(_)) => {
                         ui.label("Control");
 
@@ -2178,9 +6586,14 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                         3,
                         NoiseNode::BasicMulti(FractalNode { lacunarity, .. })
                         | NoiseNode::Billow(FractalNode { lacunarity, .. })
+                        | NoiseNode::Erosion(ErosionNode { lacunarity, .. })
                         | NoiseNode::Fbm(FractalNode { lacunarity, .. })
+                        | NoiseNode::GenericFractal(GenericFractalNode { lacunarity, .. })
                         | NoiseNode::HybridMulti(FractalNode { lacunarity, .. })
-                        | NoiseNode::RigidMulti(RigidFractalNode { lacunarity, .. }),
+                        | NoiseNode::Jordan(FractalNode { lacunarity, .. })
+                        | NoiseNode::RigidMulti(RigidFractalNode { lacunarity, .. })
+                        | NoiseNode::Swiss(FractalNode { lacunarity, .. })
+                        | NoiseNode::WarpedFbm(WarpedFbmNode { lacunarity, .. }),
                     ) => {
                         ui.label("Lacunarity");
 
@@ -2198,6 +6611,40 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                             Self::f64_pin_info(true, true)
                         }
                     }
+                    (3, NoiseNode::RandomConstant(node)) => {
+                        ui.label("Max");
+
+                        if let Some(value) = node.max.as_value_mut() {
+                            self.drag_value_f64(ui, scale, value, pin.id.node);
+
+                            Self::f64_pin_info(true, false)
+                        } else {
+                            #[cfg(debug_assertions)]
+                            ui.label(
+                                RichText::new(format!("#{:?}", node.max.as_node_id().unwrap()))
+                                    .color(Color32::DEBUG_COLOR),
+                            );
+
+                            Self::f64_pin_info(true, true)
+                        }
+                    }
+                    (3, NoiseNode::Remap(node)) => {
+                        ui.label("Out Min");
+
+                        if let Some(value) = node.out_min.as_value_mut() {
+                            self.drag_value_f64(ui, scale, value, pin.id.node);
+
+                            Self::f64_pin_info(true, false)
+                        } else {
+                            #[cfg(debug_assertions)]
+                            ui.label(
+                                RichText::new(format!("#{:?}", node.out_min.as_node_id().unwrap()))
+                                    .color(Color32::DEBUG_COLOR),
+                            );
+
+                            Self::f64_pin_info(true, true)
+                        }
+                    }
                     (3, NoiseNode::Select(node)) => {
                         ui.label("Lower Bound");
 
@@ -2239,9 +6686,14 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                         4,
                         NoiseNode::BasicMulti(FractalNode { persistence, .. })
                         | NoiseNode::Billow(FractalNode { persistence, .. })
+                        | NoiseNode::Erosion(ErosionNode { persistence, .. })
                         | NoiseNode::Fbm(FractalNode { persistence, .. })
+                        | NoiseNode::GenericFractal(GenericFractalNode { persistence, .. })
                         | NoiseNode::HybridMulti(FractalNode { persistence, .. })
-                        | NoiseNode::RigidMulti(RigidFractalNode { persistence, .. }),
+                        | NoiseNode::Jordan(FractalNode { persistence, .. })
+                        | NoiseNode::RigidMulti(RigidFractalNode { persistence, .. })
+                        | NoiseNode::Swiss(FractalNode { persistence, .. })
+                        | NoiseNode::WarpedFbm(WarpedFbmNode { persistence, .. }),
                     ) => {
                         ui.label("Persistence");
 
@@ -2259,6 +6711,57 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                             Self::f64_pin_info(true, true)
                         }
                     }
+                    (4, NoiseNode::Crater(node)) => {
+                        ui.label("Depth");
+
+                        if let Some(value) = node.depth.as_value_mut() {
+                            self.drag_value_f64(ui, scale, value, pin.id.node);
+
+                            Self::f64_pin_info(true, false)
+                        } else {
+                            #[cfg(debug_assertions)]
+                            ui.label(
+                                RichText::new(format!("#{:?}", node.depth.as_node_id().unwrap()))
+                                    .color(Color32::DEBUG_COLOR),
+                            );
+
+                            Self::f64_pin_info(true, true)
+                        }
+                    }
+                    (4, NoiseNode::Remap(node)) => {
+                        ui.label("Out Max");
+
+                        if let Some(value) = node.out_max.as_value_mut() {
+                            self.drag_value_f64(ui, scale, value, pin.id.node);
+
+                            Self::f64_pin_info(true, false)
+                        } else {
+                            #[cfg(debug_assertions)]
+                            ui.label(
+                                RichText::new(format!("#{:?}", node.out_max.as_node_id().unwrap()))
+                                    .color(Color32::DEBUG_COLOR),
+                            );
+
+                            Self::f64_pin_info(true, true)
+                        }
+                    }
+                    (4, NoiseNode::RotateAxis(node)) => {
+                        ui.label("Angle");
+
+                        if let Some(value) = node.angle.as_value_mut() {
+                            self.drag_value_f64(ui, scale, value, pin.id.node);
+
+                            Self::f64_pin_info(true, false)
+                        } else {
+                            #[cfg(debug_assertions)]
+                            ui.label(
+                                RichText::new(format!("#{:?}", node.angle.as_node_id().unwrap()))
+                                    .color(Color32::DEBUG_COLOR),
+                            );
+
+                            Self::f64_pin_info(true, true)
+                        }
+                    }
                     (4, NoiseNode::Select(node)) => {
                         ui.label("Upper Bound");
 
@@ -2299,6 +6802,26 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                             Self::u32_pin_info(true, true)
                         }
                     }
+                    (5, NoiseNode::Erosion(node)) => {
+                        ui.label("Strength");
+
+                        if let Some(value) = node.strength.as_value_mut() {
+                            self.drag_value_f64(ui, scale, value, pin.id.node);
+
+                            Self::f64_pin_info(true, false)
+                        } else {
+                            #[cfg(debug_assertions)]
+                            ui.label(
+                                RichText::new(format!(
+                                    "#{:?}",
+                                    node.strength.as_node_id().unwrap()
+                                ))
+                                .color(Color32::DEBUG_COLOR),
+                            );
+
+                            Self::f64_pin_info(true, true)
+                        }
+                    }
                     (5, NoiseNode::RigidMulti(node)) => {
                         ui.label("Attenuation");
 
@@ -2336,6 +6859,46 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                             Self::f64_pin_info(true, true)
                         }
                     }
+                    (5, NoiseNode::WarpedFbm(node)) => {
+                        ui.label("Strength");
+
+                        if let Some(value) = node.strength.as_value_mut() {
+                            self.drag_value_f64(ui, scale, value, pin.id.node);
+
+                            Self::f64_pin_info(true, false)
+                        } else {
+                            #[cfg(debug_assertions)]
+                            ui.label(
+                                RichText::new(format!(
+                                    "#{:?}",
+                                    node.strength.as_node_id().unwrap()
+                                ))
+                                .color(Color32::DEBUG_COLOR),
+                            );
+
+                            Self::f64_pin_info(true, true)
+                        }
+                    }
+                    (6, NoiseNode::WarpedFbm(node)) => {
+                        ui.label("Iterations");
+
+                        if let Some(value) = node.iterations.as_value_mut() {
+                            self.drag_value_u32(ui, scale, value, pin.id.node);
+
+                            Self::u32_pin_info(true, false)
+                        } else {
+                            #[cfg(debug_assertions)]
+                            ui.label(
+                                RichText::new(format!(
+                                    "#{:?}",
+                                    node.iterations.as_node_id().unwrap()
+                                ))
+                                .color(Color32::DEBUG_COLOR),
+                            );
+
+                            Self::u32_pin_info(true, true)
+                        }
+                    }
                     (control_point_idx, NoiseNode::Curve(node)) => {
                         ui.label("Control Point");
 
@@ -2414,6 +6977,54 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                             Self::f64_pin_info(true, true)
                         }
                     }
+                    (0, NoiseNode::SubgraphOutput(_)) => {
+                        ui.label("Source");
+
+                        #[cfg(debug_assertions)]
+                        ui.label(
+                            RichText::new(format!("#{:?}", in_pin_remote_node(snarl, pin.id)))
+                                .color(Color32::DEBUG_COLOR),
+                        );
+
+                        Self::image_pin_info(true, !snarl.in_pin(pin.id).remotes.is_empty())
+                    }
+                    (input, NoiseNode::Formula(_) | NoiseNode::Script(_)) => {
+                        // The name used by the formula
+                        ui.label(match input {
+                            0 => "source".to_owned(),
+                            _ => format!("s{input}"),
+                        });
+
+                        #[cfg(debug_assertions)]
+                        ui.label(
+                            RichText::new(format!("#{:?}", in_pin_remote_node(snarl, pin.id)))
+                                .color(Color32::DEBUG_COLOR),
+                        );
+
+                        Self::image_pin_info(true, !snarl.in_pin(pin.id).remotes.is_empty())
+                    }
+                    (input, NoiseNode::Subgraph(_)) => {
+                        ui.label(format!("Input {}", input + 1));
+
+                        #[cfg(debug_assertions)]
+                        ui.label(
+                            RichText::new(format!("#{:?}", in_pin_remote_node(snarl, pin.id)))
+                                .color(Color32::DEBUG_COLOR),
+                        );
+
+                        Self::image_pin_info(true, !snarl.in_pin(pin.id).remotes.is_empty())
+                    }
+                    (input, NoiseNode::Switch(_)) => {
+                        ui.label(format!("Source {input}"));
+
+                        #[cfg(debug_assertions)]
+                        ui.label(
+                            RichText::new(format!("#{:?}", in_pin_remote_node(snarl, pin.id)))
+                                .color(Color32::DEBUG_COLOR),
+                        );
+
+                        Self::image_pin_info(true, !snarl.in_pin(pin.id).remotes.is_empty())
+                    }
                     _ => unreachable!(),
                 }
             },
@@ -2429,44 +7040,163 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
         snarl: &mut Snarl<NoiseNode>,
     ) -> PinInfo {
         let node = snarl.get_node(pin.id.node).unwrap();
+        let mut view = None;
+
+        if let Some(image) = node.image().filter(|image| !image.disabled) {
+            if let Some(texture) = &image.texture {
+                let response = ui.add(
+                    Image::new((texture.id(), texture.size_vec2() * scale))
+                        .sense(Sense::click_and_drag()),
+                );
+
+                if response.double_clicked() {
+                    *self.preview_window = Some(PreviewWindow::new(pin.id.node));
+                }
+
+                // Dragging pans the image and scrolling zooms it about the hovered point; the
+                // horizontal axis of the image is offset by `x` (as in `process_image_request`)
+                let drag = response.drag_delta() / response.rect.size();
+                let zoom = if response.hovered() {
+                    ui.ctx().input_mut(|input| {
+                        let scroll = input.smooth_scroll_delta.y;
+                        input.smooth_scroll_delta = Vec2::ZERO;
+
+                        (-scroll as f64 * 0.002).exp()
+                    })
+                } else {
+                    1.0
+                };
+
+                if drag != Vec2::ZERO || zoom != 1.0 {
+                    let uv = response
+                        .hover_pos()
+                        .map(|pos| (pos - response.rect.min) / response.rect.size())
+                        .unwrap_or(Vec2::splat(0.5));
+                    let image_scale = (image.scale * zoom).clamp(1e-6, 1e6);
+                    let offset = |offset: f64, uv: f32, drag: f32| {
+                        (uv as f64 + offset) * image.scale / image_scale - uv as f64 - drag as f64
+                    };
+
+                    view = Some((
+                        image_scale,
+                        offset(image.x, uv.x, drag.x),
+                        offset(image.y, uv.y, drag.y),
+                    ));
+                }
+
+                // The hovered pixel is evaluated on demand at its center; preview rows run along
+                // the X axis of the noise function (see `Threads::process_image_request`)
+                let hovered_sample = response.hover_pos().map(|pos| {
+                    let size = image.resolution.size() as f32;
+                    let uv = ((pos - response.rect.min) / response.rect.size() * size)
+                        .floor()
+                        .clamp(Vec2::ZERO, Vec2::splat(size - 1.0))
+                        + Vec2::splat(0.5);
+                    let x = (uv.y as f64 / size as f64 + image.y) * image.scale;
+                    let y = (uv.x as f64 / size as f64 + image.x) * image.scale;
+
+                    (
+                        [x, y],
+                        node.expr(pin.id.node, snarl)
+                            .noise_at_w(self.w)
+                            .get([x, y, 0.0]),
+                    )
+                });
+
+                Self::image_stats(ui, response, &image.stats, scale, hovered_sample);
+            }
+        }
 
-        if let Some(texture) = node.image().and_then(|image| image.texture.as_ref()) {
-            ui.image((texture.id(), texture.size_vec2() * scale));
+        if let Some((image_scale, x, y)) = view {
+            let node_ids = if self.linked_previews {
+                snarl
+                    .node_ids()
+                    .filter_map(|(node_id, node)| node.has_image().then_some(node_id))
+                    .collect()
+            } else {
+                vec![pin.id.node]
+            };
+
+            for node_id in node_ids {
+                let image = snarl
+                    .get_node_mut(node_id)
+                    .and_then(NoiseNode::image_mut)
+                    .unwrap();
+                image.scale = image_scale;
+                image.x = x;
+                image.y = y;
+                self.updated_node_ids.insert(node_id);
+            }
         }
 
+        let node = snarl.get_node(pin.id.node).unwrap();
+
         match node {
             NoiseNode::Abs(_)
             | NoiseNode::Add(_)
             | NoiseNode::BasicMulti(_)
+            | NoiseNode::BiasGain(_)
             | NoiseNode::Billow(_)
             | NoiseNode::Blend(_)
+            | NoiseNode::BlueNoise(_)
+            | NoiseNode::Cache(_)
             | NoiseNode::Checkerboard(_)
             | NoiseNode::Clamp(_)
+            | NoiseNode::ColorRamp(_)
+            | NoiseNode::CombineRgb(_)
+            | NoiseNode::Compare(_)
+            | NoiseNode::Contrast(_)
+            | NoiseNode::Crater(_)
             | NoiseNode::Curve(_)
             | NoiseNode::Cylinders(_)
+            | NoiseNode::Difference(_)
             | NoiseNode::Displace(_)
+            | NoiseNode::DomainWarp(_)
+            | NoiseNode::Ease(_)
+            | NoiseNode::Erosion(_)
             | NoiseNode::Exponent(_)
+            | NoiseNode::FaultLines(_)
             | NoiseNode::Fbm(_)
+            | NoiseNode::Formula(_)
+            | NoiseNode::GenericFractal(_)
+            | NoiseNode::Gradient(_)
             | NoiseNode::HybridMulti(_)
+            | NoiseNode::ImageSource(_)
+            | NoiseNode::Jordan(_)
             | NoiseNode::Min(_)
             | NoiseNode::Max(_)
+            | NoiseNode::MirrorPoint(_)
+            | NoiseNode::Mix(_)
             | NoiseNode::Multiply(_)
             | NoiseNode::Negate(_)
+            | NoiseNode::Normal(_)
+            | NoiseNode::Normalize(_)
             | NoiseNode::OpenSimplex(_)
             | NoiseNode::Perlin(_)
             | NoiseNode::PerlinSurflet(_)
             | NoiseNode::Power(_)
+            | NoiseNode::Remap(_)
             | NoiseNode::RigidMulti(_)
+            | NoiseNode::RotateAxis(_)
             | NoiseNode::RotatePoint(_)
             | NoiseNode::ScaleBias(_)
             | NoiseNode::ScalePoint(_)
+            | NoiseNode::Script(_)
             | NoiseNode::Select(_)
             | NoiseNode::Simplex(_)
+            | NoiseNode::Slope(_)
+            | NoiseNode::Subgraph(_)
+            | NoiseNode::SubgraphInput(_)
             | NoiseNode::SuperSimplex(_)
+            | NoiseNode::Swiss(_)
+            | NoiseNode::Switch(_)
+            | NoiseNode::Swizzle(_)
             | NoiseNode::Terrace(_)
+            | NoiseNode::TilePoint(_)
             | NoiseNode::TranslatePoint(_)
             | NoiseNode::Turbulence(_)
             | NoiseNode::Value(_)
+            | NoiseNode::WarpedFbm(_)
             | NoiseNode::Worley(_) => Self::image_pin_info(
                 false,
                 !snarl
@@ -2477,6 +7207,9 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                     .remotes
                     .is_empty(),
             ),
+            NoiseNode::Comment(_) | NoiseNode::Group(_) | NoiseNode::SubgraphOutput(_) => {
+                unreachable!()
+            }
             NoiseNode::ControlPoint(_) => Self::control_point_pin_info(
                 false,
                 !snarl
@@ -2487,7 +7220,20 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
                     .remotes
                     .is_empty(),
             ),
-            NoiseNode::F64(_) | NoiseNode::F64Operation(_) => Self::f64_pin_info(
+            NoiseNode::Vector(_) => Self::vector_pin_info(
+                false,
+                !snarl
+                    .out_pin(OutPinId {
+                        node: pin.id.node,
+                        output: 0,
+                    })
+                    .remotes
+                    .is_empty(),
+            ),
+            NoiseNode::F64(_)
+            | NoiseNode::F64Operation(_)
+            | NoiseNode::RandomConstant(_)
+            | NoiseNode::Time(_) => Self::f64_pin_info(
                 false,
                 !snarl
                     .out_pin(OutPinId {
@@ -2520,643 +7266,605 @@ impl<'a> SnarlViewer<NoiseNode> for Viewer<'a> {
         }
     }
 
-    fn has_graph_menu(&mut self, _pos: egui::Pos2, _snarl: &mut Snarl<NoiseNode>) -> bool {
-        true
+    fn node_frame(
+        &mut self,
+        default: Frame,
+        node_id: NodeId,
+        _inputs: &[InPin],
+        _outputs: &[OutPin],
+        snarl: &Snarl<NoiseNode>,
+    ) -> Frame {
+        if let Some(dependency_chain) = &self.dependency_chain {
+            return dependency_chain.frame(default, node_id);
+        }
+
+        // Groups and their members are outlined in the group color
+        let group_node_id = match snarl.get_node(node_id) {
+            Some(NoiseNode::Group(_)) => Some(node_id),
+            _ => Self::group_node_id(node_id, snarl),
+        };
+
+        match group_node_id.and_then(|node_id| snarl.get_node(node_id)) {
+            Some(NoiseNode::Group(group)) => default.stroke(Stroke::new(2.0, group.color)),
+            _ => default,
+        }
+    }
+
+    fn has_body(&mut self, node: &NoiseNode) -> bool {
+        matches!(
+            node,
+            NoiseNode::ColorRamp(_)
+                | NoiseNode::Comment(_)
+                | NoiseNode::Curve(CurveNode { editor: true, .. })
+                | NoiseNode::Formula(_)
+                | NoiseNode::Script(_)
+                | NoiseNode::Swizzle(_)
+                | NoiseNode::Terrace(TerraceNode { editor: true, .. })
+        )
     }
 
-    fn show_graph_menu(
+    fn show_body(
         &mut self,
-        pos: Pos2,
+        node_id: NodeId,
+        _inputs: &[InPin],
+        _outputs: &[OutPin],
         ui: &mut Ui,
-        _scale: f32,
+        scale: f32,
         snarl: &mut Snarl<NoiseNode>,
     ) {
-        ui.label("Add node");
+        // Formulas refer to constants by name, so errors depend on the rest of the graph
+        let formula_error = match snarl.get_node(node_id) {
+            Some(NoiseNode::Formula(node)) => node.parse(snarl).err(),
+            _ => None,
+        };
+        let script_error = match snarl.get_node(node_id) {
+            Some(NoiseNode::Script(node)) => node.check().err(),
+            _ => None,
+        };
+        let control_points = match snarl.get_node(node_id) {
+            Some(NoiseNode::Curve(node)) => node.control_points(snarl),
+            _ => Vec::new(),
+        };
+        let terrace_points = match snarl.get_node(node_id) {
+            Some(NoiseNode::Terrace(node)) => node.control_points(snarl),
+            _ => Vec::new(),
+        };
+
+        let node = match snarl.get_node_mut(node_id) {
+            Some(NoiseNode::ColorRamp(node)) => {
+                if Self::color_ramp_editor(ui, &mut node.ramp, scale) {
+                    self.updated_node_ids.insert(node_id);
+                }
 
-        ui.menu_button("Combiners", |ui| {
-            if ui.button("Add").clicked() {
-                self.updated_node_ids
-                    .insert(snarl.insert_node(pos, NoiseNode::Add(Default::default())));
-                ui.close_menu();
+                return;
             }
+            Some(NoiseNode::Comment(node)) => node,
+            Some(NoiseNode::Curve(node)) => {
+                if Self::curve_editor(ui, node, control_points, scale) {
+                    self.updated_node_ids.insert(node_id);
+                }
 
-            if ui.button("Min").clicked() {
-                self.updated_node_ids
-                    .insert(snarl.insert_node(pos, NoiseNode::Min(Default::default())));
-                ui.close_menu();
+                return;
             }
+            Some(NoiseNode::Formula(node)) => {
+                let mut text_edit = TextEdit::singleline(&mut node.formula)
+                    .code_editor()
+                    .desired_width(160.0 * scale)
+                    .hint_text("sin(x * 3) * a + source");
+
+                if formula_error.is_some() {
+                    text_edit = text_edit.text_color(Color32::RED);
+                }
 
-            if ui.button("Max").clicked() {
-                self.updated_node_ids
-                    .insert(snarl.insert_node(pos, NoiseNode::Max(Default::default())));
-                ui.close_menu();
-            }
+                let response = ui.add(text_edit);
 
-            if ui.button("Multiply").clicked() {
-                self.updated_node_ids
-                    .insert(snarl.insert_node(pos, NoiseNode::Multiply(Default::default())));
-                ui.close_menu();
-            }
+                if response.changed() {
+                    self.updated_node_ids.insert(node_id);
+                }
 
-            if ui.button("Power").clicked() {
-                self.updated_node_ids
-                    .insert(snarl.insert_node(pos, NoiseNode::Power(Default::default())));
-                ui.close_menu();
-            }
-        });
-        ui.menu_button("Generators", |ui| {
-            if ui.button("Checkerboard").clicked() {
-                self.updated_node_ids
-                    .insert(snarl.insert_node(pos, NoiseNode::Checkerboard(Default::default())));
-                ui.close_menu();
-            }
+                if let Some(err) = formula_error {
+                    response.on_hover_text(err.to_string());
+                }
 
-            if ui.button("Cylinders").clicked() {
-                self.updated_node_ids
-                    .insert(snarl.insert_node(pos, NoiseNode::Cylinders(Default::default())));
-                ui.close_menu();
+                return;
             }
+            Some(NoiseNode::Script(node)) => {
+                let mut text_edit = TextEdit::multiline(&mut node.script)
+                    .code_editor()
+                    .desired_rows(3)
+                    .desired_width(192.0 * scale);
+
+                if script_error.is_some() {
+                    text_edit = text_edit.text_color(Color32::RED);
+                }
 
-            if ui.button("Open Simplex").clicked() {
-                self.updated_node_ids
-                    .insert(snarl.insert_node(pos, NoiseNode::OpenSimplex(Default::default())));
-                ui.close_menu();
-            }
+                let response = ui.add(text_edit);
 
-            if ui.button("Perlin").clicked() {
-                self.updated_node_ids
-                    .insert(snarl.insert_node(pos, NoiseNode::Perlin(Default::default())));
-                ui.close_menu();
-            }
+                if response.changed() {
+                    self.updated_node_ids.insert(node_id);
+                }
 
-            if ui.button("Perlin Surflet").clicked() {
-                self.updated_node_ids
-                    .insert(snarl.insert_node(pos, NoiseNode::PerlinSurflet(Default::default())));
-                ui.close_menu();
-            }
+                if let Some(err) = script_error {
+                    response.on_hover_text(err);
+                }
 
-            if ui.button("Simplex").clicked() {
-                self.updated_node_ids
-                    .insert(snarl.insert_node(pos, NoiseNode::Simplex(Default::default())));
-                ui.close_menu();
+                return;
             }
+            Some(NoiseNode::Swizzle(node)) => {
+                Grid::new(("swizzle", node_id)).show(ui, |ui| {
+                    ui.spacing_mut().combo_width = 48.0;
 
-            if ui.button("Super Simplex").clicked() {
-                self.updated_node_ids
-                    .insert(snarl.insert_node(pos, NoiseNode::SuperSimplex(Default::default())));
-                ui.close_menu();
-            }
+                    for (axis, component) in Self::AXES.into_iter().zip(&mut node.components) {
+                        ui.label(axis);
+                        self.enum_combo_box(ui, axis, component, &SwizzleComponent::ALL, node_id);
+                        ui.end_row();
+                    }
+                });
 
-            if ui.button("Value").clicked() {
-                self.updated_node_ids
-                    .insert(snarl.insert_node(pos, NoiseNode::Value(Default::default())));
-                ui.close_menu();
+                return;
             }
+            Some(NoiseNode::Terrace(node)) => {
+                if Self::terrace_editor(ui, node, terrace_points, scale) {
+                    self.updated_node_ids.insert(node_id);
+                }
 
-            if ui.button("Worley").clicked() {
-                self.updated_node_ids
-                    .insert(snarl.insert_node(pos, NoiseNode::Worley(Default::default())));
-                ui.close_menu();
-            }
-        });
-        ui.menu_button("Fractals", |ui| {
-            if ui.button("Basic Multi").clicked() {
-                self.updated_node_ids
-                    .insert(snarl.insert_node(pos, NoiseNode::BasicMulti(Default::default())));
-                ui.close_menu();
+                return;
             }
+            _ => return,
+        };
+
+        Resize::default()
+            .id_salt(("comment", node_id))
+            .default_size(node.size * scale)
+            .min_size(vec2(64.0, 32.0) * scale)
+            .show(ui, |ui| {
+                node.size = ui.available_size() / scale;
+
+                Frame::none()
+                    .fill(node.color)
+                    .inner_margin(4.0 * scale)
+                    .show(ui, |ui| {
+                        ui.add_sized(
+                            ui.available_size(),
+                            TextEdit::multiline(&mut node.text)
+                                .frame(false)
+                                .hint_text("Comment")
+                                .text_color(Color32::BLACK),
+                        );
+                    });
+            });
+    }
 
-            if ui.button("Hybrid Multi").clicked() {
-                self.updated_node_ids
-                    .insert(snarl.insert_node(pos, NoiseNode::HybridMulti(Default::default())));
-                ui.close_menu();
-            }
+    fn has_graph_menu(&mut self, _pos: egui::Pos2, _snarl: &mut Snarl<NoiseNode>) -> bool {
+        true
+    }
 
-            if ui.button("Rigid Multi").clicked() {
+    fn show_graph_menu(
+        &mut self,
+        pos: Pos2,
+        ui: &mut Ui,
+        _scale: f32,
+        snarl: &mut Snarl<NoiseNode>,
+    ) {
+        if !self.clipboard.is_empty() {
+            if ui.button("Paste").clicked() {
                 self.updated_node_ids
-                    .insert(snarl.insert_node(pos, NoiseNode::RigidMulti(Default::default())));
+                    .extend(self.clipboard.paste(snarl, pos).into_values());
                 ui.close_menu();
             }
 
-            if ui.button("Billow").clicked() {
-                self.updated_node_ids
-                    .insert(snarl.insert_node(pos, NoiseNode::Billow(Default::default())));
-                ui.close_menu();
-            }
+            ui.separator();
+        }
 
-            if ui.button("fBm").clicked() {
-                self.updated_node_ids
-                    .insert(snarl.insert_node(pos, NoiseNode::Fbm(Default::default())));
-                ui.close_menu();
-            }
+        ui.label("Add node");
+
+        for category in [
+            "Combiners",
+            "Generators",
+            "Fractals",
+            "Modifiers",
+            "Selectors",
+            "Transformers",
+        ] {
+            ui.menu_button(category, |ui| {
+                self.show_node_type_buttons(pos, ui, snarl, category);
+            });
+        }
+
+        ui.menu_button("Constants", |ui| {
+            self.show_node_type_buttons(pos, ui, snarl, "Constants");
+
+            ui.separator();
+            ui.label("Operations");
+
+            self.show_node_type_buttons(pos, ui, snarl, "Operations");
         });
-        ui.menu_button("Modifiers", |ui| {
-            if ui.button("Abs").clicked() {
-                self.updated_node_ids
-                    .insert(snarl.insert_node(pos, NoiseNode::Abs(Default::default())));
-                ui.close_menu();
-            }
 
-            if ui.button("Clamp").clicked() {
-                self.updated_node_ids
-                    .insert(snarl.insert_node(pos, NoiseNode::Clamp(Default::default())));
+        if !self.presets.presets.is_empty() {
+            ui.menu_button("Presets", |ui| {
+                let mut removed = None;
+
+                for (idx, preset) in self.presets.presets.iter().enumerate() {
+                    ui.horizontal(|ui| {
+                        if ui.button(&preset.name).clicked() {
+                            preset.insert(snarl, pos, self.updated_node_ids);
+                            ui.close_menu();
+                        }
+
+                        if ui
+                            .small_button("🗑")
+                            .on_hover_text("Delete preset")
+                            .clicked()
+                        {
+                            removed = Some(idx);
+                        }
+                    });
+                }
+
+                if let Some(idx) = removed {
+                    self.presets.presets.remove(idx);
+                }
+            });
+        }
+
+        ui.separator();
+
+        self.show_node_type_buttons(pos, ui, snarl, "Annotations");
+    }
+
+    fn show_node_menu(
+        &mut self,
+        node_id: NodeId,
+        _inputs: &[InPin],
+        _outputs: &[OutPin],
+        ui: &mut Ui,
+        _scale: f32,
+        snarl: &mut Snarl<NoiseNode>,
+    ) {
+        ui.label("Node menu");
+
+        let node = snarl.get_node_mut(node_id).unwrap();
+        let name = node.name();
+        if let Some(title) = node.title_mut() {
+            let mut text = title.clone().unwrap_or_default();
+
+            ui.horizontal(|ui| {
+                ui.label("Title");
+
+                if ui
+                    .add(
+                        TextEdit::singleline(&mut text)
+                            .hint_text(name)
+                            .desired_width(128.0),
+                    )
+                    .changed()
+                {
+                    *title = (!text.is_empty()).then_some(text);
+                }
+            });
+
+            ui.separator();
+        }
+
+        if !matches!(
+            snarl.get_node(node_id),
+            Some(
+                NoiseNode::Comment(_)
+                    | NoiseNode::Group(_)
+                    | NoiseNode::SubgraphInput(_)
+                    | NoiseNode::SubgraphOutput(_)
+            )
+        ) {
+            ui.menu_button("Save as Preset", |ui| {
+                // The name being typed is kept until the menu closes
+                let id = Id::new("preset_name");
+                let mut name = ui.data_mut(|data| {
+                    data.get_temp::<String>(id).unwrap_or_else(|| {
+                        let node = snarl.get_node(node_id).unwrap();
+                        node.title().unwrap_or(node.name()).to_owned()
+                    })
+                });
+
+                ui.horizontal(|ui| {
+                    ui.label("Name");
+                    ui.add(TextEdit::singleline(&mut name).desired_width(128.0));
+                });
+
+                if ui
+                    .add_enabled(!name.trim().is_empty(), Button::new("Save"))
+                    .on_hover_text("Replaces any preset with the same name")
+                    .clicked()
+                {
+                    self.presets.insert(name.trim().to_owned(), node_id, snarl);
+                    ui.data_mut(|data| data.remove::<String>(id));
+
+                    ui.close_menu();
+                } else {
+                    ui.data_mut(|data| data.insert_temp(id, name));
+                }
+            });
+
+            ui.separator();
+        }
+
+        if let Some(NoiseNode::Subgraph(_)) = snarl.get_node(node_id) {
+            if ui.button("Edit Subgraph").clicked() {
+                self.edit_subgraph = Some(node_id);
+
                 ui.close_menu();
             }
 
-            if ui.button("Curve").clicked() {
-                self.updated_node_ids
-                    .insert(snarl.insert_node(pos, NoiseNode::Curve(Default::default())));
-                ui.close_menu();
+            ui.separator();
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let node = snarl.get_node(node_id).unwrap();
+
+            match node {
+                NoiseNode::Comment(_)
+                | NoiseNode::ControlPoint(_)
+                | NoiseNode::F64(_)
+                | NoiseNode::F64Operation(_)
+                | NoiseNode::Group(_)
+                | NoiseNode::Operation(_)
+                | NoiseNode::RandomConstant(_)
+                | NoiseNode::SubgraphInput(_)
+                | NoiseNode::SubgraphOutput(_)
+                | NoiseNode::Time(_)
+                | NoiseNode::U32(_)
+                | NoiseNode::U32Operation(_) => (),
+                _ => {
+                    if ui.button("Export File...").clicked() {
+                        if let Some(path) = App::file_dialog().save_file() {
+                            App::save_as(path, &node.expr(node_id, snarl)).unwrap_or_default();
+                        }
+
+                        ui.close_menu();
+                    }
+
+                    if ui.button("Export Bundle...").clicked() {
+                        if let Some(path) = App::file_dialog().save_file() {
+                            App::save_as(path, &expr_bundle(node_id, snarl)).unwrap_or_default();
+                        }
+
+                        ui.close_menu();
+                    }
+
+                    if ui.button("Export Animation...").clicked() {
+                        *self.animation_export = Some(AnimationExport::new(
+                            expr_bundle(node_id, snarl),
+                            node.image().unwrap(),
+                        ));
+
+                        ui.close_menu();
+                    }
+
+                    if ui.button("Export Normal Map...").clicked() {
+                        *self.normal_map_export = Some(NormalMapExport::new(
+                            node.expr(node_id, snarl),
+                            node.image().unwrap(),
+                        ));
+
+                        ui.close_menu();
+                    }
+
+                    if ui.button("Export Raw...").clicked() {
+                        *self.raw_export = Some(RawExport::new(node.expr(node_id, snarl)));
+
+                        ui.close_menu();
+                    }
+
+                    let selected_node_ids = get_selected_nodes(Id::new("snarl"), ui.ctx())
+                        .into_iter()
+                        .filter(|node_id| {
+                            snarl.get_node(*node_id).is_some_and(NoiseNode::has_image)
+                        })
+                        .collect::<Vec<_>>();
+
+                    if selected_node_ids.len() > 1
+                        && selected_node_ids.contains(&node_id)
+                        && ui
+                            .button(format!("Export Selected ({})...", selected_node_ids.len()))
+                            .clicked()
+                    {
+                        *self.batch_export = Some(BatchExport::new(selected_node_ids));
+
+                        ui.close_menu();
+                    }
+
+                    ui.separator();
+
+                    if ui.button("Copy Expr (RON)").clicked() {
+                        if let Ok(text) =
+                            to_string_pretty(&node.expr(node_id, snarl), PrettyConfig::default())
+                                .inspect_err(|_| warn!("Unable to serialize expression"))
+                        {
+                            ui.ctx().copy_text(text);
+                        }
+
+                        ui.close_menu();
+                    }
+
+                    if ui.button("Copy Expr (JSON)").clicked() {
+                        if let Ok(text) = serde_json::to_string_pretty(&node.expr(node_id, snarl))
+                            .inspect_err(|_| warn!("Unable to serialize expression"))
+                        {
+                            ui.ctx().copy_text(text);
+                        }
+
+                        ui.close_menu();
+                    }
+
+                    ui.separator();
+                }
             }
+        }
+
+        if let Some(image) = snarl.get_node_mut(node_id).and_then(NoiseNode::image_mut) {
+            ui.menu_button("Preview Resolution", |ui| {
+                for resolution in ImageResolution::ALL {
+                    if ui
+                        .radio(image.resolution == resolution, resolution.label())
+                        .clicked()
+                    {
+                        image.resolution = resolution;
+                        self.updated_node_ids.insert(node_id);
+
+                        ui.close_menu();
+                    }
+                }
+            });
+
+            ui.menu_button("Preview Colormap", |ui| {
+                for colormap in Colormap::ALL {
+                    if ui
+                        .radio(image.colormap == colormap, colormap.label())
+                        .clicked()
+                    {
+                        image.colormap = colormap;
+                        self.updated_node_ids.insert(node_id);
+
+                        ui.close_menu();
+                    }
+                }
+            });
 
-            if ui.button("Exponent").clicked() {
-                self.updated_node_ids
-                    .insert(snarl.insert_node(pos, NoiseNode::Exponent(Default::default())));
-                ui.close_menu();
-            }
+            if ui.button("Preview Region...").clicked() {
+                *self.region_dialog = Some(RegionDialog::new(node_id, image));
 
-            if ui.button("Negate").clicked() {
-                self.updated_node_ids
-                    .insert(snarl.insert_node(pos, NoiseNode::Negate(Default::default())));
                 ui.close_menu();
             }
 
-            if ui.button("Scale + Bias").clicked() {
-                self.updated_node_ids
-                    .insert(snarl.insert_node(pos, NoiseNode::ScaleBias(Default::default())));
-                ui.close_menu();
+            if ui.checkbox(&mut image.shaded, "Shaded Preview").changed() {
+                self.updated_node_ids.insert(node_id);
             }
 
-            if ui.button("Terrace").clicked() {
-                self.updated_node_ids
-                    .insert(snarl.insert_node(pos, NoiseNode::Terrace(Default::default())));
-                ui.close_menu();
-            }
-        });
-        ui.menu_button("Selectors", |ui| {
-            if ui.button("Blend").clicked() {
-                self.updated_node_ids
-                    .insert(snarl.insert_node(pos, NoiseNode::Blend(Default::default())));
-                ui.close_menu();
-            }
+            ui.menu_button("Contour Lines", |ui| {
+                let contours = image.contours;
 
-            if ui.button("Select").clicked() {
-                self.updated_node_ids
-                    .insert(snarl.insert_node(pos, NoiseNode::Select(Default::default())));
-                ui.close_menu();
-            }
-        });
-        ui.menu_button("Transformers", |ui| {
-            if ui.button("Displace").clicked() {
-                self.updated_node_ids
-                    .insert(snarl.insert_node(pos, NoiseNode::Displace(Default::default())));
-                ui.close_menu();
-            }
+                if ui.radio(image.contours.is_none(), "Off").clicked() {
+                    image.contours = None;
+                }
 
-            if ui.button("Rotate Point").clicked() {
-                self.updated_node_ids
-                    .insert(snarl.insert_node(pos, NoiseNode::RotatePoint(TransformNode::zero())));
-                ui.close_menu();
-            }
+                for interval in [0.05, 0.1, 0.25, 0.5] {
+                    if ui
+                        .radio(
+                            image.contours == Some(interval),
+                            format!("Every {interval}"),
+                        )
+                        .clicked()
+                    {
+                        image.contours = Some(interval);
+                    }
+                }
 
-            if ui.button("Scale Point").clicked() {
-                self.updated_node_ids
-                    .insert(snarl.insert_node(pos, NoiseNode::ScalePoint(TransformNode::one())));
-                ui.close_menu();
-            }
+                if let Some(interval) = &mut image.contours {
+                    ui.horizontal(|ui| {
+                        ui.label("Interval");
+                        ui.add(DragValue::new(interval).range(0.01..=2.0).speed(0.01));
+                    });
+                }
 
-            if ui.button("Translate Point").clicked() {
-                self.updated_node_ids.insert(
-                    snarl.insert_node(pos, NoiseNode::TranslatePoint(TransformNode::zero())),
-                );
-                ui.close_menu();
-            }
+                if image.contours != contours {
+                    self.updated_node_ids.insert(node_id);
+                }
+            });
 
-            if ui.button("Turbulence").clicked() {
-                self.updated_node_ids
-                    .insert(snarl.insert_node(pos, NoiseNode::Turbulence(Default::default())));
-                ui.close_menu();
-            }
-        });
-        ui.menu_button("Constants", |ui| {
-            if ui.button("Control Point").clicked() {
-                snarl.insert_node(pos, NoiseNode::ControlPoint(Default::default()));
-                ui.close_menu();
-            }
+            if ui.button("Plot").clicked() {
+                *self.plot_window = Some(PlotWindow::new(node_id));
 
-            if ui.button("Decimal").clicked() {
-                snarl.insert_node(pos, NoiseNode::F64(Default::default()));
                 ui.close_menu();
             }
 
-            if ui.button("Integer").clicked() {
-                snarl.insert_node(pos, NoiseNode::U32(Default::default()));
+            if ui.button("Terrain Preview").clicked() {
+                *self.terrain_preview = Some(TerrainPreview::new(node_id));
+
                 ui.close_menu();
             }
 
             ui.separator();
-            ui.label("Operations");
+        }
 
-            if ui.button("Add").clicked() {
-                snarl.insert_node(
-                    pos,
-                    NoiseNode::Operation(ConstantOpNode::new(OpType::Add, ())),
-                );
-                ui.close_menu();
-            }
+        // Copies or removes the whole selection when this node is part of it
+        let selected_node_ids = get_selected_nodes(Id::new("snarl"), ui.ctx());
+        let node_ids = if selected_node_ids.len() > 1 && selected_node_ids.contains(&node_id) {
+            selected_node_ids
+        } else {
+            vec![node_id]
+        };
+
+        if !matches!(snarl.get_node(node_id), Some(NoiseNode::Group(_))) {
+            if ui
+                .button(if node_ids.len() > 1 {
+                    format!("Group Selected ({})", node_ids.len())
+                } else {
+                    "Group".to_owned()
+                })
+                .clicked()
+            {
+                Self::group_nodes(&node_ids, snarl);
 
-            if ui.button("Divide").clicked() {
-                snarl.insert_node(
-                    pos,
-                    NoiseNode::Operation(ConstantOpNode::new(OpType::Divide, ())),
-                );
                 ui.close_menu();
             }
 
-            if ui.button("Multiply").clicked() {
-                snarl.insert_node(
-                    pos,
-                    NoiseNode::Operation(ConstantOpNode::new(OpType::Multiply, ())),
-                );
-                ui.close_menu();
-            }
+            if Self::group_node_id(node_id, snarl).is_some()
+                && ui.button("Remove From Group").clicked()
+            {
+                Self::ungroup_nodes(&node_ids, snarl);
 
-            if ui.button("Subtract").clicked() {
-                snarl.insert_node(
-                    pos,
-                    NoiseNode::Operation(ConstantOpNode::new(OpType::Subtract, ())),
-                );
                 ui.close_menu();
             }
-        });
-    }
-
-    fn show_node_menu(
-        &mut self,
-        node_id: NodeId,
-        inputs: &[InPin],
-        outputs: &[OutPin],
-        ui: &mut Ui,
-        _scale: f32,
-        snarl: &mut Snarl<NoiseNode>,
-    ) {
-        ui.label("Node menu");
 
-        #[cfg(not(target_arch = "wasm32"))]
-        {
-            let node = snarl.get_node(node_id).unwrap();
+            let text = if node_ids.len() > 1 {
+                format!("Collapse Selected to Subgraph ({})", node_ids.len())
+            } else {
+                "Collapse to Subgraph".to_owned()
+            };
+            let subgraph_node_ids = node_ids.iter().copied().collect::<HashSet<_>>();
 
-            match node {
-                NoiseNode::ControlPoint(_)
-                | NoiseNode::F64(_)
-                | NoiseNode::F64Operation(_)
-                | NoiseNode::Operation(_)
-                | NoiseNode::U32(_)
-                | NoiseNode::U32Operation(_) => (),
-                _ => {
-                    if ui.button("Export File...").clicked() {
-                        if let Some(path) = App::file_dialog().save_file() {
-                            App::save_as(path, &node.expr(node_id, snarl)).unwrap_or_default();
-                        }
+            match Self::subgraph_output_node_id(&subgraph_node_ids, snarl) {
+                Ok(output_node_id) => {
+                    if ui.button(text).clicked() {
+                        self.collapse_to_subgraph(&subgraph_node_ids, output_node_id, snarl);
 
                         ui.close_menu();
                     }
-
-                    ui.separator();
+                }
+                Err(reason) => {
+                    ui.add_enabled(false, Button::new(text))
+                        .on_disabled_hover_text(reason);
                 }
             }
-        }
 
-        if ui.button("Remove").clicked() {
-            self.removed_node_ids.insert(node_id);
-
-            for remote in outputs.iter().flat_map(|output| output.remotes.iter()) {
-                self.updated_node_ids.insert(remote.node);
-                match (remote.input, snarl.get_node(remote.node).unwrap()) {
-                    (
-                        0,
-                        NoiseNode::BasicMulti(_)
-                        | NoiseNode::Billow(_)
-                        | NoiseNode::Fbm(_)
-                        | NoiseNode::HybridMulti(_),
-                    ) => {
-                        snarl
-                            .get_node_mut(remote.node)
-                            .and_then(NoiseNode::as_fractal_mut)
-                            .unwrap()
-                            .seed = Value(snarl.get_node(node_id).unwrap().eval_u32(snarl));
-                    }
-                    (0, NoiseNode::Checkerboard(_)) => {
-                        snarl
-                            .get_node_mut(remote.node)
-                            .and_then(NoiseNode::as_checkerboard_mut)
-                            .unwrap()
-                            .size = Value(snarl.get_node(node_id).unwrap().eval_u32(snarl));
-                    }
-                    (0, NoiseNode::ControlPoint(_)) => {
-                        snarl
-                            .get_node_mut(remote.node)
-                            .and_then(NoiseNode::as_control_point_mut)
-                            .unwrap()
-                            .input = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
-                    }
-                    (0, NoiseNode::Cylinders(_)) => {
-                        snarl
-                            .get_node_mut(remote.node)
-                            .and_then(NoiseNode::as_cylinders_mut)
-                            .unwrap()
-                            .frequency = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
-                    }
-                    (
-                        0,
-                        NoiseNode::OpenSimplex(_)
-                        | NoiseNode::Perlin(_)
-                        | NoiseNode::PerlinSurflet(_)
-                        | NoiseNode::Simplex(_)
-                        | NoiseNode::SuperSimplex(_)
-                        | NoiseNode::Value(_),
-                    ) => {
-                        snarl
-                            .get_node_mut(remote.node)
-                            .and_then(NoiseNode::as_generator_mut)
-                            .unwrap()
-                            .seed = Value(snarl.get_node(node_id).unwrap().eval_u32(snarl));
-                    }
-                    (0, NoiseNode::RigidMulti(_)) => {
-                        snarl
-                            .get_node_mut(remote.node)
-                            .and_then(NoiseNode::as_rigid_fractal_mut)
-                            .unwrap()
-                            .seed = Value(snarl.get_node(node_id).unwrap().eval_u32(snarl));
-                    }
-                    (0, NoiseNode::Worley(_)) => {
-                        snarl
-                            .get_node_mut(remote.node)
-                            .and_then(NoiseNode::as_worley_mut)
-                            .unwrap()
-                            .seed = Value(snarl.get_node(node_id).unwrap().eval_u32(snarl));
-                    }
-                    (0 | 1, NoiseNode::F64Operation(_)) => {
-                        snarl
-                            .get_node_mut(remote.node)
-                            .and_then(NoiseNode::as_const_op_f64_mut)
-                            .unwrap()
-                            .inputs[remote.input] =
-                            Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
-                    }
-                    (0 | 1, NoiseNode::Operation(_)) => {
-                        snarl
-                            .get_node_mut(remote.node)
-                            .and_then(NoiseNode::as_const_op_tuple_mut)
-                            .unwrap()
-                            .inputs[remote.input] = Default::default();
-                    }
-                    (0 | 1, NoiseNode::U32Operation(_)) => {
-                        snarl
-                            .get_node_mut(remote.node)
-                            .and_then(NoiseNode::as_const_op_u32_mut)
-                            .unwrap()
-                            .inputs[remote.input] =
-                            Value(snarl.get_node(node_id).unwrap().eval_u32(snarl));
-                    }
-                    (
-                        1,
-                        NoiseNode::BasicMulti(_)
-                        | NoiseNode::Billow(_)
-                        | NoiseNode::Fbm(_)
-                        | NoiseNode::HybridMulti(_),
-                    ) => {
-                        snarl
-                            .get_node_mut(remote.node)
-                            .and_then(NoiseNode::as_fractal_mut)
-                            .unwrap()
-                            .octaves = Value(snarl.get_node(node_id).unwrap().eval_u32(snarl));
-                    }
-                    (1, NoiseNode::Clamp(_)) => {
-                        snarl
-                            .get_node_mut(remote.node)
-                            .and_then(NoiseNode::as_clamp_mut)
-                            .unwrap()
-                            .lower_bound = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
-                    }
-                    (1, NoiseNode::ControlPoint(_)) => {
-                        snarl
-                            .get_node_mut(remote.node)
-                            .and_then(NoiseNode::as_control_point_mut)
-                            .unwrap()
-                            .output = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
-                    }
-                    (1, NoiseNode::Exponent(_)) => {
-                        snarl
-                            .get_node_mut(remote.node)
-                            .and_then(NoiseNode::as_exponent_mut)
-                            .unwrap()
-                            .exponent = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
-                    }
-                    (1, NoiseNode::RigidMulti(_)) => {
-                        snarl
-                            .get_node_mut(remote.node)
-                            .and_then(NoiseNode::as_rigid_fractal_mut)
-                            .unwrap()
-                            .octaves = Value(snarl.get_node(node_id).unwrap().eval_u32(snarl));
-                    }
-                    (1, NoiseNode::ScaleBias(_)) => {
-                        snarl
-                            .get_node_mut(remote.node)
-                            .and_then(NoiseNode::as_scale_bias_mut)
-                            .unwrap()
-                            .scale = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
-                    }
-                    (1, NoiseNode::Turbulence(_)) => {
-                        snarl
-                            .get_node_mut(remote.node)
-                            .and_then(NoiseNode::as_turbulence_mut)
-                            .unwrap()
-                            .seed = Value(snarl.get_node(node_id).unwrap().eval_u32(snarl));
-                    }
-                    (1, NoiseNode::Worley(_)) => {
-                        snarl
-                            .get_node_mut(remote.node)
-                            .and_then(NoiseNode::as_worley_mut)
-                            .unwrap()
-                            .frequency = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
-                    }
-                    (
-                        1..=4,
-                        NoiseNode::RotatePoint(_)
-                        | NoiseNode::ScalePoint(_)
-                        | NoiseNode::TranslatePoint(_),
-                    ) => {
-                        snarl
-                            .get_node_mut(remote.node)
-                            .and_then(NoiseNode::as_transform_mut)
-                            .unwrap()
-                            .axes[remote.input - 1] =
-                            Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
-                    }
-                    (
-                        2,
-                        NoiseNode::BasicMulti(_)
-                        | NoiseNode::Billow(_)
-                        | NoiseNode::Fbm(_)
-                        | NoiseNode::HybridMulti(_),
-                    ) => {
-                        snarl
-                            .get_node_mut(remote.node)
-                            .and_then(NoiseNode::as_fractal_mut)
-                            .unwrap()
-                            .frequency = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
-                    }
-                    (2, NoiseNode::Clamp(_)) => {
-                        snarl
-                            .get_node_mut(remote.node)
-                            .and_then(NoiseNode::as_clamp_mut)
-                            .unwrap()
-                            .upper_bound = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
-                    }
-                    (2, NoiseNode::RigidMulti(_)) => {
-                        snarl
-                            .get_node_mut(remote.node)
-                            .and_then(NoiseNode::as_rigid_fractal_mut)
-                            .unwrap()
-                            .frequency = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
-                    }
-                    (2, NoiseNode::ScaleBias(_)) => {
-                        snarl
-                            .get_node_mut(remote.node)
-                            .and_then(NoiseNode::as_scale_bias_mut)
-                            .unwrap()
-                            .bias = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
-                    }
-                    (2, NoiseNode::Turbulence(_)) => {
-                        snarl
-                            .get_node_mut(remote.node)
-                            .and_then(NoiseNode::as_turbulence_mut)
-                            .unwrap()
-                            .frequency = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
-                    }
-                    (
-                        3,
-                        NoiseNode::BasicMulti(_)
-                        | NoiseNode::Billow(_)
-                        | NoiseNode::Fbm(_)
-                        | NoiseNode::HybridMulti(_),
-                    ) => {
-                        snarl
-                            .get_node_mut(remote.node)
-                            .and_then(NoiseNode::as_fractal_mut)
-                            .unwrap()
-                            .lacunarity = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
-                    }
-                    (3, NoiseNode::RigidMulti(_)) => {
-                        snarl
-                            .get_node_mut(remote.node)
-                            .and_then(NoiseNode::as_rigid_fractal_mut)
-                            .unwrap()
-                            .lacunarity = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
-                    }
-                    (3, NoiseNode::Select(_)) => {
-                        snarl
-                            .get_node_mut(remote.node)
-                            .and_then(NoiseNode::as_select_mut)
-                            .unwrap()
-                            .lower_bound = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
-                    }
-                    (3, NoiseNode::Turbulence(_)) => {
-                        snarl
-                            .get_node_mut(remote.node)
-                            .and_then(NoiseNode::as_turbulence_mut)
-                            .unwrap()
-                            .power = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
-                    }
-                    (
-                        4,
-                        NoiseNode::BasicMulti(_)
-                        | NoiseNode::Billow(_)
-                        | NoiseNode::Fbm(_)
-                        | NoiseNode::HybridMulti(_),
-                    ) => {
-                        snarl
-                            .get_node_mut(remote.node)
-                            .and_then(NoiseNode::as_fractal_mut)
-                            .unwrap()
-                            .persistence = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
-                    }
-                    (4, NoiseNode::RigidMulti(_)) => {
-                        snarl
-                            .get_node_mut(remote.node)
-                            .and_then(NoiseNode::as_rigid_fractal_mut)
-                            .unwrap()
-                            .persistence = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
-                    }
-                    (4, NoiseNode::Select(_)) => {
-                        snarl
-                            .get_node_mut(remote.node)
-                            .and_then(NoiseNode::as_select_mut)
-                            .unwrap()
-                            .upper_bound = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
-                    }
-                    (4, NoiseNode::Turbulence(_)) => {
-                        snarl
-                            .get_node_mut(remote.node)
-                            .and_then(NoiseNode::as_turbulence_mut)
-                            .unwrap()
-                            .roughness = Value(snarl.get_node(node_id).unwrap().eval_u32(snarl));
-                    }
-                    (5, NoiseNode::RigidMulti(_)) => {
-                        snarl
-                            .get_node_mut(remote.node)
-                            .and_then(NoiseNode::as_rigid_fractal_mut)
-                            .unwrap()
-                            .attenuation = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
-                    }
-                    (5, NoiseNode::Select(_)) => {
-                        snarl
-                            .get_node_mut(remote.node)
-                            .and_then(NoiseNode::as_select_mut)
-                            .unwrap()
-                            .falloff = Value(snarl.get_node(node_id).unwrap().eval_f64(snarl));
-                    }
-                    (control_point_idx, NoiseNode::Curve(_)) if control_point_idx > 0 => {
-                        let node = snarl
-                            .get_node_mut(remote.node)
-                            .and_then(NoiseNode::as_curve_mut)
-                            .unwrap();
-                        node.control_point_node_ids[control_point_idx - 1] = None;
+            ui.separator();
+        }
 
-                        while let Some(None) = node.control_point_node_ids.last() {
-                            node.control_point_node_ids.pop();
-                        }
-                    }
-                    (control_point_idx, NoiseNode::Terrace(_)) if control_point_idx > 0 => {
-                        let node = snarl
-                            .get_node_mut(remote.node)
-                            .and_then(NoiseNode::as_terrace_mut)
-                            .unwrap();
-                        node.control_point_node_ids[control_point_idx - 1] = None;
+        if ui
+            .button(if node_ids.len() > 1 {
+                format!("Copy Selected ({})", node_ids.len())
+            } else {
+                "Copy".to_owned()
+            })
+            .clicked()
+        {
+            self.clipboard.copy(snarl, &node_ids);
 
-                        while let Some(None) = node.control_point_node_ids.last() {
-                            node.control_point_node_ids.pop();
-                        }
-                    }
-                    _ => {}
-                }
-            }
+            ui.close_menu();
+        }
 
-            for node_id in inputs
-                .iter()
-                .flat_map(|input| input.remotes.iter().map(|remote| remote.node))
-                .chain(
-                    outputs
-                        .iter()
-                        .flat_map(|output| output.remotes.iter().map(|remote| remote.node)),
-                )
-            {
-                NoiseNode::propagate_tuple_from_f64_op(node_id, snarl);
-                NoiseNode::propagate_tuple_from_u32_op(node_id, snarl);
+        if ui
+            .button(if node_ids.len() > 1 {
+                format!("Remove Selected ({})", node_ids.len())
+            } else {
+                "Remove".to_owned()
+            })
+            .clicked()
+        {
+            for node_id in node_ids {
+                self.remove_node(node_id, snarl);
             }
 
-            snarl.remove_node(node_id);
             ui.close_menu();
         }
     }